@@ -0,0 +1,115 @@
+//! External address validation per chain family.
+
+use serde::{Deserialize, Serialize};
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32_MAX_LENGTH: usize = 90;
+
+/// Address format accepted by an external chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressFormat {
+    /// 20-byte hex address with 0x prefix
+    Evm,
+    /// Bech32 address with the given human-readable prefix
+    Bech32 { hrp: String },
+    /// Any non-empty string (chain-specific checks done off-chain)
+    Opaque,
+}
+
+impl AddressFormat {
+    pub fn validate(&self, address: &str) -> bool {
+        match self {
+            AddressFormat::Evm => is_valid_evm_address(address),
+            AddressFormat::Bech32 { hrp } => is_valid_bech32(address, hrp),
+            AddressFormat::Opaque => !address.is_empty(),
+        }
+    }
+}
+
+/// Checks for a 0x-prefixed, 40 hex digit address.
+pub fn is_valid_evm_address(address: &str) -> bool {
+    match address.strip_prefix("0x") {
+        Some(hex) => hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/// Checks a bech32 (BIP-173) address and its human-readable prefix.
+pub fn is_valid_bech32(address: &str, expected_hrp: &str) -> bool {
+    if address.len() > BECH32_MAX_LENGTH {
+        return false;
+    }
+    // Mixed case is invalid
+    let has_lower = address.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = address.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return false;
+    }
+
+    let address = address.to_ascii_lowercase();
+    let Some(separator) = address.rfind('1') else {
+        return false;
+    };
+    let (hrp, data) = (&address[..separator], &address[separator + 1..]);
+    if hrp.is_empty() || hrp != expected_hrp.to_ascii_lowercase() || data.len() < 6 {
+        return false;
+    }
+    if !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return false;
+    }
+
+    let mut values = Vec::with_capacity(hrp.len() * 2 + 1 + data.len());
+    values.extend(hrp.bytes().map(|b| b >> 5));
+    values.push(0);
+    values.extend(hrp.bytes().map(|b| b & 0x1f));
+    for byte in data.bytes() {
+        match BECH32_CHARSET.iter().position(|&c| c == byte) {
+            Some(value) => values.push(value as u8),
+            None => return false,
+        }
+    }
+
+    bech32_polymod(&values) == 1
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    let mut checksum = 1u32;
+    for &value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ u32::from(value);
+        for (i, generator) in BECH32_GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bech32_vectors() {
+        assert!(is_valid_bech32("A12UEL5L", "a"));
+        assert!(is_valid_bech32("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", "abcdef"));
+        assert!(is_valid_bech32("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02", "cosmos"));
+        assert!(is_valid_bech32("osmo1clpqr4nrk4khgkxj78fcwwh6dl3uw4epasmvnj", "osmo"));
+    }
+
+    #[test]
+    fn test_bech32_rejects_bad_checksum_and_hrp() {
+        assert!(!is_valid_bech32("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd03", "cosmos"));
+        assert!(!is_valid_bech32("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02", "osmo"));
+        assert!(!is_valid_bech32("Cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02", "cosmos"));
+    }
+
+    #[test]
+    fn test_evm_address() {
+        assert!(is_valid_evm_address("0x52908400098527886E0F7030069857D2E4169EE7"));
+        assert!(!is_valid_evm_address("52908400098527886E0F7030069857D2E4169EE7"));
+        assert!(!is_valid_evm_address("0x1234"));
+    }
+}
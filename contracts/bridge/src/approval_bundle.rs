@@ -0,0 +1,103 @@
+//! Approval bundles: the quorum signatures of an outbound withdrawal, in the form the destination
+//! chain's verifier contract takes them.
+//!
+//! Signatures are captured as validators approve and frozen at quorum, with the keys and weights
+//! they approved under, so later validator set changes never alter a bundle.
+
+use linera_base::{data_types::Timestamp, identifiers::Account};
+use serde::{Deserialize, Serialize};
+
+use crate::TransferId;
+
+/// One validator's approval, with the key the verifier checks it against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub validator: Account,
+    /// Validator's public key at approval
+    pub external_key: Vec<u8>,
+    /// Over the bundle's payload
+    pub signature: Vec<u8>,
+    pub weight: u32,
+}
+
+/// Validator set the quorum was reached against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalQuorum {
+    /// Validator set epoch, bumped by every validator addition, update or removal
+    pub epoch: u64,
+    pub total_weight: u32,
+    /// Weight the approvals had to reach
+    pub threshold: u32,
+    pub threshold_percentage: u32,
+    pub reached_at: Timestamp,
+}
+
+/// Approvals of a withdrawal up to its quorum
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalSnapshot {
+    pub signatures: Vec<BundleSignature>,
+    /// None until the approvals reach quorum; later approvals are not part of the snapshot
+    pub quorum: Option<ApprovalQuorum>,
+}
+
+impl ApprovalSnapshot {
+    /// The bundle for `payload`, once the snapshot has reached quorum
+    pub fn bundle(&self, transfer_id: TransferId, payload: Vec<u8>) -> Option<ApprovalBundle> {
+        let quorum = self.quorum?;
+        let mut signatures = self.signatures.clone();
+        signatures.sort_by(|a, b| a.validator.cmp(&b.validator));
+        Some(ApprovalBundle { transfer_id, payload, signatures, quorum })
+    }
+}
+
+/// Everything a relayer passes to the destination chain's verifier, returned by
+/// `Query::GetApprovalBundle`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalBundle {
+    pub transfer_id: TransferId,
+    /// Canonical `TransferApproval` payload the signatures are over
+    pub payload: Vec<u8>,
+    /// Sorted by validator address
+    pub signatures: Vec<BundleSignature>,
+    pub quorum: ApprovalQuorum,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_base::identifiers::ChainId;
+
+    fn signature(chain: u32, weight: u32) -> BundleSignature {
+        BundleSignature {
+            validator: Account::chain(ChainId::root(chain)),
+            external_key: vec![chain as u8; 33],
+            signature: vec![chain as u8; 65],
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_bundles_need_quorum_and_sort_signers() {
+        let mut snapshot = ApprovalSnapshot {
+            signatures: vec![signature(2, 40), signature(0, 30), signature(1, 30)],
+            quorum: None,
+        };
+        assert_eq!(snapshot.bundle(7, vec![1, 2]), None);
+
+        let quorum = ApprovalQuorum {
+            epoch: 3,
+            total_weight: 100,
+            threshold: 67,
+            threshold_percentage: 67,
+            reached_at: Timestamp::from(0),
+        };
+        snapshot.quorum = Some(quorum);
+        let bundle = snapshot.bundle(7, vec![1, 2]).unwrap();
+        let mut sorted: Vec<Account> = snapshot.signatures.iter().map(|signature| signature.validator).collect();
+        sorted.sort();
+        let validators: Vec<Account> = bundle.signatures.iter().map(|signature| signature.validator).collect();
+        assert_eq!(validators, sorted);
+        assert_eq!(bundle.payload, vec![1, 2]);
+        assert_eq!(bundle.quorum, quorum);
+    }
+}
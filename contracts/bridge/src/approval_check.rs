@@ -0,0 +1,166 @@
+//! Pre-approval checks for validator daemons: everything about a transfer a validator can verify
+//! before signing, so it never spends a signature on a transfer the bridge would refuse.
+
+use linera_base::{data_types::Timestamp, identifiers::Account};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encoding::{SignedPayload, TransferApproval},
+    AddressFormat, BridgeTransfer, ChainConfig, TransferId, TransferStatus,
+};
+
+/// A consistency check run on a transfer before approval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalCheck {
+    /// Awaiting approval, approved and still taking votes, or scheduled with its window open
+    Status,
+    /// Net amount and fee add up to the amount, with the relayer share inside the fee
+    AmountsConsistent,
+    /// External address well formed for the corridor chain
+    ExternalAddress,
+    /// Fee at most the standard fee of the chain config the transfer was created under
+    FeeWithinBounds,
+    /// Payload decodes back to the transfer's fields
+    CanonicalPayload,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalCheckResult {
+    pub check: ApprovalCheck,
+    pub passed: bool,
+}
+
+/// Why a validator's approval would be refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalIneligibility {
+    /// Not in the validator set of the current epoch
+    NotRegistered,
+    Inactive,
+    AlreadyVoted,
+}
+
+/// Returned by `Query::ValidateForApproval`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalPrevalidation {
+    pub transfer_id: TransferId,
+    pub validator: Account,
+    /// Canonical `TransferApproval` payload to sign; None without a corridor chain
+    pub payload: Option<Vec<u8>>,
+    pub checks: Vec<ApprovalCheckResult>,
+    /// Validator set epoch eligibility was checked against
+    pub validator_set_epoch: u64,
+    /// Empty when the validator may approve
+    pub ineligible: Vec<ApprovalIneligibility>,
+}
+
+impl ApprovalPrevalidation {
+    /// Whether signing now yields an approval the bridge accepts
+    pub fn ready_to_sign(&self) -> bool {
+        self.payload.is_some() && self.ineligible.is_empty() && self.checks.iter().all(|result| result.passed)
+    }
+}
+
+/// State a transfer is checked against, read by `BridgeState::approval_context`
+#[derive(Debug, Clone)]
+pub struct ApprovalContext {
+    pub address_format: AddressFormat,
+    /// Chain config at the transfer's `config_version`; None once it left the history
+    pub config: Option<ChainConfig>,
+    /// When a scheduled withdrawal would be released by an approval at the time checked
+    pub scheduled_release_at: Timestamp,
+    pub now: Timestamp,
+}
+
+/// Runs every check on `transfer`, in `ApprovalCheck` order
+pub fn approval_checks(transfer: &BridgeTransfer, context: &ApprovalContext) -> Vec<ApprovalCheckResult> {
+    let status = match transfer.status {
+        TransferStatus::AwaitingApproval | TransferStatus::Approved => true,
+        TransferStatus::Scheduled => context.scheduled_release_at <= context.now,
+        _ => false,
+    };
+    let amounts_consistent = transfer.fee <= transfer.amount
+        && transfer.relayer_fee <= transfer.fee
+        && transfer.net_amount == transfer.amount.saturating_sub(transfer.fee);
+    let fee_within_bounds = context.config.as_ref().is_some_and(|config| {
+        config
+            .fee_breakdown(transfer.amount, transfer.direction)
+            .is_ok_and(|standard| transfer.fee <= standard.total_fee && transfer.relayer_fee <= standard.relayer_fee)
+    });
+    let canonical_payload = transfer.approval_payload().is_ok_and(|payload| {
+        TransferApproval::decode(&payload).is_ok_and(|approval| {
+            approval.transfer_id == transfer.id
+                && approval.direction == transfer.direction
+                && approval.external_address == transfer.external_address
+                && approval.asset == transfer.asset
+                && approval.net_amount == transfer.net_amount
+        })
+    });
+    [
+        (ApprovalCheck::Status, status),
+        (ApprovalCheck::AmountsConsistent, amounts_consistent),
+        (ApprovalCheck::ExternalAddress, context.address_format.validate(&transfer.external_address)),
+        (ApprovalCheck::FeeWithinBounds, fee_within_bounds),
+        (ApprovalCheck::CanonicalPayload, canonical_payload),
+    ]
+    .into_iter()
+    .map(|(check, passed)| ApprovalCheckResult { check, passed })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::{test_chain_config, test_transfer}, ExternalChain, TransferDirection};
+    use linera_base::data_types::Amount;
+
+    fn failed(transfer: &BridgeTransfer, context: &ApprovalContext) -> Vec<ApprovalCheck> {
+        approval_checks(transfer, context).into_iter().filter(|result| !result.passed).map(|result| result.check).collect()
+    }
+
+    #[test]
+    fn test_checks_flag_what_the_bridge_would_refuse() {
+        let config = test_chain_config(150);
+        let fees = config.fee_breakdown(Amount::from(1_000_000), TransferDirection::Outbound).unwrap();
+        let mut transfer = test_transfer(TransferDirection::Outbound, None);
+        transfer.destination_chain = Some(ExternalChain::Ethereum);
+        transfer.amount = Amount::from(1_000_000);
+        transfer.fee = fees.total_fee;
+        transfer.relayer_fee = fees.relayer_fee;
+        transfer.net_amount = fees.net_amount;
+        let context = ApprovalContext {
+            address_format: AddressFormat::Evm,
+            config: Some(config),
+            scheduled_release_at: Timestamp::from(10),
+            now: Timestamp::from(5),
+        };
+        assert_eq!(failed(&transfer, &context), vec![]);
+
+        // A scheduled withdrawal passes once its window is open
+        transfer.status = TransferStatus::Scheduled;
+        assert_eq!(failed(&transfer, &context), vec![ApprovalCheck::Status]);
+        assert_eq!(failed(&transfer, &ApprovalContext { now: Timestamp::from(10), ..context.clone() }), vec![]);
+        transfer.status = TransferStatus::Approved;
+
+        let mut overcharged = transfer.clone();
+        overcharged.fee = fees.total_fee.saturating_add(Amount::from(1));
+        overcharged.net_amount = transfer.net_amount.saturating_sub(Amount::from(1));
+        assert_eq!(failed(&overcharged, &context), vec![ApprovalCheck::FeeWithinBounds]);
+        overcharged.net_amount = transfer.net_amount;
+        assert_eq!(failed(&overcharged, &context), vec![
+            ApprovalCheck::AmountsConsistent,
+            ApprovalCheck::FeeWithinBounds,
+        ]);
+
+        let mut misaddressed = transfer.clone();
+        misaddressed.external_address = "not-an-address".to_string();
+        assert_eq!(failed(&misaddressed, &context), vec![ApprovalCheck::ExternalAddress]);
+
+        // Without a corridor chain there is no payload, and without the config no fee bound
+        let mut unrouted = transfer.clone();
+        unrouted.destination_chain = None;
+        assert_eq!(failed(&unrouted, &ApprovalContext { config: None, ..context }), vec![
+            ApprovalCheck::FeeWithinBounds,
+            ApprovalCheck::CanonicalPayload,
+        ]);
+    }
+}
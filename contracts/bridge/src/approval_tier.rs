@@ -0,0 +1,205 @@
+//! Per-chain approval requirements, tiered by transfer size: larger transfers need more validator
+//! weight and can require approvals from designated senior validators on top.
+//!
+//! Tier maximums are in one unit for all assets (say USD): each asset's amounts are converted at
+//! its rate before the tier is picked, and an asset without a rate falls in the top tier.
+//!
+//! A transfer snapshots the requirement of its tier when created, and a batch when sealed, so
+//! later tier changes never move the quorum of work already in flight.
+
+use axelarx_math as math;
+use linera_base::{data_types::Amount, identifiers::Account};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Most tiers a chain can have
+pub const MAX_APPROVAL_TIERS: usize = 8;
+
+/// Quorum a transfer or batch must reach
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRequirement {
+    /// Share of the total validator weight the approvals must reach
+    pub threshold_percentage: u32,
+    /// Validators of which at least `min_senior_approvals` must be among the approvers
+    pub senior_validators: Vec<Account>,
+    pub min_senior_approvals: u32,
+}
+
+impl ApprovalRequirement {
+    /// Weight the approvals must reach out of `total_weight`
+    pub fn required_weight(&self, total_weight: u32) -> u32 {
+        (u64::from(total_weight) * u64::from(self.threshold_percentage) / 100) as u32
+    }
+
+    /// Whether approvals of `approval_weight`, `senior_approvals` of them by senior validators,
+    /// meet the requirement
+    pub fn is_met(&self, approval_weight: u32, total_weight: u32, senior_approvals: u32) -> bool {
+        approval_weight >= self.required_weight(total_weight) && senior_approvals >= self.min_senior_approvals
+    }
+}
+
+/// Requirement of transfers up to `max_amount`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalTier {
+    /// Largest amount the tier covers, inclusive; None for the top tier
+    pub max_amount: Option<Amount>,
+    pub requirement: ApprovalRequirement,
+}
+
+/// Approval tiers of one chain, by increasing `max_amount`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalTiers {
+    pub tiers: Vec<ApprovalTier>,
+    /// Rate (1e8 fixed point) converting each asset's amounts into the unit of `max_amount`
+    #[serde(default)]
+    pub asset_rates: BTreeMap<String, u64>,
+}
+
+impl ApprovalTiers {
+    /// Up to `MAX_APPROVAL_TIERS` tiers with strictly increasing maximums, only the last one
+    /// unbounded, percentages from 1 to 100, enough distinct senior validators for each, and no
+    /// zero rate
+    pub fn is_valid(&self) -> bool {
+        let Some((top, bounded)) = self.tiers.split_last() else {
+            return false;
+        };
+        let maximums: Option<Vec<Amount>> = bounded.iter().map(|tier| tier.max_amount).collect();
+        let Some(maximums) = maximums else {
+            return false;
+        };
+        self.tiers.len() <= MAX_APPROVAL_TIERS
+            && top.max_amount.is_none()
+            && maximums.windows(2).all(|pair| pair[0] < pair[1])
+            && self.tiers.iter().all(|tier| {
+                let requirement = &tier.requirement;
+                let seniors = &requirement.senior_validators;
+                (1..=100).contains(&requirement.threshold_percentage)
+                    && requirement.min_senior_approvals as usize <= seniors.len()
+                    && seniors.iter().enumerate().all(|(index, senior)| !seniors[..index].contains(senior))
+            })
+            && self.asset_rates.values().all(|rate| *rate > 0)
+    }
+
+    /// Combined value of `amounts` in the unit of the tier maximums; None when an asset has no
+    /// rate or the value overflows
+    pub fn normalize<'a>(&self, amounts: impl IntoIterator<Item = (&'a str, Amount)>) -> Option<Amount> {
+        amounts.into_iter().try_fold(Amount::ZERO, |total, (asset, amount)| {
+            let value = math::convert(amount, *self.asset_rates.get(asset)?).ok()?;
+            math::checked_add(total, value).ok()
+        })
+    }
+
+    /// Requirement of the first tier covering the normalized value of `amounts`, or of the top
+    /// tier when they cannot be normalized; valid tiers always have one
+    pub fn requirement_for<'a>(
+        &self,
+        amounts: impl IntoIterator<Item = (&'a str, Amount)>,
+    ) -> Option<&ApprovalRequirement> {
+        let tier = match self.normalize(amounts) {
+            Some(value) => {
+                self.tiers.iter().find(|tier| tier.max_amount.map_or(true, |max_amount| value <= max_amount))
+            }
+            None => self.tiers.last(),
+        };
+        tier.map(|tier| &tier.requirement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_base::identifiers::ChainId;
+
+    fn senior(index: u32) -> Account {
+        Account::chain(ChainId::root(index))
+    }
+
+    fn tier(max_tokens: Option<u128>, threshold_percentage: u32, seniors: Vec<Account>, minimum: u32) -> ApprovalTier {
+        ApprovalTier {
+            max_amount: max_tokens.map(Amount::from_tokens),
+            requirement: ApprovalRequirement {
+                threshold_percentage,
+                senior_validators: seniors,
+                min_senior_approvals: minimum,
+            },
+        }
+    }
+
+    /// Tiers in USD, with USDC at par and ETH at 2,000
+    fn tiers() -> ApprovalTiers {
+        ApprovalTiers {
+            tiers: vec![
+                tier(Some(100), 51, vec![], 0),
+                tier(Some(1_000_000), 67, vec![], 0),
+                tier(None, 80, vec![senior(1), senior(2), senior(3)], 2),
+            ],
+            asset_rates: BTreeMap::from([("USDC".to_string(), 100_000_000), ("ETH".to_string(), 200_000_000_000)]),
+        }
+    }
+
+    #[test]
+    fn test_tier_boundaries() {
+        let tiers = tiers();
+        assert!(tiers.is_valid());
+        let percentage = |amount| tiers.requirement_for([("USDC", amount)]).unwrap().threshold_percentage;
+        assert_eq!(percentage(Amount::ZERO), 51);
+        assert_eq!(percentage(Amount::from_tokens(100)), 51);
+        assert_eq!(percentage(Amount::from_tokens(100).saturating_add(Amount::from_attos(1))), 67);
+        assert_eq!(percentage(Amount::from_tokens(1_000_000)), 67);
+        assert_eq!(percentage(Amount::from_tokens(1_000_001)), 80);
+    }
+
+    #[test]
+    fn test_senior_approvals_needed_beyond_weight() {
+        let tiers = tiers();
+        let top = tiers.requirement_for([("USDC", Amount::from_tokens(5_000_000))]).unwrap();
+        assert_eq!(top.required_weight(10), 8);
+        assert!(!top.is_met(10, 10, 1));
+        assert!(!top.is_met(7, 10, 2));
+        assert!(top.is_met(8, 10, 2));
+    }
+
+    #[test]
+    fn test_amounts_are_normalized() {
+        let tiers = tiers();
+        let percentage = |amounts: &[(&str, u128)]| {
+            let amounts = amounts.iter().map(|(asset, tokens)| (*asset, Amount::from_tokens(*tokens)));
+            tiers.requirement_for(amounts).unwrap().threshold_percentage
+        };
+        // 100 ETH is worth 200,000 USD, past the 100 USD tier its raw amount would fit
+        assert_eq!(percentage(&[("ETH", 100)]), 67);
+        assert_eq!(percentage(&[("ETH", 500), ("USDC", 1)]), 80);
+        // Without a rate the amount cannot be valued, so the top tier applies
+        assert_eq!(percentage(&[("BTC", 1)]), 80);
+        assert_eq!(percentage(&[("USDC", 1), ("BTC", 1)]), 80);
+    }
+
+    #[test]
+    fn test_invalid_tiers() {
+        assert!(!ApprovalTiers::default().is_valid());
+
+        let mut bounded_top = tiers();
+        bounded_top.tiers[2].max_amount = Some(Amount::from_tokens(2_000_000));
+        assert!(!bounded_top.is_valid());
+
+        let mut unordered = tiers();
+        unordered.tiers.swap(0, 1);
+        assert!(!unordered.is_valid());
+
+        let mut too_few_seniors = tiers();
+        too_few_seniors.tiers[2].requirement.min_senior_approvals = 4;
+        assert!(!too_few_seniors.is_valid());
+
+        let mut repeated_senior = tiers();
+        repeated_senior.tiers[2].requirement.senior_validators = vec![senior(1), senior(1)];
+        assert!(!repeated_senior.is_valid());
+
+        let mut over_full = tiers();
+        over_full.tiers[0].requirement.threshold_percentage = 101;
+        assert!(!over_full.is_valid());
+
+        let mut worthless = tiers();
+        worthless.asset_rates.insert("ETH".to_string(), 0);
+        assert!(!worthless.is_valid());
+    }
+}
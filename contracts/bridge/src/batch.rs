@@ -0,0 +1,200 @@
+//! Withdrawal batches: limits, payload items and the root validators sign.
+
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::Account,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    elapsed_seconds,
+    encoding::{Decoder, Domain, Encoder, EncodingError, SignedPayload},
+    ApprovalRequirement, ExternalChain, TransferId,
+};
+
+/// Most withdrawals a single batch can hold
+pub const MAX_BATCH_TRANSFERS: u32 = 64;
+
+/// Per-chain limits of an open batch; it is sealed as soon as any of them is reached
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchLimits {
+    pub max_transfers: u32,
+    /// Cap on the summed net amount of the batch
+    pub max_total_amount: Amount,
+    /// Age after which anyone may seal the batch
+    pub max_age_seconds: u64,
+}
+
+impl BatchLimits {
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_BATCH_TRANSFERS).contains(&self.max_transfers) && self.max_total_amount > Amount::ZERO
+    }
+
+    /// Whether a batch with `transfers` items worth `total` has to be sealed
+    pub fn is_full(&self, transfers: usize, total: Amount) -> bool {
+        transfers >= self.max_transfers as usize || total >= self.max_total_amount
+    }
+
+    pub fn is_due(&self, opened_at: Timestamp, now: Timestamp) -> bool {
+        elapsed_seconds(opened_at, now) >= self.max_age_seconds
+    }
+}
+
+/// Batch lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchStatus {
+    /// Accepting withdrawals
+    Open,
+    /// Sealed with a root, waiting for validators to sign it
+    AwaitingApproval,
+    /// Signed by enough validator weight, ready for the relayer
+    Approved,
+    /// Submitted to the destination chain as one transaction
+    Executing,
+    /// Outcome of every item reported
+    Completed,
+}
+
+/// A group of withdrawals to one chain, approved and executed as a single transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawalBatch {
+    pub id: u64,
+    pub chain: ExternalChain,
+    pub status: BatchStatus,
+    /// Withdrawals in payload order
+    pub transfer_ids: Vec<TransferId>,
+    pub total_amount: Amount,
+    pub opened_at: Timestamp,
+    pub sealed_at: Option<Timestamp>,
+    /// Set when sealed
+    pub root: Option<[u8; 32]>,
+    /// Approval summary; individual approvals live in `BridgeState::batch_approvals`
+    pub approval_weight: u32,
+    pub approval_count: u32,
+    /// Requirement of the chain's approval tier for `total_amount`, snapshotted when sealed;
+    /// None without tiers
+    #[serde(default)]
+    pub approval_requirement: Option<ApprovalRequirement>,
+    pub relayer: Option<Account>,
+    pub destination_tx_hash: Option<String>,
+    pub completed_at: Option<Timestamp>,
+}
+
+impl WithdrawalBatch {
+    pub fn new(id: u64, chain: ExternalChain, opened_at: Timestamp) -> Self {
+        WithdrawalBatch {
+            id,
+            chain,
+            status: BatchStatus::Open,
+            transfer_ids: Vec::new(),
+            total_amount: Amount::ZERO,
+            opened_at,
+            sealed_at: None,
+            root: None,
+            approval_weight: 0,
+            approval_count: 0,
+            approval_requirement: None,
+            relayer: None,
+            destination_tx_hash: None,
+            completed_at: None,
+        }
+    }
+}
+
+/// One withdrawal as executed on the destination chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchItem {
+    pub transfer_id: TransferId,
+    pub recipient_address: String,
+    pub asset: String,
+    /// Net amount paid out
+    pub amount: Amount,
+    pub memo: Option<String>,
+}
+
+impl SignedPayload for BatchItem {
+    const DOMAIN: Domain = Domain::BatchItem;
+
+    fn encode_fields(&self, encoder: Encoder) -> Encoder {
+        encoder
+            .u64(self.transfer_id)
+            .str(&self.recipient_address)
+            .str(&self.asset)
+            .amount(self.amount)
+            .opt_str(self.memo.as_deref())
+    }
+
+    fn decode_fields(decoder: &mut Decoder<'_>) -> Result<Self, EncodingError> {
+        Ok(BatchItem {
+            transfer_id: decoder.u64()?,
+            recipient_address: decoder.string()?,
+            asset: decoder.string()?,
+            amount: decoder.amount()?,
+            memo: decoder.opt_string()?,
+        })
+    }
+}
+
+/// keccak256 over the canonical encoding of the chain, batch id and the hashes of the items in order
+pub fn batch_root(chain: ExternalChain, batch_id: u64, items: &[BatchItem]) -> [u8; 32] {
+    let length = u32::try_from(items.len()).expect("batches hold at most MAX_BATCH_TRANSFERS items");
+    let mut encoder = Encoder::new(Domain::BatchRoot).chain(chain).u64(batch_id).u32(length);
+    for item in items {
+        encoder = encoder.fixed(&Keccak256::digest(item.encode()));
+    }
+    Keccak256::digest(encoder.finish()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(transfer_id: TransferId, memo: Option<&str>) -> BatchItem {
+        BatchItem {
+            transfer_id,
+            recipient_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+            asset: "USDC".to_string(),
+            amount: Amount::from(1_000),
+            memo: memo.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_batch_root_commits_to_order_and_payload() {
+        let items = [item(1, None), item(2, None)];
+        let root = batch_root(ExternalChain::Ethereum, 1, &items);
+        assert_eq!(root, batch_root(ExternalChain::Ethereum, 1, &items));
+
+        assert_ne!(root, batch_root(ExternalChain::Ethereum, 1, &[item(2, None), item(1, None)]));
+        assert_ne!(root, batch_root(ExternalChain::Ethereum, 2, &items));
+        assert_ne!(root, batch_root(ExternalChain::Polygon, 1, &items));
+        assert_ne!(root, batch_root(ExternalChain::Ethereum, 1, &[item(1, Some("")), item(2, None)]));
+    }
+
+    #[test]
+    fn test_batch_item_round_trip() {
+        for item in [item(1, None), item(2, Some("invoice 42"))] {
+            assert_eq!(BatchItem::decode(&item.encode()), Ok(item));
+        }
+    }
+
+    #[test]
+    fn test_batch_limits() {
+        let limits = BatchLimits {
+            max_transfers: 3,
+            max_total_amount: Amount::from(5_000),
+            max_age_seconds: 600,
+        };
+        assert!(limits.is_valid());
+        assert!(!limits.is_full(2, Amount::from(4_999)));
+        assert!(limits.is_full(3, Amount::from(1)));
+        assert!(limits.is_full(1, Amount::from(5_000)));
+
+        assert!(!limits.is_due(Timestamp::from(0), Timestamp::from(599_000_000)));
+        assert!(limits.is_due(Timestamp::from(0), Timestamp::from(600_000_000)));
+
+        assert!(!BatchLimits { max_transfers: MAX_BATCH_TRANSFERS + 1, ..limits.clone() }.is_valid());
+        assert!(!BatchLimits { max_total_amount: Amount::ZERO, ..limits }.is_valid());
+    }
+}
@@ -0,0 +1,92 @@
+//! Per-asset confirmation requirements, tiered by deposit size, in place of a chain's default.
+
+use linera_base::data_types::Amount;
+use serde::{Deserialize, Serialize};
+
+use crate::FinalityProfile;
+
+/// Confirmations required of deposits of at least `min_amount`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationTier {
+    pub min_amount: Amount,
+    pub confirmations: u64,
+}
+
+/// Confirmation tiers for one asset on one chain, by increasing `min_amount`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationOverride {
+    pub tiers: Vec<ConfirmationTier>,
+}
+
+impl ConfirmationOverride {
+    /// At least one tier, with strictly increasing thresholds
+    pub fn is_valid(&self) -> bool {
+        !self.tiers.is_empty() && self.tiers.windows(2).all(|pair| pair[0].min_amount < pair[1].min_amount)
+    }
+
+    /// Confirmations of the highest tier `amount` reaches; None below the first tier
+    pub fn confirmations_for(&self, amount: Amount) -> Option<u64> {
+        self.tiers.iter().rev().find(|tier| amount >= tier.min_amount).map(|tier| tier.confirmations)
+    }
+
+    /// The chain's profile with the confirmation count of the tier `amount` reaches. Attestations
+    /// are still required where the profile asks for them.
+    pub fn apply(&self, profile: FinalityProfile, amount: Amount) -> FinalityProfile {
+        let Some(confirmations) = self.confirmations_for(amount) else {
+            return profile;
+        };
+        match profile {
+            FinalityProfile::ConfirmationCount(_) => FinalityProfile::ConfirmationCount(confirmations),
+            FinalityProfile::FinalityTag if confirmations == 0 => FinalityProfile::FinalityTag,
+            FinalityProfile::FinalityTag | FinalityProfile::Hybrid { .. } => {
+                FinalityProfile::Hybrid { min_confirmations: confirmations }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wbtc() -> ConfirmationOverride {
+        ConfirmationOverride {
+            tiers: vec![
+                ConfirmationTier { min_amount: Amount::ZERO, confirmations: 12 },
+                ConfirmationTier { min_amount: Amount::from_tokens(1_000_000), confirmations: 64 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_tier_by_amount() {
+        let tiers = wbtc();
+        assert!(tiers.is_valid());
+        assert_eq!(tiers.confirmations_for(Amount::from_tokens(5)), Some(12));
+        assert_eq!(tiers.confirmations_for(Amount::from_tokens(1_000_000)), Some(64));
+        assert_eq!(
+            tiers.apply(FinalityProfile::ConfirmationCount(12), Amount::from_tokens(2_000_000)),
+            FinalityProfile::ConfirmationCount(64),
+        );
+        // A finality tag keeps its attestation and gains the confirmation floor
+        assert_eq!(
+            tiers.apply(FinalityProfile::FinalityTag, Amount::from_tokens(2_000_000)),
+            FinalityProfile::Hybrid { min_confirmations: 64 },
+        );
+
+        let large_only = ConfirmationOverride { tiers: tiers.tiers[1..].to_vec() };
+        assert_eq!(large_only.confirmations_for(Amount::from_tokens(5)), None);
+        assert_eq!(
+            large_only.apply(FinalityProfile::ConfirmationCount(12), Amount::from_tokens(5)),
+            FinalityProfile::ConfirmationCount(12),
+        );
+    }
+
+    #[test]
+    fn test_tiers_must_increase() {
+        let mut tiers = wbtc();
+        tiers.tiers.swap(0, 1);
+        assert!(!tiers.is_valid());
+        assert!(!ConfirmationOverride::default().is_valid());
+    }
+}
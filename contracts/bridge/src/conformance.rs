@@ -0,0 +1,292 @@
+//! Reference computations the external bridge contracts must reproduce, checked against the
+//! test vectors in `vectors/`.
+//!
+//! Each vector kind pairs inputs with the outputs this contract derives from them, and its
+//! `compute` fills in those outputs; external fixtures are generated the same way. Byte strings
+//! are `0x`-prefixed hex and amounts decimal strings, since they exceed JSON's safe integers.
+
+use linera_base::{
+    data_types::Amount,
+    identifiers::Account,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+use axelarx_math::{self as math, MathError};
+
+use crate::{
+    batch::{batch_root, BatchItem},
+    encoding::{BlockAttestationPayload, DepositClaim, SignedPayload, TransferApproval},
+    signature::{self, SignatureError, SignatureScheme},
+    ExternalChain, TransferDirection, TransferId,
+};
+
+/// Why a vector's outputs could not be computed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConformanceError {
+    #[error("Malformed vector field: {field}")]
+    MalformedField { field: &'static str },
+
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+
+    #[error(transparent)]
+    Math(#[from] MathError),
+}
+
+/// Fields of a withdrawal in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchItemFields {
+    pub transfer_id: TransferId,
+    pub recipient_address: String,
+    pub asset: String,
+    pub amount: String,
+    pub memo: Option<String>,
+}
+
+impl BatchItemFields {
+    fn item(&self) -> Result<BatchItem, ConformanceError> {
+        Ok(BatchItem {
+            transfer_id: self.transfer_id,
+            recipient_address: self.recipient_address.clone(),
+            asset: self.asset.clone(),
+            amount: Amount::from(parse_u128(&self.amount, "amount")?),
+            memo: self.memo.clone(),
+        })
+    }
+}
+
+/// A signed payload by message type; chains are given by their numeric id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PayloadFields {
+    DepositClaim {
+        transfer_id: TransferId,
+        source_tx_hash: String,
+        claimer_chain_id: String,
+        claimer_owner: Option<String>,
+    },
+    TransferApproval {
+        transfer_id: TransferId,
+        direction: TransferDirection,
+        chain_id: u64,
+        external_address: String,
+        asset: String,
+        net_amount: String,
+    },
+    BatchItem(BatchItemFields),
+    BlockAttestation {
+        chain_id: u64,
+        height: u64,
+        block_hash: String,
+    },
+}
+
+impl PayloadFields {
+    /// Canonical encoding of the payload
+    pub fn encode(&self) -> Result<Vec<u8>, ConformanceError> {
+        Ok(match self {
+            PayloadFields::DepositClaim { transfer_id, source_tx_hash, claimer_chain_id, claimer_owner } => {
+                let claimer = Account {
+                    chain_id: claimer_chain_id.parse()
+                        .map_err(|_| ConformanceError::MalformedField { field: "claimer_chain_id" })?,
+                    owner: claimer_owner.as_deref()
+                        .map(str::parse)
+                        .transpose()
+                        .map_err(|_| ConformanceError::MalformedField { field: "claimer_owner" })?,
+                };
+                DepositClaim { transfer_id: *transfer_id, source_tx_hash: source_tx_hash.clone(), claimer }.encode()
+            }
+            PayloadFields::TransferApproval { transfer_id, direction, chain_id, external_address, asset, net_amount } => {
+                TransferApproval {
+                    transfer_id: *transfer_id,
+                    direction: *direction,
+                    chain: ExternalChain::from_chain_id(*chain_id),
+                    external_address: external_address.clone(),
+                    asset: asset.clone(),
+                    net_amount: Amount::from(parse_u128(net_amount, "net_amount")?),
+                }
+                .encode()
+            }
+            PayloadFields::BatchItem(fields) => fields.item()?.encode(),
+            PayloadFields::BlockAttestation { chain_id, height, block_hash } => {
+                BlockAttestationPayload {
+                    chain: ExternalChain::from_chain_id(*chain_id),
+                    height: *height,
+                    block_hash: from_hex(block_hash)
+                        .and_then(|hash| hash.try_into().ok())
+                        .ok_or(ConformanceError::MalformedField { field: "block_hash" })?,
+                }
+                .encode()
+            }
+        })
+    }
+}
+
+/// A payload with its canonical encoding and the keccak256 of that encoding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadVector {
+    pub name: String,
+    pub payload: PayloadFields,
+    pub encoding: String,
+    pub keccak256: String,
+}
+
+impl PayloadVector {
+    pub fn compute(&self) -> Result<Self, ConformanceError> {
+        let encoding = self.payload.encode()?;
+        Ok(PayloadVector {
+            encoding: to_hex(&encoding),
+            keccak256: to_hex(&Keccak256::digest(&encoding)),
+            ..self.clone()
+        })
+    }
+}
+
+/// A signature by a published test key, and whether it proves ownership of `address`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureVector {
+    pub name: String,
+    pub scheme: SignatureScheme,
+    /// Test key the signature was made with; never used for real funds
+    pub secret_key: String,
+    pub address: String,
+    pub message: String,
+    pub signature: String,
+    pub valid: bool,
+}
+
+impl SignatureVector {
+    pub fn compute(&self) -> Result<Self, ConformanceError> {
+        let message = from_hex(&self.message).ok_or(ConformanceError::MalformedField { field: "message" })?;
+        let signature = from_hex(&self.signature).ok_or(ConformanceError::MalformedField { field: "signature" })?;
+        let valid = match signature::verify_ownership(self.scheme, &self.address, &message, &signature) {
+            Ok(()) => true,
+            Err(SignatureError::Mismatch) => false,
+            Err(error) => return Err(error.into()),
+        };
+        Ok(SignatureVector { valid, ..self.clone() })
+    }
+}
+
+/// A withdrawal batch with the root validators sign
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRootVector {
+    pub name: String,
+    pub chain_id: u64,
+    pub batch_id: u64,
+    pub items: Vec<BatchItemFields>,
+    pub root: String,
+}
+
+impl BatchRootVector {
+    pub fn compute(&self) -> Result<Self, ConformanceError> {
+        let items = self.items.iter().map(BatchItemFields::item).collect::<Result<Vec<_>, _>>()?;
+        let root = batch_root(ExternalChain::from_chain_id(self.chain_id), self.batch_id, &items);
+        Ok(BatchRootVector { root: to_hex(&root), ..self.clone() })
+    }
+}
+
+/// An amount converted between an asset mapping's Linera and external decimals, each way
+/// rounding down
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecimalVector {
+    pub name: String,
+    pub decimals_linera: u8,
+    pub decimals_external: u8,
+    pub linera_amount: String,
+    pub external_amount: String,
+    /// `external_amount` converted back; below `linera_amount` when precision was lost
+    pub round_trip: String,
+}
+
+impl DecimalVector {
+    pub fn compute(&self) -> Result<Self, ConformanceError> {
+        let linera_amount = parse_u128(&self.linera_amount, "linera_amount")?;
+        let external_amount = math::rescale(linera_amount, self.decimals_linera, self.decimals_external)?;
+        let round_trip = math::rescale(external_amount, self.decimals_external, self.decimals_linera)?;
+        Ok(DecimalVector {
+            external_amount: external_amount.to_string(),
+            round_trip: round_trip.to_string(),
+            ..self.clone()
+        })
+    }
+}
+
+fn parse_u128(value: &str, field: &'static str) -> Result<u128, ConformanceError> {
+    value.parse().map_err(|_| ConformanceError::MalformedField { field })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{digits}")
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits = hex.strip_prefix("0x")?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    fn vectors<T: DeserializeOwned>(json: &str) -> Vec<T> {
+        serde_json::from_str(json).expect("vector file is well formed")
+    }
+
+    #[test]
+    fn test_payload_vectors() {
+        let vectors: Vec<PayloadVector> = vectors(include_str!("../vectors/payloads.json"));
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            assert_eq!(vector.compute().as_ref(), Ok(&vector), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_signature_vectors() {
+        let vectors: Vec<SignatureVector> = vectors(include_str!("../vectors/signatures.json"));
+        for scheme in [SignatureScheme::Eip191, SignatureScheme::Ed25519] {
+            assert!(vectors.iter().any(|vector| vector.scheme == scheme && vector.valid));
+        }
+        for vector in vectors {
+            assert_eq!(vector.compute().as_ref(), Ok(&vector), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_batch_root_vectors() {
+        let vectors: Vec<BatchRootVector> = vectors(include_str!("../vectors/batch_roots.json"));
+        let sizes: Vec<usize> = vectors.iter().map(|vector| vector.items.len()).collect();
+        assert_eq!(sizes, vec![1, 2, 33]);
+        for vector in vectors {
+            assert_eq!(vector.compute().as_ref(), Ok(&vector), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_decimal_vectors() {
+        let vectors: Vec<DecimalVector> = vectors(include_str!("../vectors/decimals.json"));
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            assert_eq!(vector.compute().as_ref(), Ok(&vector), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(from_hex("0x00ff"), Some(vec![0, 255]));
+        assert_eq!(to_hex(&[0, 255]), "0x00ff");
+        assert_eq!(from_hex("00ff"), None);
+        assert_eq!(from_hex("0x0ff"), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Corridor availability: whether withdrawals to a chain can proceed now. Consumer chains
+//! registered by the admin get a `CorridorStatus` message whenever it changes; applications on
+//! the bridge's chain, such as settlement, call `CorridorAvailabilityOf` before withdrawing
+//! through a corridor.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{next_processing_time, ChainConfig, ExternalChain, ProcessingWindow};
+
+/// Consumer chains that may be registered for corridor status messages
+pub const MAX_CORRIDOR_CONSUMERS: usize = 16;
+
+/// Whether withdrawals to a chain proceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorridorAvailability {
+    Open,
+    /// The bridge is paused or the chain disabled; no resume time is known
+    Paused,
+    /// Outside the chain's processing windows; withdrawals are scheduled for `resume_at`
+    RateLimited { resume_at: Timestamp },
+}
+
+impl CorridorAvailability {
+    /// Availability of a corridor at `now`; an unconfigured chain counts as paused
+    pub fn at(paused: bool, config: Option<&ChainConfig>, windows: &[ProcessingWindow], now: Timestamp) -> Self {
+        if paused || !config.is_some_and(|config| config.is_enabled) {
+            return CorridorAvailability::Paused;
+        }
+        match next_processing_time(windows, now) {
+            resume_at if resume_at > now => CorridorAvailability::RateLimited { resume_at },
+            _ => CorridorAvailability::Open,
+        }
+    }
+}
+
+/// Last availability announced for a corridor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorridorStatus {
+    pub chain: ExternalChain,
+    pub availability: CorridorAvailability,
+    pub changed_at: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_chain_config;
+
+    #[test]
+    fn test_availability_follows_pause_config_and_windows() {
+        let config = test_chain_config(150);
+        let now = Timestamp::from(3_600 * 1_000_000);
+        assert_eq!(CorridorAvailability::at(false, Some(&config), &[], now), CorridorAvailability::Open);
+        assert_eq!(CorridorAvailability::at(true, Some(&config), &[], now), CorridorAvailability::Paused);
+        assert_eq!(CorridorAvailability::at(false, None, &[], now), CorridorAvailability::Paused);
+        let disabled = ChainConfig { is_enabled: false, ..config.clone() };
+        assert_eq!(CorridorAvailability::at(false, Some(&disabled), &[], now), CorridorAvailability::Paused);
+
+        // Closed from 01:00 until the window opens at 02:00
+        let windows = [ProcessingWindow { start: 7_200, end: 10_800 }];
+        assert_eq!(CorridorAvailability::at(false, Some(&config), &windows, now), CorridorAvailability::RateLimited {
+            resume_at: Timestamp::from(7_200 * 1_000_000),
+        });
+        let open = Timestamp::from(7_200 * 1_000_000);
+        assert_eq!(CorridorAvailability::at(false, Some(&config), &windows, open), CorridorAvailability::Open);
+    }
+}
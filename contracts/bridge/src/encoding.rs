@@ -0,0 +1,421 @@
+//! Canonical encoding of every payload validators, relayers and external contracts sign.
+//!
+//! A payload is `ENCODING_VERSION`, a one-byte domain tag, then the fields in order:
+//! integers fixed-width big-endian, strings and byte strings prefixed with their length
+//! as a u32, optional fields tagged with 0 or 1. Any change to a signed structure bumps
+//! `ENCODING_VERSION`, and payloads of any other version are rejected.
+
+use linera_base::{
+    data_types::Amount,
+    identifiers::Account,
+};
+use thiserror::Error;
+
+use crate::{BlockHash, ExternalChain, TransferDirection, TransferId};
+
+/// Version byte leading every signed payload
+pub const ENCODING_VERSION: u8 = 1;
+
+/// Separates payload kinds, so a signature over one can never pass as another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    DepositClaim = 1,
+    TransferApproval = 2,
+    BatchItem = 3,
+    BatchRoot = 4,
+    BlockAttestation = 5,
+    ReservesReport = 6,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EncodingError {
+    #[error("Unsupported payload version {version}")]
+    UnsupportedVersion { version: u8 },
+
+    #[error("Unexpected payload domain tag {tag}")]
+    WrongDomain { tag: u8 },
+
+    #[error("Payload truncated")]
+    Truncated,
+
+    #[error("Trailing bytes after payload")]
+    TrailingBytes,
+
+    #[error("Malformed payload field")]
+    MalformedField,
+}
+
+/// Writes a payload field by field
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new(domain: Domain) -> Self {
+        Encoder { bytes: vec![ENCODING_VERSION, domain as u8] }
+    }
+
+    pub fn u8(mut self, value: u8) -> Self {
+        self.bytes.push(value);
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Self {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn u64(mut self, value: u64) -> Self {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn amount(mut self, value: Amount) -> Self {
+        self.bytes.extend_from_slice(&value.into_inner().to_be_bytes());
+        self
+    }
+
+    /// Fixed-size field such as a hash, written without a length
+    pub fn fixed(mut self, value: &[u8]) -> Self {
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub fn bytes(self, value: &[u8]) -> Self {
+        let length = u32::try_from(value.len()).expect("signed fields are far below 4 GiB");
+        self.u32(length).fixed(value)
+    }
+
+    pub fn str(self, value: &str) -> Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn opt_str(self, value: Option<&str>) -> Self {
+        match value {
+            Some(value) => self.u8(1).str(value),
+            None => self.u8(0),
+        }
+    }
+
+    /// Chains are identified by their numeric id
+    pub fn chain(self, chain: ExternalChain) -> Self {
+        self.u64(chain.chain_id())
+    }
+
+    pub fn account(self, account: &Account) -> Self {
+        let owner = account.owner.as_ref().map(|owner| owner.to_string());
+        self.str(&account.chain_id.to_string()).opt_str(owner.as_deref())
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads a payload back in the order it was written
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    /// Checks the version byte and domain tag
+    pub fn new(payload: &'a [u8], domain: Domain) -> Result<Self, EncodingError> {
+        let mut decoder = Decoder { bytes: payload };
+        let version = decoder.u8()?;
+        if version != ENCODING_VERSION {
+            return Err(EncodingError::UnsupportedVersion { version });
+        }
+        let tag = decoder.u8()?;
+        if tag != domain as u8 {
+            return Err(EncodingError::WrongDomain { tag });
+        }
+        Ok(decoder)
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], EncodingError> {
+        if self.bytes.len() < length {
+            return Err(EncodingError::Truncated);
+        }
+        let (field, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(field)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, EncodingError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, EncodingError> {
+        Ok(u32::from_be_bytes(self.fixed()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, EncodingError> {
+        Ok(u64::from_be_bytes(self.fixed()?))
+    }
+
+    pub fn amount(&mut self) -> Result<Amount, EncodingError> {
+        Ok(Amount::from(u128::from_be_bytes(self.fixed()?)))
+    }
+
+    pub fn fixed<const N: usize>(&mut self) -> Result<[u8; N], EncodingError> {
+        let field = self.take(N)?;
+        Ok(field.try_into().expect("took exactly N bytes"))
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, EncodingError> {
+        let length = self.u32()? as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+
+    pub fn string(&mut self) -> Result<String, EncodingError> {
+        String::from_utf8(self.bytes()?).map_err(|_| EncodingError::MalformedField)
+    }
+
+    pub fn opt_string(&mut self) -> Result<Option<String>, EncodingError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.string()?)),
+            _ => Err(EncodingError::MalformedField),
+        }
+    }
+
+    pub fn chain(&mut self) -> Result<ExternalChain, EncodingError> {
+        Ok(ExternalChain::from_chain_id(self.u64()?))
+    }
+
+    pub fn account(&mut self) -> Result<Account, EncodingError> {
+        let chain_id = self.string()?.parse().map_err(|_| EncodingError::MalformedField)?;
+        let owner = self.opt_string()?
+            .map(|owner| owner.parse())
+            .transpose()
+            .map_err(|_| EncodingError::MalformedField)?;
+        Ok(Account { chain_id, owner })
+    }
+
+    /// Rejects bytes left over after the last field
+    pub fn finish(self) -> Result<(), EncodingError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(EncodingError::TrailingBytes)
+        }
+    }
+}
+
+/// A structure with a canonical signed form
+pub trait SignedPayload: Sized {
+    const DOMAIN: Domain;
+
+    fn encode_fields(&self, encoder: Encoder) -> Encoder;
+
+    fn decode_fields(decoder: &mut Decoder<'_>) -> Result<Self, EncodingError>;
+
+    fn encode(&self) -> Vec<u8> {
+        self.encode_fields(Encoder::new(Self::DOMAIN)).finish()
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, EncodingError> {
+        let mut decoder = Decoder::new(payload, Self::DOMAIN)?;
+        let payload = Self::decode_fields(&mut decoder)?;
+        decoder.finish()?;
+        Ok(payload)
+    }
+}
+
+/// Signed by the source address owner to claim a deposit for `claimer`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositClaim {
+    pub transfer_id: TransferId,
+    pub source_tx_hash: String,
+    pub claimer: Account,
+}
+
+impl SignedPayload for DepositClaim {
+    const DOMAIN: Domain = Domain::DepositClaim;
+
+    fn encode_fields(&self, encoder: Encoder) -> Encoder {
+        encoder.u64(self.transfer_id).str(&self.source_tx_hash).account(&self.claimer)
+    }
+
+    fn decode_fields(decoder: &mut Decoder<'_>) -> Result<Self, EncodingError> {
+        Ok(DepositClaim {
+            transfer_id: decoder.u64()?,
+            source_tx_hash: decoder.string()?,
+            claimer: decoder.account()?,
+        })
+    }
+}
+
+/// Signed by a validator approving a transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferApproval {
+    pub transfer_id: TransferId,
+    pub direction: TransferDirection,
+    pub chain: ExternalChain,
+    pub external_address: String,
+    pub asset: String,
+    /// Amount released on the far side, net of fees
+    pub net_amount: Amount,
+}
+
+impl SignedPayload for TransferApproval {
+    const DOMAIN: Domain = Domain::TransferApproval;
+
+    fn encode_fields(&self, encoder: Encoder) -> Encoder {
+        let direction = match self.direction {
+            TransferDirection::Inbound => 0,
+            TransferDirection::Outbound => 1,
+        };
+        encoder
+            .u64(self.transfer_id)
+            .u8(direction)
+            .chain(self.chain)
+            .str(&self.external_address)
+            .str(&self.asset)
+            .amount(self.net_amount)
+    }
+
+    fn decode_fields(decoder: &mut Decoder<'_>) -> Result<Self, EncodingError> {
+        let transfer_id = decoder.u64()?;
+        let direction = match decoder.u8()? {
+            0 => TransferDirection::Inbound,
+            1 => TransferDirection::Outbound,
+            _ => return Err(EncodingError::MalformedField),
+        };
+        Ok(TransferApproval {
+            transfer_id,
+            direction,
+            chain: decoder.chain()?,
+            external_address: decoder.string()?,
+            asset: decoder.string()?,
+            net_amount: decoder.amount()?,
+        })
+    }
+}
+
+/// Signed by a validator attesting the block hash at a height
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockAttestationPayload {
+    pub chain: ExternalChain,
+    pub height: u64,
+    pub block_hash: BlockHash,
+}
+
+impl SignedPayload for BlockAttestationPayload {
+    const DOMAIN: Domain = Domain::BlockAttestation;
+
+    fn encode_fields(&self, encoder: Encoder) -> Encoder {
+        encoder.chain(self.chain).u64(self.height).fixed(&self.block_hash)
+    }
+
+    fn decode_fields(decoder: &mut Decoder<'_>) -> Result<Self, EncodingError> {
+        Ok(BlockAttestationPayload {
+            chain: decoder.chain()?,
+            height: decoder.u64()?,
+            block_hash: decoder.fixed()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_base::identifiers::ChainId;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn approval() -> TransferApproval {
+        TransferApproval {
+            transfer_id: 7,
+            direction: TransferDirection::Outbound,
+            chain: ExternalChain::Ethereum,
+            external_address: "0xab".to_string(),
+            asset: "USDC".to_string(),
+            net_amount: Amount::from(1_000),
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(
+            hex(&approval().encode()),
+            concat!(
+                "0102",
+                "0000000000000007",
+                "01",
+                "0000000000000001",
+                "00000004", "30786162",
+                "00000004", "55534443",
+                "000000000000000000000000000003e8",
+            ),
+        );
+        let attestation = BlockAttestationPayload {
+            chain: ExternalChain::Polygon,
+            height: 256,
+            block_hash: [0x11; 32],
+        };
+        assert_eq!(
+            hex(&attestation.encode()),
+            concat!(
+                "0105",
+                "0000000000000089",
+                "0000000000000100",
+                "1111111111111111111111111111111111111111111111111111111111111111",
+            ),
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let approval = approval();
+        assert_eq!(TransferApproval::decode(&approval.encode()), Ok(approval));
+
+        let attestation = BlockAttestationPayload {
+            chain: ExternalChain::Custom(77),
+            height: 1,
+            block_hash: [0xab; 32],
+        };
+        assert_eq!(BlockAttestationPayload::decode(&attestation.encode()), Ok(attestation));
+
+        let claim = DepositClaim {
+            transfer_id: 3,
+            source_tx_hash: "0xdeposit".to_string(),
+            claimer: Account::chain(ChainId::root(0)),
+        };
+        assert_eq!(DepositClaim::decode(&claim.encode()), Ok(claim));
+    }
+
+    #[test]
+    fn test_rejects_other_versions_and_domains() {
+        let mut payload = approval().encode();
+        assert_eq!(
+            BlockAttestationPayload::decode(&payload),
+            Err(EncodingError::WrongDomain { tag: Domain::TransferApproval as u8 }),
+        );
+
+        payload[0] = ENCODING_VERSION + 1;
+        assert_eq!(
+            TransferApproval::decode(&payload),
+            Err(EncodingError::UnsupportedVersion { version: ENCODING_VERSION + 1 }),
+        );
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_trailing_bytes() {
+        let payload = approval().encode();
+        assert_eq!(TransferApproval::decode(&payload[..payload.len() - 1]), Err(EncodingError::Truncated));
+
+        let mut extended = payload.clone();
+        extended.push(0);
+        assert_eq!(TransferApproval::decode(&extended), Err(EncodingError::TrailingBytes));
+
+        // An oversized length prefix cannot read past the payload
+        let mut oversized = payload;
+        oversized[19..23].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(TransferApproval::decode(&oversized), Err(EncodingError::Truncated));
+    }
+}
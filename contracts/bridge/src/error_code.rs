@@ -0,0 +1,216 @@
+//! Machine-readable codes for `BridgeError`, carried on failed transfers and their updates.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BridgeError;
+
+/// Stable code for each `BridgeError` variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BridgeErrorCode {
+    TransferNotFound,
+    ChainNotConfigured,
+    ChainDisabled,
+    AssetNotSupported,
+    BelowMinimum,
+    AboveMaximum,
+    InsufficientBalance,
+    InsufficientConfirmations,
+    InsufficientApprovals,
+    AlreadyProcessed,
+    Expired,
+    ConfirmationsDecreased,
+    InconsistentConfirmations,
+    InvalidStatus,
+    Unauthorized,
+    ValidatorNotFound,
+    AlreadyApproved,
+    AlreadyAttested,
+    Paused,
+    DuplicateDeposit,
+    OutstandingDebt,
+    AlreadyReportedReorg,
+    TokenApplicationFailed,
+    MemoRequired,
+    InvalidAddress,
+    InvalidFeeOverride,
+    InvalidConfirmationOverride,
+    FeeVoucherUnavailable,
+    BridgeAddressRetired,
+    UnknownBridgeAddress,
+    UnsupportedOwnershipProof,
+    InvalidOwnershipProof,
+    QuarantineActive,
+    InvalidConfig,
+    DuplicateClientRequest,
+    MissingExternalChain,
+    BatchNotFound,
+    NoOpenBatch,
+    InvalidBatchStatus,
+    BatchResultsMismatch,
+    TooManyDepositHooks,
+    DepositHookNotFound,
+    TooManyCorridorConsumers,
+    CorridorConsumerNotFound,
+    DestinationTransactionFailed,
+    ExecutionAttemptsExhausted,
+    TransferRejected,
+    InsufficientAllowance,
+    HeldByTokenApplication,
+    RelayerBondsNotConfigured,
+    RelayerBondInsufficient,
+    RelayerBondLocked,
+    NotReportedByRelayer,
+    WithdrawalScheduled,
+    ApprovalBundleUnavailable,
+    WorkClaimed,
+    InvalidApprovalTiers,
+    CustomChainExists,
+    Math,
+    ViewError,
+}
+
+/// Why a transfer failed, for relayers and UIs that should not parse error messages
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFailure {
+    pub code: BridgeErrorCode,
+    /// Error fields by name, e.g. `required` and `current` for missing confirmations
+    pub details: BTreeMap<String, String>,
+}
+
+impl BridgeError {
+    /// No wildcard: a new variant does not compile until it has a code
+    pub fn code(&self) -> BridgeErrorCode {
+        match self {
+            BridgeError::TransferNotFound { .. } => BridgeErrorCode::TransferNotFound,
+            BridgeError::ChainNotConfigured { .. } => BridgeErrorCode::ChainNotConfigured,
+            BridgeError::ChainDisabled { .. } => BridgeErrorCode::ChainDisabled,
+            BridgeError::AssetNotSupported { .. } => BridgeErrorCode::AssetNotSupported,
+            BridgeError::BelowMinimum { .. } => BridgeErrorCode::BelowMinimum,
+            BridgeError::AboveMaximum { .. } => BridgeErrorCode::AboveMaximum,
+            BridgeError::InsufficientBalance { .. } => BridgeErrorCode::InsufficientBalance,
+            BridgeError::InsufficientConfirmations { .. } => BridgeErrorCode::InsufficientConfirmations,
+            BridgeError::InsufficientApprovals { .. } => BridgeErrorCode::InsufficientApprovals,
+            BridgeError::AlreadyProcessed => BridgeErrorCode::AlreadyProcessed,
+            BridgeError::Expired => BridgeErrorCode::Expired,
+            BridgeError::ConfirmationsDecreased { .. } => BridgeErrorCode::ConfirmationsDecreased,
+            BridgeError::InconsistentConfirmations { .. } => BridgeErrorCode::InconsistentConfirmations,
+            BridgeError::InvalidStatus { .. } => BridgeErrorCode::InvalidStatus,
+            BridgeError::Unauthorized { .. } => BridgeErrorCode::Unauthorized,
+            BridgeError::ValidatorNotFound { .. } => BridgeErrorCode::ValidatorNotFound,
+            BridgeError::AlreadyApproved => BridgeErrorCode::AlreadyApproved,
+            BridgeError::AlreadyAttested { .. } => BridgeErrorCode::AlreadyAttested,
+            BridgeError::Paused => BridgeErrorCode::Paused,
+            BridgeError::DuplicateDeposit => BridgeErrorCode::DuplicateDeposit,
+            BridgeError::OutstandingDebt { .. } => BridgeErrorCode::OutstandingDebt,
+            BridgeError::AlreadyReportedReorg { .. } => BridgeErrorCode::AlreadyReportedReorg,
+            BridgeError::TokenApplicationFailed { .. } => BridgeErrorCode::TokenApplicationFailed,
+            BridgeError::MemoRequired { .. } => BridgeErrorCode::MemoRequired,
+            BridgeError::InvalidAddress { .. } => BridgeErrorCode::InvalidAddress,
+            BridgeError::InvalidFeeOverride => BridgeErrorCode::InvalidFeeOverride,
+            BridgeError::InvalidConfirmationOverride => BridgeErrorCode::InvalidConfirmationOverride,
+            BridgeError::FeeVoucherUnavailable { .. } => BridgeErrorCode::FeeVoucherUnavailable,
+            BridgeError::BridgeAddressRetired { .. } => BridgeErrorCode::BridgeAddressRetired,
+            BridgeError::UnknownBridgeAddress { .. } => BridgeErrorCode::UnknownBridgeAddress,
+            BridgeError::UnsupportedOwnershipProof { .. } => BridgeErrorCode::UnsupportedOwnershipProof,
+            BridgeError::InvalidOwnershipProof(_) => BridgeErrorCode::InvalidOwnershipProof,
+            BridgeError::QuarantineActive { .. } => BridgeErrorCode::QuarantineActive,
+            BridgeError::InvalidConfig { .. } => BridgeErrorCode::InvalidConfig,
+            BridgeError::DuplicateClientRequest { .. } => BridgeErrorCode::DuplicateClientRequest,
+            BridgeError::MissingExternalChain { .. } => BridgeErrorCode::MissingExternalChain,
+            BridgeError::BatchNotFound { .. } => BridgeErrorCode::BatchNotFound,
+            BridgeError::NoOpenBatch { .. } => BridgeErrorCode::NoOpenBatch,
+            BridgeError::InvalidBatchStatus { .. } => BridgeErrorCode::InvalidBatchStatus,
+            BridgeError::BatchResultsMismatch { .. } => BridgeErrorCode::BatchResultsMismatch,
+            BridgeError::TooManyDepositHooks { .. } => BridgeErrorCode::TooManyDepositHooks,
+            BridgeError::DepositHookNotFound { .. } => BridgeErrorCode::DepositHookNotFound,
+            BridgeError::TooManyCorridorConsumers { .. } => BridgeErrorCode::TooManyCorridorConsumers,
+            BridgeError::CorridorConsumerNotFound { .. } => BridgeErrorCode::CorridorConsumerNotFound,
+            BridgeError::DestinationTransactionFailed { .. } => BridgeErrorCode::DestinationTransactionFailed,
+            BridgeError::ExecutionAttemptsExhausted { .. } => BridgeErrorCode::ExecutionAttemptsExhausted,
+            BridgeError::TransferRejected { .. } => BridgeErrorCode::TransferRejected,
+            BridgeError::InsufficientAllowance { .. } => BridgeErrorCode::InsufficientAllowance,
+            BridgeError::HeldByTokenApplication { .. } => BridgeErrorCode::HeldByTokenApplication,
+            BridgeError::RelayerBondsNotConfigured => BridgeErrorCode::RelayerBondsNotConfigured,
+            BridgeError::RelayerBondInsufficient { .. } => BridgeErrorCode::RelayerBondInsufficient,
+            BridgeError::RelayerBondLocked { .. } => BridgeErrorCode::RelayerBondLocked,
+            BridgeError::NotReportedByRelayer { .. } => BridgeErrorCode::NotReportedByRelayer,
+            BridgeError::WithdrawalScheduled { .. } => BridgeErrorCode::WithdrawalScheduled,
+            BridgeError::ApprovalBundleUnavailable { .. } => BridgeErrorCode::ApprovalBundleUnavailable,
+            BridgeError::WorkClaimed { .. } => BridgeErrorCode::WorkClaimed,
+            BridgeError::InvalidApprovalTiers => BridgeErrorCode::InvalidApprovalTiers,
+            BridgeError::CustomChainExists { .. } => BridgeErrorCode::CustomChainExists,
+            BridgeError::Math(_) => BridgeErrorCode::Math,
+            BridgeError::ViewError(_) => BridgeErrorCode::ViewError,
+        }
+    }
+
+    /// Fields of the error a client may act on; the message carries the rest
+    pub fn details(&self) -> BTreeMap<String, String> {
+        let fields: Vec<(&str, String)> = match self {
+            BridgeError::TransferNotFound { transfer_id } => vec![("transfer_id", transfer_id.to_string())],
+            BridgeError::BelowMinimum { amount, minimum } => {
+                vec![("amount", amount.to_string()), ("minimum", minimum.to_string())]
+            }
+            BridgeError::AboveMaximum { amount, maximum } => {
+                vec![("amount", amount.to_string()), ("maximum", maximum.to_string())]
+            }
+            BridgeError::InsufficientBalance { required, available }
+            | BridgeError::InsufficientAllowance { required, available } => {
+                vec![("required", required.to_string()), ("available", available.to_string())]
+            }
+            BridgeError::InsufficientConfirmations { current, required } => {
+                vec![("current", current.to_string()), ("required", required.to_string())]
+            }
+            BridgeError::InsufficientApprovals { current, required } => {
+                vec![("current", current.to_string()), ("required", required.to_string())]
+            }
+            BridgeError::ConfirmationsDecreased { current, reported } => {
+                vec![("current", current.to_string()), ("reported", reported.to_string())]
+            }
+            BridgeError::InconsistentConfirmations { reported, minimum } => {
+                vec![("reported", reported.to_string()), ("minimum", minimum.to_string())]
+            }
+            BridgeError::InvalidStatus { status } => vec![("status", format!("{status:?}"))],
+            BridgeError::QuarantineActive { release_at } => vec![("release_at", release_at.micros().to_string())],
+            BridgeError::RelayerBondInsufficient { bonded, minimum, .. } => {
+                vec![("bonded", bonded.to_string()), ("minimum", minimum.to_string())]
+            }
+            BridgeError::RelayerBondLocked { until } => vec![("until", until.micros().to_string())],
+            BridgeError::WithdrawalScheduled { release_at } => vec![("release_at", release_at.micros().to_string())],
+            BridgeError::ApprovalBundleUnavailable { transfer_id, reason } => {
+                vec![("transfer_id", transfer_id.to_string()), ("reason", reason.clone())]
+            }
+            BridgeError::WorkClaimed { transfer_id, until, .. } => {
+                vec![("transfer_id", transfer_id.to_string()), ("until", until.micros().to_string())]
+            }
+            BridgeError::DestinationTransactionFailed { tx_hash } => vec![("tx_hash", tx_hash.clone())],
+            BridgeError::ExecutionAttemptsExhausted { attempts } => vec![("attempts", attempts.to_string())],
+            BridgeError::TransferRejected { reason } => vec![("reason", reason.clone())],
+            _ => Vec::new(),
+        };
+        fields.into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+    }
+
+    pub fn to_failure(&self) -> TransferFailure {
+        TransferFailure { code: self.code(), details: self.details() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_carries_code_and_details() {
+        let failure = BridgeError::InsufficientConfirmations { current: 3, required: 12 }.to_failure();
+        assert_eq!(failure.code, BridgeErrorCode::InsufficientConfirmations);
+        assert_eq!(failure.details["current"], "3");
+        assert_eq!(failure.details["required"], "12");
+
+        let failure = BridgeError::Paused.to_failure();
+        assert_eq!(failure.code, BridgeErrorCode::Paused);
+        assert!(failure.details.is_empty());
+    }
+}
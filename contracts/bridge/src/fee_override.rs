@@ -0,0 +1,102 @@
+//! Negotiated fee overrides for institutional flows, keyed by account or one-time voucher.
+
+use axelarx_math::MathError;
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::Account,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{ChainConfig, FeeBreakdown, TransferDirection};
+
+/// Who an override applies to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FeeOverrideKey {
+    /// Every transfer of the account until the override expires
+    Account(Account),
+    /// A single withdrawal quoting the code
+    Voucher(String),
+}
+
+/// Custom fee terms replacing a chain's `base_fee` and `fee_percentage_bps`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeOverride {
+    pub base_fee: Amount,
+    pub fee_percentage_bps: u64,
+    pub expires_at: Timestamp,
+    /// Transfers charged under the override
+    pub uses: u64,
+}
+
+impl FeeOverride {
+    pub fn is_valid(&self) -> bool {
+        self.fee_percentage_bps <= 10_000
+    }
+
+    /// Vouchers are spent by their first use; account overrides last until they expire
+    pub fn is_usable(&self, key: &FeeOverrideKey, now: Timestamp) -> bool {
+        now < self.expires_at && (matches!(key, FeeOverrideKey::Account(_)) || self.uses == 0)
+    }
+
+    /// Fee under the override, capped at the chain's standard fee
+    pub fn fee_breakdown(
+        &self,
+        config: &ChainConfig,
+        amount: Amount,
+        direction: TransferDirection,
+    ) -> Result<FeeBreakdown, MathError> {
+        let standard = config.fee_breakdown(amount, direction)?;
+        let negotiated = ChainConfig {
+            base_fee: self.base_fee,
+            fee_percentage_bps: self.fee_percentage_bps,
+            ..config.clone()
+        };
+        let negotiated = negotiated.fee_breakdown(amount, direction)?;
+        Ok(if negotiated.total_fee < standard.total_fee { negotiated } else { standard })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_base::identifiers::ChainId;
+
+    #[test]
+    fn test_override_never_exceeds_standard_fee() {
+        let config = crate::tests::test_chain_config(0);
+        let amount = Amount::from(1_000_000);
+        let standard = config.fee_breakdown(amount, TransferDirection::Inbound).unwrap();
+
+        let discount = FeeOverride {
+            base_fee: Amount::ZERO,
+            fee_percentage_bps: 5,
+            expires_at: Timestamp::from(1_000),
+            uses: 0,
+        };
+        let fees = discount.fee_breakdown(&config, amount, TransferDirection::Inbound).unwrap();
+        assert_eq!(fees.total_fee, Amount::from(500));
+        assert_eq!(fees.net_amount, Amount::from(999_500));
+
+        let markup = FeeOverride { base_fee: Amount::from(10_000), ..discount.clone() };
+        assert_eq!(markup.fee_breakdown(&config, amount, TransferDirection::Inbound).unwrap(), standard);
+        assert!(!FeeOverride { fee_percentage_bps: 10_001, ..discount }.is_valid());
+    }
+
+    #[test]
+    fn test_voucher_is_single_use() {
+        let mut terms = FeeOverride {
+            base_fee: Amount::ZERO,
+            fee_percentage_bps: 0,
+            expires_at: Timestamp::from(1_000),
+            uses: 0,
+        };
+        let voucher = FeeOverrideKey::Voucher("OTC-1".to_string());
+        let account = FeeOverrideKey::Account(Account::chain(ChainId::root(0)));
+        assert!(terms.is_usable(&voucher, Timestamp::from(999)));
+        assert!(!terms.is_usable(&voucher, Timestamp::from(1_000)));
+
+        terms.uses = 1;
+        assert!(!terms.is_usable(&voucher, Timestamp::from(0)));
+        assert!(terms.is_usable(&account, Timestamp::from(0)));
+    }
+}
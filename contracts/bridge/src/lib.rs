@@ -14,9 +14,11 @@ including Ethereum, Bitcoin, Solana, Avalanche, and more.
 */
 
 use async_trait::async_trait;
+use axelarx_math::{self as math, MathError};
 use linera_base::{
-    data_types::{Amount, Timestamp},
-    identifiers::{Account, ChainId},
+    abi::{ContractAbi, ServiceAbi},
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::{Account, ApplicationId, ChainId},
 };
 use linera_sdk::{
     base::{ContractRuntime, ServiceRuntime},
@@ -27,12 +29,85 @@ use linera_views::{
     views::{MapView, QueueView, RegisterView, ViewError},
     RootView,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
+mod address;
+mod approval_bundle;
+mod approval_check;
+mod approval_tier;
+mod batch;
+pub mod conformance;
+mod confirmation_override;
+mod corridor;
+pub mod encoding;
+mod error_code;
+mod fee_override;
+mod overview;
+mod processing_window;
+mod relayer_bond;
+mod relayer_work;
+mod reserves;
+mod signature;
+mod withdrawal;
+
+pub use address::AddressFormat;
+pub use approval_bundle::{ApprovalBundle, ApprovalQuorum, ApprovalSnapshot, BundleSignature};
+pub use approval_check::{
+    approval_checks, ApprovalCheck, ApprovalCheckResult, ApprovalContext, ApprovalIneligibility, ApprovalPrevalidation,
+};
+pub use approval_tier::{ApprovalRequirement, ApprovalTier, ApprovalTiers, MAX_APPROVAL_TIERS};
+pub use batch::{batch_root, BatchItem, BatchLimits, BatchStatus, WithdrawalBatch, MAX_BATCH_TRANSFERS};
+pub use confirmation_override::{ConfirmationOverride, ConfirmationTier};
+pub use corridor::{CorridorAvailability, CorridorStatus, MAX_CORRIDOR_CONSUMERS};
+pub use error_code::{BridgeErrorCode, TransferFailure};
+pub use fee_override::{FeeOverride, FeeOverrideKey};
+pub use overview::{
+    hour_bucket, BridgeOverview, ChainOverview, ChainVolume, HourlyVolume, OldestOpenTransfer, ValidatorSummary,
+    VolumeBucket, VOLUME_WINDOW_HOURS,
+};
+pub use processing_window::{
+    next_processing_time, ProcessingWindow, ProcessingWindowStatus, MAX_PROCESSING_WINDOWS, SECONDS_PER_DAY,
+};
+pub use relayer_bond::{ChainBondMinimum, RelayerBond, RelayerBondStatus, RelayerChainStatus};
+pub use relayer_work::{
+    in_shard, RelayerAction, RelayerTask, RelayerWorkItem, RelayerWorkStats, WorkClaim, WorkReceipt,
+    WORK_CLAIM_SECONDS,
+};
+pub use reserves::{reserves_hash, AssetLiabilities, ProofOfReserves, ReservesSnapshot};
+use reserves::liabilities_of;
+pub use signature::{SignatureError, SignatureScheme};
+pub use withdrawal::{
+    check_withdrawal, validate_withdrawal, WithdrawalContext, WithdrawalIssue, WithdrawalQuote, WithdrawalRequest,
+};
+use encoding::{DepositClaim, SignedPayload, TransferApproval};
+
 /// Unique identifier for bridge transfers
 pub type TransferId = u64;
 
+/// Block hash on an external chain
+pub type BlockHash = [u8; 32];
+
+/// Prior chain config versions kept per chain
+pub const CHAIN_CONFIG_HISTORY_LIMIT: u64 = 32;
+
+/// Time a relayer has to report a claimed withdrawal before it is re-queued
+pub const EXECUTION_TIMEOUT_SECONDS: u64 = 3600;
+
+/// Expiry of transfers created before chain configs set their own
+pub const DEFAULT_TRANSFER_EXPIRY_SECONDS: u64 = 3600 * 24;
+
+/// Bounds on a chain's inbound and outbound transfer expiries
+pub const MIN_TRANSFER_EXPIRY_SECONDS: u64 = 3600;
+pub const MAX_TRANSFER_EXPIRY_SECONDS: u64 = 3600 * 24 * 7;
+
+/// Re-queues of a stalled withdrawal before it is failed and refunded
+pub const MAX_EXECUTION_RETRIES: u32 = 3;
+
+/// Deposit hooks an account can register
+pub const MAX_DEPOSIT_HOOKS: usize = 8;
+
 /// External chain identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ExternalChain {
@@ -44,10 +119,31 @@ pub enum ExternalChain {
     Arbitrum,
     Optimism,
     BSC,
+    CosmosHub,
+    Osmosis,
     Custom(u64),
 }
 
 impl ExternalChain {
+    /// Inverse of `chain_id`; ids of the named chains never map to `Custom`
+    pub fn from_chain_id(chain_id: u64) -> Self {
+        [
+            ExternalChain::Ethereum,
+            ExternalChain::Bitcoin,
+            ExternalChain::Solana,
+            ExternalChain::Avalanche,
+            ExternalChain::Polygon,
+            ExternalChain::Arbitrum,
+            ExternalChain::Optimism,
+            ExternalChain::BSC,
+            ExternalChain::CosmosHub,
+            ExternalChain::Osmosis,
+        ]
+        .into_iter()
+        .find(|chain| chain.chain_id() == chain_id)
+        .unwrap_or(ExternalChain::Custom(chain_id))
+    }
+    
     pub fn chain_id(&self) -> u64 {
         match self {
             ExternalChain::Ethereum => 1,
@@ -58,6 +154,9 @@ impl ExternalChain {
             ExternalChain::Arbitrum => 42161,
             ExternalChain::Optimism => 10,
             ExternalChain::BSC => 56,
+            // Cosmos chain ids are strings; these are internal numeric identifiers
+            ExternalChain::CosmosHub => 1_000_118,
+            ExternalChain::Osmosis => 1_000_119,
             ExternalChain::Custom(id) => *id,
         }
     }
@@ -72,10 +171,14 @@ impl ExternalChain {
             ExternalChain::Arbitrum => "Arbitrum",
             ExternalChain::Optimism => "Optimism",
             ExternalChain::BSC => "BNB Smart Chain",
+            ExternalChain::CosmosHub => "Cosmos Hub",
+            ExternalChain::Osmosis => "Osmosis",
             ExternalChain::Custom(_) => "Custom Chain",
         }
     }
     
+    /// Fallback confirmation count, used only when neither the chain config nor
+    /// the custom chain registry specifies one
     pub fn required_confirmations(&self) -> u64 {
         match self {
             ExternalChain::Ethereum => 12,
@@ -86,13 +189,166 @@ impl ExternalChain {
             ExternalChain::Arbitrum => 1,
             ExternalChain::Optimism => 1,
             ExternalChain::BSC => 15,
+            ExternalChain::CosmosHub => 1,
+            ExternalChain::Osmosis => 1,
             ExternalChain::Custom(_) => 12,
         }
     }
+    
+    /// Typical block interval, for wait time estimates
+    pub fn block_time_ms(&self) -> u64 {
+        match self {
+            ExternalChain::Ethereum => 12_000,
+            ExternalChain::Bitcoin => 600_000,
+            ExternalChain::Solana => 400,
+            ExternalChain::Avalanche => 2_000,
+            ExternalChain::Polygon => 2_000,
+            ExternalChain::Arbitrum => 250,
+            ExternalChain::Optimism => 2_000,
+            ExternalChain::BSC => 3_000,
+            ExternalChain::CosmosHub => 6_000,
+            ExternalChain::Osmosis => 6_000,
+            ExternalChain::Custom(_) => 12_000,
+        }
+    }
+    
+    /// Typical time for a block to be finalized, for wait time estimates
+    pub fn finality_seconds(&self) -> u64 {
+        match self {
+            ExternalChain::Ethereum => 768,
+            ExternalChain::Bitcoin => 3_600,
+            ExternalChain::Solana => 13,
+            ExternalChain::Avalanche => 2,
+            ExternalChain::Polygon => 120,
+            ExternalChain::Arbitrum => 960,
+            ExternalChain::Optimism => 960,
+            ExternalChain::BSC => 8,
+            ExternalChain::CosmosHub => 6,
+            ExternalChain::Osmosis => 6,
+            ExternalChain::Custom(_) => 768,
+        }
+    }
+    
+    /// Address format for built-in chains; custom chains use their registry entry
+    pub fn address_format(&self) -> AddressFormat {
+        match self {
+            ExternalChain::Ethereum
+            | ExternalChain::Avalanche
+            | ExternalChain::Polygon
+            | ExternalChain::Arbitrum
+            | ExternalChain::Optimism
+            | ExternalChain::BSC => AddressFormat::Evm,
+            ExternalChain::CosmosHub => AddressFormat::Bech32 { hrp: "cosmos".to_string() },
+            ExternalChain::Osmosis => AddressFormat::Bech32 { hrp: "osmo".to_string() },
+            ExternalChain::Bitcoin | ExternalChain::Solana | ExternalChain::Custom(_) => AddressFormat::Opaque,
+        }
+    }
+}
+
+/// Stored form of a transfer's source chain: `ExternalChain`'s variants in their original
+/// order, with `Linera` appended so records written before it existed still decode
+#[derive(Serialize, Deserialize)]
+enum StoredSourceChain {
+    Ethereum,
+    Bitcoin,
+    Solana,
+    Avalanche,
+    Polygon,
+    Arbitrum,
+    Optimism,
+    BSC,
+    CosmosHub,
+    Osmosis,
+    Custom(u64),
+    Linera,
+}
+
+impl From<Option<ExternalChain>> for StoredSourceChain {
+    fn from(source: Option<ExternalChain>) -> Self {
+        match source {
+            None => StoredSourceChain::Linera,
+            Some(ExternalChain::Ethereum) => StoredSourceChain::Ethereum,
+            Some(ExternalChain::Bitcoin) => StoredSourceChain::Bitcoin,
+            Some(ExternalChain::Solana) => StoredSourceChain::Solana,
+            Some(ExternalChain::Avalanche) => StoredSourceChain::Avalanche,
+            Some(ExternalChain::Polygon) => StoredSourceChain::Polygon,
+            Some(ExternalChain::Arbitrum) => StoredSourceChain::Arbitrum,
+            Some(ExternalChain::Optimism) => StoredSourceChain::Optimism,
+            Some(ExternalChain::BSC) => StoredSourceChain::BSC,
+            Some(ExternalChain::CosmosHub) => StoredSourceChain::CosmosHub,
+            Some(ExternalChain::Osmosis) => StoredSourceChain::Osmosis,
+            Some(ExternalChain::Custom(id)) => StoredSourceChain::Custom(id),
+        }
+    }
+}
+
+impl From<StoredSourceChain> for Option<ExternalChain> {
+    fn from(source: StoredSourceChain) -> Self {
+        Some(match source {
+            StoredSourceChain::Linera => return None,
+            StoredSourceChain::Ethereum => ExternalChain::Ethereum,
+            StoredSourceChain::Bitcoin => ExternalChain::Bitcoin,
+            StoredSourceChain::Solana => ExternalChain::Solana,
+            StoredSourceChain::Avalanche => ExternalChain::Avalanche,
+            StoredSourceChain::Polygon => ExternalChain::Polygon,
+            StoredSourceChain::Arbitrum => ExternalChain::Arbitrum,
+            StoredSourceChain::Optimism => ExternalChain::Optimism,
+            StoredSourceChain::BSC => ExternalChain::BSC,
+            StoredSourceChain::CosmosHub => ExternalChain::CosmosHub,
+            StoredSourceChain::Osmosis => ExternalChain::Osmosis,
+            StoredSourceChain::Custom(id) => ExternalChain::Custom(id),
+        })
+    }
+}
+
+/// Serde adapter for `BridgeTransfer::source_chain`
+mod source_chain_format {
+    use super::{ExternalChain, StoredSourceChain};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(source: &Option<ExternalChain>, serializer: S) -> Result<S::Ok, S::Error> {
+        StoredSourceChain::from(*source).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<ExternalChain>, D::Error> {
+        StoredSourceChain::deserialize(deserializer).map(Into::into)
+    }
+}
+
+/// Runtime registry entry for a custom external chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomChainInfo {
+    pub name: String,
+    pub address_format: AddressFormat,
+    pub required_confirmations: u64,
+}
+
+/// Deposits a hook is notified of
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositHookFilter {
+    /// None matches every asset
+    pub asset: Option<String>,
+    /// Smallest credited (net) amount that triggers the hook
+    pub min_amount: Amount,
+}
+
+impl DepositHookFilter {
+    pub fn matches(&self, asset: &str, net_amount: Amount) -> bool {
+        self.asset.as_deref().map_or(true, |filtered| filtered == asset) && net_amount >= self.min_amount
+    }
+}
+
+/// Chain told with `DepositCompleted` whenever one of the owner's deposits matching the filter completes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositHook {
+    pub id: u64,
+    pub application_chain: ChainId,
+    pub filter: DepositHookFilter,
+    pub registered_at: Timestamp,
 }
 
 /// Transfer status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TransferStatus {
     /// Transfer initiated, waiting for processing
     Pending,
@@ -112,6 +368,34 @@ pub enum TransferStatus {
     Refunded,
     /// Transfer expired
     Expired,
+    /// Credited deposit reverted after a source chain reorg
+    Reverted,
+    /// Confirmed deposit with no creditable recipient, claimable by the source address owner
+    ClaimPending,
+    /// Claim window lapsed; awaiting admin resolution
+    Unclaimed,
+    /// Large confirmed deposit held until its release time
+    Quarantined,
+    /// Quarantined deposit frozen by the admin pending investigation
+    Frozen,
+    /// Withdrawal in a batch, which carries its approval and execution
+    Batched,
+    /// Withdrawal initiated outside its chain's processing windows, held until `release_at`
+    Scheduled,
+}
+
+impl TransferStatus {
+    /// Whether the transfer still needs work, or a decision, before it is finished
+    pub fn is_open(&self) -> bool {
+        !matches!(
+            self,
+            TransferStatus::Completed
+                | TransferStatus::Failed
+                | TransferStatus::Refunded
+                | TransferStatus::Expired
+                | TransferStatus::Reverted
+        )
+    }
 }
 
 /// Transfer direction
@@ -123,6 +407,14 @@ pub enum TransferDirection {
     Outbound,
 }
 
+/// Transfer waiting for a validator's signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub transfer: BridgeTransfer,
+    /// Canonical bytes to sign for `ApproveTransfer`
+    pub payload: Vec<u8>,
+}
+
 /// Validator approval record
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ValidatorApproval {
@@ -132,14 +424,36 @@ pub struct ValidatorApproval {
     pub timestamp: Timestamp,
 }
 
+/// Validator attestation of an external block hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAttestation {
+    pub validator: Account,
+    pub block_hash: BlockHash,
+    pub signature: Vec<u8>,
+    pub timestamp: Timestamp,
+}
+
+/// Record of a validator attesting a block hash that lost to the finalized one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictingAttestation {
+    pub chain: ExternalChain,
+    pub height: u64,
+    pub attested_hash: BlockHash,
+    pub finalized_hash: BlockHash,
+    pub flagged_at: Timestamp,
+}
+
 /// Bridge transfer record
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct BridgeTransfer {
     pub id: TransferId,
     
     // Transfer details
     pub direction: TransferDirection,
-    pub source_chain: ExternalChain,
+    /// None for transfers leaving Linera
+    #[serde(with = "source_chain_format")]
+    pub source_chain: Option<ExternalChain>,
     pub destination_chain: Option<ExternalChain>,
     
     // User information
@@ -150,27 +464,271 @@ pub struct BridgeTransfer {
     pub asset: String,
     pub amount: Amount,
     pub fee: Amount,
+    /// Part of `fee` reimbursing the relayer's destination-chain gas
+    pub relayer_fee: Amount,
+    /// Part of the booked fee that went to the insurance fund, at the share in effect then;
+    /// reversing the fee takes back exactly this
+    #[serde(default)]
+    pub insurance_fee: Amount,
     pub net_amount: Amount,
+    /// Negotiated terms `fee` was charged under, if any
+    pub fee_override: Option<FeeOverrideKey>,
     
     // Transaction hashes
     pub source_tx_hash: Option<String>,
     pub destination_tx_hash: Option<String>,
+    pub source_block_height: Option<u64>,
+    pub memo: Option<String>,
+    /// When a quarantined deposit may be credited, or a scheduled withdrawal enters approval
+    pub release_at: Option<Timestamp>,
+    /// Chain config version in effect when the transfer was created
+    pub config_version: u64,
     
     // Status and timing
     pub status: TransferStatus,
     pub confirmations: u64,
     pub required_confirmations: u64,
+    /// Finality rule in effect when the deposit was reported; None for withdrawals
+    pub finality: Option<FinalityProfile>,
     pub created_at: Timestamp,
     pub completed_at: Option<Timestamp>,
     pub expires_at: Timestamp,
     
-    // Validator approvals
-    pub approvals: Vec<ValidatorApproval>,
+    // Validator approvals (individual approvals live in `BridgeState::transfer_approvals`)
     pub approval_threshold: u32,
+    /// Approval summary, recorded when the transfer reaches a terminal state
+    pub approval_count: u32,
+    pub approval_weight: u32,
+    /// Requirement of the chain's approval tier for `amount`, snapshotted at creation; None
+    /// without tiers, when the bridge-wide percentage applies
+    #[serde(default)]
+    pub approval_requirement: Option<ApprovalRequirement>,
+    
+    /// Signer that reported the deposit; None for withdrawals
+    #[serde(default)]
+    pub reporter: Option<Account>,
+    // Relayer that reported completion
+    pub relayer: Option<Account>,
+    /// Relayer that claimed the withdrawal with `ExecuteTransfer`
+    pub executing_relayer: Option<Account>,
+    /// When an unreported execution is re-queued for another relayer
+    pub executing_deadline: Option<Timestamp>,
     
     // Error handling
     pub error_message: Option<String>,
+    /// Code and details of the error, once the transfer has failed
+    #[serde(default)]
+    pub failure: Option<TransferFailure>,
+    /// Release of an abandoned deposit back to the depositor on the source chain
+    #[serde(default)]
+    pub external_refund: Option<ExternalRefund>,
+    /// Latest confirmation count accepted by `UpdateConfirmations`
+    #[serde(default)]
+    pub last_confirmation_report: Option<ConfirmationReport>,
+    /// When a reorg last reset the confirmations; observations before it are ignored
+    #[serde(default)]
+    pub reorged_at: Option<Timestamp>,
     pub retry_count: u32,
+    /// Expiry the chain config set for the transfer's direction when it was created; later
+    /// config changes leave it alone
+    #[serde(default)]
+    pub expiry_seconds: Option<u64>,
+}
+
+impl BridgeTransfer {
+    /// Time the transfer has to complete, from its creation or scheduled release
+    pub fn expiry(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.expiry_seconds.unwrap_or(DEFAULT_TRANSFER_EXPIRY_SECONDS))
+    }
+    
+    /// Whether the claiming relayer let its execution deadline pass without reporting back
+    pub fn execution_stalled(&self, now: Timestamp) -> bool {
+        self.status == TransferStatus::Executing && self.executing_deadline.is_some_and(|deadline| now > deadline)
+    }
+    
+    /// Whether the source block is final under the profile the deposit was reported with
+    pub fn is_final(&self, finalized_height: Option<u64>) -> bool {
+        match self.finality {
+            Some(profile) => {
+                profile.is_final(self.confirmations, self.source_block_height.unwrap_or_default(), finalized_height)
+            }
+            None => self.confirmations >= self.required_confirmations,
+        }
+    }
+    
+    /// External chain on the far side of the transfer
+    pub fn corridor_chain(&self) -> Result<ExternalChain, BridgeError> {
+        match self.direction {
+            TransferDirection::Inbound => self.source_chain,
+            TransferDirection::Outbound => self.destination_chain,
+        }
+        .ok_or(BridgeError::MissingExternalChain { transfer_id: self.id })
+    }
+    
+    /// What cancelling the withdrawal now would return, or None once it can no longer be
+    /// cancelled. Before quorum nobody has worked on it and the whole fee comes back; after
+    /// quorum the validators' work is paid for and only the unearned relayer share does.
+    pub fn cancellation_refund(&self) -> Option<CancellationRefund> {
+        if self.direction != TransferDirection::Outbound {
+            return None;
+        }
+        let fee_retained = match self.status {
+            TransferStatus::Scheduled | TransferStatus::AwaitingApproval => Amount::ZERO,
+            TransferStatus::Approved => self.fee.saturating_sub(self.relayer_fee),
+            _ => return None,
+        };
+        Some(CancellationRefund {
+            refund: self.amount.saturating_sub(fee_retained),
+            fee_refunded: self.fee.saturating_sub(fee_retained),
+            fee_retained,
+        })
+    }
+    
+    /// Payload a validator signs to approve the transfer
+    pub fn approval_payload(&self) -> Result<Vec<u8>, BridgeError> {
+        Ok(TransferApproval {
+            transfer_id: self.id,
+            direction: self.direction,
+            chain: self.corridor_chain()?,
+            external_address: self.external_address.clone(),
+            asset: self.asset.clone(),
+            net_amount: self.net_amount,
+        }
+        .encode())
+    }
+}
+
+impl Serialize for BridgeTransfer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BridgeTransfer::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BridgeTransfer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut transfer = BridgeTransfer::deserialize(deserializer)?;
+        // Outbound transfers used to record Linera as `Custom(0)`; inbound ones really came from it
+        if transfer.direction == TransferDirection::Outbound
+            && transfer.source_chain == Some(ExternalChain::Custom(0))
+        {
+            transfer.source_chain = None;
+        }
+        Ok(transfer)
+    }
+}
+
+/// Source chain transaction returning an abandoned deposit, as reported by a validator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalRefund {
+    pub tx_hash: String,
+    pub reported_by: Account,
+    pub reported_at: Timestamp,
+}
+
+/// Confirmation count a relayer reported, and when it observed it on the source chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationReport {
+    pub reported_by: Account,
+    pub confirmations: u64,
+    pub observed_at: Timestamp,
+    pub reported_at: Timestamp,
+}
+
+/// Notable bridge events kept for relayers and support
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeEvent {
+    /// A deposit expired before reaching finality; the bridge contract on the source chain
+    /// should release it back to `source_address`
+    InboundAbandoned {
+        transfer_id: TransferId,
+        source_chain: ExternalChain,
+        source_tx_hash: Option<String>,
+        source_address: String,
+        asset: String,
+        amount: Amount,
+        confirmations: u64,
+        required_confirmations: u64,
+        timestamp: Timestamp,
+    },
+    /// An abandoned deposit was released on the source chain
+    ExternalRefundReported {
+        transfer_id: TransferId,
+        refund_tx_hash: String,
+        reported_by: Account,
+        timestamp: Timestamp,
+    },
+    /// A confirmation report observed too long before the last accepted one, or before a reorg
+    /// reset the deposit, was dropped
+    StaleConfirmationReportIgnored {
+        transfer_id: TransferId,
+        reported_by: Account,
+        confirmations: u64,
+        observed_at: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// A relayer added to its bond
+    RelayerBonded {
+        relayer: Account,
+        amount: Amount,
+        bonded: Amount,
+        timestamp: Timestamp,
+    },
+    /// A relayer started unbonding; the amount stays slashable until `unbonding_until`
+    RelayerUnbonding {
+        relayer: Account,
+        amount: Amount,
+        unbonding_until: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// Part of a relayer's bond went to the insurance fund over a fraudulent report
+    RelayerSlashed {
+        relayer: Account,
+        transfer_id: TransferId,
+        asset: String,
+        amount: Amount,
+        reason: String,
+        slashed_by: Account,
+        timestamp: Timestamp,
+    },
+    /// A relayer repeated an action already performed on the transfer; accepted as a no-op and
+    /// counted against it. `first_by` is who performed it first, if recorded
+    DuplicateSubmission {
+        transfer_id: TransferId,
+        relayer: Account,
+        action: RelayerAction,
+        first_by: Option<Account>,
+        timestamp: Timestamp,
+    },
+    /// A withdrawal arrived outside its chain's processing windows and waits for the next one
+    WithdrawalScheduled {
+        transfer_id: TransferId,
+        chain: ExternalChain,
+        release_at: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// A corridor's availability changed and its consumers were told
+    CorridorStatusChanged {
+        chain: ExternalChain,
+        availability: CorridorAvailability,
+        consumers: usize,
+        timestamp: Timestamp,
+    },
+    /// An admin set or cleared the token application issuing a wrapped asset
+    TokenApplicationConfigured {
+        asset: String,
+        application_id: Option<ApplicationId>,
+        configured_by: Account,
+        timestamp: Timestamp,
+    },
+}
+
+/// Split of a cancelled withdrawal between the user and the bridge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancellationRefund {
+    /// Credited back to the user
+    pub refund: Amount,
+    pub fee_refunded: Amount,
+    pub fee_retained: Amount,
 }
 
 /// Chain configuration
@@ -178,14 +736,181 @@ pub struct BridgeTransfer {
 pub struct ChainConfig {
     pub chain: ExternalChain,
     pub is_enabled: bool,
+    /// Contract deposits go to
     pub bridge_contract_address: String,
+    /// Previous contracts, still accepting deposits until their cutoffs
+    pub deprecated_addresses: Vec<DeprecatedAddress>,
     pub supported_assets: Vec<AssetMapping>,
     pub min_transfer_amount: Amount,
     pub max_transfer_amount: Amount,
     pub base_fee: Amount,
     pub fee_percentage_bps: u64, // Basis points
+    /// Carved out of withdrawal fees to reimburse the completing relayer
+    pub relayer_gas_fee: Amount,
     pub required_confirmations: u64,
+    /// How deposits become final; None counts confirmations against the resolved
+    /// `required_confirmations`
+    pub finality: Option<FinalityProfile>,
     pub estimated_time_seconds: u64,
+    /// Withdrawals must carry a memo (e.g. IBC transfers)
+    pub memo_required: bool,
+    /// Deposits above this amount are quarantined after approval
+    pub large_transfer_threshold: Option<Amount>,
+    /// How long large deposits are held before crediting
+    pub quarantine_seconds: u64,
+    /// Time a deposit has to reach finality and be approved
+    pub inbound_expiry_seconds: u64,
+    /// Time a withdrawal has to be approved and executed, from its initiation or scheduled release
+    pub outbound_expiry_seconds: u64,
+    /// Bumped on every change; assigned by the contract
+    pub version: u64,
+}
+
+/// A replaced bridge contract and the end of its overlap window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecatedAddress {
+    pub address: String,
+    /// Deposits to the address are rejected from this time on
+    pub cutoff: Timestamp,
+}
+
+/// Rule deciding when a deposit's source block can no longer be reverted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FinalityProfile {
+    /// Probabilistic finality: final after this many confirmations
+    ConfirmationCount(u64),
+    /// Deterministic finality: final once validators attest a block at or above the
+    /// deposit's height through `SubmitBlockAttestation`
+    FinalityTag,
+    /// Both a confirmation floor and a finality attestation
+    Hybrid { min_confirmations: u64 },
+}
+
+impl FinalityProfile {
+    /// Whether a deposit at `block_height` with `confirmations` is final, given the chain's
+    /// latest attested height
+    pub fn is_final(&self, confirmations: u64, block_height: u64, finalized_height: Option<u64>) -> bool {
+        let attested = finalized_height.is_some_and(|finalized| block_height <= finalized);
+        match *self {
+            FinalityProfile::ConfirmationCount(required) => confirmations >= required,
+            FinalityProfile::FinalityTag => attested,
+            FinalityProfile::Hybrid { min_confirmations } => confirmations >= min_confirmations && attested,
+        }
+    }
+    
+    /// Confirmations the profile requires regardless of attestations
+    pub fn min_confirmations(&self) -> u64 {
+        match *self {
+            FinalityProfile::ConfirmationCount(required) => required,
+            FinalityProfile::FinalityTag => 0,
+            FinalityProfile::Hybrid { min_confirmations } => min_confirmations,
+        }
+    }
+    
+    /// Typical time from inclusion until a deposit on `chain` is final
+    pub fn expected_wait_seconds(&self, chain: ExternalChain) -> u64 {
+        let confirmations = self.min_confirmations().saturating_mul(chain.block_time_ms()).div_ceil(1_000);
+        match self {
+            FinalityProfile::ConfirmationCount(_) => confirmations,
+            FinalityProfile::FinalityTag => chain.finality_seconds(),
+            FinalityProfile::Hybrid { .. } => confirmations.max(chain.finality_seconds()),
+        }
+    }
+}
+
+/// Split of a transfer fee between protocol and relayer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    pub total_fee: Amount,
+    pub protocol_fee: Amount,
+    pub relayer_fee: Amount,
+    pub net_amount: Amount,
+}
+
+impl ChainConfig {
+    /// Expiry applying to new transfers in `direction`
+    pub fn expiry_seconds(&self, direction: TransferDirection) -> u64 {
+        match direction {
+            TransferDirection::Inbound => self.inbound_expiry_seconds,
+            TransferDirection::Outbound => self.outbound_expiry_seconds,
+        }
+    }
+    
+    /// Both expiries must lie within `MIN_TRANSFER_EXPIRY_SECONDS..=MAX_TRANSFER_EXPIRY_SECONDS`
+    pub fn check_expiries(&self) -> Result<(), BridgeError> {
+        let bounds = MIN_TRANSFER_EXPIRY_SECONDS..=MAX_TRANSFER_EXPIRY_SECONDS;
+        for (direction, seconds) in [("Inbound", self.inbound_expiry_seconds), ("Outbound", self.outbound_expiry_seconds)] {
+            if !bounds.contains(&seconds) {
+                return Err(BridgeError::InvalidConfig {
+                    reason: format!(
+                        "{direction} expiry of {seconds}s outside {MIN_TRANSFER_EXPIRY_SECONDS}s to {MAX_TRANSFER_EXPIRY_SECONDS}s"
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+    
+    /// Contracts deposits are accepted at, primary first
+    pub fn accepted_addresses(&self, now: Timestamp) -> Vec<String> {
+        std::iter::once(self.bridge_contract_address.clone())
+            .chain(self.deprecated_addresses.iter().filter(|d| now < d.cutoff).map(|d| d.address.clone()))
+            .collect()
+    }
+    
+    /// Checks the contract a deposit was sent to; deprecated ones only count before their cutoff.
+    pub fn check_deposit_address(&self, address: &str, now: Timestamp) -> Result<(), BridgeError> {
+        if address.eq_ignore_ascii_case(&self.bridge_contract_address) {
+            return Ok(());
+        }
+        match self.deprecated_addresses.iter().find(|d| address.eq_ignore_ascii_case(&d.address)) {
+            Some(deprecated) if now < deprecated.cutoff => Ok(()),
+            Some(deprecated) => Err(BridgeError::BridgeAddressRetired {
+                address: address.to_string(),
+                cutoff: deprecated.cutoff,
+                primary: self.bridge_contract_address.clone(),
+            }),
+            None => Err(BridgeError::UnknownBridgeAddress { address: address.to_string() }),
+        }
+    }
+    
+    /// Makes `new_address` the primary, keeping the current one open for `overlap_seconds`.
+    /// Entries past their cutoff are dropped.
+    pub fn rotate_address(&mut self, new_address: String, overlap_seconds: u64, now: Timestamp) {
+        let previous = std::mem::replace(&mut self.bridge_contract_address, new_address);
+        self.deprecated_addresses.retain(|d| {
+            now < d.cutoff
+                && !d.address.eq_ignore_ascii_case(&previous)
+                && !d.address.eq_ignore_ascii_case(&self.bridge_contract_address)
+        });
+        if overlap_seconds > 0 && !previous.eq_ignore_ascii_case(&self.bridge_contract_address) {
+            self.deprecated_addresses.push(DeprecatedAddress {
+                address: previous,
+                cutoff: now + std::time::Duration::from_secs(overlap_seconds),
+            });
+        }
+    }
+    
+    /// Whether a deposit of `amount` must wait out the quarantine period
+    pub fn requires_quarantine(&self, amount: Amount) -> bool {
+        self.large_transfer_threshold.map_or(false, |threshold| amount > threshold)
+    }
+    
+    /// Fee for a transfer of `amount`; relayer gas is only reimbursed on withdrawals
+    pub fn fee_breakdown(&self, amount: Amount, direction: TransferDirection) -> Result<FeeBreakdown, MathError> {
+        let percentage_fee = math::amount_fee(amount, self.fee_percentage_bps)?;
+        let total_fee = math::checked_add(self.base_fee, percentage_fee)?;
+        let relayer_fee = match direction {
+            TransferDirection::Outbound => self.relayer_gas_fee.min(total_fee),
+            TransferDirection::Inbound => Amount::ZERO,
+        };
+        Ok(FeeBreakdown {
+            total_fee,
+            protocol_fee: total_fee - relayer_fee,
+            relayer_fee,
+            net_amount: math::saturating_sub(amount, total_fee),
+        })
+    }
 }
 
 /// Asset mapping between chains
@@ -199,6 +924,30 @@ pub struct AssetMapping {
     pub is_native: bool,
 }
 
+/// ABI of the fungible token application issuing a wrapped asset
+pub struct WrappedTokenAbi;
+
+impl ContractAbi for WrappedTokenAbi {
+    type Operation = WrappedTokenOperation;
+    type Response = WrappedTokenResponse;
+}
+
+/// Calls the bridge makes into a wrapped-asset token application
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WrappedTokenOperation {
+    /// Issue wrapped tokens to an account
+    Mint { target_account: Account, amount: Amount },
+    /// Burn wrapped tokens held by the authenticated owner
+    Burn { owner: Account, amount: Amount },
+}
+
+/// Result of a wrapped-asset token application call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WrappedTokenResponse {
+    Ok,
+    Failed { reason: String },
+}
+
 /// Validator configuration
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ValidatorConfig {
@@ -209,6 +958,43 @@ pub struct ValidatorConfig {
     pub registered_at: Timestamp,
 }
 
+/// Approval record of a validator since the last `ResetValidatorPerformance`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorPerformance {
+    pub approvals: u64,
+    /// Sum over approvals of the seconds from the transfer awaiting approval to the vote
+    pub total_latency_seconds: u64,
+    /// Transfers that reached quorum without the validator's vote
+    pub missed_quorums: u64,
+}
+
+impl ValidatorPerformance {
+    pub fn average_latency_seconds(&self) -> Option<u64> {
+        self.total_latency_seconds.checked_div(self.approvals)
+    }
+}
+
+/// Payout from the insurance fund, linked to the transfer whose loss it covers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsurancePayout {
+    pub id: u64,
+    pub recipient: Account,
+    pub asset: String,
+    pub amount: Amount,
+    pub reference_transfer_id: TransferId,
+    pub paid_by: Account,
+    pub paid_at: Timestamp,
+}
+
+/// Record created by an operation submitted with a client request id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationReceipt {
+    pub client_request_id: u64,
+    /// Id of the created record
+    pub id: u64,
+    pub created_at: Timestamp,
+}
+
 /// Bridge operations
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Operation {
@@ -218,6 +1004,18 @@ pub enum Operation {
         destination_address: String,
         asset: String,
         amount: Amount,
+        memo: Option<String>,
+        /// Caller-chosen id under which a receipt with the transfer id is stored
+        #[serde(default)]
+        client_request_id: Option<u64>,
+        /// One-time code for negotiated fees
+        #[serde(default)]
+        fee_voucher: Option<String>,
+    },
+    
+    /// Cancel an own withdrawal that no relayer has claimed yet
+    CancelWithdrawal {
+        transfer_id: TransferId,
     },
     
     /// Report inbound deposit (External -> Linera)
@@ -225,21 +1023,32 @@ pub enum Operation {
         source_chain: ExternalChain,
         tx_hash: String,
         source_address: String,
-        recipient: Account,
+        /// None when the deposit names no resolvable Linera account
+        recipient: Option<Account>,
         asset: String,
         amount: Amount,
+        block_height: u64,
         confirmations: u64,
+        /// Bridge contract the deposit was sent to; None for the primary
+        #[serde(default)]
+        bridge_contract_address: Option<String>,
     },
     
-    /// Update deposit confirmations
+    /// Update deposit confirmations. Counts may not decrease, nor fall short of what the attested
+    /// finalized height implies; reports observed more than the staleness window before the last
+    /// accepted one are ignored.
     UpdateConfirmations {
         transfer_id: TransferId,
         confirmations: u64,
+        /// When the relayer observed the count; defaults to the block time
+        #[serde(default)]
+        observed_at: Option<Timestamp>,
     },
     
     /// Approve transfer as validator
     ApproveTransfer {
         transfer_id: TransferId,
+        /// Over `BridgeTransfer::approval_payload`
         signature: Vec<u8>,
     },
     
@@ -255,6 +1064,12 @@ pub enum Operation {
         success: bool,
     },
     
+    /// Announce that the signer is handling an open transfer, for `WORK_CLAIM_SECONDS`. Advisory:
+    /// other relayers see it in `GetRelayerWork`, but their reports are still accepted
+    ClaimRelayerWork {
+        transfer_id: TransferId,
+    },
+    
     /// Claim refund for failed/expired transfer
     ClaimRefund {
         transfer_id: TransferId,
@@ -263,6 +1078,51 @@ pub enum Operation {
     /// Process expired transfers
     ProcessExpiredTransfers,
     
+    /// Record that a deposit abandoned in `Confirming` was released back to its depositor on the
+    /// source chain (active validators only)
+    ReportExternalRefund {
+        transfer_id: TransferId,
+        refund_tx_hash: String,
+    },
+    
+    /// Re-queue withdrawals whose relayer missed its execution deadline (permissionless)
+    RequeueStalledWithdrawals,
+    
+    /// Credit a quarantined deposit whose release time has passed (permissionless)
+    ReleaseQuarantined {
+        transfer_id: TransferId,
+    },
+    
+    /// Claim a deposit awaiting a recipient by signing `claim_challenge` with the source address key
+    ClaimDeposit {
+        transfer_id: TransferId,
+        proof_of_source_ownership: Vec<u8>,
+    },
+    
+    /// Seal a chain's open withdrawal batch (anyone once it reaches its age limit, the admin at any time)
+    SealBatch {
+        chain: ExternalChain,
+    },
+    
+    /// Approve a sealed batch's root as validator
+    ApproveBatch {
+        batch_id: u64,
+        /// Over the batch root
+        signature: Vec<u8>,
+    },
+    
+    /// Take an approved batch for submission as one destination chain transaction
+    ExecuteBatch {
+        batch_id: u64,
+    },
+    
+    /// Report a batch transaction with one success flag per item, in batch order
+    CompleteBatch {
+        batch_id: u64,
+        tx_hash: String,
+        results: Vec<bool>,
+    },
+    
     // Admin operations
     
     /// Configure chain support
@@ -290,13 +1150,307 @@ pub enum Operation {
         chain: ExternalChain,
         base_fee: Option<Amount>,
         fee_percentage_bps: Option<u64>,
+        relayer_gas_fee: Option<Amount>,
     },
     
-    /// Emergency pause
-    EmergencyPause,
+    /// Change a chain's transfer expiries (admin only); transfers already created keep theirs
+    UpdateExpiries {
+        chain: ExternalChain,
+        inbound_expiry_seconds: Option<u64>,
+        outbound_expiry_seconds: Option<u64>,
+    },
     
-    /// Resume from pause
-    Resume,
+    /// Move accrued relayer gas reimbursements into the relayer's balance
+    ClaimRelayerFees {
+        asset: String,
+    },
+    
+    /// Attest a block hash on an external chain as validator
+    SubmitBlockAttestation {
+        chain: ExternalChain,
+        height: u64,
+        block_hash: BlockHash,
+        /// Over the encoded `BlockAttestationPayload`
+        signature: Vec<u8>,
+    },
+    
+    /// Report a source chain reorg as validator (executes once quorum weight agrees)
+    ReportReorg {
+        chain: ExternalChain,
+        from_height: u64,
+    },
+    
+    /// Register a custom external chain; an existing one is only replaced with `replace` set
+    /// (admin only)
+    RegisterCustomChain {
+        chain_id: u64,
+        info: CustomChainInfo,
+        #[serde(default)]
+        replace: bool,
+    },
+    
+    /// Configure (or clear) the token application issuing a wrapped asset (admin only)
+    ConfigureTokenApplication {
+        asset: String,
+        application_id: Option<ApplicationId>,
+    },
+    
+    /// Batch withdrawals to a chain under the given limits, or approve them one by one (admin only)
+    ConfigureBatching {
+        chain: ExternalChain,
+        limits: Option<BatchLimits>,
+    },
+    
+    /// Daily UTC windows in which `chain`'s withdrawals enter approval; withdrawals outside them
+    /// are scheduled for the next opening. Empty processes at any time (admin only).
+    SetProcessingWindows {
+        chain: ExternalChain,
+        windows: Vec<ProcessingWindow>,
+    },
+    
+    /// Move a scheduled withdrawal into approval once its chain's window is open (permissionless)
+    ReleaseScheduledWithdrawal {
+        transfer_id: TransferId,
+    },
+    
+    /// Drop corridor statistics buckets older than the given day (admin only)
+    PruneCorridorStats {
+        before_day: u64,
+    },
+    
+    /// Start validator performance over from zero, e.g. at a validator set review (admin only)
+    ResetValidatorPerformance,
+    
+    /// Update the window during which completed deposits can be clawed back
+    UpdateReorgClawbackWindow {
+        seconds: u64,
+    },
+    
+    /// Block deposits to an account; they are held for claim instead (admin only)
+    QuarantineAccount {
+        account: Account,
+    },
+    
+    /// Lift an account quarantine (admin only)
+    ReleaseAccount {
+        account: Account,
+    },
+    
+    /// Update how far a confirmation report may have been observed before the last accepted one
+    /// (admin only)
+    UpdateConfirmationStalenessWindow {
+        seconds: u64,
+    },
+    
+    /// Update how long uncreditable deposits stay claimable (admin only)
+    UpdateClaimWindow {
+        seconds: u64,
+    },
+    
+    /// Freeze a quarantined deposit pending investigation (admin only)
+    FreezeTransfer {
+        transfer_id: TransferId,
+        reason: String,
+    },
+    
+    /// Return a frozen deposit to quarantine, or fail it (admin only)
+    ResolveFrozenTransfer {
+        transfer_id: TransferId,
+        release: bool,
+    },
+    
+    /// Credit an unclaimed deposit to a recipient chosen by the admin (admin only)
+    ResolveUnclaimedDeposit {
+        transfer_id: TransferId,
+        recipient: Account,
+    },
+    
+    /// Set the share of every collected fee (in basis points) accruing to the insurance fund
+    UpdateInsuranceFeeBps {
+        bps: u64,
+    },
+    
+    /// Pay out of the insurance fund for a loss on `reference_transfer_id` (admin only)
+    PayInsuranceClaim {
+        recipient: Account,
+        asset: String,
+        amount: Amount,
+        reference_transfer_id: TransferId,
+    },
+    
+    /// Pay protocol revenue (excluding the insurance reserve) to the fee collector (admin only)
+    WithdrawCollectedFees {
+        asset: String,
+        amount: Option<Amount>,
+    },
+    
+    /// Keep the hash of the current proof-of-reserves report on chain (admin only)
+    RecordReservesSnapshot,
+    
+    /// Negotiated fees for an account or voucher, in place of the chain's (admin only).
+    /// The fee charged never exceeds the standard one.
+    SetFeeOverride {
+        key: FeeOverrideKey,
+        base_fee: Amount,
+        fee_percentage_bps: u64,
+        expires_at: Timestamp,
+    },
+    
+    /// Withdraw a fee override (admin only)
+    RemoveFeeOverride {
+        key: FeeOverrideKey,
+    },
+    
+    /// Confirmations required of new deposits of `asset` from `chain`, by deposit size, in
+    /// place of the chain's count (admin only)
+    SetConfirmationOverride {
+        chain: ExternalChain,
+        asset: String,
+        confirmation_override: ConfirmationOverride,
+    },
+    
+    /// Return deposits of `asset` from `chain` to the chain's confirmation count (admin only)
+    RemoveConfirmationOverride {
+        chain: ExternalChain,
+        asset: String,
+    },
+    
+    /// Approval requirements of new transfers on `chain`, by amount normalized at the tiers' asset
+    /// rates; transfers already created keep theirs (admin only)
+    SetApprovalTiers {
+        chain: ExternalChain,
+        tiers: ApprovalTiers,
+    },
+    
+    /// Return new transfers on `chain` to the bridge-wide approval percentage (admin only)
+    RemoveApprovalTiers {
+        chain: ExternalChain,
+    },
+    
+    /// Set the fee collector account (admin only)
+    SetFeeCollector {
+        collector: Option<Account>,
+    },
+    
+    /// Point a chain at a redeployed bridge contract; the old one keeps accepting deposits
+    /// for `overlap_seconds` (admin only)
+    RotateBridgeAddress {
+        chain: ExternalChain,
+        new_address: String,
+        overlap_seconds: u64,
+    },
+    
+    /// Send `DepositCompleted` to `application_chain` when the caller's deposits matching
+    /// `filter` complete
+    RegisterDepositHook {
+        application_chain: ChainId,
+        filter: DepositHookFilter,
+    },
+    
+    /// Remove one of the caller's deposit hooks
+    RemoveDepositHook {
+        hook_id: u64,
+    },
+    
+    /// Send `CorridorStatus` to `consumer_chain` whenever a corridor's availability changes
+    /// (admin only)
+    RegisterCorridorConsumer {
+        consumer_chain: ChainId,
+    },
+    
+    /// Stop sending `CorridorStatus` to `consumer_chain` (admin only)
+    RemoveCorridorConsumer {
+        consumer_chain: ChainId,
+    },
+    
+    /// Announce a change in `chain`'s availability that no operation caused, such as a processing
+    /// window opening (permissionless)
+    RefreshCorridorStatus {
+        chain: ExternalChain,
+    },
+    
+    /// Bridged balance of an account, returned as `BridgeResponse::Balance` to a calling application
+    BalanceOf {
+        account: Account,
+        asset: String,
+    },
+    
+    /// Current availability of a corridor, returned as `BridgeResponse::CorridorAvailability` to a
+    /// calling application
+    CorridorAvailabilityOf {
+        chain: ExternalChain,
+    },
+    
+    /// Move bridged funds from the signer to another account. Applications call it with the
+    /// user's signature forwarded.
+    Transfer {
+        to: Account,
+        asset: String,
+        amount: Amount,
+    },
+    
+    /// Let `spender` move up to `allowance` of the signer's `asset` with `TransferFrom`; zero
+    /// revokes it
+    Approve {
+        spender: ApplicationId,
+        asset: String,
+        allowance: Amount,
+    },
+    
+    /// Move funds from `owner` to `to` out of the allowance `owner` gave the calling application
+    TransferFrom {
+        owner: Account,
+        to: Account,
+        asset: String,
+        amount: Amount,
+    },
+    
+    /// Post relayer bonds in `asset`, slashable for `unbonding_seconds` after unbonding. The asset
+    /// cannot change while bonds are held (admin only).
+    ConfigureRelayerBonds {
+        asset: String,
+        unbonding_seconds: u64,
+    },
+    
+    /// Bond required to report deposits and withdrawal completions for `chain`; zero lets
+    /// anyone report (admin only)
+    SetRelayerBondMinimum {
+        chain: ExternalChain,
+        minimum: Amount,
+    },
+    
+    /// Lock part of the signer's balance of the bond asset as relayer bond
+    BondRelayer {
+        amount: Amount,
+    },
+    
+    /// Start the unbonding cooldown for part of the signer's bond
+    UnbondRelayer {
+        amount: Amount,
+    },
+    
+    /// Return the signer's unbonded balance once the cooldown has passed
+    WithdrawRelayerBond,
+    
+    /// Move up to `amount` of a relayer's bond, unbonding balance included, into the insurance
+    /// fund over a fraudulent report of `transfer_id` (admin only)
+    SlashRelayer {
+        relayer: Account,
+        transfer_id: TransferId,
+        amount: Amount,
+        reason: String,
+    },
+    
+    /// Hand the admin role to another account (admin only)
+    TransferAdmin {
+        new_admin: Account,
+    },
+    
+    /// Emergency pause
+    EmergencyPause,
+    
+    /// Resume from pause
+    Resume,
 }
 
 /// Cross-chain messages
@@ -306,10 +1460,14 @@ pub enum Message {
     DepositNotification {
         chain: ExternalChain,
         tx_hash: String,
-        recipient: Account,
+        recipient: Option<Account>,
         asset: String,
         amount: Amount,
+        block_height: u64,
         confirmations: u64,
+        /// Bridge contract the deposit was sent to; None for the primary
+        #[serde(default)]
+        bridge_contract_address: Option<String>,
     },
     
     /// Withdrawal request to relayer
@@ -319,6 +1477,16 @@ pub enum Message {
         recipient_address: String,
         asset: String,
         amount: Amount,
+        memo: Option<String>,
+    },
+    
+    /// Approved batch, with its validator signatures, to relayer
+    BatchWithdrawalRequest {
+        batch_id: u64,
+        chain: ExternalChain,
+        root: [u8; 32],
+        items: Vec<BatchItem>,
+        signatures: Vec<ValidatorApproval>,
     },
     
     /// Transfer status update
@@ -326,6 +1494,9 @@ pub enum Message {
         transfer_id: TransferId,
         status: TransferStatus,
         tx_hash: Option<String>,
+        /// Set when the transfer moved to `Failed`
+        #[serde(default)]
+        failure: Option<TransferFailure>,
     },
     
     /// Validator signature for approval
@@ -335,6 +1506,27 @@ pub enum Message {
         signature: Vec<u8>,
         approved: bool,
     },
+    
+    /// A deposit matching a registered hook was credited
+    DepositCompleted {
+        hook_id: u64,
+        transfer_id: TransferId,
+        user: Account,
+        source_chain: ExternalChain,
+        asset: String,
+        /// Amount credited, net of fees
+        amount: Amount,
+        source_tx_hash: Option<String>,
+        completed_at: Timestamp,
+    },
+    
+    /// A corridor's availability changed, sent to every registered consumer chain
+    CorridorStatus {
+        /// `ExternalChain::chain_id` of the corridor
+        corridor: u64,
+        availability: CorridorAvailability,
+        changed_at: Timestamp,
+    },
 }
 
 /// Bridge errors
@@ -373,6 +1565,12 @@ pub enum BridgeError {
     #[error("Transfer expired")]
     Expired,
     
+    #[error("Confirmations cannot decrease: have {current}, reported {reported}")]
+    ConfirmationsDecreased { current: u64, reported: u64 },
+    
+    #[error("Confirmations inconsistent with the attested finalized height: reported {reported}, at least {minimum}")]
+    InconsistentConfirmations { reported: u64, minimum: u64 },
+    
     #[error("Invalid status for operation: {status:?}")]
     InvalidStatus { status: TransferStatus },
     
@@ -385,15 +1583,132 @@ pub enum BridgeError {
     #[error("Already approved by this validator")]
     AlreadyApproved,
     
+    #[error("Block already attested by this validator: chain {chain:?}, height {height}")]
+    AlreadyAttested { chain: ExternalChain, height: u64 },
+    
     #[error("Bridge is paused")]
     Paused,
     
     #[error("Duplicate deposit: tx_hash already processed")]
     DuplicateDeposit,
     
+    #[error("Account has outstanding reorg debt: {account:?}")]
+    OutstandingDebt { account: Account },
+    
+    #[error("Already reported this reorg: chain {chain:?}, height {from_height}")]
+    AlreadyReportedReorg { chain: ExternalChain, from_height: u64 },
+    
+    #[error("Token application call failed for {asset}: {reason}")]
+    TokenApplicationFailed { asset: String, reason: String },
+    
+    #[error("Memo required for transfers to {chain:?}")]
+    MemoRequired { chain: ExternalChain },
+    
     #[error("Invalid address format: {address}")]
     InvalidAddress { address: String },
     
+    #[error("Fee override percentage above 100%")]
+    InvalidFeeOverride,
+    
+    #[error("Confirmation tiers must be non-empty with increasing minimum amounts")]
+    InvalidConfirmationOverride,
+    
+    #[error("Approval tiers need increasing maximums, an unbounded top tier, valid percentages and enough seniors")]
+    InvalidApprovalTiers,
+    
+    #[error("Fee voucher unknown, expired or already used: {code}")]
+    FeeVoucherUnavailable { code: String },
+    
+    #[error("Bridge contract {address} stopped accepting deposits at {cutoff:?}; the primary is {primary}")]
+    BridgeAddressRetired { address: String, cutoff: Timestamp, primary: String },
+    
+    #[error("Not a bridge contract address of this chain: {address}")]
+    UnknownBridgeAddress { address: String },
+    
+    #[error("Ownership proofs not supported for {chain:?}")]
+    UnsupportedOwnershipProof { chain: ExternalChain },
+    
+    #[error("Invalid ownership proof: {0}")]
+    InvalidOwnershipProof(#[from] SignatureError),
+    
+    #[error("Transfer quarantined until {release_at:?}")]
+    QuarantineActive { release_at: Timestamp },
+    
+    #[error("Invalid configuration: {reason}")]
+    InvalidConfig { reason: String },
+    
+    #[error("Client request id already used: {client_request_id}")]
+    DuplicateClientRequest { client_request_id: u64 },
+    
+    #[error("Transfer {transfer_id} has no external chain")]
+    MissingExternalChain { transfer_id: TransferId },
+    
+    #[error("Batch not found: {batch_id}")]
+    BatchNotFound { batch_id: u64 },
+    
+    #[error("No open batch for {chain:?}")]
+    NoOpenBatch { chain: ExternalChain },
+    
+    #[error("Invalid batch status for operation: {status:?}")]
+    InvalidBatchStatus { status: BatchStatus },
+    
+    #[error("Batch has {expected} items, got {got} results")]
+    BatchResultsMismatch { expected: usize, got: usize },
+    
+    #[error("Too many deposit hooks: maximum {maximum}")]
+    TooManyDepositHooks { maximum: usize },
+    
+    #[error("Deposit hook not found: {hook_id}")]
+    DepositHookNotFound { hook_id: u64 },
+    
+    #[error("Too many corridor consumers: maximum {maximum}")]
+    TooManyCorridorConsumers { maximum: usize },
+    
+    #[error("Corridor consumer not registered: {consumer_chain}")]
+    CorridorConsumerNotFound { consumer_chain: ChainId },
+    
+    #[error("Transaction failed on destination chain: {tx_hash}")]
+    DestinationTransactionFailed { tx_hash: String },
+    
+    #[error("Not completed by a relayer after {attempts} attempts")]
+    ExecutionAttemptsExhausted { attempts: u32 },
+    
+    #[error("Transfer rejected: {reason}")]
+    TransferRejected { reason: String },
+    
+    #[error("Insufficient allowance: required {required}, available {available}")]
+    InsufficientAllowance { required: Amount, available: Amount },
+    
+    #[error("{asset} is held by its token application; transfer it there")]
+    HeldByTokenApplication { asset: String },
+    
+    #[error("Relayer bonds are not configured")]
+    RelayerBondsNotConfigured,
+    
+    #[error("Relayer bond too small: {relayer:?} has {bonded}, minimum {minimum}")]
+    RelayerBondInsufficient { relayer: Account, bonded: Amount, minimum: Amount },
+    
+    #[error("Relayer bond unbonding until {until:?}")]
+    RelayerBondLocked { until: Timestamp },
+    
+    #[error("Transfer {transfer_id} was not reported by {relayer:?}")]
+    NotReportedByRelayer { transfer_id: TransferId, relayer: Account },
+    
+    #[error("Withdrawal scheduled for the processing window opening at {release_at:?}")]
+    WithdrawalScheduled { release_at: Timestamp },
+    
+    #[error("No approval bundle for transfer {transfer_id}: {reason}")]
+    ApprovalBundleUnavailable { transfer_id: TransferId, reason: String },
+    
+    #[error("Transfer {transfer_id} claimed by {relayer:?} until {until:?}")]
+    WorkClaimed { transfer_id: TransferId, relayer: Account, until: Timestamp },
+    
+    #[error("Custom chain {chain_id} is already registered")]
+    CustomChainExists { chain_id: u64 },
+    
+    #[error("Math error: {0}")]
+    Math(#[from] MathError),
+    
     #[error("View error: {0}")]
     ViewError(#[from] ViewError),
 }
@@ -410,6 +1725,93 @@ pub struct BridgeStats {
     pub failed_transfers: u64,
 }
 
+/// Per-direction aggregates within a corridor bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectionStats {
+    pub transfers: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub volume: Amount,
+    pub fees: Amount,
+    /// Sum of created -> completed durations, in seconds
+    pub total_completion_seconds: u64,
+}
+
+impl DirectionStats {
+    pub fn average_completion_seconds(&self) -> Option<u64> {
+        (self.completed > 0).then(|| self.total_completion_seconds / self.completed)
+    }
+    
+    /// Failure rate in basis points of all finished transfers
+    pub fn failure_rate_bps(&self) -> u64 {
+        let finished = self.completed + self.failed;
+        if finished == 0 {
+            0
+        } else {
+            self.failed * 10_000 / finished
+        }
+    }
+    
+    pub fn merge(&mut self, other: &DirectionStats) {
+        self.transfers += other.transfers;
+        self.completed += other.completed;
+        self.failed += other.failed;
+        self.volume = self.volume.saturating_add(other.volume);
+        self.fees = self.fees.saturating_add(other.fees);
+        self.total_completion_seconds += other.total_completion_seconds;
+    }
+}
+
+/// Aggregates for one (chain, asset, day) corridor bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorridorStats {
+    pub inbound: DirectionStats,
+    pub outbound: DirectionStats,
+}
+
+impl CorridorStats {
+    pub fn direction_mut(&mut self, direction: TransferDirection) -> &mut DirectionStats {
+        match direction {
+            TransferDirection::Inbound => &mut self.inbound,
+            TransferDirection::Outbound => &mut self.outbound,
+        }
+    }
+    
+    pub fn merge(&mut self, other: &CorridorStats) {
+        self.inbound.merge(&other.inbound);
+        self.outbound.merge(&other.outbound);
+    }
+}
+
+/// Transfer lifecycle events tracked in corridor statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorridorEvent {
+    Created { volume: Amount, fee: Amount },
+    Completed { fee: Amount, completion_seconds: u64 },
+    Failed,
+}
+
+/// Whole seconds elapsed between two timestamps
+pub fn elapsed_seconds(from: Timestamp, to: Timestamp) -> u64 {
+    to.micros().saturating_sub(from.micros()) / 1_000_000
+}
+
+/// Day bucket (days since epoch) for a timestamp
+pub fn day_bucket(timestamp: Timestamp) -> u64 {
+    timestamp.micros() / (86_400 * 1_000_000)
+}
+
+/// Message the source address owner signs to claim a deposit for `claimer`.
+/// Binding the transfer and claimer prevents replaying a proof for another account.
+pub fn claim_challenge(transfer: &BridgeTransfer, claimer: &Account) -> Vec<u8> {
+    DepositClaim {
+        transfer_id: transfer.id,
+        source_tx_hash: transfer.source_tx_hash.clone().unwrap_or_default(),
+        claimer: *claimer,
+    }
+    .encode()
+}
+
 /// Bridge contract state
 #[derive(RootView)]
 pub struct BridgeState<C> {
@@ -431,9 +1833,30 @@ pub struct BridgeState<C> {
     /// Processed deposit tx hashes (to prevent duplicates)
     pub processed_deposits: MapView<C, String, TransferId>,
     
+    /// Validator approvals of active transfers: (transfer, validator) -> approval
+    pub transfer_approvals: MapView<C, (TransferId, Account), ValidatorApproval>,
+    
+    /// Approval weight and count accumulated per active transfer
+    pub approval_weights: MapView<C, TransferId, (u32, u32)>,
+    
+    /// Approvals of each withdrawal up to its quorum, kept for its approval bundle
+    pub approval_snapshots: MapView<C, TransferId, ApprovalSnapshot>,
+    
+    /// Bumped by every change to the validator set
+    pub validator_set_epoch: RegisterView<C, u64>,
+    
+    /// Transfers still short of approval quorum: the validators' work queue
+    pub awaiting_approval: MapView<C, TransferId, ()>,
+    
+    /// Withdrawals claimed by a relayer and not yet reported
+    pub executing_transfers: MapView<C, TransferId, ()>,
+    
     /// Chain configurations
     pub chain_configs: MapView<C, u64, ChainConfig>,
     
+    /// Prior chain configurations: (chain_id, version) -> config, bounded per chain
+    pub chain_config_history: MapView<C, (u64, u64), ChainConfig>,
+    
     /// Validators
     pub validators: MapView<C, Account, ValidatorConfig>,
     
@@ -449,36 +1872,381 @@ pub struct BridgeState<C> {
     /// Bridge statistics
     pub stats: RegisterView<C, BridgeStats>,
     
+    /// Open transfers per chain and status
+    pub open_transfer_counts: MapView<C, u64, BTreeMap<TransferStatus, u64>>,
+    
+    /// Lowest-numbered open transfer
+    pub oldest_open_transfer: RegisterView<C, Option<TransferId>>,
+    
+    /// Volume created per chain over the last 24 hours, by hour
+    pub hourly_volume: MapView<C, u64, HourlyVolume>,
+    
+    /// Number of validators, and how many of them are inactive
+    pub validator_count: RegisterView<C, u32>,
+    pub inactive_validator_count: RegisterView<C, u32>,
+    
     /// Fee collector address
     pub fee_collector: RegisterView<C, Option<Account>>,
     
-    /// Collected fees (per asset)
+    /// Negotiated fee terms by account or voucher
+    pub fee_overrides: MapView<C, FeeOverrideKey, FeeOverride>,
+    
+    /// Tiered confirmation requirements by (chain id, asset)
+    pub confirmation_overrides: MapView<C, (u64, String), ConfirmationOverride>,
+    
+    /// Approval tiers by chain id
+    pub approval_tiers: MapView<C, u64, ApprovalTiers>,
+    
+    /// Collected protocol fees (per asset), net of the insurance carve-out
     pub collected_fees: MapView<C, String, Amount>,
     
+    /// Bridge admin, set at instantiation; privileged operations are refused while unset
+    pub admin: RegisterView<C, Option<Account>>,
+    
+    /// Share of every collected fee accruing to the insurance fund, in basis points
+    pub insurance_fee_bps: RegisterView<C, u64>,
+    
+    /// Insurance reserve (per asset)
+    pub insurance_fund: MapView<C, String, Amount>,
+    
+    /// Insurance payout history
+    pub insurance_payouts: MapView<C, u64, InsurancePayout>,
+    
+    /// Next insurance payout ID
+    pub next_insurance_payout_id: RegisterView<C, u64>,
+    
+    /// Receipts of operations submitted with a client request id: (caller, client id) -> receipt
+    pub receipts: MapView<C, (Account, u64), OperationReceipt>,
+    
     /// Bridge pause status
     pub is_paused: RegisterView<C, bool>,
-}
-
-/// Bridge contract implementation
-pub struct BridgeContract;
-
-#[async_trait]
-impl Contract for BridgeContract {
-    type Message = Message;
-    type Parameters = ();
-    type State = BridgeState<ContractRuntime<Self>>;
-
-    async fn load(runtime: ContractRuntime<Self>) -> Self {
-        BridgeContract
-    }
-
-    async fn instantiate(&mut self, state: &mut Self::State, _argument: ()) {
-        state.next_transfer_id.set(1);
-        state.stats.set(BridgeStats::default());
-        state.total_validator_weight.set(0);
-        state.approval_threshold_percentage.set(67); // 2/3 majority
+    
+    /// Block attestations awaiting quorum: (chain_id, height) -> attestations
+    pub block_attestations: MapView<C, (u64, u64), Vec<BlockAttestation>>,
+    
+    /// Finalized block hashes: (chain_id, height) -> block hash
+    pub finalized_blocks: MapView<C, (u64, u64), BlockHash>,
+    
+    /// Latest finalized block height per chain
+    pub latest_finalized_height: MapView<C, u64, u64>,
+    
+    /// Validators flagged for review after attesting a conflicting block hash
+    pub flagged_validators: MapView<C, Account, Vec<ConflictingAttestation>>,
+    
+    /// Deposits above the finalized height per chain: chain_id -> (block height, transfer)
+    pub unfinalized_deposits: MapView<C, u64, Vec<(u64, TransferId)>>,
+    
+    /// Validator reorg reports awaiting quorum: (chain_id, from_height) -> reporters
+    pub reorg_reports: MapView<C, (u64, u64), Vec<Account>>,
+    
+    /// Window after completion during which a reorged deposit is clawed back
+    pub reorg_clawback_window_seconds: RegisterView<C, u64>,
+    
+    /// Debt from clawed back deposits that were already spent: (account, asset) -> amount
+    pub debts: MapView<C, (Account, String), Amount>,
+    
+    /// Number of assets each account owes debt in
+    pub indebted_accounts: MapView<C, Account, u32>,
+    
+    /// Token applications issuing wrapped assets; assets without one use internal balances
+    pub token_applications: MapView<C, String, ApplicationId>,
+    
+    /// Claimable relayer gas reimbursements: (relayer, asset) -> amount
+    pub relayer_fees: MapView<C, (Account, String), Amount>,
+    
+    /// Asset relayer bonds are posted in; None until configured
+    pub relayer_bond_asset: RegisterView<C, Option<String>>,
+    
+    /// How long unbonded balance stays slashable before it can be withdrawn
+    pub relayer_unbonding_seconds: RegisterView<C, u64>,
+    
+    /// Bond required to report for a chain: chain_id -> minimum; chains without one are open
+    pub relayer_bond_minimums: MapView<C, u64, ChainBondMinimum>,
+    
+    /// Relayer bonds
+    pub relayer_bonds: MapView<C, Account, RelayerBond>,
+    
+    /// Sum of all bonded and unbonding balances
+    pub total_relayer_bonds: RegisterView<C, Amount>,
+    
+    /// Custom chain registry
+    pub custom_chains: MapView<C, u64, CustomChainInfo>,
+    
+    /// Corridor statistics: (chain_id, asset, day bucket) -> aggregates
+    pub corridor_stats: MapView<C, (u64, String, u64), CorridorStats>,
+    
+    /// Accounts that may not receive deposits
+    pub quarantined_accounts: MapView<C, Account, ()>,
+    
+    /// How long a ClaimPending deposit can be claimed before it becomes Unclaimed
+    pub claim_window_seconds: RegisterView<C, u64>,
+    
+    /// How far before the last accepted confirmation report a new one may have been observed
+    pub confirmation_staleness_seconds: RegisterView<C, u64>,
+    
+    /// Deposits whose claim window lapsed, awaiting admin resolution
+    pub unclaimed_deposits: MapView<C, TransferId, ()>,
+    
+    /// Quarantined or frozen deposits -> release time
+    pub quarantined_transfers: MapView<C, TransferId, Timestamp>,
+    
+    /// Withdrawal batching limits per chain; chains without an entry approve withdrawals one by one
+    pub batch_limits: MapView<C, u64, BatchLimits>,
+    
+    /// Processing windows per chain; chains without an entry process withdrawals at any time
+    pub processing_windows: MapView<C, u64, Vec<ProcessingWindow>>,
+    
+    /// Open batch per chain: chain_id -> batch
+    pub open_batches: MapView<C, u64, u64>,
+    
+    /// Withdrawal batches
+    pub batches: MapView<C, u64, WithdrawalBatch>,
+    
+    /// Next batch ID
+    pub next_batch_id: RegisterView<C, u64>,
+    
+    /// Validator approvals of unfinished batches: (batch, validator) -> approval
+    pub batch_approvals: MapView<C, (u64, Account), ValidatorApproval>,
+    
+    /// Approval metrics per validator since `performance_since`
+    pub validator_performance: MapView<C, Account, ValidatorPerformance>,
+    
+    /// When validator performance was last reset; zero until the first reset
+    pub performance_since: RegisterView<C, Timestamp>,
+    
+    /// Deposit hooks per account
+    pub deposit_hooks: MapView<C, Account, Vec<DepositHook>>,
+    
+    /// Next deposit hook ID
+    pub next_deposit_hook_id: RegisterView<C, u64>,
+    
+    /// Hooks already notified of a transfer: (hook, transfer)
+    pub hook_deliveries: MapView<C, (u64, TransferId), ()>,
+    
+    /// Chains told about corridor availability changes, in registration order
+    pub corridor_consumers: RegisterView<C, Vec<ChainId>>,
+    
+    /// Last availability announced per corridor; corridors without an entry were last open
+    pub corridor_statuses: MapView<C, u64, CorridorStatus>,
+    
+    /// What an application may still move for an owner: (owner, spender, asset) -> allowance
+    pub allowances: MapView<C, (Account, ApplicationId, String), Amount>,
+    
+    /// Deposits that expired before reaching finality, until their external refund is reported
+    pub abandoned_deposits: MapView<C, TransferId, ()>,
+    
+    /// Sum of `balances` per asset
+    pub total_balances: MapView<C, String, Amount>,
+    
+    /// Net amount of approved and executing outbound transfers per asset
+    pub in_flight_outbound: MapView<C, String, Amount>,
+    
+    /// Completed deposits less completed withdrawals per asset, floored at zero
+    pub bridged_supply: MapView<C, String, Amount>,
+    
+    /// Height of the last block the bridge executed in
+    pub last_block_height: RegisterView<C, BlockHeight>,
+    
+    /// Recorded proof-of-reserves hashes
+    pub reserves_snapshots: MapView<C, u64, ReservesSnapshot>,
+    
+    /// Next reserves snapshot ID
+    pub next_reserves_snapshot_id: RegisterView<C, u64>,
+    
+    /// Relayer actions per transfer, in order
+    pub work_receipts: MapView<C, TransferId, Vec<WorkReceipt>>,
+    
+    /// Recorded actions and duplicate submissions per relayer
+    pub relayer_work_stats: MapView<C, Account, RelayerWorkStats>,
+    
+    /// Soft claims on open transfers, dropped once the transfer closes
+    pub work_claims: MapView<C, TransferId, WorkClaim>,
+    
+    /// Monitoring events
+    pub events: QueueView<C, BridgeEvent>,
+}
+
+impl<C> BridgeState<C>
+where
+    C: Context + Clone + Send + Sync + 'static,
+    ViewError: From<C::Error>,
+{
+    /// Address format of a chain, taking registered custom chains into account
+    pub async fn address_format(&self, chain: ExternalChain) -> Result<AddressFormat, ViewError> {
+        if let ExternalChain::Custom(id) = chain {
+            if let Some(info) = self.custom_chains.get(&id).await? {
+                return Ok(info.address_format);
+            }
+        }
+        Ok(chain.address_format())
+    }
+    
+    /// Finality rule for new deposits. A configured profile wins; otherwise confirmations are
+    /// counted, with the chain config's count taking precedence, then the custom chain
+    /// registry, then the built-in default.
+    pub async fn finality_profile(&self, chain: ExternalChain, config: &ChainConfig) -> Result<FinalityProfile, ViewError> {
+        if let Some(profile) = config.finality {
+            return Ok(profile);
+        }
+        if config.required_confirmations > 0 {
+            return Ok(FinalityProfile::ConfirmationCount(config.required_confirmations));
+        }
+        if let ExternalChain::Custom(id) = chain {
+            if let Some(info) = self.custom_chains.get(&id).await? {
+                return Ok(FinalityProfile::ConfirmationCount(info.required_confirmations));
+            }
+        }
+        Ok(FinalityProfile::ConfirmationCount(chain.required_confirmations()))
+    }
+    
+    /// Finality rule for a new deposit of `amount` of `asset`: the chain's, with the
+    /// confirmation count of the asset's override tier if it has one
+    pub async fn deposit_finality(
+        &self,
+        chain: ExternalChain,
+        config: &ChainConfig,
+        asset: &str,
+        amount: Amount,
+    ) -> Result<FinalityProfile, ViewError> {
+        let profile = self.finality_profile(chain, config).await?;
+        Ok(match self.confirmation_overrides.get(&(chain.chain_id(), asset.to_string())).await? {
+            Some(confirmation_override) => confirmation_override.apply(profile, amount),
+            None => profile,
+        })
+    }
+    
+    /// Config of a chain at `version`: the current one, or one kept in the history
+    pub async fn chain_config_at(&self, chain_id: u64, version: u64) -> Result<Option<ChainConfig>, ViewError> {
+        match self.chain_configs.get(&chain_id).await? {
+            Some(current) if current.version == version => Ok(Some(current)),
+            _ => self.chain_config_history.get(&(chain_id, version)).await,
+        }
+    }
+    
+    /// Everything `approval_checks` checks a transfer against at `now`
+    pub async fn approval_context(&self, transfer: &BridgeTransfer, now: Timestamp) -> Result<ApprovalContext, ViewError> {
+        let Ok(chain) = transfer.corridor_chain() else {
+            return Ok(ApprovalContext {
+                address_format: AddressFormat::Opaque,
+                config: None,
+                scheduled_release_at: now,
+                now,
+            });
+        };
+        let windows = self.processing_windows.get(&chain.chain_id()).await?.unwrap_or_default();
+        Ok(ApprovalContext {
+            address_format: self.address_format(chain).await?,
+            config: self.chain_config_at(chain.chain_id(), transfer.config_version).await?,
+            scheduled_release_at: next_processing_time(&windows, now),
+            now,
+        })
+    }
+    
+    /// Whether withdrawals to `chain` proceed at `now`
+    pub async fn corridor_availability(&self, chain: ExternalChain, now: Timestamp) -> Result<CorridorAvailability, ViewError> {
+        let config = self.chain_configs.get(&chain.chain_id()).await?;
+        let windows = self.processing_windows.get(&chain.chain_id()).await?.unwrap_or_default();
+        Ok(CorridorAvailability::at(self.is_paused.get(), config.as_ref(), &windows, now))
+    }
+    
+    /// Everything `validate_withdrawal` checks a request against
+    pub async fn withdrawal_context(
+        &self,
+        request: &WithdrawalRequest,
+        now: Timestamp,
+    ) -> Result<WithdrawalContext, ViewError> {
+        Ok(WithdrawalContext {
+            paused: self.is_paused.get(),
+            indebted: self.indebted_accounts.contains_key(&request.account).await?,
+            config: self.chain_configs.get(&request.chain.chain_id()).await?,
+            address_format: self.address_format(request.chain).await?,
+            fee_override: self.fee_overrides.get(&request.fee_override_key()).await?,
+            token_application: self.token_applications.get(&request.asset).await?,
+            balance: self.balances.get(&(request.account, request.asset.clone())).await?.unwrap_or_default(),
+            processing_windows: self.processing_windows.get(&request.chain.chain_id()).await?.unwrap_or_default(),
+            now,
+        })
+    }
+    
+    /// Refuses an account with outstanding reorg debt; only credits, which repay it first, reach it
+    pub async fn ensure_no_debt(&self, account: Account) -> Result<(), BridgeError> {
+        if self.indebted_accounts.contains_key(&account).await? {
+            return Err(BridgeError::OutstandingDebt { account });
+        }
+        Ok(())
+    }
+    
+    /// Sets a user balance, keeping `total_balances` in step
+    pub async fn set_balance(
+        &mut self,
+        key: &(Account, String),
+        previous: Amount,
+        balance: Amount,
+    ) -> Result<(), ViewError> {
+        let total = self.total_balances.get(&key.1).await?.unwrap_or_default();
+        let total = math::saturating_sub(total, previous).saturating_add(balance);
+        self.total_balances.insert(&key.1, total)?;
+        self.balances.insert(key, balance)
+    }
+    
+    /// Liabilities of every asset the bridge holds anything of, as of the last executed block
+    pub async fn proof_of_reserves(&self) -> Result<ProofOfReserves, ViewError> {
+        let mut liabilities = BTreeMap::new();
+        self.total_balances.for_each_index_value(|asset, amount| {
+            liabilities_of(&mut liabilities, asset).user_balances = amount;
+            Ok(())
+        }).await?;
+        self.in_flight_outbound.for_each_index_value(|asset, amount| {
+            liabilities_of(&mut liabilities, asset).in_flight_outbound = amount;
+            Ok(())
+        }).await?;
+        self.bridged_supply.for_each_index_value(|asset, amount| {
+            liabilities_of(&mut liabilities, asset).bridged_supply = amount;
+            Ok(())
+        }).await?;
+        self.collected_fees.for_each_index_value(|asset, amount| {
+            liabilities_of(&mut liabilities, asset).collected_fees = amount;
+            Ok(())
+        }).await?;
+        self.insurance_fund.for_each_index_value(|asset, amount| {
+            liabilities_of(&mut liabilities, asset).insurance_fund = amount;
+            Ok(())
+        }).await?;
+        Ok(ProofOfReserves::new(self.last_block_height.get(), liabilities.into_values().collect()))
+    }
+}
+
+/// Bridge contract implementation
+pub struct BridgeContract;
+
+#[async_trait]
+impl Contract for BridgeContract {
+    type Message = Message;
+    type Parameters = ();
+    type InstantiationArgument = Account;
+    type State = BridgeState<ContractRuntime<Self>>;
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        BridgeContract
+    }
+
+    async fn instantiate(&mut self, state: &mut Self::State, admin: Account) {
+        state.next_transfer_id.set(1);
+        state.stats.set(BridgeStats::default());
+        state.total_validator_weight.set(0);
+        state.approval_threshold_percentage.set(67); // 2/3 majority
         state.fee_collector.set(None);
+        state.admin.set(Some(admin));
+        state.insurance_fee_bps.set(0);
+        state.next_insurance_payout_id.set(1);
         state.is_paused.set(false);
+        state.reorg_clawback_window_seconds.set(3600 * 24 * 7);
+        state.claim_window_seconds.set(3600 * 24 * 30);
+        state.confirmation_staleness_seconds.set(600);
+        state.next_batch_id.set(1);
+        state.next_deposit_hook_id.set(1);
+        state.next_reserves_snapshot_id.set(1);
+        state.relayer_unbonding_seconds.set(3600 * 24 * 7);
     }
 
     async fn execute_operation(
@@ -486,27 +2254,40 @@ impl Contract for BridgeContract {
         runtime: &mut ContractRuntime<Self>,
         state: &mut Self::State,
         operation: Operation,
-    ) -> Result<(), Self::Error> {
-        // Check pause status (except for admin operations)
+    ) -> Result<BridgeResponse, Self::Error> {
+        state.last_block_height.set(runtime.block_height());
+        
+        // Check pause status (except for admin operations and reads)
         if state.is_paused.get() {
             match &operation {
-                Operation::EmergencyPause | Operation::Resume => {}
+                Operation::EmergencyPause
+                | Operation::Resume
+                | Operation::BalanceOf { .. }
+                | Operation::CorridorAvailabilityOf { .. } => {}
                 _ => return Err(BridgeError::Paused),
             }
         }
         
-        match operation {
+        let result = match operation {
             Operation::InitiateWithdrawal {
                 destination_chain,
                 destination_address,
                 asset,
                 amount,
+                memo,
+                client_request_id,
+                fee_voucher,
             } => {
                 self.initiate_withdrawal(
-                    runtime, state, destination_chain, destination_address, asset, amount
+                    runtime, state, destination_chain, destination_address, asset, amount, memo,
+                    client_request_id, fee_voucher,
                 ).await
             }
             
+            Operation::CancelWithdrawal { transfer_id } => {
+                self.cancel_withdrawal(runtime, state, transfer_id).await
+            }
+            
             Operation::ReportDeposit {
                 source_chain,
                 tx_hash,
@@ -514,16 +2295,18 @@ impl Contract for BridgeContract {
                 recipient,
                 asset,
                 amount,
+                block_height,
                 confirmations,
+                bridge_contract_address,
             } => {
                 self.report_deposit(
                     runtime, state, source_chain, tx_hash, source_address,
-                    recipient, asset, amount, confirmations
+                    recipient, asset, amount, block_height, confirmations, bridge_contract_address,
                 ).await
             }
             
-            Operation::UpdateConfirmations { transfer_id, confirmations } => {
-                self.update_confirmations(runtime, state, transfer_id, confirmations).await
+            Operation::UpdateConfirmations { transfer_id, confirmations, observed_at } => {
+                self.update_confirmations(runtime, state, transfer_id, confirmations, observed_at).await
             }
             
             Operation::ApproveTransfer { transfer_id, signature } => {
@@ -538,6 +2321,10 @@ impl Contract for BridgeContract {
                 self.complete_withdrawal(runtime, state, transfer_id, tx_hash, success).await
             }
             
+            Operation::ClaimRelayerWork { transfer_id } => {
+                self.claim_relayer_work(runtime, state, transfer_id).await
+            }
+            
             Operation::ClaimRefund { transfer_id } => {
                 self.claim_refund(runtime, state, transfer_id).await
             }
@@ -546,12 +2333,151 @@ impl Contract for BridgeContract {
                 self.process_expired_transfers(runtime, state).await
             }
             
+            Operation::ReportExternalRefund { transfer_id, refund_tx_hash } => {
+                self.report_external_refund(runtime, state, transfer_id, refund_tx_hash).await
+            }
+            
+            Operation::RequeueStalledWithdrawals => {
+                self.requeue_stalled_withdrawals(runtime, state).await
+            }
+            
+            Operation::ReleaseQuarantined { transfer_id } => {
+                self.release_quarantined(runtime, state, transfer_id).await
+            }
+            
+            Operation::ClaimDeposit { transfer_id, proof_of_source_ownership } => {
+                self.claim_deposit(runtime, state, transfer_id, proof_of_source_ownership).await
+            }
+            
+            Operation::SealBatch { chain } => {
+                self.seal_open_batch(runtime, state, chain).await
+            }
+            
+            Operation::ApproveBatch { batch_id, signature } => {
+                self.approve_batch(runtime, state, batch_id, signature).await
+            }
+            
+            Operation::ExecuteBatch { batch_id } => {
+                self.execute_batch(runtime, state, batch_id).await
+            }
+            
+            Operation::CompleteBatch { batch_id, tx_hash, results } => {
+                self.complete_batch(runtime, state, batch_id, tx_hash, results).await
+            }
+            
+            Operation::ConfigureBatching { chain, limits } => {
+                self.require_admin(runtime, state)?;
+                self.configure_batching(state, chain, limits).await
+            }
+            
+            Operation::SetProcessingWindows { chain, windows } => {
+                self.require_admin(runtime, state)?;
+                if windows.len() > MAX_PROCESSING_WINDOWS {
+                    return Err(BridgeError::InvalidConfig {
+                        reason: format!("At most {} processing windows per chain", MAX_PROCESSING_WINDOWS),
+                    });
+                }
+                for window in &windows {
+                    window.validate()?;
+                }
+                // Scheduled withdrawals keep their release time; an earlier opening is picked
+                // up by `ReleaseScheduledWithdrawal`
+                if windows.is_empty() {
+                    state.processing_windows.remove(&chain.chain_id())?;
+                } else {
+                    state.processing_windows.insert(&chain.chain_id(), windows)?;
+                }
+                tracing::info!("Processing windows set: chain={:?}", chain);
+                self.refresh_corridor(runtime, state, chain).await
+            }
+            
+            Operation::ReleaseScheduledWithdrawal { transfer_id } => {
+                let now = runtime.system_time();
+                let mut transfer = state.transfers.get(&transfer_id).await?
+                    .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                if transfer.status != TransferStatus::Scheduled {
+                    return Err(BridgeError::InvalidStatus { status: transfer.status });
+                }
+                self.release_scheduled(state, &mut transfer, now).await?;
+                self.refresh_corridor(runtime, state, transfer.corridor_chain()?).await
+            }
+            
+            Operation::RegisterDepositHook { application_chain, filter } => {
+                let owner = runtime.authenticated_signer()
+                    .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                let mut hooks = state.deposit_hooks.get(&owner).await?.unwrap_or_default();
+                if hooks.len() >= MAX_DEPOSIT_HOOKS {
+                    return Err(BridgeError::TooManyDepositHooks { maximum: MAX_DEPOSIT_HOOKS });
+                }
+                let id = state.next_deposit_hook_id.get();
+                state.next_deposit_hook_id.set(id + 1);
+                hooks.push(DepositHook { id, application_chain, filter, registered_at: runtime.system_time() });
+                state.deposit_hooks.insert(&owner, hooks)?;
+                tracing::info!("Deposit hook registered: id={}, owner={:?}, chain={}", id, owner, application_chain);
+                Ok(())
+            }
+            
+            Operation::RemoveDepositHook { hook_id } => {
+                let owner = runtime.authenticated_signer()
+                    .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                let mut hooks = state.deposit_hooks.get(&owner).await?.unwrap_or_default();
+                let count = hooks.len();
+                hooks.retain(|hook| hook.id != hook_id);
+                if hooks.len() == count {
+                    return Err(BridgeError::DepositHookNotFound { hook_id });
+                }
+                if hooks.is_empty() {
+                    state.deposit_hooks.remove(&owner)?;
+                } else {
+                    state.deposit_hooks.insert(&owner, hooks)?;
+                }
+                Ok(())
+            }
+            
+            Operation::RegisterCorridorConsumer { consumer_chain } => {
+                self.require_admin(runtime, state)?;
+                let mut consumers = state.corridor_consumers.get();
+                if !consumers.contains(&consumer_chain) {
+                    if consumers.len() >= MAX_CORRIDOR_CONSUMERS {
+                        return Err(BridgeError::TooManyCorridorConsumers { maximum: MAX_CORRIDOR_CONSUMERS });
+                    }
+                    consumers.push(consumer_chain);
+                    state.corridor_consumers.set(consumers);
+                    tracing::info!("Corridor consumer registered: chain={}", consumer_chain);
+                }
+                Ok(())
+            }
+            
+            Operation::RemoveCorridorConsumer { consumer_chain } => {
+                self.require_admin(runtime, state)?;
+                let mut consumers = state.corridor_consumers.get();
+                let count = consumers.len();
+                consumers.retain(|consumer| *consumer != consumer_chain);
+                if consumers.len() == count {
+                    return Err(BridgeError::CorridorConsumerNotFound { consumer_chain });
+                }
+                state.corridor_consumers.set(consumers);
+                Ok(())
+            }
+            
+            Operation::RefreshCorridorStatus { chain } => {
+                self.refresh_corridor(runtime, state, chain).await
+            }
+            
+            Operation::RotateBridgeAddress { chain, new_address, overlap_seconds } => {
+                self.require_admin(runtime, state)?;
+                self.rotate_bridge_address(runtime, state, chain, new_address, overlap_seconds).await
+            }
+            
             Operation::ConfigureChain { config } => {
-                self.configure_chain(state, config).await
+                let chain = config.chain;
+                self.configure_chain(state, config).await?;
+                self.refresh_corridor(runtime, state, chain).await
             }
             
             Operation::DisableChain { chain } => {
-                self.disable_chain(state, chain).await
+                self.disable_chain(state, chain).await?;
+                self.refresh_corridor(runtime, state, chain).await
             }
             
             Operation::AddValidator { config } => {
@@ -562,167 +2488,502 @@ impl Contract for BridgeContract {
                 self.remove_validator(state, validator).await
             }
             
-            Operation::UpdateFees { chain, base_fee, fee_percentage_bps } => {
-                self.update_fees(state, chain, base_fee, fee_percentage_bps).await
+            Operation::UpdateFees { chain, base_fee, fee_percentage_bps, relayer_gas_fee } => {
+                self.update_fees(state, chain, base_fee, fee_percentage_bps, relayer_gas_fee).await
             }
             
-            Operation::EmergencyPause => {
-                state.is_paused.set(true);
-                tracing::warn!("Bridge paused!");
+            Operation::UpdateExpiries { chain, inbound_expiry_seconds, outbound_expiry_seconds } => {
+                self.require_admin(runtime, state)?;
+                let mut config = state.chain_configs.get(&chain.chain_id()).await?
+                    .ok_or(BridgeError::ChainNotConfigured { chain })?;
+                config.inbound_expiry_seconds = inbound_expiry_seconds.unwrap_or(config.inbound_expiry_seconds);
+                config.outbound_expiry_seconds = outbound_expiry_seconds.unwrap_or(config.outbound_expiry_seconds);
+                config.check_expiries()?;
+                let config = self.store_chain_config(state, config).await?;
+                tracing::info!("Expiries updated for chain {:?}, version={}", chain, config.version);
                 Ok(())
             }
             
-            Operation::Resume => {
-                state.is_paused.set(false);
-                tracing::info!("Bridge resumed");
+            Operation::ClaimRelayerFees { asset } => {
+                self.claim_relayer_fees(runtime, state, asset).await
+            }
+            
+            Operation::SubmitBlockAttestation { chain, height, block_hash, signature } => {
+                self.submit_block_attestation(runtime, state, chain, height, block_hash, signature).await
+            }
+            
+            Operation::ReportReorg { chain, from_height } => {
+                self.report_reorg(runtime, state, chain, from_height).await
+            }
+            
+            Operation::RegisterCustomChain { chain_id, info, replace } => {
+                self.require_admin(runtime, state)?;
+                if !replace && state.custom_chains.contains_key(&chain_id).await? {
+                    return Err(BridgeError::CustomChainExists { chain_id });
+                }
+                tracing::info!("Custom chain registered: id={}, name={}", chain_id, info.name);
+                state.custom_chains.insert(&chain_id, info)?;
                 Ok(())
             }
-        }
-    }
-
-    async fn execute_message(
-        &mut self,
-        runtime: &mut ContractRuntime<Self>,
-        state: &mut Self::State,
-        message: Message,
-    ) {
-        match message {
-            Message::DepositNotification {
-                chain, tx_hash, recipient, asset, amount, confirmations,
-            } => {
-                if let Err(e) = self.report_deposit(
-                    runtime, state, chain, tx_hash, "".to_string(),
-                    recipient, asset, amount, confirmations
-                ).await {
-                    tracing::error!("Failed to process deposit notification: {}", e);
+            
+            Operation::ConfigureTokenApplication { asset, application_id } => {
+                let admin = self.require_admin(runtime, state)?;
+                match application_id {
+                    Some(application_id) => state.token_applications.insert(&asset, application_id)?,
+                    None => state.token_applications.remove(&asset)?,
                 }
+                tracing::info!("Token application configured: asset={}, app={:?}", asset, application_id);
+                state.events.push_back(BridgeEvent::TokenApplicationConfigured {
+                    asset,
+                    application_id,
+                    configured_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
             }
             
-            Message::WithdrawalRequest { .. } => {
-                // Handled by relayer
+            Operation::PruneCorridorStats { before_day } => {
+                self.require_admin(runtime, state)?;
+                self.prune_corridor_stats(state, before_day).await
             }
             
-            Message::TransferUpdate { transfer_id, status, tx_hash } => {
-                tracing::info!(
-                    "Transfer update: id={}, status={:?}, tx_hash={:?}",
-                    transfer_id, status, tx_hash
-                );
+            Operation::ResetValidatorPerformance => {
+                self.require_admin(runtime, state)?;
+                for validator in state.validator_performance.indices().await? {
+                    state.validator_performance.remove(&validator)?;
+                }
+                state.performance_since.set(runtime.system_time());
+                Ok(())
             }
             
-            Message::ValidatorSignature { transfer_id, validator, signature, approved } => {
-                if approved {
-                    if let Err(e) = self.approve_transfer(runtime, state, transfer_id, signature).await {
-                        tracing::error!("Failed to process validator signature: {}", e);
-                    }
+            Operation::UpdateReorgClawbackWindow { seconds } => {
+                self.require_admin(runtime, state)?;
+                state.reorg_clawback_window_seconds.set(seconds);
+                tracing::info!("Reorg clawback window updated: {}s", seconds);
+                Ok(())
+            }
+            
+            Operation::QuarantineAccount { account } => {
+                self.require_admin(runtime, state)?;
+                state.quarantined_accounts.insert(&account, ())?;
+                tracing::warn!("Account quarantined: {:?}", account);
+                Ok(())
+            }
+            
+            Operation::ReleaseAccount { account } => {
+                self.require_admin(runtime, state)?;
+                state.quarantined_accounts.remove(&account)?;
+                tracing::info!("Account released: {:?}", account);
+                Ok(())
+            }
+            
+            Operation::UpdateConfirmationStalenessWindow { seconds } => {
+                self.require_admin(runtime, state)?;
+                state.confirmation_staleness_seconds.set(seconds);
+                Ok(())
+            }
+            
+            Operation::UpdateClaimWindow { seconds } => {
+                self.require_admin(runtime, state)?;
+                state.claim_window_seconds.set(seconds);
+                tracing::info!("Deposit claim window updated: {}s", seconds);
+                Ok(())
+            }
+            
+            Operation::FreezeTransfer { transfer_id, reason } => {
+                self.freeze_transfer(runtime, state, transfer_id, reason).await
+            }
+            
+            Operation::ResolveFrozenTransfer { transfer_id, release } => {
+                self.resolve_frozen_transfer(runtime, state, transfer_id, release).await
+            }
+            
+            Operation::ResolveUnclaimedDeposit { transfer_id, recipient } => {
+                self.resolve_unclaimed_deposit(runtime, state, transfer_id, recipient).await
+            }
+            
+            Operation::UpdateInsuranceFeeBps { bps } => {
+                self.require_admin(runtime, state)?;
+                if bps > 10_000 {
+                    return Err(BridgeError::InvalidConfig { reason: "Insurance share above 100%".to_string() });
                 }
+                state.insurance_fee_bps.set(bps);
+                tracing::info!("Insurance fee share updated: {}bps", bps);
+                Ok(())
             }
-        }
-    }
-}
-
-impl BridgeContract {
-    async fn initiate_withdrawal(
-        &mut self,
-        runtime: &mut ContractRuntime<Self>,
-        state: &mut BridgeState<ContractRuntime<Self>>,
-        destination_chain: ExternalChain,
-        destination_address: String,
-        asset: String,
-        amount: Amount,
-    ) -> Result<(), BridgeError> {
-        let user = runtime.authenticated_signer()
-            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        let now = runtime.system_time();
-        
-        // Get chain configuration
-        let chain_config = state.chain_configs.get(&destination_chain.chain_id()).await?
-            .ok_or(BridgeError::ChainNotConfigured { chain: destination_chain })?;
-        
-        if !chain_config.is_enabled {
-            return Err(BridgeError::ChainDisabled { chain: destination_chain });
-        }
-        
-        // Validate asset
-        let asset_mapping = chain_config.supported_assets.iter()
-            .find(|m| m.linera_asset == asset)
-            .ok_or(BridgeError::AssetNotSupported { asset: asset.clone(), chain: destination_chain })?;
-        
-        // Validate amount
-        if amount < chain_config.min_transfer_amount {
-            return Err(BridgeError::BelowMinimum { amount, minimum: chain_config.min_transfer_amount });
-        }
-        if amount > chain_config.max_transfer_amount {
-            return Err(BridgeError::AboveMaximum { amount, maximum: chain_config.max_transfer_amount });
-        }
-        
-        // Validate address format (basic check)
-        if destination_address.is_empty() {
-            return Err(BridgeError::InvalidAddress { address: destination_address });
-        }
-        
-        // Calculate fee
-        let percentage_fee = Amount::from((amount.into_inner() * chain_config.fee_percentage_bps as u128) / 10000);
-        let fee = chain_config.base_fee + percentage_fee;
-        let net_amount = amount.saturating_sub(fee);
-        
-        // Check user balance
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-        
-        if current_balance < amount {
-            return Err(BridgeError::InsufficientBalance {
-                required: amount,
-                available: current_balance,
-            });
-        }
-        
-        // Deduct balance
-        let new_balance = current_balance - amount;
-        state.balances.insert(&balance_key, new_balance)?;
-        
+            
+            Operation::PayInsuranceClaim { recipient, asset, amount, reference_transfer_id } => {
+                self.pay_insurance_claim(runtime, state, recipient, asset, amount, reference_transfer_id).await
+            }
+            
+            Operation::WithdrawCollectedFees { asset, amount } => {
+                self.withdraw_collected_fees(runtime, state, asset, amount).await
+            }
+            
+            Operation::RecordReservesSnapshot => {
+                self.record_reserves_snapshot(runtime, state).await
+            }
+            
+            Operation::SetFeeOverride { key, base_fee, fee_percentage_bps, expires_at } => {
+                self.require_admin(runtime, state)?;
+                let fee_override = FeeOverride { base_fee, fee_percentage_bps, expires_at, uses: 0 };
+                if !fee_override.is_valid() {
+                    return Err(BridgeError::InvalidFeeOverride);
+                }
+                state.fee_overrides.insert(&key, fee_override)?;
+                Ok(())
+            }
+            
+            Operation::RemoveFeeOverride { key } => {
+                self.require_admin(runtime, state)?;
+                state.fee_overrides.remove(&key)?;
+                Ok(())
+            }
+            
+            Operation::SetConfirmationOverride { chain, asset, confirmation_override } => {
+                self.require_admin(runtime, state)?;
+                if !confirmation_override.is_valid() {
+                    return Err(BridgeError::InvalidConfirmationOverride);
+                }
+                state.confirmation_overrides.insert(&(chain.chain_id(), asset), confirmation_override)?;
+                Ok(())
+            }
+            
+            Operation::RemoveConfirmationOverride { chain, asset } => {
+                self.require_admin(runtime, state)?;
+                state.confirmation_overrides.remove(&(chain.chain_id(), asset))?;
+                Ok(())
+            }
+            
+            Operation::SetApprovalTiers { chain, tiers } => {
+                self.require_admin(runtime, state)?;
+                if !tiers.is_valid() {
+                    return Err(BridgeError::InvalidApprovalTiers);
+                }
+                state.approval_tiers.insert(&chain.chain_id(), tiers)?;
+                Ok(())
+            }
+            
+            Operation::RemoveApprovalTiers { chain } => {
+                self.require_admin(runtime, state)?;
+                state.approval_tiers.remove(&chain.chain_id())?;
+                Ok(())
+            }
+            
+            Operation::SetFeeCollector { collector } => {
+                self.require_admin(runtime, state)?;
+                state.fee_collector.set(collector);
+                tracing::info!("Fee collector set: {:?}", collector);
+                Ok(())
+            }
+            
+            Operation::ConfigureRelayerBonds { asset, unbonding_seconds } => {
+                self.require_admin(runtime, state)?;
+                let current = state.relayer_bond_asset.get();
+                if current.as_ref().is_some_and(|current| *current != asset)
+                    && state.total_relayer_bonds.get() > Amount::ZERO
+                {
+                    return Err(BridgeError::InvalidConfig {
+                        reason: "Relayer bond asset cannot change while bonds are held".to_string(),
+                    });
+                }
+                tracing::info!("Relayer bonds configured: asset={}, unbonding={}s", asset, unbonding_seconds);
+                state.relayer_bond_asset.set(Some(asset));
+                state.relayer_unbonding_seconds.set(unbonding_seconds);
+                Ok(())
+            }
+            
+            Operation::SetRelayerBondMinimum { chain, minimum } => {
+                self.require_admin(runtime, state)?;
+                if minimum == Amount::ZERO {
+                    state.relayer_bond_minimums.remove(&chain.chain_id())?;
+                } else {
+                    if state.relayer_bond_asset.get().is_none() {
+                        return Err(BridgeError::RelayerBondsNotConfigured);
+                    }
+                    state.relayer_bond_minimums.insert(&chain.chain_id(), ChainBondMinimum { chain, minimum })?;
+                }
+                tracing::info!("Relayer bond minimum set: chain={:?}, minimum={}", chain, minimum);
+                Ok(())
+            }
+            
+            Operation::BondRelayer { amount } => {
+                self.bond_relayer(runtime, state, amount).await
+            }
+            
+            Operation::UnbondRelayer { amount } => {
+                self.unbond_relayer(runtime, state, amount).await
+            }
+            
+            Operation::WithdrawRelayerBond => {
+                self.withdraw_relayer_bond(runtime, state).await
+            }
+            
+            Operation::SlashRelayer { relayer, transfer_id, amount, reason } => {
+                self.slash_relayer(runtime, state, relayer, transfer_id, amount, reason).await
+            }
+            
+            Operation::TransferAdmin { new_admin } => {
+                self.require_admin(runtime, state)?;
+                state.admin.set(Some(new_admin));
+                tracing::info!("Bridge admin transferred: {:?}", new_admin);
+                Ok(())
+            }
+            
+            Operation::EmergencyPause => {
+                state.is_paused.set(true);
+                tracing::warn!("Bridge paused!");
+                self.refresh_corridors(runtime, state).await
+            }
+            
+            Operation::Resume => {
+                state.is_paused.set(false);
+                tracing::info!("Bridge resumed");
+                self.refresh_corridors(runtime, state).await
+            }
+            
+            Operation::BalanceOf { account, asset } => {
+                let balance = state.balances.get(&(account, asset)).await?.unwrap_or_default();
+                return Ok(BridgeResponse::Balance(balance));
+            }
+            
+            Operation::CorridorAvailabilityOf { chain } => {
+                let availability = state.corridor_availability(chain, runtime.system_time()).await?;
+                return Ok(BridgeResponse::CorridorAvailability(availability));
+            }
+            
+            Operation::Transfer { to, asset, amount } => {
+                let owner = runtime.authenticated_signer()
+                    .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                self.transfer_balance(runtime, state, owner, to, asset, amount).await
+            }
+            
+            Operation::Approve { spender, asset, allowance } => {
+                let owner = runtime.authenticated_signer()
+                    .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                if allowance == Amount::ZERO {
+                    state.allowances.remove(&(owner, spender, asset))?;
+                } else {
+                    state.allowances.insert(&(owner, spender, asset), allowance)?;
+                }
+                Ok(())
+            }
+            
+            Operation::TransferFrom { owner, to, asset, amount } => {
+                let spender = runtime.authenticated_caller_id()
+                    .ok_or(BridgeError::Unauthorized { reason: "Only applications spend allowances".to_string() })?;
+                let key = (owner, spender, asset.clone());
+                let available = state.allowances.get(&key).await?.unwrap_or_default();
+                if amount > available {
+                    return Err(BridgeError::InsufficientAllowance { required: amount, available });
+                }
+                if amount == available {
+                    state.allowances.remove(&key)?;
+                } else {
+                    state.allowances.insert(&key, available - amount)?;
+                }
+                self.transfer_balance(runtime, state, owner, to, asset, amount).await
+            }
+        };
+        result.map(|()| BridgeResponse::Ok)
+    }
+
+    async fn execute_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut Self::State,
+        message: Message,
+    ) {
+        state.last_block_height.set(runtime.block_height());
+        
+        match message {
+            Message::DepositNotification {
+                chain, tx_hash, recipient, asset, amount, block_height, confirmations, bridge_contract_address,
+            } => {
+                if let Err(e) = self.report_deposit(
+                    runtime, state, chain, tx_hash, "".to_string(),
+                    recipient, asset, amount, block_height, confirmations, bridge_contract_address,
+                ).await {
+                    tracing::error!("Failed to process deposit notification: {}", e);
+                }
+            }
+            
+            Message::WithdrawalRequest { .. } | Message::BatchWithdrawalRequest { .. } => {
+                // Handled by relayer
+            }
+            
+            Message::TransferUpdate { transfer_id, status, tx_hash, failure } => {
+                tracing::info!(
+                    "Transfer update: id={}, status={:?}, tx_hash={:?}, failure={:?}",
+                    transfer_id, status, tx_hash, failure.map(|failure| failure.code)
+                );
+            }
+            
+            Message::ValidatorSignature { transfer_id, validator, signature, approved } => {
+                if approved {
+                    if let Err(e) = self.approve_transfer(runtime, state, transfer_id, signature).await {
+                        tracing::error!("Failed to process validator signature: {}", e);
+                    }
+                }
+            }
+            
+            Message::DepositCompleted { hook_id, transfer_id, user, asset, amount, .. } => {
+                // Consumed by applications on the hook's chain
+                tracing::info!(
+                    "Deposit completed: hook_id={}, transfer_id={}, user={:?}, asset={}, amount={}",
+                    hook_id, transfer_id, user, asset, amount
+                );
+            }
+            
+            Message::CorridorStatus { corridor, availability, changed_at } => {
+                // Consumed by the settlement application on the consumer chain
+                tracing::info!(
+                    "Corridor status: corridor={}, availability={:?}, changed_at={:?}",
+                    corridor, availability, changed_at
+                );
+            }
+        }
+    }
+}
+
+impl BridgeContract {
+    async fn initiate_withdrawal(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        destination_chain: ExternalChain,
+        destination_address: String,
+        asset: String,
+        amount: Amount,
+        memo: Option<String>,
+        client_request_id: Option<u64>,
+        fee_voucher: Option<String>,
+    ) -> Result<(), BridgeError> {
+        let user = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let now = runtime.system_time();
+        
+        let request = WithdrawalRequest {
+            account: user,
+            chain: destination_chain,
+            destination_address,
+            asset,
+            amount,
+            memo: memo.filter(|memo| !memo.is_empty()),
+            fee_voucher,
+        };
+        let context = state.withdrawal_context(&request, now).await?;
+        let quote = check_withdrawal(&request, &context)?;
+        let WithdrawalRequest { destination_address, asset, memo, .. } = request;
+        let fees = quote.fees;
+        let fee = fees.total_fee;
+        let net_amount = fees.net_amount;
+        
+        // Spend the override's use, which retires a voucher
+        if let (Some(key), Some(mut terms)) = (&quote.fee_override, context.fee_override) {
+            terms.uses += 1;
+            state.fee_overrides.insert(key, terms)?;
+        }
+        
+        // Pull the funds from the user
+        if let Some(token_app) = context.token_application {
+            self.call_token_application(
+                runtime, token_app, &asset, WrappedTokenOperation::Burn { owner: user, amount }
+            )?;
+        } else {
+            state.set_balance(&(user, asset.clone()), context.balance, context.balance - amount).await?;
+        }
+        
         // Create transfer
         let transfer_id = state.next_transfer_id.get();
-        let approval_threshold = self.calculate_approval_threshold(state).await?;
+        let approval_requirement = self.tier_requirement(state, destination_chain, [(asset.as_str(), amount)]).await?;
+        let approval_threshold = self.approval_requirement(state, approval_requirement.as_ref())
+            .required_weight(state.total_validator_weight.get());
+        let batch_limits = state.batch_limits.get(&destination_chain.chain_id()).await?;
+        let status = if quote.release_at.is_some() {
+            TransferStatus::Scheduled
+        } else if batch_limits.is_some() {
+            TransferStatus::Batched
+        } else {
+            TransferStatus::AwaitingApproval
+        };
+        // Collect the protocol share; the relayer share is held until completion
+        let insurance_fee = self.collect_fee(state, &asset, fees.protocol_fee).await?;
         
         let transfer = BridgeTransfer {
             id: transfer_id,
             direction: TransferDirection::Outbound,
-            source_chain: ExternalChain::Custom(0), // Linera
+            source_chain: None,
             destination_chain: Some(destination_chain),
             user,
             external_address: destination_address.clone(),
             asset: asset.clone(),
             amount,
             fee,
+            relayer_fee: fees.relayer_fee,
+            insurance_fee,
             net_amount,
+            fee_override: quote.fee_override,
             source_tx_hash: None,
             destination_tx_hash: None,
-            status: TransferStatus::AwaitingApproval,
+            source_block_height: None,
+            memo,
+            release_at: quote.release_at,
+            config_version: quote.config_version,
+            status,
             confirmations: 0,
             required_confirmations: 0,
+            finality: None,
             created_at: now,
             completed_at: None,
-            expires_at: now + std::time::Duration::from_secs(3600 * 24), // 24 hour expiry
-            approvals: vec![],
+            expires_at: quote.expires_at,
             approval_threshold,
+            approval_count: 0,
+            approval_weight: 0,
+            approval_requirement,
+            reporter: None,
+            relayer: None,
+            executing_relayer: None,
+            executing_deadline: None,
             error_message: None,
+            failure: None,
+            external_refund: None,
+            last_confirmation_report: None,
+            reorged_at: None,
             retry_count: 0,
+            expiry_seconds: Some(quote.expiry_seconds),
         };
         
         // Store transfer
-        state.transfers.insert(&transfer_id, transfer.clone())?;
+        self.save_transfer(state, transfer.clone()).await?;
         state.active_transfers.insert(&transfer_id, ())?;
-        state.expiration_queue.push_back((transfer.expires_at, transfer_id));
         state.next_transfer_id.set(transfer_id + 1);
+        match quote.release_at {
+            // The sweep releases it when due; its expiry is queued then
+            Some(release_at) => {
+                state.expiration_queue.push_back((release_at, transfer_id));
+                state.events.push_back(BridgeEvent::WithdrawalScheduled {
+                    transfer_id,
+                    chain: destination_chain,
+                    release_at,
+                    timestamp: now,
+                });
+                self.refresh_corridor(runtime, state, destination_chain).await?;
+            }
+            None => state.expiration_queue.push_back((transfer.expires_at, transfer_id)),
+        }
+        if transfer.status == TransferStatus::AwaitingApproval {
+            state.awaiting_approval.insert(&transfer_id, ())?;
+        }
         
         // Add to user transfers
         let mut user_transfers = state.user_transfers.get(&user).await?.unwrap_or_default();
         user_transfers.push(transfer_id);
         state.user_transfers.insert(&user, user_transfers)?;
         
-        // Collect fee
-        let current_fees = state.collected_fees.get(&asset).await?.unwrap_or_default();
-        state.collected_fees.insert(&asset, current_fees + fee)?;
+        if let Some(limits) = batch_limits.filter(|_| transfer.status == TransferStatus::Batched) {
+            self.add_to_batch(state, &limits, &transfer, now).await?;
+        }
         
         // Update stats
         let mut stats = state.stats.get();
@@ -732,6 +2993,12 @@ impl BridgeContract {
         stats.pending_transfers += 1;
         state.stats.set(stats);
         
+        self.record_corridor(
+            state, destination_chain, &asset, TransferDirection::Outbound, now,
+            CorridorEvent::Created { volume: net_amount, fee },
+        ).await?;
+        self.record_receipt(state, user, client_request_id, transfer_id, now).await?;
+        
         tracing::info!(
             "Withdrawal initiated: id={}, user={:?}, chain={:?}, asset={}, amount={}, fee={}",
             transfer_id, user, destination_chain, asset, amount, fee
@@ -747,15 +3014,30 @@ impl BridgeContract {
         source_chain: ExternalChain,
         tx_hash: String,
         source_address: String,
-        recipient: Account,
+        recipient: Option<Account>,
         asset: String,
         amount: Amount,
+        block_height: u64,
         confirmations: u64,
+        bridge_contract_address: Option<String>,
     ) -> Result<(), BridgeError> {
         let now = runtime.system_time();
         
-        // Check for duplicate
-        if state.processed_deposits.get(&tx_hash).await?.is_some() {
+        // A signed relayer repeating a recorded report is counted and let through; anything that
+        // disagrees with the recorded deposit is refused
+        if let Some(transfer_id) = state.processed_deposits.get(&tx_hash).await? {
+            let transfer = state.transfers.get(&transfer_id).await?
+                .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+            let repeated = transfer.source_chain == Some(source_chain)
+                && transfer.asset == asset
+                && transfer.amount == amount
+                && transfer.source_block_height == Some(block_height);
+            if repeated {
+                if let Some(relayer) = self.require_bonded_relayer(runtime, state, source_chain).await? {
+                    let action = RelayerAction::ReportedDeposit;
+                    return self.record_work(state, transfer_id, relayer, action, true, now).await;
+                }
+            }
             return Err(BridgeError::DuplicateDeposit);
         }
         
@@ -766,6 +3048,10 @@ impl BridgeContract {
         if !chain_config.is_enabled {
             return Err(BridgeError::ChainDisabled { chain: source_chain });
         }
+        if let Some(address) = &bridge_contract_address {
+            chain_config.check_deposit_address(address, now)?;
+        }
+        let reporter = self.require_bonded_relayer(runtime, state, source_chain).await?;
         
         // Validate asset
         let _asset_mapping = chain_config.supported_assets.iter()
@@ -773,80 +3059,111 @@ impl BridgeContract {
             .ok_or(BridgeError::AssetNotSupported { asset: asset.clone(), chain: source_chain })?;
         
         // Calculate fee
-        let percentage_fee = Amount::from((amount.into_inner() * chain_config.fee_percentage_bps as u128) / 10000);
-        let fee = chain_config.base_fee + percentage_fee;
-        let net_amount = amount.saturating_sub(fee);
+        let (fees, fee_override) = self.charge_fees(
+            state, &chain_config, amount, TransferDirection::Inbound, recipient, None, now,
+        ).await?;
+        let fee = fees.total_fee;
+        let net_amount = fees.net_amount;
         
-        // Determine status based on confirmations
-        let required_confirmations = chain_config.required_confirmations;
-        let status = if confirmations >= required_confirmations {
+        // Determine status by the chain's finality profile, or the asset's confirmation tier;
+        // deposits nobody can receive wait for a claim
+        let finality = state.deposit_finality(source_chain, &chain_config, &asset, amount).await?;
+        let latest_finalized = state.latest_finalized_height.get(&source_chain.chain_id()).await?;
+        let placeholder = Self::unclaimed_placeholder(runtime);
+        let user = recipient.unwrap_or(placeholder);
+        let creditable = self.can_receive_deposit(state, user, placeholder).await?;
+        let status = if !finality.is_final(confirmations, block_height, latest_finalized) {
+            TransferStatus::Confirming
+        } else if creditable {
             TransferStatus::Approved
         } else {
-            TransferStatus::Confirming
+            TransferStatus::ClaimPending
+        };
+        let expiry_seconds = chain_config.inbound_expiry_seconds;
+        let expires_at = if status == TransferStatus::ClaimPending {
+            now + std::time::Duration::from_secs(state.claim_window_seconds.get())
+        } else {
+            now + std::time::Duration::from_secs(expiry_seconds)
         };
         
         // Create transfer
         let transfer_id = state.next_transfer_id.get();
-        let approval_threshold = self.calculate_approval_threshold(state).await?;
+        let approval_requirement = self.tier_requirement(state, source_chain, [(asset.as_str(), amount)]).await?;
+        let approval_threshold = self.approval_requirement(state, approval_requirement.as_ref())
+            .required_weight(state.total_validator_weight.get());
         
-        let transfer = BridgeTransfer {
+        let mut transfer = BridgeTransfer {
             id: transfer_id,
             direction: TransferDirection::Inbound,
-            source_chain,
+            source_chain: Some(source_chain),
             destination_chain: None,
-            user: recipient,
+            user,
             external_address: source_address,
             asset: asset.clone(),
             amount,
             fee,
+            relayer_fee: fees.relayer_fee,
+            insurance_fee: Amount::ZERO,
             net_amount,
+            fee_override,
             source_tx_hash: Some(tx_hash.clone()),
             destination_tx_hash: None,
+            source_block_height: Some(block_height),
+            memo: None,
+            release_at: None,
+            config_version: chain_config.version,
             status,
             confirmations,
-            required_confirmations,
+            required_confirmations: finality.min_confirmations(),
+            finality: Some(finality),
             created_at: now,
             completed_at: None,
-            expires_at: now + std::time::Duration::from_secs(3600 * 24),
-            approvals: vec![],
+            expires_at,
             approval_threshold,
+            approval_count: 0,
+            approval_weight: 0,
+            approval_requirement,
+            reporter,
+            relayer: None,
+            executing_relayer: None,
+            executing_deadline: None,
             error_message: None,
+            failure: None,
+            external_refund: None,
+            last_confirmation_report: None,
+            reorged_at: None,
             retry_count: 0,
+            expiry_seconds: Some(expiry_seconds),
         };
         
+        // Credit first (inside complete_deposit) so a failed token call leaves no trace
+        if status == TransferStatus::Approved {
+            self.settle_deposit(runtime, state, &chain_config, &mut transfer, now).await?;
+        }
+        
         // Store transfer
-        state.transfers.insert(&transfer_id, transfer.clone())?;
+        self.save_transfer(state, transfer.clone()).await?;
         state.processed_deposits.insert(&tx_hash, transfer_id)?;
         state.next_transfer_id.set(transfer_id + 1);
+        if let Some(relayer) = reporter {
+            self.record_work(state, transfer_id, relayer, RelayerAction::ReportedDeposit, false, now).await?;
+        }
         
-        // Add to user transfers
-        let mut user_transfers = state.user_transfers.get(&recipient).await?.unwrap_or_default();
-        user_transfers.push(transfer_id);
-        state.user_transfers.insert(&recipient, user_transfers)?;
+        // Track deposits that a reorg could still revert
+        if latest_finalized.map_or(true, |finalized| block_height > finalized) {
+            let mut unfinalized = state.unfinalized_deposits.get(&source_chain.chain_id()).await?.unwrap_or_default();
+            unfinalized.push((block_height, transfer_id));
+            state.unfinalized_deposits.insert(&source_chain.chain_id(), unfinalized)?;
+        }
         
-        // If confirmed, credit user immediately
-        if status == TransferStatus::Approved {
-            let balance_key = (recipient, asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            state.balances.insert(&balance_key, current_balance + net_amount)?;
-            
-            // Collect fee
-            let current_fees = state.collected_fees.get(&asset).await?.unwrap_or_default();
-            state.collected_fees.insert(&asset, current_fees + fee)?;
-            
-            // Update transfer status
-            let mut completed_transfer = transfer;
-            completed_transfer.status = TransferStatus::Completed;
-            completed_transfer.completed_at = Some(now);
-            state.transfers.insert(&transfer_id, completed_transfer)?;
-            
-            // Update stats
-            let mut stats = state.stats.get();
-            stats.total_inbound_transfers += 1;
-            stats.total_inbound_volume = stats.total_inbound_volume + net_amount;
-            stats.total_fees_collected = stats.total_fees_collected + fee;
-            state.stats.set(stats);
-        } else {
+        // Add to user transfers; unclaimed deposits are added once claimed
+        if creditable {
+            let mut user_transfers = state.user_transfers.get(&user).await?.unwrap_or_default();
+            user_transfers.push(transfer_id);
+            state.user_transfers.insert(&user, user_transfers)?;
+        }
+        
+        if transfer.status != TransferStatus::Completed {
             state.active_transfers.insert(&transfer_id, ())?;
             state.expiration_queue.push_back((transfer.expires_at, transfer_id));
             
@@ -855,9 +3172,14 @@ impl BridgeContract {
             state.stats.set(stats);
         }
         
+        self.record_corridor(
+            state, source_chain, &asset, TransferDirection::Inbound, now,
+            CorridorEvent::Created { volume: net_amount, fee: Amount::ZERO },
+        ).await?;
+        
         tracing::info!(
-            "Deposit reported: id={}, chain={:?}, tx_hash={}, recipient={:?}, asset={}, amount={}, confirmations={}",
-            transfer_id, source_chain, tx_hash, recipient, asset, amount, confirmations
+            "Deposit reported: id={}, chain={:?}, tx_hash={}, recipient={:?}, asset={}, amount={}, confirmations={}, status={:?}",
+            transfer_id, source_chain, tx_hash, recipient, asset, amount, confirmations, transfer.status
         );
         
         Ok(())
@@ -869,48 +3191,66 @@ impl BridgeContract {
         state: &mut BridgeState<ContractRuntime<Self>>,
         transfer_id: TransferId,
         confirmations: u64,
+        observed_at: Option<Timestamp>,
     ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         let now = runtime.system_time();
+        let observed_at = observed_at.unwrap_or(now);
         
         let mut transfer = state.transfers.get(&transfer_id).await?
             .ok_or(BridgeError::TransferNotFound { transfer_id })?;
         
-        if transfer.status != TransferStatus::Confirming {
+        // Reorged deposits return to Pending and are re-confirmed from scratch
+        if !matches!(transfer.status, TransferStatus::Pending | TransferStatus::Confirming) {
             return Err(BridgeError::InvalidStatus { status: transfer.status });
         }
         
-        transfer.confirmations = confirmations;
+        // Replayed observations are dropped rather than failed, so the event is kept
+        let staleness = std::time::Duration::from_secs(state.confirmation_staleness_seconds.get());
+        let stale = transfer.reorged_at.is_some_and(|reorged_at| observed_at < reorged_at)
+            || transfer.last_confirmation_report.as_ref()
+                .is_some_and(|last| observed_at + staleness < last.observed_at);
+        if stale {
+            state.events.push_back(BridgeEvent::StaleConfirmationReportIgnored {
+                transfer_id,
+                reported_by: relayer,
+                confirmations,
+                observed_at,
+                timestamp: now,
+            });
+            return Ok(());
+        }
         
-        // Check if now confirmed
-        if confirmations >= transfer.required_confirmations {
-            transfer.status = TransferStatus::Approved;
-            
-            // Credit user for inbound transfers
-            if transfer.direction == TransferDirection::Inbound {
-                let balance_key = (transfer.user, transfer.asset.clone());
-                let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-                state.balances.insert(&balance_key, current_balance + transfer.net_amount)?;
-                
-                // Collect fee
-                let current_fees = state.collected_fees.get(&transfer.asset).await?.unwrap_or_default();
-                state.collected_fees.insert(&transfer.asset, current_fees + transfer.fee)?;
-                
-                transfer.status = TransferStatus::Completed;
-                transfer.completed_at = Some(now);
-                
-                state.active_transfers.remove(&transfer_id)?;
-                
-                // Update stats
-                let mut stats = state.stats.get();
-                stats.total_inbound_transfers += 1;
-                stats.total_inbound_volume = stats.total_inbound_volume + transfer.net_amount;
-                stats.total_fees_collected = stats.total_fees_collected + transfer.fee;
-                stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
-                state.stats.set(stats);
+        if confirmations < transfer.confirmations {
+            return Err(BridgeError::ConfirmationsDecreased { current: transfer.confirmations, reported: confirmations });
+        }
+        let chain_id = transfer.corridor_chain()?.chain_id();
+        let finalized_height = state.latest_finalized_height.get(&chain_id).await?;
+        if let (Some(finalized), Some(block_height)) = (finalized_height, transfer.source_block_height) {
+            // A finalized block is buried under at least the blocks up to the finalized height
+            if let Some(minimum) = finalized.checked_sub(block_height).map(|depth| depth + 1) {
+                if confirmations < minimum {
+                    return Err(BridgeError::InconsistentConfirmations { reported: confirmations, minimum });
+                }
             }
         }
         
-        state.transfers.insert(&transfer_id, transfer)?;
+        transfer.status = TransferStatus::Confirming;
+        transfer.confirmations = confirmations;
+        transfer.last_confirmation_report = Some(ConfirmationReport {
+            reported_by: relayer,
+            confirmations,
+            observed_at,
+            reported_at: now,
+        });
+        
+        // Check if now final under the profile the deposit was reported with
+        if transfer.is_final(finalized_height) {
+            self.finalize_deposit(runtime, state, &mut transfer, now).await?;
+        }
+        
+        self.save_transfer(state, transfer).await?;
         
         tracing::info!(
             "Confirmations updated: transfer_id={}, confirmations={}",
@@ -920,64 +3260,201 @@ impl BridgeContract {
         Ok(())
     }
     
-    async fn approve_transfer(
+    /// Approves a transfer whose source block is final, crediting inbound deposits or holding
+    /// them for a claim
+    async fn finalize_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        transfer.status = TransferStatus::Approved;
+        if transfer.direction != TransferDirection::Inbound {
+            return Ok(());
+        }
+        
+        let placeholder = Self::unclaimed_placeholder(runtime);
+        if self.can_receive_deposit(state, transfer.user, placeholder).await? {
+            let source_chain = transfer.corridor_chain()?;
+            let chain_config = state.chain_configs.get(&source_chain.chain_id()).await?
+                .ok_or(BridgeError::ChainNotConfigured { chain: source_chain })?;
+            self.settle_deposit(runtime, state, &chain_config, transfer, now).await?;
+            if transfer.status == TransferStatus::Completed {
+                state.active_transfers.remove(&transfer.id)?;
+                
+                let mut stats = state.stats.get();
+                stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+                state.stats.set(stats);
+            }
+        } else {
+            transfer.status = TransferStatus::ClaimPending;
+            transfer.expires_at = now + std::time::Duration::from_secs(state.claim_window_seconds.get());
+            state.expiration_queue.push_back((transfer.expires_at, transfer.id));
+        }
+        Ok(())
+    }
+    
+    async fn cancel_withdrawal(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
         transfer_id: TransferId,
-        signature: Vec<u8>,
     ) -> Result<(), BridgeError> {
-        let validator = runtime.authenticated_signer()
+        let user = runtime.authenticated_signer()
             .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         let now = runtime.system_time();
         
-        // Verify validator
-        let validator_config = state.validators.get(&validator).await?
-            .ok_or(BridgeError::ValidatorNotFound { address: validator })?;
-        
-        if !validator_config.is_active {
-            return Err(BridgeError::Unauthorized { reason: "Validator is not active".to_string() });
-        }
-        
-        // Get transfer
         let mut transfer = state.transfers.get(&transfer_id).await?
             .ok_or(BridgeError::TransferNotFound { transfer_id })?;
-        
-        if transfer.status != TransferStatus::AwaitingApproval && transfer.status != TransferStatus::Approved {
-            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        if transfer.user != user {
+            return Err(BridgeError::Unauthorized { reason: "Not the owner of the withdrawal".to_string() });
         }
+        let refund = transfer.cancellation_refund()
+            .ok_or(BridgeError::InvalidStatus { status: transfer.status })?;
         
-        // Check if already approved by this validator
-        if transfer.approvals.iter().any(|a| a.validator == validator) {
-            return Err(BridgeError::AlreadyApproved);
+        // The protocol share was booked at initiation, and comes back whole or not at all; the
+        // relayer share is still held
+        let booked_refund = math::saturating_sub(refund.fee_refunded, transfer.relayer_fee);
+        if booked_refund > Amount::ZERO {
+            self.reverse_fee(state, &transfer.asset, booked_refund, transfer.insurance_fee).await?;
+            transfer.insurance_fee = Amount::ZERO;
         }
+        self.credit_balance(runtime, state, user, &transfer.asset, refund.refund).await?;
         
-        // Add approval
-        transfer.approvals.push(ValidatorApproval {
-            validator,
-            approved: true,
-            signature,
-            timestamp: now,
-        });
+        transfer.status = TransferStatus::Refunded;
+        transfer.completed_at = Some(now);
+        self.prune_approvals(state, &mut transfer).await?;
+        self.save_transfer(state, transfer.clone()).await?;
+        state.active_transfers.remove(&transfer_id)?;
+        state.awaiting_approval.remove(&transfer_id)?;
         
-        // Calculate current approval weight
-        let mut approval_weight = 0u32;
-        for approval in &transfer.approvals {
-            if let Some(config) = state.validators.get(&approval.validator).await? {
-                approval_weight += config.weight;
-            }
-        }
+        let mut stats = state.stats.get();
+        stats.total_outbound_volume = stats.total_outbound_volume.saturating_sub(transfer.net_amount);
+        stats.total_fees_collected = stats.total_fees_collected.saturating_sub(refund.fee_refunded);
+        stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+        state.stats.set(stats);
         
-        // Check if threshold met
-        let total_weight = state.total_validator_weight.get();
-        let threshold_percentage = state.approval_threshold_percentage.get();
-        let required_weight = (total_weight * threshold_percentage) / 100;
+        self.record_corridor(
+            state, transfer.corridor_chain()?, &transfer.asset, TransferDirection::Outbound, now,
+            CorridorEvent::Failed,
+        ).await?;
         
-        if approval_weight >= required_weight {
-            transfer.status = TransferStatus::Approved;
+        tracing::info!(
+            "Withdrawal cancelled: transfer_id={}, refund={}, fee_retained={}",
+            transfer_id, refund.refund, refund.fee_retained
+        );
+        
+        Ok(())
+    }
+    
+    async fn approve_transfer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        signature: Vec<u8>,
+    ) -> Result<(), BridgeError> {
+        let validator = self.require_active_validator(runtime, state).await?;
+        let now = runtime.system_time();
+        
+        // Get transfer
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        // A scheduled withdrawal whose window has opened is released by its first approval
+        if transfer.status == TransferStatus::Scheduled {
+            self.release_scheduled(state, &mut transfer, now).await?;
+        }
+        if transfer.status != TransferStatus::AwaitingApproval && transfer.status != TransferStatus::Approved {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
         }
         
-        state.transfers.insert(&transfer_id, transfer)?;
+        // Check if already approved by this validator
+        let approval_key = (transfer_id, validator);
+        if state.transfer_approvals.contains_key(&approval_key).await? {
+            return Err(BridgeError::AlreadyApproved);
+        }
+        
+        // Add approval
+        state.transfer_approvals.insert(&approval_key, ValidatorApproval {
+            validator,
+            approved: true,
+            signature: signature.clone(),
+            timestamp: now,
+        })?;
+        
+        // Accumulate approval weight at the validator's current weight
+        let validator_config = state.validators.get(&validator).await?;
+        let validator_weight = validator_config.as_ref().map_or(0, |config| config.weight);
+        let (weight, count) = state.approval_weights.get(&transfer_id).await?.unwrap_or_default();
+        let approval_weight = weight + validator_weight;
+        state.approval_weights.insert(&transfer_id, (approval_weight, count + 1))?;
+        
+        // Withdrawals keep the approvals that reach quorum, with the keys they were made under
+        let mut snapshot = None;
+        if transfer.direction == TransferDirection::Outbound && transfer.status == TransferStatus::AwaitingApproval {
+            let mut approvals = state.approval_snapshots.get(&transfer_id).await?.unwrap_or_default();
+            approvals.signatures.push(BundleSignature {
+                validator,
+                external_key: validator_config.map_or_else(Vec::new, |config| config.public_key),
+                signature,
+                weight: validator_weight,
+            });
+            snapshot = Some(approvals);
+        }
+        
+        // Withdrawals await approval from creation, or from their release when scheduled
+        let mut performance = state.validator_performance.get(&validator).await?.unwrap_or_default();
+        performance.approvals += 1;
+        performance.total_latency_seconds = performance.total_latency_seconds
+            .saturating_add(elapsed_seconds(transfer.release_at.unwrap_or(transfer.created_at), now));
+        state.validator_performance.insert(&validator, performance)?;
+        
+        // Check the requirement the transfer was created under
+        let total_weight = state.total_validator_weight.get();
+        let requirement = self.approval_requirement(state, transfer.approval_requirement.as_ref());
+        let threshold_percentage = requirement.threshold_percentage;
+        let required_weight = requirement.required_weight(total_weight);
+        let mut senior_approvals = 0;
+        for senior in &requirement.senior_validators {
+            // A senior removed from the set no longer vouches for the transfer
+            if state.validators.contains_key(senior).await?
+                && state.transfer_approvals.contains_key(&(transfer_id, *senior)).await?
+            {
+                senior_approvals += 1;
+            }
+        }
+        
+        if requirement.is_met(approval_weight, total_weight, senior_approvals)
+            && transfer.status != TransferStatus::Approved
+        {
+            if let Some(snapshot) = &mut snapshot {
+                snapshot.quorum = Some(ApprovalQuorum {
+                    epoch: state.validator_set_epoch.get(),
+                    total_weight,
+                    threshold: required_weight,
+                    threshold_percentage,
+                    reached_at: now,
+                });
+            }
+            transfer.status = TransferStatus::Approved;
+            self.save_transfer(state, transfer).await?;
+            state.awaiting_approval.remove(&transfer_id)?;
+            
+            for other in state.validators.indices().await? {
+                let active = state.validators.get(&other).await?.is_some_and(|config| config.is_active);
+                if !active || state.transfer_approvals.contains_key(&(transfer_id, other)).await? {
+                    continue;
+                }
+                let mut performance = state.validator_performance.get(&other).await?.unwrap_or_default();
+                performance.missed_quorums += 1;
+                state.validator_performance.insert(&other, performance)?;
+            }
+        }
+        if let Some(snapshot) = snapshot {
+            state.approval_snapshots.insert(&transfer_id, snapshot)?;
+        }
         
         tracing::info!(
             "Transfer approved: transfer_id={}, validator={:?}, weight={}/{}",
@@ -994,6 +3471,8 @@ impl BridgeContract {
         transfer_id: TransferId,
     ) -> Result<(), BridgeError> {
         let now = runtime.system_time();
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         
         let mut transfer = state.transfers.get(&transfer_id).await?
             .ok_or(BridgeError::TransferNotFound { transfer_id })?;
@@ -1007,12 +3486,28 @@ impl BridgeContract {
         }
         
         transfer.status = TransferStatus::Executing;
-        state.transfers.insert(&transfer_id, transfer)?;
+        transfer.executing_relayer = Some(relayer);
+        transfer.executing_deadline = Some(now + std::time::Duration::from_secs(EXECUTION_TIMEOUT_SECONDS));
+        self.save_transfer(state, transfer.clone()).await?;
+        self.record_work(state, transfer_id, relayer, RelayerAction::ClaimedExecution, false, now).await?;
         
-        // For outbound transfers, the relayer will pick up and execute on destination chain
+        // For outbound transfers, the claiming relayer executes on the destination chain
         // For inbound transfers, funds are already credited
+        if transfer.direction == TransferDirection::Outbound {
+            state.executing_transfers.insert(&transfer_id, ())?;
+            runtime
+                .prepare_message(Message::WithdrawalRequest {
+                    transfer_id,
+                    chain: transfer.corridor_chain()?,
+                    recipient_address: transfer.external_address,
+                    asset: transfer.asset,
+                    amount: transfer.net_amount,
+                    memo: transfer.memo,
+                })
+                .send_to(relayer.chain_id);
+        }
         
-        tracing::info!("Transfer executing: transfer_id={}", transfer_id);
+        tracing::info!("Transfer executing: transfer_id={}, relayer={:?}", transfer_id, relayer);
         
         Ok(())
     }
@@ -1030,234 +3525,2315 @@ impl BridgeContract {
         let mut transfer = state.transfers.get(&transfer_id).await?
             .ok_or(BridgeError::TransferNotFound { transfer_id })?;
         
-        if transfer.direction != TransferDirection::Outbound {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        
+        // Batched withdrawals complete with their batch; scheduled ones have not been released
+        if transfer.direction != TransferDirection::Outbound
+            || matches!(transfer.status, TransferStatus::Batched | TransferStatus::Scheduled)
+        {
             return Err(BridgeError::InvalidStatus { status: transfer.status });
         }
+        self.require_bonded_relayer(runtime, state, transfer.corridor_chain()?).await?;
+        
+        if matches!(
+            transfer.status,
+            TransferStatus::Completed | TransferStatus::Failed | TransferStatus::Refunded | TransferStatus::Expired
+        ) {
+            // Repeating the recorded success is a no-op, e.g. a late report after a re-queue
+            if success
+                && transfer.status == TransferStatus::Completed
+                && transfer.destination_tx_hash.as_deref() == Some(tx_hash.as_str())
+            {
+                let action = RelayerAction::CompletedWithdrawal;
+                return self.record_work(state, transfer_id, relayer, action, true, now).await;
+            }
+            return Err(BridgeError::AlreadyProcessed);
+        }
+        
+        // Once re-queued, a failure from anyone but the current claimant is stale: refunding on it
+        // could pay out twice if the withdrawal is still sent. A late success is still recorded.
+        if !success && transfer.retry_count > 0 && transfer.executing_relayer != Some(relayer) {
+            tracing::warn!(
+                "Ignoring stale failure report: transfer_id={}, relayer={:?}",
+                transfer_id, relayer
+            );
+            return self.record_work(state, transfer_id, relayer, RelayerAction::CompletedWithdrawal, true, now).await;
+        }
+        
+        self.finish_withdrawal(runtime, state, &mut transfer, relayer, &tx_hash, success, now).await?;
+        
+        tracing::info!(
+            "Withdrawal completed: transfer_id={}, success={}, tx_hash={}",
+            transfer_id, success, tx_hash
+        );
+        
+        Ok(())
+    }
+    
+    /// Records the destination chain outcome of a withdrawal, refunding the net amount on failure.
+    async fn finish_withdrawal(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+        relayer: Account,
+        tx_hash: &str,
+        success: bool,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        // The relayer paid destination gas whether or not the transaction succeeded
+        transfer.relayer = Some(relayer);
+        transfer.executing_deadline = None;
+        state.executing_transfers.remove(&transfer.id)?;
+        if transfer.relayer_fee > Amount::ZERO {
+            let key = (relayer, transfer.asset.clone());
+            let accrued = state.relayer_fees.get(&key).await?.unwrap_or_default();
+            state.relayer_fees.insert(&key, accrued + transfer.relayer_fee)?;
+        }
         
         if success {
             transfer.status = TransferStatus::Completed;
-            transfer.destination_tx_hash = Some(tx_hash);
+            transfer.destination_tx_hash = Some(tx_hash.to_string());
             transfer.completed_at = Some(now);
         } else {
-            transfer.status = TransferStatus::Failed;
-            transfer.error_message = Some("Transaction failed on destination chain".to_string());
+            self.fail_transfer(runtime, transfer, BridgeError::DestinationTransactionFailed {
+                tx_hash: tx_hash.to_string(),
+            });
             
             // Refund user (minus fee)
-            let balance_key = (transfer.user, transfer.asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            state.balances.insert(&balance_key, current_balance + transfer.net_amount)?;
+            self.credit_balance(runtime, state, transfer.user, &transfer.asset, transfer.net_amount).await?;
             
             let mut stats = state.stats.get();
             stats.failed_transfers += 1;
             state.stats.set(stats);
         }
         
-        state.transfers.insert(&transfer_id, transfer.clone())?;
-        state.active_transfers.remove(&transfer_id)?;
+        self.prune_approvals(state, transfer).await?;
+        self.save_transfer(state, transfer.clone()).await?;
+        state.active_transfers.remove(&transfer.id)?;
+        self.record_work(state, transfer.id, relayer, RelayerAction::CompletedWithdrawal, false, now).await?;
         
         // Update stats
         let mut stats = state.stats.get();
         stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
         state.stats.set(stats);
         
-        tracing::info!(
-            "Withdrawal completed: transfer_id={}, success={}, tx_hash={}",
-            transfer_id, success, tx_hash
-        );
-        
+        let event = if success {
+            CorridorEvent::Completed {
+                fee: Amount::ZERO,
+                completion_seconds: elapsed_seconds(transfer.created_at, now),
+            }
+        } else {
+            CorridorEvent::Failed
+        };
+        self.record_corridor(
+            state, transfer.corridor_chain()?, &transfer.asset, TransferDirection::Outbound, now, event,
+        ).await
+    }
+    
+    async fn configure_batching(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        limits: Option<BatchLimits>,
+    ) -> Result<(), BridgeError> {
+        match limits {
+            Some(limits) => {
+                if !limits.is_valid() {
+                    return Err(BridgeError::InvalidConfig {
+                        reason: format!("Batches hold 1 to {} transfers and a positive amount", MAX_BATCH_TRANSFERS),
+                    });
+                }
+                state.batch_limits.insert(&chain.chain_id(), limits)?;
+            }
+            // An open batch is left to be sealed, so its withdrawals still go out
+            None => state.batch_limits.remove(&chain.chain_id())?,
+        }
+        tracing::info!("Batching configured: chain={:?}", chain);
         Ok(())
     }
     
-    async fn claim_refund(
+    /// Moves a scheduled withdrawal into approval, or its chain's batch, if a processing window is
+    /// open at `now`; its expiry starts over from the release.
+    async fn release_scheduled(
         &mut self,
-        runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        transfer_id: TransferId,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
     ) -> Result<(), BridgeError> {
-        let caller = runtime.authenticated_signer()
-            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        let now = runtime.system_time();
-        
-        let mut transfer = state.transfers.get(&transfer_id).await?
-            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
-        
-        // Only transfer owner can claim refund
-        if transfer.user != caller {
-            return Err(BridgeError::Unauthorized { reason: "Not transfer owner".to_string() });
+        let chain_id = transfer.corridor_chain()?.chain_id();
+        let windows = state.processing_windows.get(&chain_id).await?.unwrap_or_default();
+        let release_at = next_processing_time(&windows, now);
+        if release_at > now {
+            return Err(BridgeError::WithdrawalScheduled { release_at });
         }
         
-        // Check if refund is allowed
-        let can_refund = match transfer.status {
-            TransferStatus::Failed | TransferStatus::Expired => true,
-            _ if now > transfer.expires_at => {
-                transfer.status = TransferStatus::Expired;
-                true
-            }
-            _ => false,
-        };
-        
-        if !can_refund {
-            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        transfer.release_at = Some(now);
+        transfer.expires_at = now + transfer.expiry();
+        state.expiration_queue.push_back((transfer.expires_at, transfer.id));
+        let batch_limits = state.batch_limits.get(&chain_id).await?;
+        transfer.status = if batch_limits.is_some() { TransferStatus::Batched } else { TransferStatus::AwaitingApproval };
+        self.save_transfer(state, transfer.clone()).await?;
+        match batch_limits {
+            Some(limits) => self.add_to_batch(state, &limits, transfer, now).await?,
+            None => state.awaiting_approval.insert(&transfer.id, ())?,
         }
+        tracing::info!("Scheduled withdrawal released: transfer_id={}", transfer.id);
+        Ok(())
+    }
+    
+    /// Appends a new withdrawal to its chain's open batch, sealing batches that reach a limit.
+    async fn add_to_batch(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        limits: &BatchLimits,
+        transfer: &BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let chain = transfer.corridor_chain()?;
+        let mut batch = self.open_batch(state, chain, now).await?;
         
-        if transfer.status == TransferStatus::Refunded {
-            return Err(BridgeError::AlreadyProcessed);
+        // A withdrawal that would overflow the batch, or arrives after its age limit, starts the next one
+        let total = math::checked_add(batch.total_amount, transfer.net_amount)?;
+        if !batch.transfer_ids.is_empty() && (total > limits.max_total_amount || limits.is_due(batch.opened_at, now)) {
+            self.seal_batch(state, batch, now).await?;
+            batch = self.open_batch(state, chain, now).await?;
         }
         
-        // Refund user for outbound transfers
-        if transfer.direction == TransferDirection::Outbound {
-            let balance_key = (transfer.user, transfer.asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            // Refund net amount (fee was already deducted)
-            state.balances.insert(&balance_key, current_balance + transfer.net_amount)?;
+        batch.transfer_ids.push(transfer.id);
+        batch.total_amount = math::checked_add(batch.total_amount, transfer.net_amount)?;
+        if limits.is_full(batch.transfer_ids.len(), batch.total_amount) {
+            self.seal_batch(state, batch, now).await
+        } else {
+            state.batches.insert(&batch.id, batch)?;
+            Ok(())
         }
-        
-        transfer.status = TransferStatus::Refunded;
-        transfer.completed_at = Some(now);
-        
-        state.transfers.insert(&transfer_id, transfer)?;
-        state.active_transfers.remove(&transfer_id)?;
-        
-        tracing::info!("Refund claimed: transfer_id={}, user={:?}", transfer_id, caller);
-        
-        Ok(())
     }
     
-    async fn process_expired_transfers(
+    /// The chain's open batch, or a new empty one registered as open.
+    async fn open_batch(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        now: Timestamp,
+    ) -> Result<WithdrawalBatch, BridgeError> {
+        if let Some(batch_id) = state.open_batches.get(&chain.chain_id()).await? {
+            return state.batches.get(&batch_id).await?.ok_or(BridgeError::BatchNotFound { batch_id });
+        }
+        let batch_id = state.next_batch_id.get();
+        state.next_batch_id.set(batch_id + 1);
+        state.open_batches.insert(&chain.chain_id(), batch_id)?;
+        Ok(WithdrawalBatch::new(batch_id, chain, now))
+    }
+    
+    async fn seal_open_batch(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
     ) -> Result<(), BridgeError> {
         let now = runtime.system_time();
-        let mut processed = 0;
-        
-        while processed < 10 {
-            let Some((expires_at, transfer_id)) = state.expiration_queue.front().await? else {
-                break;
-            };
-            
-            if expires_at > now {
-                break;
-            }
-            
-            state.expiration_queue.pop_front();
-            
-            if let Some(mut transfer) = state.transfers.get(&transfer_id).await? {
-                if matches!(transfer.status, 
-                    TransferStatus::Pending | 
-                    TransferStatus::Confirming | 
-                    TransferStatus::AwaitingApproval |
-                    TransferStatus::Executing
-                ) {
-                    transfer.status = TransferStatus::Expired;
-                    state.transfers.insert(&transfer_id, transfer)?;
-                    state.active_transfers.remove(&transfer_id)?;
-                    
-                    let mut stats = state.stats.get();
-                    stats.failed_transfers += 1;
-                    stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
-                    state.stats.set(stats);
-                    
-                    processed += 1;
-                }
-            }
-        }
+        let batch_id = state.open_batches.get(&chain.chain_id()).await?
+            .ok_or(BridgeError::NoOpenBatch { chain })?;
+        let batch = state.batches.get(&batch_id).await?
+            .ok_or(BridgeError::BatchNotFound { batch_id })?;
         
-        if processed > 0 {
-            tracing::info!("Processed {} expired transfers", processed);
+        // Without limits (batching switched off) the leftover batch can be flushed by anyone
+        let due = state.batch_limits.get(&chain.chain_id()).await?
+            .map_or(true, |limits| limits.is_due(batch.opened_at, now));
+        if !due {
+            self.require_admin(runtime, state)?;
         }
         
-        Ok(())
+        self.seal_batch(state, batch, now).await
     }
     
-    async fn configure_chain(
+    /// Fixes a batch's contents under its root and hands it to the validators.
+    async fn seal_batch(
         &mut self,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        config: ChainConfig,
+        mut batch: WithdrawalBatch,
+        now: Timestamp,
     ) -> Result<(), BridgeError> {
-        state.chain_configs.insert(&config.chain.chain_id(), config.clone())?;
+        let items = self.batch_items(state, &batch).await?;
+        batch.root = Some(batch_root(batch.chain, batch.id, &items));
+        let amounts = items.iter().map(|item| (item.asset.as_str(), item.amount));
+        batch.approval_requirement = self.tier_requirement(state, batch.chain, amounts).await?;
+        batch.status = BatchStatus::AwaitingApproval;
+        batch.sealed_at = Some(now);
+        state.open_batches.remove(&batch.chain.chain_id())?;
         
         tracing::info!(
-            "Chain configured: chain={:?}, enabled={}, assets={}",
-            config.chain, config.is_enabled, config.supported_assets.len()
+            "Batch sealed: batch_id={}, chain={:?}, transfers={}, total={}",
+            batch.id, batch.chain, batch.transfer_ids.len(), batch.total_amount
         );
         
+        state.batches.insert(&batch.id, batch)?;
         Ok(())
     }
     
-    async fn disable_chain(
+    async fn batch_items(
+        &self,
+        state: &BridgeState<ContractRuntime<Self>>,
+        batch: &WithdrawalBatch,
+    ) -> Result<Vec<BatchItem>, BridgeError> {
+        let mut items = Vec::with_capacity(batch.transfer_ids.len());
+        for &transfer_id in &batch.transfer_ids {
+            let transfer = state.transfers.get(&transfer_id).await?
+                .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+            items.push(BatchItem {
+                transfer_id,
+                recipient_address: transfer.external_address,
+                asset: transfer.asset,
+                amount: transfer.net_amount,
+                memo: transfer.memo,
+            });
+        }
+        Ok(items)
+    }
+    
+    async fn approve_batch(
         &mut self,
+        runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        chain: ExternalChain,
+        batch_id: u64,
+        signature: Vec<u8>,
     ) -> Result<(), BridgeError> {
-        let mut config = state.chain_configs.get(&chain.chain_id()).await?
-            .ok_or(BridgeError::ChainNotConfigured { chain })?;
+        let validator = self.require_active_validator(runtime, state).await?;
+        let now = runtime.system_time();
         
-        config.is_enabled = false;
-        state.chain_configs.insert(&chain.chain_id(), config)?;
+        let mut batch = state.batches.get(&batch_id).await?
+            .ok_or(BridgeError::BatchNotFound { batch_id })?;
+        if !matches!(batch.status, BatchStatus::AwaitingApproval | BatchStatus::Approved) {
+            return Err(BridgeError::InvalidBatchStatus { status: batch.status });
+        }
         
-        tracing::info!("Chain disabled: {:?}", chain);
+        let approval_key = (batch_id, validator);
+        if state.batch_approvals.contains_key(&approval_key).await? {
+            return Err(BridgeError::AlreadyApproved);
+        }
+        state.batch_approvals.insert(&approval_key, ValidatorApproval {
+            validator,
+            approved: true,
+            signature,
+            timestamp: now,
+        })?;
         
+        let validator_weight = state.validators.get(&validator).await?
+            .map_or(0, |config| config.weight);
+        batch.approval_weight += validator_weight;
+        batch.approval_count += 1;
+        
+        let total_weight = state.total_validator_weight.get();
+        let requirement = self.approval_requirement(state, batch.approval_requirement.as_ref());
+        let required_weight = requirement.required_weight(total_weight);
+        let mut senior_approvals = 0;
+        for senior in &requirement.senior_validators {
+            if state.validators.contains_key(senior).await?
+                && state.batch_approvals.contains_key(&(batch_id, *senior)).await?
+            {
+                senior_approvals += 1;
+            }
+        }
+        if requirement.is_met(batch.approval_weight, total_weight, senior_approvals) {
+            batch.status = BatchStatus::Approved;
+        }
+        
+        tracing::info!(
+            "Batch approved: batch_id={}, validator={:?}, weight={}/{}",
+            batch_id, validator, batch.approval_weight, required_weight
+        );
+        
+        state.batches.insert(&batch_id, batch)?;
         Ok(())
     }
     
-    async fn add_validator(
+    async fn execute_batch(
         &mut self,
+        runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        config: ValidatorConfig,
+        batch_id: u64,
     ) -> Result<(), BridgeError> {
-        let total_weight = state.total_validator_weight.get();
-        state.total_validator_weight.set(total_weight + config.weight);
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         
-        state.validators.insert(&config.address, config.clone())?;
+        let mut batch = state.batches.get(&batch_id).await?
+            .ok_or(BridgeError::BatchNotFound { batch_id })?;
+        let (BatchStatus::Approved, Some(root)) = (batch.status, batch.root) else {
+            return Err(BridgeError::InvalidBatchStatus { status: batch.status });
+        };
         
-        tracing::info!("Validator added: {:?}, weight={}", config.address, config.weight);
+        let items = self.batch_items(state, &batch).await?;
+        let mut signatures = Vec::new();
+        for validator in state.validators.indices().await? {
+            if let Some(approval) = state.batch_approvals.get(&(batch_id, validator)).await? {
+                signatures.push(approval);
+            }
+        }
+        
+        batch.status = BatchStatus::Executing;
+        batch.relayer = Some(relayer);
+        state.batches.insert(&batch_id, batch.clone())?;
+        
+        runtime
+            .prepare_message(Message::BatchWithdrawalRequest {
+                batch_id,
+                chain: batch.chain,
+                root,
+                items,
+                signatures,
+            })
+            .send_to(relayer.chain_id);
+        
+        tracing::info!("Batch executing: batch_id={}, relayer={:?}", batch_id, relayer);
         
         Ok(())
     }
     
-    async fn remove_validator(
+    async fn complete_batch(
         &mut self,
+        runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        validator: Account,
+        batch_id: u64,
+        tx_hash: String,
+        results: Vec<bool>,
     ) -> Result<(), BridgeError> {
-        let config = state.validators.get(&validator).await?
-            .ok_or(BridgeError::ValidatorNotFound { address: validator })?;
+        let now = runtime.system_time();
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         
-        let total_weight = state.total_validator_weight.get();
-        state.total_validator_weight.set(total_weight.saturating_sub(config.weight));
+        let mut batch = state.batches.get(&batch_id).await?
+            .ok_or(BridgeError::BatchNotFound { batch_id })?;
+        if batch.status != BatchStatus::Executing {
+            return Err(BridgeError::InvalidBatchStatus { status: batch.status });
+        }
+        if batch.relayer != Some(relayer) {
+            return Err(BridgeError::Unauthorized { reason: "Not the relayer executing this batch".to_string() });
+        }
+        self.require_bonded_relayer(runtime, state, batch.chain).await?;
+        if results.len() != batch.transfer_ids.len() {
+            return Err(BridgeError::BatchResultsMismatch { expected: batch.transfer_ids.len(), got: results.len() });
+        }
         
-        state.validators.remove(&validator)?;
+        // Only failed items are refunded; the rest completed in the batch transaction
+        for (&transfer_id, &success) in batch.transfer_ids.iter().zip(&results) {
+            let mut transfer = state.transfers.get(&transfer_id).await?
+                .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+            self.finish_withdrawal(runtime, state, &mut transfer, relayer, &tx_hash, success, now).await?;
+        }
         
-        tracing::info!("Validator removed: {:?}", validator);
+        batch.status = BatchStatus::Completed;
+        batch.destination_tx_hash = Some(tx_hash.clone());
+        batch.completed_at = Some(now);
+        for validator in state.validators.indices().await? {
+            state.batch_approvals.remove(&(batch_id, validator))?;
+        }
+        state.batches.insert(&batch_id, batch)?;
+        
+        tracing::info!(
+            "Batch completed: batch_id={}, failed={}, tx_hash={}",
+            batch_id, results.iter().filter(|success| !**success).count(), tx_hash
+        );
         
         Ok(())
     }
     
-    async fn update_fees(
+    async fn claim_refund(
         &mut self,
+        runtime: &mut ContractRuntime<Self>,
         state: &mut BridgeState<ContractRuntime<Self>>,
-        chain: ExternalChain,
-        base_fee: Option<Amount>,
+        transfer_id: TransferId,
+    ) -> Result<(), BridgeError> {
+        let caller = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        state.ensure_no_debt(caller).await?;
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        // Only transfer owner can claim refund
+        if transfer.user != caller {
+            return Err(BridgeError::Unauthorized { reason: "Not transfer owner".to_string() });
+        }
+        
+        // Deposits never credited anything here; abandoned ones are returned on their source chain
+        if transfer.direction == TransferDirection::Inbound {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        // Check if refund is allowed
+        let can_refund = match transfer.status {
+            TransferStatus::Failed | TransferStatus::Expired => true,
+            // Uncreditable deposits are resolved by claim or admin, never refunded;
+            // batched withdrawals may still be paid out by their batch
+            TransferStatus::ClaimPending
+            | TransferStatus::Unclaimed
+            | TransferStatus::Quarantined
+            | TransferStatus::Frozen
+            | TransferStatus::Batched => false,
+            _ if now > transfer.expires_at => {
+                transfer.status = TransferStatus::Expired;
+                self.forfeit_relayer_fee(state, &transfer).await?;
+                true
+            }
+            _ => false,
+        };
+        
+        if !can_refund {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        if transfer.status == TransferStatus::Refunded {
+            return Err(BridgeError::AlreadyProcessed);
+        }
+        
+        // Refund net amount (fee was already deducted)
+        self.credit_balance(runtime, state, transfer.user, &transfer.asset, transfer.net_amount).await?;
+        
+        transfer.status = TransferStatus::Refunded;
+        transfer.completed_at = Some(now);
+        
+        self.prune_approvals(state, &mut transfer).await?;
+        self.save_transfer(state, transfer).await?;
+        state.active_transfers.remove(&transfer_id)?;
+        
+        tracing::info!("Refund claimed: transfer_id={}, user={:?}", transfer_id, caller);
+        
+        Ok(())
+    }
+    
+    async fn claim_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        proof_of_source_ownership: Vec<u8>,
+    ) -> Result<(), BridgeError> {
+        let claimer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        state.ensure_no_debt(claimer).await?;
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        if transfer.status != TransferStatus::ClaimPending {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        if now > transfer.expires_at {
+            return Err(BridgeError::Expired);
+        }
+        if state.quarantined_accounts.contains_key(&claimer).await? {
+            return Err(BridgeError::Unauthorized { reason: "Claimer is quarantined".to_string() });
+        }
+        
+        // The claimer must control the address that sent the deposit
+        let source_chain = transfer.corridor_chain()?;
+        let scheme = self.ownership_scheme(state, source_chain).await?
+            .ok_or(BridgeError::UnsupportedOwnershipProof { chain: source_chain })?;
+        signature::verify_ownership(
+            scheme,
+            &transfer.external_address,
+            &claim_challenge(&transfer, &claimer),
+            &proof_of_source_ownership,
+        )?;
+        
+        transfer.user = claimer;
+        let chain_config = state.chain_configs.get(&source_chain.chain_id()).await?
+            .ok_or(BridgeError::ChainNotConfigured { chain: source_chain })?;
+        self.settle_deposit(runtime, state, &chain_config, &mut transfer, now).await?;
+        if transfer.status == TransferStatus::Completed {
+            state.active_transfers.remove(&transfer_id)?;
+            
+            let mut stats = state.stats.get();
+            stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+            state.stats.set(stats);
+        }
+        self.save_transfer(state, transfer).await?;
+        
+        let mut user_transfers = state.user_transfers.get(&claimer).await?.unwrap_or_default();
+        user_transfers.push(transfer_id);
+        state.user_transfers.insert(&claimer, user_transfers)?;
+        
+        tracing::info!("Deposit claimed: transfer_id={}, claimer={:?}", transfer_id, claimer);
+        
+        Ok(())
+    }
+    
+    async fn resolve_unclaimed_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        recipient: Account,
+    ) -> Result<(), BridgeError> {
+        self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        if transfer.status != TransferStatus::Unclaimed {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        transfer.user = recipient;
+        self.complete_deposit(runtime, state, &mut transfer, now).await?;
+        self.save_transfer(state, transfer).await?;
+        state.unclaimed_deposits.remove(&transfer_id)?;
+        
+        let mut user_transfers = state.user_transfers.get(&recipient).await?.unwrap_or_default();
+        user_transfers.push(transfer_id);
+        state.user_transfers.insert(&recipient, user_transfers)?;
+        
+        tracing::info!("Unclaimed deposit resolved: transfer_id={}, recipient={:?}", transfer_id, recipient);
+        
+        Ok(())
+    }
+    
+    async fn release_quarantined(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+    ) -> Result<(), BridgeError> {
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        if transfer.status != TransferStatus::Quarantined {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        let release_at = transfer.release_at.unwrap_or(now);
+        if now < release_at {
+            return Err(BridgeError::QuarantineActive { release_at });
+        }
+        
+        self.complete_quarantined(runtime, state, &mut transfer, now).await?;
+        self.save_transfer(state, transfer).await?;
+        
+        Ok(())
+    }
+    
+    async fn freeze_transfer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        reason: String,
+    ) -> Result<(), BridgeError> {
+        self.require_admin(runtime, state)?;
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        if transfer.status != TransferStatus::Quarantined {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        transfer.status = TransferStatus::Frozen;
+        transfer.error_message = Some(reason);
+        self.save_transfer(state, transfer).await?;
+        
+        tracing::warn!("Quarantined transfer frozen: transfer_id={}", transfer_id);
+        
+        Ok(())
+    }
+    
+    async fn resolve_frozen_transfer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        release: bool,
+    ) -> Result<(), BridgeError> {
+        self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        
+        if transfer.status != TransferStatus::Frozen {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        if release {
+            // Back to quarantine; credited once the original release time has passed
+            transfer.status = TransferStatus::Quarantined;
+            transfer.error_message = None;
+        } else {
+            let reason = transfer.error_message.clone().unwrap_or_default();
+            self.fail_transfer(runtime, &mut transfer, BridgeError::TransferRejected { reason });
+            transfer.completed_at = Some(now);
+            state.quarantined_transfers.remove(&transfer_id)?;
+            state.active_transfers.remove(&transfer_id)?;
+            
+            let mut stats = state.stats.get();
+            stats.failed_transfers += 1;
+            stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+            state.stats.set(stats);
+            
+            self.record_corridor(
+                state, transfer.corridor_chain()?, &transfer.asset, TransferDirection::Inbound, now,
+                CorridorEvent::Failed,
+            ).await?;
+        }
+        self.save_transfer(state, transfer).await?;
+        
+        tracing::warn!("Frozen transfer resolved: transfer_id={}, released={}", transfer_id, release);
+        
+        Ok(())
+    }
+    
+    /// Hands withdrawals whose relayer missed its deadline back to `Approved` for another relayer
+    /// to claim, failing and refunding them once `MAX_EXECUTION_RETRIES` re-queues are used up.
+    async fn requeue_stalled_withdrawals(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+    ) -> Result<(), BridgeError> {
+        let now = runtime.system_time();
+        let mut processed = 0;
+        
+        for transfer_id in state.executing_transfers.indices().await? {
+            if processed >= 10 {
+                break;
+            }
+            let Some(mut transfer) = state.transfers.get(&transfer_id).await? else {
+                state.executing_transfers.remove(&transfer_id)?;
+                continue;
+            };
+            if !transfer.execution_stalled(now) {
+                continue;
+            }
+            
+            state.executing_transfers.remove(&transfer_id)?;
+            transfer.executing_relayer = None;
+            transfer.executing_deadline = None;
+            
+            if transfer.retry_count >= MAX_EXECUTION_RETRIES {
+                let attempts = transfer.retry_count + 1;
+                self.fail_transfer(runtime, &mut transfer, BridgeError::ExecutionAttemptsExhausted { attempts });
+                self.credit_balance(runtime, state, transfer.user, &transfer.asset, transfer.net_amount).await?;
+                self.forfeit_relayer_fee(state, &transfer).await?;
+                self.prune_approvals(state, &mut transfer).await?;
+                state.active_transfers.remove(&transfer_id)?;
+                
+                let mut stats = state.stats.get();
+                stats.failed_transfers += 1;
+                stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+                state.stats.set(stats);
+                
+                self.record_corridor(
+                    state, transfer.corridor_chain()?, &transfer.asset, transfer.direction, now,
+                    CorridorEvent::Failed,
+                ).await?;
+                tracing::warn!("Stalled withdrawal failed: transfer_id={}", transfer_id);
+            } else {
+                transfer.status = TransferStatus::Approved;
+                transfer.retry_count += 1;
+                tracing::info!(
+                    "Stalled withdrawal re-queued: transfer_id={}, retry={}",
+                    transfer_id, transfer.retry_count
+                );
+            }
+            self.save_transfer(state, transfer).await?;
+            processed += 1;
+        }
+        
+        Ok(())
+    }
+    
+    async fn process_expired_transfers(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+    ) -> Result<(), BridgeError> {
+        let now = runtime.system_time();
+        let mut processed = 0;
+        let mut scanned = 0;
+        
+        // Entries have different delays (expiry, claim windows, quarantines), so the queue is not
+        // sorted: entries that are not yet due rotate to the back instead of blocking the sweep
+        while processed < 10 && scanned < 32 {
+            let Some((expires_at, transfer_id)) = state.expiration_queue.front().await? else {
+                break;
+            };
+            scanned += 1;
+            
+            state.expiration_queue.pop_front();
+            
+            if expires_at > now {
+                state.expiration_queue.push_back((expires_at, transfer_id));
+                continue;
+            }
+            
+            if let Some(mut transfer) = state.transfers.get(&transfer_id).await? {
+                // Quarantined deposits are released lazily once due
+                if transfer.status == TransferStatus::Quarantined && transfer.release_at.map_or(true, |at| at <= now) {
+                    self.complete_quarantined(runtime, state, &mut transfer, now).await?;
+                    self.save_transfer(state, transfer).await?;
+                    processed += 1;
+                } else if transfer.status == TransferStatus::Scheduled {
+                    // Released into approval, or requeued for the next opening if the windows moved
+                    match self.release_scheduled(state, &mut transfer, now).await {
+                        Ok(()) => processed += 1,
+                        Err(BridgeError::WithdrawalScheduled { release_at }) => {
+                            transfer.release_at = Some(release_at);
+                            transfer.expires_at = release_at + transfer.expiry();
+                            self.save_transfer(state, transfer).await?;
+                            state.expiration_queue.push_back((release_at, transfer_id));
+                        }
+                        Err(error) => return Err(error),
+                    }
+                } else if transfer.status == TransferStatus::ClaimPending && transfer.expires_at <= now {
+                    // Lapsed claims are parked for the admin rather than failed
+                    transfer.status = TransferStatus::Unclaimed;
+                    self.save_transfer(state, transfer).await?;
+                    state.active_transfers.remove(&transfer_id)?;
+                    state.unclaimed_deposits.insert(&transfer_id, ())?;
+                    
+                    let mut stats = state.stats.get();
+                    stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+                    state.stats.set(stats);
+                    
+                    processed += 1;
+                } else if matches!(transfer.status, 
+                    TransferStatus::Pending | 
+                    TransferStatus::Confirming | 
+                    TransferStatus::AwaitingApproval |
+                    TransferStatus::Executing
+                ) && transfer.expires_at <= now {
+                    // A withdrawal released early leaves its release entry behind; its expiry
+                    // has an entry of its own
+                    let abandoned = transfer.direction == TransferDirection::Inbound
+                        && transfer.status == TransferStatus::Confirming;
+                    transfer.status = TransferStatus::Expired;
+                    self.forfeit_relayer_fee(state, &transfer).await?;
+                    self.prune_approvals(state, &mut transfer).await?;
+                    self.save_transfer(state, transfer.clone()).await?;
+                    state.active_transfers.remove(&transfer_id)?;
+                    state.awaiting_approval.remove(&transfer_id)?;
+                    state.executing_transfers.remove(&transfer_id)?;
+                    
+                    let mut stats = state.stats.get();
+                    stats.failed_transfers += 1;
+                    stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+                    state.stats.set(stats);
+                    
+                    self.record_corridor(
+                        state, transfer.corridor_chain()?, &transfer.asset, transfer.direction, now,
+                        CorridorEvent::Failed,
+                    ).await?;
+                    
+                    // The funds are still locked in the source chain's bridge contract
+                    if abandoned {
+                        state.abandoned_deposits.insert(&transfer_id, ())?;
+                        state.events.push_back(BridgeEvent::InboundAbandoned {
+                            transfer_id,
+                            source_chain: transfer.corridor_chain()?,
+                            source_tx_hash: transfer.source_tx_hash.clone(),
+                            source_address: transfer.external_address.clone(),
+                            asset: transfer.asset.clone(),
+                            amount: transfer.amount,
+                            confirmations: transfer.confirmations,
+                            required_confirmations: transfer.required_confirmations,
+                            timestamp: now,
+                        });
+                    }
+                    
+                    processed += 1;
+                }
+            }
+        }
+        
+        if processed > 0 {
+            tracing::info!("Processed {} expired transfers", processed);
+        }
+        
+        Ok(())
+    }
+    
+    async fn report_external_refund(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        refund_tx_hash: String,
+    ) -> Result<(), BridgeError> {
+        let validator = self.require_active_validator(runtime, state).await?;
+        let now = runtime.system_time();
+        
+        let mut transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        if !state.abandoned_deposits.contains_key(&transfer_id).await? {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        
+        transfer.status = TransferStatus::Refunded;
+        transfer.completed_at = Some(now);
+        transfer.external_refund = Some(ExternalRefund {
+            tx_hash: refund_tx_hash.clone(),
+            reported_by: validator,
+            reported_at: now,
+        });
+        self.save_transfer(state, transfer.clone()).await?;
+        state.abandoned_deposits.remove(&transfer_id)?;
+        state.events.push_back(BridgeEvent::ExternalRefundReported {
+            transfer_id,
+            refund_tx_hash: refund_tx_hash.clone(),
+            reported_by: validator,
+            timestamp: now,
+        });
+        
+        runtime
+            .prepare_message(Message::TransferUpdate {
+                transfer_id,
+                status: TransferStatus::Refunded,
+                tx_hash: Some(refund_tx_hash.clone()),
+                failure: None,
+            })
+            .send_to(transfer.user.chain_id);
+        
+        tracing::info!(
+            "External refund reported: transfer_id={}, refund_tx_hash={}, validator={:?}",
+            transfer_id, refund_tx_hash, validator
+        );
+        
+        Ok(())
+    }
+    
+    async fn configure_chain(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        config: ChainConfig,
+    ) -> Result<(), BridgeError> {
+        config.check_expiries()?;
+        let config = self.store_chain_config(state, config).await?;
+        
+        tracing::info!(
+            "Chain configured: chain={:?}, enabled={}, assets={}, version={}",
+            config.chain, config.is_enabled, config.supported_assets.len(), config.version
+        );
+        
+        Ok(())
+    }
+    
+    async fn rotate_bridge_address(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        new_address: String,
+        overlap_seconds: u64,
+    ) -> Result<(), BridgeError> {
+        let mut config = state.chain_configs.get(&chain.chain_id()).await?
+            .ok_or(BridgeError::ChainNotConfigured { chain })?;
+        if new_address.is_empty() {
+            return Err(BridgeError::InvalidAddress { address: new_address });
+        }
+        
+        config.rotate_address(new_address, overlap_seconds, runtime.system_time());
+        let config = self.store_chain_config(state, config).await?;
+        
+        tracing::info!(
+            "Bridge contract rotated: chain={:?}, address={}, version={}",
+            config.chain, config.bridge_contract_address, config.version
+        );
+        Ok(())
+    }
+    
+    async fn disable_chain(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+    ) -> Result<(), BridgeError> {
+        let mut config = state.chain_configs.get(&chain.chain_id()).await?
+            .ok_or(BridgeError::ChainNotConfigured { chain })?;
+        
+        config.is_enabled = false;
+        self.store_chain_config(state, config).await?;
+        
+        tracing::info!("Chain disabled: {:?}", chain);
+        
+        Ok(())
+    }
+    
+    async fn add_validator(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        config: ValidatorConfig,
+    ) -> Result<(), BridgeError> {
+        if let Some(previous) = state.validators.get(&config.address).await? {
+            self.uncount_validator(state, &previous);
+        }
+        let total_weight = state.total_validator_weight.get();
+        state.total_validator_weight.set(total_weight + config.weight);
+        state.validator_count.set(state.validator_count.get() + 1);
+        if !config.is_active {
+            state.inactive_validator_count.set(state.inactive_validator_count.get() + 1);
+        }
+        
+        state.validators.insert(&config.address, config.clone())?;
+        state.validator_set_epoch.set(state.validator_set_epoch.get() + 1);
+        
+        tracing::info!("Validator added: {:?}, weight={}", config.address, config.weight);
+        
+        Ok(())
+    }
+    
+    async fn remove_validator(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        validator: Account,
+    ) -> Result<(), BridgeError> {
+        let config = state.validators.get(&validator).await?
+            .ok_or(BridgeError::ValidatorNotFound { address: validator })?;
+        
+        self.uncount_validator(state, &config);
+        self.withdraw_approvals(state, validator, config.weight).await?;
+        state.validators.remove(&validator)?;
+        state.validator_set_epoch.set(state.validator_set_epoch.get() + 1);
+        
+        tracing::info!("Validator removed: {:?}", validator);
+        
+        Ok(())
+    }
+    
+    /// Drops a removed validator's approvals. Transfers and batches still awaiting approval stop
+    /// counting its weight; those already approved keep the quorum they reached.
+    async fn withdraw_approvals(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        validator: Account,
+        weight: u32,
+    ) -> Result<(), BridgeError> {
+        for (transfer_id, approver) in state.transfer_approvals.indices().await? {
+            if approver != validator {
+                continue;
+            }
+            state.transfer_approvals.remove(&(transfer_id, validator))?;
+            let awaiting = state.transfers.get(&transfer_id).await?
+                .is_some_and(|transfer| transfer.status == TransferStatus::AwaitingApproval);
+            if !awaiting {
+                continue;
+            }
+            if let Some((total, count)) = state.approval_weights.get(&transfer_id).await? {
+                state.approval_weights.insert(&transfer_id, (total.saturating_sub(weight), count.saturating_sub(1)))?;
+            }
+            if let Some(mut snapshot) = state.approval_snapshots.get(&transfer_id).await? {
+                snapshot.signatures.retain(|signature| signature.validator != validator);
+                state.approval_snapshots.insert(&transfer_id, snapshot)?;
+            }
+        }
+        
+        for (batch_id, approver) in state.batch_approvals.indices().await? {
+            if approver != validator {
+                continue;
+            }
+            state.batch_approvals.remove(&(batch_id, validator))?;
+            let Some(mut batch) = state.batches.get(&batch_id).await? else {
+                continue;
+            };
+            if batch.status == BatchStatus::AwaitingApproval {
+                batch.approval_weight = batch.approval_weight.saturating_sub(weight);
+                batch.approval_count = batch.approval_count.saturating_sub(1);
+                state.batches.insert(&batch_id, batch)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Takes a registered validator out of the weight and count totals
+    fn uncount_validator(&mut self, state: &mut BridgeState<ContractRuntime<Self>>, config: &ValidatorConfig) {
+        let total_weight = state.total_validator_weight.get();
+        state.total_validator_weight.set(total_weight.saturating_sub(config.weight));
+        state.validator_count.set(state.validator_count.get().saturating_sub(1));
+        if !config.is_active {
+            state.inactive_validator_count.set(state.inactive_validator_count.get().saturating_sub(1));
+        }
+    }
+    
+    async fn update_fees(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        base_fee: Option<Amount>,
         fee_percentage_bps: Option<u64>,
+        relayer_gas_fee: Option<Amount>,
+    ) -> Result<(), BridgeError> {
+        let mut config = state.chain_configs.get(&chain.chain_id()).await?
+            .ok_or(BridgeError::ChainNotConfigured { chain })?;
+        
+        if let Some(fee) = base_fee {
+            config.base_fee = fee;
+        }
+        if let Some(bps) = fee_percentage_bps {
+            config.fee_percentage_bps = bps;
+        }
+        if let Some(gas_fee) = relayer_gas_fee {
+            config.relayer_gas_fee = gas_fee;
+        }
+        
+        let config = self.store_chain_config(state, config).await?;
+        
+        tracing::info!("Fees updated for chain {:?}, version={}", chain, config.version);
+        
+        Ok(())
+    }
+    
+    /// Stores a chain config under the next version, archiving the one it replaces.
+    /// Fees for a transfer: a voucher's terms, else the account's, else the chain's. The override
+    /// used is counted and returned for the transfer record.
+    async fn charge_fees(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        config: &ChainConfig,
+        amount: Amount,
+        direction: TransferDirection,
+        account: Option<Account>,
+        voucher: Option<String>,
+        now: Timestamp,
+    ) -> Result<(FeeBreakdown, Option<FeeOverrideKey>), BridgeError> {
+        let key = match (voucher, account) {
+            (Some(code), _) => {
+                let key = FeeOverrideKey::Voucher(code.clone());
+                match state.fee_overrides.get(&key).await? {
+                    Some(terms) if terms.is_usable(&key, now) => Some((key, terms)),
+                    _ => return Err(BridgeError::FeeVoucherUnavailable { code }),
+                }
+            }
+            (None, Some(account)) => {
+                let key = FeeOverrideKey::Account(account);
+                state.fee_overrides.get(&key).await?
+                    .filter(|terms| terms.is_usable(&key, now))
+                    .map(|terms| (key, terms))
+            }
+            (None, None) => None,
+        };
+        let Some((key, mut terms)) = key else {
+            return Ok((config.fee_breakdown(amount, direction)?, None));
+        };
+        
+        let fees = terms.fee_breakdown(config, amount, direction)?;
+        terms.uses += 1;
+        state.fee_overrides.insert(&key, terms)?;
+        Ok((fees, Some(key)))
+    }
+    
+    async fn store_chain_config(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        mut config: ChainConfig,
+    ) -> Result<ChainConfig, BridgeError> {
+        let chain_id = config.chain.chain_id();
+        let previous = state.chain_configs.get(&chain_id).await?;
+        
+        config.version = match previous {
+            Some(previous) => {
+                let version = previous.version;
+                state.chain_config_history.insert(&(chain_id, version), previous)?;
+                if version >= CHAIN_CONFIG_HISTORY_LIMIT {
+                    state.chain_config_history.remove(&(chain_id, version - CHAIN_CONFIG_HISTORY_LIMIT))?;
+                }
+                version + 1
+            }
+            None => 1,
+        };
+        
+        state.chain_configs.insert(&chain_id, config.clone())?;
+        Ok(config)
+    }
+    
+    /// Summarizes a finished transfer's approvals onto it and drops the individual entries.
+    async fn prune_approvals(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+    ) -> Result<(), BridgeError> {
+        let Some((weight, count)) = state.approval_weights.get(&transfer.id).await? else {
+            return Ok(());
+        };
+        transfer.approval_weight = weight;
+        transfer.approval_count = count;
+        state.approval_weights.remove(&transfer.id)?;
+        
+        // Removed validators' approvals went with them, so only registered validators hold entries
+        for validator in state.validators.indices().await? {
+            state.transfer_approvals.remove(&(transfer.id, validator))?;
+        }
+        Ok(())
+    }
+    
+    async fn claim_relayer_fees(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        asset: String,
+    ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        state.ensure_no_debt(relayer).await?;
+        
+        let key = (relayer, asset.clone());
+        let claimable = state.relayer_fees.get(&key).await?.unwrap_or_default();
+        if claimable == Amount::ZERO {
+            return Err(BridgeError::InsufficientBalance { required: Amount::ZERO, available: Amount::ZERO });
+        }
+        
+        self.credit_balance(runtime, state, relayer, &asset, claimable).await?;
+        state.relayer_fees.remove(&key)?;
+        
+        tracing::info!("Relayer fees claimed: relayer={:?}, asset={}, amount={}", relayer, asset, claimable);
+        
+        Ok(())
+    }
+    
+    /// Moves the relayer share of an expired withdrawal's fee to the protocol.
+    async fn forfeit_relayer_fee(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &BridgeTransfer,
+    ) -> Result<(), BridgeError> {
+        if transfer.relayer_fee > Amount::ZERO && transfer.relayer.is_none() {
+            self.collect_fee(state, &transfer.asset, transfer.relayer_fee).await?;
+        }
+        Ok(())
+    }
+    
+    async fn submit_block_attestation(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        height: u64,
+        block_hash: BlockHash,
+        signature: Vec<u8>,
+    ) -> Result<(), BridgeError> {
+        let validator = self.require_active_validator(runtime, state).await?;
+        let now = runtime.system_time();
+        let key = (chain.chain_id(), height);
+        
+        // Late attestations against an already finalized height only need a conflict check
+        if let Some(finalized_hash) = state.finalized_blocks.get(&key).await? {
+            if finalized_hash != block_hash {
+                self.flag_validator(state, validator, chain, height, block_hash, finalized_hash, now).await?;
+            }
+            return Ok(());
+        }
+        
+        let mut attestations = state.block_attestations.get(&key).await?.unwrap_or_default();
+        if attestations.iter().any(|a| a.validator == validator) {
+            return Err(BridgeError::AlreadyAttested { chain, height });
+        }
+        
+        attestations.push(BlockAttestation {
+            validator,
+            block_hash,
+            signature,
+            timestamp: now,
+        });
+        
+        // Calculate weight attesting the same hash
+        let mut attested_weight = 0u32;
+        for attestation in attestations.iter().filter(|a| a.block_hash == block_hash) {
+            if let Some(config) = state.validators.get(&attestation.validator).await? {
+                attested_weight += config.weight;
+            }
+        }
+        
+        let required_weight = self.calculate_approval_threshold(state).await?;
+        
+        if attested_weight >= required_weight {
+            state.finalized_blocks.insert(&key, block_hash)?;
+            state.block_attestations.remove(&key)?;
+            
+            let latest = state.latest_finalized_height.get(&chain.chain_id()).await?.unwrap_or_default();
+            if height > latest {
+                state.latest_finalized_height.insert(&chain.chain_id(), height)?;
+                
+                // Deposits at or below the finalized height can no longer be reorged
+                let unfinalized = state.unfinalized_deposits.get(&chain.chain_id()).await?.unwrap_or_default();
+                let (finalized, unfinalized): (Vec<_>, Vec<_>) =
+                    unfinalized.into_iter().partition(|(deposit_height, _)| *deposit_height <= height);
+                state.unfinalized_deposits.insert(&chain.chain_id(), unfinalized)?;
+                
+                // Deposits waiting on an attestation are final now
+                for (_, transfer_id) in finalized {
+                    let Some(mut transfer) = state.transfers.get(&transfer_id).await? else { continue };
+                    if transfer.status == TransferStatus::Confirming && transfer.is_final(Some(height)) {
+                        self.finalize_deposit(runtime, state, &mut transfer, now).await?;
+                        self.save_transfer(state, transfer).await?;
+                    }
+                }
+            }
+            
+            // Flag validators that attested a different hash at this height
+            for attestation in attestations.iter().filter(|a| a.block_hash != block_hash) {
+                self.flag_validator(
+                    state, attestation.validator, chain, height, attestation.block_hash, block_hash, now
+                ).await?;
+            }
+            
+            tracing::info!(
+                "Block finalized: chain={:?}, height={}, weight={}/{}",
+                chain, height, attested_weight, required_weight
+            );
+        } else {
+            state.block_attestations.insert(&key, attestations)?;
+            
+            tracing::info!(
+                "Block attested: chain={:?}, height={}, validator={:?}, weight={}/{}",
+                chain, height, validator, attested_weight, required_weight
+            );
+        }
+        
+        Ok(())
+    }
+    
+    async fn report_reorg(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        from_height: u64,
+    ) -> Result<(), BridgeError> {
+        let validator = self.require_active_validator(runtime, state).await?;
+        let now = runtime.system_time();
+        let key = (chain.chain_id(), from_height);
+        
+        let mut reporters = state.reorg_reports.get(&key).await?.unwrap_or_default();
+        if reporters.contains(&validator) {
+            return Err(BridgeError::AlreadyReportedReorg { chain, from_height });
+        }
+        reporters.push(validator);
+        
+        let mut reported_weight = 0u32;
+        for reporter in &reporters {
+            if let Some(config) = state.validators.get(reporter).await? {
+                reported_weight += config.weight;
+            }
+        }
+        
+        let required_weight = self.calculate_approval_threshold(state).await?;
+        if reported_weight < required_weight {
+            state.reorg_reports.insert(&key, reporters)?;
+            tracing::info!(
+                "Reorg reported: chain={:?}, from_height={}, weight={}/{}",
+                chain, from_height, reported_weight, required_weight
+            );
+            return Ok(());
+        }
+        state.reorg_reports.remove(&key)?;
+        
+        let finalized_height = state.latest_finalized_height.get(&chain.chain_id()).await?;
+        let clawback_window = std::time::Duration::from_secs(state.reorg_clawback_window_seconds.get());
+        let unfinalized = state.unfinalized_deposits.get(&chain.chain_id()).await?.unwrap_or_default();
+        let mut remaining = Vec::with_capacity(unfinalized.len());
+        let mut reverted = 0;
+        
+        for (height, transfer_id) in unfinalized {
+            // Finalized blocks cannot be reorged, whatever validators claim
+            if finalized_height.map_or(false, |finalized| height <= finalized) {
+                continue;
+            }
+            if height < from_height {
+                remaining.push((height, transfer_id));
+                continue;
+            }
+            
+            let Some(mut transfer) = state.transfers.get(&transfer_id).await? else {
+                continue;
+            };
+            
+            match transfer.status {
+                // Nothing was credited yet; re-confirm (and re-route to a claim) from scratch
+                TransferStatus::Confirming
+                | TransferStatus::ClaimPending
+                | TransferStatus::Quarantined
+                | TransferStatus::Frozen => {
+                    state.quarantined_transfers.remove(&transfer_id)?;
+                    transfer.release_at = None;
+                    transfer.status = TransferStatus::Pending;
+                    transfer.confirmations = 0;
+                    transfer.reorged_at = Some(now);
+                    self.save_transfer(state, transfer).await?;
+                    remaining.push((height, transfer_id));
+                    reverted += 1;
+                }
+                TransferStatus::Completed => {
+                    let within_window = transfer.completed_at
+                        .map_or(false, |completed_at| now <= completed_at + clawback_window);
+                    if within_window {
+                        self.claw_back_deposit(state, &mut transfer, now).await?;
+                        self.save_transfer(state, transfer).await?;
+                        reverted += 1;
+                    } else {
+                        tracing::warn!(
+                            "Reorged deposit outside clawback window: transfer_id={}",
+                            transfer_id
+                        );
+                    }
+                }
+                _ => remaining.push((height, transfer_id)),
+            }
+        }
+        
+        state.unfinalized_deposits.insert(&chain.chain_id(), remaining)?;
+        
+        tracing::warn!(
+            "Reorg processed: chain={:?}, from_height={}, reverted={}",
+            chain, from_height, reverted
+        );
+        
+        Ok(())
+    }
+    
+    /// Debits a reorged deposit back from the recipient, recording debt for any shortfall.
+    async fn claw_back_deposit(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let balance_key = (transfer.user, transfer.asset.clone());
+        
+        // Wrapped tokens live in the token application, so the full amount becomes debt
+        let shortfall = if state.token_applications.get(&transfer.asset).await?.is_some() {
+            transfer.net_amount
+        } else {
+            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
+            let (new_balance, shortfall) = clawback(current_balance, transfer.net_amount);
+            state.set_balance(&balance_key, current_balance, new_balance).await?;
+            shortfall
+        };
+        
+        if shortfall > Amount::ZERO {
+            let debt = state.debts.get(&balance_key).await?.unwrap_or_default();
+            if debt == Amount::ZERO {
+                let count = state.indebted_accounts.get(&transfer.user).await?.unwrap_or_default();
+                state.indebted_accounts.insert(&transfer.user, count + 1)?;
+            }
+            state.debts.insert(&balance_key, debt + shortfall)?;
+        }
+        
+        // The fee was taken from funds that never arrived
+        self.reverse_fee(state, &transfer.asset, transfer.fee, transfer.insurance_fee).await?;
+        transfer.insurance_fee = Amount::ZERO;
+        
+        transfer.status = TransferStatus::Reverted;
+        transfer.error_message = Some("Source transaction removed by chain reorg".to_string());
+        
+        let mut stats = state.stats.get();
+        stats.total_inbound_volume = stats.total_inbound_volume.saturating_sub(transfer.net_amount);
+        stats.total_fees_collected = stats.total_fees_collected.saturating_sub(transfer.fee);
+        stats.failed_transfers += 1;
+        state.stats.set(stats);
+        
+        self.record_corridor(
+            state, transfer.corridor_chain()?, &transfer.asset, TransferDirection::Inbound, now,
+            CorridorEvent::Failed,
+        ).await?;
+        
+        tracing::warn!(
+            "Deposit clawed back: transfer_id={}, user={:?}, shortfall={}",
+            transfer.id, transfer.user, shortfall
+        );
+        
+        Ok(())
+    }
+    
+    /// Completes a confirmed deposit, or quarantines it if it is large for its chain.
+    async fn settle_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain_config: &ChainConfig,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        if !chain_config.requires_quarantine(transfer.amount) {
+            return self.complete_deposit(runtime, state, transfer, now).await;
+        }
+        
+        let release_at = now + std::time::Duration::from_secs(chain_config.quarantine_seconds);
+        transfer.status = TransferStatus::Quarantined;
+        transfer.release_at = Some(release_at);
+        state.quarantined_transfers.insert(&transfer.id, release_at)?;
+        state.expiration_queue.push_back((release_at, transfer.id));
+        
+        tracing::warn!(
+            "Large deposit quarantined: transfer_id={}, amount={}, release_at={:?}",
+            transfer.id, transfer.amount, release_at
+        );
+        
+        Ok(())
+    }
+    
+    /// Credits a quarantined deposit whose release time has passed.
+    async fn complete_quarantined(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        self.complete_deposit(runtime, state, transfer, now).await?;
+        state.quarantined_transfers.remove(&transfer.id)?;
+        state.active_transfers.remove(&transfer.id)?;
+        
+        let mut stats = state.stats.get();
+        stats.pending_transfers = stats.pending_transfers.saturating_sub(1);
+        state.stats.set(stats);
+        
+        tracing::info!("Quarantined deposit released: transfer_id={}", transfer.id);
+        
+        Ok(())
+    }
+    
+    /// Credits a confirmed deposit to `transfer.user` and marks it completed.
+    /// The credit happens first, so callers may still write after a failed token call.
+    async fn complete_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &mut BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        self.credit_balance(runtime, state, transfer.user, &transfer.asset, transfer.net_amount).await?;
+        
+        transfer.insurance_fee = self.collect_fee(state, &transfer.asset, transfer.fee).await?;
+        
+        transfer.status = TransferStatus::Completed;
+        transfer.completed_at = Some(now);
+        
+        // Update stats
+        let mut stats = state.stats.get();
+        stats.total_inbound_transfers += 1;
+        stats.total_inbound_volume = stats.total_inbound_volume + transfer.net_amount;
+        stats.total_fees_collected = stats.total_fees_collected + transfer.fee;
+        state.stats.set(stats);
+        
+        self.record_corridor(
+            state, transfer.corridor_chain()?, &transfer.asset, TransferDirection::Inbound, now,
+            CorridorEvent::Completed {
+                fee: transfer.fee,
+                completion_seconds: elapsed_seconds(transfer.created_at, now),
+            },
+        ).await?;
+        
+        // Hooks are best effort: nothing they do may hold up the credit
+        if let Err(error) = self.notify_deposit_hooks(runtime, state, transfer, now).await {
+            tracing::warn!("Deposit hooks not notified: transfer_id={}, error={}", transfer.id, error);
+        }
+        Ok(())
+    }
+    
+    /// Announces `chain`'s availability to the corridor consumers if it changed since the last
+    /// announcement
+    async fn refresh_corridor(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+    ) -> Result<(), BridgeError> {
+        let now = runtime.system_time();
+        let chain_id = chain.chain_id();
+        let availability = state.corridor_availability(chain, now).await?;
+        let announced = state.corridor_statuses.get(&chain_id).await?
+            .map_or(CorridorAvailability::Open, |status| status.availability);
+        if availability == announced {
+            return Ok(());
+        }
+        
+        if availability == CorridorAvailability::Open {
+            state.corridor_statuses.remove(&chain_id)?;
+        } else {
+            state.corridor_statuses.insert(&chain_id, CorridorStatus { chain, availability, changed_at: now })?;
+        }
+        let consumers = state.corridor_consumers.get();
+        for consumer_chain in &consumers {
+            runtime
+                .prepare_message(Message::CorridorStatus { corridor: chain_id, availability, changed_at: now })
+                .with_authentication()
+                .send_to(*consumer_chain);
+        }
+        state.events.push_back(BridgeEvent::CorridorStatusChanged {
+            chain,
+            availability,
+            consumers: consumers.len(),
+            timestamp: now,
+        });
+        tracing::info!("Corridor status changed: chain={:?}, availability={:?}", chain, availability);
+        Ok(())
+    }
+    
+    /// `refresh_corridor` for every configured chain
+    async fn refresh_corridors(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+    ) -> Result<(), BridgeError> {
+        for chain_id in state.chain_configs.indices().await? {
+            self.refresh_corridor(runtime, state, ExternalChain::from_chain_id(chain_id)).await?;
+        }
+        Ok(())
+    }
+    
+    /// Sends `DepositCompleted` to each of the recipient's hooks matching the deposit. A hook is
+    /// told about a transfer once, even if a reorged deposit completes again.
+    async fn notify_deposit_hooks(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: &BridgeTransfer,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let hooks = state.deposit_hooks.get(&transfer.user).await?.unwrap_or_default();
+        let source_chain = transfer.corridor_chain()?;
+        for hook in hooks.iter().filter(|hook| hook.filter.matches(&transfer.asset, transfer.net_amount)) {
+            let delivery = (hook.id, transfer.id);
+            if state.hook_deliveries.contains_key(&delivery).await? {
+                continue;
+            }
+            state.hook_deliveries.insert(&delivery, ())?;
+            runtime
+                .prepare_message(Message::DepositCompleted {
+                    hook_id: hook.id,
+                    transfer_id: transfer.id,
+                    user: transfer.user,
+                    source_chain,
+                    asset: transfer.asset.clone(),
+                    amount: transfer.net_amount,
+                    source_tx_hash: transfer.source_tx_hash.clone(),
+                    completed_at: now,
+                })
+                .send_to(hook.application_chain);
+        }
+        Ok(())
+    }
+    
+    /// Moves a transfer to `Failed` with the error's code, and tells the user's chain.
+    fn fail_transfer(&mut self, runtime: &mut ContractRuntime<Self>, transfer: &mut BridgeTransfer, error: BridgeError) {
+        let failure = error.to_failure();
+        tracing::warn!("Transfer failed: transfer_id={}, code={:?}, error={}", transfer.id, failure.code, error);
+        transfer.status = TransferStatus::Failed;
+        transfer.error_message = Some(error.to_string());
+        transfer.failure = Some(failure.clone());
+        runtime
+            .prepare_message(Message::TransferUpdate {
+                transfer_id: transfer.id,
+                status: TransferStatus::Failed,
+                tx_hash: transfer.destination_tx_hash.clone(),
+                failure: Some(failure),
+            })
+            .send_to(transfer.user.chain_id);
+    }
+    
+    /// Moves an internally held balance between accounts. Accounts with reorg debt cannot send, and
+    /// the receiver's debt in the asset is repaid first, as with any credit.
+    async fn transfer_balance(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        from: Account,
+        to: Account,
+        asset: String,
+        amount: Amount,
     ) -> Result<(), BridgeError> {
-        let mut config = state.chain_configs.get(&chain.chain_id()).await?
-            .ok_or(BridgeError::ChainNotConfigured { chain })?;
+        if state.token_applications.contains_key(&asset).await? {
+            return Err(BridgeError::HeldByTokenApplication { asset });
+        }
+        state.ensure_no_debt(from).await?;
+        let balance = state.balances.get(&(from, asset.clone())).await?.unwrap_or_default();
+        if amount > balance {
+            return Err(BridgeError::InsufficientBalance { required: amount, available: balance });
+        }
+        state.set_balance(&(from, asset.clone()), balance, balance - amount).await?;
+        self.credit_balance(runtime, state, to, &asset, amount).await?;
         
-        if let Some(fee) = base_fee {
-            config.base_fee = fee;
+        tracing::info!("Balance transferred: from={:?}, to={:?}, asset={}, amount={}", from, to, asset, amount);
+        Ok(())
+    }
+    
+    /// Credits a user balance, repaying any outstanding debt in the asset first.
+    /// Assets with a token application are minted there instead of held internally.
+    async fn credit_balance(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        user: Account,
+        asset: &str,
+        amount: Amount,
+    ) -> Result<(), BridgeError> {
+        let balance_key = (user, asset.to_string());
+        let debt = state.debts.get(&balance_key).await?;
+        let (remaining_debt, credit) = match debt {
+            Some(debt) => repay_debt(debt, amount),
+            None => (Amount::ZERO, amount),
+        };
+        
+        // The token call goes first: if it fails nothing has been written yet
+        if let Some(token_app) = state.token_applications.get(&balance_key.1).await? {
+            if credit > Amount::ZERO {
+                self.call_token_application(
+                    runtime, token_app, asset, WrappedTokenOperation::Mint { target_account: user, amount: credit }
+                )?;
+            }
+        } else {
+            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
+            let new_balance = math::checked_add(current_balance, credit)?;
+            state.set_balance(&balance_key, current_balance, new_balance).await?;
         }
-        if let Some(bps) = fee_percentage_bps {
-            config.fee_percentage_bps = bps;
+        
+        if debt.is_some() {
+            if remaining_debt == Amount::ZERO {
+                state.debts.remove(&balance_key)?;
+                let count = state.indebted_accounts.get(&user).await?.unwrap_or_default();
+                if count <= 1 {
+                    state.indebted_accounts.remove(&user)?;
+                } else {
+                    state.indebted_accounts.insert(&user, count - 1)?;
+                }
+            } else {
+                state.debts.insert(&balance_key, remaining_debt)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    fn call_token_application(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        token_app: ApplicationId,
+        asset: &str,
+        operation: WrappedTokenOperation,
+    ) -> Result<(), BridgeError> {
+        let response = runtime.call_application(true, token_app.with_abi::<WrappedTokenAbi>(), &operation);
+        match response {
+            WrappedTokenResponse::Ok => Ok(()),
+            WrappedTokenResponse::Failed { reason } => Err(BridgeError::TokenApplicationFailed {
+                asset: asset.to_string(),
+                reason,
+            }),
+        }
+    }
+    
+    /// Books a collected fee, splitting off the insurance share. Returns the insurance share.
+    async fn collect_fee(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        asset: &str,
+        fee: Amount,
+    ) -> Result<Amount, BridgeError> {
+        let (protocol_share, insurance_share) = split_fee(fee, state.insurance_fee_bps.get())?;
+        let asset = asset.to_string();
+        
+        let current_fees = state.collected_fees.get(&asset).await?.unwrap_or_default();
+        state.collected_fees.insert(&asset, math::checked_add(current_fees, protocol_share)?)?;
+        if insurance_share > Amount::ZERO {
+            let fund = state.insurance_fund.get(&asset).await?.unwrap_or_default();
+            state.insurance_fund.insert(&asset, math::checked_add(fund, insurance_share)?)?;
+        }
+        Ok(insurance_share)
+    }
+    
+    /// Undoes `collect_fee` for a fee that turned out never to be earned. `insurance_share` is
+    /// what `collect_fee` returned, since the share may have changed since.
+    async fn reverse_fee(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        asset: &str,
+        fee: Amount,
+        insurance_share: Amount,
+    ) -> Result<(), BridgeError> {
+        let protocol_share = math::saturating_sub(fee, insurance_share);
+        let asset = asset.to_string();
+        
+        let current_fees = state.collected_fees.get(&asset).await?.unwrap_or_default();
+        state.collected_fees.insert(&asset, math::saturating_sub(current_fees, protocol_share))?;
+        let fund = state.insurance_fund.get(&asset).await?.unwrap_or_default();
+        state.insurance_fund.insert(&asset, math::saturating_sub(fund, insurance_share))?;
+        Ok(())
+    }
+    
+    async fn pay_insurance_claim(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        recipient: Account,
+        asset: String,
+        amount: Amount,
+        reference_transfer_id: TransferId,
+    ) -> Result<(), BridgeError> {
+        let admin = self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
+        
+        let transfer = state.transfers.get(&reference_transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id: reference_transfer_id })?;
+        if transfer.asset != asset {
+            return Err(BridgeError::InvalidConfig {
+                reason: format!("Transfer {} is not in {}", reference_transfer_id, asset),
+            });
+        }
+        
+        let fund = state.insurance_fund.get(&asset).await?.unwrap_or_default();
+        if fund < amount {
+            return Err(BridgeError::InsufficientBalance { required: amount, available: fund });
+        }
+        
+        self.credit_balance(runtime, state, recipient, &asset, amount).await?;
+        state.insurance_fund.insert(&asset, fund - amount)?;
+        
+        let payout_id = state.next_insurance_payout_id.get();
+        state.insurance_payouts.insert(&payout_id, InsurancePayout {
+            id: payout_id,
+            recipient,
+            asset: asset.clone(),
+            amount,
+            reference_transfer_id,
+            paid_by: admin,
+            paid_at: now,
+        })?;
+        state.next_insurance_payout_id.set(payout_id + 1);
+        
+        tracing::warn!(
+            "Insurance claim paid: id={}, recipient={:?}, asset={}, amount={}, transfer_id={}",
+            payout_id, recipient, asset, amount, reference_transfer_id
+        );
+        
+        Ok(())
+    }
+    
+    async fn record_reserves_snapshot(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+    ) -> Result<(), BridgeError> {
+        let admin = self.require_admin(runtime, state)?;
+        let report = state.proof_of_reserves().await?;
+        
+        let snapshot_id = state.next_reserves_snapshot_id.get();
+        state.reserves_snapshots.insert(&snapshot_id, ReservesSnapshot {
+            id: snapshot_id,
+            block_height: report.block_height,
+            hash: report.hash,
+            recorded_by: admin,
+            recorded_at: runtime.system_time(),
+        })?;
+        state.next_reserves_snapshot_id.set(snapshot_id + 1);
+        
+        tracing::info!(
+            "Reserves snapshot recorded: id={}, block_height={}, assets={}",
+            snapshot_id, report.block_height, report.assets.len()
+        );
+        Ok(())
+    }
+    
+    async fn withdraw_collected_fees(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        asset: String,
+        amount: Option<Amount>,
+    ) -> Result<(), BridgeError> {
+        self.require_admin(runtime, state)?;
+        let collector = state.fee_collector.get()
+            .ok_or(BridgeError::InvalidConfig { reason: "No fee collector set".to_string() })?;
+        
+        // Only protocol revenue is withdrawable; the insurance reserve is tracked separately
+        let available = state.collected_fees.get(&asset).await?.unwrap_or_default();
+        let amount = amount.unwrap_or(available);
+        if amount > available {
+            return Err(BridgeError::InsufficientBalance { required: amount, available });
+        }
+        
+        self.credit_balance(runtime, state, collector, &asset, amount).await?;
+        state.collected_fees.insert(&asset, available - amount)?;
+        
+        tracing::info!("Collected fees withdrawn: collector={:?}, asset={}, amount={}", collector, asset, amount);
+        
+        Ok(())
+    }
+    
+    async fn bond_relayer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        amount: Amount,
+    ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let asset = state.relayer_bond_asset.get().ok_or(BridgeError::RelayerBondsNotConfigured)?;
+        if state.token_applications.contains_key(&asset).await? {
+            return Err(BridgeError::HeldByTokenApplication { asset });
+        }
+        state.ensure_no_debt(relayer).await?;
+        
+        let balance_key = (relayer, asset);
+        let balance = state.balances.get(&balance_key).await?.unwrap_or_default();
+        if amount > balance {
+            return Err(BridgeError::InsufficientBalance { required: amount, available: balance });
+        }
+        state.set_balance(&balance_key, balance, balance - amount).await?;
+        
+        let mut bond = state.relayer_bonds.get(&relayer).await?.unwrap_or_default();
+        bond.bonded = math::checked_add(bond.bonded, amount)?;
+        let bonded = bond.bonded;
+        state.relayer_bonds.insert(&relayer, bond)?;
+        state.total_relayer_bonds.set(math::checked_add(state.total_relayer_bonds.get(), amount)?);
+        state.events.push_back(BridgeEvent::RelayerBonded { relayer, amount, bonded, timestamp: runtime.system_time() });
+        
+        tracing::info!("Relayer bonded: relayer={:?}, amount={}, bonded={}", relayer, amount, bonded);
+        Ok(())
+    }
+    
+    async fn unbond_relayer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        amount: Amount,
+    ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let now = runtime.system_time();
+        let unbonding_until = now + std::time::Duration::from_secs(state.relayer_unbonding_seconds.get());
+        
+        let mut bond = state.relayer_bonds.get(&relayer).await?.unwrap_or_default();
+        bond.unbond(amount, unbonding_until)
+            .map_err(|available| BridgeError::InsufficientBalance { required: amount, available })?;
+        state.relayer_bonds.insert(&relayer, bond)?;
+        state.events.push_back(BridgeEvent::RelayerUnbonding { relayer, amount, unbonding_until, timestamp: now });
+        
+        tracing::info!("Relayer unbonding: relayer={:?}, amount={}, until={:?}", relayer, amount, unbonding_until);
+        Ok(())
+    }
+    
+    async fn withdraw_relayer_bond(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+    ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let asset = state.relayer_bond_asset.get().ok_or(BridgeError::RelayerBondsNotConfigured)?;
+        
+        let mut bond = state.relayer_bonds.get(&relayer).await?.unwrap_or_default();
+        let amount = bond.withdrawable(runtime.system_time());
+        if amount == Amount::ZERO {
+            return match bond.unbonding_until {
+                Some(until) => Err(BridgeError::RelayerBondLocked { until }),
+                None => Err(BridgeError::InsufficientBalance { required: Amount::ZERO, available: Amount::ZERO }),
+            };
+        }
+        
+        self.credit_balance(runtime, state, relayer, &asset, amount).await?;
+        bond.unbonding = Amount::ZERO;
+        bond.unbonding_until = None;
+        if bond.is_empty() && bond.slashed == Amount::ZERO {
+            state.relayer_bonds.remove(&relayer)?;
+        } else {
+            state.relayer_bonds.insert(&relayer, bond)?;
+        }
+        state.total_relayer_bonds.set(math::saturating_sub(state.total_relayer_bonds.get(), amount));
+        
+        tracing::info!("Relayer bond withdrawn: relayer={:?}, asset={}, amount={}", relayer, asset, amount);
+        Ok(())
+    }
+    
+    /// Slashing is the admin's call: the bridge has no on-chain proof of a fraudulent report, so
+    /// it only checks that the relayer reported the transfer.
+    async fn slash_relayer(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        relayer: Account,
+        transfer_id: TransferId,
+        amount: Amount,
+        reason: String,
+    ) -> Result<(), BridgeError> {
+        let admin = self.require_admin(runtime, state)?;
+        let asset = state.relayer_bond_asset.get().ok_or(BridgeError::RelayerBondsNotConfigured)?;
+        
+        let transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        if transfer.reporter != Some(relayer) && transfer.relayer != Some(relayer) {
+            return Err(BridgeError::NotReportedByRelayer { transfer_id, relayer });
+        }
+        
+        let mut bond = state.relayer_bonds.get(&relayer).await?.unwrap_or_default();
+        let slashed = bond.slash(amount);
+        if slashed == Amount::ZERO {
+            return Err(BridgeError::InsufficientBalance { required: amount, available: Amount::ZERO });
+        }
+        state.relayer_bonds.insert(&relayer, bond)?;
+        state.total_relayer_bonds.set(math::saturating_sub(state.total_relayer_bonds.get(), slashed));
+        let fund = state.insurance_fund.get(&asset).await?.unwrap_or_default();
+        state.insurance_fund.insert(&asset, math::checked_add(fund, slashed)?)?;
+        
+        tracing::warn!(
+            "Relayer slashed: relayer={:?}, transfer_id={}, asset={}, amount={}, reason={}",
+            relayer, transfer_id, asset, slashed, reason
+        );
+        state.events.push_back(BridgeEvent::RelayerSlashed {
+            relayer,
+            transfer_id,
+            asset,
+            amount: slashed,
+            reason,
+            slashed_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    async fn claim_relayer_work(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+    ) -> Result<(), BridgeError> {
+        let relayer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        state.ensure_no_debt(relayer).await?;
+        let now = runtime.system_time();
+        
+        let transfer = state.transfers.get(&transfer_id).await?
+            .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+        if !transfer.status.is_open() {
+            return Err(BridgeError::InvalidStatus { status: transfer.status });
+        }
+        self.require_bonded_relayer(runtime, state, transfer.corridor_chain()?).await?;
+        
+        // The claimant may renew its own claim; others wait for it to lapse
+        if let Some(claim) = state.work_claims.get(&transfer_id).await? {
+            if claim.relayer != relayer && claim.is_live(now) {
+                return Err(BridgeError::WorkClaimed { transfer_id, relayer: claim.relayer, until: claim.until });
+            }
+        }
+        let until = now + std::time::Duration::from_secs(WORK_CLAIM_SECONDS);
+        state.work_claims.insert(&transfer_id, WorkClaim { relayer, until })?;
+        self.record_work(state, transfer_id, relayer, RelayerAction::SoftClaimed, false, now).await?;
+        
+        tracing::info!("Relayer work claimed: transfer_id={}, relayer={:?}, until={:?}", transfer_id, relayer, until);
+        Ok(())
+    }
+    
+    /// Appends a work receipt to the transfer and counts it for the relayer; a duplicate is also
+    /// reported as an event naming who performed the action first
+    async fn record_work(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer_id: TransferId,
+        relayer: Account,
+        action: RelayerAction,
+        duplicate: bool,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let mut receipts = state.work_receipts.get(&transfer_id).await?.unwrap_or_default();
+        if duplicate {
+            let first_by = receipts.iter()
+                .find(|receipt| receipt.action == action && !receipt.duplicate)
+                .map(|receipt| receipt.relayer);
+            state.events.push_back(BridgeEvent::DuplicateSubmission {
+                transfer_id,
+                relayer,
+                action,
+                first_by,
+                timestamp: now,
+            });
+            tracing::warn!(
+                "Duplicate relayer submission: transfer_id={}, relayer={:?}, action={:?}, first_by={:?}",
+                transfer_id, relayer, action, first_by
+            );
+        }
+        receipts.push(WorkReceipt { relayer, action, duplicate, at: now });
+        state.work_receipts.insert(&transfer_id, receipts)?;
+        
+        let mut stats = state.relayer_work_stats.get(&relayer).await?.unwrap_or_default();
+        stats.record(transfer_id, duplicate);
+        state.relayer_work_stats.insert(&relayer, stats)?;
+        Ok(())
+    }
+    
+    /// Returns the signer if it is the admin; open while no admin is set.
+    /// Stores the receipt for `client_request_id`, rejecting reuse of the id by the same caller.
+    async fn record_receipt(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        caller: Account,
+        client_request_id: Option<u64>,
+        id: u64,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let Some(client_request_id) = client_request_id else {
+            return Ok(());
+        };
+        let key = (caller, client_request_id);
+        if state.receipts.get(&key).await?.is_some() {
+            return Err(BridgeError::DuplicateClientRequest { client_request_id });
+        }
+        state.receipts.insert(&key, OperationReceipt { client_request_id, id, created_at: now })?;
+        Ok(())
+    }
+    
+    fn require_admin(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &BridgeState<ContractRuntime<Self>>,
+    ) -> Result<Account, BridgeError> {
+        let signer = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        match state.admin.get() {
+            Some(admin) if admin == signer => Ok(signer),
+            _ => Err(BridgeError::Unauthorized { reason: "Not bridge admin".to_string() }),
+        }
+    }
+    
+    /// Returns the signer reporting for `chain`, which must hold the chain's bond minimum if it
+    /// has one; chains without a minimum accept unsigned reports
+    async fn require_bonded_relayer(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+    ) -> Result<Option<Account>, BridgeError> {
+        let signer = runtime.authenticated_signer();
+        let Some(ChainBondMinimum { minimum, .. }) = state.relayer_bond_minimums.get(&chain.chain_id()).await? else {
+            return Ok(signer);
+        };
+        let relayer = signer.ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let bonded = state.relayer_bonds.get(&relayer).await?.unwrap_or_default().bonded;
+        if bonded < minimum {
+            return Err(BridgeError::RelayerBondInsufficient { relayer, bonded, minimum });
+        }
+        Ok(Some(relayer))
+    }
+    
+    /// Stores the transfer, keeping the open transfer counters behind `GetBridgeOverview` and the
+    /// liability totals behind `GetProofOfReserves` in step; closing it drops any soft claim
+    async fn save_transfer(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        transfer: BridgeTransfer,
+    ) -> Result<(), BridgeError> {
+        let previous_transfer = state.transfers.get(&transfer.id).await?;
+        self.track_liabilities(state, previous_transfer.as_ref(), &transfer).await?;
+        let previous = previous_transfer
+            .map(|previous| previous.status)
+            .filter(TransferStatus::is_open);
+        let current = Some(transfer.status).filter(TransferStatus::is_open);
+        let transfer_id = transfer.id;
+        
+        if previous != current {
+            let chain_id = transfer.corridor_chain()?.chain_id();
+            let mut counts = state.open_transfer_counts.get(&chain_id).await?.unwrap_or_default();
+            if let Some(status) = previous {
+                if let Some(count) = counts.get_mut(&status) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        counts.remove(&status);
+                    }
+                }
+            }
+            if let Some(status) = current {
+                *counts.entry(status).or_insert(0) += 1;
+            }
+            state.open_transfer_counts.insert(&chain_id, counts)?;
+        }
+        state.transfers.insert(&transfer_id, transfer)?;
+        
+        let oldest = state.oldest_open_transfer.get();
+        if current.is_some() && oldest.map_or(true, |oldest| transfer_id < oldest) {
+            state.oldest_open_transfer.set(Some(transfer_id));
+        } else if current.is_none() && oldest == Some(transfer_id) {
+            // Move on to the next open transfer
+            let next_transfer_id = state.next_transfer_id.get();
+            let mut next_open = None;
+            for candidate in transfer_id + 1..next_transfer_id {
+                if state.transfers.get(&candidate).await?.is_some_and(|transfer| transfer.status.is_open()) {
+                    next_open = Some(candidate);
+                    break;
+                }
+            }
+            state.oldest_open_transfer.set(next_open);
+        }
+        if current.is_none() {
+            state.work_claims.remove(&transfer_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Moves a transfer's amounts in and out of the in-flight and bridged supply totals as its
+    /// status changes
+    async fn track_liabilities(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        previous: Option<&BridgeTransfer>,
+        transfer: &BridgeTransfer,
+    ) -> Result<(), BridgeError> {
+        let in_flight = |transfer: &BridgeTransfer| {
+            transfer.direction == TransferDirection::Outbound
+                && matches!(transfer.status, TransferStatus::Approved | TransferStatus::Executing)
+        };
+        let was_in_flight = previous.is_some_and(in_flight);
+        if was_in_flight != in_flight(transfer) {
+            let total = state.in_flight_outbound.get(&transfer.asset).await?.unwrap_or_default();
+            let total = if was_in_flight {
+                math::saturating_sub(total, transfer.net_amount)
+            } else {
+                math::checked_add(total, transfer.net_amount)?
+            };
+            state.in_flight_outbound.insert(&transfer.asset, total)?;
+        }
+        
+        // Deposits lock their full amount on the source chain; withdrawals release their net amount
+        let was_completed = previous.is_some_and(|previous| previous.status == TransferStatus::Completed);
+        if was_completed != (transfer.status == TransferStatus::Completed) {
+            let (locked, released) = match transfer.direction {
+                TransferDirection::Inbound => (transfer.amount, Amount::ZERO),
+                TransferDirection::Outbound => (Amount::ZERO, transfer.net_amount),
+            };
+            let (added, removed) = if was_completed { (released, locked) } else { (locked, released) };
+            let supply = state.bridged_supply.get(&transfer.asset).await?.unwrap_or_default();
+            let supply = math::saturating_sub(math::checked_add(supply, added)?, removed);
+            state.bridged_supply.insert(&transfer.asset, supply)?;
+        }
+        Ok(())
+    }
+    
+    async fn record_corridor(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        asset: &str,
+        direction: TransferDirection,
+        now: Timestamp,
+        event: CorridorEvent,
+    ) -> Result<(), BridgeError> {
+        let key = (chain.chain_id(), asset.to_string(), day_bucket(now));
+        let mut corridor = state.corridor_stats.get(&key).await?.unwrap_or_default();
+        let stats = corridor.direction_mut(direction);
+        
+        match event {
+            CorridorEvent::Created { volume, fee } => {
+                stats.transfers += 1;
+                stats.volume = stats.volume.saturating_add(volume);
+                stats.fees = stats.fees.saturating_add(fee);
+                
+                let mut hourly = state.hourly_volume.get(&chain.chain_id()).await?.unwrap_or_default();
+                hourly.record(hour_bucket(now), direction, asset, volume);
+                state.hourly_volume.insert(&chain.chain_id(), hourly)?;
+            }
+            CorridorEvent::Completed { fee, completion_seconds } => {
+                stats.completed += 1;
+                stats.fees = stats.fees.saturating_add(fee);
+                stats.total_completion_seconds += completion_seconds;
+            }
+            CorridorEvent::Failed => {
+                stats.failed += 1;
+            }
+        }
+        
+        state.corridor_stats.insert(&key, corridor)?;
+        Ok(())
+    }
+    
+    async fn prune_corridor_stats(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        before_day: u64,
+    ) -> Result<(), BridgeError> {
+        let mut pruned = 0;
+        for key in state.corridor_stats.indices().await? {
+            if key.2 < before_day {
+                state.corridor_stats.remove(&key)?;
+                pruned += 1;
+            }
         }
         
-        state.chain_configs.insert(&chain.chain_id(), config)?;
+        tracing::info!("Pruned {} corridor stats buckets before day {}", pruned, before_day);
+        
+        Ok(())
+    }
+    
+    async fn flag_validator(
+        &mut self,
+        state: &mut BridgeState<ContractRuntime<Self>>,
+        validator: Account,
+        chain: ExternalChain,
+        height: u64,
+        attested_hash: BlockHash,
+        finalized_hash: BlockHash,
+        now: Timestamp,
+    ) -> Result<(), BridgeError> {
+        let mut flags = state.flagged_validators.get(&validator).await?.unwrap_or_default();
+        flags.push(ConflictingAttestation {
+            chain,
+            height,
+            attested_hash,
+            finalized_hash,
+            flagged_at: now,
+        });
+        state.flagged_validators.insert(&validator, flags)?;
         
-        tracing::info!("Fees updated for chain {:?}", chain);
+        tracing::warn!(
+            "Validator flagged for conflicting attestation: validator={:?}, chain={:?}, height={}",
+            validator, chain, height
+        );
         
         Ok(())
     }
     
+    async fn require_active_validator(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &BridgeState<ContractRuntime<Self>>,
+    ) -> Result<Account, BridgeError> {
+        let validator = runtime.authenticated_signer()
+            .ok_or(BridgeError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        
+        let validator_config = state.validators.get(&validator).await?
+            .ok_or(BridgeError::ValidatorNotFound { address: validator })?;
+        
+        if !validator_config.is_active {
+            return Err(BridgeError::Unauthorized { reason: "Validator is not active".to_string() });
+        }
+        
+        Ok(validator)
+    }
+    
+    /// Account holding deposits that name no recipient until they are claimed
+    fn unclaimed_placeholder(runtime: &mut ContractRuntime<Self>) -> Account {
+        Account::chain(runtime.chain_id())
+    }
+    
+    async fn can_receive_deposit(
+        &self,
+        state: &BridgeState<ContractRuntime<Self>>,
+        recipient: Account,
+        placeholder: Account,
+    ) -> Result<bool, BridgeError> {
+        Ok(recipient != placeholder && !state.quarantined_accounts.contains_key(&recipient).await?)
+    }
+    
+    /// Scheme the source address owner signs claim challenges with, if the chain has one
+    async fn ownership_scheme(
+        &self,
+        state: &BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+    ) -> Result<Option<SignatureScheme>, BridgeError> {
+        Ok(match state.address_format(chain).await? {
+            AddressFormat::Evm => Some(SignatureScheme::Eip191),
+            AddressFormat::Opaque if chain == ExternalChain::Solana => Some(SignatureScheme::Ed25519),
+            _ => None,
+        })
+    }
+    
     async fn calculate_approval_threshold(
         &self,
         state: &BridgeState<ContractRuntime<Self>>,
@@ -1266,6 +5842,265 @@ impl BridgeContract {
         let threshold_percentage = state.approval_threshold_percentage.get();
         Ok((total_weight * threshold_percentage) / 100)
     }
+    
+    /// Requirement of the approval tier the normalized value of `amounts` falls in on `chain`;
+    /// None without tiers
+    async fn tier_requirement<'a>(
+        &self,
+        state: &BridgeState<ContractRuntime<Self>>,
+        chain: ExternalChain,
+        amounts: impl IntoIterator<Item = (&'a str, Amount)>,
+    ) -> Result<Option<ApprovalRequirement>, BridgeError> {
+        Ok(state.approval_tiers.get(&chain.chain_id()).await?
+            .and_then(|tiers| tiers.requirement_for(amounts).cloned()))
+    }
+    
+    /// A snapshotted requirement, or the bridge-wide percentage in its absence
+    fn approval_requirement(
+        &self,
+        state: &BridgeState<ContractRuntime<Self>>,
+        snapshot: Option<&ApprovalRequirement>,
+    ) -> ApprovalRequirement {
+        snapshot.cloned().unwrap_or_else(|| ApprovalRequirement {
+            threshold_percentage: state.approval_threshold_percentage.get(),
+            senior_validators: Vec::new(),
+            min_senior_approvals: 0,
+        })
+    }
+}
+
+/// Debits `amount` from `balance`, returning the new balance and the unpaid shortfall.
+pub fn clawback(balance: Amount, amount: Amount) -> (Amount, Amount) {
+    if balance >= amount {
+        (balance - amount, Amount::ZERO)
+    } else {
+        (Amount::ZERO, amount - balance)
+    }
+}
+
+/// Splits a fee into (protocol, insurance) shares; rounding favours the protocol share.
+pub fn split_fee(fee: Amount, insurance_bps: u64) -> Result<(Amount, Amount), MathError> {
+    let insurance = math::amount_fee(fee, insurance_bps)?;
+    Ok((math::checked_sub(fee, insurance)?, insurance))
+}
+
+/// Applies `credit` to `debt`, returning the remaining debt and the credit left over.
+pub fn repay_debt(debt: Amount, credit: Amount) -> (Amount, Amount) {
+    if credit >= debt {
+        (Amount::ZERO, credit - debt)
+    } else {
+        (debt - credit, Amount::ZERO)
+    }
+}
+
+/// Query types for Service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Query {
+    GetTransfer { transfer_id: TransferId },
+    /// What `CancelWithdrawal` would refund now; None if the transfer cannot be cancelled
+    GetCancellationRefund { transfer_id: TransferId },
+    GetBatch { batch_id: u64 },
+    /// Batch currently accepting withdrawals to `chain`
+    GetOpenBatch { chain: ExternalChain },
+    GetReceipt { account: Account, client_request_id: u64 },
+    GetBalance { account: Account, asset: String },
+    /// Individual approvals of a transfer still awaiting a terminal state
+    GetTransferApprovals { transfer_id: TransferId },
+    GetStats,
+    /// Approval metrics of every validator with any, since the last reset
+    GetValidatorPerformance,
+    GetLatestFinalizedHeight { chain: ExternalChain },
+    GetFinalizedBlock { chain: ExternalChain, height: u64 },
+    /// Attestations by `validator` that conflicted with a finalized block hash
+    GetValidatorFlags { validator: Account },
+    GetDebt { account: Account, asset: String },
+    GetCustomChain { chain_id: u64 },
+    GetDepositHooks { account: Account },
+    /// Chain config at a given version; None for the current one
+    GetChainConfig { chain: ExternalChain, version: Option<u64> },
+    /// Bridge contracts accepting deposits at time `at`, primary first
+    GetAcceptedAddresses { chain: ExternalChain, at: Timestamp },
+    EstimateFee {
+        chain: ExternalChain,
+        amount: Amount,
+        direction: TransferDirection,
+        /// Quote under this account's negotiated fees, if it has any
+        #[serde(default)]
+        account: Option<Account>,
+        /// Deposited asset, whose confirmation tiers then apply to the finality estimate
+        #[serde(default)]
+        asset: Option<String>,
+    },
+    GetConfirmationOverride { chain: ExternalChain, asset: String },
+    /// Approval tiers of `chain`; None while the bridge-wide percentage applies
+    GetApprovalTiers { chain: ExternalChain },
+    /// Dry run of `InitiateWithdrawal` by `account` at time `at`, through the same checks
+    ValidateWithdrawal {
+        chain: ExternalChain,
+        asset: String,
+        amount: Amount,
+        destination_address: String,
+        account: Account,
+        #[serde(default)]
+        memo: Option<String>,
+        #[serde(default)]
+        fee_voucher: Option<String>,
+        at: Timestamp,
+    },
+    GetRelayerFees { relayer: Account, asset: String },
+    /// Message to sign for `ClaimDeposit`
+    GetClaimChallenge { transfer_id: TransferId, claimer: Account },
+    /// Payload validators sign for `ApproveTransfer`
+    GetApprovalPayload { transfer_id: TransferId },
+    /// Payload and quorum signatures of an approved withdrawal, for the destination chain's
+    /// verifier; the validators that reached quorum, whatever the validator set is now
+    GetApprovalBundle { transfer_id: TransferId },
+    /// Up to `limit` transfers after `cursor`, in id order, still short of quorum that `validator` has not signed
+    GetPendingApprovals { validator: Account, cursor: Option<TransferId>, limit: usize },
+    GetUnclaimedDeposits,
+    /// Deposits that expired before finality and await their external refund
+    GetAbandonedDeposits,
+    /// Most recent monitoring events
+    GetEvents { count: usize },
+    /// Quarantined and frozen deposits with their release times
+    GetQuarantinedTransfers,
+    GetCollectedFees { asset: String },
+    /// What `spender` may still move of the owner's asset with `TransferFrom`
+    GetAllowance { owner: Account, spender: ApplicationId, asset: String },
+    GetInsuranceFund { asset: String },
+    /// Payout history, optionally filtered by asset
+    GetInsurancePayouts { asset: Option<String> },
+    GetCorridorStats {
+        chain: Option<ExternalChain>,
+        asset: Option<String>,
+        from_day: u64,
+        to_day: u64,
+    },
+    /// Operator dashboard in one call, built from counters; ages and the volume window are taken at `at`
+    GetBridgeOverview { at: Timestamp },
+    /// A relayer's bond, what of it is withdrawable at `at`, and the chains it may report for
+    GetRelayerBond { relayer: Account, at: Timestamp },
+    /// Open transfers waiting on a relayer, in id order, that fall to shard `relayer_index` of
+    /// `relayer_count` by transfer id modulo the count, with soft claims live at `at`. Deposits
+    /// nobody has reported yet cannot be listed: finalized blocks are only recorded by hash
+    GetRelayerWork {
+        chain: Option<ExternalChain>,
+        relayer_count: u64,
+        relayer_index: u64,
+        at: Timestamp,
+    },
+    /// Which relayer performed each recorded action on a transfer, in order
+    GetWorkReceipts { transfer_id: TransferId },
+    GetRelayerWorkStats { relayer: Account },
+    /// A chain's processing windows, and when a withdrawal initiated at `at` would enter approval
+    GetProcessingWindows { chain: ExternalChain, at: Timestamp },
+    /// Liabilities per asset as of the last executed block, with the report hash
+    GetProofOfReserves,
+    /// Recorded report hashes, oldest first
+    GetReservesSnapshots,
+    /// Availability of `chain` at `at`, and the last one announced to corridor consumers
+    GetCorridorStatus { chain: ExternalChain, at: Timestamp },
+    /// Chains told about corridor availability changes
+    GetCorridorConsumers,
+    /// What a validator daemon checks before signing `transfer_id`: the payload to sign, the
+    /// transfer's consistency checks at `at`, and whether `validator` may approve it
+    ValidateForApproval { transfer_id: TransferId, validator: Account, at: Timestamp },
+}
+
+/// Query response type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueryResponse {
+    Transfer(Option<BridgeTransfer>),
+    CancellationRefund(Option<CancellationRefund>),
+    Batch(Option<WithdrawalBatch>),
+    Receipt(Option<OperationReceipt>),
+    Balance(Amount),
+    TransferApprovals { approvals: Vec<ValidatorApproval>, weight: u32 },
+    Stats(BridgeStats),
+    ValidatorPerformance { since: Timestamp, validators: Vec<(Account, ValidatorPerformance)> },
+    LatestFinalizedHeight(Option<u64>),
+    FinalizedBlock(Option<BlockHash>),
+    ValidatorFlags(Vec<ConflictingAttestation>),
+    Debt(Amount),
+    CustomChain(Option<CustomChainInfo>),
+    DepositHooks(Vec<DepositHook>),
+    FeeEstimate {
+        fees: FeeBreakdown,
+        config_version: u64,
+        /// Negotiated terms applied; the quote only holds before they expire
+        fee_override: Option<FeeOverride>,
+        /// Finality rule a deposit reported now would wait for
+        finality: FinalityProfile,
+        expected_wait_seconds: u64,
+        /// How long a transfer created now may stay pending before it expires
+        expiry_seconds: u64,
+    },
+    /// The quote the withdrawal would be charged, or every check it fails
+    WithdrawalValidation(Result<WithdrawalQuote, Vec<WithdrawalIssue>>),
+    ChainConfig(Option<ChainConfig>),
+    ConfirmationOverride(Option<ConfirmationOverride>),
+    ApprovalTiers(Option<ApprovalTiers>),
+    AcceptedAddresses(Vec<String>),
+    RelayerFees(Amount),
+    ClaimChallenge(Vec<u8>),
+    ApprovalPayload(Vec<u8>),
+    ApprovalBundle(ApprovalBundle),
+    PendingApprovals {
+        approvals: Vec<PendingApproval>,
+        /// Pass back as `cursor` for the next page; None once the queue is exhausted
+        next_cursor: Option<TransferId>,
+    },
+    UnclaimedDeposits(Vec<TransferId>),
+    AbandonedDeposits(Vec<TransferId>),
+    Events(Vec<BridgeEvent>),
+    QuarantinedTransfers(Vec<BridgeTransfer>),
+    CollectedFees(Amount),
+    Allowance(Amount),
+    InsuranceFund { balance: Amount, fee_bps: u64 },
+    InsurancePayouts(Vec<InsurancePayout>),
+    CorridorStats {
+        buckets: Vec<((u64, String, u64), CorridorStats)>,
+        summary: CorridorStats,
+    },
+    BridgeOverview(BridgeOverview),
+    RelayerBond(RelayerBondStatus),
+    RelayerWork(Vec<RelayerWorkItem>),
+    WorkReceipts(Vec<WorkReceipt>),
+    RelayerWorkStats(RelayerWorkStats),
+    ProcessingWindows(ProcessingWindowStatus),
+    ProofOfReserves(ProofOfReserves),
+    ReservesSnapshots(Vec<ReservesSnapshot>),
+    ApprovalPrevalidation(ApprovalPrevalidation),
+    CorridorStatus {
+        availability: CorridorAvailability,
+        /// None while the corridor was last announced open
+        announced: Option<CorridorStatus>,
+    },
+    CorridorConsumers(Vec<ChainId>),
+    Error(String),
+}
+
+/// Bridge application ABI
+pub struct BridgeAbi;
+
+impl ContractAbi for BridgeAbi {
+    type Operation = Operation;
+    type Response = BridgeResponse;
+}
+
+/// Result of an operation, returned to applications calling this one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeResponse {
+    Ok,
+    /// Answer to `BalanceOf`
+    Balance(Amount),
+    /// Answer to `CorridorAvailabilityOf`
+    CorridorAvailability(CorridorAvailability),
+}
+
+impl ServiceAbi for BridgeAbi {
+    type Query = Query;
+    type QueryResponse = QueryResponse;
 }
 
 /// Service for queries
@@ -1281,7 +6116,392 @@ impl Service for BridgeService {
     }
 
     async fn handle_query(&mut self, state: &Self::State, query: &[u8]) -> Vec<u8> {
-        serde_json::to_vec(&"Bridge query handled").unwrap_or_default()
+        let response = match serde_json::from_slice::<Query>(query) {
+            Ok(query) => self.query(state, query).await.unwrap_or_else(|e| QueryResponse::Error(e.to_string())),
+            Err(e) => QueryResponse::Error(e.to_string()),
+        };
+        serde_json::to_vec(&response).unwrap_or_default()
+    }
+}
+
+impl BridgeService {
+    async fn query(
+        &self,
+        state: &BridgeState<ServiceRuntime<Self>>,
+        query: Query,
+    ) -> Result<QueryResponse, BridgeError> {
+        match query {
+            Query::GetTransfer { transfer_id } => {
+                Ok(QueryResponse::Transfer(state.transfers.get(&transfer_id).await?))
+            }
+            Query::GetCancellationRefund { transfer_id } => {
+                let transfer = state.transfers.get(&transfer_id).await?;
+                Ok(QueryResponse::CancellationRefund(transfer.and_then(|transfer| transfer.cancellation_refund())))
+            }
+            Query::GetBatch { batch_id } => {
+                Ok(QueryResponse::Batch(state.batches.get(&batch_id).await?))
+            }
+            Query::GetOpenBatch { chain } => {
+                let batch = match state.open_batches.get(&chain.chain_id()).await? {
+                    Some(batch_id) => state.batches.get(&batch_id).await?,
+                    None => None,
+                };
+                Ok(QueryResponse::Batch(batch))
+            }
+            Query::GetReceipt { account, client_request_id } => {
+                Ok(QueryResponse::Receipt(state.receipts.get(&(account, client_request_id)).await?))
+            }
+            Query::GetBalance { account, asset } => {
+                Ok(QueryResponse::Balance(state.balances.get(&(account, asset)).await?.unwrap_or_default()))
+            }
+            Query::GetTransferApprovals { transfer_id } => {
+                let mut approvals = Vec::new();
+                for validator in state.validators.indices().await? {
+                    if let Some(approval) = state.transfer_approvals.get(&(transfer_id, validator)).await? {
+                        approvals.push(approval);
+                    }
+                }
+                let (weight, _) = state.approval_weights.get(&transfer_id).await?.unwrap_or_default();
+                Ok(QueryResponse::TransferApprovals { approvals, weight })
+            }
+            Query::GetStats => Ok(QueryResponse::Stats(state.stats.get())),
+            Query::GetValidatorPerformance => {
+                let mut validators = Vec::new();
+                state.validator_performance.for_each_index_value(|validator, performance| {
+                    validators.push((validator, performance));
+                    Ok(())
+                }).await?;
+                Ok(QueryResponse::ValidatorPerformance { since: state.performance_since.get(), validators })
+            }
+            Query::GetLatestFinalizedHeight { chain } => {
+                Ok(QueryResponse::LatestFinalizedHeight(
+                    state.latest_finalized_height.get(&chain.chain_id()).await?,
+                ))
+            }
+            Query::GetFinalizedBlock { chain, height } => {
+                Ok(QueryResponse::FinalizedBlock(
+                    state.finalized_blocks.get(&(chain.chain_id(), height)).await?,
+                ))
+            }
+            Query::GetValidatorFlags { validator } => {
+                Ok(QueryResponse::ValidatorFlags(
+                    state.flagged_validators.get(&validator).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetDebt { account, asset } => {
+                Ok(QueryResponse::Debt(state.debts.get(&(account, asset)).await?.unwrap_or_default()))
+            }
+            Query::GetChainConfig { chain, version } => {
+                let config = match version {
+                    Some(version) => state.chain_config_at(chain.chain_id(), version).await?,
+                    None => state.chain_configs.get(&chain.chain_id()).await?,
+                };
+                Ok(QueryResponse::ChainConfig(config))
+            }
+            Query::GetConfirmationOverride { chain, asset } => {
+                Ok(QueryResponse::ConfirmationOverride(
+                    state.confirmation_overrides.get(&(chain.chain_id(), asset)).await?,
+                ))
+            }
+            Query::GetApprovalTiers { chain } => {
+                Ok(QueryResponse::ApprovalTiers(state.approval_tiers.get(&chain.chain_id()).await?))
+            }
+            Query::GetAcceptedAddresses { chain, at } => {
+                let config = state.chain_configs.get(&chain.chain_id()).await?
+                    .ok_or(BridgeError::ChainNotConfigured { chain })?;
+                Ok(QueryResponse::AcceptedAddresses(config.accepted_addresses(at)))
+            }
+            Query::GetCustomChain { chain_id } => {
+                Ok(QueryResponse::CustomChain(state.custom_chains.get(&chain_id).await?))
+            }
+            Query::GetDepositHooks { account } => {
+                Ok(QueryResponse::DepositHooks(state.deposit_hooks.get(&account).await?.unwrap_or_default()))
+            }
+            Query::EstimateFee { chain, amount, direction, account, asset } => {
+                let config = state.chain_configs.get(&chain.chain_id()).await?
+                    .ok_or(BridgeError::ChainNotConfigured { chain })?;
+                let fee_override = match account {
+                    Some(account) => state.fee_overrides.get(&FeeOverrideKey::Account(account)).await?,
+                    None => None,
+                };
+                let fees = match &fee_override {
+                    Some(terms) => terms.fee_breakdown(&config, amount, direction)?,
+                    None => config.fee_breakdown(amount, direction)?,
+                };
+                let finality = match &asset {
+                    Some(asset) => state.deposit_finality(chain, &config, asset, amount).await?,
+                    None => state.finality_profile(chain, &config).await?,
+                };
+                Ok(QueryResponse::FeeEstimate {
+                    fees,
+                    config_version: config.version,
+                    fee_override,
+                    finality,
+                    expected_wait_seconds: finality.expected_wait_seconds(chain),
+                    expiry_seconds: config.expiry_seconds(direction),
+                })
+            }
+            Query::ValidateWithdrawal { chain, asset, amount, destination_address, account, memo, fee_voucher, at } => {
+                let request = WithdrawalRequest { account, chain, destination_address, asset, amount, memo, fee_voucher };
+                let context = state.withdrawal_context(&request, at).await?;
+                Ok(QueryResponse::WithdrawalValidation(validate_withdrawal(&request, &context)))
+            }
+            Query::GetRelayerFees { relayer, asset } => {
+                Ok(QueryResponse::RelayerFees(
+                    state.relayer_fees.get(&(relayer, asset)).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetClaimChallenge { transfer_id, claimer } => {
+                let transfer = state.transfers.get(&transfer_id).await?
+                    .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                Ok(QueryResponse::ClaimChallenge(claim_challenge(&transfer, &claimer)))
+            }
+            Query::GetApprovalPayload { transfer_id } => {
+                let transfer = state.transfers.get(&transfer_id).await?
+                    .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                Ok(QueryResponse::ApprovalPayload(transfer.approval_payload()?))
+            }
+            Query::GetApprovalBundle { transfer_id } => {
+                let transfer = state.transfers.get(&transfer_id).await?
+                    .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                let unavailable = |reason: &str| BridgeError::ApprovalBundleUnavailable {
+                    transfer_id,
+                    reason: reason.to_string(),
+                };
+                if !matches!(
+                    transfer.status,
+                    TransferStatus::Approved | TransferStatus::Executing | TransferStatus::Completed
+                ) {
+                    return Err(unavailable(&format!("status is {:?}", transfer.status)));
+                }
+                let payload = transfer.approval_payload()?;
+                let bundle = state.approval_snapshots.get(&transfer_id).await?
+                    .and_then(|snapshot| snapshot.bundle(transfer_id, payload))
+                    .ok_or_else(|| unavailable("not approved by validator quorum"))?;
+                Ok(QueryResponse::ApprovalBundle(bundle))
+            }
+            Query::GetPendingApprovals { validator, cursor, limit } => {
+                let mut transfer_ids = state.awaiting_approval.indices().await?;
+                transfer_ids.sort_unstable();
+                
+                let mut approvals: Vec<PendingApproval> = Vec::new();
+                let mut next_cursor = None;
+                for transfer_id in transfer_ids.into_iter().filter(|id| cursor.map_or(true, |cursor| *id > cursor)) {
+                    if approvals.len() == limit {
+                        next_cursor = approvals.last().map(|approval| approval.transfer.id).or(cursor);
+                        break;
+                    }
+                    if state.transfer_approvals.contains_key(&(transfer_id, validator)).await? {
+                        continue;
+                    }
+                    let transfer = state.transfers.get(&transfer_id).await?
+                        .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                    approvals.push(PendingApproval { payload: transfer.approval_payload()?, transfer });
+                }
+                Ok(QueryResponse::PendingApprovals { approvals, next_cursor })
+            }
+            Query::GetUnclaimedDeposits => {
+                Ok(QueryResponse::UnclaimedDeposits(state.unclaimed_deposits.indices().await?))
+            }
+            Query::GetAbandonedDeposits => {
+                Ok(QueryResponse::AbandonedDeposits(state.abandoned_deposits.indices().await?))
+            }
+            Query::GetEvents { count } => {
+                Ok(QueryResponse::Events(state.events.read_back(count).await?))
+            }
+            Query::GetQuarantinedTransfers => {
+                let mut transfers = Vec::new();
+                for transfer_id in state.quarantined_transfers.indices().await? {
+                    if let Some(transfer) = state.transfers.get(&transfer_id).await? {
+                        transfers.push(transfer);
+                    }
+                }
+                Ok(QueryResponse::QuarantinedTransfers(transfers))
+            }
+            Query::GetCollectedFees { asset } => {
+                Ok(QueryResponse::CollectedFees(state.collected_fees.get(&asset).await?.unwrap_or_default()))
+            }
+            Query::GetAllowance { owner, spender, asset } => {
+                Ok(QueryResponse::Allowance(state.allowances.get(&(owner, spender, asset)).await?.unwrap_or_default()))
+            }
+            Query::GetInsuranceFund { asset } => {
+                Ok(QueryResponse::InsuranceFund {
+                    balance: state.insurance_fund.get(&asset).await?.unwrap_or_default(),
+                    fee_bps: state.insurance_fee_bps.get(),
+                })
+            }
+            Query::GetInsurancePayouts { asset } => {
+                let mut payouts = Vec::new();
+                state.insurance_payouts.for_each_index_value(|_, payout| {
+                    if asset.as_ref().map_or(true, |a| *a == payout.asset) {
+                        payouts.push(payout);
+                    }
+                    Ok(())
+                }).await?;
+                Ok(QueryResponse::InsurancePayouts(payouts))
+            }
+            Query::GetCorridorStats { chain, asset, from_day, to_day } => {
+                let mut buckets = Vec::new();
+                let mut summary = CorridorStats::default();
+                state.corridor_stats.for_each_index_value(|key, value| {
+                    let (chain_id, bucket_asset, day) = &key;
+                    let matches = chain.map_or(true, |c| c.chain_id() == *chain_id)
+                        && asset.as_ref().map_or(true, |a| a == bucket_asset)
+                        && (from_day..=to_day).contains(day);
+                    if matches {
+                        summary.merge(&value);
+                        buckets.push((key, value));
+                    }
+                    Ok(())
+                }).await?;
+                Ok(QueryResponse::CorridorStats { buckets, summary })
+            }
+            Query::GetBridgeOverview { at } => {
+                let hour = hour_bucket(at);
+                let mut chains = Vec::new();
+                let mut open_transfers = BTreeMap::new();
+                for chain_id in state.chain_configs.indices().await? {
+                    let Some(config) = state.chain_configs.get(&chain_id).await? else { continue };
+                    let chain_open = state.open_transfer_counts.get(&chain_id).await?.unwrap_or_default();
+                    for (status, count) in &chain_open {
+                        *open_transfers.entry(*status).or_insert(0) += count;
+                    }
+                    chains.push(ChainOverview {
+                        chain: config.chain,
+                        enabled: config.is_enabled,
+                        open_transfers: chain_open,
+                        volume_24h: state.hourly_volume.get(&chain_id).await?.unwrap_or_default().window(hour),
+                    });
+                }
+                
+                let oldest_open_transfer = match state.oldest_open_transfer.get() {
+                    Some(transfer_id) => state.transfers.get(&transfer_id).await?.map(|transfer| OldestOpenTransfer {
+                        transfer_id,
+                        created_at: transfer.created_at,
+                        age_seconds: at.micros().saturating_sub(transfer.created_at.micros()) / 1_000_000,
+                    }),
+                    None => None,
+                };
+                
+                let mut collected_fees = BTreeMap::new();
+                state.collected_fees.for_each_index_value(|asset, amount| {
+                    collected_fees.insert(asset, amount);
+                    Ok(())
+                }).await?;
+                let mut insurance_fund = BTreeMap::new();
+                state.insurance_fund.for_each_index_value(|asset, amount| {
+                    insurance_fund.insert(asset, amount);
+                    Ok(())
+                }).await?;
+                
+                Ok(QueryResponse::BridgeOverview(BridgeOverview {
+                    paused: state.is_paused.get(),
+                    chains,
+                    validators: ValidatorSummary {
+                        count: state.validator_count.get(),
+                        inactive: state.inactive_validator_count.get(),
+                        total_weight: state.total_validator_weight.get(),
+                    },
+                    open_transfers,
+                    oldest_open_transfer,
+                    collected_fees,
+                    insurance_fund,
+                }))
+            }
+            Query::GetRelayerBond { relayer, at } => {
+                let bond = state.relayer_bonds.get(&relayer).await?.unwrap_or_default();
+                let mut chains = Vec::new();
+                state.relayer_bond_minimums.for_each_index_value(|_, ChainBondMinimum { chain, minimum }| {
+                    chains.push(RelayerChainStatus { chain, minimum, active: bond.bonded >= minimum });
+                    Ok(())
+                }).await?;
+                Ok(QueryResponse::RelayerBond(RelayerBondStatus {
+                    asset: state.relayer_bond_asset.get(),
+                    withdrawable: bond.withdrawable(at),
+                    bond,
+                    chains,
+                }))
+            }
+            Query::GetRelayerWork { chain, relayer_count, relayer_index, at } => {
+                let mut transfer_ids = state.active_transfers.indices().await?;
+                transfer_ids.sort_unstable();
+                
+                let mut work = Vec::new();
+                for transfer_id in transfer_ids.into_iter().filter(|id| in_shard(*id, relayer_count, relayer_index)) {
+                    let Some(transfer) = state.transfers.get(&transfer_id).await? else { continue };
+                    let transfer_chain = transfer.corridor_chain()?;
+                    if chain.is_some_and(|chain| chain != transfer_chain) {
+                        continue;
+                    }
+                    let task = match (transfer.direction, transfer.status) {
+                        (TransferDirection::Inbound, TransferStatus::Pending | TransferStatus::Confirming) => {
+                            RelayerTask::ConfirmDeposit
+                        }
+                        (TransferDirection::Outbound, TransferStatus::Approved) => RelayerTask::ExecuteWithdrawal,
+                        (TransferDirection::Outbound, TransferStatus::Executing) => {
+                            RelayerTask::CompleteWithdrawal { executing_relayer: transfer.executing_relayer }
+                        }
+                        _ => continue,
+                    };
+                    let claim = state.work_claims.get(&transfer_id).await?.filter(|claim| claim.is_live(at));
+                    work.push(RelayerWorkItem { transfer_id, chain: transfer_chain, task, claim });
+                }
+                Ok(QueryResponse::RelayerWork(work))
+            }
+            Query::GetWorkReceipts { transfer_id } => {
+                Ok(QueryResponse::WorkReceipts(state.work_receipts.get(&transfer_id).await?.unwrap_or_default()))
+            }
+            Query::GetRelayerWorkStats { relayer } => {
+                Ok(QueryResponse::RelayerWorkStats(state.relayer_work_stats.get(&relayer).await?.unwrap_or_default()))
+            }
+            Query::GetProcessingWindows { chain, at } => {
+                let windows = state.processing_windows.get(&chain.chain_id()).await?.unwrap_or_default();
+                let next_processing_at = next_processing_time(&windows, at);
+                Ok(QueryResponse::ProcessingWindows(ProcessingWindowStatus {
+                    open: next_processing_at == at,
+                    windows,
+                    next_processing_at,
+                }))
+            }
+            Query::GetCorridorStatus { chain, at } => {
+                Ok(QueryResponse::CorridorStatus {
+                    availability: state.corridor_availability(chain, at).await?,
+                    announced: state.corridor_statuses.get(&chain.chain_id()).await?,
+                })
+            }
+            Query::GetCorridorConsumers => Ok(QueryResponse::CorridorConsumers(state.corridor_consumers.get())),
+            Query::GetProofOfReserves => Ok(QueryResponse::ProofOfReserves(state.proof_of_reserves().await?)),
+            Query::GetReservesSnapshots => {
+                let mut snapshots = Vec::new();
+                state.reserves_snapshots.for_each_index_value(|_, snapshot| {
+                    snapshots.push(snapshot);
+                    Ok(())
+                }).await?;
+                Ok(QueryResponse::ReservesSnapshots(snapshots))
+            }
+            Query::ValidateForApproval { transfer_id, validator, at } => {
+                let transfer = state.transfers.get(&transfer_id).await?
+                    .ok_or(BridgeError::TransferNotFound { transfer_id })?;
+                let context = state.approval_context(&transfer, at).await?;
+                let mut ineligible = Vec::new();
+                match state.validators.get(&validator).await? {
+                    None => ineligible.push(ApprovalIneligibility::NotRegistered),
+                    Some(config) if !config.is_active => ineligible.push(ApprovalIneligibility::Inactive),
+                    Some(_) => {}
+                }
+                if state.transfer_approvals.contains_key(&(transfer_id, validator)).await? {
+                    ineligible.push(ApprovalIneligibility::AlreadyVoted);
+                }
+                Ok(QueryResponse::ApprovalPrevalidation(ApprovalPrevalidation {
+                    transfer_id,
+                    validator,
+                    payload: transfer.approval_payload().ok(),
+                    checks: approval_checks(&transfer, &context),
+                    validator_set_epoch: state.validator_set_epoch.get(),
+                    ineligible,
+                }))
+            }
+        }
     }
 }
 
@@ -1303,11 +6523,370 @@ mod tests {
         assert_eq!(ExternalChain::Solana.required_confirmations(), 32);
     }
     
+    #[test]
+    fn test_finality_profiles() {
+        let count = FinalityProfile::ConfirmationCount(12);
+        assert!(!count.is_final(11, 100, Some(200)));
+        assert!(count.is_final(12, 100, None));
+
+        let tag = FinalityProfile::FinalityTag;
+        assert!(!tag.is_final(1_000, 100, None));
+        assert!(!tag.is_final(1_000, 100, Some(99)));
+        assert!(tag.is_final(0, 100, Some(100)));
+
+        let hybrid = FinalityProfile::Hybrid { min_confirmations: 2 };
+        assert!(!hybrid.is_final(1, 100, Some(100)));
+        assert!(!hybrid.is_final(2, 100, Some(99)));
+        assert!(hybrid.is_final(2, 100, Some(100)));
+
+        assert_eq!(count.expected_wait_seconds(ExternalChain::Ethereum), 144);
+        assert_eq!(tag.expected_wait_seconds(ExternalChain::Ethereum), 768);
+        assert_eq!(FinalityProfile::ConfirmationCount(32).expected_wait_seconds(ExternalChain::Solana), 13);
+        assert_eq!(FinalityProfile::Hybrid { min_confirmations: 6 }.expected_wait_seconds(ExternalChain::Bitcoin), 3_600);
+    }
+
+    #[test]
+    fn test_validator_average_latency() {
+        let mut performance = ValidatorPerformance::default();
+        assert_eq!(performance.average_latency_seconds(), None);
+        performance.approvals = 4;
+        performance.total_latency_seconds = 10;
+        assert_eq!(performance.average_latency_seconds(), Some(2));
+    }
+
+    #[test]
+    fn test_deposit_hook_filter() {
+        let any = DepositHookFilter::default();
+        assert!(any.matches("USDC", Amount::ZERO));
+
+        let filter = DepositHookFilter { asset: Some("USDC".to_string()), min_amount: Amount::from(1_000) };
+        assert!(filter.matches("USDC", Amount::from(1_000)));
+        assert!(!filter.matches("USDC", Amount::from(999)));
+        assert!(!filter.matches("USDT", Amount::from(5_000)));
+    }
+
+    #[test]
+    fn test_cosmos_chain_address_format() {
+        assert_eq!(ExternalChain::CosmosHub.name(), "Cosmos Hub");
+        assert!(ExternalChain::CosmosHub.address_format()
+            .validate("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"));
+        assert!(!ExternalChain::Osmosis.address_format()
+            .validate("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"));
+        assert!(!ExternalChain::Ethereum.address_format().validate("cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"));
+    }
+    
+    #[test]
+    fn test_insurance_split_never_double_counts() {
+        let (protocol, insurance) = split_fee(Amount::from(1_003), 1_000).unwrap();
+        assert_eq!(insurance, Amount::from(100));
+        assert_eq!(protocol, Amount::from(903));
+        assert_eq!(protocol + insurance, Amount::from(1_003));
+        
+        assert_eq!(split_fee(Amount::from(50), 0), Ok((Amount::from(50), Amount::ZERO)));
+        assert_eq!(split_fee(Amount::from(50), 10_000), Ok((Amount::ZERO, Amount::from(50))));
+    }
+    
+    #[test]
+    fn test_clawback_within_balance() {
+        let (balance, shortfall) = clawback(Amount::from(500), Amount::from(200));
+        assert_eq!(balance, Amount::from(300));
+        assert_eq!(shortfall, Amount::ZERO);
+    }
+    
+    #[test]
+    fn test_clawback_already_spent_creates_debt() {
+        let (balance, shortfall) = clawback(Amount::from(50), Amount::from(200));
+        assert_eq!(balance, Amount::ZERO);
+        assert_eq!(shortfall, Amount::from(150));
+        
+        // A later credit repays the debt before reaching the balance
+        let (debt, credit) = repay_debt(shortfall, Amount::from(100));
+        assert_eq!(debt, Amount::from(50));
+        assert_eq!(credit, Amount::ZERO);
+        
+        let (debt, credit) = repay_debt(debt, Amount::from(80));
+        assert_eq!(debt, Amount::ZERO);
+        assert_eq!(credit, Amount::from(30));
+    }
+    
+    #[test]
+    fn test_corridor_stats_rollup() {
+        let mut day_one = CorridorStats::default();
+        day_one.outbound.transfers = 3;
+        day_one.outbound.completed = 2;
+        day_one.outbound.failed = 1;
+        day_one.outbound.total_completion_seconds = 600;
+        
+        let mut day_two = CorridorStats::default();
+        day_two.outbound.transfers = 1;
+        day_two.outbound.completed = 1;
+        day_two.outbound.total_completion_seconds = 300;
+        
+        let mut summary = CorridorStats::default();
+        summary.merge(&day_one);
+        summary.merge(&day_two);
+        
+        assert_eq!(summary.outbound.transfers, 4);
+        assert_eq!(summary.outbound.average_completion_seconds(), Some(300));
+        assert_eq!(summary.outbound.failure_rate_bps(), 2_500);
+        assert_eq!(summary.inbound.average_completion_seconds(), None);
+    }
+    
+    pub(crate) fn test_chain_config(relayer_gas_fee: u128) -> ChainConfig {
+        ChainConfig {
+            chain: ExternalChain::Ethereum,
+            is_enabled: true,
+            bridge_contract_address: "0x52908400098527886E0F7030069857D2E4169EE7".to_string(),
+            deprecated_addresses: vec![],
+            supported_assets: vec![],
+            min_transfer_amount: Amount::from(1),
+            max_transfer_amount: Amount::from(1_000_000_000),
+            base_fee: Amount::from(100),
+            fee_percentage_bps: 30,
+            relayer_gas_fee: Amount::from(relayer_gas_fee),
+            required_confirmations: 12,
+            finality: None,
+            estimated_time_seconds: 900,
+            memo_required: false,
+            large_transfer_threshold: Some(Amount::from(1_000_000)),
+            quarantine_seconds: 3600,
+            inbound_expiry_seconds: 3600 * 24,
+            outbound_expiry_seconds: 3600 * 24,
+            version: 1,
+        }
+    }
+    
+    #[test]
+    fn test_expiries_per_direction() {
+        let config = ChainConfig { inbound_expiry_seconds: 7_200, ..test_chain_config(0) };
+        assert!(config.check_expiries().is_ok());
+        assert_eq!(config.expiry_seconds(TransferDirection::Inbound), 7_200);
+        assert_eq!(config.expiry_seconds(TransferDirection::Outbound), 3600 * 24);
+
+        let too_short = ChainConfig { outbound_expiry_seconds: MIN_TRANSFER_EXPIRY_SECONDS - 1, ..config.clone() };
+        assert!(matches!(too_short.check_expiries(), Err(BridgeError::InvalidConfig { .. })));
+        let too_long = ChainConfig { inbound_expiry_seconds: MAX_TRANSFER_EXPIRY_SECONDS + 1, ..config };
+        assert!(too_long.check_expiries().is_err());
+
+        // Transfers from before the snapshot keep the old fixed expiry
+        let transfer = test_transfer(TransferDirection::Outbound, None);
+        assert_eq!(transfer.expiry(), std::time::Duration::from_secs(DEFAULT_TRANSFER_EXPIRY_SECONDS));
+    }
+
+    #[test]
+    fn test_bridge_address_rotation() {
+        let old = "0x52908400098527886E0F7030069857D2E4169EE7";
+        let new = "0xde0B295669a9FD93d5F28D9Ec85E40f4cb697BAe";
+        let mut config = test_chain_config(0);
+        config.rotate_address(new.to_string(), 60, Timestamp::from(1_000_000));
+        
+        let before_cutoff = Timestamp::from(60_999_999);
+        let at_cutoff = Timestamp::from(61_000_000);
+        assert_eq!(config.accepted_addresses(before_cutoff), vec![new.to_string(), old.to_string()]);
+        assert_eq!(config.accepted_addresses(at_cutoff), vec![new.to_string()]);
+        assert!(config.check_deposit_address(&old.to_lowercase(), before_cutoff).is_ok());
+        assert!(matches!(
+            config.check_deposit_address(old, at_cutoff),
+            Err(BridgeError::BridgeAddressRetired { .. })
+        ));
+        assert!(matches!(
+            config.check_deposit_address("0x0000000000000000000000000000000000000001", before_cutoff),
+            Err(BridgeError::UnknownBridgeAddress { .. })
+        ));
+        
+        // Rotating back to a deprecated address makes it primary again, not also deprecated
+        config.rotate_address(old.to_string(), 0, before_cutoff);
+        assert!(config.deprecated_addresses.is_empty());
+        assert_eq!(config.bridge_contract_address, old);
+    }
+    
+    #[test]
+    fn test_fee_split_accounting() {
+        let config = test_chain_config(150);
+        let amounts = [10_000u128, 250_000, 1_000_000, 77_777, 120];
+        
+        let mut collected = Amount::ZERO;
+        let mut protocol = Amount::ZERO;
+        let mut relayer = Amount::ZERO;
+        for amount in amounts {
+            let fees = config.fee_breakdown(Amount::from(amount), TransferDirection::Outbound).unwrap();
+            assert!(fees.relayer_fee <= fees.total_fee);
+            collected = collected + fees.total_fee;
+            protocol = protocol + fees.protocol_fee;
+            relayer = relayer + fees.relayer_fee;
+        }
+        
+        assert_eq!(collected, protocol + relayer);
+        assert_eq!(relayer, Amount::from(150 * amounts.len() as u128));
+    }
+    
+    #[test]
+    fn test_quarantine_threshold() {
+        let mut config = test_chain_config(0);
+        assert!(!config.requires_quarantine(Amount::from(1_000_000)));
+        assert!(config.requires_quarantine(Amount::from(1_000_001)));
+        
+        config.large_transfer_threshold = None;
+        assert!(!config.requires_quarantine(Amount::from(u128::MAX)));
+    }
+    
+    #[test]
+    fn test_relayer_fee_capped_and_outbound_only() {
+        let config = test_chain_config(10_000);
+        let fees = config.fee_breakdown(Amount::from(1_000), TransferDirection::Outbound).unwrap();
+        assert_eq!(fees.relayer_fee, fees.total_fee);
+        assert_eq!(fees.protocol_fee, Amount::ZERO);
+        
+        let fees = config.fee_breakdown(Amount::from(1_000), TransferDirection::Inbound).unwrap();
+        assert_eq!(fees.relayer_fee, Amount::ZERO);
+    }
+    
     #[test]
     fn test_transfer_status() {
         let status = TransferStatus::Pending;
         assert!(matches!(status, TransferStatus::Pending));
     }
+    
+    pub(crate) fn test_transfer(direction: TransferDirection, source_chain: Option<ExternalChain>) -> BridgeTransfer {
+        BridgeTransfer {
+            id: 1,
+            direction,
+            source_chain,
+            destination_chain: None,
+            user: Account::chain(ChainId::root(0)),
+            external_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+            asset: "USDC".to_string(),
+            amount: Amount::from(1_000),
+            fee: Amount::ZERO,
+            relayer_fee: Amount::ZERO,
+            insurance_fee: Amount::ZERO,
+            net_amount: Amount::from(1_000),
+            fee_override: None,
+            source_tx_hash: None,
+            destination_tx_hash: None,
+            source_block_height: None,
+            memo: None,
+            release_at: None,
+            config_version: 0,
+            status: TransferStatus::AwaitingApproval,
+            confirmations: 0,
+            required_confirmations: 0,
+            finality: None,
+            created_at: Timestamp::from(0),
+            completed_at: None,
+            expires_at: Timestamp::from(0),
+            approval_threshold: 1,
+            approval_count: 0,
+            approval_weight: 0,
+            approval_requirement: None,
+            reporter: None,
+            relayer: None,
+            executing_relayer: None,
+            executing_deadline: None,
+            error_message: None,
+            failure: None,
+            external_refund: None,
+            last_confirmation_report: None,
+            reorged_at: None,
+            retry_count: 0,
+            expiry_seconds: None,
+        }
+    }
+    
+    #[test]
+    fn test_cancellation_fee_accounting() {
+        let config = test_chain_config(150);
+        let scenarios = [
+            (10_000u128, TransferStatus::AwaitingApproval),
+            (250_000, TransferStatus::Approved),
+            (77_777, TransferStatus::AwaitingApproval),
+            (1_000_000, TransferStatus::Approved),
+        ];
+        
+        let (mut collected, mut retained, mut refunded) = (Amount::ZERO, Amount::ZERO, Amount::ZERO);
+        for (amount, status) in scenarios {
+            let fees = config.fee_breakdown(Amount::from(amount), TransferDirection::Outbound).unwrap();
+            let mut transfer = test_transfer(TransferDirection::Outbound, None);
+            transfer.amount = Amount::from(amount);
+            transfer.fee = fees.total_fee;
+            transfer.relayer_fee = fees.relayer_fee;
+            transfer.net_amount = fees.net_amount;
+            transfer.status = status;
+            
+            let refund = transfer.cancellation_refund().unwrap();
+            assert_eq!(refund.refund + refund.fee_retained, transfer.amount);
+            match status {
+                TransferStatus::AwaitingApproval => assert_eq!(refund.fee_retained, Amount::ZERO),
+                _ => assert_eq!(refund.fee_refunded, fees.relayer_fee),
+            }
+            collected = collected + fees.total_fee;
+            retained = retained + refund.fee_retained;
+            refunded = refunded + refund.fee_refunded;
+        }
+        assert_eq!(collected, retained + refunded);
+        
+        // Claimed by a relayer, or not a withdrawal
+        let mut executing = test_transfer(TransferDirection::Outbound, None);
+        executing.status = TransferStatus::Executing;
+        assert_eq!(executing.cancellation_refund(), None);
+        let deposit = test_transfer(TransferDirection::Inbound, Some(ExternalChain::Ethereum));
+        assert_eq!(deposit.cancellation_refund(), None);
+    }
+    
+    #[test]
+    fn test_execution_stalled_after_deadline() {
+        let mut transfer = test_transfer(TransferDirection::Outbound, None);
+        transfer.status = TransferStatus::Executing;
+        transfer.executing_deadline = Some(Timestamp::from(1_000));
+        assert!(!transfer.execution_stalled(Timestamp::from(1_000)));
+        assert!(transfer.execution_stalled(Timestamp::from(1_001)));
+        
+        // Reported or re-queued withdrawals are not stalled
+        transfer.status = TransferStatus::Approved;
+        assert!(!transfer.execution_stalled(Timestamp::from(1_001)));
+    }
+    
+    #[test]
+    fn test_stored_source_chain_keeps_legacy_encoding() {
+        for chain in [ExternalChain::Ethereum, ExternalChain::Osmosis, ExternalChain::Custom(0), ExternalChain::Custom(7)] {
+            assert_eq!(
+                bcs::to_bytes(&StoredSourceChain::from(Some(chain))).unwrap(),
+                bcs::to_bytes(&chain).unwrap(),
+            );
+        }
+        // Linera takes the first index past the legacy variants
+        assert_eq!(bcs::to_bytes(&StoredSourceChain::Linera).unwrap(), vec![11]);
+    }
+    
+    #[test]
+    fn test_legacy_outbound_source_decodes_as_linera() {
+        // Bytes of an outbound transfer written with the old `Custom(0)` stand-in
+        let mut legacy = test_transfer(TransferDirection::Outbound, Some(ExternalChain::Custom(0)));
+        legacy.destination_chain = Some(ExternalChain::Ethereum);
+        let bytes = bcs::to_bytes(&legacy).unwrap();
+        
+        let transfer: BridgeTransfer = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(transfer.source_chain, None);
+        assert_eq!(transfer.corridor_chain().unwrap(), ExternalChain::Ethereum);
+        
+        let json = serde_json::to_value(&legacy).unwrap();
+        assert_eq!(json["source_chain"], serde_json::json!({ "Custom": 0 }));
+        let transfer: BridgeTransfer = serde_json::from_value(json).unwrap();
+        assert_eq!(transfer.source_chain, None);
+    }
+    
+    #[test]
+    fn test_custom_chain_zero_deposit_keeps_its_source() {
+        let deposit = test_transfer(TransferDirection::Inbound, Some(ExternalChain::Custom(0)));
+        let transfer: BridgeTransfer = bcs::from_bytes(&bcs::to_bytes(&deposit).unwrap()).unwrap();
+        assert_eq!(transfer.source_chain, Some(ExternalChain::Custom(0)));
+        assert_eq!(transfer.corridor_chain().unwrap(), ExternalChain::Custom(0));
+        
+        let withdrawal = test_transfer(TransferDirection::Outbound, None);
+        let transfer: BridgeTransfer = bcs::from_bytes(&bcs::to_bytes(&withdrawal).unwrap()).unwrap();
+        assert_eq!(transfer, withdrawal);
+        assert!(matches!(transfer.corridor_chain(), Err(BridgeError::MissingExternalChain { transfer_id: 1 })));
+    }
 }
 
 
@@ -0,0 +1,133 @@
+//! Operator overview returned by `Query::GetBridgeOverview`, and the rolling volume window the
+//! contract keeps for it. Every figure is read from a counter maintained as transfers change.
+
+use std::collections::BTreeMap;
+
+use linera_base::data_types::{Amount, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::{ExternalChain, TransferDirection, TransferId, TransferStatus};
+
+/// Hours covered by `HourlyVolume`
+pub const VOLUME_WINDOW_HOURS: u64 = 24;
+
+pub fn hour_bucket(timestamp: Timestamp) -> u64 {
+    timestamp.micros() / (3_600 * 1_000_000)
+}
+
+/// Volume created in one hour, per asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeBucket {
+    pub hour: u64,
+    pub inbound: BTreeMap<String, Amount>,
+    pub outbound: BTreeMap<String, Amount>,
+}
+
+/// A chain's transfer volume over the last `VOLUME_WINDOW_HOURS` hours, by hour
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourlyVolume {
+    /// Oldest first; at most one bucket per hour of the window
+    pub buckets: Vec<VolumeBucket>,
+}
+
+impl HourlyVolume {
+    /// Adds `amount` to the bucket of `hour`, dropping buckets that left the window
+    pub fn record(&mut self, hour: u64, direction: TransferDirection, asset: &str, amount: Amount) {
+        self.buckets.retain(|bucket| bucket.hour + VOLUME_WINDOW_HOURS > hour);
+        if self.buckets.last().map_or(true, |bucket| bucket.hour < hour) {
+            self.buckets.push(VolumeBucket { hour, ..VolumeBucket::default() });
+        }
+        let Some(bucket) = self.buckets.iter_mut().rev().find(|bucket| bucket.hour == hour) else {
+            return;
+        };
+        let volumes = match direction {
+            TransferDirection::Inbound => &mut bucket.inbound,
+            TransferDirection::Outbound => &mut bucket.outbound,
+        };
+        let volume = volumes.entry(asset.to_string()).or_insert(Amount::ZERO);
+        *volume = volume.saturating_add(amount);
+    }
+
+    /// Totals of the buckets still inside the window at `hour`
+    pub fn window(&self, hour: u64) -> ChainVolume {
+        let mut volume = ChainVolume::default();
+        for bucket in self.buckets.iter().filter(|bucket| bucket.hour + VOLUME_WINDOW_HOURS > hour) {
+            for (totals, amounts) in [(&mut volume.inbound, &bucket.inbound), (&mut volume.outbound, &bucket.outbound)] {
+                for (asset, amount) in amounts {
+                    let total = totals.entry(asset.clone()).or_insert(Amount::ZERO);
+                    *total = total.saturating_add(*amount);
+                }
+            }
+        }
+        volume
+    }
+}
+
+/// Volume created on a chain within the window, per asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVolume {
+    pub inbound: BTreeMap<String, Amount>,
+    pub outbound: BTreeMap<String, Amount>,
+}
+
+/// One configured chain in the overview
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainOverview {
+    pub chain: ExternalChain,
+    /// False while the chain is disabled, which pauses its transfers
+    pub enabled: bool,
+    /// Unfinished transfers by status
+    pub open_transfers: BTreeMap<TransferStatus, u64>,
+    pub volume_24h: ChainVolume,
+}
+
+/// Size of the validator set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSummary {
+    pub count: u32,
+    pub inactive: u32,
+    pub total_weight: u32,
+}
+
+/// The unfinished transfer created first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OldestOpenTransfer {
+    pub transfer_id: TransferId,
+    pub created_at: Timestamp,
+    pub age_seconds: u64,
+}
+
+/// Everything the operator dashboard shows, in one response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeOverview {
+    pub paused: bool,
+    pub chains: Vec<ChainOverview>,
+    pub validators: ValidatorSummary,
+    /// Unfinished transfers by status, over all chains
+    pub open_transfers: BTreeMap<TransferStatus, u64>,
+    pub oldest_open_transfer: Option<OldestOpenTransfer>,
+    /// Protocol fees collected per asset, net of the insurance carve-out
+    pub collected_fees: BTreeMap<String, Amount>,
+    /// Insurance reserve per asset
+    pub insurance_fund: BTreeMap<String, Amount>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_window_rolls() {
+        let mut volume = HourlyVolume::default();
+        volume.record(10, TransferDirection::Inbound, "USDC", Amount::from_tokens(5));
+        volume.record(10, TransferDirection::Inbound, "USDC", Amount::from_tokens(3));
+        volume.record(20, TransferDirection::Outbound, "USDC", Amount::from_tokens(2));
+        assert_eq!(volume.window(20).inbound["USDC"], Amount::from_tokens(8));
+        assert_eq!(volume.window(20).outbound["USDC"], Amount::from_tokens(2));
+
+        // Hour 10 leaves the window at hour 34, and is dropped by the next write
+        assert!(volume.window(34).inbound.is_empty());
+        volume.record(34, TransferDirection::Inbound, "WETH", Amount::from_tokens(1));
+        assert_eq!(volume.buckets.iter().map(|bucket| bucket.hour).collect::<Vec<_>>(), vec![20, 34]);
+    }
+}
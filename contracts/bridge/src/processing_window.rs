@@ -0,0 +1,102 @@
+//! Processing windows: daily UTC time ranges in which a chain's withdrawals enter approval.
+//! Withdrawals initiated outside them are scheduled for the next window opening.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::BridgeError;
+
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Most windows a chain may have
+pub const MAX_PROCESSING_WINDOWS: usize = 16;
+
+/// Range of the UTC day, in seconds since midnight; wraps past midnight when `end` is before `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessingWindow {
+    pub start: u64,
+    /// Exclusive
+    pub end: u64,
+}
+
+impl ProcessingWindow {
+    pub fn validate(&self) -> Result<(), BridgeError> {
+        if self.start >= SECONDS_PER_DAY || self.end >= SECONDS_PER_DAY {
+            return Err(BridgeError::InvalidConfig { reason: "Window bounds must fall within the day".to_string() });
+        }
+        if self.start == self.end {
+            return Err(BridgeError::InvalidConfig { reason: "Window must not be empty".to_string() });
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, now: Timestamp) -> bool {
+        let second = seconds_of_day(now);
+        if self.start < self.end {
+            self.start <= second && second < self.end
+        } else {
+            second >= self.start || second < self.end
+        }
+    }
+
+    /// First opening of the window strictly after `now`
+    fn next_start(&self, now: Timestamp) -> Timestamp {
+        let now_seconds = now.micros() / 1_000_000;
+        let mut start = now_seconds - seconds_of_day(now) + self.start;
+        if start <= now_seconds {
+            start += SECONDS_PER_DAY;
+        }
+        Timestamp::from(start.saturating_mul(1_000_000))
+    }
+}
+
+fn seconds_of_day(now: Timestamp) -> u64 {
+    (now.micros() / 1_000_000) % SECONDS_PER_DAY
+}
+
+/// When a withdrawal initiated at `now` may enter approval: `now` itself inside a window, or
+/// without any window configured
+pub fn next_processing_time(windows: &[ProcessingWindow], now: Timestamp) -> Timestamp {
+    if windows.is_empty() || windows.iter().any(|window| window.contains(now)) {
+        return now;
+    }
+    windows.iter().map(|window| window.next_start(now)).min().unwrap_or(now)
+}
+
+/// A chain's windows and whether they are open, returned by `Query::GetProcessingWindows`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessingWindowStatus {
+    /// Empty when withdrawals are processed at any time
+    pub windows: Vec<ProcessingWindow>,
+    pub open: bool,
+    /// When a withdrawal initiated at the queried time enters approval
+    pub next_processing_at: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u64, hour: u64) -> Timestamp {
+        Timestamp::from((day * SECONDS_PER_DAY + hour * 3600) * 1_000_000)
+    }
+
+    #[test]
+    fn test_withdrawals_wait_for_the_next_window() {
+        let business_hours = ProcessingWindow { start: 9 * 3600, end: 17 * 3600 };
+        let overnight = ProcessingWindow { start: 22 * 3600, end: 2 * 3600 };
+        assert!(overnight.validate().is_ok());
+        assert!(ProcessingWindow { start: 3600, end: 3600 }.validate().is_err());
+        assert!(ProcessingWindow { start: 0, end: SECONDS_PER_DAY }.validate().is_err());
+
+        // Open windows and no windows at all process right away
+        assert_eq!(next_processing_time(&[], at(3, 5)), at(3, 5));
+        assert_eq!(next_processing_time(&[business_hours], at(3, 9)), at(3, 9));
+        assert_eq!(next_processing_time(&[overnight], at(3, 1)), at(3, 1));
+
+        // Closed: the earliest next opening, on the following day once today's has passed
+        assert_eq!(next_processing_time(&[business_hours, overnight], at(3, 5)), at(3, 9));
+        assert_eq!(next_processing_time(&[business_hours, overnight], at(3, 18)), at(3, 22));
+        assert_eq!(next_processing_time(&[business_hours], at(3, 17)), at(4, 9));
+    }
+}
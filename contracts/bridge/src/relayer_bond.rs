@@ -0,0 +1,110 @@
+//! Relayer bonds: balance a relayer locks to report deposits and withdrawal completions on chains
+//! with a bond minimum, slashed into the insurance fund for fraudulent reports.
+
+use linera_base::data_types::{Amount, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::ExternalChain;
+
+/// Bond posted by one relayer, in the configured bond asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayerBond {
+    /// Counts toward chain minimums
+    pub bonded: Amount,
+    /// Leaving the bond; still slashable until withdrawn
+    pub unbonding: Amount,
+    /// When `unbonding` can be withdrawn
+    pub unbonding_until: Option<Timestamp>,
+    /// Taken from the bond so far
+    pub slashed: Amount,
+}
+
+impl RelayerBond {
+    /// Whether there is nothing left to slash or withdraw
+    pub fn is_empty(&self) -> bool {
+        self.bonded == Amount::ZERO && self.unbonding == Amount::ZERO
+    }
+
+    /// Moves `amount` out of the active bond; the cooldown restarts for everything unbonding.
+    /// Returns the bonded amount when it is short.
+    pub fn unbond(&mut self, amount: Amount, until: Timestamp) -> Result<(), Amount> {
+        if amount > self.bonded {
+            return Err(self.bonded);
+        }
+        self.bonded = self.bonded.saturating_sub(amount);
+        self.unbonding = self.unbonding.saturating_add(amount);
+        self.unbonding_until = Some(until);
+        Ok(())
+    }
+
+    /// Unbonded balance whose cooldown has passed at `now`
+    pub fn withdrawable(&self, now: Timestamp) -> Amount {
+        match self.unbonding_until {
+            Some(until) if now >= until => self.unbonding,
+            _ => Amount::ZERO,
+        }
+    }
+
+    /// Takes up to `amount`, from the active bond first, and returns what was taken
+    pub fn slash(&mut self, amount: Amount) -> Amount {
+        let from_bonded = amount.min(self.bonded);
+        let from_unbonding = amount.saturating_sub(from_bonded).min(self.unbonding);
+        self.bonded = self.bonded.saturating_sub(from_bonded);
+        self.unbonding = self.unbonding.saturating_sub(from_unbonding);
+        if self.unbonding == Amount::ZERO {
+            self.unbonding_until = None;
+        }
+        let taken = from_bonded.saturating_add(from_unbonding);
+        self.slashed = self.slashed.saturating_add(taken);
+        taken
+    }
+}
+
+/// Bond a relayer needs to report for a chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBondMinimum {
+    pub chain: ExternalChain,
+    pub minimum: Amount,
+}
+
+/// Whether a relayer may report for one chain with a bond minimum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayerChainStatus {
+    pub chain: ExternalChain,
+    pub minimum: Amount,
+    pub active: bool,
+}
+
+/// A relayer's bond and where it is active, returned by `Query::GetRelayerBond`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayerBondStatus {
+    /// None until `ConfigureRelayerBonds`
+    pub asset: Option<String>,
+    pub bond: RelayerBond,
+    /// Part of `bond.unbonding` withdrawable at the queried time
+    pub withdrawable: Amount,
+    /// Chains without a minimum accept reports from anyone and are not listed
+    pub chains: Vec<RelayerChainStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slash_reaches_unbonding_balance() {
+        let mut bond = RelayerBond { bonded: Amount::from_tokens(10), ..RelayerBond::default() };
+        assert_eq!(bond.unbond(Amount::from_tokens(11), Timestamp::from(100)), Err(Amount::from_tokens(10)));
+        bond.unbond(Amount::from_tokens(6), Timestamp::from(100)).unwrap();
+        assert_eq!(bond.withdrawable(Timestamp::from(99)), Amount::ZERO);
+        assert_eq!(bond.withdrawable(Timestamp::from(100)), Amount::from_tokens(6));
+
+        // The active bond goes first, then the cooling-down part
+        assert_eq!(bond.slash(Amount::from_tokens(7)), Amount::from_tokens(7));
+        assert_eq!((bond.bonded, bond.unbonding), (Amount::ZERO, Amount::from_tokens(3)));
+        assert_eq!(bond.slash(Amount::from_tokens(5)), Amount::from_tokens(3));
+        assert_eq!(bond.slashed, Amount::from_tokens(10));
+        assert_eq!(bond.unbonding_until, None);
+        assert!(bond.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Relayer work: receipts of which relayer did what on each transfer, counts of submissions that
+//! repeated another relayer's (or their own) work, and the soft claims relayers announce so they
+//! can split open transfers between them.
+
+use linera_base::{data_types::Timestamp, identifiers::Account};
+use serde::{Deserialize, Serialize};
+
+use crate::{ExternalChain, TransferId};
+
+/// How long a soft claim holds before other relayers may claim the transfer
+pub const WORK_CLAIM_SECONDS: u64 = 600;
+
+/// Relayer actions recorded on a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelayerAction {
+    ReportedDeposit,
+    ClaimedExecution,
+    CompletedWithdrawal,
+    SoftClaimed,
+}
+
+/// One relayer action on a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkReceipt {
+    pub relayer: Account,
+    pub action: RelayerAction,
+    /// The action had already been performed; it was accepted as a no-op
+    pub duplicate: bool,
+    pub at: Timestamp,
+}
+
+/// Counters of one relayer's recorded actions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayerWorkStats {
+    pub actions: u64,
+    /// Actions that repeated work already done, by this relayer or another
+    pub duplicates: u64,
+    pub last_duplicate: Option<TransferId>,
+}
+
+impl RelayerWorkStats {
+    pub fn record(&mut self, transfer_id: TransferId, duplicate: bool) {
+        self.actions = self.actions.saturating_add(1);
+        if duplicate {
+            self.duplicates = self.duplicates.saturating_add(1);
+            self.last_duplicate = Some(transfer_id);
+        }
+    }
+}
+
+/// A relayer's announcement that it is handling a transfer; advisory, it blocks nobody's reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkClaim {
+    pub relayer: Account,
+    pub until: Timestamp,
+}
+
+impl WorkClaim {
+    pub fn is_live(&self, now: Timestamp) -> bool {
+        now < self.until
+    }
+}
+
+/// What an open transfer is waiting for a relayer to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelayerTask {
+    /// A reported deposit short of finality, awaiting `UpdateConfirmations`
+    ConfirmDeposit,
+    /// An approved withdrawal awaiting `ExecuteTransfer`
+    ExecuteWithdrawal,
+    /// A withdrawal claimed by `executing_relayer`, awaiting `CompleteWithdrawal`
+    CompleteWithdrawal { executing_relayer: Option<Account> },
+}
+
+/// An open transfer in a relayer's shard, returned by `Query::GetRelayerWork`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayerWorkItem {
+    pub transfer_id: TransferId,
+    pub chain: ExternalChain,
+    pub task: RelayerTask,
+    /// Soft claim still live at the queried time
+    pub claim: Option<WorkClaim>,
+}
+
+/// Whether `transfer_id` falls to relayer `relayer_index` of `relayer_count`; a count of zero or
+/// one puts everything in a single shard
+pub fn in_shard(transfer_id: TransferId, relayer_count: u64, relayer_index: u64) -> bool {
+    relayer_count <= 1 || transfer_id % relayer_count == relayer_index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_cover_each_transfer_once() {
+        for transfer_id in 0..20 {
+            let owners = (0..3).filter(|index| in_shard(transfer_id, 3, *index)).count();
+            assert_eq!(owners, 1);
+            assert!(in_shard(transfer_id, 0, 0) && in_shard(transfer_id, 1, 0));
+        }
+
+        let mut stats = RelayerWorkStats::default();
+        stats.record(4, false);
+        stats.record(7, true);
+        assert_eq!(stats, RelayerWorkStats { actions: 2, duplicates: 1, last_duplicate: Some(7) });
+    }
+}
@@ -0,0 +1,112 @@
+//! Proof-of-reserves reports: the bridge's liabilities per asset at a block height, with a hash
+//! auditors can sign and later check a snapshot against.
+//!
+//! Every figure comes from a per-asset total kept up to date as balances and transfers change,
+//! so a report costs one read per asset whatever the number of users.
+
+use linera_base::{
+    data_types::{Amount, BlockHeight, Timestamp},
+    identifiers::Account,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
+
+use crate::encoding::{Domain, Encoder};
+
+/// What the bridge owes in one asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetLiabilities {
+    pub asset: String,
+    /// Sum of the balances held for users
+    pub user_balances: Amount,
+    /// Approved and executing withdrawals, net of their fees
+    pub in_flight_outbound: Amount,
+    /// Completed deposits less completed withdrawals, as locked on the external chains
+    pub bridged_supply: Amount,
+    /// Protocol share of collected fees
+    pub collected_fees: Amount,
+    pub insurance_fund: Amount,
+}
+
+/// Liabilities of every asset with any, as of `block_height`, returned by `Query::GetProofOfReserves`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofOfReserves {
+    /// Last block executed by the bridge before the report was taken
+    pub block_height: BlockHeight,
+    /// Sorted by asset
+    pub assets: Vec<AssetLiabilities>,
+    /// keccak256 of the canonical encoding of the height and the assets in order
+    pub hash: [u8; 32],
+}
+
+impl ProofOfReserves {
+    /// The report of `assets`, sorted and hashed
+    pub fn new(block_height: BlockHeight, mut assets: Vec<AssetLiabilities>) -> Self {
+        assets.sort_by(|a, b| a.asset.cmp(&b.asset));
+        let hash = reserves_hash(block_height, &assets);
+        ProofOfReserves { block_height, assets, hash }
+    }
+}
+
+/// The entry of `asset` in a report being assembled
+pub(crate) fn liabilities_of(
+    liabilities: &mut BTreeMap<String, AssetLiabilities>,
+    asset: String,
+) -> &mut AssetLiabilities {
+    liabilities.entry(asset.clone()).or_insert_with(|| AssetLiabilities { asset, ..AssetLiabilities::default() })
+}
+
+/// keccak256 over the canonical encoding of the height, the asset count and each asset's figures
+pub fn reserves_hash(block_height: BlockHeight, assets: &[AssetLiabilities]) -> [u8; 32] {
+    let length = u32::try_from(assets.len()).expect("fewer assets than fit in a u32");
+    let mut encoder = Encoder::new(Domain::ReservesReport).u64(block_height.0).u32(length);
+    for liabilities in assets {
+        encoder = encoder
+            .str(&liabilities.asset)
+            .amount(liabilities.user_balances)
+            .amount(liabilities.in_flight_outbound)
+            .amount(liabilities.bridged_supply)
+            .amount(liabilities.collected_fees)
+            .amount(liabilities.insurance_fund);
+    }
+    Keccak256::digest(encoder.finish()).into()
+}
+
+/// A report's hash kept on chain by `RecordReservesSnapshot`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReservesSnapshot {
+    pub id: u64,
+    pub block_height: BlockHeight,
+    pub hash: [u8; 32],
+    pub recorded_by: Account,
+    pub recorded_at: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn liabilities(asset: &str, user_balances: u128) -> AssetLiabilities {
+        AssetLiabilities {
+            asset: asset.to_string(),
+            user_balances: Amount::from_tokens(user_balances),
+            ..AssetLiabilities::default()
+        }
+    }
+
+    #[test]
+    fn test_reports_hash_their_contents_in_asset_order() {
+        let report = ProofOfReserves::new(BlockHeight(7), vec![liabilities("USDC", 10), liabilities("ETH", 2)]);
+        let assets: Vec<&str> = report.assets.iter().map(|liabilities| liabilities.asset.as_str()).collect();
+        assert_eq!(assets, vec!["ETH", "USDC"]);
+
+        // The order figures arrive in does not matter, the figures and height do
+        let reordered = ProofOfReserves::new(BlockHeight(7), vec![liabilities("ETH", 2), liabilities("USDC", 10)]);
+        assert_eq!(reordered.hash, report.hash);
+        let later = ProofOfReserves::new(BlockHeight(8), report.assets.clone());
+        assert_ne!(later.hash, report.hash);
+        let changed = ProofOfReserves::new(BlockHeight(7), vec![liabilities("ETH", 2), liabilities("USDC", 11)]);
+        assert_ne!(changed.hash, report.hash);
+    }
+}
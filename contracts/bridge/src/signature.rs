@@ -0,0 +1,171 @@
+//! Verification of external address ownership proofs, per chain signature scheme.
+
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey as Ed25519VerifyingKey};
+use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey as EcdsaVerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Signature scheme used to prove control of an external address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureScheme {
+    /// Ethereum `personal_sign` (EIP-191) with a 65-byte recoverable secp256k1 signature
+    Eip191,
+    /// Ed25519 over the raw message, address is the base58 public key (Solana)
+    Ed25519,
+}
+
+/// Signature verification errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("Malformed signature")]
+    MalformedSignature,
+
+    #[error("Malformed address")]
+    MalformedAddress,
+
+    #[error("Signature does not match address")]
+    Mismatch,
+}
+
+/// Verifies that `signature` over `message` was produced by the owner of `address`.
+pub fn verify_ownership(
+    scheme: SignatureScheme,
+    address: &str,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    match scheme {
+        SignatureScheme::Eip191 => verify_eip191(address, message, signature),
+        SignatureScheme::Ed25519 => verify_ed25519(address, message, signature),
+    }
+}
+
+/// Hash signed by `personal_sign`: keccak256("\x19Ethereum Signed Message:\n" || len || message).
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"\x19Ethereum Signed Message:\n");
+    hasher.update(message.len().to_string().as_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Recovers the 20-byte EVM address that produced an EIP-191 signature.
+pub fn recover_evm_address(message: &[u8], signature: &[u8]) -> Result<[u8; 20], SignatureError> {
+    if signature.len() != 65 {
+        return Err(SignatureError::MalformedSignature);
+    }
+    let v = match signature[64] {
+        27 | 28 => signature[64] - 27,
+        0 | 1 => signature[64],
+        _ => return Err(SignatureError::MalformedSignature),
+    };
+    let recovery_id = RecoveryId::from_byte(v).ok_or(SignatureError::MalformedSignature)?;
+    let ecdsa_signature =
+        EcdsaSignature::from_slice(&signature[..64]).map_err(|_| SignatureError::MalformedSignature)?;
+
+    let key = EcdsaVerifyingKey::recover_from_prehash(&eip191_hash(message), &ecdsa_signature, recovery_id)
+        .map_err(|_| SignatureError::Mismatch)?;
+    let point = key.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+pub fn verify_eip191(address: &str, message: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+    let expected = parse_evm_address(address).ok_or(SignatureError::MalformedAddress)?;
+    if recover_evm_address(message, signature)? == expected {
+        Ok(())
+    } else {
+        Err(SignatureError::Mismatch)
+    }
+}
+
+pub fn verify_ed25519(address: &str, message: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+    let public_key: [u8; 32] = decode_base58(address)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SignatureError::MalformedAddress)?;
+    let key = Ed25519VerifyingKey::from_bytes(&public_key).map_err(|_| SignatureError::MalformedAddress)?;
+    let signature = Ed25519Signature::from_slice(signature).map_err(|_| SignatureError::MalformedSignature)?;
+    key.verify_strict(message, &signature).map_err(|_| SignatureError::Mismatch)
+}
+
+fn parse_evm_address(address: &str) -> Option<[u8; 20]> {
+    let hex = address.strip_prefix("0x")?;
+    if hex.len() != 40 {
+        return None;
+    }
+    let mut bytes = [0u8; 20];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Decodes a base58 (Bitcoin alphabet) string.
+pub fn decode_base58(input: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    // Leading '1's encode leading zero bytes
+    bytes.extend(input.bytes().take_while(|&c| c == b'1').map(|_| 0));
+    bytes.reverse();
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_eip191_known_signature() {
+        let signature = hex(
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+             6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c",
+        );
+        let address = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+        assert_eq!(verify_eip191(address, b"Some data", &signature), Ok(()));
+        assert_eq!(verify_eip191(address, b"Other data", &signature), Err(SignatureError::Mismatch));
+        assert_eq!(
+            verify_eip191(address, b"Some data", &signature[..64]),
+            Err(SignatureError::MalformedSignature)
+        );
+    }
+
+    #[test]
+    fn test_ed25519_rfc8032_vector() {
+        let signature = hex(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555\
+             fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        );
+        let address = "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z";
+
+        assert_eq!(verify_ed25519(address, b"", &signature), Ok(()));
+        assert_eq!(verify_ed25519(address, b"x", &signature), Err(SignatureError::Mismatch));
+    }
+
+    #[test]
+    fn test_base58_leading_zeros() {
+        assert_eq!(decode_base58("1112"), Some(vec![0, 0, 0, 1]));
+        assert_eq!(decode_base58("0OIl"), None);
+    }
+}
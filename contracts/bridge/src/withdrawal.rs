@@ -0,0 +1,329 @@
+//! Withdrawal preconditions, shared by `InitiateWithdrawal` and the `ValidateWithdrawal` dry run.
+
+use axelarx_math::MathError;
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    next_processing_time, AddressFormat, BridgeError, ChainConfig, ExternalChain, FeeBreakdown, FeeOverride, FeeOverrideKey,
+    ProcessingWindow, TransferDirection,
+};
+
+/// A withdrawal as submitted, before any state is read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalRequest {
+    pub account: Account,
+    pub chain: ExternalChain,
+    pub destination_address: String,
+    pub asset: String,
+    pub amount: Amount,
+    pub memo: Option<String>,
+    pub fee_voucher: Option<String>,
+}
+
+impl WithdrawalRequest {
+    /// Override the fees are looked up under: the quoted voucher, else the account's own
+    pub fn fee_override_key(&self) -> FeeOverrideKey {
+        match &self.fee_voucher {
+            Some(code) => FeeOverrideKey::Voucher(code.clone()),
+            None => FeeOverrideKey::Account(self.account),
+        }
+    }
+}
+
+/// State a withdrawal is checked against, read by `BridgeState::withdrawal_context`
+#[derive(Debug, Clone)]
+pub struct WithdrawalContext {
+    pub paused: bool,
+    pub indebted: bool,
+    pub config: Option<ChainConfig>,
+    pub address_format: AddressFormat,
+    /// Terms stored under the request's `fee_override_key`, usable or not
+    pub fee_override: Option<FeeOverride>,
+    /// Token application burning the asset; the bridge balance is only checked without one
+    pub token_application: Option<ApplicationId>,
+    pub balance: Amount,
+    /// The chain's processing windows; empty processes at any time
+    pub processing_windows: Vec<ProcessingWindow>,
+    pub now: Timestamp,
+}
+
+/// Reason a withdrawal would be refused
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WithdrawalIssue {
+    Paused,
+    OutstandingDebt,
+    ChainNotConfigured,
+    ChainDisabled,
+    AssetNotSupported,
+    BelowMinimum { minimum: Amount },
+    AboveMaximum { maximum: Amount },
+    InvalidAddress,
+    MemoRequired,
+    FeeVoucherUnavailable,
+    FeeOverflow,
+    InsufficientBalance { available: Amount },
+}
+
+impl WithdrawalIssue {
+    /// Error `InitiateWithdrawal` fails with
+    pub fn error(&self, request: &WithdrawalRequest) -> BridgeError {
+        let chain = request.chain;
+        match self {
+            WithdrawalIssue::Paused => BridgeError::Paused,
+            WithdrawalIssue::OutstandingDebt => BridgeError::OutstandingDebt { account: request.account },
+            WithdrawalIssue::ChainNotConfigured => BridgeError::ChainNotConfigured { chain },
+            WithdrawalIssue::ChainDisabled => BridgeError::ChainDisabled { chain },
+            WithdrawalIssue::AssetNotSupported => BridgeError::AssetNotSupported { asset: request.asset.clone(), chain },
+            WithdrawalIssue::BelowMinimum { minimum } => {
+                BridgeError::BelowMinimum { amount: request.amount, minimum: *minimum }
+            }
+            WithdrawalIssue::AboveMaximum { maximum } => {
+                BridgeError::AboveMaximum { amount: request.amount, maximum: *maximum }
+            }
+            WithdrawalIssue::InvalidAddress => BridgeError::InvalidAddress { address: request.destination_address.clone() },
+            WithdrawalIssue::MemoRequired => BridgeError::MemoRequired { chain },
+            WithdrawalIssue::FeeVoucherUnavailable => {
+                BridgeError::FeeVoucherUnavailable { code: request.fee_voucher.clone().unwrap_or_default() }
+            }
+            WithdrawalIssue::FeeOverflow => BridgeError::Math(MathError::Overflow),
+            WithdrawalIssue::InsufficientBalance { available } => {
+                BridgeError::InsufficientBalance { required: request.amount, available: *available }
+            }
+        }
+    }
+}
+
+/// Fees a valid withdrawal is charged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawalQuote {
+    pub fees: FeeBreakdown,
+    pub config_version: u64,
+    /// Negotiated terms applied; a voucher is spent by the withdrawal
+    pub fee_override: Option<FeeOverrideKey>,
+    /// When the withdrawal enters approval, if it falls outside the chain's processing windows
+    #[serde(default)]
+    pub release_at: Option<Timestamp>,
+    /// The chain's outbound expiry, snapshotted on the withdrawal
+    #[serde(default)]
+    pub expiry_seconds: u64,
+    /// When the withdrawal expires unless executed, counted from its release
+    #[serde(default)]
+    pub expires_at: Timestamp,
+}
+
+/// Runs every withdrawal check, returning the quote or all failed checks in the order
+/// `InitiateWithdrawal` reports them.
+pub fn validate_withdrawal(
+    request: &WithdrawalRequest,
+    context: &WithdrawalContext,
+) -> Result<WithdrawalQuote, Vec<WithdrawalIssue>> {
+    let mut issues = Vec::new();
+    if context.paused {
+        issues.push(WithdrawalIssue::Paused);
+    }
+    if context.indebted {
+        issues.push(WithdrawalIssue::OutstandingDebt);
+    }
+
+    let config = match &context.config {
+        Some(config) => {
+            if !config.is_enabled {
+                issues.push(WithdrawalIssue::ChainDisabled);
+            }
+            if !config.supported_assets.iter().any(|mapping| mapping.linera_asset == request.asset) {
+                issues.push(WithdrawalIssue::AssetNotSupported);
+            }
+            if request.amount < config.min_transfer_amount {
+                issues.push(WithdrawalIssue::BelowMinimum { minimum: config.min_transfer_amount });
+            }
+            if request.amount > config.max_transfer_amount {
+                issues.push(WithdrawalIssue::AboveMaximum { maximum: config.max_transfer_amount });
+            }
+            Some(config)
+        }
+        None => {
+            issues.push(WithdrawalIssue::ChainNotConfigured);
+            None
+        }
+    };
+
+    if !context.address_format.validate(&request.destination_address) {
+        issues.push(WithdrawalIssue::InvalidAddress);
+    }
+    let memo_missing = request.memo.as_deref().is_none_or(str::is_empty);
+    if config.is_some_and(|config| config.memo_required) && memo_missing {
+        issues.push(WithdrawalIssue::MemoRequired);
+    }
+
+    let key = request.fee_override_key();
+    let terms = context.fee_override.as_ref().filter(|terms| terms.is_usable(&key, context.now));
+    if request.fee_voucher.is_some() && terms.is_none() {
+        issues.push(WithdrawalIssue::FeeVoucherUnavailable);
+    }
+    let fees = config.and_then(|config| {
+        let fees = match terms {
+            Some(terms) => terms.fee_breakdown(config, request.amount, TransferDirection::Outbound),
+            None => config.fee_breakdown(request.amount, TransferDirection::Outbound),
+        };
+        if fees.is_err() {
+            issues.push(WithdrawalIssue::FeeOverflow);
+        }
+        fees.ok()
+    });
+
+    if context.token_application.is_none() && context.balance < request.amount {
+        issues.push(WithdrawalIssue::InsufficientBalance { available: context.balance });
+    }
+
+    match (config, fees) {
+        (Some(config), Some(fees)) if issues.is_empty() => {
+            let release_at = Some(next_processing_time(&context.processing_windows, context.now))
+                .filter(|release_at| *release_at > context.now);
+            let expiry_seconds = config.outbound_expiry_seconds;
+            Ok(WithdrawalQuote {
+                fees,
+                config_version: config.version,
+                fee_override: terms.map(|_| key),
+                release_at,
+                expiry_seconds,
+                expires_at: release_at.unwrap_or(context.now) + std::time::Duration::from_secs(expiry_seconds),
+            })
+        }
+        _ => Err(issues),
+    }
+}
+
+/// `validate_withdrawal` as `InitiateWithdrawal` applies it: the first failed check is the error.
+pub fn check_withdrawal(request: &WithdrawalRequest, context: &WithdrawalContext) -> Result<WithdrawalQuote, BridgeError> {
+    validate_withdrawal(request, context).map_err(|issues| issues[0].error(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AssetMapping;
+    use linera_base::identifiers::ChainId;
+    use proptest::prelude::*;
+
+    const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+    fn request(amount: u128) -> WithdrawalRequest {
+        WithdrawalRequest {
+            account: Account::chain(ChainId::root(0)),
+            chain: ExternalChain::Ethereum,
+            destination_address: ADDRESS.to_string(),
+            asset: "USDC".to_string(),
+            amount: Amount::from(amount),
+            memo: None,
+            fee_voucher: None,
+        }
+    }
+
+    fn context(balance: u128) -> WithdrawalContext {
+        let mut config = crate::tests::test_chain_config(0);
+        config.supported_assets.push(AssetMapping {
+            linera_asset: "USDC".to_string(),
+            external_asset: "USDC".to_string(),
+            external_contract_address: None,
+            decimals_linera: 18,
+            decimals_external: 6,
+            is_native: false,
+        });
+        WithdrawalContext {
+            paused: false,
+            indebted: false,
+            config: Some(config),
+            address_format: AddressFormat::Evm,
+            fee_override: None,
+            token_application: None,
+            balance: Amount::from(balance),
+            processing_windows: Vec::new(),
+            now: Timestamp::from(0),
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_reports_every_issue() {
+        let quote = validate_withdrawal(&request(10_000), &context(10_000)).unwrap();
+        assert_eq!(quote.fees.total_fee, Amount::from(130));
+        assert_eq!(quote.config_version, 1);
+
+        let mut bad = request(2_000_000_000);
+        bad.destination_address = "0x1234".to_string();
+        bad.fee_voucher = Some("OTC-1".to_string());
+        let issues = validate_withdrawal(&bad, &context(10_000)).unwrap_err();
+        assert_eq!(issues, vec![
+            WithdrawalIssue::AboveMaximum { maximum: Amount::from(1_000_000_000) },
+            WithdrawalIssue::InvalidAddress,
+            WithdrawalIssue::FeeVoucherUnavailable,
+            WithdrawalIssue::InsufficientBalance { available: Amount::from(10_000) },
+        ]);
+        assert!(matches!(check_withdrawal(&bad, &context(10_000)), Err(BridgeError::AboveMaximum { .. })));
+    }
+
+    fn arbitrary_case() -> impl Strategy<Value = (WithdrawalRequest, WithdrawalContext)> {
+        (
+            (0u128..2_000_000_000, 0u128..2_000_000_000, any::<bool>(), any::<bool>(), any::<bool>()),
+            (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()),
+            (proptest::option::of(0u64..2), proptest::option::of((0u128..1_000, 0u64..=10_000, 0u64..2))),
+        )
+            .prop_map(|(
+                (amount, balance, paused, indebted, configured),
+                (enabled, supported, memo_required, memo, valid_address),
+                (voucher, fee_override),
+            )| {
+                let mut request = request(amount);
+                if !valid_address {
+                    request.destination_address = "bc1-not-evm".to_string();
+                }
+                request.memo = memo.then(|| "tag".to_string());
+                request.fee_voucher = voucher.map(|n| format!("OTC-{n}"));
+
+                let mut context = context(balance);
+                context.paused = paused;
+                context.indebted = indebted;
+                context.fee_override = fee_override.map(|(base_fee, bps, uses)| FeeOverride {
+                    base_fee: Amount::from(base_fee),
+                    fee_percentage_bps: bps,
+                    expires_at: Timestamp::from(1),
+                    uses,
+                });
+                match &mut context.config {
+                    Some(config) if configured => {
+                        config.is_enabled = enabled;
+                        config.memo_required = memo_required;
+                        if !supported {
+                            config.supported_assets.clear();
+                        }
+                    }
+                    _ => context.config = None,
+                }
+                (request, context)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_dry_run_matches_initiate((request, context) in arbitrary_case()) {
+            match (validate_withdrawal(&request, &context), check_withdrawal(&request, &context)) {
+                (Ok(dry_run), Ok(initiated)) => {
+                    prop_assert_eq!(&dry_run, &initiated);
+                    let config = context.config.as_ref().unwrap();
+                    prop_assert!(config.is_enabled && !context.paused && !context.indebted);
+                    prop_assert!(config.min_transfer_amount <= request.amount && request.amount <= config.max_transfer_amount);
+                    prop_assert!(context.balance >= request.amount);
+                    prop_assert!(dry_run.fees.total_fee <= config.fee_breakdown(request.amount, TransferDirection::Outbound).unwrap().total_fee);
+                }
+                (Err(issues), Err(error)) => {
+                    prop_assert!(!issues.is_empty());
+                    prop_assert_eq!(issues[0].error(&request).to_string(), error.to_string());
+                }
+                (dry_run, initiated) => prop_assert!(false, "dry run {:?} but initiate {:?}", dry_run, initiated),
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+/*!
+# AxelarX Integration Test Harness
+
+Deploys the orderbook, settlement and bridge applications on a Linera `TestValidator`
+and provides helpers shared by the end-to-end tests in `tests/`.
+
+Each test chain has a single owner, so every user gets their own chain. The applications'
+admin role is held by the creator chain's owner, so tests of admin-only operations run on
+`Deployment::admin`; an order book market on a user's chain gets its admin from the creator
+chain's `AppointChainAdmin`. Tests that rely on message wiring that is still stubbed (order routing to
+the market chain, the orderbook's `SettlementRequest`) or on an admin beyond the creator chain
+are `#[ignore]`d with the missing piece named in the reason. Order book
+behaviour that needs no routing is exercised on a single chain, with one user on both sides.
+*/
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{AssetMapping, BridgeAbi, ChainConfig, ExternalChain, ValidatorConfig};
+use axelarx_orderbook::{
+    Operation as OrderBookOperation, OrderBookAbi, OrderSide, OrderType, Price, Quantity, TimeInForce,
+};
+use axelarx_settlement::{ConfirmationTerms, Operation as SettlementOperation, SettlementAbi, SettlementFees};
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::{Account, ApplicationId, ChainId},
+};
+use linera_sdk::test::{ActiveChain, TestValidator};
+
+/// Asset bridged in the tests
+pub const TEST_ASSET: &str = "USDC";
+
+/// The three applications, created on the admin chain
+pub struct Deployment {
+    pub validator: TestValidator,
+    /// Creator chain; its owner is the admin of all three applications, so admin-only operations run here
+    pub admin: ActiveChain,
+    pub orderbook: ApplicationId<OrderBookAbi>,
+    pub settlement: ApplicationId<SettlementAbi>,
+    pub bridge: ApplicationId<BridgeAbi>,
+}
+
+impl Deployment {
+    pub async fn new() -> Self {
+        let validator = TestValidator::default();
+        let mut admin = validator.new_chain().await;
+
+        let orderbook_bytecode = admin.publish_bytecodes_in("../orderbook").await;
+        let settlement_bytecode = admin.publish_bytecodes_in("../settlement").await;
+        let bridge_bytecode = admin.publish_bytecodes_in("../bridge").await;
+
+        let orderbook = admin.create_application(orderbook_bytecode, (), owner_account(&admin), vec![]).await;
+        let settlement = admin.create_application(settlement_bytecode, (), owner_account(&admin), vec![]).await;
+        let bridge = admin.create_application(bridge_bytecode, (), owner_account(&admin), vec![]).await;
+
+        Deployment { validator, admin, orderbook, settlement, bridge }
+    }
+
+    /// A fresh chain owned by a new user
+    pub async fn new_user(&self) -> ActiveChain {
+        self.validator.new_chain().await
+    }
+}
+
+/// The account that signs blocks on `chain`
+pub fn owner_account(chain: &ActiveChain) -> Account {
+    Account {
+        chain_id: chain.id(),
+        owner: Some(chain.public_key().into()),
+    }
+}
+
+/// `OrderBookOperation::PlaceOrder` fields; tests start from `place_order` and override the
+/// fields they exercise
+#[derive(Clone, Debug)]
+pub struct OrderPlacement {
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub time_in_force: TimeInForce,
+    pub expires_at: Option<Timestamp>,
+    pub require_full_fill: bool,
+    pub min_fill_quantity: Option<Quantity>,
+    pub client_request_id: Option<u64>,
+    pub on_behalf_of: Option<Account>,
+    pub sweep_exempt: bool,
+}
+
+/// Good-till-cancelled limit order with every option off
+pub fn place_order(side: OrderSide, price: Price, quantity: Quantity) -> OrderPlacement {
+    OrderPlacement {
+        side,
+        order_type: OrderType::Limit,
+        price,
+        quantity,
+        time_in_force: TimeInForce::GTC,
+        expires_at: None,
+        require_full_fill: false,
+        min_fill_quantity: None,
+        client_request_id: None,
+        on_behalf_of: None,
+        sweep_exempt: false,
+    }
+}
+
+impl From<OrderPlacement> for OrderBookOperation {
+    fn from(order: OrderPlacement) -> Self {
+        OrderBookOperation::PlaceOrder {
+            side: order.side,
+            order_type: order.order_type,
+            price: order.price,
+            quantity: order.quantity,
+            time_in_force: order.time_in_force,
+            expires_at: order.expires_at,
+            require_full_fill: order.require_full_fill,
+            min_fill_quantity: order.min_fill_quantity,
+            client_request_id: order.client_request_id,
+            on_behalf_of: order.on_behalf_of,
+            sweep_exempt: order.sweep_exempt,
+        }
+    }
+}
+
+/// `SettlementOperation::InitiateSettlement` fields; tests start from `initiate_settlement` and
+/// override the fields they exercise
+#[derive(Clone, Debug)]
+pub struct SettlementTerms {
+    pub trade_id: u64,
+    pub maker: Account,
+    pub taker: Account,
+    pub maker_asset: String,
+    pub taker_asset: String,
+    pub maker_amount: Amount,
+    pub taker_amount: Amount,
+    pub maker_chain: ChainId,
+    pub taker_chain: ChainId,
+    pub timeout_seconds: u64,
+    pub fees: Option<SettlementFees>,
+    pub windowed: bool,
+    pub client_request_id: Option<u64>,
+    pub memo: Option<String>,
+    pub external_ref: Option<String>,
+    pub confirmation: Option<ConfirmationTerms>,
+    pub one_sided: bool,
+}
+
+/// 40 `TEST_ASSET` from `maker` against 1 BTC from `taker`, each escrowing on its own chain,
+/// expiring after an hour, with every option off
+pub fn initiate_settlement(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64) -> SettlementTerms {
+    SettlementTerms {
+        trade_id,
+        maker: owner_account(maker),
+        taker: owner_account(taker),
+        maker_asset: TEST_ASSET.to_string(),
+        taker_asset: "BTC".to_string(),
+        maker_amount: Amount::from_tokens(40),
+        taker_amount: Amount::from_tokens(1),
+        maker_chain: maker.id(),
+        taker_chain: taker.id(),
+        timeout_seconds: 3_600,
+        fees: None,
+        windowed: false,
+        client_request_id: None,
+        memo: None,
+        external_ref: None,
+        confirmation: None,
+        one_sided: false,
+    }
+}
+
+impl From<SettlementTerms> for SettlementOperation {
+    fn from(terms: SettlementTerms) -> Self {
+        SettlementOperation::InitiateSettlement {
+            trade_id: terms.trade_id,
+            maker: terms.maker,
+            taker: terms.taker,
+            maker_asset: terms.maker_asset,
+            taker_asset: terms.taker_asset,
+            maker_amount: terms.maker_amount,
+            taker_amount: terms.taker_amount,
+            maker_chain: terms.maker_chain,
+            taker_chain: terms.taker_chain,
+            timeout_seconds: terms.timeout_seconds,
+            fees: terms.fees,
+            windowed: terms.windowed,
+            client_request_id: terms.client_request_id,
+            memo: terms.memo,
+            external_ref: terms.external_ref,
+            confirmation: terms.confirmation,
+            one_sided: terms.one_sided,
+        }
+    }
+}
+
+/// Ethereum bridge config supporting `TEST_ASSET`, with no quarantine
+pub fn ethereum_config() -> ChainConfig {
+    ChainConfig {
+        chain: ExternalChain::Ethereum,
+        is_enabled: true,
+        bridge_contract_address: "0x52908400098527886E0F7030069857D2E4169EE7".to_string(),
+        deprecated_addresses: vec![],
+        supported_assets: vec![AssetMapping {
+            linera_asset: TEST_ASSET.to_string(),
+            external_asset: TEST_ASSET.to_string(),
+            external_contract_address: Some("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string()),
+            decimals_linera: 18,
+            decimals_external: 6,
+            is_native: false,
+        }],
+        min_transfer_amount: Amount::from_tokens(1),
+        max_transfer_amount: Amount::from_tokens(1_000_000),
+        base_fee: Amount::from_tokens(1),
+        fee_percentage_bps: 30,
+        relayer_gas_fee: Amount::ZERO,
+        required_confirmations: 12,
+        finality: None,
+        estimated_time_seconds: 900,
+        memo_required: false,
+        large_transfer_threshold: None,
+        quarantine_seconds: 0,
+        inbound_expiry_seconds: 24 * 3600,
+        outbound_expiry_seconds: 24 * 3600,
+        version: 0,
+    }
+}
+
+/// Single full-weight validator: `chain`'s owner approves every transfer alone
+pub fn sole_validator(chain: &ActiveChain) -> ValidatorConfig {
+    ValidatorConfig {
+        address: owner_account(chain),
+        public_key: chain.public_key().as_bytes().to_vec(),
+        is_active: true,
+        weight: 1,
+        registered_at: Timestamp::from(0),
+    }
+}
@@ -0,0 +1,87 @@
+//! Abandoned deposits: a deposit expiring short of finality is flagged for release on its source chain, and a validator records the refund.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeEvent, ExternalChain, Operation, Query, QueryResponse, TransferStatus};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::data_types::{Amount, TimeDelta};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn expired_confirming_deposit_is_refunded_externally() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xstuck".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                block_height: 100,
+                confirmations: 1,
+                bridge_contract_address: None,
+            });
+    }).await;
+
+    // Nothing is abandoned, or refundable, before the deposit expires
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ReportExternalRefund {
+            transfer_id: 1,
+            refund_tx_hash: "0xrefund".to_string(),
+        });
+    }).await;
+    assert!(result.is_err());
+
+    deployment.validator.clock().add(TimeDelta::from_secs(24 * 3600 + 1));
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ProcessExpiredTransfers);
+    }).await;
+    match user.query(bridge, Query::GetAbandonedDeposits).await {
+        QueryResponse::AbandonedDeposits(ids) => assert_eq!(ids, vec![1]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => match events.as_slice() {
+            [BridgeEvent::InboundAbandoned { transfer_id: 1, source_tx_hash, confirmations: 1, .. }] => {
+                assert_eq!(source_tx_hash.as_deref(), Some("0xstuck"));
+            }
+            other => panic!("unexpected events: {other:?}"),
+        },
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ReportExternalRefund {
+            transfer_id: 1,
+            refund_tx_hash: "0xrefund".to_string(),
+        });
+    }).await;
+    match user.query(bridge, Query::GetTransfer { transfer_id: 1 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.status, TransferStatus::Refunded);
+            let refund = transfer.external_refund.expect("the refund is recorded");
+            assert_eq!((refund.tx_hash.as_str(), refund.reported_by), ("0xrefund", account));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetAbandonedDeposits).await {
+        QueryResponse::AbandonedDeposits(ids) => assert!(ids.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The refund is only recorded once
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ReportExternalRefund {
+            transfer_id: 1,
+            refund_tx_hash: "0xrefund".to_string(),
+        });
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,63 @@
+//! Admin roles: the creator chain's owner holds each application's admin role from instantiation,
+//! so no other account can claim it.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::Operation as BridgeOperation;
+use axelarx_integration_tests::{owner_account, Deployment};
+use axelarx_orderbook::Operation as OrderBookOperation;
+use axelarx_settlement::Operation as SettlementOperation;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bridge_admin_cannot_be_claimed() {
+    let deployment = Deployment::new().await;
+    let mut admin = deployment.admin.clone();
+    let mut user = deployment.new_user().await;
+    let bridge = deployment.bridge;
+    let claim = BridgeOperation::TransferAdmin { new_admin: owner_account(&user) };
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, claim.clone());
+    }).await;
+    assert!(result.is_err());
+
+    admin.add_block(|block| {
+        block.with_operation(bridge, claim);
+    }).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn orderbook_admin_cannot_be_claimed() {
+    let deployment = Deployment::new().await;
+    let mut admin = deployment.admin.clone();
+    let mut user = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+    let claim = OrderBookOperation::TransferAdmin { new_admin: owner_account(&user) };
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, claim.clone());
+    }).await;
+    assert!(result.is_err());
+
+    admin.add_block(|block| {
+        block.with_operation(orderbook, claim);
+    }).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settlement_admin_cannot_be_claimed() {
+    let deployment = Deployment::new().await;
+    let mut admin = deployment.admin.clone();
+    let mut user = deployment.new_user().await;
+    let settlement = deployment.settlement;
+    let claim = SettlementOperation::TransferAdmin { new_admin: owner_account(&user) };
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(settlement, claim.clone());
+    }).await;
+    assert!(result.is_err());
+
+    admin.add_block(|block| {
+        block.with_operation(settlement, claim);
+    }).await;
+}
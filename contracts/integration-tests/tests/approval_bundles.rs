@@ -0,0 +1,84 @@
+//! Approval bundles: an approved withdrawal's payload and quorum signatures, unchanged by later validator set changes.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+async fn bundle_response(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> QueryResponse {
+    user.query(bridge, Query::GetApprovalBundle { transfer_id: 2 }).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundles_keep_the_approving_validators() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let other = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            });
+    }).await;
+
+    // Nothing to submit before quorum, nor for deposits
+    match bundle_response(&user, bridge).await {
+        QueryResponse::Error(message) => assert!(message.contains("No approval bundle")),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetApprovalBundle { transfer_id: 1 }).await {
+        QueryResponse::Error(message) => assert!(message.contains("No approval bundle")),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![7; 65] });
+    }).await;
+    let payload = match user.query(bridge, Query::GetApprovalPayload { transfer_id: 2 }).await {
+        QueryResponse::ApprovalPayload(payload) => payload,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    let bundle = match bundle_response(&user, bridge).await {
+        QueryResponse::ApprovalBundle(bundle) => bundle,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!(bundle.payload, payload);
+    assert_eq!(bundle.signatures.len(), 1);
+    assert_eq!(bundle.signatures[0].validator, account);
+    assert_eq!(bundle.signatures[0].external_key, sole_validator(&user).public_key);
+    assert_eq!(bundle.signatures[0].signature, vec![7; 65]);
+    assert_eq!((bundle.quorum.epoch, bundle.quorum.total_weight, bundle.quorum.threshold), (1, 1, 0));
+
+    // A new validator changes the set, not the bundle
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::AddValidator { config: sole_validator(&other) });
+    }).await;
+    match bundle_response(&user, bridge).await {
+        QueryResponse::ApprovalBundle(regenerated) => assert_eq!(regenerated, bundle),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,82 @@
+//! Approval pre-validation: validators see the payload they would sign, the transfer's consistency checks, and whether they may still vote.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    ApprovalIneligibility, ApprovalPrevalidation, BridgeAbi, ExternalChain, Operation, Query, QueryResponse,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+async fn prevalidation(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, validator: Account) -> ApprovalPrevalidation {
+    let query = Query::ValidateForApproval { transfer_id: 2, validator, at: Timestamp::from(0) };
+    match user.query(bridge, query).await {
+        QueryResponse::ApprovalPrevalidation(prevalidation) => prevalidation,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validators_prevalidate_before_signing() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let other = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            });
+    }).await;
+
+    // The payload is the one the contract verifies, and every check passes
+    let before = prevalidation(&user, bridge, account).await;
+    match user.query(bridge, Query::GetApprovalPayload { transfer_id: 2 }).await {
+        QueryResponse::ApprovalPayload(payload) => assert_eq!(before.payload, Some(payload)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(before.checks.len(), 5);
+    assert!(before.ready_to_sign());
+    assert_eq!(prevalidation(&user, bridge, owner_account(&other)).await.ineligible, vec![
+        ApprovalIneligibility::NotRegistered,
+    ]);
+
+    // A validator that voted may not vote again
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![7; 65] });
+    }).await;
+    let after = prevalidation(&user, bridge, account).await;
+    assert_eq!(after.ineligible, vec![ApprovalIneligibility::AlreadyVoted]);
+    assert!(after.checks.iter().all(|result| result.passed));
+    assert!(!after.ready_to_sign());
+
+    match user.query(bridge, Query::ValidateForApproval { transfer_id: 9, validator: account, at: Timestamp::from(0) }).await {
+        QueryResponse::Error(message) => assert!(message.contains("not found")),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
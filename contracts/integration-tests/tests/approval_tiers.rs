@@ -0,0 +1,132 @@
+//! Approval tiers: the quorum a transfer needs depends on its amount, a tier can require senior validators on top of weight, and transfers keep the tier they were created under.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    ApprovalRequirement, ApprovalTier, ApprovalTiers, BridgeAbi, ExternalChain, Operation, Query, QueryResponse,
+    TransferId, TransferStatus,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+use std::collections::BTreeMap;
+
+fn withdraw(tokens: u128) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(tokens),
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+/// Half the weight up to 100 tokens of the test asset, valued at par; above that, half the weight
+/// and the senior validator
+fn tiers(senior: Account) -> ApprovalTiers {
+    ApprovalTiers {
+        tiers: vec![
+            ApprovalTier {
+                max_amount: Some(Amount::from_tokens(100)),
+                requirement: ApprovalRequirement {
+                    threshold_percentage: 50,
+                    senior_validators: vec![],
+                    min_senior_approvals: 0,
+                },
+            },
+            ApprovalTier {
+                max_amount: None,
+                requirement: ApprovalRequirement {
+                    threshold_percentage: 50,
+                    senior_validators: vec![senior],
+                    min_senior_approvals: 1,
+                },
+            },
+        ],
+        asset_rates: BTreeMap::from([(TEST_ASSET.to_string(), 100_000_000)]),
+    }
+}
+
+async fn status(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: TransferId) -> TransferStatus {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer.status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quorum_follows_the_snapshotted_tier() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let senior_chain = deployment.new_user().await;
+    let account = owner_account(&user);
+    let senior = owner_account(&senior_chain);
+    let bridge = deployment.bridge;
+
+    // The top tier must be unbounded
+    let mut bounded = tiers(senior);
+    bounded.tiers[1].max_amount = Some(Amount::from_tokens(1_000));
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::SetApprovalTiers { chain: ExternalChain::Ethereum, tiers: bounded });
+    }).await;
+    assert!(result.is_err());
+
+    // Two validators of weight 1; the user approves alone
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&senior_chain) })
+            .with_operation(bridge, Operation::SetApprovalTiers {
+                chain: ExternalChain::Ethereum,
+                tiers: tiers(senior),
+            })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw(100))
+            .with_operation(bridge, withdraw(101));
+    }).await;
+    match user.query(bridge, Query::GetApprovalTiers { chain: ExternalChain::Ethereum }).await {
+        QueryResponse::ApprovalTiers(configured) => assert_eq!(configured, Some(tiers(senior))),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // At the boundary the lower tier applies; just above it the weight is there but the senior is not
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![7; 65] })
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 3, signature: vec![7; 65] });
+    }).await;
+    assert_eq!(status(&user, bridge, 2).await, TransferStatus::Approved);
+    assert_eq!(status(&user, bridge, 3).await, TransferStatus::AwaitingApproval);
+
+    // Without tiers, new transfers fall back to the bridge-wide percentage; earlier ones keep theirs
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RemoveApprovalTiers { chain: ExternalChain::Ethereum })
+            .with_operation(bridge, withdraw(101))
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 4, signature: vec![7; 65] });
+    }).await;
+    assert_eq!(status(&user, bridge, 4).await, TransferStatus::Approved);
+    assert_eq!(status(&user, bridge, 3).await, TransferStatus::AwaitingApproval);
+    match user.query(bridge, Query::GetTransfer { transfer_id: 3 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.approval_requirement, Some(tiers(senior).tiers[1].requirement.clone()));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
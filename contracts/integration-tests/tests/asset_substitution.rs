@@ -0,0 +1,79 @@
+//! Asset substitution: escrow in another asset needs a fresh published rate for the pair.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain) -> Operation {
+    SettlementTerms { maker_amount: Amount::from_tokens(100), ..initiate_settlement(maker, taker, 1) }.into()
+}
+
+fn confirm_in_dai() -> Operation {
+    Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: Some("DAI".to_string()), bridge_transfer_id: None }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn substitutes_escrow_at_the_published_rate() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.admin.clone();
+    let taker = deployment.new_user().await;
+    let account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    // No rate for the pair yet
+    let result = maker.try_add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: "DAI".to_string(), amount: Amount::from_tokens(200) })
+            .with_operation(settlement, initiate(&maker, &taker))
+            .with_operation(settlement, confirm_in_dai());
+    }).await;
+    assert!(result.is_err());
+
+    // A rate of zero is rejected
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::SetConversionRate {
+            from_asset: TEST_ASSET.to_string(),
+            to_asset: "DAI".to_string(),
+            rate: 0,
+            max_staleness_seconds: 600,
+        });
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetConversionRate {
+                from_asset: TEST_ASSET.to_string(),
+                to_asset: "DAI".to_string(),
+                rate: 150_000_000,
+                max_staleness_seconds: 600,
+            })
+            .with_operation(settlement, Operation::Deposit { asset: "DAI".to_string(), amount: Amount::from_tokens(200) })
+            .with_operation(settlement, initiate(&maker, &taker))
+            .with_operation(settlement, confirm_in_dai());
+    }).await;
+    let query = Query::GetConversionRate { from_asset: TEST_ASSET.to_string(), to_asset: "DAI".to_string() };
+    match maker.query(settlement, query).await {
+        QueryResponse::ConversionRate(Some(rate)) => assert_eq!(rate.updated_by, account),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // 100 USDC at 1.5 DAI each, recorded on the settlement
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert_eq!(record.maker_escrow.asset, "DAI");
+            assert_eq!(record.maker_escrow.amount, Amount::from_tokens(150));
+            let substitution = record.maker_escrow.substitution.unwrap();
+            assert_eq!(substitution.original_asset, TEST_ASSET);
+            assert_eq!(substitution.original_amount, Amount::from_tokens(100));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetBalance { account, asset: "DAI".to_string() }).await {
+        QueryResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(50)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
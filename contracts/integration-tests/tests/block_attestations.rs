@@ -0,0 +1,94 @@
+//! Block attestations: a block is finalized once validators holding a quorum of weight attest its hash, and validators attesting a different hash are flagged.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BlockHash, BridgeAbi, ExternalChain, Operation, Query, QueryResponse, ValidatorConfig};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment};
+use linera_base::identifiers::ApplicationId;
+use linera_sdk::test::ActiveChain;
+
+fn attest(height: u64, block_hash: BlockHash) -> Operation {
+    Operation::SubmitBlockAttestation { chain: ExternalChain::Ethereum, height, block_hash, signature: vec![] }
+}
+
+async fn finalized_block(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, height: u64) -> Option<BlockHash> {
+    match chain.query(bridge, Query::GetFinalizedBlock { chain: ExternalChain::Ethereum, height }).await {
+        QueryResponse::FinalizedBlock(block_hash) => block_hash,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn latest_finalized_height(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> Option<u64> {
+    match chain.query(bridge, Query::GetLatestFinalizedHeight { chain: ExternalChain::Ethereum }).await {
+        QueryResponse::LatestFinalizedHeight(height) => height,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_is_finalized_at_quorum() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let other = deployment.new_user().await;
+    let bridge = deployment.bridge;
+    let absent = ValidatorConfig { weight: 2, ..sole_validator(&other) };
+
+    // Two of three weight are needed, and the user's single unit is not enough
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::AddValidator { config: absent })
+            .with_operation(bridge, attest(100, [7; 32]));
+    }).await;
+    assert_eq!(finalized_block(&user, bridge, 100).await, None);
+    assert_eq!(latest_finalized_height(&user, bridge).await, None);
+
+    // A validator attests a height once
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, attest(100, [7; 32]));
+    }).await;
+    assert!(result.is_err());
+
+    // With the other validator gone the user's weight is a quorum
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RemoveValidator { validator: owner_account(&other) })
+            .with_operation(bridge, attest(101, [8; 32]));
+    }).await;
+    assert_eq!(finalized_block(&user, bridge, 101).await, Some([8; 32]));
+    assert_eq!(latest_finalized_height(&user, bridge).await, Some(101));
+    assert_eq!(finalized_block(&user, bridge, 100).await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conflicting_attestation_is_flagged() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let validator = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, attest(100, [7; 32]));
+    }).await;
+    assert_eq!(finalized_block(&user, bridge, 100).await, Some([7; 32]));
+
+    // A second attestation of the finalized height with another hash is flagged, not counted
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, attest(100, [9; 32]))
+            .with_operation(bridge, attest(100, [7; 32]));
+    }).await;
+    assert_eq!(finalized_block(&user, bridge, 100).await, Some([7; 32]));
+    match user.query(bridge, Query::GetValidatorFlags { validator }).await {
+        QueryResponse::ValidatorFlags(flags) => {
+            assert_eq!(flags.len(), 1);
+            assert_eq!((flags[0].chain, flags[0].height), (ExternalChain::Ethereum, 100));
+            assert_eq!((flags[0].attested_hash, flags[0].finalized_hash), ([9; 32], [7; 32]));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,59 @@
+//! Bridge contract rotation: the old contract keeps accepting deposits until its cutoff.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{ExternalChain, Operation, Query, QueryResponse};
+use axelarx_integration_tests::{ethereum_config, Deployment, TEST_ASSET};
+use linera_base::data_types::{Amount, TimeDelta};
+
+const NEW_ADDRESS: &str = "0xde0B295669a9FD93d5F28D9Ec85E40f4cb697BAe";
+
+fn deposit(tx_hash: &str, bridge_contract_address: &str) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: None,
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        block_height: 100,
+        confirmations: 12,
+        bridge_contract_address: Some(bridge_contract_address.to_string()),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn old_contract_accepts_deposits_until_cutoff() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let bridge = deployment.bridge;
+    let config = ethereum_config();
+    let old_address = config.bridge_contract_address.clone();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config })
+            .with_operation(bridge, Operation::RotateBridgeAddress {
+                chain: ExternalChain::Ethereum,
+                new_address: NEW_ADDRESS.to_string(),
+                overlap_seconds: 600,
+            })
+            .with_operation(bridge, deposit("0xold", &old_address))
+            .with_operation(bridge, deposit("0xnew", NEW_ADDRESS));
+    }).await;
+
+    let now = deployment.validator.clock().current_time();
+    let query = Query::GetAcceptedAddresses { chain: ExternalChain::Ethereum, at: now };
+    match user.query(bridge, query).await {
+        QueryResponse::AcceptedAddresses(addresses) => {
+            assert_eq!(addresses, vec![NEW_ADDRESS.to_string(), old_address.clone()]);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    deployment.validator.clock().add(TimeDelta::from_secs(600));
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, deposit("0xlate", &old_address));
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,83 @@
+//! Bridge overview: open transfer counts, the oldest open transfer and the 24h volume follow transfers as they change.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::collections::BTreeMap;
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeOverview, ExternalChain, Operation, Query, QueryResponse, TransferStatus, ValidatorSummary,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::{ActiveChain, TestValidator};
+
+fn deposit(tx_hash: &str, recipient: Account) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: Some(recipient),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        block_height: 100,
+        confirmations: 1,
+        bridge_contract_address: None,
+    }
+}
+
+async fn overview(validator: &TestValidator, user: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> BridgeOverview {
+    let at = validator.clock().current_time();
+    match user.query(bridge, Query::GetBridgeOverview { at }).await {
+        QueryResponse::BridgeOverview(overview) => overview,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn overview_tracks_open_transfers() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, deposit("0xfirst", account));
+    }).await;
+    deployment.validator.clock().add(TimeDelta::from_secs(12 * 3600));
+    user.add_block(|block| {
+        block.with_operation(bridge, deposit("0xsecond", account));
+    }).await;
+
+    let summary = overview(&deployment.validator, &user, bridge).await;
+    assert!(!summary.paused);
+    assert_eq!(summary.validators, ValidatorSummary { count: 1, inactive: 0, total_weight: 1 });
+    assert_eq!(summary.open_transfers, BTreeMap::from([(TransferStatus::Confirming, 2)]));
+    let oldest = summary.oldest_open_transfer.expect("two deposits are open");
+    assert_eq!((oldest.transfer_id, oldest.age_seconds), (1, 12 * 3600));
+    let [chain] = summary.chains.as_slice() else { panic!("one chain is configured") };
+    assert_eq!((chain.chain, chain.enabled), (ExternalChain::Ethereum, true));
+    assert_eq!(chain.open_transfers, summary.open_transfers);
+
+    // The first deposit expires, and leaves the volume window
+    deployment.validator.clock().add(TimeDelta::from_secs(12 * 3600 + 1));
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ProcessExpiredTransfers);
+    }).await;
+
+    let summary = overview(&deployment.validator, &user, bridge).await;
+    assert_eq!(summary.open_transfers, BTreeMap::from([(TransferStatus::Confirming, 1)]));
+    assert_eq!(summary.oldest_open_transfer.map(|oldest| oldest.transfer_id), Some(2));
+    let net_amount = match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer.net_amount,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    let volume = &summary.chains[0].volume_24h;
+    assert_eq!(volume.inbound, BTreeMap::from([(TEST_ASSET.to_string(), net_amount)]));
+    assert!(volume.outbound.is_empty());
+}
@@ -0,0 +1,95 @@
+//! Bridged balances move between accounts, and only applications spend the allowances owners give them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+async fn balance(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> Amount {
+    match chain.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => balance,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn allowance(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, owner: Account, spender: ApplicationId) -> Amount {
+    match chain.query(bridge, Query::GetAllowance { owner, spender, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Allowance(allowance) => allowance,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transfers_and_allowances() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let recipient = owner_account(&deployment.new_user().await);
+    let bridge = deployment.bridge;
+    let spender = deployment.orderbook.forget_abi();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xfunding".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            });
+    }).await;
+    let funded = balance(&user, bridge, account).await;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::Transfer {
+                to: recipient,
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(300),
+            })
+            .with_operation(bridge, Operation::Approve {
+                spender,
+                asset: TEST_ASSET.to_string(),
+                allowance: Amount::from_tokens(50),
+            });
+    }).await;
+    assert_eq!(balance(&user, bridge, account).await, funded - Amount::from_tokens(300));
+    assert_eq!(balance(&user, bridge, recipient).await, Amount::from_tokens(300));
+    assert_eq!(allowance(&user, bridge, account, spender).await, Amount::from_tokens(50));
+
+    // A signed operation has no calling application, so it cannot draw on an allowance
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::TransferFrom {
+            owner: account,
+            to: recipient,
+            asset: TEST_ASSET.to_string(),
+            amount: Amount::from_tokens(10),
+        });
+    }).await;
+    assert!(result.is_err());
+
+    // More than the balance is refused outright
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::Transfer {
+            to: recipient,
+            asset: TEST_ASSET.to_string(),
+            amount: funded,
+        });
+    }).await;
+    assert!(result.is_err());
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::Approve { spender, asset: TEST_ASSET.to_string(), allowance: Amount::ZERO });
+    }).await;
+    assert_eq!(allowance(&user, bridge, account, spender).await, Amount::ZERO);
+}
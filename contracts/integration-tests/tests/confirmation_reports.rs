@@ -0,0 +1,95 @@
+//! Confirmation reports: counts only grow, stay consistent with attested finality, and stale observations are ignored.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, BridgeEvent, BridgeTransfer, ExternalChain, Operation, Query, QueryResponse, TransferStatus};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta, Timestamp},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+fn update(confirmations: u64, observed_at: Option<Timestamp>) -> Operation {
+    Operation::UpdateConfirmations { transfer_id: 1, confirmations, observed_at }
+}
+
+async fn transfer(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> BridgeTransfer {
+    match user.query(bridge, Query::GetTransfer { transfer_id: 1 }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn confirmation_reports_are_checked() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                block_height: 100,
+                confirmations: 5,
+                bridge_contract_address: None,
+            });
+    }).await;
+
+    // A duplicate report is accepted and refreshes the record
+    deployment.validator.clock().add(TimeDelta::from_secs(3600));
+    user.add_block(|block| {
+        block.with_operation(bridge, update(8, None)).with_operation(bridge, update(8, None));
+    }).await;
+    let record = transfer(&user, bridge).await;
+    assert_eq!((record.status, record.confirmations), (TransferStatus::Confirming, 8));
+    let report = record.last_confirmation_report.expect("report recorded");
+    assert_eq!((report.reported_by, report.confirmations), (account, 8));
+
+    // Counts never decrease
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, update(6, None));
+    }).await;
+    assert!(result.is_err());
+
+    // An observation from an hour before the last report is dropped, not applied
+    user.add_block(|block| {
+        block.with_operation(bridge, update(10, Some(Timestamp::from(0))));
+    }).await;
+    assert_eq!(transfer(&user, bridge).await.confirmations, 8);
+    match user.query(bridge, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [BridgeEvent::StaleConfirmationReportIgnored { transfer_id: 1, confirmations: 10, .. }]
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Once height 110 is attested final, the deposit at 100 has at least 11 confirmations
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::SubmitBlockAttestation {
+            chain: ExternalChain::Ethereum,
+            height: 110,
+            block_hash: [7; 32],
+            signature: vec![],
+        });
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, update(9, None));
+    }).await;
+    assert!(result.is_err());
+
+    user.add_block(|block| {
+        block.with_operation(bridge, update(11, None));
+    }).await;
+    assert_eq!(transfer(&user, bridge).await.confirmations, 11);
+}
@@ -0,0 +1,162 @@
+//! Corridor status: availability changes are announced once to registered consumers, and settlement asks its bridge
+//! source before a withdrawal, refusing one through a paused corridor.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeEvent, CorridorAvailability, ExternalChain, Operation, Query, QueryResponse,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use axelarx_settlement::{
+    BridgeConfig, Operation as SettlementOperation, Query as SettlementQuery,
+    QueryResponse as SettlementQueryResponse, SettlementAbi,
+};
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+async fn corridor(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> (CorridorAvailability, Option<CorridorAvailability>) {
+    let query = Query::GetCorridorStatus { chain: ExternalChain::Ethereum, at: Timestamp::from(0) };
+    match chain.query(bridge, query).await {
+        QueryResponse::CorridorStatus { availability, announced } => {
+            (availability, announced.map(|status| status.availability))
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn settlement_corridor(
+    chain: &ActiveChain,
+    settlement: ApplicationId<SettlementAbi>,
+) -> Option<CorridorAvailability> {
+    let query = SettlementQuery::GetCorridorStatus { corridor: ExternalChain::Ethereum.chain_id() };
+    match chain.query(settlement, query).await {
+        SettlementQueryResponse::CorridorStatus(status) => status.map(|status| status.availability),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn withdraw() -> SettlementOperation {
+    SettlementOperation::InitiateBridgeWithdrawal {
+        chain_id: "ethereum".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(10),
+        destination_address: "0x52908400098527886E0F7030069857D2E4169EE7".to_string(),
+        client_request_id: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn availability_changes_are_announced_to_consumers() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let mut consumer = deployment.new_user().await;
+    let bridge = deployment.bridge;
+    let consumer_chain = consumer.id();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RegisterCorridorConsumer { consumer_chain })
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() });
+    }).await;
+    match user.query(bridge, Query::GetCorridorConsumers).await {
+        QueryResponse::CorridorConsumers(consumers) => assert_eq!(consumers, vec![consumer_chain]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(corridor(&user, bridge).await, (CorridorAvailability::Open, None));
+
+    // A pause is announced once; refreshing without a change sends nothing
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::EmergencyPause);
+    }).await;
+    assert_eq!(corridor(&user, bridge).await, (CorridorAvailability::Paused, Some(CorridorAvailability::Paused)));
+    consumer.handle_received_messages().await;
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::Resume)
+            .with_operation(bridge, Operation::RefreshCorridorStatus { chain: ExternalChain::Ethereum });
+    }).await;
+    assert_eq!(corridor(&user, bridge).await, (CorridorAvailability::Open, None));
+    match user.query(bridge, Query::GetEvents { count: 2 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [
+                BridgeEvent::CorridorStatusChanged { availability: CorridorAvailability::Paused, consumers: 1, .. },
+                BridgeEvent::CorridorStatusChanged { availability: CorridorAvailability::Open, consumers: 1, .. },
+            ]
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    let unregistered = user.id();
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::RemoveCorridorConsumer { consumer_chain: unregistered });
+    }).await;
+    assert!(result.is_err());
+
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paused_corridor_refuses_settlement_withdrawals() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let (bridge, settlement) = (deployment.bridge, deployment.settlement);
+    let corridor = ExternalChain::Ethereum.chain_id();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(settlement, SettlementOperation::SetCorridorSource {
+                application_id: Some(bridge.forget_abi()),
+            })
+            .with_operation(settlement, SettlementOperation::ConfigureBridge {
+                chain_id: "ethereum".to_string(),
+                config: BridgeConfig {
+                    chain_id: "ethereum".to_string(),
+                    chain_name: "Ethereum".to_string(),
+                    bridge_address: "0x52908400098527886E0F7030069857D2E4169EE7".to_string(),
+                    confirmation_blocks: 12,
+                    min_amount: Amount::from_tokens(1),
+                    max_amount: Amount::from_tokens(1_000_000),
+                    fee_rate_bps: 0,
+                    is_active: true,
+                    supported_assets: vec![TEST_ASSET.to_string()],
+                    corridor: Some(corridor),
+                },
+            })
+            .with_operation(settlement, SettlementOperation::ProcessBridgeDeposit {
+                chain_id: "ethereum".to_string(),
+                tx_hash: "0xfunding".to_string(),
+                user: account,
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                confirmations: 12,
+            })
+            .with_operation(bridge, Operation::EmergencyPause);
+    }).await;
+
+    // Settlement asks the bridge, so the paused corridor refuses the withdrawal
+    let result = user.try_add_block(|block| {
+        block.with_operation(settlement, withdraw());
+    }).await;
+    assert!(result.is_err());
+    user.add_block(|block| {
+        block.with_operation(settlement, SettlementOperation::RefreshCorridorStatus { corridor });
+    }).await;
+    assert_eq!(settlement_corridor(&user, settlement).await, Some(CorridorAvailability::Paused));
+
+    // Once the bridge resumes, the withdrawal goes through and the corridor shows as open again
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::Resume)
+            .with_operation(settlement, withdraw());
+    }).await;
+    assert_eq!(settlement_corridor(&user, settlement).await, None);
+    match user.query(settlement, SettlementQuery::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        SettlementQueryResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(90)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,68 @@
+//! Counterparty policies: an account's denylist always blocks new settlements, and a non-empty allowlist admits only its entries.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms};
+use axelarx_settlement::{CounterpartyListing, CounterpartyPolicy, Operation, Query, QueryResponse};
+use linera_base::identifiers::Account;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: Account, trade_id: u64) -> Operation {
+    SettlementTerms { taker, taker_chain: maker.id(), ..initiate_settlement(maker, maker, trade_id) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn policies_gate_new_settlements() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let approved = owner_account(&deployment.new_user().await);
+    let other = owner_account(&deployment.new_user().await);
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    // A denylisted counterparty is refused
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::SetCounterpartyPolicy {
+            counterparty: other,
+            listing: Some(CounterpartyListing::Denied),
+        });
+    }).await;
+    let operation = initiate(&maker, other, 1);
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, operation);
+    }).await;
+    assert!(result.is_err());
+
+    // Once an allowlist exists, only its entries pass
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetCounterpartyPolicy {
+                counterparty: other,
+                listing: None,
+            })
+            .with_operation(settlement, Operation::SetCounterpartyPolicy {
+                counterparty: approved,
+                listing: Some(CounterpartyListing::Allowed),
+            });
+    }).await;
+    let operation = initiate(&maker, other, 1);
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, operation);
+    }).await;
+    assert!(result.is_err());
+
+    let operation = initiate(&maker, approved, 1);
+    maker.add_block(|block| {
+        block.with_operation(settlement, operation);
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.taker, approved),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetCounterpartyPolicy { account: maker_account }).await {
+        QueryResponse::CounterpartyPolicy(policy) => {
+            assert_eq!(policy, CounterpartyPolicy { allowlist: vec![approved], denylist: vec![] });
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,72 @@
+//! Counterparty reputation: finalized outcomes are counted once and can gate new settlements.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse, Reputation};
+use linera_base::data_types::{Amount, TimeDelta};
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64) -> Operation {
+    SettlementTerms {
+        maker_amount: Amount::from_tokens(100),
+        timeout_seconds: 60,
+        ..initiate_settlement(maker, taker, trade_id)
+    }
+    .into()
+}
+
+async fn reputation(chain: &ActiveChain, deployment: &Deployment, of: &ActiveChain) -> Reputation {
+    match chain.query(deployment.settlement, Query::GetReputation { account: owner_account(of) }).await {
+        QueryResponse::Reputation(reputation) => reputation,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn default_is_counted_once_and_gates_new_settlements() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(100) })
+            .with_operation(settlement, initiate(&maker, &taker, 1))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None });
+    }).await;
+
+    // The taker never escrows; the refund claim expires the settlement, the sweep must not recount it
+    deployment.validator.clock().add(TimeDelta::from_secs(120));
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::ClaimRefund { settlement_id: 1, on_behalf_of: None })
+            .with_operation(settlement, Operation::ProcessExpiredSettlements { limit: None });
+    }).await;
+
+    let taker_record = reputation(&maker, &deployment, &taker).await;
+    assert_eq!((taker_record.finalized, taker_record.defaulted), (1, 1));
+    assert_eq!(taker_record.default_rate_bps(), 10_000);
+    let maker_record = reputation(&maker, &deployment, &maker).await;
+    assert_eq!((maker_record.finalized, maker_record.defaulted, maker_record.escrows), (1, 0, 1));
+
+    // Requiring at most a 50% default rate now refuses this taker
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::SetCounterpartyRequirement { max_default_rate_bps: Some(5_000) });
+    }).await;
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 2));
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetCounterpartyRequirement { max_default_rate_bps: None })
+            .with_operation(settlement, initiate(&maker, &taker, 2))
+            .with_operation(settlement, Operation::CancelSettlement { settlement_id: 2, reason: "changed my mind".to_string() });
+    }).await;
+    let maker_record = reputation(&maker, &deployment, &maker).await;
+    assert_eq!((maker_record.finalized, maker_record.cancelled), (2, 1));
+    assert_eq!(reputation(&maker, &deployment, &taker).await.cancelled, 0);
+}
@@ -0,0 +1,61 @@
+//! Custodians: escrow confirmations and refund claims signed for a party need the party's grant.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{CustodianGrant, Operation, Query, QueryResponse, SettlementAbi};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+async fn custodian(
+    chain: &ActiveChain,
+    settlement: ApplicationId<SettlementAbi>,
+    party: Account,
+    custodian: Account,
+) -> Option<CustodianGrant> {
+    match chain.query(settlement, Query::GetCustodian { party, custodian }).await {
+        QueryResponse::Custodian(grant) => grant,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_granted_custodians_act_for_a_party() {
+    let deployment = Deployment::new().await;
+    let mut party = deployment.new_user().await;
+    let client = deployment.new_user().await;
+    let (party_account, client_account) = (owner_account(&party), owner_account(&client));
+    let settlement = deployment.settlement;
+
+    // The client never named this chain's owner as its custodian
+    let result = party.try_add_block(|block| {
+        block
+            .with_operation(settlement, Operation::from(SettlementTerms {
+                maker_amount: Amount::from_tokens(100),
+                ..initiate_settlement(&client, &party, 1)
+            }))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: Some(client_account), substitute_asset: None, bridge_transfer_id: None });
+    }).await;
+    assert!(result.is_err());
+
+    party.add_block(|block| {
+        block.with_operation(settlement, Operation::GrantCustodian {
+            custodian: client_account,
+            assets: vec![TEST_ASSET.to_string()],
+        });
+    }).await;
+    let grant = custodian(&party, settlement, party_account, client_account).await.unwrap();
+    assert_eq!(grant.assets, vec![TEST_ASSET.to_string()]);
+
+    party.add_block(|block| {
+        block.with_operation(settlement, Operation::RevokeCustodian { custodian: client_account });
+    }).await;
+    assert_eq!(custodian(&party, settlement, party_account, client_account).await, None);
+    let result = party.try_add_block(|block| {
+        block.with_operation(settlement, Operation::RevokeCustodian { custodian: client_account });
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,144 @@
+//! Dead letters: a settlement request that fails to apply is kept and rejected back to the chain that relayed it, where
+//! its market takes the rejection and fails the trade; such a dead letter can be purged but not retried, and only
+//! existing dead letters resolve, by the admin.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, TEST_ASSET};
+use axelarx_orderbook::{
+    Operation as OrderBookOperation, OrderBookAbi, OrderSide, Query as OrderBookQuery,
+    QueryResponse as OrderBookResponse, TradeSettlement,
+};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn place(side: OrderSide) -> OrderBookOperation {
+    place_order(side, 50_000 * 100_000_000, 100_000_000).into()
+}
+
+/// Proceeds of `asset` held until `account`'s trades settle
+async fn unsettled(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> Amount {
+    match chain.query(orderbook, OrderBookQuery::GetAccountBalance { account, asset: asset.to_string() }).await {
+        OrderBookResponse::AccountBalance { unsettled, .. } => unsettled,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_existing_dead_letters_resolve() {
+    let deployment = Deployment::new().await;
+    let mut admin = deployment.admin.clone();
+    let mut user = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    match admin.query(settlement, Query::GetDeadLetters).await {
+        QueryResponse::DeadLetters(dead_letters) => assert!(dead_letters.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    let dead_letter_id = 0;
+    for operation in [Operation::RetryDeadLetter { dead_letter_id }, Operation::PurgeDeadLetter { dead_letter_id }] {
+        for chain in [&mut admin, &mut user] {
+            let result = chain.try_add_block(|block| {
+                block.with_operation(settlement, operation.clone());
+            }).await;
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_settlement_request_is_rejected_and_purged() {
+    let deployment = Deployment::new().await;
+    let mut hub = deployment.admin.clone();
+    let mut market = deployment.new_user().await;
+    let account = owner_account(&market);
+    let orderbook = deployment.orderbook;
+    let settlement = deployment.settlement;
+
+    hub.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::AppointChainAdmin {
+            chain_id: market.id(),
+            admin: account,
+        });
+    }).await;
+    market.handle_received_messages().await;
+
+    // The market is never registered with the settlement chain, so its request fails there
+    market.add_block(|block| {
+        block
+            .with_operation(orderbook, OrderBookOperation::SetSettlementApplication {
+                application_id: settlement.forget_abi(),
+                chain_id: hub.id(),
+            })
+            .with_operation(orderbook, OrderBookOperation::Deposit {
+                asset: "BTC".to_string(),
+                amount: Amount::from_tokens(1),
+            })
+            .with_operation(orderbook, OrderBookOperation::Deposit {
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(50_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell))
+            .with_operation(orderbook, place(OrderSide::Buy))
+            .with_operation(orderbook, OrderBookOperation::RequestTradeSettlement {
+                trade_id: 0,
+                timeout_seconds: 3_600,
+            });
+    }).await;
+    assert!(unsettled(&market, orderbook, account, "BTC").await > Amount::ZERO);
+    hub.handle_received_messages().await;
+
+    let dead_letter_id = match hub.query(settlement, Query::GetDeadLetters).await {
+        QueryResponse::DeadLetters(dead_letters) => {
+            assert_eq!(dead_letters.len(), 1);
+            assert_eq!(dead_letters[0].trade_id, Some(0));
+            assert!(dead_letters[0].rejection_sent());
+            dead_letters[0].id
+        }
+        other => panic!("unexpected response: {other:?}"),
+    };
+
+    // The rejection waits on the market's chain until the market takes it and fails the trade
+    market.handle_received_messages().await;
+    match market.query(orderbook, OrderBookQuery::GetTradeSettlement { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlement(status) => assert_eq!(status, Some(TradeSettlement::Requested)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    market.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::SyncTradeSettlement { trade_id: 0 });
+    }).await;
+    match market.query(orderbook, OrderBookQuery::GetTradeSettlement { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlement(Some(TradeSettlement::Failed { .. })) => {}
+        other => panic!("unexpected response: {other:?}"),
+    }
+    for asset in ["BTC", TEST_ASSET] {
+        assert_eq!(unsettled(&market, orderbook, account, asset).await, Amount::ZERO);
+    }
+
+    // Settling it now would contradict the market, so the dead letter can only be purged
+    let result = hub.try_add_block(|block| {
+        block.with_operation(settlement, Operation::RetryDeadLetter { dead_letter_id });
+    }).await;
+    assert!(result.is_err());
+    hub.add_block(|block| {
+        block.with_operation(settlement, Operation::PurgeDeadLetter { dead_letter_id });
+    }).await;
+    match hub.query(settlement, Query::GetDeadLetters).await {
+        QueryResponse::DeadLetters(dead_letters) => assert!(dead_letters.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match hub.query(settlement, Query::GetUserSettlements { account }).await {
+        QueryResponse::UserSettlements(settlement_ids) => assert!(settlement_ids.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
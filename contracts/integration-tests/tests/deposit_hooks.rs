@@ -0,0 +1,76 @@
+//! Deposit hooks: applications are told about completed deposits matching the owner's filters.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, DepositHook, DepositHookFilter, ExternalChain, Operation, Query, QueryResponse, TransferStatus,
+    MAX_DEPOSIT_HOOKS,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+async fn hooks(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> Vec<DepositHook> {
+    match chain.query(bridge, Query::GetDepositHooks { account }).await {
+        QueryResponse::DepositHooks(hooks) => hooks,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn hooks_do_not_hold_up_deposits() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let app = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let filter = DepositHookFilter { asset: Some(TEST_ASSET.to_string()), min_amount: Amount::from_tokens(10) };
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RegisterDepositHook { application_chain: app.id(), filter: filter.clone() })
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xhooked".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            });
+    }).await;
+
+    let registered = hooks(&user, bridge, account).await;
+    assert_eq!(registered.len(), 1);
+    assert_eq!((registered[0].application_chain, &registered[0].filter), (app.id(), &filter));
+    match user.query(bridge, Query::GetTransfer { transfer_id: 1 }).await {
+        QueryResponse::Transfer(Some(transfer)) => assert_eq!(transfer.status, TransferStatus::Completed),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    let hook_id = registered[0].id;
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::RemoveDepositHook { hook_id });
+    }).await;
+    assert!(hooks(&user, bridge, account).await.is_empty());
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::RemoveDepositHook { hook_id });
+    }).await;
+    assert!(result.is_err());
+
+    let result = user.try_add_block(|block| {
+        for _ in 0..=MAX_DEPOSIT_HOOKS {
+            block.with_operation(bridge, Operation::RegisterDepositHook {
+                application_chain: app.id(),
+                filter: DepositHookFilter::default(),
+            });
+        }
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,66 @@
+//! Settlement per-asset totals track every balance and escrow movement.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{AssetTotals, Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn escrow_totals_match_records_through_a_settlement() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit {
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+            })
+            .with_operation(settlement, Operation::from(SettlementTerms {
+                client_request_id: Some(7),
+                ..initiate_settlement(&maker, &taker, 1)
+            }))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None })
+            .with_operation(settlement, Operation::AuditEscrow);
+    }).await;
+
+    let receipt_query = Query::GetReceipt { account: owner_account(&maker), client_request_id: 7 };
+    match maker.query(settlement, receipt_query).await {
+        QueryResponse::Receipt(Some(receipt)) => assert_eq!(receipt.id, 1),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    match maker.query(settlement, Query::GetAssetTotals).await {
+        QueryResponse::AssetTotals(totals) => assert_eq!(totals, vec![AssetTotals {
+            asset: TEST_ASSET.to_string(),
+            balances: Amount::from_tokens(60),
+            escrowed: Amount::from_tokens(40),
+        }]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetHealth).await {
+        QueryResponse::Health { healthy, last_escrow_audit, .. } => {
+            assert!(healthy);
+            assert_eq!(last_escrow_audit.map(|audit| audit.active_settlements), Some(1));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Cancelling returns the escrow to the free balance
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::CancelSettlement { settlement_id: 1, reason: "test".to_string() })
+            .with_operation(settlement, Operation::AuditEscrow);
+    }).await;
+
+    match maker.query(settlement, Query::GetAssetTotals).await {
+        QueryResponse::AssetTotals(totals) => {
+            assert_eq!(totals[0].balances, Amount::from_tokens(100));
+            assert_eq!(totals[0].escrowed, Amount::ZERO);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
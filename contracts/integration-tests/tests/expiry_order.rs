@@ -0,0 +1,72 @@
+//! Expiry order: the sweep refunds settlements by expiry time, not by creation order, a bounded number per call.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, Deployment, SettlementTerms};
+use axelarx_settlement::{Operation, Query, QueryResponse, SettlementEvent, SettlementStatus};
+use linera_base::data_types::{Amount, TimeDelta};
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64, timeout_seconds: u64) -> Operation {
+    SettlementTerms {
+        maker_amount: Amount::from_tokens(10),
+        timeout_seconds,
+        ..initiate_settlement(maker, taker, trade_id)
+    }
+    .into()
+}
+
+async fn status(chain: &ActiveChain, deployment: &Deployment, settlement_id: u64) -> SettlementStatus {
+    match chain.query(deployment.settlement, Query::GetSettlement { settlement_id }).await {
+        QueryResponse::Settlement(Some(settlement)) => settlement.status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sweep_follows_expiry_time() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    // The long timeout is queued first; the short ones expire before it
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, initiate(&maker, &taker, 1, 3600))
+            .with_operation(settlement, initiate(&maker, &taker, 2, 120))
+            .with_operation(settlement, initiate(&maker, &taker, 3, 60));
+    }).await;
+
+    deployment.validator.clock().add(TimeDelta::from_secs(300));
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ProcessExpiredSettlements { limit: Some(1) });
+    }).await;
+    assert_eq!(status(&maker, &deployment, 3).await, SettlementStatus::Expired);
+    assert_eq!(status(&maker, &deployment, 2).await, SettlementStatus::Pending);
+
+    let first_expiry = match maker.query(settlement, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => match events.as_slice() {
+            [SettlementEvent::ExpiredSettlementsProcessed { expired: 1, next_expires_at: Some(next), .. }] => *next,
+            other => panic!("unexpected events: {other:?}"),
+        },
+        other => panic!("unexpected response: {other:?}"),
+    };
+
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ProcessExpiredSettlements { limit: None });
+    }).await;
+    assert_eq!(status(&maker, &deployment, 2).await, SettlementStatus::Expired);
+    assert_eq!(status(&maker, &deployment, 1).await, SettlementStatus::Pending);
+
+    // The second call reports the long timeout as the next one due
+    match maker.query(settlement, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => match events.as_slice() {
+            [SettlementEvent::ExpiredSettlementsProcessed { expired: 1, next_expires_at: Some(next), .. }] => {
+                assert!(*next > first_expiry);
+            }
+            other => panic!("unexpected events: {other:?}"),
+        },
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,49 @@
+//! Failure codes: failed transfers carry a machine-readable code and details next to the message.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeErrorCode, ExternalChain, Operation, Query, QueryResponse, TransferStatus};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejected_deposit_reports_its_code() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let mut config = ethereum_config();
+    config.large_transfer_threshold = Some(Amount::from_tokens(100));
+    config.quarantine_seconds = 3_600;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xsuspicious".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::FreezeTransfer {
+                transfer_id: 1,
+                reason: "sanctioned source".to_string(),
+            })
+            .with_operation(bridge, Operation::ResolveFrozenTransfer { transfer_id: 1, release: false });
+    }).await;
+
+    match user.query(bridge, Query::GetTransfer { transfer_id: 1 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.status, TransferStatus::Failed);
+            let failure = transfer.failure.unwrap();
+            assert_eq!(failure.code, BridgeErrorCode::TransferRejected);
+            assert_eq!(failure.details["reason"], "sanctioned source");
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
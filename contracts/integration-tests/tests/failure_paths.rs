@@ -0,0 +1,129 @@
+//! Failure paths: expired settlements and failed withdrawals return funds.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    ExternalChain, Operation as BridgeOperation, Query as BridgeQuery,
+    QueryResponse as BridgeResponse, TransferDirection, TransferStatus,
+};
+use axelarx_integration_tests::{
+    ethereum_config, initiate_settlement, owner_account, sole_validator, Deployment, SettlementTerms, TEST_ASSET,
+};
+use axelarx_settlement::{
+    Operation as SettlementOperation, Query as SettlementQuery,
+    QueryResponse as SettlementResponse, SettlementStatus,
+};
+use linera_base::data_types::{Amount, TimeDelta};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn expired_settlement_refunds_escrow() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+    let amount = Amount::from_tokens(100);
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, SettlementOperation::Deposit {
+                asset: TEST_ASSET.to_string(),
+                amount,
+            })
+            .with_operation(settlement, SettlementOperation::from(SettlementTerms {
+                maker_amount: amount,
+                timeout_seconds: 60,
+                ..initiate_settlement(&maker, &taker, 1)
+            }))
+            .with_operation(settlement, SettlementOperation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None });
+    }).await;
+
+    let balance_query = SettlementQuery::GetBalance { account: maker_account, asset: TEST_ASSET.to_string() };
+    match maker.query(settlement, balance_query.clone()).await {
+        SettlementResponse::Balance(balance) => assert_eq!(balance, Amount::ZERO),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The taker never escrows
+    deployment.validator.clock().add(TimeDelta::from_secs(120));
+    maker.add_block(|block| {
+        block.with_operation(settlement, SettlementOperation::ProcessExpiredSettlements { limit: None });
+    }).await;
+
+    match maker.query(settlement, SettlementQuery::GetSettlement { settlement_id: 1 }).await {
+        SettlementResponse::Settlement(Some(settlement)) => {
+            assert_eq!(settlement.status, SettlementStatus::Expired);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, balance_query).await {
+        SettlementResponse::Balance(balance) => assert_eq!(balance, amount),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_withdrawal_refunds_net_amount() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let config = ethereum_config();
+    let validator = sole_validator(&user);
+
+    let deposit = Amount::from_tokens(1_000);
+    let withdrawal = Amount::from_tokens(500);
+    let deposit_fees = config.fee_breakdown(deposit, TransferDirection::Inbound).unwrap();
+    let withdrawal_fees = config.fee_breakdown(withdrawal, TransferDirection::Outbound).unwrap();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, BridgeOperation::ConfigureChain { config: config.clone() })
+            .with_operation(bridge, BridgeOperation::AddValidator { config: validator })
+            .with_operation(bridge, BridgeOperation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: deposit,
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, BridgeOperation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: withdrawal,
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            });
+    }).await;
+
+    // Transfer 1 is the deposit, 2 the withdrawal; the relayer reports a failed send
+    let transfer_id = 2;
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, BridgeOperation::ApproveTransfer { transfer_id, signature: vec![] })
+            .with_operation(bridge, BridgeOperation::ExecuteTransfer { transfer_id })
+            .with_operation(bridge, BridgeOperation::CompleteWithdrawal {
+                transfer_id,
+                tx_hash: "0xreverted".to_string(),
+                success: false,
+            });
+    }).await;
+
+    match user.query(bridge, BridgeQuery::GetTransfer { transfer_id }).await {
+        BridgeResponse::Transfer(Some(transfer)) => assert_eq!(transfer.status, TransferStatus::Failed),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The fee is kept; the rest of the withdrawal comes back
+    let expected = deposit_fees.net_amount - withdrawal + withdrawal_fees.net_amount;
+    match user.query(bridge, BridgeQuery::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        BridgeResponse::Balance(balance) => assert_eq!(balance, expected),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,105 @@
+//! Trading fees: under either fee model, balances plus collected fees add up to what was deposited.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{FeeModel, Operation, OrderBookAbi, OrderSide, OrderType, Query, QueryResponse};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const ONE_BTC: u64 = 100_000_000;
+
+fn price(usdt: u64) -> u64 {
+    usdt * 100_000_000
+}
+
+fn place(side: OrderSide, order_type: OrderType, price: u64, quantity: u64) -> Operation {
+    OrderPlacement { order_type, ..place_order(side, price, quantity) }.into()
+}
+
+/// Available, locked and collected fees of an asset, which must sum to the deposit
+async fn holdings(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> (Amount, Amount, Amount) {
+    let (available, locked) =
+        match chain.query(orderbook, Query::GetAccountBalance { account, asset: asset.to_string() }).await {
+            QueryResponse::AccountBalance { available, locked, .. } => (available, locked),
+            other => panic!("unexpected response: {other:?}"),
+        };
+    match chain.query(orderbook, Query::GetCollectedFees { asset: asset.to_string() }).await {
+        QueryResponse::CollectedFees(collected) => (available, locked, collected),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn assert_conserved(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, account: Account) {
+    for (asset, deposit) in [("BTC", Amount::from_tokens(10)), ("USDT", Amount::from_tokens(1_000_000))] {
+        let (available, locked, collected) = holdings(chain, orderbook, account, asset).await;
+        assert!(collected > Amount::ZERO);
+        assert_eq!(available + locked + collected, deposit, "{asset} not conserved");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn balances_and_fees_are_conserved_under_both_fee_models() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    // Fees taken from proceeds: a crossing bid with price improvement, then a market buy
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(10) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(1_000_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Limit, price(50_000), ONE_BTC))
+            .with_operation(orderbook, place(OrderSide::Buy, OrderType::Limit, price(51_000), ONE_BTC / 2))
+            .with_operation(orderbook, place(OrderSide::Buy, OrderType::Market, 0, ONE_BTC / 2));
+    }).await;
+    assert_conserved(&user, orderbook, account).await;
+
+    // Quote fees on top of the price: a resting bid locks its fee and gives back what it did not need
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::UpdateConfig {
+                min_order_size: None,
+                max_order_size: None,
+                tick_size: None,
+                fee_model: Some(FeeModel::FeeInQuoteAsset),
+                max_orders_per_level: None,
+                max_account_orders_per_level: None,
+                allow_unsettled_trading: None,
+            })
+            .with_operation(orderbook, place(OrderSide::Buy, OrderType::Limit, price(49_000), ONE_BTC))
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Market, 0, ONE_BTC * 2 / 5))
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Limit, price(48_000), ONE_BTC * 3 / 5));
+    }).await;
+    assert_conserved(&user, orderbook, account).await;
+    assert_eq!(holdings(&user, orderbook, account, "USDT").await.1, Amount::ZERO);
+
+    // The model cannot change under a resting bid's lock
+    user.add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, OrderType::Limit, price(40_000), ONE_BTC));
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::UpdateConfig {
+            min_order_size: None,
+            max_order_size: None,
+            tick_size: None,
+            fee_model: Some(FeeModel::FeeInReceivedAsset),
+            max_orders_per_level: None,
+            max_account_orders_per_level: None,
+            allow_unsettled_trading: None,
+        });
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,68 @@
+//! Fee overrides: a voucher discounts one withdrawal and is recorded on it.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{ExternalChain, FeeOverrideKey, Operation, Query, QueryResponse, TransferDirection};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::data_types::{Amount, Timestamp};
+
+fn withdraw(fee_voucher: &str) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        memo: None,
+        client_request_id: None,
+        fee_voucher: Some(fee_voucher.to_string()),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn voucher_discounts_a_single_withdrawal() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let config = ethereum_config();
+    let voucher = FeeOverrideKey::Voucher("OTC-7".to_string());
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: config.clone() })
+            .with_operation(bridge, Operation::SetFeeOverride {
+                key: voucher.clone(),
+                base_fee: Amount::ZERO,
+                fee_percentage_bps: 10,
+                expires_at: Timestamp::from(u64::MAX),
+            })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw("OTC-7"));
+    }).await;
+
+    // Transfer 1 is the deposit, 2 the discounted withdrawal
+    let standard = config.fee_breakdown(Amount::from_tokens(100), TransferDirection::Outbound).unwrap();
+    match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.fee, Amount::from_millis(100));
+            assert!(transfer.fee < standard.total_fee);
+            assert_eq!(transfer.fee_override, Some(voucher));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, withdraw("OTC-7"));
+    }).await;
+    assert!(result.is_err());
+}
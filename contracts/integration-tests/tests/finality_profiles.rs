@@ -0,0 +1,185 @@
+//! Finality profiles: deposits wait for confirmations, a finality attestation, or both, per chain and asset.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, ConfirmationOverride, ConfirmationTier, ExternalChain, FinalityProfile, Operation, Query,
+    QueryResponse, TransferDirection, TransferId, TransferStatus,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn deposit(tx_hash: &str, recipient: Account, block_height: u64) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: Some(recipient),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(1_000),
+        block_height,
+        confirmations: 64,
+        bridge_contract_address: None,
+    }
+}
+
+async fn status(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: TransferId) -> TransferStatus {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer.status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn finality_tag_waits_for_attestation() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let mut config = ethereum_config();
+    config.finality = Some(FinalityProfile::FinalityTag);
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: config.clone() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, deposit("0xfirst", account, 100))
+            .with_operation(bridge, Operation::UpdateConfirmations { transfer_id: 1, confirmations: 1_000, observed_at: None });
+    }).await;
+
+    // Confirmations alone never finalize a tagged chain
+    assert_eq!(status(&user, bridge, 1).await, TransferStatus::Confirming);
+
+    // The sole validator's attestation is a quorum
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::SubmitBlockAttestation {
+            chain: ExternalChain::Ethereum,
+            height: 100,
+            block_hash: [7; 32],
+            signature: vec![],
+        });
+    }).await;
+    assert_eq!(status(&user, bridge, 1).await, TransferStatus::Completed);
+    match user.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => assert!(balance > Amount::ZERO),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Switching to a confirmation count applies to new deposits only
+    config.finality = Some(FinalityProfile::ConfirmationCount(1_000));
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config })
+            .with_operation(bridge, deposit("0xsecond", account, 100));
+    }).await;
+    assert_eq!(status(&user, bridge, 1).await, TransferStatus::Completed);
+    assert_eq!(status(&user, bridge, 2).await, TransferStatus::Confirming);
+
+    let query = Query::EstimateFee {
+        chain: ExternalChain::Ethereum,
+        amount: Amount::from_tokens(1_000),
+        direction: TransferDirection::Inbound,
+        account: None,
+        asset: None,
+    };
+    match user.query(bridge, query).await {
+        QueryResponse::FeeEstimate { finality, expected_wait_seconds, .. } => {
+            assert_eq!(finality, FinalityProfile::ConfirmationCount(1_000));
+            assert_eq!(expected_wait_seconds, 12_000);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn large_deposits_wait_for_their_tier() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let confirmation_override = ConfirmationOverride {
+        tiers: vec![ConfirmationTier { min_amount: Amount::from_tokens(500), confirmations: 64 }],
+    };
+    let sized_deposit = |tx_hash: &str, tokens: u128| Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: Some(account),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(tokens),
+        block_height: 100,
+        confirmations: 20,
+        bridge_contract_address: None,
+    };
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::SetConfirmationOverride {
+                chain: ExternalChain::Ethereum,
+                asset: TEST_ASSET.to_string(),
+                confirmation_override: confirmation_override.clone(),
+            })
+            .with_operation(bridge, sized_deposit("0xsmall", 100))
+            .with_operation(bridge, sized_deposit("0xlarge", 1_000));
+    }).await;
+
+    // Below the tier the chain's 12 confirmations apply; above it the deposit waits for 64
+    assert_ne!(status(&user, bridge, 1).await, TransferStatus::Confirming);
+    match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.status, TransferStatus::Confirming);
+            assert_eq!(transfer.required_confirmations, 64);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The snapshot outlives the override
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RemoveConfirmationOverride {
+                chain: ExternalChain::Ethereum,
+                asset: TEST_ASSET.to_string(),
+            })
+            .with_operation(bridge, Operation::UpdateConfirmations { transfer_id: 2, confirmations: 63, observed_at: None });
+    }).await;
+    assert_eq!(status(&user, bridge, 2).await, TransferStatus::Confirming);
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::SetConfirmationOverride {
+            chain: ExternalChain::Ethereum,
+            asset: TEST_ASSET.to_string(),
+            confirmation_override,
+        });
+    }).await;
+    let query = Query::EstimateFee {
+        chain: ExternalChain::Ethereum,
+        amount: Amount::from_tokens(1_000),
+        direction: TransferDirection::Inbound,
+        account: None,
+        asset: Some(TEST_ASSET.to_string()),
+    };
+    match user.query(bridge, query).await {
+        QueryResponse::FeeEstimate { finality, .. } => assert_eq!(finality, FinalityProfile::ConfirmationCount(64)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Tiers out of order are refused
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::SetConfirmationOverride {
+            chain: ExternalChain::Ethereum,
+            asset: TEST_ASSET.to_string(),
+            confirmation_override: ConfirmationOverride {
+                tiers: vec![
+                    ConfirmationTier { min_amount: Amount::from_tokens(500), confirmations: 64 },
+                    ConfirmationTier { min_amount: Amount::ZERO, confirmations: 12 },
+                ],
+            },
+        });
+    }).await;
+    assert!(result.is_err());
+}
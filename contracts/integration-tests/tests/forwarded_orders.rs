@@ -0,0 +1,122 @@
+//! Forwarded orders: sibling market chains place each other's users' orders and acknowledge them, and orders from unregistered chains are dropped.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, Deployment};
+use axelarx_orderbook::{
+    ForwardOutcome, ForwardedOrder, ForwardedOrderSpec, Operation, OrderBookAbi, OrderBookEvent, OrderSide, OrderType,
+    Query, QueryResponse, RejectionCode, TimeInForce,
+};
+use linera_base::identifiers::{ApplicationId, ChainId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE_SCALE: u64 = 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn register(chain_id: ChainId, base_asset: &str) -> Operation {
+    Operation::RegisterSiblingMarket {
+        chain_id,
+        base_asset: base_asset.to_string(),
+        quote_asset: "USDT".to_string(),
+    }
+}
+
+fn forward(market_chain: ChainId) -> Operation {
+    Operation::ForwardOrder {
+        market_chain,
+        spec: ForwardedOrderSpec {
+            side: OrderSide::Sell,
+            order_type: OrderType::Limit,
+            price: 50_000 * PRICE_SCALE,
+            quantity: ONE_BTC,
+            time_in_force: TimeInForce::GTC,
+            expires_at: None,
+            require_full_fill: false,
+            min_fill_quantity: None,
+        },
+    }
+}
+
+async fn forwarded_orders(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>) -> Vec<ForwardedOrder> {
+    match chain.query(orderbook, Query::GetForwardedOrders { user: owner_account(chain) }).await {
+        QueryResponse::ForwardedOrders(orders) => orders,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn rejection_code(order: &ForwardedOrder) -> RejectionCode {
+    match &order.outcome {
+        ForwardOutcome::Rejected { code, .. } => *code,
+        other => panic!("unexpected outcome: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs the admin role on every market chain; only the creator chain has one"]
+async fn forwarded_orders_are_acknowledged() {
+    let deployment = Deployment::new().await;
+    let mut home = deployment.new_user().await;
+    let mut market = deployment.new_user().await;
+    let mut stranger = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    // Only registered siblings can be forwarded to
+    let result = home.try_add_block(|block| {
+        block.with_operation(orderbook, forward(market.id()));
+    }).await;
+    assert!(result.is_err());
+
+    market.add_block(|block| {
+        block.with_operation(orderbook, register(home.id(), "BTC"));
+    }).await;
+    home.add_block(|block| {
+        block
+            .with_operation(orderbook, register(market.id(), "ETH"))
+            .with_operation(orderbook, forward(market.id()));
+    }).await;
+    match home.query(orderbook, Query::GetSiblingMarkets).await {
+        QueryResponse::SiblingMarkets(markets) => {
+            assert_eq!(markets.len(), 1);
+            assert_eq!(markets[0].0, market.id());
+            assert_eq!(markets[0].1.base_asset, "ETH");
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(forwarded_orders(&home, orderbook).await[0].outcome, ForwardOutcome::Pending);
+
+    // The market checks the pair against its own config
+    market.handle_received_messages().await;
+    home.handle_received_messages().await;
+    let orders = forwarded_orders(&home, orderbook).await;
+    assert_eq!(rejection_code(&orders[0]), RejectionCode::MarketPairMismatch);
+    assert!(orders[0].acknowledged_at.is_some());
+
+    // With the right pair the order is placed under the home user, who holds nothing there
+    home.add_block(|block| {
+        block
+            .with_operation(orderbook, register(market.id(), "BTC"))
+            .with_operation(orderbook, forward(market.id()));
+    }).await;
+    market.handle_received_messages().await;
+    home.handle_received_messages().await;
+    let orders = forwarded_orders(&home, orderbook).await;
+    assert_eq!(orders.len(), 2);
+    assert_eq!(rejection_code(&orders[1]), RejectionCode::InsufficientBalance);
+
+    // An origin the market never registered is dropped with an event and never acknowledged
+    stranger.add_block(|block| {
+        block
+            .with_operation(orderbook, register(market.id(), "BTC"))
+            .with_operation(orderbook, forward(market.id()));
+    }).await;
+    market.handle_received_messages().await;
+    stranger.handle_received_messages().await;
+    match market.query(orderbook, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [OrderBookEvent::ForwardedOrderDropped { origin, forward_id: 0, .. }] if *origin == stranger.id()
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(forwarded_orders(&stranger, orderbook).await[0].outcome, ForwardOutcome::Pending);
+}
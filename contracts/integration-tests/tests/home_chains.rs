@@ -0,0 +1,79 @@
+//! Home chains: settlement requests carry the chain each party registered, this chain by default.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{
+    Operation as OrderBookOperation, OrderSide, Query as OrderBookQuery, QueryResponse as OrderBookResponse,
+};
+use axelarx_settlement::{
+    MarketRegistration, Operation as SettlementOperation, Query as SettlementQuery,
+    QueryResponse as SettlementResponse,
+};
+use linera_base::data_types::Amount;
+
+fn place(side: OrderSide) -> OrderBookOperation {
+    place_order(side, 50_000 * 100_000_000, 100_000_000).into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settlement_request_carries_home_chains() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let chain_id = user.id();
+    let escrow_chain = deployment.new_user().await.id();
+    let (orderbook, settlement) = (deployment.orderbook, deployment.settlement);
+
+    user.add_block(|block| {
+        block
+            .with_operation(settlement, SettlementOperation::SetMarket {
+                application_id: orderbook.forget_abi(),
+                market: Some(MarketRegistration {
+                    chain_id,
+                    base_asset: "BTC".to_string(),
+                    quote_asset: "USDT".to_string(),
+                }),
+            })
+            .with_operation(orderbook, OrderBookOperation::SetSettlementApplication {
+                application_id: settlement.forget_abi(),
+                chain_id,
+            })
+            .with_operation(orderbook, OrderBookOperation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) });
+    }).await;
+
+    // The first deposit registered the account's own chain
+    match user.query(orderbook, OrderBookQuery::GetHomeChain { account }).await {
+        OrderBookResponse::HomeChain(home_chain) => assert_eq!(home_chain, Some(chain_id)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, OrderBookOperation::SetHomeChain { chain_id: escrow_chain })
+            .with_operation(orderbook, OrderBookOperation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell))
+            .with_operation(orderbook, place(OrderSide::Buy))
+            .with_operation(orderbook, OrderBookOperation::RequestTradeSettlement { trade_id: 0, timeout_seconds: 3_600 });
+    }).await;
+
+    // A later deposit leaves the registration alone
+    match user.query(orderbook, OrderBookQuery::GetHomeChain { account }).await {
+        OrderBookResponse::HomeChain(home_chain) => assert_eq!(home_chain, Some(escrow_chain)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    let settlement_id = match user.query(orderbook, OrderBookQuery::GetTradeSettlementId { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlementId(Some(settlement_id)) => settlement_id,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    match user.query(settlement, SettlementQuery::GetSettlement { settlement_id }).await {
+        SettlementResponse::Settlement(Some(record)) => {
+            assert_eq!((record.maker_chain, record.taker_chain), (escrow_chain, escrow_chain));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
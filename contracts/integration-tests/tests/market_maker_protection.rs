@@ -0,0 +1,104 @@
+//! Market maker protection: fills reaching an account's caps pull its other quotes mid-sweep and refuse placements until the cooldown ends or the account lifts it.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    MmpConfig, Operation, OrderBookAbi, OrderBookEvent, OrderId, OrderSide, OrderStatus, Query, QueryResponse,
+    TimeInForce,
+};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const TENTH_BTC: u64 = 10_000_000;
+
+fn place(side: OrderSide, quantity: u64, time_in_force: TimeInForce) -> Operation {
+    OrderPlacement { time_in_force, ..place_order(side, PRICE, quantity) }.into()
+}
+
+async fn filled(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: OrderId) -> (OrderStatus, u64) {
+    match chain.query(orderbook, Query::GetOrder { order_id }).await {
+        QueryResponse::Order(Some(order)) => (order.status, order.filled_quantity),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn protection_pulls_quotes_mid_sweep() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+    let now = deployment.validator.clock().current_time();
+
+    // Caps must be usable
+    let config = MmpConfig { window_seconds: 60, max_fills: Some(2), max_delta: None, cooldown_seconds: 300 };
+    let result = user.try_add_block(|block| {
+        let config = MmpConfig { max_fills: None, ..config };
+        block.with_operation(orderbook, Operation::SetMmpConfig { config: Some(config) });
+    }).await;
+    assert!(result.is_err());
+
+    // Asks 0 to 3 rest at one price; the sweep of order 4 triggers on its second fill
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            })
+            .with_operation(orderbook, Operation::SetMmpConfig { config: Some(config) });
+        for _ in 0..4 {
+            block.with_operation(orderbook, place(OrderSide::Sell, TENTH_BTC, TimeInForce::GTC));
+        }
+        block.with_operation(orderbook, place(OrderSide::Buy, 4 * TENTH_BTC, TimeInForce::IOC));
+    }).await;
+
+    assert_eq!(filled(&user, orderbook, 0).await, (OrderStatus::Filled, TENTH_BTC));
+    assert_eq!(filled(&user, orderbook, 1).await, (OrderStatus::Filled, TENTH_BTC));
+    assert_eq!(filled(&user, orderbook, 2).await, (OrderStatus::Cancelled, 0));
+    assert_eq!(filled(&user, orderbook, 3).await, (OrderStatus::Cancelled, 0));
+    assert_eq!(filled(&user, orderbook, 4).await, (OrderStatus::Cancelled, 2 * TENTH_BTC));
+    let cooldown_end = now.saturating_add(TimeDelta::from_secs(300));
+    match user.query(orderbook, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [OrderBookEvent::MmpTriggered { fills: 2, delta, order_ids, remaining: 0, cooldown_until, .. }]
+                if *delta == -2 * i128::from(TENTH_BTC) && *order_ids == vec![2, 3] && *cooldown_until == cooldown_end
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Placements wait for the cooldown, which the account may lift
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Sell, TENTH_BTC, TimeInForce::GTC));
+    }).await;
+    assert!(result.is_err());
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::ResetMmp)
+            .with_operation(orderbook, place(OrderSide::Sell, TENTH_BTC, TimeInForce::GTC));
+    }).await;
+    assert_eq!(filled(&user, orderbook, 5).await, (OrderStatus::Open, 0));
+    match user.query(orderbook, Query::GetMmpStatus { account }).await {
+        QueryResponse::MmpStatus(Some(tracker)) => {
+            assert_eq!((tracker.fills, tracker.cooldown_until), (0, None));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Fills count again from the reset, in a fresh window
+    deployment.validator.clock().add(TimeDelta::from_secs(61));
+    user.add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, TENTH_BTC, TimeInForce::IOC));
+    }).await;
+    assert_eq!(filled(&user, orderbook, 5).await, (OrderStatus::Filled, TENTH_BTC));
+    match user.query(orderbook, Query::GetMmpStatus { account }).await {
+        QueryResponse::MmpStatus(Some(tracker)) => assert_eq!(tracker.fills, 1),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,131 @@
+//! Market orders against thin books: nothing stays locked for the unfilled part, in either mode.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, OrderStatus, OrderType, Query, QueryResponse};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+async fn balance(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> (Amount, Amount) {
+    match chain.query(orderbook, Query::GetAccountBalance { account, asset: asset.to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, .. } => (available, locked),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn place(side: OrderSide, order_type: OrderType, quantity: u64, require_full_fill: bool) -> Operation {
+    let price = if order_type == OrderType::Limit { PRICE } else { 0 };
+    OrderPlacement { order_type, require_full_fill, ..place_order(side, price, quantity) }.into()
+}
+
+/// A funded user with 2 BTC and 200,000 USDT and one resting ask of 1 BTC at 50,000
+async fn funded_book() -> (Deployment, ActiveChain, Account) {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Limit, ONE_BTC, false));
+    }).await;
+
+    (deployment, user, account)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn market_order_on_empty_side_cancels_without_locking() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Market, ONE_BTC, false));
+    }).await;
+
+    match user.query(orderbook, Query::GetOrder { order_id: 0 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, OrderStatus::Cancelled);
+            assert_eq!(order.filled_quantity, 0);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(balance(&user, orderbook, account, "BTC").await, (Amount::from_tokens(1), Amount::ZERO));
+
+    // Require a full fill: the order is rejected outright
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Sell, OrderType::Market, ONE_BTC, true));
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(balance(&user, orderbook, account, "BTC").await, (Amount::from_tokens(1), Amount::ZERO));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn market_order_exhausting_book_fills_available_and_cancels_rest() {
+    let (deployment, mut user, account) = funded_book().await;
+    let orderbook = deployment.orderbook;
+
+    // 3 BTC against 1 BTC of asks: 1 fills, 2 lapse
+    user.add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, OrderType::Market, 3 * ONE_BTC, false));
+    }).await;
+
+    match user.query(orderbook, Query::GetOrder { order_id: 1 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, OrderStatus::Cancelled);
+            assert_eq!(order.filled_quantity, ONE_BTC);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetOrderBook { depth: 10 }).await {
+        QueryResponse::OrderBook { asks, .. } => assert!(asks.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Both sides of the trade were this user, so only the fees are gone and the locks all released:
+    // 0.2% of the BTC bought and 0.1% of the USDT received
+    assert_eq!(balance(&user, orderbook, account, "BTC").await, (Amount::from_millis(1_998), Amount::ZERO));
+    assert_eq!(balance(&user, orderbook, account, "USDT").await, (Amount::from_tokens(199_950), Amount::ZERO));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_fill_market_order_exhausting_book_is_rejected() {
+    let (deployment, mut user, account) = funded_book().await;
+    let orderbook = deployment.orderbook;
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, OrderType::Market, 3 * ONE_BTC, true));
+    }).await;
+    assert!(result.is_err());
+
+    // The resting ask is untouched and still holds its lock
+    match user.query(orderbook, Query::GetOrder { order_id: 0 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, OrderStatus::Open);
+            assert_eq!(order.filled_quantity, 0);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(balance(&user, orderbook, account, "BTC").await, (Amount::from_tokens(1), Amount::from_tokens(1)));
+    assert_eq!(balance(&user, orderbook, account, "USDT").await, (Amount::from_tokens(200_000), Amount::ZERO));
+}
@@ -0,0 +1,98 @@
+//! Market phases: a pre-open book builds without trades, and opening a crossed auction book clears it at one price.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    MarketPhase, MarketStats, Operation, OrderBookAbi, OrderSide, OrderStatus, OrderType, Query, QueryResponse,
+};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const ONE_BTC: u64 = 100_000_000;
+
+fn order(side: OrderSide, order_type: OrderType, price: u64) -> Operation {
+    OrderPlacement { order_type, ..place_order(side, price * 100_000_000, ONE_BTC) }.into()
+}
+
+fn set_phase(phase: MarketPhase, clear_crossed_book: bool) -> Operation {
+    Operation::SetMarketPhase { phase, clear_crossed_book }
+}
+
+async fn stats(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>) -> MarketStats {
+    match chain.query(orderbook, Query::GetMarketStats).await {
+        QueryResponse::MarketStats(stats) => stats,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn balance(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> (Amount, Amount) {
+    match chain.query(orderbook, Query::GetAccountBalance { account, asset: asset.to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, .. } => (available, locked),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn book_builds_before_open_and_clears_at_one_price() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, set_phase(MarketPhase::PreOpen, false))
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, order(OrderSide::Sell, OrderType::Limit, 50_000));
+    }).await;
+    assert_eq!(stats(&user, orderbook).await.phase, MarketPhase::PreOpen);
+
+    // Pre-open takes neither crossing limits nor market orders
+    for operation in [order(OrderSide::Buy, OrderType::Limit, 50_000), order(OrderSide::Buy, OrderType::Market, 0)] {
+        let result = user.try_add_block(|block| {
+            block.with_operation(orderbook, operation);
+        }).await;
+        assert!(result.is_err());
+    }
+
+    // In the auction a crossing bid rests, and the crossed book cannot open as it is
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, set_phase(MarketPhase::Auction, false))
+            .with_operation(orderbook, order(OrderSide::Buy, OrderType::Limit, 51_000));
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, set_phase(MarketPhase::Continuous, false));
+    }).await;
+    assert!(result.is_err());
+
+    // Both prices clear the same volume; the lower one wins
+    user.add_block(|block| {
+        block.with_operation(orderbook, set_phase(MarketPhase::Continuous, true));
+    }).await;
+    let stats = stats(&user, orderbook).await;
+    assert_eq!((stats.phase, stats.last_price), (MarketPhase::Continuous, 50_000 * 100_000_000));
+    for order_id in [0, 1] {
+        match user.query(orderbook, Query::GetOrder { order_id }).await {
+            QueryResponse::Order(Some(order)) => assert_eq!(order.status, OrderStatus::Filled),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    // The bid locked 51,000 and paid 50,000; the later order paid the 0.2% taker fee in BTC
+    assert_eq!(balance(&user, orderbook, account, "USDT").await, (Amount::from_tokens(199_950), Amount::ZERO));
+    assert_eq!(balance(&user, orderbook, account, "BTC").await, (Amount::from_millis(1_998), Amount::ZERO));
+}
@@ -0,0 +1,53 @@
+//! Minimum fill size: small resting orders are passed over but keep their queue position.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderId, OrderSide, OrderStatus, Query, QueryResponse};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, quantity: u64, min_fill_quantity: Option<u64>) -> Operation {
+    OrderPlacement { min_fill_quantity, ..place_order(side, PRICE, quantity) }.into()
+}
+
+async fn filled(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: OrderId) -> (OrderStatus, u64) {
+    match chain.query(orderbook, Query::GetOrder { order_id }).await {
+        QueryResponse::Order(Some(order)) => (order.status, order.filled_quantity),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn small_makers_are_passed_over_in_place() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    // Order 0 is a small ask ahead of the large order 1 at the same price
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, ONE_BTC / 10, None))
+            .with_operation(orderbook, place(OrderSide::Sell, ONE_BTC, None))
+            .with_operation(orderbook, place(OrderSide::Buy, ONE_BTC * 6 / 10, Some(ONE_BTC / 2)));
+    }).await;
+
+    assert_eq!(filled(&user, orderbook, 0).await, (OrderStatus::Open, 0));
+    assert_eq!(filled(&user, orderbook, 1).await, (OrderStatus::PartiallyFilled, ONE_BTC * 6 / 10));
+    assert_eq!(filled(&user, orderbook, 2).await, (OrderStatus::Filled, ONE_BTC * 6 / 10));
+
+    // Order 0 kept its place ahead of order 1
+    user.add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, ONE_BTC / 10, None));
+    }).await;
+    assert_eq!(filled(&user, orderbook, 0).await, (OrderStatus::Filled, ONE_BTC / 10));
+    assert_eq!(filled(&user, orderbook, 3).await, (OrderStatus::Filled, ONE_BTC / 10));
+}
@@ -0,0 +1,64 @@
+//! One-sided settlements: a zero leg needs the explicit flag, its party owes only an acceptance, and a lapsed one refunds the paying side alone.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse, SettlementStatus};
+use linera_base::data_types::{Amount, TimeDelta};
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, taker_amount: Amount, one_sided: bool) -> Operation {
+    SettlementTerms { taker_amount, one_sided, ..initiate_settlement(maker, taker, 1) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn zero_legs_are_explicit_and_refund_one_side() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    // A zero leg is refused unless flagged, and the flag needs exactly one
+    for (taker_amount, one_sided) in [(Amount::ZERO, false), (Amount::from_tokens(1), true)] {
+        let operation = initiate(&maker, &taker, taker_amount, one_sided);
+        let result = maker.try_add_block(|block| {
+            block.with_operation(settlement, operation);
+        }).await;
+        assert!(result.is_err());
+    }
+
+    let operation = initiate(&maker, &taker, Amount::ZERO, true);
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(100) })
+            .with_operation(settlement, operation)
+            .with_operation(settlement, Operation::ConfirmEscrow {
+                settlement_id: 1,
+                on_behalf_of: None,
+                substitute_asset: None,
+                bridge_transfer_id: None,
+            });
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert!(record.one_sided);
+            assert_eq!(record.status, SettlementStatus::MakerEscrowed);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Without the taker's acceptance it expires, and the maker gets its escrow back
+    deployment.validator.clock().add(TimeDelta::from_secs(3_601));
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ProcessExpiredSettlements { limit: None });
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.status, SettlementStatus::Expired),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetBalance { account: maker_account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(100)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,45 @@
+//! Operation receipts: a client request id maps to the created order, and cannot be reused.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{Operation, OrderSide, Query, QueryResponse};
+use linera_base::data_types::Amount;
+
+fn place(client_request_id: u64) -> Operation {
+    OrderPlacement {
+        client_request_id: Some(client_request_id),
+        ..place_order(OrderSide::Sell, 50_000 * 100_000_000, 10_000_000)
+    }
+    .into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn client_request_id_returns_order_id_once() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, place(41))
+            .with_operation(orderbook, place(42));
+    }).await;
+
+    match user.query(orderbook, Query::GetReceipt { account, client_request_id: 42 }).await {
+        QueryResponse::Receipt(Some(receipt)) => assert_eq!(receipt.id, 1),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Retrying with a used id is rejected, so no second order is created
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, place(42));
+    }).await;
+    assert!(result.is_err());
+    match user.query(orderbook, Query::GetOrder { order_id: 2 }).await {
+        QueryResponse::Order(order) => assert!(order.is_none()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,91 @@
+//! Order history: every change to an order is recorded in order, and fills point at the trades in the trade export.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{place_order, Deployment};
+use axelarx_orderbook::{
+    Operation, OrderBookAbi, OrderHistory, OrderHistoryEvent, OrderSide, OrderType, Query, QueryResponse, TimeInForce,
+    TradePage,
+};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, quantity: u64) -> Operation {
+    place_order(side, PRICE, quantity).into()
+}
+
+async fn history(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: u64) -> OrderHistory {
+    match chain.query(orderbook, Query::GetOrderHistory { order_id }).await {
+        QueryResponse::OrderHistory(Some(history)) => history,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn events(history: OrderHistory) -> Vec<OrderHistoryEvent> {
+    assert_eq!(history.dropped, 0);
+    history.entries.into_iter().map(|entry| entry.event).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn history_follows_the_order() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(50_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, ONE_BTC))
+            .with_operation(orderbook, Operation::ReduceOrder {
+                order_id: 0,
+                reduce_by: ONE_BTC / 4,
+                cancel_if_below_minimum: false,
+                on_behalf_of: None,
+            })
+            .with_operation(orderbook, place(OrderSide::Buy, ONE_BTC / 2))
+            .with_operation(orderbook, Operation::CancelOrder { order_id: 0, on_behalf_of: None });
+    }).await;
+
+    let placed = |side, quantity| OrderHistoryEvent::Placed {
+        side,
+        order_type: OrderType::Limit,
+        price: PRICE,
+        quantity,
+        time_in_force: TimeInForce::GTC,
+    };
+    assert_eq!(events(history(&user, orderbook, 0).await), vec![
+        placed(OrderSide::Sell, ONE_BTC),
+        OrderHistoryEvent::Reduced { reduce_by: ONE_BTC / 4, remaining: ONE_BTC * 3 / 4 },
+        OrderHistoryEvent::Filled {
+            trade_id: 0,
+            price: PRICE,
+            quantity: ONE_BTC / 2,
+            remaining: ONE_BTC / 4,
+            maker: true,
+        },
+        OrderHistoryEvent::Cancelled { remaining: ONE_BTC / 4 },
+    ]);
+    assert_eq!(events(history(&user, orderbook, 1).await), vec![
+        placed(OrderSide::Buy, ONE_BTC / 2),
+        OrderHistoryEvent::Filled { trade_id: 0, price: PRICE, quantity: ONE_BTC / 2, remaining: 0, maker: false },
+    ]);
+
+    // The fill's trade id finds the trade in the export
+    match user.query(orderbook, Query::GetTrades { from_id: 0, limit: 1 }).await {
+        QueryResponse::Trades(TradePage::Trades { trades, .. }) => {
+            assert_eq!((trades[0].maker_order_id, trades[0].taker_order_id), (0, 1));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetOrderHistory { order_id: 2 }).await {
+        QueryResponse::OrderHistory(history) => assert_eq!(history, None),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,91 @@
+//! Order rejections: an account recording its rejections keeps refused placements, with their codes, instead of failing.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    Operation, OrderBookEvent, OrderSide, Query, QueryResponse, RejectedOrder, RejectionCode, TimeInForce,
+};
+use linera_base::data_types::Amount;
+
+const PRICE_SCALE: u64 = 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, price: u64, quantity: u64, time_in_force: TimeInForce) -> Operation {
+    OrderPlacement {
+        time_in_force,
+        client_request_id: Some(7),
+        ..place_order(side, price * PRICE_SCALE, quantity)
+    }
+    .into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recorded_rejections_keep_the_reason() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let mut bot = deployment.new_user().await;
+    let bot_account = owner_account(&bot);
+    let orderbook = deployment.orderbook;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, place(OrderSide::Sell, 50_000, ONE_BTC, TimeInForce::GTC));
+    }).await;
+
+    // Without recording, a refused placement fails the block
+    let result = bot.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, 50_000, ONE_BTC, TimeInForce::GTC));
+    }).await;
+    assert!(result.is_err());
+
+    bot.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, Operation::RecordRejections { enabled: true })
+            // More than the book holds: refused, and the fill it would have made is not kept
+            .with_operation(orderbook, place(OrderSide::Buy, 50_000, 2 * ONE_BTC, TimeInForce::FOK));
+    }).await;
+
+    let rejections = match bot.query(orderbook, Query::GetRecentRejections { account: bot_account }).await {
+        QueryResponse::RecentRejections(rejections) => rejections,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!(rejections.len(), 1);
+    assert_eq!(rejections[0].code, RejectionCode::NoLiquidity);
+    assert!(matches!(
+        rejections[0].order,
+        RejectedOrder::Order { quantity, client_request_id: Some(7), .. } if quantity == 2 * ONE_BTC
+    ));
+
+    match bot.query(orderbook, Query::GetOrderBook { depth: 1 }).await {
+        QueryResponse::OrderBook { asks, .. } => assert_eq!(asks, vec![(50_000 * PRICE_SCALE, ONE_BTC)]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match bot.query(orderbook, Query::GetAccountBalance { account: bot_account, asset: "USDT".to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, .. } => {
+            assert_eq!((available, locked), (Amount::from_tokens(200_000), Amount::ZERO));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match bot.query(orderbook, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [OrderBookEvent::OrderRejected { account, rejection }] if *account == bot_account && *rejection == rejections[0]
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Once recording stops, refusals fail again
+    bot.add_block(|block| {
+        block.with_operation(orderbook, Operation::RecordRejections { enabled: false });
+    }).await;
+    let result = bot.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, 50_000, 2 * ONE_BTC, TimeInForce::FOK));
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,99 @@
+//! Order simulation: a dry run reports the fills, status and book a real placement then produces.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    Operation, OrderSide, OrderStatus, OrderType, PlacementSimulation, Query, QueryResponse, TimeInForce,
+};
+use linera_base::data_types::{Amount, Timestamp};
+
+const PRICE_SCALE: u64 = 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, order_type: OrderType, price: u64, quantity: u64) -> Operation {
+    OrderPlacement { order_type, ..place_order(side, price * PRICE_SCALE, quantity) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulation_matches_placement() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Limit, 50_000, ONE_BTC / 2))
+            .with_operation(orderbook, place(OrderSide::Sell, OrderType::Limit, 51_000, ONE_BTC))
+            .with_operation(orderbook, place(OrderSide::Buy, OrderType::Limit, 49_000, ONE_BTC));
+    }).await;
+
+    // A bid through the first ask takes it and rests the rest below the second
+    let query = Query::SimulatePlaceOrder {
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        price: 50_500 * PRICE_SCALE,
+        quantity: ONE_BTC,
+        time_in_force: TimeInForce::GTC,
+        require_full_fill: false,
+        min_fill_quantity: None,
+        account,
+        at: Timestamp::from(0),
+    };
+    let simulation: PlacementSimulation = match user.query(orderbook, query).await {
+        QueryResponse::PlacementSimulation(simulation) => simulation,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!((simulation.order_id, simulation.status), (3, OrderStatus::PartiallyFilled));
+    assert_eq!(simulation.fills.len(), 1);
+    assert_eq!((simulation.fills[0].maker_order_id, simulation.fills[0].price), (0, 50_000 * PRICE_SCALE));
+    assert_eq!(simulation.best_bid, Some(50_500 * PRICE_SCALE));
+    assert_eq!(simulation.best_ask, Some(51_000 * PRICE_SCALE));
+
+    // Nothing was stored by the query
+    match user.query(orderbook, Query::GetOrder { order_id: 3 }).await {
+        QueryResponse::Order(order) => assert_eq!(order, None),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    user.add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, OrderType::Limit, 50_500, ONE_BTC));
+    }).await;
+    match user.query(orderbook, Query::GetOrder { order_id: 3 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, simulation.status);
+            assert_eq!(order.filled_quantity, simulation.filled_quantity);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetOrderBook { depth: 1 }).await {
+        QueryResponse::OrderBook { bids, asks } => {
+            assert_eq!(bids.first().map(|(price, _)| *price), simulation.best_bid);
+            assert_eq!(asks.first().map(|(price, _)| *price), simulation.best_ask);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // A fill-or-kill beyond the book's depth is refused just as placing it would be
+    let query = Query::SimulatePlaceOrder {
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        price: 51_000 * PRICE_SCALE,
+        quantity: ONE_BTC * 3 / 2,
+        time_in_force: TimeInForce::FOK,
+        require_full_fill: false,
+        min_fill_quantity: None,
+        account,
+        at: Timestamp::from(0),
+    };
+    match user.query(orderbook, query).await {
+        QueryResponse::Error(_) => {}
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,259 @@
+//! Order book invariants: seeded random sequences of deposits, placements, modifications, cancels,
+//! fills, expiries and withdrawals keep both assets conserved, the book consistent and the open-order
+//! requirements in step with the locks after every step.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::collections::BTreeMap;
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    Operation, OrderBookAbi, OrderSide, OrderType, Price, Quantity, Query, QueryResponse, TimeInForce, TradingView,
+    MAX_TRADING_VIEW_DEPTH,
+};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::{ActiveChain, TestValidator};
+use proptest::{
+    prelude::*,
+    strategy::ValueTree,
+    test_runner::{RngAlgorithm, TestRng, TestRunner},
+};
+
+const ONE_BTC: u64 = 100_000_000;
+const USD: u64 = 100_000_000;
+const MID_PRICE: Price = 50_000 * USD;
+const TICK: Price = 100 * USD;
+const BASE: &str = "BTC";
+const QUOTE: &str = "USDT";
+
+/// Seeds run, each on a fresh deployment
+const SEEDS: [u8; 3] = [1, 2, 3];
+
+/// Steps per sequence; few enough that every order fits in one trading view
+const STEPS: usize = 40;
+
+/// One step of a sequence, applied as its own block
+#[derive(Debug, Clone)]
+enum Step {
+    Execute(Operation),
+    /// Resting limit order expiring this many seconds after it is placed
+    PlaceExpiring { side: OrderSide, price: Price, quantity: Quantity, seconds: u64 },
+    /// Advance the clock, letting expiring orders lapse
+    Wait { seconds: u64 },
+}
+
+fn asset() -> impl Strategy<Value = String> {
+    prop_oneof![Just(BASE.to_string()), Just(QUOTE.to_string())]
+}
+
+fn amount(asset: &str, units: u64) -> Amount {
+    match asset {
+        BASE => Amount::from_millis(units * 100),
+        _ => Amount::from_tokens(units * 5_000),
+    }
+}
+
+fn side() -> impl Strategy<Value = OrderSide> {
+    prop_oneof![Just(OrderSide::Buy), Just(OrderSide::Sell)]
+}
+
+/// Prices within four ticks of the middle, so that orders cross often
+fn price() -> impl Strategy<Value = Price> {
+    (0u64..=8).prop_map(|tick| MID_PRICE - 4 * TICK + tick * TICK)
+}
+
+fn quantity() -> impl Strategy<Value = Quantity> {
+    (1u64..=50).prop_map(|hundredths| hundredths * ONE_BTC / 100)
+}
+
+fn order_type() -> impl Strategy<Value = OrderType> {
+    prop_oneof![
+        4 => Just(OrderType::Limit),
+        2 => Just(OrderType::Market),
+        1 => price().prop_map(|trigger_price| OrderType::StopLoss { trigger_price }),
+        1 => price().prop_map(|trigger_price| OrderType::TakeProfit { trigger_price }),
+    ]
+}
+
+fn time_in_force() -> impl Strategy<Value = TimeInForce> {
+    prop_oneof![
+        3 => Just(TimeInForce::GTC),
+        1 => Just(TimeInForce::IOC),
+        1 => Just(TimeInForce::FOK),
+        1 => Just(TimeInForce::PostOnly),
+    ]
+}
+
+/// Ids of orders the sequence may have placed; unknown ones are refused
+fn order_id() -> impl Strategy<Value = u64> {
+    0u64..STEPS as u64
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        3 => (asset(), 1u64..=20).prop_map(|(asset, units)| {
+            Step::Execute(Operation::Deposit { amount: amount(&asset, units), asset })
+        }),
+        1 => (asset(), 1u64..=20).prop_map(|(asset, units)| {
+            Step::Execute(Operation::Withdraw { amount: amount(&asset, units), asset })
+        }),
+        6 => (side(), order_type(), price(), quantity(), time_in_force(), any::<bool>()).prop_map(
+            |(side, order_type, price, quantity, time_in_force, require_full_fill)| {
+                Step::Execute(Operation::from(OrderPlacement {
+                    order_type,
+                    time_in_force,
+                    require_full_fill,
+                    ..place_order(side, price, quantity)
+                }))
+            }
+        ),
+        1 => (1u64..=20, proptest::option::of(price())).prop_map(|(units, max_price)| {
+            Step::Execute(Operation::PlaceQuoteOrder {
+                quote_amount: amount(QUOTE, units),
+                max_price,
+                client_request_id: None,
+                on_behalf_of: None,
+            })
+        }),
+        1 => (side(), price(), quantity(), 1u64..=120).prop_map(|(side, price, quantity, seconds)| {
+            Step::PlaceExpiring { side, price, quantity, seconds }
+        }),
+        2 => (order_id(), proptest::option::of(price()), proptest::option::of(quantity())).prop_map(
+            |(order_id, new_price, new_quantity)| Step::Execute(Operation::ModifyOrder { order_id, new_price, new_quantity })
+        ),
+        1 => (order_id(), quantity(), any::<bool>()).prop_map(|(order_id, reduce_by, cancel_if_below_minimum)| {
+            Step::Execute(Operation::ReduceOrder { order_id, reduce_by, cancel_if_below_minimum, on_behalf_of: None })
+        }),
+        2 => order_id().prop_map(|order_id| Step::Execute(Operation::CancelOrder { order_id, on_behalf_of: None })),
+        1 => (1u64..=120).prop_map(|seconds| Step::Wait { seconds }),
+    ]
+}
+
+/// The steps `seed` always produces
+fn sequence(seed: u8) -> Vec<Step> {
+    let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[seed; 32]);
+    let mut runner = TestRunner::new_with_rng(ProptestConfig::default(), rng);
+    proptest::collection::vec(step(), STEPS).new_tree(&mut runner).expect("steps are never rejected").current()
+}
+
+async fn trading_view(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, account: Account) -> TradingView {
+    match chain.query(orderbook, Query::GetTradingView { account, depth_levels: MAX_TRADING_VIEW_DEPTH }).await {
+        QueryResponse::TradingView(view) => view,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn collected_fees(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, asset: &str) -> Amount {
+    match chain.query(orderbook, Query::GetCollectedFees { asset: asset.to_string() }).await {
+        QueryResponse::CollectedFees(collected) => collected,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+/// Checks every invariant against the state after `step`
+async fn check_invariants(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    deposited: &BTreeMap<String, Amount>,
+    step: &str,
+) {
+    let view = trading_view(chain, orderbook, account).await;
+    assert!(!view.more_orders, "{step}: open orders do not fit in the view");
+
+    // Free, locked and collected fees add up to what was deposited and not withdrawn
+    for balance in &view.balances {
+        let fees = collected_fees(chain, orderbook, &balance.asset).await;
+        let total = balance.available.saturating_add(balance.locked).saturating_add(fees);
+        let expected = deposited.get(&balance.asset).copied().unwrap_or_default();
+        assert_eq!(total, expected, "{step}: {} is not conserved: {balance:?}, fees {fees}", balance.asset);
+        // Amounts cannot go negative, but a lock outliving every order would be a leak
+        if view.open_orders.is_empty() {
+            assert_eq!(balance.locked, Amount::ZERO, "{step}: {} stays locked without orders", balance.asset);
+        }
+
+        // The open-order requirement follows the locks, so the withdrawal guard never holds back free balance
+        let paying = view.open_orders.iter()
+            .filter(|order| (order.side == OrderSide::Buy) == (balance.asset == QUOTE))
+            .count() as u32;
+        let query = Query::GetMaxWithdrawable { account, asset: balance.asset.clone() };
+        match chain.query(orderbook, query).await {
+            QueryResponse::MaxWithdrawable { amount, blocking_orders } => {
+                assert_eq!(amount, balance.available, "{step}: {} withdrawal held back", balance.asset);
+                assert_eq!(blocking_orders, paying, "{step}: {} open orders miscounted", balance.asset);
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    // Every level holds exactly the remaining quantity of its orders
+    let mut bids = BTreeMap::new();
+    let mut asks = BTreeMap::new();
+    for order in &view.open_orders {
+        assert!(order.filled_quantity < order.quantity, "{step}: order {} is open but filled", order.id);
+        let levels = match order.side {
+            OrderSide::Buy => &mut bids,
+            OrderSide::Sell => &mut asks,
+        };
+        *levels.entry(order.price).or_insert(0) += order.remaining_quantity();
+    }
+    assert_eq!(view.bids.iter().copied().collect::<BTreeMap<_, _>>(), bids, "{step}: bid levels");
+    assert_eq!(view.asks.iter().copied().collect::<BTreeMap<_, _>>(), asks, "{step}: ask levels");
+
+    // The book is never left crossed
+    if let (Some((best_bid, _)), Some((best_ask, _))) = (view.bids.first(), view.asks.first()) {
+        assert!(best_bid < best_ask, "{step}: book crossed at {best_bid} / {best_ask}");
+    }
+}
+
+async fn run(validator: &TestValidator, orderbook: ApplicationId<OrderBookAbi>, mut chain: ActiveChain, seed: u8) {
+    let account = owner_account(&chain);
+    let mut deposited = BTreeMap::<String, Amount>::new();
+    for (index, step) in sequence(seed).into_iter().enumerate() {
+        let description = format!("seed {seed}, step {index} ({step:?})");
+        let operation = match step {
+            Step::Execute(operation) => operation,
+            Step::PlaceExpiring { side, price, quantity, seconds } => Operation::from(OrderPlacement {
+                expires_at: Some(validator.clock().current_time().saturating_add(TimeDelta::from_secs(seconds))),
+                ..place_order(side, price, quantity)
+            }),
+            Step::Wait { seconds } => {
+                validator.clock().add(TimeDelta::from_secs(seconds));
+                check_invariants(&chain, orderbook, account, &deposited, &description).await;
+                continue;
+            }
+        };
+
+        // Refused operations leave no trace, and must leave the invariants holding too
+        let applied = chain.try_add_block(|block| {
+            block.with_operation(orderbook, operation.clone());
+        }).await.is_ok();
+        if applied {
+            match &operation {
+                Operation::Deposit { asset, amount } => {
+                    let total = deposited.entry(asset.clone()).or_default();
+                    *total = total.saturating_add(*amount);
+                }
+                Operation::Withdraw { asset, amount } => {
+                    let total = deposited.entry(asset.clone()).or_default();
+                    *total = total.saturating_sub(*amount);
+                }
+                _ => {}
+            }
+        }
+        check_invariants(&chain, orderbook, account, &deposited, &description).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn random_sequences_keep_the_book_consistent() {
+    // One account trades against itself, as each chain has its own book
+    for seed in SEEDS {
+        let deployment = Deployment::new().await;
+        let chain = deployment.new_user().await;
+        run(&deployment.validator, deployment.orderbook, chain, seed).await;
+    }
+}
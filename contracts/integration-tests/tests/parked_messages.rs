@@ -0,0 +1,84 @@
+//! Parked messages: sealed messages between current builds are applied, not parked, and only existing parked messages can be replayed or discarded.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, Deployment};
+use axelarx_orderbook::{
+    ForwardOutcome, ForwardedOrderSpec, Operation, OrderBookAbi, OrderSide, OrderType, Query, QueryResponse,
+    TimeInForce,
+};
+use axelarx_settlement::{
+    Operation as SettlementOperation, Query as SettlementQuery, QueryResponse as SettlementQueryResponse,
+};
+use linera_base::identifiers::{ApplicationId, ChainId};
+use linera_sdk::test::ActiveChain;
+
+fn register(chain_id: ChainId) -> Operation {
+    Operation::RegisterSiblingMarket {
+        chain_id,
+        base_asset: "BTC".to_string(),
+        quote_asset: "USDT".to_string(),
+    }
+}
+
+async fn parked_count(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>) -> usize {
+    match chain.query(orderbook, Query::GetParkedMessages).await {
+        QueryResponse::ParkedMessages(messages) => messages.len(),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs the admin role on every market chain; only the creator chain has one"]
+async fn current_messages_are_not_parked() {
+    let deployment = Deployment::new().await;
+    let mut home = deployment.new_user().await;
+    let mut market = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    market.add_block(|block| {
+        block.with_operation(orderbook, register(home.id()));
+    }).await;
+    home.add_block(|block| {
+        block.with_operation(orderbook, register(market.id())).with_operation(orderbook, Operation::ForwardOrder {
+            market_chain: market.id(),
+            spec: ForwardedOrderSpec {
+                side: OrderSide::Sell,
+                order_type: OrderType::Limit,
+                price: 50_000 * 100_000_000,
+                quantity: 100_000_000,
+                time_in_force: TimeInForce::GTC,
+                expires_at: None,
+                require_full_fill: false,
+                min_fill_quantity: None,
+            },
+        });
+    }).await;
+    market.handle_received_messages().await;
+    home.handle_received_messages().await;
+
+    // The forward and its acknowledgment both went through their envelopes
+    match home.query(orderbook, Query::GetForwardedOrders { user: owner_account(&home) }).await {
+        QueryResponse::ForwardedOrders(orders) => assert_ne!(orders[0].outcome, ForwardOutcome::Pending),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(parked_count(&home, orderbook).await, 0);
+    assert_eq!(parked_count(&market, orderbook).await, 0);
+
+    // Nothing to replay or discard
+    for operation in [Operation::ReplayParkedMessage { parked_id: 0 }, Operation::DiscardParkedMessage { parked_id: 0 }] {
+        let result = home.try_add_block(|block| {
+            block.with_operation(orderbook, operation.clone());
+        }).await;
+        assert!(result.is_err());
+    }
+    let settlement = deployment.settlement;
+    let result = home.try_add_block(|block| {
+        block.with_operation(settlement, SettlementOperation::DiscardParkedMessage { parked_id: 0 });
+    }).await;
+    assert!(result.is_err());
+    match home.query(settlement, SettlementQuery::GetParkedMessages).await {
+        SettlementQueryResponse::ParkedMessages(messages) => assert!(messages.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
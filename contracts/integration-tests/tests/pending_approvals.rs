@@ -0,0 +1,78 @@
+//! Validator work queue: withdrawals short of quorum, with the payload to sign, until approved.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse, TransferId};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn withdraw(amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+async fn pending(
+    user: &ActiveChain,
+    bridge: ApplicationId<BridgeAbi>,
+    validator: Account,
+    cursor: Option<TransferId>,
+    limit: usize,
+) -> (Vec<TransferId>, Option<TransferId>) {
+    match user.query(bridge, Query::GetPendingApprovals { validator, cursor, limit }).await {
+        QueryResponse::PendingApprovals { approvals, next_cursor } => {
+            for approval in &approvals {
+                assert_eq!(approval.payload, approval.transfer.approval_payload().unwrap());
+            }
+            (approvals.iter().map(|approval| approval.transfer.id).collect(), next_cursor)
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn withdrawals_leave_the_queue_at_quorum() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw(Amount::from_tokens(100)))
+            .with_operation(bridge, withdraw(Amount::from_tokens(200)));
+    }).await;
+
+    assert_eq!(pending(&user, bridge, account, None, 10).await, (vec![2, 3], None));
+    assert_eq!(pending(&user, bridge, account, None, 1).await, (vec![2], Some(2)));
+    assert_eq!(pending(&user, bridge, account, Some(2), 1).await, (vec![3], None));
+
+    // The sole validator's signature is a quorum
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![] });
+    }).await;
+    assert_eq!(pending(&user, bridge, account, None, 10).await, (vec![3], None));
+}
@@ -0,0 +1,99 @@
+//! Portfolio notifications: balance changes leave as one net delta per account and asset per block, numbered so the
+//! aggregator can match them against a snapshot, and the aggregator adds them up.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, Deployment};
+use axelarx_orderbook::{AssetBalance, BalanceSnapshot, OrderBookAbi, Operation, Query, QueryResponse};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn deposit(asset: &str, tokens: u128) -> Operation {
+    Operation::Deposit { asset: asset.to_string(), amount: Amount::from_tokens(tokens) }
+}
+
+fn withdraw(asset: &str, tokens: u128) -> Operation {
+    Operation::Withdraw { asset: asset.to_string(), amount: Amount::from_tokens(tokens) }
+}
+
+async fn snapshot(user: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, account: Account) -> BalanceSnapshot {
+    match user.query(orderbook, Query::BalanceSnapshot { account }).await {
+        QueryResponse::BalanceSnapshot(snapshot) => snapshot,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn attos(tokens: u128) -> i128 {
+    i128::try_from(u128::from(Amount::from_tokens(tokens))).unwrap()
+}
+
+fn free(asset: &str, tokens: u128) -> AssetBalance {
+    AssetBalance {
+        asset: asset.to_string(),
+        available: Amount::from_tokens(tokens),
+        locked: Amount::ZERO,
+        unsettled: Amount::ZERO,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deltas_are_coalesced_and_numbered() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let mut aggregator = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    // Nothing is numbered before an aggregator is configured
+    user.add_block(|block| {
+        block.with_operation(orderbook, deposit("BTC", 1));
+    }).await;
+    assert_eq!(snapshot(&user, orderbook, account).await.sequence, 0);
+
+    // Three changes to two assets make one delta per asset
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetPortfolioAggregator { chain: Some(aggregator.id()) })
+            .with_operation(orderbook, deposit("BTC", 2))
+            .with_operation(orderbook, deposit("USDT", 500))
+            .with_operation(orderbook, withdraw("BTC", 1));
+    }).await;
+    assert_eq!(snapshot(&user, orderbook, account).await, BalanceSnapshot {
+        account,
+        sequence: 2,
+        balances: vec![free("BTC", 2), free("USDT", 500)],
+    });
+
+    // The aggregator applies each net delta once
+    aggregator.handle_received_messages().await;
+    for (asset, tokens) in [("BTC", 1), ("USDT", 500)] {
+        let query = Query::GetAggregatedBalance { account, asset: asset.to_string() };
+        match aggregator.query(orderbook, query).await {
+            QueryResponse::AggregatedBalance(balance) => assert_eq!(balance, attos(tokens)),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+    match aggregator.query(orderbook, Query::GetAggregatedSequence { market: user.id(), account }).await {
+        QueryResponse::AggregatedSequence(sequence) => assert_eq!(sequence, 2),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Changes that net out within a block send nothing
+    user.add_block(|block| {
+        block.with_operation(orderbook, deposit("USDT", 100)).with_operation(orderbook, withdraw("USDT", 100));
+    }).await;
+    assert_eq!(snapshot(&user, orderbook, account).await.sequence, 2);
+
+    // Once the aggregator is removed, nothing more is numbered
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetPortfolioAggregator { chain: None })
+            .with_operation(orderbook, deposit("USDT", 100));
+    }).await;
+    let snapshot = snapshot(&user, orderbook, account).await;
+    assert_eq!(snapshot.sequence, 2);
+    assert_eq!(snapshot.balances, vec![free("BTC", 2), free("USDT", 600)]);
+}
@@ -0,0 +1,84 @@
+//! Price level caps: orders past a level's total or per-account cap are refused, by placement and simulation alike.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderSide, OrderType, Query, QueryResponse, TimeInForce};
+use linera_base::data_types::{Amount, Timestamp};
+
+const PRICE_SCALE: u64 = 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn ask(price: u64) -> Operation {
+    place_order(OrderSide::Sell, price * PRICE_SCALE, ONE_BTC / 10).into()
+}
+
+fn level_caps(max_orders_per_level: u32, max_account_orders_per_level: u32) -> Operation {
+    Operation::UpdateConfig {
+        min_order_size: None,
+        max_order_size: None,
+        tick_size: None,
+        fee_model: None,
+        max_orders_per_level: Some(max_orders_per_level),
+        max_account_orders_per_level: Some(max_account_orders_per_level),
+        allow_unsettled_trading: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_levels_refuse_new_orders() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, level_caps(2, 0))
+            .with_operation(orderbook, ask(50_000))
+            .with_operation(orderbook, ask(50_000));
+    }).await;
+
+    // The level is full: a third order there is refused, one a tick away is not
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, ask(50_000));
+    }).await;
+    assert!(result.is_err());
+    let query = Query::SimulatePlaceOrder {
+        side: OrderSide::Sell,
+        order_type: OrderType::Limit,
+        price: 50_000 * PRICE_SCALE,
+        quantity: ONE_BTC / 10,
+        time_in_force: TimeInForce::GTC,
+        require_full_fill: false,
+        min_fill_quantity: None,
+        account,
+        at: Timestamp::from(0),
+    };
+    match user.query(orderbook, query).await {
+        QueryResponse::Error(_) => {}
+        other => panic!("unexpected response: {other:?}"),
+    }
+    user.add_block(|block| {
+        block.with_operation(orderbook, ask(50_001));
+    }).await;
+
+    // Cancelling frees a place at the level
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::CancelOrder { order_id: 0, on_behalf_of: None })
+            .with_operation(orderbook, ask(50_000));
+    }).await;
+
+    // The account cap applies without a level cap
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, level_caps(0, 1))
+            .with_operation(orderbook, ask(52_000));
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, ask(52_000));
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,111 @@
+//! Processing windows: withdrawals outside them are scheduled, and released into approval with a fresh expiry when one opens.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeTransfer, ExternalChain, Operation, ProcessingWindow, Query, QueryResponse, TransferStatus,
+    SECONDS_PER_DAY,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+fn withdraw(amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+async fn transfer(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: u64) -> BridgeTransfer {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn withdrawals_wait_for_the_processing_window() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    // The only window opens in an hour and lasts an hour
+    let now = deployment.validator.clock().current_time();
+    let second_of_day = now.micros() / 1_000_000 % SECONDS_PER_DAY;
+    let window = ProcessingWindow {
+        start: (second_of_day + 3600) % SECONDS_PER_DAY,
+        end: (second_of_day + 7200) % SECONDS_PER_DAY,
+    };
+    let opening = now.saturating_add(TimeDelta::from_secs(3600));
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::SetProcessingWindows {
+                chain: ExternalChain::Ethereum,
+                windows: vec![window],
+            })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw(Amount::from_tokens(100)));
+    }).await;
+
+    match user.query(bridge, Query::GetProcessingWindows { chain: ExternalChain::Ethereum, at: now }).await {
+        QueryResponse::ProcessingWindows(status) => {
+            assert_eq!(status.windows, vec![window]);
+            assert!(!status.open);
+            assert_eq!(status.next_processing_at, opening);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Accepted, but held; its expiry counts from the opening
+    let scheduled = transfer(&user, bridge, 2).await;
+    assert_eq!(scheduled.status, TransferStatus::Scheduled);
+    assert_eq!(scheduled.release_at, Some(opening));
+    let expiry = TimeDelta::from_secs(ethereum_config().outbound_expiry_seconds);
+    assert_eq!(scheduled.expires_at, opening.saturating_add(expiry));
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![] });
+    }).await;
+    assert!(result.is_err());
+
+    // The keeper sweep releases it once the window opens
+    deployment.validator.clock().add(TimeDelta::from_secs(3600));
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ProcessExpiredTransfers);
+    }).await;
+    let released = transfer(&user, bridge, 2).await;
+    assert_eq!(released.status, TransferStatus::AwaitingApproval);
+    assert_eq!(released.release_at, Some(opening));
+
+    // Inside the window withdrawals go straight to approval
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, withdraw(Amount::from_tokens(100)))
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![] });
+    }).await;
+    assert_eq!(transfer(&user, bridge, 3).await.status, TransferStatus::AwaitingApproval);
+    assert_eq!(transfer(&user, bridge, 3).await.release_at, None);
+    assert_eq!(transfer(&user, bridge, 2).await.status, TransferStatus::Approved);
+}
@@ -0,0 +1,114 @@
+//! Proof of reserves: per-asset liabilities follow deposits and withdrawals through their lifecycle, and snapshots keep the report hash of a block.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    AssetLiabilities, BridgeAbi, BridgeTransfer, ExternalChain, Operation, ProofOfReserves, Query, QueryResponse,
+    TransferId,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+async fn report(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> ProofOfReserves {
+    match user.query(bridge, Query::GetProofOfReserves).await {
+        QueryResponse::ProofOfReserves(report) => report,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn liabilities(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> AssetLiabilities {
+    let report = report(user, bridge).await;
+    assert_eq!(report.assets.len(), 1);
+    report.assets[0].clone()
+}
+
+async fn transfer(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: TransferId) -> BridgeTransfer {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reserves_follow_transfers() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    // Transfer 1 is the deposit, 2 the withdrawal
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            });
+    }).await;
+    let (deposit, withdrawal) = (transfer(&user, bridge, 1).await, transfer(&user, bridge, 2).await);
+    let user_balances = deposit.net_amount.saturating_sub(Amount::from_tokens(100));
+    let initiated = liabilities(&user, bridge).await;
+    assert_eq!(initiated.user_balances, user_balances);
+    assert_eq!(initiated.bridged_supply, Amount::from_tokens(1_000));
+    assert_eq!(initiated.in_flight_outbound, Amount::ZERO);
+
+    // Approval puts the withdrawal in flight; completion releases it from the bridged supply
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![] });
+    }).await;
+    assert_eq!(liabilities(&user, bridge).await.in_flight_outbound, withdrawal.net_amount);
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ExecuteTransfer { transfer_id: 2 })
+            .with_operation(bridge, Operation::CompleteWithdrawal {
+                transfer_id: 2,
+                tx_hash: "0xsent".to_string(),
+                success: true,
+            })
+            .with_operation(bridge, Operation::RecordReservesSnapshot);
+    }).await;
+    let completed = report(&user, bridge).await;
+    let figures = &completed.assets[0];
+    assert_eq!(figures.user_balances, user_balances);
+    assert_eq!(figures.in_flight_outbound, Amount::ZERO);
+    assert_eq!(figures.bridged_supply, Amount::from_tokens(1_000).saturating_sub(withdrawal.net_amount));
+    match user.query(bridge, Query::GetCollectedFees { asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::CollectedFees(fees) => assert_eq!(figures.collected_fees, fees),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The snapshot keeps the hash of the block it was recorded in, which later blocks move past
+    let snapshots = match user.query(bridge, Query::GetReservesSnapshots).await {
+        QueryResponse::ReservesSnapshots(snapshots) => snapshots,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!((snapshots[0].block_height, snapshots[0].hash), (completed.block_height, completed.hash));
+    assert_eq!(snapshots[0].recorded_by, account);
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::RecordReservesSnapshot);
+    }).await;
+    let later = report(&user, bridge).await;
+    assert_eq!(later.assets, completed.assets);
+    assert_ne!(later.hash, completed.hash);
+}
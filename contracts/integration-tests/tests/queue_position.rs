@@ -0,0 +1,64 @@
+//! Queue position: quantity and orders ahead of a resting order in its price level's FIFO.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, OrderStatus, Query, QueryResponse, QueuePosition};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn sell(quantity: u64) -> Operation {
+    place_order(OrderSide::Sell, PRICE, quantity).into()
+}
+
+async fn position(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: u64) -> QueuePosition {
+    match chain.query(orderbook, Query::GetQueuePosition { order_id }).await {
+        QueryResponse::QueuePosition(position) => position,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn position_counts_active_orders_ahead() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, sell(ONE_BTC))
+            .with_operation(orderbook, sell(ONE_BTC / 2))
+            .with_operation(orderbook, sell(ONE_BTC / 4));
+    }).await;
+
+    assert_eq!(position(&user, orderbook, 2).await, QueuePosition::Resting {
+        side: OrderSide::Sell,
+        price: PRICE,
+        orders_ahead: 2,
+        quantity_ahead: ONE_BTC * 3 / 2,
+        level_quantity: ONE_BTC * 7 / 4,
+        exact: true,
+    });
+
+    // Cancelling the head moves the rest up
+    user.add_block(|block| {
+        block.with_operation(orderbook, Operation::CancelOrder { order_id: 0, on_behalf_of: None });
+    }).await;
+    assert_eq!(position(&user, orderbook, 2).await, QueuePosition::Resting {
+        side: OrderSide::Sell,
+        price: PRICE,
+        orders_ahead: 1,
+        quantity_ahead: ONE_BTC / 2,
+        level_quantity: ONE_BTC * 3 / 4,
+        exact: true,
+    });
+    assert_eq!(
+        position(&user, orderbook, 0).await,
+        QueuePosition::NotResting { status: Some(OrderStatus::Cancelled) },
+    );
+    assert_eq!(position(&user, orderbook, 99).await, QueuePosition::NotResting { status: None });
+}
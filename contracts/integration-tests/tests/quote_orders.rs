@@ -0,0 +1,92 @@
+//! Quote orders: a market buy sized by what it spends never takes more than its budget and locks nothing after.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, OrderStatus, Query, QueryResponse};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const PRICE_SCALE: u64 = 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+async fn balance(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> (Amount, Amount) {
+    match chain.query(orderbook, Query::GetAccountBalance { account, asset: asset.to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, .. } => (available, locked),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+fn sell(price: u64) -> Operation {
+    place_order(OrderSide::Sell, price * PRICE_SCALE, ONE_BTC).into()
+}
+
+fn spend(quote_amount: Amount, max_price: Option<u64>) -> Operation {
+    Operation::PlaceQuoteOrder {
+        quote_amount,
+        max_price: max_price.map(|price| price * PRICE_SCALE),
+        client_request_id: None,
+        on_behalf_of: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quote_order_spends_within_budget() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, sell(50_000))
+            .with_operation(orderbook, sell(51_000));
+    }).await;
+
+    // The price cap stops the sweep after the first level and the rest of the budget comes back
+    user.add_block(|block| {
+        block.with_operation(orderbook, spend(Amount::from_tokens(75_000), Some(50_000)));
+    }).await;
+    match user.query(orderbook, Query::GetOrder { order_id: 2 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, OrderStatus::Filled);
+            assert_eq!(order.filled_quantity, ONE_BTC);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    // Both sides were this user: only the seller's 0.1% in USDT is gone
+    assert_eq!(balance(&user, orderbook, account, "USDT").await, (Amount::from_tokens(199_950), Amount::ZERO));
+
+    // 20,000 at 51,000 buys just under 0.3922 BTC, rounded down
+    user.add_block(|block| {
+        block.with_operation(orderbook, spend(Amount::from_tokens(20_000), None));
+    }).await;
+    match user.query(orderbook, Query::GetOrder { order_id: 3 }).await {
+        QueryResponse::Order(Some(order)) => {
+            assert_eq!(order.status, OrderStatus::Filled);
+            assert_eq!(order.filled_quantity, 39_215_686);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    let (_, locked) = balance(&user, orderbook, account, "USDT").await;
+    assert_eq!(locked, Amount::ZERO);
+
+    // A budget too small for the minimum order size is rejected
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, spend(Amount::from_millis(100), None));
+    }).await;
+    assert!(result.is_err());
+}
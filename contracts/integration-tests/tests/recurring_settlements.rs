@@ -0,0 +1,128 @@
+//! Recurring settlements: each due payment becomes a linked settlement instance, and the schedule stops after too many unpaid instances in a row or on cancellation.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, Deployment, TEST_ASSET};
+use axelarx_settlement::{
+    InstanceOutcome, Operation, Query, QueryResponse, RecurringEnd, RecurringInstance, RecurringLink,
+    RecurringSettlement, RecurringStatus, SettlementAbi,
+};
+use linera_base::{
+    data_types::{Amount, TimeDelta, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const WEEK: u64 = 7 * 24 * 3_600;
+
+fn create(payee: Account, timeout_seconds: u64) -> Operation {
+    Operation::CreateRecurringSettlement {
+        payee,
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        payee_chain: None,
+        interval_seconds: WEEK,
+        timeout_seconds,
+        end: RecurringEnd::Count(5),
+        max_consecutive_defaults: 2,
+        first_due_at: None,
+    }
+}
+
+fn process() -> Operation {
+    Operation::ProcessRecurringSettlements { limit: None }
+}
+
+async fn recurring(chain: &ActiveChain, settlement: ApplicationId<SettlementAbi>, recurring_id: u64) -> RecurringSettlement {
+    match chain.query(settlement, Query::GetRecurringSettlement { recurring_id }).await {
+        QueryResponse::RecurringSettlement(Some(recurring)) => recurring,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn instances(
+    chain: &ActiveChain,
+    settlement: ApplicationId<SettlementAbi>,
+    recurring_id: u64,
+    at: Timestamp,
+) -> (Vec<(RecurringInstance, InstanceOutcome)>, Vec<Timestamp>) {
+    match chain.query(settlement, Query::GetRecurringInstances { recurring_id, at }).await {
+        QueryResponse::RecurringInstances { past, upcoming } => (past, upcoming),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recurring_payments_stop_after_defaults() {
+    let deployment = Deployment::new().await;
+    let mut payer = deployment.new_user().await;
+    let payee = deployment.new_user().await;
+    let (payer_account, payee_account) = (owner_account(&payer), owner_account(&payee));
+    let settlement = deployment.settlement;
+    let clock = deployment.validator.clock();
+
+    // Each instance has to be escrowed before the next is due
+    let result = payer.try_add_block(|block| {
+        block.with_operation(settlement, create(payee_account, WEEK + 1));
+    }).await;
+    assert!(result.is_err());
+
+    payer.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(300) })
+            .with_operation(settlement, create(payee_account, 3_600))
+            .with_operation(settlement, process());
+    }).await;
+    match payer.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert_eq!(record.recurring, Some(RecurringLink { recurring_id: 0, sequence: 0 }));
+            assert_eq!((record.maker, record.taker), (payer_account, payee_account));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The payer escrows the first payment, which pays out at once; the next one is not due yet
+    payer.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::ConfirmEscrow {
+                settlement_id: 1,
+                on_behalf_of: None,
+                substitute_asset: None,
+                bridge_transfer_id: None,
+            })
+            .with_operation(settlement, process());
+    }).await;
+    let (past, upcoming) = instances(&payer, settlement, 0, clock.current_time()).await;
+    assert_eq!(past.len(), 1);
+    assert!(matches!(past[0].1, InstanceOutcome::Paid { completed_at: Some(_) }));
+    assert_eq!(upcoming.len(), 4);
+    assert_eq!(recurring(&payer, settlement, 0).await.next_due_at, upcoming[0]);
+
+    // Two payments in a row left unescrowed stop the schedule
+    for _ in 0..3 {
+        clock.add(TimeDelta::from_secs(WEEK));
+        payer.add_block(|block| {
+            block.with_operation(settlement, process());
+        }).await;
+    }
+    assert_eq!(recurring(&payer, settlement, 0).await.status, RecurringStatus::Defaulted);
+    let (past, upcoming) = instances(&payer, settlement, 0, clock.current_time()).await;
+    assert_eq!(past.len(), 3);
+    assert!(past[1..].iter().all(|(_, outcome)| matches!(outcome, InstanceOutcome::Defaulted { .. })));
+    assert!(upcoming.is_empty());
+
+    // Either party may cancel; nothing is created afterwards
+    payer.add_block(|block| {
+        block
+            .with_operation(settlement, create(payee_account, 3_600))
+            .with_operation(settlement, Operation::CancelRecurringSettlement { recurring_id: 1 })
+            .with_operation(settlement, process());
+    }).await;
+    let cancelled = recurring(&payer, settlement, 1).await;
+    assert_eq!(cancelled.status, RecurringStatus::Cancelled { by: payer_account });
+    assert!(instances(&payer, settlement, 1, clock.current_time()).await.0.is_empty());
+    match payer.query(settlement, Query::GetUserRecurringSettlements { account: payee_account }).await {
+        QueryResponse::UserRecurringSettlements(ids) => assert_eq!(ids, vec![0, 1]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,70 @@
+//! Order reduction: remaining quantity shrinks in place and the freed lock is released.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, OrderStatus, Query, QueryResponse, QueuePosition};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn sell(quantity: u64) -> Operation {
+    place_order(OrderSide::Sell, PRICE, quantity).into()
+}
+
+fn reduce(order_id: u64, reduce_by: u64, cancel_if_below_minimum: bool) -> Operation {
+    Operation::ReduceOrder { order_id, reduce_by, cancel_if_below_minimum, on_behalf_of: None }
+}
+
+async fn position(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: u64) -> QueuePosition {
+    match chain.query(orderbook, Query::GetQueuePosition { order_id }).await {
+        QueryResponse::QueuePosition(position) => position,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reduced_orders_keep_their_place() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, sell(ONE_BTC))
+            .with_operation(orderbook, sell(ONE_BTC / 2))
+            .with_operation(orderbook, reduce(0, ONE_BTC / 2, false));
+    }).await;
+
+    assert_eq!(position(&user, orderbook, 1).await, QueuePosition::Resting {
+        side: OrderSide::Sell,
+        price: PRICE,
+        orders_ahead: 1,
+        quantity_ahead: ONE_BTC / 2,
+        level_quantity: ONE_BTC,
+        exact: true,
+    });
+    match user.query(orderbook, Query::GetAccountBalance { account, asset: "BTC".to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, .. } => {
+            assert_eq!((available, locked), (Amount::from_tokens(1), Amount::from_tokens(1)));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Reducing to nothing needs the cancel flag
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, reduce(0, ONE_BTC / 2, false));
+    }).await;
+    assert!(result.is_err());
+    user.add_block(|block| {
+        block.with_operation(orderbook, reduce(0, ONE_BTC, true));
+    }).await;
+    match user.query(orderbook, Query::GetOrder { order_id: 0 }).await {
+        QueryResponse::Order(Some(order)) => assert_eq!(order.status, OrderStatus::Cancelled),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
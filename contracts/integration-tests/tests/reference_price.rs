@@ -0,0 +1,97 @@
+//! Reference price: a size-weighted trade price shrugs off a one-lot print, and index updates stay near it and go stale.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{
+    Operation, OrderBookAbi, OrderSide, Query, QueryResponse, ReferencePrice, ReferencePriceConfig,
+    ReferencePriceSource,
+};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::{ActiveChain, TestValidator};
+
+const ONE_BTC: u64 = 100_000_000;
+const USD: u64 = 100_000_000;
+
+fn order(side: OrderSide, price: u64, quantity: u64) -> Operation {
+    place_order(side, price, quantity).into()
+}
+
+async fn reference(
+    validator: &TestValidator,
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+) -> ReferencePrice {
+    let at = validator.clock().current_time();
+    match chain.query(orderbook, Query::GetReferencePrice { at }).await {
+        QueryResponse::ReferencePrice(reference) => reference,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reference_price_follows_size_and_index() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+    let config = ReferencePriceConfig {
+        source: ReferencePriceSource::Index,
+        window_trades: 3,
+        max_index_age_seconds: 60,
+        max_index_deviation_bps: 500,
+    };
+
+    // A full bitcoin trades at 50k, then a minimum-size order prints 60k
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetReferencePrice { config })
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, order(OrderSide::Sell, 50_000 * USD, ONE_BTC))
+            .with_operation(orderbook, order(OrderSide::Buy, 50_000 * USD, ONE_BTC))
+            .with_operation(orderbook, order(OrderSide::Sell, 60_000 * USD, 1_000))
+            .with_operation(orderbook, order(OrderSide::Buy, 60_000 * USD, 1_000));
+    }).await;
+
+    let summary = reference(&deployment.validator, &user, orderbook).await;
+    assert_eq!(summary.config, config);
+    assert_eq!(summary.price, None);
+    let trade_price = summary.trade_price.expect("two trades were recorded");
+    assert!((50_000 * USD..50_001 * USD).contains(&trade_price));
+
+    // The user publishes as the oracle once the admin role is handed over
+    let successor = deployment.new_user().await;
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetPriceOracle { oracle: Some(account) })
+            .with_operation(orderbook, Operation::TransferAdmin { new_admin: owner_account(&successor) });
+    }).await;
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::UpdateIndexPrice { price: 60_000 * USD, emergency: false });
+    }).await;
+    assert!(result.is_err());
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::UpdateIndexPrice { price: 60_000 * USD, emergency: true });
+    }).await;
+    assert!(result.is_err());
+
+    user.add_block(|block| {
+        block.with_operation(orderbook, Operation::UpdateIndexPrice { price: 51_000 * USD, emergency: false });
+    }).await;
+    let summary = reference(&deployment.validator, &user, orderbook).await;
+    assert_eq!(summary.price, Some(51_000 * USD));
+    assert_eq!(summary.index.map(|index| (index.updated_by, index.emergency)), Some((account, false)));
+
+    deployment.validator.clock().add(TimeDelta::from_secs(61));
+    let summary = reference(&deployment.validator, &user, orderbook).await;
+    assert_eq!(summary.price, None);
+    assert!(summary.index_stale);
+}
@@ -0,0 +1,128 @@
+//! Relayer bonds: reports need the chain's bond minimum, and slashing reaches balance still unbonding.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse, RelayerBondStatus};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::{ActiveChain, TestValidator};
+
+fn deposit(tx_hash: &str, recipient: Account) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: Some(recipient),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        block_height: 100,
+        confirmations: 12,
+        bridge_contract_address: None,
+    }
+}
+
+async fn bond(
+    validator: &TestValidator,
+    user: &ActiveChain,
+    bridge: ApplicationId<BridgeAbi>,
+    relayer: Account,
+) -> RelayerBondStatus {
+    let at = validator.clock().current_time();
+    match user.query(bridge, Query::GetRelayerBond { relayer, at }).await {
+        QueryResponse::RelayerBond(status) => status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn balance(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> Amount {
+    match user.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => balance,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_require_a_bond_that_can_be_slashed() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let relayer = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    // The first deposit funds the relayer before Ethereum asks for a bond
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, deposit("0xfirst", relayer))
+            .with_operation(bridge, Operation::ConfigureRelayerBonds {
+                asset: TEST_ASSET.to_string(),
+                unbonding_seconds: 3600,
+            })
+            .with_operation(bridge, Operation::SetRelayerBondMinimum {
+                chain: ExternalChain::Ethereum,
+                minimum: Amount::from_tokens(50),
+            });
+    }).await;
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, deposit("0xsecond", relayer));
+    }).await;
+    assert!(result.is_err());
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::BondRelayer { amount: Amount::from_tokens(50) })
+            .with_operation(bridge, deposit("0xsecond", relayer));
+    }).await;
+    let status = bond(&deployment.validator, &user, bridge, relayer).await;
+    assert_eq!(status.asset.as_deref(), Some(TEST_ASSET));
+    assert_eq!(status.bond.bonded, Amount::from_tokens(50));
+    let [chain] = status.chains.as_slice() else { panic!("one chain has a minimum") };
+    assert_eq!((chain.chain, chain.active), (ExternalChain::Ethereum, true));
+    match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => assert_eq!(transfer.reporter, Some(relayer)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Unbonding drops the relayer below the minimum
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::UnbondRelayer { amount: Amount::from_tokens(20) });
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, deposit("0xthird", relayer));
+    }).await;
+    assert!(result.is_err());
+
+    // The second deposit turns out fraudulent: the slash takes the bond, then the unbonding part
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::SlashRelayer {
+            relayer,
+            transfer_id: 2,
+            amount: Amount::from_tokens(40),
+            reason: "Deposit not found on Ethereum".to_string(),
+        });
+    }).await;
+    let status = bond(&deployment.validator, &user, bridge, relayer).await;
+    assert_eq!(status.bond.bonded, Amount::ZERO);
+    assert_eq!(status.bond.unbonding, Amount::from_tokens(10));
+    assert_eq!(status.bond.slashed, Amount::from_tokens(40));
+    assert!(!status.chains[0].active);
+    match user.query(bridge, Query::GetInsuranceFund { asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::InsuranceFund { balance, .. } => assert_eq!(balance, Amount::from_tokens(40)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The rest comes back after the cooldown
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::WithdrawRelayerBond);
+    }).await;
+    assert!(result.is_err());
+    deployment.validator.clock().add(TimeDelta::from_secs(3600));
+    let before = balance(&user, bridge, relayer).await;
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::WithdrawRelayerBond);
+    }).await;
+    assert_eq!(balance(&user, bridge, relayer).await, before.saturating_add(Amount::from_tokens(10)));
+}
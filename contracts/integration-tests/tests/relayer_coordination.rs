@@ -0,0 +1,116 @@
+//! Relayer coordination: work receipts per transfer, repeated reports counted as duplicates, and sharded work lists with soft claims.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeEvent, ExternalChain, Operation, Query, QueryResponse, RelayerAction, RelayerTask,
+    RelayerWorkItem, RelayerWorkStats, WorkClaim, WORK_CLAIM_SECONDS,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+/// Deposit at 3 of the 12 confirmations Ethereum needs, so it stays open for relayers
+fn deposit(tx_hash: &str, recipient: Account, tokens: u128) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        recipient: Some(recipient),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(tokens),
+        block_height: 100,
+        confirmations: 3,
+        bridge_contract_address: None,
+    }
+}
+
+async fn work(
+    user: &ActiveChain,
+    bridge: ApplicationId<BridgeAbi>,
+    relayer_index: u64,
+    at: Timestamp,
+) -> Vec<RelayerWorkItem> {
+    let query = Query::GetRelayerWork { chain: Some(ExternalChain::Ethereum), relayer_count: 2, relayer_index, at };
+    match user.query(bridge, query).await {
+        QueryResponse::RelayerWork(work) => work,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn relayers_see_receipts_duplicates_and_their_shard() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let relayer = owner_account(&user);
+    let bridge = deployment.bridge;
+    let now = deployment.validator.clock().current_time();
+
+    // Repeating a recorded report is accepted and counted; a conflicting one is refused
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, deposit("0xfirst", relayer, 100))
+            .with_operation(bridge, deposit("0xsecond", relayer, 100))
+            .with_operation(bridge, deposit("0xfirst", relayer, 100));
+    }).await;
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, deposit("0xfirst", relayer, 200));
+    }).await;
+    assert!(result.is_err());
+
+    match user.query(bridge, Query::GetRelayerWorkStats { relayer }).await {
+        QueryResponse::RelayerWorkStats(stats) => {
+            assert_eq!(stats, RelayerWorkStats { actions: 3, duplicates: 1, last_duplicate: Some(1) });
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetWorkReceipts { transfer_id: 1 }).await {
+        QueryResponse::WorkReceipts(receipts) => {
+            let actions: Vec<_> =
+                receipts.iter().map(|receipt| (receipt.relayer, receipt.action, receipt.duplicate)).collect();
+            assert_eq!(actions, vec![
+                (relayer, RelayerAction::ReportedDeposit, false),
+                (relayer, RelayerAction::ReportedDeposit, true),
+            ]);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [BridgeEvent::DuplicateSubmission { transfer_id: 1, action: RelayerAction::ReportedDeposit, first_by, .. }]
+                if *first_by == Some(relayer)
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Transfer ids split across two relayers
+    let unclaimed = |transfer_id| RelayerWorkItem {
+        transfer_id,
+        chain: ExternalChain::Ethereum,
+        task: RelayerTask::ConfirmDeposit,
+        claim: None,
+    };
+    assert_eq!(work(&user, bridge, 0, now).await, vec![unclaimed(2)]);
+    assert_eq!(work(&user, bridge, 1, now).await, vec![unclaimed(1)]);
+
+    // A soft claim shows until it lapses; only open transfers can be claimed
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ClaimRelayerWork { transfer_id: 2 });
+    }).await;
+    let until = now.saturating_add(TimeDelta::from_secs(WORK_CLAIM_SECONDS));
+    assert_eq!(work(&user, bridge, 0, now).await, vec![RelayerWorkItem {
+        claim: Some(WorkClaim { relayer, until }),
+        ..unclaimed(2)
+    }]);
+    assert_eq!(work(&user, bridge, 0, until).await, vec![unclaimed(2)]);
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ClaimRelayerWork { transfer_id: 9 });
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,203 @@
+//! Reorgs: a validator quorum reverts unfinalized deposits, and deposits already credited are clawed back within the window, leaving debt that blocks withdrawals until a credit repays it.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeTransfer, ExternalChain, Operation, Query, QueryResponse, TransferStatus, ValidatorConfig,
+    WithdrawalIssue,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+fn deposit(tx_hash: &str, recipient: Account, amount: u128, block_height: u64, confirmations: u64) -> Operation {
+    Operation::ReportDeposit {
+        source_chain: ExternalChain::Ethereum,
+        tx_hash: tx_hash.to_string(),
+        source_address: ADDRESS.to_string(),
+        recipient: Some(recipient),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(amount),
+        block_height,
+        confirmations,
+        bridge_contract_address: None,
+    }
+}
+
+fn withdraw(amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: ADDRESS.to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+fn seconds(seconds: u64) -> Timestamp {
+    Timestamp::from(seconds * 1_000_000)
+}
+
+async fn transfer(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: u64) -> BridgeTransfer {
+    match chain.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn balance(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> Amount {
+    match chain.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => balance,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn debt(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> Amount {
+    match chain.query(bridge, Query::GetDebt { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Debt(debt) => debt,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn withdrawal_issues(
+    chain: &ActiveChain,
+    bridge: ApplicationId<BridgeAbi>,
+    account: Account,
+    amount: Amount,
+) -> Vec<WithdrawalIssue> {
+    let query = Query::ValidateWithdrawal {
+        chain: ExternalChain::Ethereum,
+        asset: TEST_ASSET.to_string(),
+        amount,
+        destination_address: ADDRESS.to_string(),
+        account,
+        memo: None,
+        fee_voucher: None,
+        at: Timestamp::from(0),
+    };
+    match chain.query(bridge, query).await {
+        QueryResponse::WithdrawalValidation(Ok(_)) => Vec::new(),
+        QueryResponse::WithdrawalValidation(Err(issues)) => issues,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reorg_needs_a_quorum_and_returns_confirming_deposits_to_pending() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let other = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let absent = ValidatorConfig { weight: 2, ..sole_validator(&other) };
+
+    // Two of three weight are needed, and the user's single unit is not enough
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::AddValidator { config: absent })
+            .with_operation(bridge, deposit("0xconfirming", account, 1_000, 100, 3));
+    }).await;
+    assert_eq!(transfer(&user, bridge, 1).await.status, TransferStatus::Confirming);
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ReportReorg { chain: ExternalChain::Ethereum, from_height: 100 });
+    }).await;
+    let unreverted = transfer(&user, bridge, 1).await;
+    assert_eq!((unreverted.status, unreverted.confirmations), (TransferStatus::Confirming, 3));
+    assert!(unreverted.reorged_at.is_none());
+
+    // A validator's report counts once
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ReportReorg { chain: ExternalChain::Ethereum, from_height: 100 });
+    }).await;
+    assert!(result.is_err());
+
+    // With the other validator gone the user's weight is a quorum, and the deposit is re-confirmed from scratch
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::RemoveValidator { validator: owner_account(&other) })
+            .with_operation(bridge, Operation::ReportReorg { chain: ExternalChain::Ethereum, from_height: 99 });
+    }).await;
+    let reverted = transfer(&user, bridge, 1).await;
+    assert_eq!((reverted.status, reverted.confirmations), (TransferStatus::Pending, 0));
+    assert!(reverted.reorged_at.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clawed_back_deposits_leave_debt_until_repaid() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let recipient = owner_account(&deployment.new_user().await);
+    let bridge = deployment.bridge;
+
+    // One deposit completes well before the reorg, the other just before it
+    user.add_block(|block| {
+        block
+            .with_timestamp(seconds(1))
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::UpdateReorgClawbackWindow { seconds: 3_600 })
+            .with_operation(bridge, deposit("0xearly", account, 1_000, 100, 12));
+    }).await;
+    user.add_block(|block| {
+        block
+            .with_timestamp(seconds(7_200))
+            .with_operation(bridge, deposit("0xlate", account, 500, 200, 12));
+    }).await;
+    assert_eq!(transfer(&user, bridge, 1).await.status, TransferStatus::Completed);
+    let late = transfer(&user, bridge, 2).await;
+    assert_eq!(late.status, TransferStatus::Completed);
+
+    // All but 100 tokens is spent before the reorg is known
+    let spent = balance(&user, bridge, account).await - Amount::from_tokens(100);
+    user.add_block(|block| {
+        block
+            .with_timestamp(seconds(7_200))
+            .with_operation(bridge, Operation::Transfer { to: recipient, asset: TEST_ASSET.to_string(), amount: spent });
+    }).await;
+
+    // Only the deposit inside the window is clawed back; what was spent becomes debt
+    user.add_block(|block| {
+        block
+            .with_timestamp(seconds(7_201))
+            .with_operation(bridge, Operation::ReportReorg { chain: ExternalChain::Ethereum, from_height: 100 });
+    }).await;
+    assert_eq!(transfer(&user, bridge, 1).await.status, TransferStatus::Completed);
+    assert_eq!(transfer(&user, bridge, 2).await.status, TransferStatus::Reverted);
+    assert_eq!(balance(&user, bridge, account).await, Amount::ZERO);
+    let owed = late.net_amount - Amount::from_tokens(100);
+    assert_eq!(debt(&user, bridge, account).await, owed);
+
+    // Debt blocks withdrawals, both in the dry run and for real
+    assert!(withdrawal_issues(&user, bridge, account, Amount::from_tokens(10)).await
+        .contains(&WithdrawalIssue::OutstandingDebt));
+    let result = user.try_add_block(|block| {
+        block.with_timestamp(seconds(7_202)).with_operation(bridge, withdraw(Amount::from_tokens(10)));
+    }).await;
+    assert!(result.is_err());
+
+    // The next credit repays the debt before anything reaches the balance
+    user.add_block(|block| {
+        block
+            .with_timestamp(seconds(7_203))
+            .with_operation(bridge, deposit("0xrepay", account, 1_000, 300, 12));
+    }).await;
+    let repaid = transfer(&user, bridge, 3).await;
+    assert_eq!(repaid.status, TransferStatus::Completed);
+    assert_eq!(debt(&user, bridge, account).await, Amount::ZERO);
+    assert_eq!(balance(&user, bridge, account).await, repaid.net_amount - owed);
+    assert!(withdrawal_issues(&user, bridge, account, Amount::from_tokens(10)).await.is_empty());
+    user.add_block(|block| {
+        block.with_timestamp(seconds(7_204)).with_operation(bridge, withdraw(Amount::from_tokens(10)));
+    }).await;
+}
@@ -0,0 +1,83 @@
+//! Same-asset settlements: only the net difference is escrowed and moved, and a refund completes without the side that owed nothing.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse, SettlementAbi, SettlementStatus};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64, maker_amount: u128, taker_amount: u128) -> Operation {
+    SettlementTerms {
+        taker_asset: TEST_ASSET.to_string(),
+        maker_amount: Amount::from_tokens(maker_amount),
+        taker_amount: Amount::from_tokens(taker_amount),
+        timeout_seconds: 60,
+        ..initiate_settlement(maker, taker, trade_id)
+    }
+    .into()
+}
+
+fn confirm(settlement_id: u64) -> Operation {
+    Operation::ConfirmEscrow { settlement_id, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None }
+}
+
+async fn balance(chain: &ActiveChain, settlement: ApplicationId<SettlementAbi>, account: Account) -> Amount {
+    match chain.query(settlement, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => balance,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn status(chain: &ActiveChain, settlement: ApplicationId<SettlementAbi>, settlement_id: u64) -> SettlementStatus {
+    match chain.query(settlement, Query::GetSettlement { settlement_id }).await {
+        QueryResponse::Settlement(Some(record)) => record.status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn same_asset_settlements_move_the_net() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let (maker_account, taker_account) = (owner_account(&maker), owner_account(&taker));
+    let settlement = deployment.settlement;
+
+    // Legs that cancel out leave nothing to settle
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 1, 40, 40));
+    }).await;
+    assert!(result.is_err());
+
+    // The taker owes nothing on 40 against 15, so it starts out escrowed
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(100) })
+            .with_operation(settlement, initiate(&maker, &taker, 1, 40, 15));
+    }).await;
+    assert_eq!(status(&maker, settlement, 1).await, SettlementStatus::TakerEscrowed);
+
+    maker.add_block(|block| {
+        block.with_operation(settlement, confirm(1));
+    }).await;
+    assert_eq!(status(&maker, settlement, 1).await, SettlementStatus::Completed);
+    assert_eq!(balance(&maker, settlement, maker_account).await, Amount::from_tokens(75));
+    assert_eq!(balance(&maker, settlement, taker_account).await, Amount::from_tokens(25));
+
+    // The maker owes nothing on 10 against 30; once the taker lets it expire, the maker's claim
+    // returns nothing and still finishes the refund
+    maker.add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 2, 10, 30));
+    }).await;
+    assert_eq!(status(&maker, settlement, 2).await, SettlementStatus::MakerEscrowed);
+    deployment.validator.clock().add(TimeDelta::from_secs(61));
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ClaimRefund { settlement_id: 2, on_behalf_of: None });
+    }).await;
+    assert_eq!(status(&maker, settlement, 2).await, SettlementStatus::Refunded);
+    assert_eq!(balance(&maker, settlement, maker_account).await, Amount::from_tokens(75));
+}
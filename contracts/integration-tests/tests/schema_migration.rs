@@ -0,0 +1,50 @@
+//! Schema migration: `Migrate` brings the state to `SCHEMA_VERSION` one version per call and indexes orders by client request id.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{Operation, OrderSide, Query, QueryResponse, SCHEMA_VERSION};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_reaches_current_version_once() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::from(OrderPlacement {
+                client_request_id: Some(7),
+                ..place_order(OrderSide::Sell, 50_000 * 100_000_000, 10_000_000)
+            }))
+            .with_operation(orderbook, Operation::Migrate)
+            .with_operation(orderbook, Operation::Migrate)
+            .with_operation(orderbook, Operation::Migrate)
+            .with_operation(orderbook, Operation::Migrate);
+    }).await;
+
+    match user.query(orderbook, Query::GetSchemaVersion).await {
+        QueryResponse::SchemaVersion { version, migration_cursor } => {
+            assert_eq!(version, SCHEMA_VERSION);
+            assert!(migration_cursor.is_none());
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetOrderReceipt { order_id: 1 }).await {
+        QueryResponse::Receipt(Some(receipt)) => assert_eq!(receipt.client_request_id, 7),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetReceipt { account, client_request_id: 7 }).await {
+        QueryResponse::Receipt(Some(receipt)) => assert_eq!(receipt.id, 1),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Nothing is left to migrate
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::Migrate);
+    }).await;
+    assert!(result.is_err());
+}
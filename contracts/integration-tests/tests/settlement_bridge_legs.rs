@@ -0,0 +1,93 @@
+//! Settlement bridge legs: an escrow proven with a bridge deposit links the deposit and the leg both ways.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, TEST_ASSET};
+use axelarx_settlement::{BridgeConfig, BridgeTransferStatus, Operation, Query, QueryResponse, SettlementRef};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64) -> Operation {
+    initiate_settlement(maker, taker, trade_id).into()
+}
+
+fn confirm(settlement_id: u64, bridge_transfer_id: u64) -> Operation {
+    Operation::ConfirmEscrow {
+        settlement_id,
+        on_behalf_of: None,
+        substitute_asset: None,
+        bridge_transfer_id: Some(bridge_transfer_id),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn escrow_proof_links_deposit_and_leg() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::ConfigureBridge {
+                chain_id: "ethereum".to_string(),
+                config: BridgeConfig {
+                    chain_id: "ethereum".to_string(),
+                    chain_name: "Ethereum".to_string(),
+                    bridge_address: "0x52908400098527886E0F7030069857D2E4169EE7".to_string(),
+                    confirmation_blocks: 12,
+                    min_amount: Amount::from_tokens(1),
+                    max_amount: Amount::from_tokens(1_000_000),
+                    fee_rate_bps: 0,
+                    is_active: true,
+                    supported_assets: vec![TEST_ASSET.to_string()],
+                    corridor: None,
+                },
+            })
+            .with_operation(settlement, Operation::ProcessBridgeDeposit {
+                chain_id: "ethereum".to_string(),
+                tx_hash: "0xfunding".to_string(),
+                user: maker_account,
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                confirmations: 12,
+            })
+            .with_operation(settlement, initiate(&maker, &taker, 1))
+            .with_operation(settlement, confirm(1, 1));
+    }).await;
+
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert_eq!(record.maker_bridge_transfer_id, Some(1));
+            assert_eq!(record.maker_bridge_status, Some(BridgeTransferStatus::Completed));
+            assert_eq!(record.taker_bridge_transfer_id, None);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetBridgeTransfer { transfer_id: 1 }).await {
+        QueryResponse::BridgeTransfer(Some(transfer)) => {
+            assert_eq!(transfer.settlement_ref, Some(SettlementRef { settlement_id: 1, payer: maker_account }));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The deposit already carries a leg, so it cannot prove a second escrow
+    let result = maker.try_add_block(|block| {
+        block
+            .with_operation(settlement, initiate(&maker, &taker, 2))
+            .with_operation(settlement, confirm(2, 1));
+    }).await;
+    assert!(result.is_err());
+
+    // Proceeds are only withdrawn once the settlement completed
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::WithdrawSettlementProceeds {
+            settlement_id: 1,
+            chain_id: "ethereum".to_string(),
+            destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+            client_request_id: None,
+        });
+    }).await;
+    assert!(result.is_err());
+}
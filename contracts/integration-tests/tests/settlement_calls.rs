@@ -0,0 +1,164 @@
+//! Settlement calls: a market on the settlement application's chain gets the settlement id back in the same block, and
+//! one on another chain has its request relayed as a message that the settlement chain checks against its registration.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{
+    Operation as OrderBookOperation, OrderSide, Query as OrderBookQuery, QueryResponse as OrderBookResponse,
+    TradeSettlement,
+};
+use axelarx_settlement::{
+    MarketRegistration, Operation as SettlementOperation, Query as SettlementQuery,
+    QueryResponse as SettlementResponse, SettlementOriginKind,
+};
+use linera_base::data_types::Amount;
+
+fn place(side: OrderSide) -> OrderBookOperation {
+    place_order(side, 50_000 * 100_000_000, 100_000_000).into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn market_receives_settlement_id_from_call() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let chain_id = user.id();
+    let (orderbook, settlement) = (deployment.orderbook, deployment.settlement);
+
+    // Both applications on the user's chain, each registered with the other
+    user.add_block(|block| {
+        block
+            .with_operation(settlement, SettlementOperation::SetMarket {
+                application_id: orderbook.forget_abi(),
+                market: Some(MarketRegistration {
+                    chain_id,
+                    base_asset: "BTC".to_string(),
+                    quote_asset: "USDT".to_string(),
+                }),
+            })
+            .with_operation(orderbook, OrderBookOperation::SetSettlementApplication {
+                application_id: settlement.forget_abi(),
+                chain_id,
+            })
+            .with_operation(orderbook, OrderBookOperation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, OrderBookOperation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell))
+            .with_operation(orderbook, place(OrderSide::Buy))
+            .with_operation(orderbook, OrderBookOperation::RequestTradeSettlement { trade_id: 0, timeout_seconds: 3_600 });
+    }).await;
+
+    let settlement_id = match user.query(orderbook, OrderBookQuery::GetTradeSettlementId { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlementId(Some(settlement_id)) => settlement_id,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    match user.query(orderbook, OrderBookQuery::GetTradeSettlement { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlement(status) => assert_eq!(status, Some(TradeSettlement::Requested)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(settlement, SettlementQuery::GetSettlement { settlement_id }).await {
+        SettlementResponse::Settlement(Some(record)) => {
+            assert_eq!(record.trade_id, 0);
+            assert_eq!((record.maker, record.taker), (account, account));
+            assert_eq!(record.maker_asset, "BTC");
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // A trade goes to settlement once
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::RequestTradeSettlement { trade_id: 0, timeout_seconds: 3_600 });
+    }).await;
+    assert!(result.is_err());
+
+    // Signing the call directly is no substitute for the registered market application
+    let result = user.try_add_block(|block| {
+        block.with_operation(settlement, SettlementOperation::RequestSettlement {
+            trade_id: 7,
+            maker: account,
+            taker: account,
+            maker_asset: "BTC".to_string(),
+            taker_asset: "USDT".to_string(),
+            maker_amount: Amount::from_tokens(1),
+            taker_amount: Amount::from_tokens(50_000),
+            timeout_seconds: 3_600,
+            fees: None,
+            maker_chain: None,
+            taker_chain: None,
+            settlement_chain: None,
+        });
+    }).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn market_on_another_chain_requests_settlement_by_message() {
+    let deployment = Deployment::new().await;
+    let mut hub = deployment.admin.clone();
+    let mut market = deployment.new_user().await;
+    let account = owner_account(&market);
+    let (orderbook, settlement) = (deployment.orderbook, deployment.settlement);
+
+    // The settlement chain registers the market for its chain, whose owner runs the order book
+    hub.add_block(|block| {
+        block
+            .with_operation(settlement, SettlementOperation::SetMarket {
+                application_id: orderbook.forget_abi(),
+                market: Some(MarketRegistration {
+                    chain_id: market.id(),
+                    base_asset: "BTC".to_string(),
+                    quote_asset: "USDT".to_string(),
+                }),
+            })
+            .with_operation(orderbook, OrderBookOperation::AppointChainAdmin { chain_id: market.id(), admin: account });
+    }).await;
+    market.handle_received_messages().await;
+
+    market.add_block(|block| {
+        block
+            .with_operation(orderbook, OrderBookOperation::SetSettlementApplication {
+                application_id: settlement.forget_abi(),
+                chain_id: hub.id(),
+            })
+            .with_operation(orderbook, OrderBookOperation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, OrderBookOperation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell))
+            .with_operation(orderbook, place(OrderSide::Buy))
+            .with_operation(orderbook, OrderBookOperation::RequestTradeSettlement { trade_id: 0, timeout_seconds: 3_600 });
+    }).await;
+
+    // The id is assigned on the settlement chain
+    match market.query(orderbook, OrderBookQuery::GetTradeSettlementId { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlementId(settlement_id) => assert_eq!(settlement_id, None),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match market.query(orderbook, OrderBookQuery::GetTradeSettlement { trade_id: 0 }).await {
+        OrderBookResponse::TradeSettlement(status) => assert_eq!(status, Some(TradeSettlement::Requested)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    hub.handle_received_messages().await;
+    let settlement_id = match hub.query(settlement, SettlementQuery::GetUserSettlements { account }).await {
+        SettlementResponse::UserSettlements(settlement_ids) => {
+            assert_eq!(settlement_ids.len(), 1);
+            settlement_ids[0]
+        }
+        other => panic!("unexpected response: {other:?}"),
+    };
+    match hub.query(settlement, SettlementQuery::GetSettlement { settlement_id }).await {
+        SettlementResponse::Settlement(Some(record)) => {
+            assert_eq!(record.trade_id, 0);
+            let provenance = record.provenance.expect("requested settlements keep their provenance");
+            assert_eq!(provenance.kind, SettlementOriginKind::Message);
+            assert_eq!(provenance.origin.chain_id, market.id());
+            assert_eq!(provenance.origin.application_id, Some(orderbook.forget_abi()));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
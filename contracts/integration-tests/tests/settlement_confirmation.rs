@@ -0,0 +1,104 @@
+//! Settlement confirmation: without auto-execution a fully escrowed settlement waits for the parties' `ExecuteSettlement`, and expires with refunds when its window lapses.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{
+    ConfirmationRule, ConfirmationTerms, Operation, Query, QueryResponse, Settlement, SettlementAbi, SettlementEvent,
+    SettlementStatus,
+};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+/// Same-asset settlement the taker owes nothing on, so the maker's escrow makes it fully escrowed
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64, rule: ConfirmationRule, windowed: bool) -> Operation {
+    SettlementTerms {
+        taker_asset: TEST_ASSET.to_string(),
+        taker_amount: Amount::from_tokens(15),
+        windowed,
+        confirmation: Some(ConfirmationTerms { window_seconds: 600, rule }),
+        ..initiate_settlement(maker, taker, trade_id)
+    }
+    .into()
+}
+
+fn confirm(settlement_id: u64) -> Operation {
+    Operation::ConfirmEscrow { settlement_id, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None }
+}
+
+async fn record(chain: &ActiveChain, settlement: ApplicationId<SettlementAbi>, settlement_id: u64) -> Settlement {
+    match chain.query(settlement, Query::GetSettlement { settlement_id }).await {
+        QueryResponse::Settlement(Some(record)) => record,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn confirmation_window_expires_with_both_sides_escrowed() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    // Windowed settlements already wait for their boundary
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 1, ConfirmationRule::EitherParty, true));
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(100) })
+            .with_operation(settlement, initiate(&maker, &taker, 1, ConfirmationRule::BothParties, false))
+            .with_operation(settlement, confirm(1));
+    }).await;
+    let escrowed = record(&maker, settlement, 1).await;
+    assert_eq!(escrowed.status, SettlementStatus::FullyEscrowed);
+    assert!(escrowed.taker_escrow.is_escrowed && escrowed.maker_escrow.is_escrowed);
+    match maker.query(settlement, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [SettlementEvent::AwaitingConfirmation { settlement_id: 1, confirm_by, .. }] if *confirm_by == escrowed.expires_at
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // One confirmation is not enough under `BothParties`, and it counts once
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ExecuteSettlement { settlement_id: 1 });
+    }).await;
+    let confirmed = record(&maker, settlement, 1).await;
+    assert_eq!((confirmed.status, confirmed.confirmed_by), (SettlementStatus::FullyEscrowed, vec![maker_account]));
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::ExecuteSettlement { settlement_id: 1 });
+    }).await;
+    assert!(result.is_err());
+
+    // Past the window nothing executes, and expiry refunds the maker's escrow
+    deployment.validator.clock().add(TimeDelta::from_secs(601));
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::ExecuteSettlement { settlement_id: 1 });
+    }).await;
+    assert!(result.is_err());
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::ProcessExpiredSettlements { limit: None });
+    }).await;
+    assert_eq!(record(&maker, settlement, 1).await.status, SettlementStatus::Expired);
+    match maker.query(settlement, Query::GetBalance { account: maker_account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(100)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Under `EitherParty` the first confirmation executes
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, initiate(&maker, &taker, 2, ConfirmationRule::EitherParty, false))
+            .with_operation(settlement, confirm(2))
+            .with_operation(settlement, Operation::ExecuteSettlement { settlement_id: 2 });
+    }).await;
+    assert_eq!(record(&maker, settlement, 2).await.status, SettlementStatus::Completed);
+}
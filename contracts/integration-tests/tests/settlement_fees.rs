@@ -0,0 +1,55 @@
+//! Settlement fees: validated against their legs at initiation, never taken from refunds.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse, SettlementFees};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, fees: SettlementFees) -> Operation {
+    SettlementTerms { fees: Some(fees), ..initiate_settlement(maker, taker, 1) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fees_are_recorded_and_refunds_return_full_escrow() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+    let fees = SettlementFees {
+        maker_fee: Amount::from_millis(1),
+        taker_fee: Amount::from_millis(40),
+        recipient: owner_account(&deployment.admin),
+    };
+
+    // The maker fee is paid in the taker asset, so it cannot exceed the 1 BTC leg
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, SettlementFees {
+            maker_fee: Amount::from_tokens(2),
+            ..fees.clone()
+        }));
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit {
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+            })
+            .with_operation(settlement, initiate(&maker, &taker, fees.clone()))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None })
+            .with_operation(settlement, Operation::CancelSettlement { settlement_id: 1, reason: "test".to_string() });
+    }).await;
+
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.fees, Some(fees)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetBalance { account: maker_account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(100)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,51 @@
+//! Settlement maximums: legs above an asset's maximum are rejected unless the counterparty pair is exempt.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, maker_amount: Amount) -> Operation {
+    SettlementTerms { maker_amount, ..initiate_settlement(maker, taker, 1) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn legs_above_maximum_need_an_exempt_pair() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.admin.clone();
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+    let cap = Amount::from_tokens(1_000_000);
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetMaxSettlementAmount { asset: TEST_ASSET.to_string(), maximum: cap })
+            .with_operation(settlement, initiate(&maker, &taker, cap));
+    }).await;
+
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, cap + Amount::from_attos(1)));
+    }).await;
+    assert!(result.is_err());
+
+    // The exemption applies whichever side each account takes
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetUncappedPair {
+                first: owner_account(&taker),
+                second: owner_account(&maker),
+                uncapped: true,
+            })
+            .with_operation(settlement, initiate(&maker, &taker, cap + Amount::from_attos(1)));
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 2 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.maker_amount, cap + Amount::from_attos(1)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetMaxSettlementAmount { asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::MaxSettlementAmount(maximum) => assert_eq!(maximum, Some(cap)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,50 @@
+//! Settlement minimums: each leg has to reach its asset's minimum, the minimum itself included.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, maker_amount: Amount) -> Operation {
+    SettlementTerms { maker_amount, ..initiate_settlement(maker, taker, 1) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn legs_below_minimum_are_rejected() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.admin.clone();
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetMinSettlementAmount {
+                asset: TEST_ASSET.to_string(),
+                minimum: Amount::from_tokens(40),
+            })
+            .with_operation(settlement, Operation::SetMinSettlementAmount {
+                asset: "BTC".to_string(),
+                minimum: Amount::from_tokens(1),
+            });
+    }).await;
+
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, Amount::from_tokens(40) - Amount::from_attos(1)));
+    }).await;
+    assert!(result.is_err());
+
+    // Exactly the minimum on both legs is accepted
+    maker.add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, Amount::from_tokens(40)));
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.maker_amount, Amount::from_tokens(40)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetMinSettlementAmount { asset: "BTC".to_string() }).await {
+        QueryResponse::MinSettlementAmount(minimum) => assert_eq!(minimum, Amount::from_tokens(1)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
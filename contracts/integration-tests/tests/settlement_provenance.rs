@@ -0,0 +1,45 @@
+//! Settlement provenance: each settlement records how and from where it was requested, and is indexed by that origin.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms};
+use axelarx_settlement::{Operation, Query, QueryResponse, SettlementOrigin, SettlementOriginKind};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn operations_record_their_origin() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block.with_operation(settlement, Operation::from(SettlementTerms {
+            client_request_id: Some(9),
+            ..initiate_settlement(&maker, &taker, 1)
+        }));
+    }).await;
+
+    let origin = SettlementOrigin { chain_id: maker.id(), application_id: None };
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            let provenance = record.provenance.expect("new settlements record their provenance");
+            assert_eq!(provenance.kind, SettlementOriginKind::Operation);
+            assert_eq!(provenance.origin, origin);
+            assert_eq!(provenance.signer, Some(maker_account));
+            assert_eq!((provenance.message_id, provenance.client_request_id), (None, Some(9)));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetSettlementsByOrigin { origin }).await {
+        QueryResponse::SettlementsByOrigin(ids) => assert_eq!(ids, vec![1]),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Nothing was requested through a market application
+    let origin = SettlementOrigin { chain_id: maker.id(), application_id: Some(deployment.orderbook.forget_abi()) };
+    match maker.query(settlement, Query::GetSettlementsByOrigin { origin }).await {
+        QueryResponse::SettlementsByOrigin(ids) => assert!(ids.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
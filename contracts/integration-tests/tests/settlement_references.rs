@@ -0,0 +1,62 @@
+//! Settlement references: memos and deal ids are kept on the settlement, deal ids are unique per creator and can be looked up.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, owner_account, Deployment, SettlementTerms};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, trade_id: u64, memo: &str, external_ref: &str) -> Operation {
+    SettlementTerms {
+        memo: Some(memo.to_string()),
+        external_ref: Some(external_ref.to_string()),
+        ..initiate_settlement(maker, taker, trade_id)
+    }
+    .into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settlements_are_found_by_deal_id() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let taker = deployment.new_user().await;
+    let creator = owner_account(&maker);
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 1, "Block trade for fund A", "DEAL-1182"));
+    }).await;
+
+    let query = Query::GetSettlementByExternalRef { creator, external_ref: "DEAL-1182".to_string() };
+    match maker.query(settlement, query).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert_eq!(record.id, 1);
+            assert_eq!(record.memo.as_deref(), Some("Block trade for fund A"));
+            assert_eq!(record.external_ref.as_deref(), Some("DEAL-1182"));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    let query = Query::GetSettlementByExternalRef { creator, external_ref: "DEAL-1183".to_string() };
+    match maker.query(settlement, query).await {
+        QueryResponse::Settlement(None) => {}
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The creator cannot reuse a deal id, nor send control characters
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 2, "Second leg", "DEAL-1182"));
+    }).await;
+    assert!(result.is_err());
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 2, "Second\u{7}leg", "DEAL-1183"));
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, 2, "Second leg", "DEAL-1183"));
+    }).await;
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 2 }).await {
+        QueryResponse::Settlement(Some(record)) => assert_eq!(record.external_ref.as_deref(), Some("DEAL-1183")),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
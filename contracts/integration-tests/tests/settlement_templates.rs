@@ -0,0 +1,101 @@
+//! Settlement templates: only the creator updates or revokes a template, only the counterparty accepts it, and nothing is initiated from it before that.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, Deployment, TEST_ASSET};
+use axelarx_settlement::{
+    Operation, Query, QueryResponse, SettlementAbi, SettlementTemplate, TemplateOptions, TemplateStatus,
+};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn create(counterparty: Account, timeout_seconds: u64) -> Operation {
+    Operation::CreateSettlementTemplate {
+        counterparty,
+        maker_asset: TEST_ASSET.to_string(),
+        taker_asset: "BTC".to_string(),
+        timeout_seconds,
+        options: TemplateOptions { memo: Some("Weekly rebalance".to_string()), ..TemplateOptions::default() },
+    }
+}
+
+fn initiate(template_id: u64) -> Operation {
+    Operation::InitiateFromTemplate {
+        template_id,
+        maker_amount: Amount::from_tokens(100),
+        taker_amount: Amount::from_tokens(1),
+        trade_id: 7,
+    }
+}
+
+async fn templates(chain: &ActiveChain, settlement: ApplicationId<SettlementAbi>, account: Account) -> Vec<SettlementTemplate> {
+    match chain.query(settlement, Query::GetAccountTemplates { account }).await {
+        QueryResponse::AccountTemplates(templates) => templates,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn templates_need_acceptance_and_stay_with_their_creator() {
+    let deployment = Deployment::new().await;
+    let mut creator = deployment.new_user().await;
+    let mut counterparty = deployment.new_user().await;
+    let (creator_account, counterparty_account) = (owner_account(&creator), owner_account(&counterparty));
+    let settlement = deployment.settlement;
+
+    // Static terms are checked when the template is created
+    let result = creator.try_add_block(|block| {
+        block.with_operation(settlement, create(counterparty_account, 0));
+    }).await;
+    assert!(result.is_err());
+
+    creator.add_block(|block| {
+        block.with_operation(settlement, create(counterparty_account, 3_600));
+    }).await;
+    let [template] = templates(&creator, settlement, counterparty_account).await.try_into().unwrap();
+    assert_eq!((template.creator, template.status), (creator_account, TemplateStatus::Proposed));
+    assert_eq!(templates(&creator, settlement, creator_account).await, vec![template.clone()]);
+
+    // Not usable before the counterparty accepts, and the creator cannot accept for it
+    for operation in [initiate(0), Operation::AcceptSettlementTemplate { template_id: 0 }] {
+        let result = creator.try_add_block(|block| {
+            block.with_operation(settlement, operation.clone());
+        }).await;
+        assert!(result.is_err());
+    }
+    let result = counterparty.try_add_block(|block| {
+        block.with_operation(settlement, Operation::RevokeSettlementTemplate { template_id: 0 });
+    }).await;
+    assert!(result.is_err());
+
+    // An update keeps the template proposed; a revoked template cannot be updated or used
+    creator.add_block(|block| {
+        block.with_operation(settlement, Operation::UpdateSettlementTemplate {
+            template_id: 0,
+            maker_asset: TEST_ASSET.to_string(),
+            taker_asset: "ETH".to_string(),
+            timeout_seconds: 7_200,
+            options: TemplateOptions::default(),
+        });
+    }).await;
+    let [updated] = templates(&creator, settlement, creator_account).await.try_into().unwrap();
+    assert_eq!((updated.taker_asset.as_str(), updated.memo, updated.status), ("ETH", None, TemplateStatus::Proposed));
+    creator.add_block(|block| {
+        block.with_operation(settlement, Operation::RevokeSettlementTemplate { template_id: 0 });
+    }).await;
+    let result = creator.try_add_block(|block| {
+        block.with_operation(settlement, Operation::RevokeSettlementTemplate { template_id: 0 });
+    }).await;
+    assert!(result.is_err());
+    match creator.query(settlement, Query::GetTemplateSettlements { template_id: 0 }).await {
+        QueryResponse::TemplateSettlements(settlement_ids) => assert!(settlement_ids.is_empty()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match creator.query(settlement, Query::GetTemplateSettlements { template_id: 1 }).await {
+        QueryResponse::Error(message) => assert!(message.contains("not found")),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
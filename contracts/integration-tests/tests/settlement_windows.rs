@@ -0,0 +1,56 @@
+//! Settlement windows: windowed settlements need a configured window and queue for its boundaries.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, Deployment, SettlementTerms, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+use linera_sdk::test::ActiveChain;
+
+fn initiate(maker: &ActiveChain, taker: &ActiveChain, windowed: bool) -> Operation {
+    SettlementTerms {
+        maker_amount: Amount::from_tokens(100),
+        timeout_seconds: 3 * 3_600,
+        windowed,
+        ..initiate_settlement(maker, taker, 1)
+    }
+    .into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn windowed_settlements_require_a_window() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.admin.clone();
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, initiate(&maker, &taker, true));
+    }).await;
+    assert!(result.is_err());
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::SetSettlementWindow { window_seconds: 3_600 })
+            .with_operation(settlement, Operation::Deposit { asset: TEST_ASSET.to_string(), amount: Amount::from_tokens(100) })
+            .with_operation(settlement, initiate(&maker, &taker, true))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None })
+            .with_operation(settlement, Operation::ExecuteWindow);
+    }).await;
+
+    // Half escrowed: not queued for any window yet
+    match maker.query(settlement, Query::GetSettlement { settlement_id: 1 }).await {
+        QueryResponse::Settlement(Some(record)) => {
+            assert!(record.windowed);
+            assert!(record.execute_at.is_none());
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match maker.query(settlement, Query::GetSettlementWindow).await {
+        QueryResponse::SettlementWindow { window_seconds, queued } => {
+            assert_eq!(window_seconds, 3_600);
+            assert!(queued.is_empty());
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,103 @@
+//! Stale-order sweeps: with a policy set and a deep enough book, distant orders are cancelled and refunded unless placed sweep-exempt.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    Operation, OrderBookAbi, OrderBookEvent, OrderSide, OrderStatus, Query, QueryResponse, StaleOrderPolicy,
+};
+use linera_base::{data_types::Amount, identifiers::ApplicationId};
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, price: u64, sweep_exempt: bool) -> Operation {
+    OrderPlacement { sweep_exempt, ..place_order(side, price, ONE_BTC) }.into()
+}
+
+fn policy(min_resting_orders: u64) -> Operation {
+    Operation::SetStaleOrderPolicy {
+        policy: Some(StaleOrderPolicy { min_age_seconds: 0, min_distance_bps: 2_000, min_resting_orders }),
+    }
+}
+
+async fn status(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>, order_id: u64) -> OrderStatus {
+    match chain.query(orderbook, Query::GetOrder { order_id }).await {
+        QueryResponse::Order(Some(order)) => order.status,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn locked_usdt(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>) -> Amount {
+    let query = Query::GetAccountBalance { account: owner_account(chain), asset: "USDT".to_string() };
+    match chain.query(orderbook, query).await {
+        QueryResponse::AccountBalance { locked, .. } => locked,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+async fn sweep(chain: &mut ActiveChain, orderbook: ApplicationId<OrderBookAbi>) {
+    chain.add_block(|block| {
+        block.with_operation(orderbook, Operation::SweepStaleOrders);
+    }).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sweeps_cancel_distant_orders() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let orderbook = deployment.orderbook;
+
+    // A trade sets the reference price; orders 2 and 3 rest 50% below it, order 4 10% above
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, PRICE, false))
+            .with_operation(orderbook, place(OrderSide::Buy, PRICE, false))
+            .with_operation(orderbook, place(OrderSide::Buy, PRICE / 2, false))
+            .with_operation(orderbook, place(OrderSide::Buy, PRICE / 2, true))
+            .with_operation(orderbook, place(OrderSide::Sell, PRICE / 10 * 11, false));
+    }).await;
+    let locked = locked_usdt(&user, orderbook).await;
+
+    // Disabled by default, and held off while the book is shallower than the threshold
+    assert!(matches!(user.query(orderbook, Query::GetStaleOrderPolicy).await, QueryResponse::StaleOrderPolicy(None)));
+    sweep(&mut user, orderbook).await;
+    user.add_block(|block| {
+        block.with_operation(orderbook, policy(3));
+    }).await;
+    sweep(&mut user, orderbook).await;
+    assert_eq!(status(&user, orderbook, 2).await, OrderStatus::Open);
+
+    // Over the threshold the distant order is swept and refunded, the exempt and near ones stay
+    user.add_block(|block| {
+        block.with_operation(orderbook, policy(2));
+    }).await;
+    sweep(&mut user, orderbook).await;
+    assert_eq!(status(&user, orderbook, 2).await, OrderStatus::Cancelled);
+    assert_eq!(status(&user, orderbook, 3).await, OrderStatus::Open);
+    assert_eq!(status(&user, orderbook, 4).await, OrderStatus::Open);
+    let remaining = locked_usdt(&user, orderbook).await;
+    assert_eq!(remaining.saturating_add(remaining), locked);
+    assert!(matches!(user.query(orderbook, Query::IsSweepExempt { order_id: 3 }).await, QueryResponse::SweepExempt(true)));
+    match user.query(orderbook, Query::GetEvents { count: 1 }).await {
+        QueryResponse::Events(events) => assert!(matches!(
+            events.as_slice(),
+            [OrderBookEvent::StaleOrderSwept { order_id: 2, price, reference_price: PRICE, .. }] if *price == PRICE / 2
+        )),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // A policy must keep a distance from the reference price
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::SetStaleOrderPolicy {
+            policy: Some(StaleOrderPolicy { min_distance_bps: 0, ..StaleOrderPolicy::default() }),
+        });
+    }).await;
+    assert!(result.is_err());
+}
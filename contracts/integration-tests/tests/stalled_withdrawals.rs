@@ -0,0 +1,106 @@
+//! Stalled withdrawals: a claim left unreported past its deadline is re-queued; stale failure reports are ignored.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeTransfer, ExternalChain, Operation, Query, QueryResponse, TransferId, TransferStatus,
+    EXECUTION_TIMEOUT_SECONDS,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+async fn transfer(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: TransferId) -> BridgeTransfer {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stalled_withdrawal_is_requeued_for_another_relayer() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    // Transfer 1 is the deposit, 2 the withdrawal
+    let transfer_id = 2;
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            })
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id, signature: vec![] })
+            .with_operation(bridge, Operation::ExecuteTransfer { transfer_id });
+    }).await;
+
+    // Nothing is stalled before the deadline
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::RequeueStalledWithdrawals);
+    }).await;
+    assert_eq!(transfer(&user, bridge, transfer_id).await.status, TransferStatus::Executing);
+
+    deployment.validator.clock().add(TimeDelta::from_secs(EXECUTION_TIMEOUT_SECONDS + 1));
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::RequeueStalledWithdrawals);
+    }).await;
+    let requeued = transfer(&user, bridge, transfer_id).await;
+    assert_eq!(requeued.status, TransferStatus::Approved);
+    assert_eq!(requeued.retry_count, 1);
+    assert!(requeued.executing_relayer.is_none());
+
+    // A late failure report from the original claim does not refund a re-queued withdrawal
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::CompleteWithdrawal {
+            transfer_id,
+            tx_hash: "0xreverted".to_string(),
+            success: false,
+        });
+    }).await;
+    assert_eq!(transfer(&user, bridge, transfer_id).await.status, TransferStatus::Approved);
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ExecuteTransfer { transfer_id })
+            .with_operation(bridge, Operation::CompleteWithdrawal {
+                transfer_id,
+                tx_hash: "0xsent".to_string(),
+                success: true,
+            });
+    }).await;
+    let completed = transfer(&user, bridge, transfer_id).await;
+    assert_eq!(completed.status, TransferStatus::Completed);
+    assert_eq!(completed.retry_count, 1);
+
+    // Repeating the recorded outcome is a no-op
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::CompleteWithdrawal {
+            transfer_id,
+            tx_hash: "0xsent".to_string(),
+            success: true,
+        });
+    }).await;
+}
@@ -0,0 +1,44 @@
+//! Stuck executions: only settlements left `Executing` can be resolved, and audits report them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{initiate_settlement, Deployment, TEST_ASSET};
+use axelarx_settlement::{Operation, Query, QueryResponse};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_executing_settlements_are_resolved() {
+    let deployment = Deployment::new().await;
+    let mut maker = deployment.admin.clone();
+    let taker = deployment.new_user().await;
+    let settlement = deployment.settlement;
+
+    maker.add_block(|block| {
+        block
+            .with_operation(settlement, Operation::Deposit {
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+            })
+            .with_operation(settlement, Operation::from(initiate_settlement(&maker, &taker, 1)))
+            .with_operation(settlement, Operation::ConfirmEscrow { settlement_id: 1, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None })
+            .with_operation(settlement, Operation::AuditEscrow);
+    }).await;
+
+    match maker.query(settlement, Query::GetHealth).await {
+        QueryResponse::Health { healthy, last_escrow_audit, .. } => {
+            assert!(healthy);
+            assert_eq!(last_escrow_audit.map(|audit| audit.stuck_executions), Some(vec![]));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Half escrowed is not stuck: the escrow is reclaimed the ordinary way
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::ResolveStuckExecution { settlement_id: 1 });
+    }).await;
+    assert!(result.is_err());
+    let result = maker.try_add_block(|block| {
+        block.with_operation(settlement, Operation::ResolveStuckExecution { settlement_id: 2 });
+    }).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,55 @@
+//! Trade export: trades page by id and by time range with stable cursors.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderSide, Query, QueryResponse, TradePage};
+use linera_base::data_types::{Amount, Timestamp};
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide) -> Operation {
+    place_order(side, PRICE, ONE_BTC / 10).into()
+}
+
+fn page(response: QueryResponse) -> (Vec<u64>, Option<u64>) {
+    match response {
+        QueryResponse::Trades(TradePage::Trades { trades, next_id }) => {
+            (trades.into_iter().map(|trade| trade.id).collect(), next_id)
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn trades_page_by_id_and_time() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let orderbook = deployment.orderbook;
+
+    // Three crossing pairs make trades 0, 1 and 2
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            });
+        for _ in 0..3 {
+            block
+                .with_operation(orderbook, place(OrderSide::Sell))
+                .with_operation(orderbook, place(OrderSide::Buy));
+        }
+    }).await;
+
+    let first = page(user.query(orderbook, Query::GetTrades { from_id: 0, limit: 2 }).await);
+    assert_eq!(first, (vec![0, 1], Some(2)));
+    let rest = page(user.query(orderbook, Query::GetTrades { from_id: 2, limit: 2 }).await);
+    assert_eq!(rest, (vec![2], None));
+
+    let range = Query::GetTradesByTimeRange { start: Timestamp::from(0), end: Timestamp::from(u64::MAX), limit: 2 };
+    assert_eq!(page(user.query(orderbook, range).await), (vec![0, 1], Some(2)));
+    let empty = Query::GetTradesByTimeRange { start: Timestamp::from(0), end: Timestamp::from(0), limit: 10 };
+    assert_eq!(page(user.query(orderbook, empty).await), (vec![], None));
+}
@@ -0,0 +1,148 @@
+//! Full trade lifecycle: orderbook match -> settlement escrow and swap -> bridge withdrawal.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    ExternalChain, Operation as BridgeOperation, Query as BridgeQuery,
+    QueryResponse as BridgeResponse, TransferStatus,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, place_order, sole_validator, Deployment, TEST_ASSET};
+use axelarx_orderbook::{Operation as OrderBookOperation, OrderSide};
+use axelarx_settlement::{
+    MarketRegistration, Operation as SettlementOperation, Query as SettlementQuery,
+    QueryResponse as SettlementResponse, SettlementStatus,
+};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs order routing to the market chain and the orderbook SettlementRequest"]
+async fn crossing_orders_settle_and_proceeds_bridge_out() {
+    let mut deployment = Deployment::new().await;
+    let mut maker = deployment.new_user().await;
+    let mut taker = deployment.new_user().await;
+    let maker_account = owner_account(&maker);
+    let taker_account = owner_account(&taker);
+
+    // Fund both sides on the orderbook
+    let orderbook = deployment.orderbook;
+    maker.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::Deposit {
+            asset: "BTC".to_string(),
+            amount: Amount::from_tokens(1),
+        });
+    }).await;
+    taker.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::Deposit {
+            asset: TEST_ASSET.to_string(),
+            amount: Amount::from_tokens(50_000),
+        });
+    }).await;
+
+    // Only a registered market may request settlements
+    let settlement = deployment.settlement;
+    let market_chain = deployment.admin.id();
+    deployment.admin.add_block(|block| {
+        block.with_operation(settlement, SettlementOperation::SetMarket {
+            application_id: orderbook.forget_abi(),
+            market: Some(MarketRegistration {
+                chain_id: market_chain,
+                base_asset: "BTC".to_string(),
+                quote_asset: TEST_ASSET.to_string(),
+            }),
+        });
+    }).await;
+
+    // Crossing limit orders: 1 BTC at 50,000
+    let price = 50_000 * 100_000_000;
+    let quantity = 100_000_000;
+    maker.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::from(place_order(OrderSide::Sell, price, quantity)));
+    }).await;
+    taker.add_block(|block| {
+        block.with_operation(orderbook, OrderBookOperation::from(place_order(OrderSide::Buy, price, quantity)));
+    }).await;
+
+    // The match reaches the settlement application as a SettlementRequest
+    deployment.admin.handle_received_messages().await;
+    let settlement_ids = match deployment.admin
+        .query(settlement, SettlementQuery::GetUserSettlements { account: maker_account })
+        .await
+    {
+        SettlementResponse::UserSettlements(ids) => ids,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!(settlement_ids.len(), 1);
+    let settlement_id = settlement_ids[0];
+
+    // Both parties escrow; the second escrow executes the swap
+    for party in [&mut maker, &mut taker] {
+        party.add_block(|block| {
+            block.with_operation(settlement, SettlementOperation::ConfirmEscrow { settlement_id, on_behalf_of: None, substitute_asset: None, bridge_transfer_id: None });
+        }).await;
+    }
+    deployment.admin.handle_received_messages().await;
+
+    match deployment.admin.query(settlement, SettlementQuery::GetSettlement { settlement_id }).await {
+        SettlementResponse::Settlement(Some(settlement)) => {
+            assert_eq!(settlement.status, SettlementStatus::Completed);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match deployment.admin
+        .query(settlement, SettlementQuery::GetBalance { account: taker_account, asset: "BTC".to_string() })
+        .await
+    {
+        SettlementResponse::Balance(balance) => assert_eq!(balance, Amount::from_tokens(1)),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // The maker bridges the USDC proceeds out, with a relayer-simulated approval and completion
+    let bridge = deployment.bridge;
+    let validator = sole_validator(&maker);
+    maker.add_block(|block| {
+        block
+            .with_operation(bridge, BridgeOperation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, BridgeOperation::AddValidator { config: validator })
+            .with_operation(bridge, BridgeOperation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xproceeds".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(maker_account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(50_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, BridgeOperation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(40_000),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            });
+    }).await;
+
+    // Transfer 1 is the deposit, 2 the withdrawal
+    let transfer_id = 2;
+    maker.add_block(|block| {
+        block
+            .with_operation(bridge, BridgeOperation::ApproveTransfer { transfer_id, signature: vec![] })
+            .with_operation(bridge, BridgeOperation::ExecuteTransfer { transfer_id })
+            .with_operation(bridge, BridgeOperation::CompleteWithdrawal {
+                transfer_id,
+                tx_hash: "0xwithdrawal".to_string(),
+                success: true,
+            });
+    }).await;
+
+    match maker.query(bridge, BridgeQuery::GetTransfer { transfer_id }).await {
+        BridgeResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.status, TransferStatus::Completed);
+            assert_eq!(transfer.destination_tx_hash.as_deref(), Some("0xwithdrawal"));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
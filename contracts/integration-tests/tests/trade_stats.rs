@@ -0,0 +1,85 @@
+//! Trade stats: the 24h market stats come from hourly buckets kept as trades are recorded, and the retained history is configurable within bounds.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{
+    Operation, OrderSide, Query, QueryResponse, TimeInForce, TradeWindow, MAX_TRADE_RETENTION, MIN_TRADE_RETENTION,
+    TRADE_RETENTION,
+};
+use linera_base::data_types::{Amount, TimeDelta};
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const TENTH_BTC: u64 = 10_000_000;
+
+fn place(side: OrderSide, price: u64, time_in_force: TimeInForce) -> Operation {
+    OrderPlacement { time_in_force, ..place_order(side, price, TENTH_BTC) }.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rolling_stats_and_retention() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let orderbook = deployment.orderbook;
+    let now = deployment.validator.clock().current_time();
+
+    // The retention stays within bounds
+    for trades in [MIN_TRADE_RETENTION - 1, MAX_TRADE_RETENTION + 1] {
+        let result = user.try_add_block(|block| {
+            block.with_operation(orderbook, Operation::SetTradeRetention { trades });
+        }).await;
+        assert!(result.is_err());
+    }
+    match user.query(orderbook, Query::GetTradeRetention).await {
+        QueryResponse::TradeRetention { trades, first_retained_id } => {
+            assert_eq!((trades, first_retained_id), (TRADE_RETENTION, 0));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Two trades, the second one higher
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(100_000),
+            })
+            .with_operation(orderbook, Operation::SetTradeRetention { trades: MIN_TRADE_RETENTION })
+            .with_operation(orderbook, place(OrderSide::Sell, PRICE, TimeInForce::GTC))
+            .with_operation(orderbook, place(OrderSide::Buy, PRICE, TimeInForce::IOC))
+            .with_operation(orderbook, place(OrderSide::Sell, PRICE + 100, TimeInForce::GTC))
+            .with_operation(orderbook, place(OrderSide::Buy, PRICE + 100, TimeInForce::IOC));
+    }).await;
+
+    let window = TradeWindow {
+        open: PRICE,
+        high: PRICE + 100,
+        low: PRICE,
+        close: PRICE + 100,
+        volume: 2 * TENTH_BTC,
+        trades: 2,
+    };
+    match user.query(orderbook, Query::GetTradeStats { at: now }).await {
+        QueryResponse::TradeStats(stats) => assert_eq!(stats, window),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetMarketStats).await {
+        QueryResponse::MarketStats(stats) => {
+            assert_eq!((stats.volume_24h, stats.high_24h, stats.low_24h), (2 * TENTH_BTC, PRICE + 100, PRICE));
+            assert_eq!(stats.price_change_24h, 100);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(orderbook, Query::GetTradeRetention).await {
+        QueryResponse::TradeRetention { trades, .. } => assert_eq!(trades, MIN_TRADE_RETENTION),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // A day later the window is empty
+    let later = now.saturating_add(TimeDelta::from_secs(24 * 3600));
+    match user.query(orderbook, Query::GetTradeStats { at: later }).await {
+        QueryResponse::TradeStats(stats) => assert_eq!(stats, TradeWindow::default()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,87 @@
+//! Delegated trading: orders placed for a principal need a trading permission it granted the caller.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment, OrderPlacement};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, Query, QueryResponse, TradingPermission};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn sell_for(principal: Account) -> Operation {
+    OrderPlacement {
+        on_behalf_of: Some(principal),
+        ..place_order(OrderSide::Sell, 50_000 * 100_000_000, 100_000_000)
+    }
+    .into()
+}
+
+async fn permission(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    principal: Account,
+    delegate: Account,
+) -> Option<TradingPermission> {
+    match chain.query(orderbook, Query::GetTradingPermission { principal, delegate }).await {
+        QueryResponse::TradingPermission(permission) => permission,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn permissions_are_granted_and_revoked_by_the_principal() {
+    let deployment = Deployment::new().await;
+    let mut treasury = deployment.new_user().await;
+    let bot = deployment.new_user().await;
+    let (principal, delegate) = (owner_account(&treasury), owner_account(&bot));
+    let orderbook = deployment.orderbook;
+
+    treasury.add_block(|block| {
+        block.with_operation(orderbook, Operation::GrantTradingPermission {
+            delegate,
+            max_order_size: Some(100_000_000),
+            side: Some(OrderSide::Sell),
+        });
+    }).await;
+    let granted = permission(&treasury, orderbook, principal, delegate).await.unwrap();
+    assert_eq!((granted.max_order_size, granted.side), (Some(100_000_000), Some(OrderSide::Sell)));
+
+    // Nobody granted the treasury a permission to trade for the bot
+    let result = treasury.try_add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, sell_for(delegate));
+    }).await;
+    assert!(result.is_err());
+
+    // Naming oneself is an ordinary order
+    treasury.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, sell_for(principal));
+    }).await;
+    match treasury.query(orderbook, Query::GetOrderDelegate { order_id: 0 }).await {
+        QueryResponse::OrderDelegate(delegate) => assert_eq!(delegate, None),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    treasury.add_block(|block| {
+        block.with_operation(orderbook, Operation::RevokeTradingPermission { delegate });
+    }).await;
+    assert_eq!(permission(&treasury, orderbook, principal, delegate).await, None);
+    let result = treasury.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::RevokeTradingPermission { delegate });
+    }).await;
+    assert!(result.is_err());
+
+    let result = treasury.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::GrantTradingPermission {
+            delegate: principal,
+            max_order_size: None,
+            side: None,
+        });
+    }).await;
+    assert!(result.is_err());
+}
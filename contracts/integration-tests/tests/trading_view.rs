@@ -0,0 +1,63 @@
+//! Trading view: depth, open orders and balances of an account come back in one consistent response.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, Query, QueryResponse, TradingView};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn sell(price: u64) -> Operation {
+    place_order(OrderSide::Sell, price * 100_000_000, 10_000_000).into()
+}
+
+async fn trading_view(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    depth_levels: usize,
+) -> TradingView {
+    match chain.query(orderbook, Query::GetTradingView { account, depth_levels }).await {
+        QueryResponse::TradingView(view) => view,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn view_tracks_the_book_sequence() {
+    let deployment = Deployment::new().await;
+    let mut trader = deployment.new_user().await;
+    let account = owner_account(&trader);
+    let orderbook = deployment.orderbook;
+
+    let empty = trading_view(&trader, orderbook, account, 10).await;
+    assert_eq!(empty.sequence, 0);
+    assert!(empty.asks.is_empty() && empty.open_orders.is_empty());
+
+    trader.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, sell(50_000))
+            .with_operation(orderbook, sell(51_000));
+    }).await;
+
+    // Only placements count; the deposit did not touch the book
+    let view = trading_view(&trader, orderbook, account, 1).await;
+    assert_eq!(view.sequence, 2);
+    assert_eq!(view.asks, vec![(50_000 * 100_000_000, 10_000_000)]);
+    assert_eq!(view.open_orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![0, 1]);
+    assert!(!view.more_orders);
+    let btc = view.balances.iter().find(|balance| balance.asset == "BTC").unwrap();
+    assert!(btc.locked > Amount::ZERO);
+
+    trader.add_block(|block| {
+        block.with_operation(orderbook, Operation::CancelOrder { order_id: 1, on_behalf_of: None });
+    }).await;
+    let view = trading_view(&trader, orderbook, account, 10).await;
+    assert_eq!(view.sequence, 3);
+    assert_eq!(view.asks.len(), 1);
+    assert_eq!(view.open_orders.len(), 1);
+}
@@ -0,0 +1,125 @@
+//! Transfer expiries: each chain sets its own per direction, transfers keep the one they were created with, and quotes show it.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BridgeAbi, BridgeTransfer, ChainConfig, ExternalChain, Operation, Query, QueryResponse, TransferDirection,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, TimeDelta},
+    identifiers::ApplicationId,
+};
+use linera_sdk::test::ActiveChain;
+
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+fn withdraw() -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: ADDRESS.to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+async fn transfer(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: u64) -> BridgeTransfer {
+    match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+        QueryResponse::Transfer(Some(transfer)) => transfer,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn expiries_follow_the_chain_and_direction() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let now = deployment.validator.clock().current_time();
+
+    // Expiries must stay within bounds
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::ConfigureChain {
+            config: ChainConfig { outbound_expiry_seconds: 0, ..ethereum_config() },
+        });
+    }).await;
+    assert!(result.is_err());
+
+    let config = ChainConfig { inbound_expiry_seconds: 2 * 3600, outbound_expiry_seconds: 48 * 3600, ..ethereum_config() };
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: ADDRESS.to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw());
+    }).await;
+    assert_eq!(transfer(&user, bridge, 1).await.expiry_seconds, Some(2 * 3600));
+    let withdrawal = transfer(&user, bridge, 2).await;
+    assert_eq!(withdrawal.expiry_seconds, Some(48 * 3600));
+    assert_eq!(withdrawal.expires_at, now.saturating_add(TimeDelta::from_secs(48 * 3600)));
+
+    // Quotes show the expiry a transfer created now would get
+    let query = Query::EstimateFee {
+        chain: ExternalChain::Ethereum,
+        amount: Amount::from_tokens(100),
+        direction: TransferDirection::Inbound,
+        account: None,
+        asset: None,
+    };
+    match user.query(bridge, query).await {
+        QueryResponse::FeeEstimate { expiry_seconds, .. } => assert_eq!(expiry_seconds, 2 * 3600),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    let query = Query::ValidateWithdrawal {
+        chain: ExternalChain::Ethereum,
+        asset: TEST_ASSET.to_string(),
+        amount: Amount::from_tokens(100),
+        destination_address: ADDRESS.to_string(),
+        account,
+        memo: None,
+        fee_voucher: None,
+        at: now,
+    };
+    match user.query(bridge, query).await {
+        QueryResponse::WithdrawalValidation(Ok(quote)) => {
+            assert_eq!(quote.expiry_seconds, 48 * 3600);
+            assert_eq!(quote.expires_at, withdrawal.expires_at);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // An update applies to new transfers only, and is bounded too
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::UpdateExpiries {
+                chain: ExternalChain::Ethereum,
+                inbound_expiry_seconds: None,
+                outbound_expiry_seconds: Some(4 * 3600),
+            })
+            .with_operation(bridge, withdraw());
+    }).await;
+    assert_eq!(transfer(&user, bridge, 2).await.expires_at, withdrawal.expires_at);
+    assert_eq!(transfer(&user, bridge, 3).await.expiry_seconds, Some(4 * 3600));
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::UpdateExpiries {
+            chain: ExternalChain::Ethereum,
+            inbound_expiry_seconds: Some(30 * 86_400),
+            outbound_expiry_seconds: None,
+        });
+    }).await;
+    assert!(result.is_err());
+}
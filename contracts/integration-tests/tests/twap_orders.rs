@@ -0,0 +1,76 @@
+//! TWAP orders: one slice per due time however often the keeper runs, and cancel unlocks the rest.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, Query, QueryResponse, TwapOrder, TwapStatus};
+use linera_base::data_types::{Amount, TimeDelta};
+use linera_base::identifiers::ApplicationId;
+use linera_sdk::test::ActiveChain;
+
+const PRICE: u64 = 50_000 * 100_000_000;
+const ONE_BTC: u64 = 100_000_000;
+
+async fn twap(chain: &ActiveChain, orderbook: ApplicationId<OrderBookAbi>) -> TwapOrder {
+    match chain.query(orderbook, Query::GetTwapOrder { twap_id: 0 }).await {
+        QueryResponse::TwapOrder { order: Some(order), .. } => order,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn twap_releases_slices_on_schedule() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let orderbook = deployment.orderbook;
+
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(2) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(200_000),
+            })
+            .with_operation(orderbook, Operation::from(place_order(OrderSide::Buy, PRICE, ONE_BTC)))
+            .with_operation(orderbook, Operation::PlaceTwapOrder {
+                side: OrderSide::Sell,
+                total_quantity: ONE_BTC,
+                duration_seconds: 400,
+                slice_count: 4,
+                limit_price: Some(PRICE),
+            });
+    }).await;
+
+    // The first slice goes out on placement; keeper calls in the same block release nothing more
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::ProcessTwapOrders)
+            .with_operation(orderbook, Operation::ProcessTwapOrders);
+    }).await;
+    let order = twap(&user, orderbook).await;
+    assert_eq!(order.slices_released, 1);
+    assert_eq!(order.filled_quantity, ONE_BTC / 4);
+
+    deployment.validator.clock().add(TimeDelta::from_secs(100));
+    user.add_block(|block| {
+        block.with_operation(orderbook, Operation::ProcessTwapOrders);
+    }).await;
+    let order = twap(&user, orderbook).await;
+    assert_eq!(order.slices_released, 2);
+    assert_eq!(order.filled_quantity, ONE_BTC / 2);
+    assert_eq!(order.child_order_ids.len(), 2);
+
+    user.add_block(|block| {
+        block.with_operation(orderbook, Operation::CancelTwapOrder { twap_id: 0 });
+    }).await;
+    let order = twap(&user, orderbook).await;
+    assert_eq!(order.status, TwapStatus::Cancelled);
+    assert_eq!(order.locked, Amount::ZERO);
+
+    // The unreleased half is no longer locked
+    match user.query(orderbook, Query::GetAccountBalance { account, asset: "BTC".to_string() }).await {
+        QueryResponse::AccountBalance { locked, .. } => assert_eq!(locked, Amount::ZERO),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
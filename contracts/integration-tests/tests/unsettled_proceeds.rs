@@ -0,0 +1,116 @@
+//! Unsettled proceeds: fills handed to settlement hold their proceeds until the trade settles, withdrawals cannot touch them, and a failed settlement drains them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_integration_tests::{owner_account, place_order, Deployment};
+use axelarx_orderbook::{Operation, OrderBookAbi, OrderSide, Query, QueryResponse};
+use linera_base::{data_types::Amount, identifiers::{Account, ApplicationId}};
+use linera_sdk::test::ActiveChain;
+
+const ONE_BTC: u64 = 100_000_000;
+
+fn place(side: OrderSide, quantity: u64) -> Operation {
+    place_order(side, 50_000 * 100_000_000, quantity).into()
+}
+
+fn allow_unsettled_trading() -> Operation {
+    Operation::UpdateConfig {
+        min_order_size: None,
+        max_order_size: None,
+        tick_size: None,
+        fee_model: None,
+        max_orders_per_level: None,
+        max_account_orders_per_level: None,
+        allow_unsettled_trading: Some(true),
+    }
+}
+
+/// Free, locked and unsettled balance of `asset`
+async fn buckets(
+    chain: &ActiveChain,
+    orderbook: ApplicationId<OrderBookAbi>,
+    account: Account,
+    asset: &str,
+) -> (Amount, Amount, Amount) {
+    match chain.query(orderbook, Query::GetAccountBalance { account, asset: asset.to_string() }).await {
+        QueryResponse::AccountBalance { available, locked, unsettled } => (available, locked, unsettled),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proceeds_wait_for_settlement() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let chain_id = user.id();
+    let (orderbook, settlement) = (deployment.orderbook, deployment.settlement);
+
+    // A USDT leg above the maximum makes the trade's settlement fail once requested
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetSettlementApplication {
+                application_id: settlement.forget_abi(),
+                chain_id,
+            })
+            .with_operation(orderbook, Operation::SetSettlementMaximum {
+                asset: "USDT".to_string(),
+                maximum: Amount::from_tokens(10_000),
+            })
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, Operation::Deposit {
+                asset: "USDT".to_string(),
+                amount: Amount::from_tokens(50_000),
+            })
+            .with_operation(orderbook, place(OrderSide::Sell, ONE_BTC))
+            .with_operation(orderbook, place(OrderSide::Buy, ONE_BTC));
+    }).await;
+    let usdt = (Amount::ZERO, Amount::ZERO, Amount::from_tokens(49_950));
+    assert_eq!(buckets(&user, orderbook, account, "USDT").await, usdt);
+    assert_eq!(buckets(&user, orderbook, account, "BTC").await.2, Amount::from_millis(998));
+
+    // Withdrawals only use the free balance, and so do orders until the market allows otherwise
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, Operation::Withdraw { asset: "USDT".to_string(), amount: Amount::from_tokens(1) });
+    }).await;
+    assert!(result.is_err());
+    let result = user.try_add_block(|block| {
+        block.with_operation(orderbook, place(OrderSide::Buy, ONE_BTC / 2));
+    }).await;
+    assert!(result.is_err());
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, allow_unsettled_trading())
+            .with_operation(orderbook, place(OrderSide::Buy, ONE_BTC / 2));
+    }).await;
+    let usdt = (Amount::ZERO, Amount::from_tokens(25_000), Amount::from_tokens(24_950));
+    assert_eq!(buckets(&user, orderbook, account, "USDT").await, usdt);
+
+    // The failed settlement drains what is left, not what the bid already locked
+    user.add_block(|block| {
+        block.with_operation(orderbook, Operation::RequestTradeSettlement { trade_id: 0, timeout_seconds: 3_600 });
+    }).await;
+    let usdt = (Amount::ZERO, Amount::from_tokens(25_000), Amount::ZERO);
+    assert_eq!(buckets(&user, orderbook, account, "USDT").await, usdt);
+    assert_eq!(buckets(&user, orderbook, account, "BTC").await.2, Amount::ZERO);
+
+    // Trades below the settlement minimum settle internally and are free at once
+    user.add_block(|block| {
+        block
+            .with_operation(orderbook, Operation::SetSettlementMinimum {
+                asset: "BTC".to_string(),
+                minimum: Amount::from_tokens(10),
+            })
+            .with_operation(orderbook, Operation::Deposit { asset: "BTC".to_string(), amount: Amount::from_tokens(1) })
+            .with_operation(orderbook, place(OrderSide::Sell, ONE_BTC / 2));
+    }).await;
+    let usdt = (Amount::from_tokens(24_950), Amount::ZERO, Amount::ZERO);
+    assert_eq!(buckets(&user, orderbook, account, "USDT").await, usdt);
+    match user.query(orderbook, Query::GetTradingView { account, depth_levels: 1 }).await {
+        QueryResponse::TradingView(view) => {
+            assert_eq!(view.balances[0].available, Amount::from_micros(999_500));
+            assert_eq!(view.balances[1].unsettled, Amount::ZERO);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
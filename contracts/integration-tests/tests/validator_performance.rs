@@ -0,0 +1,65 @@
+//! Validator performance: approvals and their latency are counted per validator until reset.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse, ValidatorPerformance};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+async fn performance(chain: &ActiveChain, bridge: ApplicationId<BridgeAbi>) -> Vec<(Account, ValidatorPerformance)> {
+    match chain.query(bridge, Query::GetValidatorPerformance).await {
+        QueryResponse::ValidatorPerformance { validators, .. } => validators,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn approvals_are_counted_until_reset() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            })
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![] });
+    }).await;
+
+    let validators = performance(&user, bridge).await;
+    assert_eq!(validators.len(), 1);
+    let (validator, record) = &validators[0];
+    assert_eq!(*validator, account);
+    assert_eq!((record.approvals, record.missed_quorums), (1, 0));
+    assert_eq!(record.average_latency_seconds(), Some(0));
+
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::ResetValidatorPerformance);
+    }).await;
+    assert!(performance(&user, bridge).await.is_empty());
+}
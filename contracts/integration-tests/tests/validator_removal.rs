@@ -0,0 +1,78 @@
+//! Validator removal: a removed validator's approvals are dropped and stop counting toward quorum.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{ExternalChain, Operation, Query, QueryResponse, TransferStatus, ValidatorConfig};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::data_types::Amount;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn removed_validator_approvals_stop_counting() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let other = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let light = ValidatorConfig { weight: 1, ..sole_validator(&user) };
+    let heavy = ValidatorConfig { weight: 2, ..sole_validator(&user) };
+    let absent = ValidatorConfig { weight: 2, ..sole_validator(&other) };
+
+    // Two of three weight are needed, and the user's single unit is not enough
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: light })
+            .with_operation(bridge, Operation::AddValidator { config: absent })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, Operation::InitiateWithdrawal {
+                destination_chain: ExternalChain::Ethereum,
+                destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(100),
+                memo: None,
+                client_request_id: None,
+                fee_voucher: None,
+            })
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![7; 65] });
+    }).await;
+
+    // Removal takes the approval and its weight with it
+    user.add_block(|block| {
+        block.with_operation(bridge, Operation::RemoveValidator { validator: account });
+    }).await;
+    match user.query(bridge, Query::GetTransferApprovals { transfer_id: 2 }).await {
+        QueryResponse::TransferApprovals { approvals, weight } => {
+            assert!(approvals.is_empty());
+            assert_eq!(weight, 0);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Re-added at a higher weight, the validator approves afresh and counts once
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::AddValidator { config: heavy })
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 2, signature: vec![7; 65] });
+    }).await;
+    match user.query(bridge, Query::GetTransferApprovals { transfer_id: 2 }).await {
+        QueryResponse::TransferApprovals { approvals, weight } => {
+            assert_eq!(approvals.len(), 1);
+            assert_eq!(weight, 2);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => assert_eq!(transfer.status, TransferStatus::Approved),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,118 @@
+//! Withdrawal batching: one approval and one completion report per batch, refunds per failed item.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{
+    BatchLimits, BatchStatus, ExternalChain, Operation, Query, QueryResponse, TransferDirection, TransferStatus,
+};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::data_types::Amount;
+
+fn withdraw(amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_batch_seals_and_refunds_only_failed_items() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.admin.clone();
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let config = ethereum_config();
+    let validator = sole_validator(&user);
+
+    let deposit = Amount::from_tokens(1_000);
+    let deposit_fees = config.fee_breakdown(deposit, TransferDirection::Inbound).unwrap();
+    let failed_fees = config.fee_breakdown(Amount::from_tokens(200), TransferDirection::Outbound).unwrap();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: config.clone() })
+            .with_operation(bridge, Operation::AddValidator { config: validator })
+            .with_operation(bridge, Operation::ConfigureBatching {
+                chain: ExternalChain::Ethereum,
+                limits: Some(BatchLimits {
+                    max_transfers: 2,
+                    max_total_amount: Amount::from_tokens(1_000_000),
+                    max_age_seconds: 3_600,
+                }),
+            })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: deposit,
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            })
+            .with_operation(bridge, withdraw(Amount::from_tokens(100)))
+            .with_operation(bridge, withdraw(Amount::from_tokens(200)));
+    }).await;
+
+    // The second withdrawal fills the batch, which is sealed for approval
+    match user.query(bridge, Query::GetBatch { batch_id: 1 }).await {
+        QueryResponse::Batch(Some(batch)) => {
+            assert_eq!(batch.status, BatchStatus::AwaitingApproval);
+            assert_eq!(batch.transfer_ids, vec![2, 3]);
+            assert!(batch.root.is_some());
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match user.query(bridge, Query::GetOpenBatch { chain: ExternalChain::Ethereum }).await {
+        QueryResponse::Batch(batch) => assert!(batch.is_none()),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    // Individual completion is refused for batched withdrawals
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::CompleteWithdrawal {
+            transfer_id: 2,
+            tx_hash: "0xsingle".to_string(),
+            success: true,
+        });
+    }).await;
+    assert!(result.is_err());
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ApproveBatch { batch_id: 1, signature: vec![] })
+            .with_operation(bridge, Operation::ExecuteBatch { batch_id: 1 })
+            .with_operation(bridge, Operation::CompleteBatch {
+                batch_id: 1,
+                tx_hash: "0xbatch".to_string(),
+                results: vec![true, false],
+            });
+    }).await;
+
+    match user.query(bridge, Query::GetBatch { batch_id: 1 }).await {
+        QueryResponse::Batch(Some(batch)) => {
+            assert_eq!(batch.status, BatchStatus::Completed);
+            assert_eq!(batch.destination_tx_hash.as_deref(), Some("0xbatch"));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    for (transfer_id, status) in [(2, TransferStatus::Completed), (3, TransferStatus::Failed)] {
+        match user.query(bridge, Query::GetTransfer { transfer_id }).await {
+            QueryResponse::Transfer(Some(transfer)) => assert_eq!(transfer.status, status),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    // Only the failed withdrawal comes back, net of its fee
+    let expected = deposit_fees.net_amount - Amount::from_tokens(300) + failed_fees.net_amount;
+    match user.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => assert_eq!(balance, expected),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,95 @@
+//! Withdrawal cancellation: the whole fee comes back before quorum, the validators' share stays after.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, CancellationRefund, ExternalChain, Operation, Query, QueryResponse, TransferId};
+use axelarx_integration_tests::{ethereum_config, owner_account, sole_validator, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+fn withdraw(amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+async fn refund(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, transfer_id: TransferId) -> Option<CancellationRefund> {
+    match user.query(bridge, Query::GetCancellationRefund { transfer_id }).await {
+        QueryResponse::CancellationRefund(refund) => refund,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+/// Free balance and collected fees in `TEST_ASSET`
+async fn books(user: &ActiveChain, bridge: ApplicationId<BridgeAbi>, account: Account) -> (Amount, Amount) {
+    let balance = match user.query(bridge, Query::GetBalance { account, asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::Balance(balance) => balance,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    match user.query(bridge, Query::GetCollectedFees { asset: TEST_ASSET.to_string() }).await {
+        QueryResponse::CollectedFees(fees) => (balance, fees),
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cancellation_refunds_unearned_fees() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: ethereum_config() })
+            .with_operation(bridge, Operation::AddValidator { config: sole_validator(&user) })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            });
+    }).await;
+    let (balance, fees) = books(&user, bridge, account).await;
+
+    // The sole validator's signature brings the second withdrawal to quorum
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, withdraw(Amount::from_tokens(100)))
+            .with_operation(bridge, withdraw(Amount::from_tokens(200)))
+            .with_operation(bridge, Operation::ApproveTransfer { transfer_id: 3, signature: vec![] });
+    }).await;
+
+    let unapproved = refund(&user, bridge, 2).await.unwrap();
+    assert_eq!((unapproved.refund, unapproved.fee_retained), (Amount::from_tokens(100), Amount::ZERO));
+    let approved = refund(&user, bridge, 3).await.unwrap();
+    assert!(approved.fee_retained > Amount::ZERO);
+    assert_eq!(approved.refund + approved.fee_retained, Amount::from_tokens(200));
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::CancelWithdrawal { transfer_id: 2 })
+            .with_operation(bridge, Operation::CancelWithdrawal { transfer_id: 3 });
+    }).await;
+    assert_eq!(books(&user, bridge, account).await, (balance - approved.fee_retained, fees + approved.fee_retained));
+    assert_eq!(refund(&user, bridge, 2).await, None);
+
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, Operation::CancelWithdrawal { transfer_id: 2 });
+    }).await;
+    assert!(result.is_err());
+}
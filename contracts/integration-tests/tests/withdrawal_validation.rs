@@ -0,0 +1,101 @@
+//! Withdrawal dry run: `ValidateWithdrawal` quotes what `InitiateWithdrawal` charges and lists what it refuses.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use axelarx_bridge::{BridgeAbi, ExternalChain, Operation, Query, QueryResponse, WithdrawalIssue};
+use axelarx_integration_tests::{ethereum_config, owner_account, Deployment, TEST_ASSET};
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::{Account, ApplicationId},
+};
+use linera_sdk::test::ActiveChain;
+
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+fn withdraw(destination_address: &str, amount: Amount) -> Operation {
+    Operation::InitiateWithdrawal {
+        destination_chain: ExternalChain::Ethereum,
+        destination_address: destination_address.to_string(),
+        asset: TEST_ASSET.to_string(),
+        amount,
+        memo: None,
+        client_request_id: None,
+        fee_voucher: None,
+    }
+}
+
+async fn validate(
+    user: &ActiveChain,
+    bridge: ApplicationId<BridgeAbi>,
+    account: Account,
+    destination_address: &str,
+    amount: Amount,
+) -> QueryResponse {
+    user.query(bridge, Query::ValidateWithdrawal {
+        chain: ExternalChain::Ethereum,
+        asset: TEST_ASSET.to_string(),
+        amount,
+        destination_address: destination_address.to_string(),
+        account,
+        memo: None,
+        fee_voucher: None,
+        at: Timestamp::from(0),
+    }).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dry_run_agrees_with_initiate() {
+    let deployment = Deployment::new().await;
+    let mut user = deployment.new_user().await;
+    let account = owner_account(&user);
+    let bridge = deployment.bridge;
+    let config = ethereum_config();
+
+    user.add_block(|block| {
+        block
+            .with_operation(bridge, Operation::ConfigureChain { config: config.clone() })
+            .with_operation(bridge, Operation::ReportDeposit {
+                source_chain: ExternalChain::Ethereum,
+                tx_hash: "0xdeposit".to_string(),
+                source_address: ADDRESS.to_string(),
+                recipient: Some(account),
+                asset: TEST_ASSET.to_string(),
+                amount: Amount::from_tokens(1_000),
+                block_height: 100,
+                confirmations: 12,
+                bridge_contract_address: None,
+            });
+    }).await;
+
+    // Every failed check is listed, and the withdrawal itself is refused
+    let too_much = Amount::from_tokens(5_000);
+    match validate(&user, bridge, account, "0x1234", too_much).await {
+        QueryResponse::WithdrawalValidation(Err(issues)) => {
+            assert!(issues.contains(&WithdrawalIssue::InvalidAddress));
+            assert!(issues.iter().any(|issue| matches!(issue, WithdrawalIssue::InsufficientBalance { .. })));
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    let result = user.try_add_block(|block| {
+        block.with_operation(bridge, withdraw("0x1234", too_much));
+    }).await;
+    assert!(result.is_err());
+
+    // A passing dry run quotes the fee the withdrawal is charged
+    let amount = Amount::from_tokens(100);
+    let quote = match validate(&user, bridge, account, ADDRESS, amount).await {
+        QueryResponse::WithdrawalValidation(Ok(quote)) => quote,
+        other => panic!("unexpected response: {other:?}"),
+    };
+    assert_eq!(quote.config_version, 1);
+    user.add_block(|block| {
+        block.with_operation(bridge, withdraw(ADDRESS, amount));
+    }).await;
+    match user.query(bridge, Query::GetTransfer { transfer_id: 2 }).await {
+        QueryResponse::Transfer(Some(transfer)) => {
+            assert_eq!(transfer.fee, quote.fees.total_fee);
+            assert_eq!(transfer.net_amount, quote.fees.net_amount);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,299 @@
+/*!
+# AxelarX Math
+
+Checked fixed-point and fee arithmetic shared by the orderbook, settlement and bridge contracts.
+
+## Conventions
+- Orderbook prices and quantities are fixed point scaled by 1e8 (`PRICE_DECIMALS`)
+- Fees are expressed in basis points (1/10000) and never exceed 100%
+- Every division rounds toward zero, so a fee or quote is never larger than its exact value.
+  The exceptions are the quote an order locks and an escrow converted into a substitute asset,
+  which round up so they cover what is owed
+- Conversion rates between assets use the same 1e8 fixed point
+- Quote notional is `price * quantity / 1e8` with a u128 intermediate: `quote_lock` for funds
+  held against an order, `quote_credit` for funds changing hands
+- Overflow is reported as `MathError` instead of wrapping, panicking or saturating
+*/
+
+use linera_base::data_types::Amount;
+use thiserror::Error;
+
+/// Decimals of orderbook prices and quantities
+pub const PRICE_DECIMALS: u8 = 8;
+
+/// Scale of orderbook prices and quantities (1e8)
+pub const PRICE_SCALE: u128 = 100_000_000;
+
+/// Decimals of a Linera `Amount`
+pub const AMOUNT_DECIMALS: u8 = 18;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Arithmetic errors
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    #[error("Arithmetic overflow")]
+    Overflow,
+
+    #[error("Arithmetic underflow")]
+    Underflow,
+
+    #[error("Basis points above 100%: {bps}")]
+    InvalidBps { bps: u64 },
+}
+
+/// Quote value of `quantity` at `price`, in the same 1e8 fixed point, rounded down.
+pub fn quote_amount(price: u64, quantity: u64) -> Result<u128, MathError> {
+    let product = (price as u128).checked_mul(quantity as u128).ok_or(MathError::Overflow)?;
+    Ok(product / PRICE_SCALE)
+}
+
+/// Quote value of `quantity` at `price`, in the same 1e8 fixed point, rounded up.
+pub fn quote_amount_up(price: u64, quantity: u64) -> Result<u128, MathError> {
+    let product = (price as u128).checked_mul(quantity as u128).ok_or(MathError::Overflow)?;
+    Ok(product.div_ceil(PRICE_SCALE))
+}
+
+/// Quote an order of `quantity` at `price` must hold, rounded up. Cumulative locks telescope:
+/// `quote_lock(p, a + b) - quote_lock(p, a) >= quote_credit(p', b)` for any `p' <= p`.
+pub fn quote_lock(price: u64, quantity: u64) -> Result<Amount, MathError> {
+    fixed_to_amount(quote_amount_up(price, quantity)?)
+}
+
+/// Quote paid and credited for a fill of `quantity` at `price`, rounded down.
+pub fn quote_credit(price: u64, quantity: u64) -> Result<Amount, MathError> {
+    fixed_to_amount(quote_amount(price, quantity)?)
+}
+
+/// Largest quantity whose `quote_amount` at `price` fits in `quote`; saturates at `u64::MAX`.
+pub fn quantity_for_quote(price: u64, quote: u128) -> Result<u64, MathError> {
+    // quote_amount(p, q) <= quote  <=>  p * q < (quote + 1) * 1e8
+    let bound = quote.checked_add(1).and_then(|next| next.checked_mul(PRICE_SCALE)).ok_or(MathError::Overflow)?;
+    let quantity = (bound - 1).checked_div(price as u128).ok_or(MathError::Overflow)?;
+    Ok(u64::try_from(quantity).unwrap_or(u64::MAX))
+}
+
+/// `bps` basis points of `amount`, rounded down. Exact for every `amount` (no intermediate overflow).
+pub fn fee_from_bps(amount: u128, bps: u64) -> Result<u128, MathError> {
+    if bps > BPS_DENOMINATOR {
+        return Err(MathError::InvalidBps { bps });
+    }
+    let bps = bps as u128;
+    let denominator = BPS_DENOMINATOR as u128;
+    // Split so neither product can overflow: amount = q * 10000 + r
+    let (quotient, remainder) = (amount / denominator, amount % denominator);
+    Ok(quotient * bps + remainder * bps / denominator)
+}
+
+/// Converts `value` between decimal precisions, rounding down when precision is lost.
+pub fn rescale(value: u128, from_decimals: u8, to_decimals: u8) -> Result<u128, MathError> {
+    if to_decimals >= from_decimals {
+        let factor = pow10(to_decimals - from_decimals)?;
+        value.checked_mul(factor).ok_or(MathError::Overflow)
+    } else {
+        match pow10(from_decimals - to_decimals) {
+            Ok(factor) => Ok(value / factor),
+            // Any u128 divided by more than 10^38 is zero
+            Err(MathError::Overflow) => Ok(0),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+fn pow10(exponent: u8) -> Result<u128, MathError> {
+    10u128.checked_pow(exponent as u32).ok_or(MathError::Overflow)
+}
+
+/// Orderbook fixed-point value (1e8) as an `Amount`.
+pub fn fixed_to_amount(value: u128) -> Result<Amount, MathError> {
+    Ok(Amount::from(rescale(value, PRICE_DECIMALS, AMOUNT_DECIMALS)?))
+}
+
+/// `Amount` as an orderbook fixed-point value (1e8), rounded down.
+pub fn amount_to_fixed(amount: Amount) -> Result<u128, MathError> {
+    rescale(amount.into_inner(), AMOUNT_DECIMALS, PRICE_DECIMALS)
+}
+
+/// `bps` basis points of an `Amount`, rounded down.
+pub fn amount_fee(amount: Amount, bps: u64) -> Result<Amount, MathError> {
+    Ok(Amount::from(fee_from_bps(amount.into_inner(), bps)?))
+}
+
+/// `amount` converted at `rate` (1e8 fixed point), rounded down.
+pub fn convert(amount: Amount, rate: u64) -> Result<Amount, MathError> {
+    let product = amount.into_inner().checked_mul(rate as u128).ok_or(MathError::Overflow)?;
+    Ok(Amount::from(product / PRICE_SCALE))
+}
+
+/// `amount` converted at `rate` (1e8 fixed point), rounded up.
+pub fn convert_up(amount: Amount, rate: u64) -> Result<Amount, MathError> {
+    let product = amount.into_inner().checked_mul(rate as u128).ok_or(MathError::Overflow)?;
+    Ok(Amount::from(product.div_ceil(PRICE_SCALE)))
+}
+
+pub fn checked_add(a: Amount, b: Amount) -> Result<Amount, MathError> {
+    a.into_inner().checked_add(b.into_inner()).map(Amount::from).ok_or(MathError::Overflow)
+}
+
+pub fn checked_sub(a: Amount, b: Amount) -> Result<Amount, MathError> {
+    a.into_inner().checked_sub(b.into_inner()).map(Amount::from).ok_or(MathError::Underflow)
+}
+
+/// `a - b`, or zero when `b` exceeds `a`. For amounts that may legitimately be consumed entirely.
+pub fn saturating_sub(a: Amount, b: Amount) -> Amount {
+    Amount::from(a.into_inner().saturating_sub(b.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_quote_amount_rounds_down() {
+        // 0.5 at 1.5 = 0.75
+        assert_eq!(quote_amount(150_000_000, 50_000_000), Ok(75_000_000));
+        // 1e-8 at 0.5 is below one unit
+        assert_eq!(quote_amount(50_000_000, 1), Ok(0));
+    }
+
+    #[test]
+    fn test_quote_lock_rounds_up() {
+        // 1e-8 at 0.5 still locks one unit, but credits nothing
+        assert_eq!(quote_amount_up(50_000_000, 1), Ok(1));
+        assert_eq!(quote_credit(50_000_000, 1), Ok(Amount::ZERO));
+        assert_eq!(quote_lock(50_000_000, 1), Ok(Amount::from(10_000_000_000)));
+        // Exact products round the same both ways
+        assert_eq!(quote_lock(150_000_000, 50_000_000), quote_credit(150_000_000, 50_000_000));
+    }
+
+    #[test]
+    fn test_quote_at_extremes() {
+        let max = u64::MAX;
+        let product = max as u128 * max as u128;
+        assert_eq!(quote_amount(max, max), Ok(product / PRICE_SCALE));
+        assert_eq!(quote_amount_up(max, max), Ok(product / PRICE_SCALE + 1));
+        // Too large to express in `Amount` precision: an error, not a wrapped value
+        assert_eq!(quote_lock(max, max), Err(MathError::Overflow));
+        assert_eq!(quote_credit(max, max), Err(MathError::Overflow));
+        assert_eq!(quote_lock(max, 1), Ok(Amount::from(max.div_ceil(100_000_000) as u128 * 10_000_000_000)));
+        assert_eq!(quote_lock(0, max), Ok(Amount::ZERO));
+        assert_eq!(quote_lock(1, 1), Ok(Amount::from(10_000_000_000)));
+    }
+
+    #[test]
+    fn test_fee_from_bps_bounds() {
+        assert_eq!(fee_from_bps(1_000_000, 30), Ok(3_000));
+        assert_eq!(fee_from_bps(9_999, 1), Ok(0));
+        assert_eq!(fee_from_bps(u128::MAX, 10_000), Ok(u128::MAX));
+        assert_eq!(fee_from_bps(1, 10_001), Err(MathError::InvalidBps { bps: 10_001 }));
+    }
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(rescale(1, 8, 18), Ok(10_000_000_000));
+        assert_eq!(rescale(19_999_999_999, 18, 8), Ok(1));
+        assert_eq!(rescale(u128::MAX, 0, 1), Err(MathError::Overflow));
+        assert_eq!(rescale(u128::MAX, 0, 39), Err(MathError::Overflow));
+        assert_eq!(rescale(u128::MAX, 39, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_convert_rounding() {
+        // 1:1 is exact both ways
+        assert_eq!(convert_up(Amount::from(7), 100_000_000), Ok(Amount::from(7)));
+        // 3 at 0.5 is 1.5
+        assert_eq!(convert(Amount::from(3), 50_000_000), Ok(Amount::from(1)));
+        assert_eq!(convert_up(Amount::from(3), 50_000_000), Ok(Amount::from(2)));
+        assert_eq!(convert(Amount::from(u128::MAX), 2), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_checked_amounts() {
+        let max = Amount::from(u128::MAX);
+        assert_eq!(checked_add(max, Amount::from(1)), Err(MathError::Overflow));
+        assert_eq!(checked_sub(Amount::from(1), Amount::from(2)), Err(MathError::Underflow));
+        assert_eq!(saturating_sub(Amount::from(1), Amount::from(2)), Amount::ZERO);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_fee_is_exact_floor(amount in 0u128..(u128::MAX / 10_000), bps in 0u64..=10_000) {
+            let fee = fee_from_bps(amount, bps).unwrap();
+            prop_assert_eq!(fee, amount * bps as u128 / 10_000);
+        }
+
+        #[test]
+        fn prop_fee_never_exceeds_amount(amount: u128, bps in 0u64..=10_000) {
+            prop_assert!(fee_from_bps(amount, bps).unwrap() <= amount);
+        }
+
+        #[test]
+        fn prop_fee_monotonic_in_bps(amount: u128, bps in 0u64..10_000) {
+            prop_assert!(fee_from_bps(amount, bps).unwrap() <= fee_from_bps(amount, bps + 1).unwrap());
+        }
+
+        #[test]
+        fn prop_quote_rounds_toward_zero(price: u64, quantity: u64) {
+            let quote = quote_amount(price, quantity).unwrap();
+            let exact = price as u128 * quantity as u128;
+            prop_assert!(quote * PRICE_SCALE <= exact);
+            prop_assert!(exact < (quote + 1) * PRICE_SCALE);
+        }
+
+        #[test]
+        fn prop_quantity_for_quote_is_largest_fit(price in 1u64.., quote in 0u128..(u64::MAX as u128)) {
+            let quantity = quantity_for_quote(price, quote).unwrap();
+            prop_assert!(quote_amount(price, quantity).unwrap() <= quote);
+            if quantity < u64::MAX {
+                prop_assert!(quote_amount(price, quantity + 1).unwrap() > quote);
+            }
+        }
+
+        #[test]
+        fn prop_quote_up_is_exact_ceiling(price: u64, quantity: u64) {
+            let up = quote_amount_up(price, quantity).unwrap();
+            let down = quote_amount(price, quantity).unwrap();
+            let exact = price as u128 * quantity as u128;
+            prop_assert!(up * PRICE_SCALE >= exact);
+            prop_assert!(up - down <= 1);
+            prop_assert_eq!(up == down, exact.is_multiple_of(PRICE_SCALE));
+        }
+
+        #[test]
+        fn prop_lock_covers_every_fill(
+            limit in 0u64..=u64::MAX / 2,
+            fill_price_ratio in 0u64..=1_000,
+            filled in 0u64..=u64::MAX / 4,
+            quantity in 0u64..=u64::MAX / 4,
+        ) {
+            // A buy at `limit` fills at or below it; the lock drawn for the fill must cover its cost
+            let fill_price = (limit as u128 * fill_price_ratio as u128 / 1_000) as u64;
+            let drawn = quote_amount_up(limit, filled + quantity).unwrap() - quote_amount_up(limit, filled).unwrap();
+            prop_assert!(drawn >= quote_amount(fill_price, quantity).unwrap());
+        }
+
+        #[test]
+        fn prop_split_fills_never_exceed_lock(
+            price in 0u64..=u64::MAX / 2,
+            quantities in prop::collection::vec(0u64..=1_000_000_000_000, 1..16),
+        ) {
+            let total: u64 = quantities.iter().sum();
+            let credited: u128 = quantities.iter().map(|quantity| quote_amount(price, *quantity).unwrap()).sum();
+            prop_assert!(credited <= quote_amount_up(price, total).unwrap());
+        }
+
+        #[test]
+        fn prop_rescale_round_trip(value in 0u128..(u128::MAX / 10_000_000_000)) {
+            let up = rescale(value, PRICE_DECIMALS, AMOUNT_DECIMALS).unwrap();
+            prop_assert_eq!(rescale(up, AMOUNT_DECIMALS, PRICE_DECIMALS).unwrap(), value);
+        }
+
+        #[test]
+        fn prop_rescale_down_never_rounds_up(value: u128) {
+            let down = rescale(value, AMOUNT_DECIMALS, PRICE_DECIMALS).unwrap();
+            prop_assert!(down * 10_000_000_000 <= value);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Orders forwarded between sibling market chains, so a user trades another market without
+//! switching to its chain. The receiving market places the order under the original user and
+//! acknowledges it with the order id or a structured rejection.
+
+use linera_base::{
+    data_types::Timestamp,
+    identifiers::{Account, ChainId},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{MarketConfig, OrderBookError, OrderId, OrderSide, OrderType, Price, Quantity, RejectionCode, TimeInForce};
+
+/// Market run by a sibling chain, as registered by the admin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiblingMarket {
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub registered_at: Timestamp,
+}
+
+/// Everything `PlaceOrder` takes besides the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardedOrderSpec {
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub time_in_force: TimeInForce,
+    pub expires_at: Option<Timestamp>,
+    #[serde(default)]
+    pub require_full_fill: bool,
+    #[serde(default)]
+    pub min_fill_quantity: Option<Quantity>,
+}
+
+/// What the receiving market did with a forwarded order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForwardOutcome {
+    /// Awaiting the acknowledgment
+    Pending,
+    Placed { order_id: OrderId },
+    Rejected { code: RejectionCode, reason: String },
+}
+
+impl ForwardOutcome {
+    /// Acknowledgment for the result of placing a forwarded order
+    pub fn of(result: Result<OrderId, OrderBookError>) -> Self {
+        match result {
+            Ok(order_id) => ForwardOutcome::Placed { order_id },
+            Err(error) => ForwardOutcome::Rejected { code: error.rejection_code(), reason: error.to_string() },
+        }
+    }
+}
+
+/// An order the user sent to a sibling market, kept on the sending chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardedOrder {
+    pub forward_id: u64,
+    pub user: Account,
+    pub market_chain: ChainId,
+    pub spec: ForwardedOrderSpec,
+    pub outcome: ForwardOutcome,
+    pub forwarded_at: Timestamp,
+    /// When the acknowledgment arrived
+    pub acknowledged_at: Option<Timestamp>,
+}
+
+/// Refuses an order meant for another pair than the one `config` trades
+pub fn check_pair(config: &MarketConfig, base_asset: &str, quote_asset: &str) -> Result<(), OrderBookError> {
+    if config.base_asset != base_asset || config.quote_asset != quote_asset {
+        return Err(OrderBookError::MarketPairMismatch {
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_orders_are_acknowledged_with_a_code() {
+        let config = MarketConfig::default();
+        assert!(check_pair(&config, "BTC", "USDT").is_ok());
+        let mismatch = check_pair(&config, "ETH", "USDT").unwrap_err();
+
+        assert_eq!(ForwardOutcome::of(Ok(7)), ForwardOutcome::Placed { order_id: 7 });
+        match ForwardOutcome::of(Err(mismatch)) {
+            ForwardOutcome::Rejected { code, reason } => {
+                assert_eq!(code, RejectionCode::MarketPairMismatch);
+                assert!(reason.contains("ETH/USDT"));
+            }
+            other => panic!("unexpected outcome: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-order amendment history, so compliance can replay every change to an order. Fills name
+//! their trade ids, which cross-link the history to `GetTrades`.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{Order, OrderSide, OrderType, Price, Quantity, TimeInForce};
+
+/// Entries kept per order. Past it the placement entry stays and the oldest entries after it
+/// are dropped, counted in `OrderHistory::dropped`.
+pub const MAX_ORDER_HISTORY: usize = 64;
+
+/// One change to an order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderHistoryEvent {
+    Placed {
+        side: OrderSide,
+        order_type: OrderType,
+        /// Zero for market orders
+        price: Price,
+        /// For quote orders, the upper bound the budget bought at placement
+        quantity: Quantity,
+        time_in_force: TimeInForce,
+    },
+    /// Part of the order traded in trade `trade_id`
+    Filled {
+        trade_id: u64,
+        price: Price,
+        quantity: Quantity,
+        /// Quantity left unfilled after the trade
+        remaining: Quantity,
+        /// Whether the order was resting when it traded
+        maker: bool,
+    },
+    /// The owner shrank the order in place
+    Reduced {
+        reduce_by: Quantity,
+        remaining: Quantity,
+    },
+    /// The order left the book unfilled, by request, force or lapse of an immediate-or-cancel rest
+    Cancelled {
+        remaining: Quantity,
+    },
+}
+
+impl OrderHistoryEvent {
+    /// Placement of `order` as it enters matching
+    pub fn placed(order: &Order) -> Self {
+        OrderHistoryEvent::Placed {
+            side: order.side,
+            order_type: order.order_type,
+            price: order.price,
+            quantity: order.quantity,
+            time_in_force: order.time_in_force,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderHistoryEntry {
+    pub event: OrderHistoryEvent,
+    pub timestamp: Timestamp,
+}
+
+/// What happened to an order, oldest first, returned by `Query::GetOrderHistory`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderHistory {
+    pub entries: Vec<OrderHistoryEntry>,
+    /// Entries dropped after the placement to stay within `MAX_ORDER_HISTORY`
+    pub dropped: u64,
+}
+
+impl OrderHistory {
+    /// Appends `entry`, dropping the oldest entry after the placement once the history is full
+    pub fn push(&mut self, entry: OrderHistoryEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ORDER_HISTORY {
+            self.entries.remove(1);
+            self.dropped += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(trade_id: u64) -> OrderHistoryEntry {
+        OrderHistoryEntry {
+            event: OrderHistoryEvent::Filled { trade_id, price: 100, quantity: 1, remaining: 0, maker: true },
+            timestamp: Timestamp::from(trade_id),
+        }
+    }
+
+    #[test]
+    fn test_history_keeps_placement_when_truncated() {
+        let placed = OrderHistoryEntry {
+            event: OrderHistoryEvent::Placed {
+                side: OrderSide::Sell,
+                order_type: OrderType::Limit,
+                price: 100,
+                quantity: 1_000,
+                time_in_force: TimeInForce::GTC,
+            },
+            timestamp: Timestamp::from(0),
+        };
+        let mut history = OrderHistory::default();
+        history.push(placed.clone());
+        for trade_id in 0..MAX_ORDER_HISTORY as u64 + 2 {
+            history.push(fill(trade_id));
+        }
+        assert_eq!(history.entries.len(), MAX_ORDER_HISTORY);
+        assert_eq!(history.dropped, 3);
+        assert_eq!(history.entries[0], placed);
+        assert_eq!(history.entries[1], fill(3));
+        assert_eq!(history.entries.last(), Some(&fill(MAX_ORDER_HISTORY as u64 + 1)));
+    }
+}
@@ -21,9 +21,14 @@ Each order book contract manages:
 */
 
 use async_trait::async_trait;
+use axelarx_math::{self as math, MathError};
+use axelarx_settlement::{
+    MessageContext, MessageEnvelope, Operation as SettlementOperation, ParkedMessage, SettlementAbi,
+    SettlementRejectionCode, SettlementResponse,
+};
 use linera_base::{
     data_types::{Amount, Timestamp},
-    identifiers::{Account, ChainId},
+    identifiers::{Account, ApplicationId, ChainId},
     abi::{ContractAbi as BaseContractAbi, ServiceAbi as BaseServiceAbi, WithContractAbi, WithServiceAbi},
 };
 use linera_sdk::{
@@ -33,11 +38,36 @@ use linera_views::{
     map_view::MapView,
     queue_view::QueueView,
     register_view::RegisterView,
-    views::RootView,
+    views::{RootView, View},
 };
 use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, collections::BTreeMap};
 use thiserror::Error;
 
+mod forwarding;
+mod matching;
+mod history;
+mod mmp;
+mod portfolio;
+mod reference_price;
+mod rejection;
+mod requirement;
+mod sweep;
+mod trade_stats;
+
+pub use forwarding::{ForwardOutcome, ForwardedOrder, ForwardedOrderSpec, SiblingMarket};
+pub use history::{OrderHistory, OrderHistoryEntry, OrderHistoryEvent, MAX_ORDER_HISTORY};
+pub use matching::{match_taker, BookSnapshot, Fill, MatchOutcome, SnapshotLevel};
+pub use mmp::{MmpConfig, MmpTracker};
+pub use portfolio::{BalanceChangeReason, BalanceSnapshot, PendingBalanceDelta};
+pub use reference_price::{
+    IndexPrice, RecentTrades, ReferencePrice, ReferencePriceConfig, ReferencePriceSource, MAX_REFERENCE_WINDOW,
+};
+pub use rejection::{OrderRejection, RejectedOrder, RejectionCode, MAX_RECENT_REJECTIONS};
+pub use requirement::OpenOrderRequirement;
+pub use sweep::{StaleOrderPolicy, MAX_SWEEP_CANCELLATIONS, MAX_SWEEP_INSPECTIONS};
+pub use trade_stats::{hour_of, RollingTradeStats, TradeBucket, TradeWindow, STATS_WINDOW_HOURS};
+
 /// Unique identifier for orders
 pub type OrderId = u64;
 
@@ -47,6 +77,48 @@ pub type Price = u64;
 /// Quantity represented as a fixed-point number (scaled by 1e8)
 pub type Quantity = u64;
 
+/// Resting orders cancelled per ban, forced cancellation or MMP trigger; the rest via
+/// `CancelBannedOrders` / `CancelForcedOrders`, or by the account
+pub const MAX_BAN_CANCELLATIONS: usize = 50;
+
+/// Registered market makers; each is re-evaluated on every book change
+pub const MAX_MARKET_MAKERS: usize = 16;
+
+/// Most child orders a TWAP order can be split into
+pub const MAX_TWAP_SLICES: u32 = 100;
+
+/// TWAP slices released per `ProcessTwapOrders` call
+pub const MAX_TWAP_RELEASES: usize = 20;
+
+/// State schema this code reads and writes; markets created before versioning are at 0
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// Records transformed per `Migrate` call
+pub const MAX_MIGRATION_ITEMS: usize = 100;
+
+/// Orders ahead loaded by `GetQueuePosition`; past it the figures ahead are lower bounds
+pub const MAX_QUEUE_POSITION_WALK: usize = 100;
+
+/// Trades kept by id until the admin sets a retention; older ones are pruned as new ones are
+/// recorded, and this many back is as far as `GetTrades` reaches
+pub const TRADE_RETENTION: u64 = 100_000;
+
+/// Bounds on the retention the admin may set
+pub const MIN_TRADE_RETENTION: u64 = 1_000;
+pub const MAX_TRADE_RETENTION: u64 = 1_000_000;
+
+/// Every how many trades the time index records a checkpoint
+pub const TRADE_CHECKPOINT_INTERVAL: u64 = 100;
+
+/// Most trades returned per `GetTrades` / `GetTradesByTimeRange` page
+pub const MAX_TRADE_PAGE: usize = 1_000;
+
+/// Most price levels per side in a `GetTradingView` response
+pub const MAX_TRADING_VIEW_DEPTH: usize = 50;
+
+/// Most open orders in a `GetTradingView` response
+pub const MAX_TRADING_VIEW_ORDERS: usize = 100;
+
 /// Price level containing orders at a specific price
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PriceLevel {
@@ -54,6 +126,138 @@ pub struct PriceLevel {
     pub total_quantity: Quantity,
     /// Orders at this price level, sorted by time (FIFO)
     pub orders: Vec<OrderId>,
+    /// Number of ids in `orders`, checked against `MarketConfig::max_orders_per_level`
+    #[serde(default)]
+    pub order_count: u32,
+    /// Number of ids in `orders` by the account that placed them; accounts without any are absent
+    #[serde(default)]
+    pub account_orders: BTreeMap<Account, u32>,
+}
+
+impl PriceLevel {
+    /// Queues `order` behind the level's other orders with its remaining quantity
+    pub fn push_order(&mut self, order: &Order) {
+        self.orders.push(order.id);
+        self.total_quantity = self.total_quantity.saturating_add(order.remaining_quantity());
+        self.order_count = self.order_count.saturating_add(1);
+        *self.account_orders.entry(order.user).or_default() += 1;
+    }
+    
+    /// Takes the id at `position` out of the queue, counting it off `account` when the order is
+    /// known. The quantity it leaves with is for the caller to take off `total_quantity`.
+    pub fn remove_at(&mut self, position: usize, account: Option<Account>) {
+        self.orders.remove(position);
+        self.order_count = self.order_count.saturating_sub(1);
+        let Some(account) = account else {
+            return;
+        };
+        if let Some(count) = self.account_orders.get_mut(&account) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.account_orders.remove(&account);
+            }
+        }
+    }
+    
+    /// Takes `order` out of the queue if it is there
+    pub fn remove_order(&mut self, order: &Order) {
+        if let Some(position) = self.orders.iter().position(|id| *id == order.id) {
+            self.remove_at(position, Some(order.user));
+        }
+    }
+    
+    /// Orders `account` has queued at this level
+    pub fn account_order_count(&self, account: &Account) -> u32 {
+        self.account_orders.get(account).copied().unwrap_or(0)
+    }
+}
+
+/// Where an order sits in the FIFO of its price level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueuePosition {
+    Resting {
+        side: OrderSide,
+        price: Price,
+        /// Active orders ahead of it at the same price
+        orders_ahead: usize,
+        /// Their remaining quantity
+        quantity_ahead: Quantity,
+        /// Remaining quantity of the whole level, the order's own included
+        level_quantity: Quantity,
+        /// False when more than `MAX_QUEUE_POSITION_WALK` orders are ahead
+        exact: bool,
+    },
+    /// Unknown, finished, or not on the book yet, such as an untriggered stop
+    NotResting { status: Option<OrderStatus> },
+}
+
+/// Free and locked balance of one asset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetBalance {
+    pub asset: String,
+    pub available: Amount,
+    pub locked: Amount,
+    /// Fill proceeds awaiting settlement
+    pub unsettled: Amount,
+}
+
+/// Everything a trading screen shows for one account, read from a single state snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradingView {
+    /// Book sequence number the view was taken at
+    pub sequence: u64,
+    pub bids: Vec<(Price, Quantity)>,
+    pub asks: Vec<(Price, Quantity)>,
+    pub stats: MarketStats,
+    /// Oldest first, at most `MAX_TRADING_VIEW_ORDERS`
+    pub open_orders: Vec<Order>,
+    /// The account has more open orders than were returned
+    pub more_orders: bool,
+    /// Base then quote asset
+    pub balances: Vec<AssetBalance>,
+}
+
+/// What `PlaceOrder` would do with an order at the moment of a `SimulatePlaceOrder` query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacementSimulation {
+    /// Id the order would be given
+    pub order_id: OrderId,
+    pub status: OrderStatus,
+    pub filled_quantity: Quantity,
+    /// In the order they would execute
+    pub fills: Vec<SimulatedFill>,
+    /// Taker fees over all fills, in the asset the order receives or pays them in
+    pub fees: Amount,
+    /// Best prices once the order is placed
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+}
+
+/// A fill of a simulated order against a resting one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatedFill {
+    pub maker_order_id: OrderId,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub amounts: FillAmounts,
+}
+
+/// A page of the trade history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradePage {
+    Trades {
+        trades: Vec<Trade>,
+        /// Where the next page starts; None once the newest trade is included
+        next_id: Option<u64>,
+    },
+    /// Part of the range was pruned; older trades have to come from an archive
+    Pruned { first_retained_id: u64 },
+}
+
+/// Ids of the trades to prune once `newest_id` is recorded, keeping the last `retention`
+pub fn trades_to_prune(first_retained_id: u64, newest_id: u64, retention: u64) -> std::ops::Range<u64> {
+    let keep_from = (newest_id + 1).saturating_sub(retention);
+    first_retained_id..keep_from.max(first_retained_id)
 }
 
 /// Order side enumeration
@@ -63,6 +267,23 @@ pub enum OrderSide {
     Sell,
 }
 
+impl OrderSide {
+    pub fn opposite(self) -> OrderSide {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+    
+    /// Whether a limit on this side at `limit` trades against a resting order at `price`
+    pub fn crosses(self, limit: Price, price: Price) -> bool {
+        match self {
+            OrderSide::Buy => limit >= price,
+            OrderSide::Sell => limit <= price,
+        }
+    }
+}
+
 /// Order type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderType {
@@ -85,6 +306,38 @@ pub enum TimeInForce {
     PostOnly,
 }
 
+/// Trading phase of the market, set by the admin
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarketPhase {
+    /// Only limit orders that rest without trading; crossing orders are rejected as post-only
+    PreOpen,
+    /// Orders match as they arrive
+    #[default]
+    Continuous,
+    /// Limit orders rest without matching, crossed or not, until the book is cleared
+    Auction,
+    /// No new orders; cancels still work
+    Halted,
+}
+
+impl MarketPhase {
+    /// Checks that an order of this type may be placed; crossing in `PreOpen` is checked against the book.
+    pub fn check_order(self, order_type: OrderType, time_in_force: TimeInForce) -> Result<(), OrderBookError> {
+        match self {
+            MarketPhase::Continuous => Ok(()),
+            MarketPhase::Halted => Err(OrderBookError::MarketHalted),
+            MarketPhase::PreOpen | MarketPhase::Auction => {
+                if order_type == OrderType::Market || matches!(time_in_force, TimeInForce::IOC | TimeInForce::FOK) {
+                    return Err(OrderBookError::InvalidOrder {
+                        reason: format!("Only resting limit orders are accepted in {:?}", self),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Order status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderStatus {
@@ -135,6 +388,61 @@ impl Order {
     }
 }
 
+/// TWAP parent order status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TwapStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+/// Parent order released as `slice_count` IOC child orders evenly over `duration_seconds`.
+/// A slice's unfilled part rolls into the next one; whatever is left after the last is released.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwapOrder {
+    pub id: u64,
+    pub user: Account,
+    pub side: OrderSide,
+    /// Children are limit orders at this price, or market orders when unset (sells only)
+    pub limit_price: Option<Price>,
+    pub total_quantity: Quantity,
+    /// Filled across all child orders
+    pub filled_quantity: Quantity,
+    pub slice_count: u32,
+    pub slices_released: u32,
+    pub duration_seconds: u64,
+    pub started_at: Timestamp,
+    pub last_released_at: Option<Timestamp>,
+    /// Part of the up-front lock not yet spent by a child order
+    pub locked: Amount,
+    pub child_order_ids: Vec<OrderId>,
+    pub status: TwapStatus,
+}
+
+impl TwapOrder {
+    /// When slice `index` (from 0) becomes due
+    pub fn slice_due_at(&self, index: u32) -> Timestamp {
+        let offset = self.duration_seconds as u128 * 1_000_000 * index as u128 / self.slice_count as u128;
+        Timestamp::from(self.started_at.micros().saturating_add(u64::try_from(offset).unwrap_or(u64::MAX)))
+    }
+    
+    /// Cumulative quantity the schedule targets once slice `index` is released
+    pub fn target_after(&self, index: u32) -> Quantity {
+        (self.total_quantity as u128 * (index as u128 + 1) / self.slice_count as u128) as Quantity
+    }
+    
+    pub fn next_slice_at(&self) -> Option<Timestamp> {
+        (self.status == TwapStatus::Active && self.slices_released < self.slice_count)
+            .then(|| self.slice_due_at(self.slices_released))
+    }
+    
+    /// Whether the next slice can be released at `now`. At most one slice goes out per block, so
+    /// repeated keeper calls never release a slice early, and an overdue schedule catches up gradually.
+    pub fn is_due(&self, now: Timestamp) -> bool {
+        self.next_slice_at().map_or(false, |due_at| due_at <= now) && self.last_released_at != Some(now)
+    }
+}
+
 /// Trade execution result
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Trade {
@@ -147,6 +455,330 @@ pub struct Trade {
     pub maker: Account,
     pub taker: Account,
     pub maker_side: OrderSide,
+    /// Fees charged to each side, in the asset `fee_model` assigns to it
+    pub maker_fee: Amount,
+    pub taker_fee: Amount,
+    pub fee_model: FeeModel,
+}
+
+impl Trade {
+    /// Settlement legs net of fees: what the maker and the taker each deliver to the other, on
+    /// their home chains. Fees taken from proceeds stay with the order book, and fees paid on top
+    /// never leave it.
+    pub fn settlement_request(
+        &self,
+        config: &MarketConfig,
+        maker_chain: ChainId,
+        taker_chain: ChainId,
+    ) -> Result<Message, MathError> {
+        let base = math::fixed_to_amount(self.quantity as u128)?;
+        let quote = math::quote_credit(self.price, self.quantity)?;
+        let taker_side = self.maker_side.opposite();
+        let (maker_gives, taker_gives) = match self.maker_side {
+            OrderSide::Buy => (quote, base),
+            OrderSide::Sell => (base, quote),
+        };
+        let maker_amount = if self.fee_model.fee_asset(config, taker_side) == config.proceeds_asset(taker_side) {
+            math::checked_sub(maker_gives, self.taker_fee)?
+        } else {
+            maker_gives
+        };
+        let taker_amount = if self.fee_model.fee_asset(config, self.maker_side) == config.proceeds_asset(self.maker_side) {
+            math::checked_sub(taker_gives, self.maker_fee)?
+        } else {
+            taker_gives
+        };
+        Ok(Message::SettlementRequest {
+            trade_id: self.id,
+            maker: self.maker,
+            taker: self.taker,
+            maker_asset: config.payment_asset(self.maker_side).to_string(),
+            taker_asset: config.payment_asset(taker_side).to_string(),
+            maker_amount,
+            taker_amount,
+            maker_chain,
+            taker_chain,
+        })
+    }
+}
+
+/// Trading ban on a single account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountBan {
+    pub account: Account,
+    pub banned_by: Account,
+    pub banned_at: Timestamp,
+    /// None bans until explicitly lifted
+    pub expires_at: Option<Timestamp>,
+    pub reason: String,
+}
+
+impl AccountBan {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+}
+
+/// Quoting obligations agreed with a designated market maker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DmmAgreement {
+    pub account: Account,
+    /// Quotes count only within this distance of mid
+    pub max_spread_bps: u64,
+    /// Share of the epoch the maker must quote both sides
+    pub min_uptime_bps: u64,
+    /// Size required on each side within the band
+    pub min_size: Quantity,
+    pub registered_at: Timestamp,
+}
+
+/// A market maker's quoting record for one epoch. Sampled on book changes and time-weighted:
+/// the interval up to each sample is credited under the state seen at the previous one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DmmEpochStats {
+    /// Terms in force when the epoch's record started
+    pub terms: DmmAgreement,
+    pub samples: u64,
+    pub qualifying_samples: u64,
+    pub observed_micros: u64,
+    pub qualifying_micros: u64,
+    /// Size quoted within the band, times the time it was quoted
+    pub bid_size_micros: u128,
+    pub ask_size_micros: u128,
+    pub last_sampled_at: Timestamp,
+    pub last_qualifying: bool,
+    pub last_bid_size: Quantity,
+    pub last_ask_size: Quantity,
+}
+
+impl DmmEpochStats {
+    pub fn new(terms: DmmAgreement, started_at: Timestamp) -> Self {
+        DmmEpochStats {
+            terms,
+            samples: 0,
+            qualifying_samples: 0,
+            observed_micros: 0,
+            qualifying_micros: 0,
+            bid_size_micros: 0,
+            ask_size_micros: 0,
+            last_sampled_at: started_at,
+            last_qualifying: false,
+            last_bid_size: 0,
+            last_ask_size: 0,
+        }
+    }
+    
+    /// Fresh record for the next epoch, continuing from the current quoting state
+    pub fn carry_over(&self, terms: DmmAgreement, now: Timestamp) -> Self {
+        DmmEpochStats {
+            last_qualifying: self.last_qualifying,
+            last_bid_size: self.last_bid_size,
+            last_ask_size: self.last_ask_size,
+            ..DmmEpochStats::new(terms, now)
+        }
+    }
+    
+    /// Credits the time since the last sample, then records the current quotes.
+    pub fn record(&mut self, now: Timestamp, bid_size: Quantity, ask_size: Quantity) {
+        let elapsed = now.micros().saturating_sub(self.last_sampled_at.micros());
+        self.observed_micros = self.observed_micros.saturating_add(elapsed);
+        if self.last_qualifying {
+            self.qualifying_micros = self.qualifying_micros.saturating_add(elapsed);
+        }
+        self.bid_size_micros = self.bid_size_micros.saturating_add(self.last_bid_size as u128 * elapsed as u128);
+        self.ask_size_micros = self.ask_size_micros.saturating_add(self.last_ask_size as u128 * elapsed as u128);
+        
+        let qualifying = bid_size >= self.terms.min_size && ask_size >= self.terms.min_size;
+        self.samples += 1;
+        if qualifying {
+            self.qualifying_samples += 1;
+        }
+        self.last_sampled_at = now;
+        self.last_qualifying = qualifying;
+        self.last_bid_size = bid_size;
+        self.last_ask_size = ask_size;
+    }
+    
+    /// Time-weighted share of the epoch spent quoting both sides, in basis points
+    pub fn uptime_bps(&self) -> u64 {
+        if self.observed_micros == 0 {
+            return 0;
+        }
+        (self.qualifying_micros as u128 * 10_000 / self.observed_micros as u128) as u64
+    }
+}
+
+/// Bounds of a DMM epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DmmEpoch {
+    pub epoch: u64,
+    pub started_at: Timestamp,
+    /// None while the epoch is still open
+    pub closed_at: Option<Timestamp>,
+}
+
+/// Per-maker line of an epoch report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DmmReportEntry {
+    pub stats: DmmEpochStats,
+    pub uptime_bps: u64,
+    pub meets_uptime: bool,
+}
+
+/// Audit log entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderBookEvent {
+    AccountBanned {
+        account: Account,
+        banned_by: Account,
+        expires_at: Option<Timestamp>,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    AccountUnbanned {
+        account: Account,
+        unbanned_by: Account,
+        timestamp: Timestamp,
+    },
+    /// One batch of a banned account's resting orders was cancelled
+    BannedOrdersCancelled {
+        account: Account,
+        order_ids: Vec<OrderId>,
+        remaining: usize,
+        timestamp: Timestamp,
+    },
+    /// One batch of an account's resting orders was cancelled at the settlement contract's request
+    OrdersForceCancelled {
+        account: Account,
+        order_ids: Vec<OrderId>,
+        remaining: usize,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    /// A DMM epoch was closed and its numbers frozen
+    DmmEpochClosed {
+        epoch: u64,
+        closed_by: Account,
+        timestamp: Timestamp,
+    },
+    /// An account let a delegate trade for it, or changed the delegate's scope
+    TradingPermissionGranted {
+        principal: Account,
+        delegate: Account,
+        permission: TradingPermission,
+    },
+    /// A delegate lost its permission; orders it already placed stay on the book
+    TradingPermissionRevoked {
+        principal: Account,
+        delegate: Account,
+        timestamp: Timestamp,
+    },
+    /// The admin moved the market to another phase; `clearing` is the price and volume of the
+    /// auction that uncrossed the book on the way
+    MarketPhaseChanged {
+        from: MarketPhase,
+        to: MarketPhase,
+        clearing: Option<(Price, Quantity)>,
+        changed_by: Account,
+        timestamp: Timestamp,
+    },
+    /// One chunk of a schema migration ran
+    MigrationStep {
+        from_version: u32,
+        /// Records migrated so far; for schema 2, the lowest price whose levels are not counted yet
+        cursor: u64,
+        completed: bool,
+        migrated_by: Account,
+        timestamp: Timestamp,
+    },
+    /// A placement by an account recording its rejections was refused
+    OrderRejected {
+        account: Account,
+        rejection: OrderRejection,
+    },
+    ReferencePriceConfigured {
+        config: ReferencePriceConfig,
+        changed_by: Account,
+        timestamp: Timestamp,
+    },
+    StaleOrderPolicyConfigured {
+        policy: Option<StaleOrderPolicy>,
+        changed_by: Account,
+        timestamp: Timestamp,
+    },
+    TradeRetentionConfigured {
+        trades: u64,
+        changed_by: Account,
+        timestamp: Timestamp,
+    },
+    PortfolioAggregatorConfigured {
+        chain: Option<ChainId>,
+        changed_by: Account,
+        timestamp: Timestamp,
+    },
+    /// The aggregator received `account`'s delta `received` from `market` while expecting
+    /// `expected`; the deltas in between are missing until reconciled with a snapshot
+    BalanceDeltaGap {
+        market: ChainId,
+        account: Account,
+        expected: u64,
+        received: u64,
+        timestamp: Timestamp,
+    },
+    /// A sweep cancelled `user`'s order, resting `price` away from `reference_price`
+    StaleOrderSwept {
+        order_id: OrderId,
+        user: Account,
+        price: Price,
+        remaining: Quantity,
+        reference_price: Price,
+        timestamp: Timestamp,
+    },
+    IndexPriceUpdated {
+        index: IndexPrice,
+        /// Trade-derived price the update was checked against, if there was one
+        trade_price: Option<Price>,
+    },
+    /// A forwarded order came from a chain that is not a registered sibling
+    ForwardedOrderDropped {
+        origin: ChainId,
+        user: Account,
+        forward_id: u64,
+        timestamp: Timestamp,
+    },
+    /// A message this build cannot read was parked instead of applied
+    MessageParked {
+        parked_id: u64,
+        version: u8,
+        origin: Option<ChainId>,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    /// An admin replayed or discarded a parked message
+    ParkedMessageResolved {
+        parked_id: u64,
+        replayed: bool,
+        resolved_by: Account,
+        timestamp: Timestamp,
+    },
+    /// Fills against an account's resting orders reached its protection caps; one batch of its
+    /// orders was cancelled and placements are refused until `cooldown_until`
+    MmpTriggered {
+        account: Account,
+        /// Counters of the window that triggered
+        fills: u32,
+        delta: i128,
+        order_ids: Vec<OrderId>,
+        remaining: usize,
+        cooldown_until: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// The settlement contract rejected a trade's settlement; its unsettled proceeds were drained
+    TradeSettlementRejected {
+        trade_id: u64,
+        reason_code: SettlementRejectionCode,
+        timestamp: Timestamp,
+    },
 }
 
 /// Market statistics
@@ -160,6 +792,8 @@ pub struct MarketStats {
     pub low_24h: Price,
     pub price_change_24h: i64,
     pub total_trades: u64,
+    #[serde(default)]
+    pub phase: MarketPhase,
 }
 
 /// User position tracking
@@ -211,6 +845,59 @@ pub struct PortfolioMetrics {
     pub total_fees_paid: Quantity,
 }
 
+/// Settlement progress of a trade
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeSettlement {
+    /// A leg is below its settlement minimum, so the fill on this chain is final
+    Internal,
+    /// Handed to the settlement contract
+    Requested,
+    Settled,
+    Failed { reason: String },
+}
+
+/// Proceeds of one side of a trade held in `unsettled_balances` until the trade settles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsettledProceeds {
+    pub account: Account,
+    pub asset: String,
+    pub amount: Amount,
+}
+
+/// Record created by an operation submitted with a client request id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationReceipt {
+    pub client_request_id: u64,
+    /// Id of the created order
+    pub id: u64,
+    pub created_at: Timestamp,
+}
+
+/// Permission an account grants a delegate to place and cancel orders on its behalf
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradingPermission {
+    /// Largest order quantity the delegate may place; None allows up to the market maximum
+    pub max_order_size: Option<Quantity>,
+    /// Only side the delegate may place; None allows both
+    pub side: Option<OrderSide>,
+    pub granted_at: Timestamp,
+}
+
+impl TradingPermission {
+    /// Checks an order the delegate places against the permission's scope
+    pub fn check(&self, side: OrderSide, quantity: Quantity) -> Result<(), OrderBookError> {
+        if self.side.is_some_and(|allowed| allowed != side) {
+            return Err(OrderBookError::OutsideTradingPermission { reason: format!("{side:?} orders not permitted") });
+        }
+        if let Some(max_order_size) = self.max_order_size.filter(|max_order_size| quantity > *max_order_size) {
+            return Err(OrderBookError::OutsideTradingPermission {
+                reason: format!("Quantity {quantity} above the permitted {max_order_size}"),
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Contract operations
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Operation {
@@ -222,10 +909,56 @@ pub enum Operation {
         quantity: Quantity,
         time_in_force: TimeInForce,
         expires_at: Option<Timestamp>,
+        /// Market orders only: reject with `NoLiquidity` unless the book fills the whole quantity.
+        /// By default whatever the book holds is filled and the rest cancelled.
+        #[serde(default)]
+        require_full_fill: bool,
+        /// While taking liquidity, pass over resting orders that would fill less than this, unless
+        /// the fill completes the order. Passed-over orders keep their place in the queue.
+        #[serde(default)]
+        min_fill_quantity: Option<Quantity>,
+        /// Caller-chosen id under which a receipt with the order id is stored
+        #[serde(default)]
+        client_request_id: Option<u64>,
+        /// Place the order for this account, under a `TradingPermission` it granted the caller
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+        /// Keep the order out of stale-order sweeps
+        #[serde(default)]
+        sweep_exempt: bool,
     },
     
-    /// Cancel an existing order
-    CancelOrder { order_id: OrderId },
+    /// Market buy spending at most `quote_amount`, fees included. The amount is locked up front
+    /// and whatever the sweep leaves unspent is released at the end.
+    PlaceQuoteOrder {
+        quote_amount: Amount,
+        /// Stop before asks above this price
+        #[serde(default)]
+        max_price: Option<Price>,
+        #[serde(default)]
+        client_request_id: Option<u64>,
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+    },
+    
+    /// Cancel an existing order, the caller's own or, with `on_behalf_of`, one of a principal's
+    CancelOrder {
+        order_id: OrderId,
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+    },
+    
+    /// Cut an order's remaining quantity by `reduce_by`, keeping its place in the queue. When
+    /// less than the minimum order size would be left, the order is cancelled if
+    /// `cancel_if_below_minimum` and the reduction refused otherwise.
+    ReduceOrder {
+        order_id: OrderId,
+        reduce_by: Quantity,
+        #[serde(default)]
+        cancel_if_below_minimum: bool,
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+    },
     
     /// Modify an existing order (cancel and replace)
     ModifyOrder {
@@ -245,10 +978,174 @@ pub enum Operation {
         min_order_size: Option<Quantity>,
         max_order_size: Option<Quantity>,
         tick_size: Option<Price>,
+        /// Only while no bids or TWAP orders hold locks, as bids lock fees under the quote model
+        fee_model: Option<FeeModel>,
+        /// Applies to orders placed from now on; levels already past it keep their orders
+        #[serde(default)]
+        max_orders_per_level: Option<u32>,
+        #[serde(default)]
+        max_account_orders_per_level: Option<u32>,
+        #[serde(default)]
+        allow_unsettled_trading: Option<bool>,
+    },
+    
+    /// Ban an account from trading and cancel its resting orders (admin only)
+    BanAccount {
+        account: Account,
+        expires_at: Option<Timestamp>,
+        reason: String,
+    },
+    
+    /// Lift a trading ban (admin only)
+    UnbanAccount { account: Account },
+    
+    /// Cancel the next batch of a banned account's resting orders
+    CancelBannedOrders { account: Account },
+    
+    /// Cancel the next batch of orders of an account the settlement contract force-cancelled
+    CancelForcedOrders { account: Account },
+    
+    /// Settlement application, and its chain, allowed to force-cancel orders (admin only)
+    SetSettlementApplication { application_id: ApplicationId, chain_id: ChainId },
+    
+    /// Move the market to another trading phase (admin only). Opening a crossed book for continuous
+    /// trading needs `clear_crossed_book`, which uncrosses it at a single auction price first.
+    SetMarketPhase {
+        phase: MarketPhase,
+        #[serde(default)]
+        clear_crossed_book: bool,
+    },
+    
+    /// Hand a trade to the settlement application with a direct call, which relays it when the
+    /// application settles on another chain; either party to the trade may ask
+    RequestTradeSettlement { trade_id: u64, timeout_seconds: u64 },
+    
+    /// Fail a trade whose settlement chain rejected its request, once the rejection has reached
+    /// the settlement application on this chain; anyone may ask
+    SyncTradeSettlement { trade_id: u64 },
+    
+    /// Register or update a designated market maker's quoting obligations (admin only)
+    RegisterMarketMaker {
+        account: Account,
+        max_spread_bps: u64,
+        min_uptime_bps: u64,
+        min_size: Quantity,
+    },
+    
+    /// Drop a designated market maker; its record for the current epoch is kept (admin only)
+    RemoveMarketMaker { account: Account },
+    
+    /// Freeze the current DMM epoch's statistics and start the next one (admin only)
+    CloseDmmEpoch,
+    
+    /// Split a parent order into `slice_count` child orders released evenly over `duration_seconds`.
+    /// The whole parent is locked up front; the first slice is released immediately.
+    PlaceTwapOrder {
+        side: OrderSide,
+        total_quantity: Quantity,
+        duration_seconds: u64,
+        slice_count: u32,
+        /// None releases market orders, which only sells can lock for
+        limit_price: Option<Price>,
+    },
+    
+    /// Cancel a TWAP order's unreleased slices and unlock their funds (owner only)
+    CancelTwapOrder { twap_id: u64 },
+    
+    /// Release every TWAP slice that is due (keeper)
+    ProcessTwapOrders,
+    
+    /// Mirror the settlement contract's minimum leg for `asset`; zero removes it (admin only)
+    SetSettlementMinimum { asset: String, minimum: Amount },
+    
+    /// Mirror the settlement contract's maximum leg for `asset`; zero removes it (admin only)
+    SetSettlementMaximum { asset: String, maximum: Amount },
+    
+    /// Mirror the settlement contract's exemption of a counterparty pair from the maximums (admin only)
+    SetUncappedSettlementPair { first: Account, second: Account, uncapped: bool },
+    
+    /// Let `delegate` place and cancel orders for the caller, optionally limited in size and side.
+    /// Granting again replaces the scope.
+    GrantTradingPermission {
+        delegate: Account,
+        max_order_size: Option<Quantity>,
+        side: Option<OrderSide>,
+    },
+    
+    /// Stop `delegate` placing orders for the caller; its orders already placed stay on the book
+    RevokeTradingPermission { delegate: Account },
+    
+    /// Register the chain the caller escrows on when its trades settle
+    SetHomeChain { chain_id: ChainId },
+    
+    /// Record the caller's refused placements and let the operation succeed, rather than failing
+    /// it without a trace. Nothing of a refused placement is applied either way.
+    RecordRejections { enabled: bool },
+    
+    /// Set or clear the caller's market maker protection. Counters restart; a running cooldown
+    /// is kept unless the protection is cleared.
+    SetMmpConfig { config: Option<MmpConfig> },
+    
+    /// Lift the caller's protection cooldown early and restart its counters
+    ResetMmp,
+    
+    /// Choose where the reference price comes from (admin only)
+    SetReferencePrice { config: ReferencePriceConfig },
+    
+    /// Account allowed to publish index prices; None leaves it to the admin (admin only)
+    SetPriceOracle { oracle: Option<Account> },
+    
+    /// Publish the index price (price oracle or admin). Prices too far from the trade-derived price
+    /// are refused unless the admin flags an emergency.
+    UpdateIndexPrice {
+        price: Price,
+        #[serde(default)]
+        emergency: bool,
     },
+    
+    /// Set or clear the policy under which `SweepStaleOrders` cancels old, distant orders (admin only)
+    SetStaleOrderPolicy { policy: Option<StaleOrderPolicy> },
+    
+    /// Cancel up to `MAX_SWEEP_CANCELLATIONS` stale orders; callable by anyone
+    SweepStaleOrders,
+    
+    /// Accept forwarded orders from `chain_id`, a sibling market chain trading the given pair, and
+    /// let users forward orders to it; registering again replaces the pair (admin only)
+    RegisterSiblingMarket { chain_id: ChainId, base_asset: String, quote_asset: String },
+    
+    /// Stop exchanging forwarded orders with a sibling market chain (admin only)
+    RemoveSiblingMarket { chain_id: ChainId },
+    
+    /// Place an order on a sibling market chain under the caller's account. The outcome arrives
+    /// later as an acknowledgment; see `Query::GetForwardedOrders`.
+    ForwardOrder { market_chain: ChainId, spec: ForwardedOrderSpec },
+    
+    /// Hand the admin role to another account (admin only)
+    TransferAdmin { new_admin: Account },
+    
+    /// Make `admin` the admin of this application on `chain_id`; chains other than the creator
+    /// chain have none until one is appointed from it (admin only)
+    AppointChainAdmin { chain_id: ChainId, admin: Account },
+    
+    /// Keep the last `trades` trades by id, between `MIN_TRADE_RETENTION` and
+    /// `MAX_TRADE_RETENTION`; a smaller retention is pruned down to two trades per new trade (admin only)
+    SetTradeRetention { trades: u64 },
+    
+    /// Send the net balance change of every account after each block to `chain`; None stops the
+    /// notifications (admin only)
+    SetPortfolioAggregator { chain: Option<ChainId> },
+    
+    /// Run the next chunk of the pending schema migration; trading is refused until it completes (admin only)
+    Migrate,
+    
+    /// Apply a parked message as it arrived, once an upgrade can read it (admin only)
+    ReplayParkedMessage { parked_id: u64 },
+    
+    /// Drop a parked message unapplied (admin only)
+    DiscardParkedMessage { parked_id: u64 },
 }
 
-/// Cross-chain messages for settlement
+/// Cross-chain messages for settlement, sent sealed in a `MessageEnvelope`
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Message {
     /// Request settlement on another chain
@@ -260,6 +1157,9 @@ pub enum Message {
         taker_asset: String,
         maker_amount: Amount,
         taker_amount: Amount,
+        /// Home chains the parties escrow on
+        maker_chain: ChainId,
+        taker_chain: ChainId,
     },
     
     /// Confirm settlement completion
@@ -280,6 +1180,43 @@ pub enum Message {
         best_ask: Price,
         last_price: Price,
     },
+    
+    /// Cancel every open order of `account`; only accepted from the configured settlement application
+    ForceCancelOrders {
+        account: Account,
+        reason: String,
+    },
+    
+    /// Order forwarded from a sibling market chain, placed under `user`; sent with the user's
+    /// authentication and only accepted from registered siblings
+    ForwardOrder {
+        forward_id: u64,
+        user: Account,
+        /// Pair the sender registered this market for
+        base_asset: String,
+        quote_asset: String,
+        spec: ForwardedOrderSpec,
+    },
+    
+    /// What the market chain did with a forwarded order
+    ForwardedOrderAck {
+        forward_id: u64,
+        outcome: ForwardOutcome,
+    },
+    
+    /// Net change of `account`'s `asset` balance over one block, sent to the portfolio
+    /// aggregator. `delta` is in attos; `sequence` counts the account's deltas from 1, so a gap
+    /// means a missed message and calls for a `Query::BalanceSnapshot`.
+    BalanceDelta {
+        account: Account,
+        asset: String,
+        delta: i128,
+        reason: BalanceChangeReason,
+        sequence: u64,
+    },
+    
+    /// Admin appointed by `AppointChainAdmin`; only accepted from the creator chain
+    AdminAppointed { admin: Account },
 }
 
 /// Contract error types
@@ -291,6 +1228,9 @@ pub enum OrderBookError {
     #[error("Insufficient balance: required {required}, available {available}")]
     InsufficientBalance { required: Amount, available: Amount },
     
+    #[error("Withdrawal of {asset} blocked by {blocking_orders} open orders: at most {max_withdrawable} can leave")]
+    WithdrawalBlockedByOpenOrders { asset: String, blocking_orders: u32, max_withdrawable: Amount },
+    
     #[error("Invalid order parameters: {reason}")]
     InvalidOrder { reason: String },
     
@@ -303,18 +1243,146 @@ pub enum OrderBookError {
     #[error("Market is closed")]
     MarketClosed,
     
+    #[error("Market is halted")]
+    MarketHalted,
+    
+    #[error("Book is crossed; open it with clear_crossed_book")]
+    BookCrossed,
+    
     #[error("Order size below minimum: {size}, minimum: {minimum}")]
     BelowMinimumSize { size: Quantity, minimum: Quantity },
     
+    #[error("Cannot reduce by {reduce_by}: only {remaining} remaining")]
+    ReductionExceedsRemaining { reduce_by: Quantity, remaining: Quantity },
+    
     #[error("Order size above maximum: {size}, maximum: {maximum}")]
     AboveMaximumSize { size: Quantity, maximum: Quantity },
     
     #[error("Price not aligned to tick size")]
     InvalidTickSize,
     
-    #[error("View error")]
-    ViewError,
-}
+    #[error("Unauthorized: admin only")]
+    NotAdmin,
+    
+    #[error("Account is banned from trading until {expires_at:?}")]
+    AccountBanned { expires_at: Option<Timestamp> },
+    
+    #[error("Account is not banned")]
+    AccountNotBanned,
+    
+    #[error("No forced cancellation pending for the account")]
+    NoForcedCancellation,
+    
+    #[error("Market maker not registered")]
+    MarketMakerNotFound,
+    
+    #[error("Too many market makers: maximum {maximum}")]
+    TooManyMarketMakers { maximum: usize },
+    
+    #[error("Invalid market maker terms: {reason}")]
+    InvalidMarketMakerTerms { reason: String },
+    
+    #[error("Not enough liquidity: requested {requested}, fillable {available}")]
+    NoLiquidity { requested: Quantity, available: Quantity },
+    
+    #[error("Client request id already used: {client_request_id}")]
+    DuplicateClientRequest { client_request_id: u64 },
+    
+    #[error("TWAP order not found: {twap_id}")]
+    TwapOrderNotFound { twap_id: u64 },
+    
+    #[error("TWAP order is not active: {status:?}")]
+    TwapOrderNotActive { status: TwapStatus },
+    
+    #[error("Schema is already at version {version}")]
+    NoMigrationPending { version: u32 },
+    
+    #[error("Trading is paused while a schema migration is in progress")]
+    MigrationInProgress,
+    
+    #[error("Fee model can only change while no bids or TWAP orders are open")]
+    FeeModelLocked,
+    
+    #[error("Unauthorized: no trading permission from {principal:?}")]
+    NoTradingPermission { principal: Account },
+    
+    #[error("Order outside the trading permission: {reason}")]
+    OutsideTradingPermission { reason: String },
+    
+    #[error("Invalid trading permission: {reason}")]
+    InvalidTradingPermission { reason: String },
+    
+    #[error("Trade not found: {trade_id}")]
+    TradeNotFound { trade_id: u64 },
+    
+    #[error("Trade cannot be settled: {reason}")]
+    SettlementUnavailable { reason: String },
+    
+    #[error("Price level {price} is full: maximum {maximum} orders")]
+    PriceLevelFull { price: Price, maximum: u32 },
+    
+    #[error("Too many orders of the account at price {price}: maximum {maximum}")]
+    AccountLevelOrderLimit { price: Price, maximum: u32 },
+    
+    #[error("Unauthorized: price oracle or admin only")]
+    NotPriceOracle,
+    
+    #[error("Invalid reference price: {reason}")]
+    InvalidReferencePrice { reason: String },
+    
+    #[error("Index price {price} deviates from trade price {trade_price} by more than {max_deviation_bps} bps")]
+    IndexPriceDeviation { price: Price, trade_price: Price, max_deviation_bps: u64 },
+    
+    #[error("Market does not trade {base_asset}/{quote_asset}")]
+    MarketPairMismatch { base_asset: String, quote_asset: String },
+    
+    #[error("Chain {chain_id} is not a registered sibling market")]
+    UnknownSiblingMarket { chain_id: ChainId },
+    
+    #[error("Invalid stale order policy: {reason}")]
+    InvalidStaleOrderPolicy { reason: String },
+    
+    #[error("Parked message not found: {parked_id}")]
+    ParkedMessageNotFound { parked_id: u64 },
+    
+    #[error("Parked message {parked_id} is still unreadable: {reason}")]
+    ParkedMessageUnreadable { parked_id: u64, reason: String },
+    
+    #[error("Market maker protection triggered; placements refused until {until:?}")]
+    MmpCooldown { until: Timestamp },
+    
+    #[error("Invalid market maker protection: {reason}")]
+    InvalidMmpConfig { reason: String },
+    
+    #[error("Trade retention must be between {minimum} and {maximum} trades")]
+    InvalidTradeRetention { minimum: u64, maximum: u64 },
+    
+    #[error("Math error: {0}")]
+    Math(#[from] MathError),
+    
+    #[error("View error")]
+    ViewError,
+}
+
+/// Asset each side of a fill pays its trading fee in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeeModel {
+    /// Taken from what each side receives: base for the buyer, quote for the seller
+    #[default]
+    FeeInReceivedAsset,
+    /// Both sides pay in quote; the buyer pays on top of the price, so bids lock the fee too
+    FeeInQuoteAsset,
+}
+
+impl FeeModel {
+    /// Asset an order on `side` pays its fee in under this model
+    pub fn fee_asset(self, config: &MarketConfig, side: OrderSide) -> &str {
+        match self {
+            FeeModel::FeeInReceivedAsset => config.proceeds_asset(side),
+            FeeModel::FeeInQuoteAsset => &config.quote_asset,
+        }
+    }
+}
 
 /// Market configuration
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -326,7 +1394,38 @@ pub struct MarketConfig {
     pub tick_size: Price,
     pub maker_fee_bps: u64,  // Basis points (1/10000)
     pub taker_fee_bps: u64,
+    pub fee_model: FeeModel,
     pub is_active: bool,
+    /// Most orders resting at one price; 0 for no limit
+    #[serde(default)]
+    pub max_orders_per_level: u32,
+    /// Most orders one account may rest at one price; 0 for no limit
+    #[serde(default)]
+    pub max_account_orders_per_level: u32,
+    /// New orders may spend fill proceeds still awaiting settlement; withdrawals never can.
+    /// Proceeds an order locked return to the free balance if it is cancelled.
+    #[serde(default)]
+    pub allow_unsettled_trading: bool,
+}
+
+impl MarketConfig {
+    /// Asset an order on `side` pays with: quote for bids, base for asks
+    pub fn payment_asset(&self, side: OrderSide) -> &str {
+        match side {
+            OrderSide::Buy => &self.quote_asset,
+            OrderSide::Sell => &self.base_asset,
+        }
+    }
+    
+    /// Asset an order on `side` receives
+    pub fn proceeds_asset(&self, side: OrderSide) -> &str {
+        self.payment_asset(side.opposite())
+    }
+    
+    /// Asset an order on `side` pays its trading fee in
+    pub fn fee_asset(&self, side: OrderSide) -> &str {
+        self.fee_model.fee_asset(self, side)
+    }
 }
 
 impl Default for MarketConfig {
@@ -339,7 +1438,11 @@ impl Default for MarketConfig {
             tick_size: 1,                // Minimum price increment
             maker_fee_bps: 10,           // 0.1%
             taker_fee_bps: 20,           // 0.2%
+            fee_model: FeeModel::FeeInReceivedAsset,
             is_active: true,
+            max_orders_per_level: 0,
+            max_account_orders_per_level: 0,
+            allow_unsettled_trading: false,
         }
     }
 }
@@ -377,9 +1480,36 @@ pub struct OrderBookState<C> {
     /// Locked balances (in open orders): (account, asset) -> amount
     pub locked_balances: MapView<C, (Account, String), Amount>,
     
-    /// Trade history (recent trades)
+    /// Remaining cost of each account's active limit orders: (account, asset) -> requirement
+    pub open_order_requirements: MapView<C, (Account, String), OpenOrderRequirement>,
+    
+    /// Fill proceeds awaiting their trade's settlement: (account, asset) -> amount
+    pub unsettled_balances: MapView<C, (Account, String), Amount>,
+    
+    /// Proceeds each trade holds in `unsettled_balances` until its settlement resolves
+    pub unsettled_trades: MapView<C, u64, Vec<UnsettledProceeds>>,
+    
+    /// Trade history before schema 2; `Migrate` moves it into `trades_by_id`
     pub trades: QueueView<C, Trade>,
     
+    /// Retained trade history by trade id (schema 2)
+    pub trades_by_id: MapView<C, u64, Trade>,
+    
+    /// Oldest trade id not yet pruned
+    pub first_retained_trade_id: RegisterView<C, u64>,
+    
+    /// Trades kept by id; None for `TRADE_RETENTION`
+    pub trade_retention: RegisterView<C, Option<u64>>,
+    
+    /// Hourly trade buckets behind the 24h market stats (schema 4)
+    pub trade_stats: RegisterView<C, RollingTradeStats>,
+    
+    /// Timestamps of every `TRADE_CHECKPOINT_INTERVAL`th trade, by trade id
+    pub trade_checkpoints: MapView<C, u64, Timestamp>,
+    
+    /// Trading fees collected per asset
+    pub collected_fees: MapView<C, String, Amount>,
+    
     /// Market statistics
     pub market_stats: RegisterView<C, MarketStats>,
     
@@ -397,6 +1527,151 @@ pub struct OrderBookState<C> {
     
     /// Portfolio metrics per user
     pub portfolio_metrics: MapView<C, Account, PortfolioMetrics>,
+    
+    /// Market admin, set at instantiation; privileged operations are refused while unset
+    pub admin: RegisterView<C, Option<Account>>,
+    
+    /// Accounts banned from trading
+    pub banned_accounts: MapView<C, Account, AccountBan>,
+    
+    /// Audit log
+    pub events: QueueView<C, OrderBookEvent>,
+    
+    /// Designated market makers
+    pub dmm_agreements: MapView<C, Account, DmmAgreement>,
+    
+    /// Current DMM epoch number
+    pub dmm_epoch: RegisterView<C, u64>,
+    
+    /// Start of the current DMM epoch
+    pub dmm_epoch_started_at: RegisterView<C, Timestamp>,
+    
+    /// Closed DMM epochs
+    pub dmm_epochs: MapView<C, u64, DmmEpoch>,
+    
+    /// Quoting records: (epoch, maker) -> stats
+    pub dmm_stats: MapView<C, (u64, Account), DmmEpochStats>,
+    
+    /// Receipts of operations submitted with a client request id: (caller, client id) -> receipt
+    pub receipts: MapView<C, (Account, u64), OperationReceipt>,
+    
+    /// TWAP parent orders
+    pub twap_orders: MapView<C, u64, TwapOrder>,
+    
+    /// TWAP orders with slices left to release
+    pub active_twap_orders: MapView<C, u64, ()>,
+    
+    /// Next TWAP order ID
+    pub next_twap_id: RegisterView<C, u64>,
+    
+    /// Settlement contract minimums per asset; trades with a smaller leg settle on this chain
+    pub settlement_minimums: MapView<C, String, Amount>,
+    
+    /// Settlement contract maximums per asset; trades with a larger leg fail settlement
+    pub settlement_maximums: MapView<C, String, Amount>,
+    
+    /// Counterparty pairs the settlement contract exempts from its maximums
+    pub uncapped_settlement_pairs: MapView<C, (Account, Account), ()>,
+    
+    /// Settlement progress by trade id
+    pub trade_settlements: MapView<C, u64, TradeSettlement>,
+    
+    /// Settlement application and chain trusted with `ForceCancelOrders`
+    pub settlement_application: RegisterView<C, Option<(ApplicationId, ChainId)>>,
+    
+    /// Settlement id the settlement application returned for a trade
+    pub trade_settlement_ids: MapView<C, u64, u64>,
+    
+    /// Accounts with force-cancelled orders still to cancel -> reason
+    pub forced_cancellations: MapView<C, Account, String>,
+    
+    /// Version of the stored state layout
+    pub schema_version: RegisterView<C, u32>,
+    
+    /// Records already migrated by the migration in progress, if any
+    pub migration_cursor: RegisterView<C, Option<u64>>,
+    
+    /// Client request id each order was placed with (schema 1)
+    pub client_order_ids: MapView<C, OrderId, u64>,
+    
+    /// Trading permissions: (principal, delegate) -> scope
+    pub trading_permissions: MapView<C, (Account, Account), TradingPermission>,
+    
+    /// Delegate that placed each order on its principal's behalf
+    pub order_delegates: MapView<C, OrderId, Account>,
+    
+    /// Bumped by every operation that may change the book
+    pub book_sequence: RegisterView<C, u64>,
+    
+    /// Current trading phase
+    pub market_phase: RegisterView<C, MarketPhase>,
+    
+    /// Chain each account escrows on when its trades settle; this chain when unregistered
+    pub home_chains: MapView<C, Account, ChainId>,
+    
+    /// Accounts whose refused placements are recorded instead of failing
+    pub rejection_recording: MapView<C, Account, ()>,
+    
+    /// Latest refused placements per submitting account, oldest first
+    pub recent_rejections: MapView<C, Account, Vec<OrderRejection>>,
+    
+    /// Where the reference price comes from
+    pub reference_price_config: RegisterView<C, ReferencePriceConfig>,
+    
+    /// Latest trades, enough for the configured window
+    pub recent_trades: RegisterView<C, RecentTrades>,
+    
+    /// Account publishing index prices besides the admin
+    pub price_oracle: RegisterView<C, Option<Account>>,
+    
+    /// Latest index price
+    pub index_price: RegisterView<C, Option<IndexPrice>>,
+    
+    /// Sibling market chains orders are forwarded to and accepted from
+    pub sibling_markets: MapView<C, ChainId, SiblingMarket>,
+    
+    /// Orders forwarded from this chain by id
+    pub forwarded_orders: MapView<C, u64, ForwardedOrder>,
+    
+    /// Ids of the orders each user forwarded, oldest first
+    pub user_forwarded_orders: MapView<C, Account, Vec<u64>>,
+    
+    /// Next forwarded order ID
+    pub next_forward_id: RegisterView<C, u64>,
+    
+    /// Amendment history of every order, bounded by `MAX_ORDER_HISTORY`
+    pub order_history: MapView<C, OrderId, OrderHistory>,
+    
+    /// When resting orders may be swept; None sweeps nothing
+    pub stale_order_policy: RegisterView<C, Option<StaleOrderPolicy>>,
+    
+    /// Orders placed with `sweep_exempt`
+    pub sweep_exempt_orders: MapView<C, OrderId, ()>,
+    
+    /// Messages this build could not read, by parked id
+    pub parked_messages: MapView<C, u64, ParkedMessage>,
+    
+    /// Next parked message ID
+    pub next_parked_message_id: RegisterView<C, u64>,
+    
+    /// Market maker protection of the accounts that set one, with its counters
+    pub mmp_trackers: MapView<C, Account, MmpTracker>,
+    
+    /// Chain balance deltas are sent to; None sends nothing
+    pub portfolio_aggregator: RegisterView<C, Option<ChainId>>,
+    
+    /// Balance changes of the current block, sent and cleared once it has executed
+    pub pending_balance_deltas: MapView<C, (Account, String), PendingBalanceDelta>,
+    
+    /// Sequence of the last balance delta sent for each account
+    pub balance_sequences: MapView<C, Account, u64>,
+    
+    /// On the aggregator chain: net of the balance deltas received per account and asset, over
+    /// every market chain; each market's `Query::BalanceSnapshot` gives the balance it started from
+    pub portfolio_balances: MapView<C, (Account, String), i128>,
+    
+    /// On the aggregator chain: sequence of the last balance delta applied per market chain and account
+    pub portfolio_sequences: MapView<C, (ChainId, Account), u64>,
 }
 
 /// Contract ABI definition  
@@ -406,11 +1681,19 @@ pub struct OrderBookAbi;
 impl BaseContractAbi for OrderBookAbi {
     type Operation = Operation;
     type Response = Result<(), OrderBookError>;
-    type Message = Message;
+    type Message = MessageEnvelope;
+}
+
+impl BaseServiceAbi for OrderBookAbi {
+    type Query = Query;
+    type QueryResponse = QueryResponse;
 }
 
 /// Contract implementation
-pub struct OrderBookContract;
+pub struct OrderBookContract {
+    /// Admin named at instantiation, written to state when the instantiating block is stored
+    admin: Option<Account>,
+}
 
 impl WithContractAbi for OrderBookContract {
     type Abi = OrderBookAbi;
@@ -418,17 +1701,17 @@ impl WithContractAbi for OrderBookContract {
 
 #[async_trait]
 impl Contract for OrderBookContract {
-    type Message = Message;
+    type Message = MessageEnvelope;
     type Parameters = ();
-    type InstantiationArgument = ();
+    type InstantiationArgument = Account;
 
     async fn load(_runtime: ContractRuntime<Self>) -> Self {
-        OrderBookContract
+        OrderBookContract { admin: None }
     }
 
-    async fn instantiate(&mut self, _argument: ()) {
-        // Initialization will be done in the actual state when deployed
-        // State can be accessed via runtime when needed
+    async fn instantiate(&mut self, admin: Account) {
+        // The rest of the state starts from its defaults when first loaded
+        self.admin = Some(admin);
     }
 
     async fn execute_operation(
@@ -437,7 +1720,36 @@ impl Contract for OrderBookContract {
         operation: Operation,
     ) -> Result<(), OrderBookError> {
         let mut state = OrderBookState::load(runtime).await.map_err(|_| OrderBookError::ViewError)?;
-        match operation {
+        let book_changed = matches!(
+            operation,
+            Operation::PlaceOrder { .. }
+                | Operation::PlaceQuoteOrder { .. }
+                | Operation::CancelOrder { .. }
+                | Operation::ReduceOrder { .. }
+                | Operation::ModifyOrder { .. }
+                | Operation::BanAccount { .. }
+                | Operation::CancelBannedOrders { .. }
+                | Operation::CancelForcedOrders { .. }
+                | Operation::PlaceTwapOrder { .. }
+                | Operation::ProcessTwapOrders
+                | Operation::SetMarketPhase { .. }
+                | Operation::SweepStaleOrders
+        );
+        let trading = matches!(
+            operation,
+            Operation::PlaceOrder { .. }
+                | Operation::PlaceQuoteOrder { .. }
+                | Operation::ModifyOrder { .. }
+                | Operation::PlaceTwapOrder { .. }
+                | Operation::ProcessTwapOrders
+                | Operation::SetMarketPhase { .. }
+        );
+        // Cancels and withdrawals stay available while the state is half migrated
+        if trading && state.migration_cursor.get().is_some() {
+            return Err(OrderBookError::MigrationInProgress);
+        }
+        let placement = RejectedOrder::of(&operation);
+        let result = match operation {
             Operation::PlaceOrder {
                 side,
                 order_type,
@@ -445,14 +1757,29 @@ impl Contract for OrderBookContract {
                 quantity,
                 time_in_force,
                 expires_at,
+                require_full_fill,
+                min_fill_quantity,
+                client_request_id,
+                on_behalf_of,
+                sweep_exempt,
             } => {
+                let signer = runtime.authenticated_signer();
                 self.place_order(
-                    runtime, &mut state, side, order_type, price, quantity, time_in_force, expires_at
+                    runtime, &mut state, signer, side, order_type, price, quantity, time_in_force, expires_at,
+                    require_full_fill, min_fill_quantity, client_request_id, on_behalf_of, sweep_exempt,
                 ).await
             }
             
-            Operation::CancelOrder { order_id } => {
-                self.cancel_order(runtime, &mut state, order_id).await
+            Operation::PlaceQuoteOrder { quote_amount, max_price, client_request_id, on_behalf_of } => {
+                self.place_quote_order(runtime, &mut state, quote_amount, max_price, client_request_id, on_behalf_of).await
+            }
+            
+            Operation::CancelOrder { order_id, on_behalf_of } => {
+                self.cancel_order(runtime, &mut state, order_id, on_behalf_of).await
+            }
+            
+            Operation::ReduceOrder { order_id, reduce_by, cancel_if_below_minimum, on_behalf_of } => {
+                self.reduce_order(runtime, &mut state, order_id, reduce_by, cancel_if_below_minimum, on_behalf_of).await
             }
             
             Operation::ModifyOrder {
@@ -475,38 +1802,355 @@ impl Contract for OrderBookContract {
                 min_order_size,
                 max_order_size,
                 tick_size,
+                fee_model,
+                max_orders_per_level,
+                max_account_orders_per_level,
+                allow_unsettled_trading,
             } => {
-                self.update_config(runtime, &mut state, min_order_size, max_order_size, tick_size).await
+                self.update_config(
+                    runtime,
+                    &mut state,
+                    min_order_size,
+                    max_order_size,
+                    tick_size,
+                    fee_model,
+                    max_orders_per_level,
+                    max_account_orders_per_level,
+                    allow_unsettled_trading,
+                ).await
             }
-        }
-    }
-
-    async fn execute_message(&mut self, _runtime: &mut ContractRuntime<Self>, message: Message) {
-        match message {
-            Message::SettlementRequest { trade_id, .. } => {
-                // Process settlement request
-                let _ = trade_id;
+            
+            Operation::BanAccount { account, expires_at, reason } => {
+                self.ban_account(runtime, &mut state, account, expires_at, reason).await
             }
             
-            Message::SettlementConfirmation { trade_id, success } => {
-                // Handle settlement confirmation
-                let _ = (trade_id, success);
+            Operation::UnbanAccount { account } => {
+                self.unban_account(runtime, &mut state, account).await
             }
             
-            Message::CrossChainOrder { order, source_chain } => {
-                // Handle cross-chain order
-                let _ = (order, source_chain);
+            Operation::CancelBannedOrders { account } => {
+                let now = runtime.system_time();
+                match state.banned_accounts.get(&account).await.map_err(|_| OrderBookError::ViewError)? {
+                    Some(ban) if ban.is_active(now) => self.cancel_banned_orders(&mut state, account, now).await,
+                    _ => Err(OrderBookError::AccountNotBanned),
+                }
             }
             
-            Message::PriceUpdate { best_bid, best_ask, last_price } => {
-                // Update price
-                let _ = (best_bid, best_ask, last_price);
+            Operation::CancelForcedOrders { account } => {
+                let now = runtime.system_time();
+                match state.forced_cancellations.get(&account).await.map_err(|_| OrderBookError::ViewError)? {
+                    Some(reason) => self.force_cancel_orders(&mut state, account, reason, now).await,
+                    None => Err(OrderBookError::NoForcedCancellation),
+                }
+            }
+            
+            Operation::SetSettlementApplication { application_id, chain_id } => {
+                self.require_admin(runtime, &state)?;
+                state.settlement_application.set(Some((application_id, chain_id)));
+                Ok(())
+            }
+            
+            Operation::SetMarketPhase { phase, clear_crossed_book } => {
+                self.set_market_phase(runtime, &mut state, phase, clear_crossed_book).await
+            }
+            
+            Operation::RequestTradeSettlement { trade_id, timeout_seconds } => {
+                self.request_trade_settlement(runtime, &mut state, trade_id, timeout_seconds).await
+            }
+            
+            Operation::SyncTradeSettlement { trade_id } => {
+                self.sync_trade_settlement(runtime, &mut state, trade_id).await
+            }
+            
+            Operation::RegisterMarketMaker { account, max_spread_bps, min_uptime_bps, min_size } => {
+                self.register_market_maker(runtime, &mut state, account, max_spread_bps, min_uptime_bps, min_size).await
+            }
+            
+            Operation::RemoveMarketMaker { account } => {
+                self.require_admin(runtime, &state)?;
+                if state.dmm_agreements.get(&account).await.map_err(|_| OrderBookError::ViewError)?.is_none() {
+                    return Err(OrderBookError::MarketMakerNotFound);
+                }
+                // Credit the time up to removal before the record stops being sampled
+                self.sample_market_makers(runtime, &mut state).await?;
+                state.dmm_agreements.remove(&account)?;
+                Ok(())
+            }
+            
+            Operation::CloseDmmEpoch => {
+                self.close_dmm_epoch(runtime, &mut state).await
+            }
+            
+            Operation::PlaceTwapOrder { side, total_quantity, duration_seconds, slice_count, limit_price } => {
+                self.place_twap_order(
+                    runtime, &mut state, side, total_quantity, duration_seconds, slice_count, limit_price,
+                ).await
+            }
+            
+            Operation::CancelTwapOrder { twap_id } => {
+                self.cancel_twap_order(runtime, &mut state, twap_id).await
+            }
+            
+            Operation::ProcessTwapOrders => {
+                self.process_twap_orders(runtime, &mut state).await
+            }
+            
+            Operation::SetSettlementMinimum { asset, minimum } => {
+                self.require_admin(runtime, &state)?;
+                if minimum == Amount::ZERO {
+                    state.settlement_minimums.remove(&asset)?;
+                } else {
+                    state.settlement_minimums.insert(&asset, minimum)?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetSettlementMaximum { asset, maximum } => {
+                self.require_admin(runtime, &state)?;
+                if maximum == Amount::ZERO {
+                    state.settlement_maximums.remove(&asset)?;
+                } else {
+                    state.settlement_maximums.insert(&asset, maximum)?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetUncappedSettlementPair { first, second, uncapped } => {
+                self.require_admin(runtime, &state)?;
+                state.uncapped_settlement_pairs.remove(&(second, first))?;
+                if uncapped {
+                    state.uncapped_settlement_pairs.insert(&(first, second), ())?;
+                } else {
+                    state.uncapped_settlement_pairs.remove(&(first, second))?;
+                }
+                Ok(())
+            }
+            
+            Operation::GrantTradingPermission { delegate, max_order_size, side } => {
+                let principal = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                if delegate == principal {
+                    return Err(OrderBookError::InvalidTradingPermission { reason: "Cannot delegate to oneself".to_string() });
+                }
+                if max_order_size == Some(0) {
+                    return Err(OrderBookError::InvalidTradingPermission {
+                        reason: "Maximum order size must be positive".to_string(),
+                    });
+                }
+                let permission = TradingPermission { max_order_size, side, granted_at: runtime.system_time() };
+                state.trading_permissions.insert(&(principal, delegate), permission.clone())?;
+                state.events.push_back(OrderBookEvent::TradingPermissionGranted { principal, delegate, permission });
+                Ok(())
+            }
+            
+            Operation::RevokeTradingPermission { delegate } => {
+                let principal = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                if !state.trading_permissions.contains_key(&(principal, delegate)).await
+                    .map_err(|_| OrderBookError::ViewError)?
+                {
+                    return Err(OrderBookError::InvalidTradingPermission { reason: "No permission to revoke".to_string() });
+                }
+                state.trading_permissions.remove(&(principal, delegate))?;
+                state.events.push_back(OrderBookEvent::TradingPermissionRevoked {
+                    principal,
+                    delegate,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::SetHomeChain { chain_id } => {
+                let account = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                state.home_chains.insert(&account, chain_id)?;
+                Ok(())
+            }
+            
+            Operation::RecordRejections { enabled } => {
+                let account = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                if enabled {
+                    state.rejection_recording.insert(&account, ())?;
+                } else {
+                    state.rejection_recording.remove(&account)?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetMmpConfig { config } => {
+                let account = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                let Some(config) = config else {
+                    state.mmp_trackers.remove(&account)?;
+                    return Ok(());
+                };
+                config.validate().map_err(|reason| OrderBookError::InvalidMmpConfig { reason })?;
+                let cooldown_until = state.mmp_trackers.get(&account).await.map_err(|_| OrderBookError::ViewError)?
+                    .and_then(|tracker| tracker.cooldown_until);
+                let tracker = MmpTracker { cooldown_until, ..MmpTracker::new(config, runtime.system_time()) };
+                state.mmp_trackers.insert(&account, tracker)?;
+                Ok(())
+            }
+            
+            Operation::ResetMmp => {
+                let account = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+                if let Some(tracker) = state.mmp_trackers.get(&account).await.map_err(|_| OrderBookError::ViewError)? {
+                    state.mmp_trackers.insert(&account, MmpTracker::new(tracker.config, runtime.system_time()))?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetReferencePrice { config } => {
+                let admin = self.require_admin(runtime, &state)?;
+                config.validate().map_err(|reason| OrderBookError::InvalidReferencePrice { reason })?;
+                state.reference_price_config.set(config);
+                state.events.push_back(OrderBookEvent::ReferencePriceConfigured {
+                    config,
+                    changed_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::SetStaleOrderPolicy { policy } => {
+                let admin = self.require_admin(runtime, &state)?;
+                if let Some(policy) = &policy {
+                    policy.validate().map_err(|reason| OrderBookError::InvalidStaleOrderPolicy { reason })?;
+                }
+                state.stale_order_policy.set(policy);
+                state.events.push_back(OrderBookEvent::StaleOrderPolicyConfigured {
+                    policy,
+                    changed_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::SweepStaleOrders => {
+                self.sweep_stale_orders(runtime, &mut state).await
+            }
+            
+            Operation::SetPriceOracle { oracle } => {
+                self.require_admin(runtime, &state)?;
+                state.price_oracle.set(oracle);
+                Ok(())
+            }
+            
+            Operation::UpdateIndexPrice { price, emergency } => {
+                self.update_index_price(runtime, &mut state, price, emergency)
+            }
+            
+            Operation::RegisterSiblingMarket { chain_id, base_asset, quote_asset } => {
+                self.require_admin(runtime, &state)?;
+                let market = SiblingMarket { base_asset, quote_asset, registered_at: runtime.system_time() };
+                state.sibling_markets.insert(&chain_id, market)?;
+                Ok(())
+            }
+            
+            Operation::RemoveSiblingMarket { chain_id } => {
+                self.require_admin(runtime, &state)?;
+                if !state.sibling_markets.contains_key(&chain_id).await.map_err(|_| OrderBookError::ViewError)? {
+                    return Err(OrderBookError::UnknownSiblingMarket { chain_id });
+                }
+                state.sibling_markets.remove(&chain_id)?;
+                Ok(())
+            }
+            
+            Operation::ForwardOrder { market_chain, spec } => {
+                self.forward_order(runtime, &mut state, market_chain, spec).await
+            }
+            
+            Operation::TransferAdmin { new_admin } => {
+                self.require_admin(runtime, &state)?;
+                state.admin.set(Some(new_admin));
+                Ok(())
+            }
+            
+            Operation::AppointChainAdmin { chain_id, admin } => {
+                self.require_admin(runtime, &state)?;
+                runtime
+                    .prepare_message(MessageEnvelope::seal(&Message::AdminAppointed { admin }))
+                    .send_to(chain_id);
+                Ok(())
+            }
+            
+            Operation::SetTradeRetention { trades } => {
+                let admin = self.require_admin(runtime, &state)?;
+                if !(MIN_TRADE_RETENTION..=MAX_TRADE_RETENTION).contains(&trades) {
+                    return Err(OrderBookError::InvalidTradeRetention {
+                        minimum: MIN_TRADE_RETENTION,
+                        maximum: MAX_TRADE_RETENTION,
+                    });
+                }
+                state.trade_retention.set(Some(trades));
+                state.events.push_back(OrderBookEvent::TradeRetentionConfigured {
+                    trades,
+                    changed_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::SetPortfolioAggregator { chain } => {
+                let admin = self.require_admin(runtime, &state)?;
+                state.portfolio_aggregator.set(chain);
+                state.events.push_back(OrderBookEvent::PortfolioAggregatorConfigured {
+                    chain,
+                    changed_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::Migrate => {
+                self.migrate(runtime, &mut state).await
+            }
+            
+            Operation::ReplayParkedMessage { parked_id } => {
+                self.resolve_parked_message(runtime, &mut state, parked_id, true).await
+            }
+            
+            Operation::DiscardParkedMessage { parked_id } => {
+                self.resolve_parked_message(runtime, &mut state, parked_id, false).await
+            }
+        };
+        
+        let result = match (result, placement) {
+            (Err(error), Some(order)) => return self.reject_placement(runtime, &mut state, error, order).await,
+            (result, _) => result,
+        };
+        
+        // Quoting obligations are sampled only when the book changes
+        if result.is_ok() && book_changed {
+            state.book_sequence.set(state.book_sequence.get() + 1);
+            self.sample_market_makers(runtime, &mut state).await?;
+        }
+        result
+    }
+
+    async fn execute_message(&mut self, runtime: &mut ContractRuntime<Self>, envelope: MessageEnvelope) {
+        let Ok(mut state) = OrderBookState::load(runtime).await else {
+            return;
+        };
+        let context = MessageContext {
+            message_id: runtime.message_id(),
+            caller: runtime.authenticated_caller_id(),
+            signer: runtime.authenticated_signer(),
+        };
+        match envelope.open() {
+            Ok(message) => self.apply_message(runtime, &mut state, message, context).await,
+            Err(reason) => {
+                let _ = self.park_message(runtime, &mut state, envelope, context, reason);
             }
         }
     }
 
-    async fn store(self, _runtime: &mut ContractRuntime<Self>) {
-        // State saving is handled automatically by the runtime
+    async fn store(self, runtime: &mut ContractRuntime<Self>) {
+        // State saving is handled automatically by the runtime; the block's balance changes
+        // leave now that all of its operations and messages have executed
+        let Ok(mut state) = OrderBookState::load(runtime).await else {
+            return;
+        };
+        if let Some(admin) = self.admin {
+            state.admin.set(Some(admin));
+        }
+        let _ = Self::send_balance_deltas(runtime, &mut state).await;
     }
 }
 
@@ -517,161 +2161,3654 @@ impl Contract for OrderBookContract {
 // This is a condensed version showing the structure
 
 impl OrderBookContract {
-    async fn place_order(
+    /// Applies a delivered message; `context` is how it arrived, which for a replayed message
+    /// is not the current block's
+    async fn apply_message(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut OrderBookState<ContractRuntime<Self>>,
-        side: OrderSide,
-        order_type: OrderType,
-        price: Price,
-        quantity: Quantity,
-        time_in_force: TimeInForce,
-        expires_at: Option<Timestamp>,
-    ) -> Result<(), OrderBookError> {
-        // Implementation would go here - placeholder for brevity
-        let _ = (runtime, side, order_type, price, quantity, time_in_force, expires_at);
-        Ok(())
-    }
-    
-    async fn cancel_order(
-        &mut self,
-        _runtime: &mut ContractRuntime<Self>,
-        _state: &mut OrderBookState<ContractRuntime<Self>>,
-        order_id: OrderId,
-    ) -> Result<(), OrderBookError> {
-        let _ = order_id;
-        Ok(())
-    }
-    
-    async fn modify_order(
-        &mut self,
-        _runtime: &mut ContractRuntime<Self>,
-        _state: &mut OrderBookState<ContractRuntime<Self>>,
-        order_id: OrderId,
-        new_price: Option<Price>,
-        new_quantity: Option<Quantity>,
-    ) -> Result<(), OrderBookError> {
-        let _ = (order_id, new_price, new_quantity);
-        Ok(())
-    }
-    
-    async fn deposit(
+        message: Message,
+        context: MessageContext,
+    ) {
+        match message {
+            Message::SettlementRequest {
+                trade_id, maker, taker, maker_asset, taker_asset, maker_amount, taker_amount, ..
+            } => {
+                let status = match self.settlement_status(
+                    state, maker, taker, (&maker_asset, maker_amount), (&taker_asset, taker_amount),
+                ).await {
+                    Ok(status) => status,
+                    Err(error) => TradeSettlement::Failed { reason: error.to_string() },
+                };
+                let _ = self.resolve_unsettled_proceeds(state, trade_id, &status).await;
+                let _ = state.trade_settlements.insert(&trade_id, status);
+            }
+            
+            Message::SettlementConfirmation { trade_id, success } => {
+                let status = if success {
+                    TradeSettlement::Settled
+                } else {
+                    TradeSettlement::Failed { reason: "Rejected by the settlement contract".to_string() }
+                };
+                let _ = self.resolve_unsettled_proceeds(state, trade_id, &status).await;
+                let _ = state.trade_settlements.insert(&trade_id, status);
+            }
+            
+            Message::CrossChainOrder { order, source_chain } => {
+                // Handle cross-chain order
+                let _ = (order, source_chain);
+            }
+            
+            Message::PriceUpdate { best_bid, best_ask, last_price } => {
+                // Update price
+                let _ = (best_bid, best_ask, last_price);
+            }
+            
+            Message::ForceCancelOrders { account, reason } => {
+                if !is_settlement_origin(state.settlement_application.get(), context.caller, context.origin()) {
+                    return;
+                }
+                let now = runtime.system_time();
+                let _ = self.force_cancel_orders(state, account, reason, now).await;
+            }
+            
+            Message::ForwardOrder { forward_id, user, base_asset, quote_asset, spec } => {
+                let Some(origin) = context.origin() else {
+                    return;
+                };
+                if !matches!(state.sibling_markets.contains_key(&origin).await, Ok(true)) {
+                    state.events.push_back(OrderBookEvent::ForwardedOrderDropped {
+                        origin,
+                        user,
+                        forward_id,
+                        timestamp: runtime.system_time(),
+                    });
+                    return;
+                }
+                let result = self.accept_forwarded_order(
+                    runtime, state, context.signer, user, &base_asset, &quote_asset, spec,
+                ).await;
+                if result.is_err() {
+                    // Nothing of a refused order is applied
+                    state.rollback();
+                }
+                runtime
+                    .prepare_message(MessageEnvelope::seal(&Message::ForwardedOrderAck {
+                        forward_id,
+                        outcome: ForwardOutcome::of(result),
+                    }))
+                    .send_to(origin);
+            }
+            
+            Message::ForwardedOrderAck { forward_id, outcome } => {
+                let origin = context.origin();
+                let Ok(Some(mut forwarded)) = state.forwarded_orders.get(&forward_id).await else {
+                    return;
+                };
+                // Only the market the order went to acknowledges it, and only once
+                if origin != Some(forwarded.market_chain) || forwarded.outcome != ForwardOutcome::Pending {
+                    return;
+                }
+                forwarded.outcome = outcome;
+                forwarded.acknowledged_at = Some(runtime.system_time());
+                let _ = state.forwarded_orders.insert(&forward_id, forwarded);
+            }
+            
+            Message::BalanceDelta { account, asset, delta, sequence, .. } => {
+                let Some(market) = context.origin() else {
+                    return;
+                };
+                let _ = self.apply_balance_delta(runtime, state, market, account, asset, delta, sequence).await;
+            }
+            
+            Message::AdminAppointed { admin } => {
+                if context.origin() != Some(runtime.application_creator_chain_id()) {
+                    return;
+                }
+                state.admin.set(Some(admin));
+            }
+        }
+    }
+    
+    /// Keeps a message this build cannot read for a later replay or discard
+    fn park_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        envelope: MessageEnvelope,
+        context: MessageContext,
+        reason: String,
+    ) -> Result<(), OrderBookError> {
+        let now = runtime.system_time();
+        let parked_id = state.next_parked_message_id.get();
+        state.events.push_back(OrderBookEvent::MessageParked {
+            parked_id,
+            version: envelope.version,
+            origin: context.origin(),
+            reason: reason.clone(),
+            timestamp: now,
+        });
+        state.parked_messages.insert(&parked_id, ParkedMessage {
+            id: parked_id,
+            envelope,
+            context,
+            reason,
+            parked_at: now,
+        })?;
+        state.next_parked_message_id.set(parked_id + 1);
+        Ok(())
+    }
+    
+    /// Replays or discards a parked message. A replay is applied before the message is removed,
+    /// so a replay that rolls back its own changes keeps the removal.
+    async fn resolve_parked_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        parked_id: u64,
+        replay: bool,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        let parked = state.parked_messages.get(&parked_id).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::ParkedMessageNotFound { parked_id })?;
+        if replay {
+            let message = parked.envelope.open()
+                .map_err(|reason| OrderBookError::ParkedMessageUnreadable { parked_id, reason })?;
+            self.apply_message(runtime, state, message, parked.context).await;
+        }
+        state.parked_messages.remove(&parked_id)?;
+        state.events.push_back(OrderBookEvent::ParkedMessageResolved {
+            parked_id,
+            replayed: replay,
+            resolved_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Places an order signed by `signer`, which is the block's signer except for forwarded
+    /// orders, whose signer travels with the message
+    async fn place_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        signer: Option<Account>,
+        side: OrderSide,
+        order_type: OrderType,
+        price: Price,
+        quantity: Quantity,
+        time_in_force: TimeInForce,
+        expires_at: Option<Timestamp>,
+        require_full_fill: bool,
+        min_fill_quantity: Option<Quantity>,
+        client_request_id: Option<u64>,
+        on_behalf_of: Option<Account>,
+        sweep_exempt: bool,
+    ) -> Result<(), OrderBookError> {
+        // Balances, locks and limits below are the principal's
+        let (user, delegate) = self.acting_account_of(state, signer, on_behalf_of, Some((side, quantity))).await?;
+        self.ensure_not_banned(runtime, state, user).await?;
+        if let Some(delegate) = delegate {
+            self.ensure_not_banned(runtime, state, delegate).await?;
+        }
+        self.ensure_not_cooling_down(runtime, state, user).await?;
+        let config = state.config.get();
+        if !config.is_active {
+            return Err(OrderBookError::MarketClosed);
+        }
+        validate_order(&config, order_type, price, quantity, time_in_force)?;
+        let phase = state.market_phase.get();
+        phase.check_order(order_type, time_in_force)?;
+        check_min_fill(min_fill_quantity, quantity)?;
+        
+        let now = runtime.system_time();
+        let order_id = state.next_order_id.get();
+        state.next_order_id.set(order_id + 1);
+        let mut order = Order {
+            id: order_id,
+            user,
+            side,
+            order_type,
+            price: if order_type == OrderType::Market { 0 } else { price },
+            quantity,
+            filled_quantity: 0,
+            status: OrderStatus::Pending,
+            time_in_force,
+            timestamp: now,
+            expires_at,
+        };
+        
+        // Limit orders lock their full cost up front; market orders pay each fill from the free balance
+        if order_type == OrderType::Limit {
+            let post_only = time_in_force == TimeInForce::PostOnly || phase == MarketPhase::PreOpen;
+            if post_only && self.would_take(state, &order) {
+                return Err(OrderBookError::InvalidOrder { reason: "Post-only order would take liquidity".to_string() });
+            }
+            let (asset, amount) = order_lock(&config, side, price, quantity)?;
+            self.add_open_requirement(state, user, asset.clone(), amount).await?;
+            self.lock_balance(state, user, asset, amount).await?;
+        }
+        
+        self.record_order_event(state, order_id, OrderHistoryEvent::placed(&order), now).await?;
+        
+        // Auction orders wait for the clearing
+        if phase == MarketPhase::Continuous {
+            self.match_order(state, &config, &mut order, min_fill_quantity.unwrap_or(0), None, now).await?;
+        }
+        
+        // An Err here rolls back every fill above, so the book and all balances are untouched
+        order.status = placement_status(&order, require_full_fill)?;
+        match order.status {
+            // Nothing is locked for market orders; their unfilled rest simply lapses
+            OrderStatus::Cancelled if order.order_type == OrderType::Limit => {
+                let (asset, locked) = locked_remaining(&config, &order)?;
+                self.release_open_requirement(state, user, asset.clone(), locked, true).await?;
+                self.unlock_balance(state, user, asset, locked).await?;
+            }
+            OrderStatus::Open | OrderStatus::PartiallyFilled => self.rest_order(state, &config, &order).await?,
+            _ => {}
+        }
+        if order.status == OrderStatus::Cancelled {
+            let remaining = order.remaining_quantity();
+            self.record_order_event(state, order_id, OrderHistoryEvent::Cancelled { remaining }, now).await?;
+        }
+        
+        state.orders.insert(&order.id, order)?;
+        if let Some(delegate) = delegate {
+            state.order_delegates.insert(&order_id, delegate)?;
+        }
+        if sweep_exempt {
+            state.sweep_exempt_orders.insert(&order_id, ())?;
+        }
+        // Receipts belong to whoever submitted the request
+        self.record_receipt(state, delegate.unwrap_or(user), client_request_id, order_id, now).await
+    }
+    
+    /// Buys with a quote budget instead of a base quantity. The order's quantity is only an upper
+    /// bound, what the budget buys at the best ask, until the sweep sets it to what was filled.
+    async fn place_quote_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        quote_amount: Amount,
+        max_price: Option<Price>,
+        client_request_id: Option<u64>,
+        on_behalf_of: Option<Account>,
+    ) -> Result<(), OrderBookError> {
+        let config = state.config.get();
+        if !config.is_active {
+            return Err(OrderBookError::MarketClosed);
+        }
+        state.market_phase.get().check_order(OrderType::Market, TimeInForce::IOC)?;
+        let best_ask = state.best_ask.get().ok_or(OrderBookError::NoLiquidity { requested: 0, available: 0 })?;
+        let quantity = affordable_quantity(&config, best_ask, quote_amount)?.min(config.max_order_size);
+        if quantity < config.min_order_size {
+            return Err(OrderBookError::InvalidOrder {
+                reason: "Quote amount buys less than the minimum order size".to_string(),
+            });
+        }
+        let (user, delegate) = self.acting_account(runtime, state, on_behalf_of, Some((OrderSide::Buy, quantity))).await?;
+        self.ensure_not_banned(runtime, state, user).await?;
+        if let Some(delegate) = delegate {
+            self.ensure_not_banned(runtime, state, delegate).await?;
+        }
+        self.ensure_not_cooling_down(runtime, state, user).await?;
+        
+        let now = runtime.system_time();
+        let order_id = state.next_order_id.get();
+        state.next_order_id.set(order_id + 1);
+        let mut order = Order {
+            id: order_id,
+            user,
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            price: 0,
+            quantity,
+            filled_quantity: 0,
+            status: OrderStatus::Pending,
+            time_in_force: TimeInForce::IOC,
+            timestamp: now,
+            expires_at: None,
+        };
+        
+        self.record_order_event(state, order_id, OrderHistoryEvent::placed(&order), now).await?;
+        let quote_asset = config.quote_asset.clone();
+        self.lock_balance(state, user, quote_asset.clone(), quote_amount).await?;
+        let mut budget = QuoteBudget { remaining: quote_amount, max_price };
+        self.match_order(state, &config, &mut order, 0, Some(&mut budget), now).await?;
+        self.unlock_balance(state, user, quote_asset, budget.remaining).await?;
+        
+        if order.filled_quantity > 0 {
+            order.quantity = order.filled_quantity;
+            order.status = OrderStatus::Filled;
+        } else {
+            order.status = OrderStatus::Cancelled;
+            let remaining = order.remaining_quantity();
+            self.record_order_event(state, order_id, OrderHistoryEvent::Cancelled { remaining }, now).await?;
+        }
+        state.orders.insert(&order.id, order)?;
+        if let Some(delegate) = delegate {
+            state.order_delegates.insert(&order_id, delegate)?;
+        }
+        self.record_receipt(state, delegate.unwrap_or(user), client_request_id, order_id, now).await
+    }
+    
+    /// Account an order operation acts for: the signer, or the principal it names if the signer
+    /// holds a trading permission from it. `placement` is checked against the permission's scope.
+    /// Returns the principal and, for delegated calls, the delegate.
+    async fn acting_account(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        on_behalf_of: Option<Account>,
+        placement: Option<(OrderSide, Quantity)>,
+    ) -> Result<(Account, Option<Account>), OrderBookError> {
+        self.acting_account_of(state, runtime.authenticated_signer(), on_behalf_of, placement).await
+    }
+    
+    /// `acting_account` for an explicit signer
+    async fn acting_account_of(
+        &self,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        signer: Option<Account>,
+        on_behalf_of: Option<Account>,
+        placement: Option<(OrderSide, Quantity)>,
+    ) -> Result<(Account, Option<Account>), OrderBookError> {
+        let signer = signer.ok_or(OrderBookError::Unauthorized)?;
+        let Some(principal) = on_behalf_of.filter(|principal| *principal != signer) else {
+            return Ok((signer, None));
+        };
+        let permission = state.trading_permissions.get(&(principal, signer)).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::NoTradingPermission { principal })?;
+        if let Some((side, quantity)) = placement {
+            permission.check(side, quantity)?;
+        }
+        Ok((principal, Some(signer)))
+    }
+    
+    /// Stores the receipt for `client_request_id`, rejecting reuse of the id by the same caller.
+    async fn record_receipt(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        caller: Account,
+        client_request_id: Option<u64>,
+        id: OrderId,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let Some(client_request_id) = client_request_id else {
+            return Ok(());
+        };
+        let key = (caller, client_request_id);
+        if state.receipts.get(&key).await.map_err(|_| OrderBookError::ViewError)?.is_some() {
+            return Err(OrderBookError::DuplicateClientRequest { client_request_id });
+        }
+        state.receipts.insert(&key, OperationReceipt { client_request_id, id, created_at: now })?;
+        state.client_order_ids.insert(&id, client_request_id)?;
+        Ok(())
+    }
+    
+    fn would_take(&self, state: &OrderBookState<ContractRuntime<Self>>, order: &Order) -> bool {
+        let best = match order.side {
+            OrderSide::Buy => state.best_ask.get(),
+            OrderSide::Sell => state.best_bid.get(),
+        };
+        best.map_or(false, |best| order.side.crosses(order.price, best))
+    }
+    
+    /// Fills `taker` against the opposite side as `match_taker` plans it, then writes the fills
+    /// and the levels they touched back to the book. A taker with a `budget` pays each fill out
+    /// of its lock.
+    async fn match_order(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        taker: &mut Order,
+        min_fill: Quantity,
+        mut budget: Option<&mut QuoteBudget>,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let maker_side = taker.side.opposite();
+        let max_price = budget.as_ref().and_then(|budget| budget.max_price);
+        let book = self.book_snapshot(state, taker, min_fill, max_price, now).await?;
+        let MatchOutcome { fills, levels, protections, mmp_triggered } =
+            match_taker(config, taker, min_fill, budget.as_deref_mut(), &book)?;
+        
+        for fill in fills {
+            if budget.is_some() {
+                // Released from the lock just before the fill takes it from the free balance
+                let paid = fill.amounts.taker_pays;
+                self.unlock_balance(state, taker.user, config.payment_asset(taker.side).to_string(), paid).await?;
+            }
+            let mut maker = fill.maker;
+            self.execute_fill(state, config, taker, &mut maker, fill.price, fill.quantity, now).await?;
+            if maker.is_fully_filled() {
+                maker.status = OrderStatus::Filled;
+                let mut user_orders = state.user_orders.get(&maker.user).await
+                    .map_err(|_| OrderBookError::ViewError)?
+                    .unwrap_or_default();
+                user_orders.retain(|id| *id != maker.id);
+                state.user_orders.insert(&maker.user, user_orders)?;
+            } else {
+                maker.status = OrderStatus::PartiallyFilled;
+            }
+            state.orders.insert(&maker.id, maker)?;
+        }
+        
+        let mut emptied = false;
+        for (price, level) in levels {
+            emptied |= level.orders.is_empty();
+            match (maker_side, level.orders.is_empty()) {
+                (OrderSide::Buy, true) => state.buy_levels.remove(&price)?,
+                (OrderSide::Buy, false) => state.buy_levels.insert(&price, level)?,
+                (OrderSide::Sell, true) => state.sell_levels.remove(&price)?,
+                (OrderSide::Sell, false) => state.sell_levels.insert(&price, level)?,
+            }
+        }
+        if emptied {
+            self.refresh_best_price(state, maker_side).await?;
+        }
+        
+        // Counters go back first so a trigger starts its cooldown from them
+        for (account, tracker) in protections {
+            state.mmp_trackers.insert(&account, tracker)?;
+        }
+        for account in mmp_triggered {
+            self.trigger_mmp(state, account, now).await?;
+        }
+        Ok(())
+    }
+    
+    /// Pulls the quotes of an account whose protection a fill triggered: the cooldown starts and
+    /// one batch of its resting orders is cancelled; the rest are passed over while it runs.
+    async fn trigger_mmp(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let Some(mut tracker) = state.mmp_trackers.get(&account).await.map_err(|_| OrderBookError::ViewError)? else {
+            return Ok(());
+        };
+        let (fills, delta) = (tracker.fills, tracker.delta);
+        let cooldown_until = tracker.trigger(now);
+        state.mmp_trackers.insert(&account, tracker)?;
+        let (order_ids, remaining) = self.cancel_account_orders(state, account, now).await?;
+        state.events.push_back(OrderBookEvent::MmpTriggered {
+            account,
+            fills,
+            delta,
+            order_ids,
+            remaining,
+            cooldown_until,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    
+    /// Levels opposite `taker` that it can reach, best first, loaded until their makers could fill it
+    async fn book_snapshot(
+        &self,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        taker: &Order,
+        min_fill: Quantity,
+        max_price: Option<Price>,
+        now: Timestamp,
+    ) -> Result<BookSnapshot, OrderBookError> {
+        let levels = match taker.side.opposite() {
+            OrderSide::Buy => &state.buy_levels,
+            OrderSide::Sell => &state.sell_levels,
+        };
+        let mut prices = levels.indices().await.map_err(|_| OrderBookError::ViewError)?;
+        prices.sort_unstable();
+        if taker.side == OrderSide::Sell {
+            prices.reverse();
+        }
+        
+        let mut book = BookSnapshot::default();
+        for price in prices {
+            if !book.needs_level(taker, max_price, price) {
+                book.next_price = Some(price);
+                break;
+            }
+            let level = levels.get(&price).await.map_err(|_| OrderBookError::ViewError)?.unwrap_or_default();
+            let mut orders = Vec::new();
+            for id in &level.orders {
+                if let Some(order) = state.orders.get(id).await.map_err(|_| OrderBookError::ViewError)? {
+                    let tracker = state.mmp_trackers.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?;
+                    if let Some(tracker) = tracker {
+                        book.protect(order.user, tracker, now);
+                    }
+                    orders.push(order);
+                }
+            }
+            book.push_level(price, level, orders, min_fill);
+        }
+        Ok(book)
+    }
+    
+    /// Exchanges `quantity` between `taker` and `maker` at the maker's price and records the trade.
+    async fn execute_fill(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        taker: &mut Order,
+        maker: &mut Order,
+        price: Price,
+        quantity: Quantity,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let amounts = fill_amounts(config, taker.side, price, quantity)?;
+        
+        self.pay_for_fill(state, config, taker, quantity, amounts.taker_pays).await?;
+        self.pay_for_fill(state, config, maker, quantity, amounts.maker_pays).await?;
+        for (order, fee) in [(&*taker, amounts.taker_fee), (&*maker, amounts.maker_fee)] {
+            let asset = config.fee_asset(order.side);
+            self.collect_fee(state, asset, fee).await?;
+            if fee > Amount::ZERO {
+                let charge = PendingBalanceDelta::new(fee, true, BalanceChangeReason::Fee);
+                self.note_balance_change(state, order.user, asset, charge).await?;
+            }
+        }
+        taker.filled_quantity += quantity;
+        maker.filled_quantity += quantity;
+        
+        let trade_id = state.next_trade_id.get();
+        state.next_trade_id.set(trade_id + 1);
+        self.credit_proceeds(state, trade_id, [
+            (taker.user, config.proceeds_asset(taker.side).to_string(), amounts.taker_receives),
+            (maker.user, config.proceeds_asset(maker.side).to_string(), amounts.maker_receives),
+        ]).await?;
+        for (order, maker) in [(&*taker, false), (&*maker, true)] {
+            let remaining = order.remaining_quantity();
+            let event = OrderHistoryEvent::Filled { trade_id, price, quantity, remaining, maker };
+            self.record_order_event(state, order.id, event, now).await?;
+        }
+        self.store_trade(state, Trade {
+            id: trade_id,
+            maker_order_id: maker.id,
+            taker_order_id: taker.id,
+            price,
+            quantity,
+            timestamp: now,
+            maker: maker.user,
+            taker: taker.user,
+            maker_side: maker.side,
+            maker_fee: amounts.maker_fee,
+            taker_fee: amounts.taker_fee,
+            fee_model: config.fee_model,
+        })?;
+        // Two per trade keeps up with new trades and catches up after a backlog or a smaller retention
+        let retention = state.trade_retention.get().unwrap_or(TRADE_RETENTION);
+        for id in trades_to_prune(state.first_retained_trade_id.get(), trade_id, retention).take(2) {
+            state.trades_by_id.remove(&id)?;
+            state.trade_checkpoints.remove(&id)?;
+            state.first_retained_trade_id.set(id + 1);
+        }
+        
+        let mut trade_stats = state.trade_stats.get();
+        trade_stats.record(trade_id, hour_of(now), price, quantity);
+        let mut stats = state.market_stats.get();
+        stats.last_price = price;
+        stats.total_trades += 1;
+        Self::set_window_stats(&mut stats, trade_stats.window(hour_of(now)));
+        state.market_stats.set(stats);
+        state.trade_stats.set(trade_stats);
+        
+        let mut recent_trades = state.recent_trades.get();
+        recent_trades.record(price, quantity, state.reference_price_config.get().window_trades);
+        state.recent_trades.set(recent_trades);
+        Ok(())
+    }
+    
+    /// Copies a rolling window into the 24h market stats, which are refreshed as trades are recorded
+    fn set_window_stats(stats: &mut MarketStats, window: TradeWindow) {
+        stats.volume_24h = window.volume;
+        stats.high_24h = window.high;
+        stats.low_24h = window.low;
+        stats.price_change_24h = window.price_change();
+    }
+    
+    /// Records `trade` by id, checkpointing its timestamp every `TRADE_CHECKPOINT_INTERVAL` trades
+    fn store_trade(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        trade: Trade,
+    ) -> Result<(), OrderBookError> {
+        let id = trade.id;
+        if id < state.first_retained_trade_id.get() {
+            return Ok(());
+        }
+        if id % TRADE_CHECKPOINT_INTERVAL == 0 {
+            state.trade_checkpoints.insert(&id, trade.timestamp)?;
+        }
+        state.trades_by_id.insert(&id, trade)?;
+        Ok(())
+    }
+    
+    /// Folds a balance change into the account's delta for the block, while an aggregator is set
+    async fn note_balance_change(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        asset: &str,
+        change: PendingBalanceDelta,
+    ) -> Result<(), OrderBookError> {
+        if state.portfolio_aggregator.get().is_none() {
+            return Ok(());
+        }
+        let key = (account, asset.to_string());
+        let pending = match state.pending_balance_deltas.get(&key).await.map_err(|_| OrderBookError::ViewError)? {
+            Some(mut pending) => {
+                pending.add(change);
+                pending
+            }
+            None => change,
+        };
+        state.pending_balance_deltas.insert(&key, pending)?;
+        Ok(())
+    }
+    
+    /// Adds a market chain's balance delta to the aggregated balance. A delta at or below the last
+    /// applied sequence is a redelivery and skipped; one past the next is applied and the gap
+    /// reported, for the portfolio service to reconcile with a snapshot.
+    async fn apply_balance_delta(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        market: ChainId,
+        account: Account,
+        asset: String,
+        delta: i128,
+        sequence: u64,
+    ) -> Result<(), OrderBookError> {
+        let sequence_key = (market, account);
+        let last = state.portfolio_sequences.get(&sequence_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(0);
+        if sequence <= last {
+            return Ok(());
+        }
+        if sequence > last + 1 {
+            state.events.push_back(OrderBookEvent::BalanceDeltaGap {
+                market,
+                account,
+                expected: last + 1,
+                received: sequence,
+                timestamp: runtime.system_time(),
+            });
+        }
+        state.portfolio_sequences.insert(&sequence_key, sequence)?;
+        let key = (account, asset);
+        let balance = state.portfolio_balances.get(&key).await.map_err(|_| OrderBookError::ViewError)?.unwrap_or(0);
+        state.portfolio_balances.insert(&key, balance.saturating_add(delta))?;
+        Ok(())
+    }
+    
+    /// Sends each pending balance change that did not net out to the aggregator, numbering every
+    /// account's deltas without gaps, and clears them
+    async fn send_balance_deltas(
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let aggregator = state.portfolio_aggregator.get();
+        for key in state.pending_balance_deltas.indices().await.map_err(|_| OrderBookError::ViewError)? {
+            let pending = state.pending_balance_deltas.get(&key).await.map_err(|_| OrderBookError::ViewError)?;
+            state.pending_balance_deltas.remove(&key)?;
+            let (Some(chain), Some(pending)) = (aggregator, pending) else {
+                continue;
+            };
+            if pending.delta == 0 {
+                continue;
+            }
+            let (account, asset) = key;
+            let sequence = state.balance_sequences.get(&account).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or(0) + 1;
+            state.balance_sequences.insert(&account, sequence)?;
+            runtime
+                .prepare_message(MessageEnvelope::seal(&Message::BalanceDelta {
+                    account,
+                    asset,
+                    delta: pending.delta,
+                    reason: pending.reason,
+                    sequence,
+                }))
+                .send_to(chain);
+        }
+        Ok(())
+    }
+    
+    async fn collect_fee(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        asset: &str,
+        fee: Amount,
+    ) -> Result<(), OrderBookError> {
+        if fee == Amount::ZERO {
+            return Ok(());
+        }
+        let asset = asset.to_string();
+        let collected = state.collected_fees.get(&asset).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.collected_fees.insert(&asset, math::checked_add(collected, fee)?)?;
+        Ok(())
+    }
+    
+    /// Takes `paid` for a fill of `quantity` from `order`'s owner. Limit orders draw on the lock
+    /// they hold for that portion and get back what a better fill price left over; market orders
+    /// pay like a new lock, see `debit_spendable`.
+    async fn pay_for_fill(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        order: &Order,
+        quantity: Quantity,
+        paid: Amount,
+    ) -> Result<(), OrderBookError> {
+        let balance_key = (order.user, config.payment_asset(order.side).to_string());
+        if order.order_type == OrderType::Market {
+            return self.debit_spendable(state, &balance_key, paid).await;
+        }
+        
+        let consumed = lock_consumed(config, order, quantity)?;
+        let locked = state.locked_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.locked_balances.insert(&balance_key, math::checked_sub(locked, consumed)?)?;
+        let (user, asset) = balance_key;
+        let fills_order = order.remaining_quantity() <= quantity;
+        self.release_open_requirement(state, user, asset.clone(), consumed, fills_order).await?;
+        self.credit_free_balance(state, user, asset, math::checked_sub(consumed, paid)?).await
+    }
+    
+    /// Adds a limit order's unfilled remainder to its price level, unless the level is at the
+    /// market's caps.
+    async fn rest_order(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        order: &Order,
+    ) -> Result<(), OrderBookError> {
+        let level = match order.side {
+            OrderSide::Buy => state.buy_levels.get(&order.price).await,
+            OrderSide::Sell => state.sell_levels.get(&order.price).await,
+        };
+        let mut level = level.map_err(|_| OrderBookError::ViewError)?.unwrap_or_default();
+        check_level_caps(config, &level, order)?;
+        level.push_order(order);
+        match order.side {
+            OrderSide::Buy => {
+                state.buy_levels.insert(&order.price, level)?;
+                if state.best_bid.get().map_or(true, |best| order.price > best) {
+                    state.best_bid.set(Some(order.price));
+                }
+            }
+            OrderSide::Sell => {
+                state.sell_levels.insert(&order.price, level)?;
+                if state.best_ask.get().map_or(true, |best| order.price < best) {
+                    state.best_ask.set(Some(order.price));
+                }
+            }
+        }
+        
+        let mut user_orders = state.user_orders.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        user_orders.push(order.id);
+        state.user_orders.insert(&order.user, user_orders)?;
+        Ok(())
+    }
+    
+    async fn cancel_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        order_id: OrderId,
+        on_behalf_of: Option<Account>,
+    ) -> Result<(), OrderBookError> {
+        let (user, _) = self.acting_account(runtime, state, on_behalf_of, None).await?;
+        let order = state.orders.get(&order_id).await.map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::OrderNotFound { order_id })?;
+        if order.user != user {
+            return Err(OrderBookError::Unauthorized);
+        }
+        if !order.is_active() {
+            return Err(OrderBookError::OrderNotModifiable { status: order.status });
+        }
+        self.cancel_resting_order(state, order, runtime.system_time()).await
+    }
+    
+    /// Shrinks the order in place: its level keeps the id where it is and only loses the
+    /// quantity, and the lock for that quantity is released.
+    async fn reduce_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        order_id: OrderId,
+        reduce_by: Quantity,
+        cancel_if_below_minimum: bool,
+        on_behalf_of: Option<Account>,
+    ) -> Result<(), OrderBookError> {
+        let (user, _) = self.acting_account(runtime, state, on_behalf_of, None).await?;
+        let mut order = state.orders.get(&order_id).await.map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::OrderNotFound { order_id })?;
+        if order.user != user {
+            return Err(OrderBookError::Unauthorized);
+        }
+        if !order.is_active() {
+            return Err(OrderBookError::OrderNotModifiable { status: order.status });
+        }
+        if reduce_by == 0 {
+            return Err(OrderBookError::InvalidOrder { reason: "Nothing to reduce".to_string() });
+        }
+        
+        let config = state.config.get();
+        let now = runtime.system_time();
+        let remaining = order.remaining_quantity();
+        if reduced_remaining(remaining, reduce_by, config.min_order_size).is_none() {
+            if cancel_if_below_minimum {
+                return self.cancel_resting_order(state, order, now).await;
+            }
+            return Err(match remaining.checked_sub(reduce_by) {
+                Some(size) => OrderBookError::BelowMinimumSize { size, minimum: config.min_order_size },
+                None => OrderBookError::ReductionExceedsRemaining { reduce_by, remaining },
+            });
+        }
+        
+        let (asset, locked_before) = locked_remaining(&config, &order)?;
+        order.quantity -= reduce_by;
+        let (_, locked_after) = locked_remaining(&config, &order)?;
+        
+        // Untriggered stops hold a lock but are not on a level yet
+        let levels = match order.side {
+            OrderSide::Buy => &mut state.buy_levels,
+            OrderSide::Sell => &mut state.sell_levels,
+        };
+        if let Some(mut level) = levels.get(&order.price).await.map_err(|_| OrderBookError::ViewError)? {
+            if level.orders.contains(&order_id) {
+                level.total_quantity = level.total_quantity.saturating_sub(reduce_by);
+                levels.insert(&order.price, level)?;
+            }
+        }
+        let released = math::checked_sub(locked_before, locked_after)?;
+        self.release_open_requirement(state, user, asset.clone(), released, false).await?;
+        self.unlock_balance(state, user, asset, released).await?;
+        let remaining = order.remaining_quantity();
+        state.orders.insert(&order_id, order)?;
+        self.record_order_event(state, order_id, OrderHistoryEvent::Reduced { reduce_by, remaining }, now).await
+    }
+    
+    async fn modify_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        order_id: OrderId,
+        new_price: Option<Price>,
+        new_quantity: Option<Quantity>,
+    ) -> Result<(), OrderBookError> {
+        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        self.ensure_not_banned(runtime, state, user).await?;
+        let _ = (order_id, new_price, new_quantity);
+        Ok(())
+    }
+    
+    /// Takes an active order off the book and releases the funds it locked.
+    async fn cancel_resting_order(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        mut order: Order,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let remaining = order.remaining_quantity();
+        let levels = match order.side {
+            OrderSide::Buy => &mut state.buy_levels,
+            OrderSide::Sell => &mut state.sell_levels,
+        };
+        if let Some(mut level) = levels.get(&order.price).await.map_err(|_| OrderBookError::ViewError)? {
+            level.remove_order(&order);
+            level.total_quantity = level.total_quantity.saturating_sub(remaining);
+            if level.orders.is_empty() {
+                levels.remove(&order.price)?;
+                let best = match order.side {
+                    OrderSide::Buy => state.best_bid.get(),
+                    OrderSide::Sell => state.best_ask.get(),
+                };
+                if best == Some(order.price) {
+                    self.refresh_best_price(state, order.side).await?;
+                }
+            } else {
+                levels.insert(&order.price, level)?;
+            }
+        }
+        
+        let config = state.config.get();
+        let (asset, locked) = locked_remaining(&config, &order)?;
+        self.release_open_requirement(state, order.user, asset.clone(), locked, true).await?;
+        self.unlock_balance(state, order.user, asset, locked).await?;
+        
+        let mut user_orders = state.user_orders.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        user_orders.retain(|id| *id != order.id);
+        state.user_orders.insert(&order.user, user_orders)?;
+        
+        order.status = OrderStatus::Cancelled;
+        let order_id = order.id;
+        state.orders.insert(&order_id, order)?;
+        self.record_order_event(state, order_id, OrderHistoryEvent::Cancelled { remaining }, now).await
+    }
+    
+    /// Appends `event` to the order's amendment history
+    async fn record_order_event(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        order_id: OrderId,
+        event: OrderHistoryEvent,
+        timestamp: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let mut history = state.order_history.get(&order_id).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        history.push(OrderHistoryEntry { event, timestamp });
+        state.order_history.insert(&order_id, history)?;
+        Ok(())
+    }
+    
+    /// Moves `amount` of `asset` from the user's spendable balance into their locked balance.
+    async fn lock_balance(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        user: Account,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let balance_key = (user, asset);
+        self.debit_spendable(state, &balance_key, amount).await?;
+        let locked = state.locked_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.locked_balances.insert(&balance_key, math::checked_add(locked, amount)?)?;
+        Ok(())
+    }
+    
+    /// Takes `amount` from the free balance under `balance_key`, and the shortfall from unsettled
+    /// proceeds when the market lets orders spend them.
+    async fn debit_spendable(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        balance_key: &(Account, String),
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let balance = state.balances.get(balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        if balance >= amount {
+            state.balances.insert(balance_key, balance - amount)?;
+            return Ok(());
+        }
+        let unsettled = if state.config.get().allow_unsettled_trading {
+            state.unsettled_balances.get(balance_key).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or(Amount::ZERO)
+        } else {
+            Amount::ZERO
+        };
+        let shortfall = amount - balance;
+        if unsettled < shortfall {
+            return Err(OrderBookError::InsufficientBalance {
+                required: amount,
+                available: math::checked_add(balance, unsettled)?,
+            });
+        }
+        state.balances.insert(balance_key, Amount::ZERO)?;
+        state.unsettled_balances.insert(balance_key, unsettled - shortfall)?;
+        Ok(())
+    }
+    
+    /// Credits a trade's proceeds, as (account, asset, amount) per side. While a settlement
+    /// application is set, proceeds of trades that do not settle internally wait in
+    /// `unsettled_balances` for the trade's settlement.
+    async fn credit_proceeds(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        trade_id: u64,
+        proceeds: [(Account, String, Amount); 2],
+    ) -> Result<(), OrderBookError> {
+        // The maker's leg is what the taker receives, and the reverse
+        let [(taker, taker_asset, taker_receives), (maker, maker_asset, maker_receives)] = &proceeds;
+        let held = state.settlement_application.get().is_some()
+            && self.settlement_status(
+                state, *maker, *taker, (taker_asset, *taker_receives), (maker_asset, *maker_receives),
+            ).await? != TradeSettlement::Internal;
+        if !held {
+            for (account, asset, amount) in proceeds {
+                self.credit_free_balance(state, account, asset, amount).await?;
+            }
+            return Ok(());
+        }
+        
+        let mut holds = Vec::new();
+        for (account, asset, amount) in proceeds {
+            if amount == Amount::ZERO {
+                continue;
+            }
+            let balance_key = (account, asset);
+            let unsettled = state.unsettled_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or(Amount::ZERO);
+            state.unsettled_balances.insert(&balance_key, math::checked_add(unsettled, amount)?)?;
+            let (account, asset) = balance_key;
+            holds.push(UnsettledProceeds { account, asset, amount });
+        }
+        state.unsettled_trades.insert(&trade_id, holds)?;
+        Ok(())
+    }
+    
+    /// Releases the proceeds `trade_id` holds to the free balances once `status` is final:
+    /// settled or internal. A failed settlement drains them instead; what orders already spent
+    /// is not clawed back, so at most the unsettled balance left is drained.
+    async fn resolve_unsettled_proceeds(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        trade_id: u64,
+        status: &TradeSettlement,
+    ) -> Result<(), OrderBookError> {
+        if *status == TradeSettlement::Requested {
+            return Ok(());
+        }
+        let Some(holds) = state.unsettled_trades.get(&trade_id).await.map_err(|_| OrderBookError::ViewError)? else {
+            return Ok(());
+        };
+        state.unsettled_trades.remove(&trade_id)?;
+        for hold in holds {
+            let balance_key = (hold.account, hold.asset);
+            let unsettled = state.unsettled_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or(Amount::ZERO);
+            let resolved = unsettled.min(hold.amount);
+            state.unsettled_balances.insert(&balance_key, unsettled - resolved)?;
+            if !matches!(status, TradeSettlement::Failed { .. }) {
+                let (account, asset) = balance_key;
+                self.credit_free_balance(state, account, asset, resolved).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    async fn credit_free_balance(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        user: Account,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        let balance_key = (user, asset);
+        let balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.balances.insert(&balance_key, math::checked_add(balance, amount)?)?;
+        Ok(())
+    }
+    
+    /// Moves `amount` of `asset` from the user's locked balance back to their free balance.
+    async fn unlock_balance(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        user: Account,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let balance_key = (user, asset);
+        let locked = state.locked_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.locked_balances.insert(&balance_key, math::saturating_sub(locked, amount))?;
+        let balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        state.balances.insert(&balance_key, math::checked_add(balance, amount)?)?;
+        Ok(())
+    }
+    
+    /// Counts a new limit order needing `amount` of `asset` in the user's open-order requirement.
+    async fn add_open_requirement(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        user: Account,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let key = (user, asset);
+        let mut requirement = state.open_order_requirements.get(&key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        requirement.amount = math::checked_add(requirement.amount, amount)?;
+        requirement.orders += 1;
+        state.open_order_requirements.insert(&key, requirement)?;
+        Ok(())
+    }
+    
+    /// Takes `amount` off the user's open-order requirement, and the order off its count when it
+    /// `closes`. Saturates, since orders placed before the counters existed were never added.
+    async fn release_open_requirement(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        user: Account,
+        asset: String,
+        amount: Amount,
+        closes: bool,
+    ) -> Result<(), OrderBookError> {
+        let key = (user, asset);
+        let Some(mut requirement) = state.open_order_requirements.get(&key).await
+            .map_err(|_| OrderBookError::ViewError)? else {
+            return Ok(());
+        };
+        requirement.amount = math::saturating_sub(requirement.amount, amount);
+        if closes {
+            requirement.orders = requirement.orders.saturating_sub(1);
+        }
+        if requirement == OpenOrderRequirement::default() {
+            state.open_order_requirements.remove(&key)?;
+        } else {
+            state.open_order_requirements.insert(&key, requirement)?;
+        }
+        Ok(())
+    }
+    
+    async fn set_market_phase(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        phase: MarketPhase,
+        clear_crossed_book: bool,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
+        let crossed = matches!((state.best_bid.get(), state.best_ask.get()), (Some(bid), Some(ask)) if bid >= ask);
+        let mut clearing = None;
+        if phase == MarketPhase::Continuous && crossed {
+            if !clear_crossed_book {
+                return Err(OrderBookError::BookCrossed);
+            }
+            let config = state.config.get();
+            clearing = self.clear_auction(state, &config, now).await?;
+        }
+        let from = state.market_phase.get();
+        state.market_phase.set(phase);
+        state.events.push_back(OrderBookEvent::MarketPhaseChanged { from, to: phase, clearing, changed_by: admin, timestamp: now });
+        Ok(())
+    }
+    
+    /// Uncrosses the book at the one price `auction_clearing_price` picks. Bids at or above it and
+    /// asks at or below it fill in price-time priority, every fill at that price, with the order
+    /// placed later paying the taker fee. Returns the price and the volume traded.
+    async fn clear_auction(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        now: Timestamp,
+    ) -> Result<Option<(Price, Quantity)>, OrderBookError> {
+        let (Some(best_bid), Some(best_ask)) = (state.best_bid.get(), state.best_ask.get()) else {
+            return Ok(None);
+        };
+        let bids = self.levels_between(state, OrderSide::Buy, best_ask, best_bid).await?;
+        let asks = self.levels_between(state, OrderSide::Sell, best_ask, best_bid).await?;
+        let depth = |levels: &[(Price, PriceLevel)]| -> Vec<(Price, Quantity)> {
+            levels.iter().map(|(price, level)| (*price, level.total_quantity)).collect()
+        };
+        let Some((price, volume)) = auction_clearing_price(&depth(&bids), &depth(&asks)) else {
+            return Ok(None);
+        };
+        let mut bids: Vec<_> = bids.into_iter().filter(|(level_price, _)| *level_price >= price).collect();
+        let mut asks: Vec<_> = asks.into_iter().filter(|(level_price, _)| *level_price <= price).collect();
+        let queue = |levels: &[(Price, PriceLevel)]| -> Vec<(usize, OrderId)> {
+            levels.iter().enumerate()
+                .flat_map(|(index, (_, level))| level.orders.iter().map(move |id| (index, *id)))
+                .collect()
+        };
+        let (bid_queue, ask_queue) = (queue(&bids), queue(&asks));
+        let (mut bid_position, mut ask_position) = (0, 0);
+        let mut bid = Self::next_active_order(state, &bid_queue, &mut bid_position).await?;
+        let mut ask = Self::next_active_order(state, &ask_queue, &mut ask_position).await?;
+        
+        let mut remaining = volume;
+        while remaining > 0 {
+            let (Some((bid_level, bid_order)), Some((ask_level, ask_order))) = (&mut bid, &mut ask) else {
+                break;
+            };
+            let quantity = remaining.min(bid_order.remaining_quantity()).min(ask_order.remaining_quantity());
+            if bid_order.id > ask_order.id {
+                self.execute_fill(state, config, bid_order, ask_order, price, quantity, now).await?;
+            } else {
+                self.execute_fill(state, config, ask_order, bid_order, price, quantity, now).await?;
+            }
+            remaining -= quantity;
+            bids[*bid_level].1.total_quantity = bids[*bid_level].1.total_quantity.saturating_sub(quantity);
+            asks[*ask_level].1.total_quantity = asks[*ask_level].1.total_quantity.saturating_sub(quantity);
+            
+            if self.store_auction_fill(state, bid_order).await? {
+                bids[*bid_level].1.remove_order(bid_order);
+                bid = Self::next_active_order(state, &bid_queue, &mut bid_position).await?;
+            }
+            if let Some((ask_level, ask_order)) = &mut ask {
+                if self.store_auction_fill(state, ask_order).await? {
+                    asks[*ask_level].1.remove_order(ask_order);
+                    ask = Self::next_active_order(state, &ask_queue, &mut ask_position).await?;
+                }
+            }
+        }
+        
+        for (level_price, level) in bids {
+            if level.orders.is_empty() {
+                state.buy_levels.remove(&level_price)?;
+            } else {
+                state.buy_levels.insert(&level_price, level)?;
+            }
+        }
+        for (level_price, level) in asks {
+            if level.orders.is_empty() {
+                state.sell_levels.remove(&level_price)?;
+            } else {
+                state.sell_levels.insert(&level_price, level)?;
+            }
+        }
+        self.refresh_best_price(state, OrderSide::Buy).await?;
+        self.refresh_best_price(state, OrderSide::Sell).await?;
+        Ok(Some((price, volume - remaining)))
+    }
+    
+    /// Levels on `side` priced from `low` to `high`, best first.
+    async fn levels_between(
+        &self,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        side: OrderSide,
+        low: Price,
+        high: Price,
+    ) -> Result<Vec<(Price, PriceLevel)>, OrderBookError> {
+        let levels = match side {
+            OrderSide::Buy => &state.buy_levels,
+            OrderSide::Sell => &state.sell_levels,
+        };
+        let mut prices: Vec<Price> = levels.indices().await.map_err(|_| OrderBookError::ViewError)?
+            .into_iter()
+            .filter(|price| (low..=high).contains(price))
+            .collect();
+        prices.sort_unstable();
+        if side == OrderSide::Buy {
+            prices.reverse();
+        }
+        let mut result = Vec::new();
+        for price in prices {
+            if let Some(level) = levels.get(&price).await.map_err(|_| OrderBookError::ViewError)? {
+                result.push((price, level));
+            }
+        }
+        Ok(result)
+    }
+    
+    /// First active order in `queue` from `position` on, with the index of its level.
+    async fn next_active_order(
+        state: &OrderBookState<ContractRuntime<Self>>,
+        queue: &[(usize, OrderId)],
+        position: &mut usize,
+    ) -> Result<Option<(usize, Order)>, OrderBookError> {
+        while let Some((level, id)) = queue.get(*position) {
+            *position += 1;
+            if let Some(order) = state.orders.get(id).await.map_err(|_| OrderBookError::ViewError)?.filter(Order::is_active) {
+                return Ok(Some((*level, order)));
+            }
+        }
+        Ok(None)
+    }
+    
+    /// Stores an order after an auction fill. Returns whether it is now filled and off the book.
+    async fn store_auction_fill(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        order: &mut Order,
+    ) -> Result<bool, OrderBookError> {
+        let filled = order.is_fully_filled();
+        if filled {
+            order.status = OrderStatus::Filled;
+            let mut user_orders = state.user_orders.get(&order.user).await
+                .map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or_default();
+            user_orders.retain(|id| *id != order.id);
+            state.user_orders.insert(&order.user, user_orders)?;
+        } else {
+            order.status = OrderStatus::PartiallyFilled;
+        }
+        state.orders.insert(&order.id, order.clone())?;
+        Ok(filled)
+    }
+    
+    /// Cancels stale orders under the market's policy, from the level farthest from the reference
+    /// price inwards: at most `MAX_SWEEP_CANCELLATIONS` out of `MAX_SWEEP_INSPECTIONS` orders
+    /// looked at. Sweeps nothing without a policy or a reference price, or while no more orders
+    /// rest than the policy's threshold.
+    async fn sweep_stale_orders(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let Some(policy) = state.stale_order_policy.get() else {
+            return Ok(());
+        };
+        let now = runtime.system_time();
+        let index = state.index_price.get();
+        let Some(reference) = state.reference_price_config.get().price(&state.recent_trades.get(), index.as_ref(), now)
+        else {
+            return Ok(());
+        };
+        
+        let mut resting = 0;
+        let mut distant = Vec::new();
+        for levels in [&state.buy_levels, &state.sell_levels] {
+            for price in levels.indices().await.map_err(|_| OrderBookError::ViewError)? {
+                let Some(level) = levels.get(&price).await.map_err(|_| OrderBookError::ViewError)? else {
+                    continue;
+                };
+                resting += level.orders.len() as u64;
+                if policy.is_distant(price, reference) {
+                    distant.push((price.abs_diff(reference), level.orders));
+                }
+            }
+        }
+        if resting <= policy.min_resting_orders {
+            return Ok(());
+        }
+        distant.sort_by_key(|(distance, _)| Reverse(*distance));
+        
+        let order_ids = distant.into_iter().flat_map(|(_, order_ids)| order_ids).take(MAX_SWEEP_INSPECTIONS);
+        let mut swept = 0;
+        for order_id in order_ids {
+            if swept == MAX_SWEEP_CANCELLATIONS {
+                break;
+            }
+            let Some(order) = state.orders.get(&order_id).await.map_err(|_| OrderBookError::ViewError)? else {
+                continue;
+            };
+            let exempt = state.sweep_exempt_orders.contains_key(&order_id).await.map_err(|_| OrderBookError::ViewError)?;
+            if exempt || !order.is_active() || !policy.is_aged(order.timestamp, now) {
+                continue;
+            }
+            let (user, price, remaining) = (order.user, order.price, order.remaining_quantity());
+            self.cancel_resting_order(state, order, now).await?;
+            state.events.push_back(OrderBookEvent::StaleOrderSwept {
+                order_id,
+                user,
+                price,
+                remaining,
+                reference_price: reference,
+                timestamp: now,
+            });
+            swept += 1;
+        }
+        Ok(())
+    }
+    
+    /// Recomputes the best price on `side` after a level was removed.
+    async fn refresh_best_price(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        side: OrderSide,
+    ) -> Result<(), OrderBookError> {
+        match side {
+            OrderSide::Buy => {
+                let prices = state.buy_levels.indices().await.map_err(|_| OrderBookError::ViewError)?;
+                state.best_bid.set(prices.into_iter().max());
+            }
+            OrderSide::Sell => {
+                let prices = state.sell_levels.indices().await.map_err(|_| OrderBookError::ViewError)?;
+                state.best_ask.set(prices.into_iter().min());
+            }
+        }
+        Ok(())
+    }
+    
+    async fn deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        self.ensure_not_banned(runtime, state, user).await?;
+        // The first deposit pins the depositor's home chain unless it registered one
+        if !state.home_chains.contains_key(&user).await.map_err(|_| OrderBookError::ViewError)? {
+            state.home_chains.insert(&user, user.chain_id)?;
+        }
+        let balance_key = (user, asset.clone());
+        let current_balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?;
+        let current_balance = current_balance.unwrap_or(Amount::ZERO);
+        let new_balance = math::checked_add(current_balance, amount)?;
+        state.balances.insert(&balance_key, new_balance)?;
+        self.note_balance_change(state, user, &asset, PendingBalanceDelta::new(
+            amount, false, BalanceChangeReason::Deposit,
+        )).await
+    }
+    
+    /// Withdraws from the free balance only; unsettled proceeds wait for their trade's settlement,
+    /// and whatever the locks fall short of the open-order requirement stays for the orders.
+    async fn withdraw(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), OrderBookError> {
+        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        let balance_key = (user, asset.clone());
+        let current_balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?;
+        let current_balance = current_balance.unwrap_or(Amount::ZERO);
+        if current_balance < amount {
+            return Err(OrderBookError::InsufficientBalance { required: amount, available: current_balance });
+        }
+        let requirement = state.open_order_requirements.get(&balance_key).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        let locked = state.locked_balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        let max_withdrawable = requirement.max_withdrawable(current_balance, locked);
+        if amount > max_withdrawable {
+            return Err(OrderBookError::WithdrawalBlockedByOpenOrders {
+                asset,
+                blocking_orders: requirement.orders,
+                max_withdrawable,
+            });
+        }
+        let new_balance = current_balance - amount;
+        state.balances.insert(&balance_key, new_balance)?;
+        self.note_balance_change(state, user, &asset, PendingBalanceDelta::new(
+            amount, true, BalanceChangeReason::Withdrawal,
+        )).await
+    }
+    
+    async fn update_config(
+        &mut self,
+        _runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        min_order_size: Option<Quantity>,
+        max_order_size: Option<Quantity>,
+        tick_size: Option<Price>,
+        fee_model: Option<FeeModel>,
+        max_orders_per_level: Option<u32>,
+        max_account_orders_per_level: Option<u32>,
+        allow_unsettled_trading: Option<bool>,
+    ) -> Result<(), OrderBookError> {
+        let mut config = state.config.get();
+        if let Some(min) = min_order_size { config.min_order_size = min; }
+        if let Some(max) = max_order_size { config.max_order_size = max; }
+        if let Some(tick) = tick_size { config.tick_size = tick; }
+        if let Some(max) = max_orders_per_level { config.max_orders_per_level = max; }
+        if let Some(max) = max_account_orders_per_level { config.max_account_orders_per_level = max; }
+        if let Some(allow) = allow_unsettled_trading { config.allow_unsettled_trading = allow; }
+        if let Some(fee_model) = fee_model.filter(|fee_model| *fee_model != config.fee_model) {
+            // Resting locks were sized under the old model
+            let twaps = state.active_twap_orders.indices().await.map_err(|_| OrderBookError::ViewError)?;
+            if state.best_bid.get().is_some() || !twaps.is_empty() {
+                return Err(OrderBookError::FeeModelLocked);
+            }
+            config.fee_model = fee_model;
+        }
+        state.config.set(config);
+        Ok(())
+    }
+    
+    async fn ban_account(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        expires_at: Option<Timestamp>,
+        reason: String,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
+        
+        state.banned_accounts.insert(&account, AccountBan {
+            account,
+            banned_by: admin,
+            banned_at: now,
+            expires_at,
+            reason: reason.clone(),
+        })?;
+        state.events.push_back(OrderBookEvent::AccountBanned {
+            account,
+            banned_by: admin,
+            expires_at,
+            reason,
+            timestamp: now,
+        });
+        
+        self.cancel_banned_orders(state, account, now).await
+    }
+    
+    async fn unban_account(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        if state.banned_accounts.get(&account).await.map_err(|_| OrderBookError::ViewError)?.is_none() {
+            return Err(OrderBookError::AccountNotBanned);
+        }
+        
+        state.banned_accounts.remove(&account)?;
+        state.events.push_back(OrderBookEvent::AccountUnbanned {
+            account,
+            unbanned_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Migrates up to `MAX_MIGRATION_ITEMS` records towards the next schema version;
+    /// call again until the schema reaches `SCHEMA_VERSION`.
+    async fn migrate(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        let version = state.schema_version.get();
+        let mut cursor = state.migration_cursor.get().unwrap_or_default();
+        let completed = match version {
+            0 => self.backfill_client_order_ids(state, &mut cursor).await?,
+            1 => self.index_trades(state, &mut cursor).await?,
+            2 => self.count_level_orders(state, &mut cursor).await?,
+            3 => self.backfill_trade_stats(state, &mut cursor, runtime.system_time()).await?,
+            _ => return Err(OrderBookError::NoMigrationPending { version }),
+        };
+        
+        if completed {
+            state.schema_version.set(version + 1);
+            state.migration_cursor.set(None);
+        } else {
+            state.migration_cursor.set(Some(cursor));
+        }
+        state.events.push_back(OrderBookEvent::MigrationStep {
+            from_version: version,
+            cursor,
+            completed,
+            migrated_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Schema 0 to 1: indexes orders placed before `client_order_ids` existed by their receipts.
+    /// `cursor` counts receipts done; none are added meanwhile since placing orders is refused.
+    async fn backfill_client_order_ids(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        cursor: &mut u64,
+    ) -> Result<bool, OrderBookError> {
+        let keys = state.receipts.indices().await.map_err(|_| OrderBookError::ViewError)?;
+        for key in keys.iter().skip(*cursor as usize).take(MAX_MIGRATION_ITEMS) {
+            if let Some(receipt) = state.receipts.get(key).await.map_err(|_| OrderBookError::ViewError)? {
+                state.client_order_ids.insert(&receipt.id, key.1)?;
+            }
+            *cursor += 1;
+        }
+        Ok(*cursor as usize >= keys.len())
+    }
+    
+    /// Schema 1 to 2: moves the trades queue into `trades_by_id`. `cursor` counts trades moved;
+    /// trades recorded meanwhile go straight to the map.
+    async fn index_trades(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        cursor: &mut u64,
+    ) -> Result<bool, OrderBookError> {
+        for _ in 0..MAX_MIGRATION_ITEMS {
+            let Some(trade) = state.trades.front().await.map_err(|_| OrderBookError::ViewError)? else {
+                return Ok(true);
+            };
+            state.trades.pop_front();
+            self.store_trade(state, trade)?;
+            *cursor += 1;
+        }
+        Ok(state.trades.front().await.map_err(|_| OrderBookError::ViewError)?.is_none())
+    }
+    
+    /// Schema 2 to 3: counts the orders of levels stored before `PriceLevel` kept its counts.
+    /// `cursor` is the lowest price not yet counted on either side; cancels meanwhile keep the
+    /// counts of levels already done, and the others are counted from their ids as they stand.
+    async fn count_level_orders(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        cursor: &mut u64,
+    ) -> Result<bool, OrderBookError> {
+        let mut prices = state.buy_levels.indices().await.map_err(|_| OrderBookError::ViewError)?;
+        prices.extend(state.sell_levels.indices().await.map_err(|_| OrderBookError::ViewError)?);
+        prices.retain(|price| *price >= *cursor);
+        prices.sort_unstable();
+        prices.dedup();
+        for price in prices.iter().take(MAX_MIGRATION_ITEMS) {
+            for levels in [&mut state.buy_levels, &mut state.sell_levels] {
+                let Some(mut level) = levels.get(price).await.map_err(|_| OrderBookError::ViewError)? else {
+                    continue;
+                };
+                level.order_count = level.orders.len() as u32;
+                level.account_orders.clear();
+                for id in &level.orders {
+                    // Ids of missing orders count towards the level but no account
+                    if let Some(order) = state.orders.get(id).await.map_err(|_| OrderBookError::ViewError)? {
+                        *level.account_orders.entry(order.user).or_default() += 1;
+                    }
+                }
+                levels.insert(price, level)?;
+            }
+            *cursor = price + 1;
+        }
+        Ok(prices.len() <= MAX_MIGRATION_ITEMS)
+    }
+    
+    /// Schema 3 to 4: folds the retained trades of the last `STATS_WINDOW_HOURS` into the rolling
+    /// stats, newest first, starting below the oldest trade they already hold. `cursor` counts
+    /// trades folded; those recorded before the first call are in the stats already.
+    async fn backfill_trade_stats(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        cursor: &mut u64,
+        now: Timestamp,
+    ) -> Result<bool, OrderBookError> {
+        let mut trade_stats = state.trade_stats.get();
+        let first_retained_id = state.first_retained_trade_id.get();
+        let mut completed = false;
+        for _ in 0..MAX_MIGRATION_ITEMS {
+            let next_id = trade_stats.first_trade_id.unwrap_or(state.next_trade_id.get());
+            if next_id <= first_retained_id {
+                completed = true;
+                break;
+            }
+            let id = next_id - 1;
+            let Some(trade) = state.trades_by_id.get(&id).await.map_err(|_| OrderBookError::ViewError)? else {
+                completed = true;
+                break;
+            };
+            if !trade_stats.backfill(id, hour_of(trade.timestamp), trade.price, trade.quantity, hour_of(now)) {
+                completed = true;
+                break;
+            }
+            *cursor += 1;
+        }
+        
+        let mut stats = state.market_stats.get();
+        Self::set_window_stats(&mut stats, trade_stats.window(hour_of(now)));
+        state.market_stats.set(stats);
+        state.trade_stats.set(trade_stats);
+        Ok(completed)
+    }
+    
+    /// How a trade settles given the mirrored settlement contract limits: legs below a minimum stay
+    /// on this chain, and legs above a maximum fail unless the pair is exempt.
+    async fn settlement_status(
+        &self,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        maker: Account,
+        taker: Account,
+        maker_leg: (&String, Amount),
+        taker_leg: (&String, Amount),
+    ) -> Result<TradeSettlement, OrderBookError> {
+        let mut minimums = [(Amount::ZERO, None); 2];
+        let mut maximums = [(Amount::ZERO, None); 2];
+        for (index, (asset, amount)) in [maker_leg, taker_leg].into_iter().enumerate() {
+            minimums[index] = (amount, state.settlement_minimums.get(asset).await.map_err(|_| OrderBookError::ViewError)?);
+            maximums[index] = (amount, state.settlement_maximums.get(asset).await.map_err(|_| OrderBookError::ViewError)?);
+        }
+        if settles_internally(minimums) {
+            // The fill already moved the book's balances, and the settlement contract would reject it
+            return Ok(TradeSettlement::Internal);
+        }
+        let uncapped = state.uncapped_settlement_pairs.contains_key(&(maker, taker)).await
+            .map_err(|_| OrderBookError::ViewError)?
+            || state.uncapped_settlement_pairs.contains_key(&(taker, maker)).await
+                .map_err(|_| OrderBookError::ViewError)?;
+        if !uncapped && exceeds_settlement_maximum(maximums) {
+            return Ok(TradeSettlement::Failed { reason: "Leg above the settlement maximum".to_string() });
+        }
+        Ok(TradeSettlement::Requested)
+    }
+    
+    /// Calls the settlement application on this chain with the trade's legs and keeps the settlement
+    /// id it returns. Trades that settle internally or exceed a maximum only record that status.
+    async fn request_trade_settlement(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        trade_id: u64,
+        timeout_seconds: u64,
+    ) -> Result<(), OrderBookError> {
+        let signer = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        let trade = state.trades_by_id.get(&trade_id).await.map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::TradeNotFound { trade_id })?;
+        if signer != trade.maker && signer != trade.taker {
+            return Err(OrderBookError::Unauthorized);
+        }
+        if state.trade_settlements.get(&trade_id).await.map_err(|_| OrderBookError::ViewError)?.is_some() {
+            return Err(OrderBookError::SettlementUnavailable { reason: "Already handed to settlement".to_string() });
+        }
+        let Some((application_id, settlement_chain)) = *state.settlement_application.get() else {
+            return Err(OrderBookError::SettlementUnavailable {
+                reason: "No settlement application configured".to_string(),
+            });
+        };
+        
+        let config = state.config.get();
+        let maker_chain = self.home_chain(runtime, state, trade.maker).await?;
+        let taker_chain = self.home_chain(runtime, state, trade.taker).await?;
+        let Message::SettlementRequest {
+            trade_id, maker, taker, maker_asset, taker_asset, maker_amount, taker_amount, maker_chain, taker_chain,
+        } = trade.settlement_request(&config, maker_chain, taker_chain)?
+        else {
+            unreachable!("settlement_request builds a SettlementRequest");
+        };
+        let status = self.settlement_status(
+            state, maker, taker, (&maker_asset, maker_amount), (&taker_asset, taker_amount),
+        ).await?;
+        if status != TradeSettlement::Requested {
+            self.resolve_unsettled_proceeds(state, trade_id, &status).await?;
+            state.trade_settlements.insert(&trade_id, status)?;
+            return Ok(());
+        }
+        
+        // Fees were taken on the book, so the legs are already net of them. The application's
+        // instance on this chain relays the request when it settles on another chain.
+        let operation = SettlementOperation::RequestSettlement {
+            trade_id, maker, taker, maker_asset, taker_asset, maker_amount, taker_amount, timeout_seconds,
+            fees: None,
+            maker_chain: Some(maker_chain),
+            taker_chain: Some(taker_chain),
+            settlement_chain: Some(settlement_chain),
+        };
+        let response = runtime.call_application(true, application_id.with_abi::<SettlementAbi>(), &operation);
+        match response {
+            SettlementResponse::SettlementInitiated { settlement_id } => {
+                state.trade_settlement_ids.insert(&trade_id, settlement_id)?;
+            }
+            // The settlement chain assigns the id
+            SettlementResponse::SettlementRequestForwarded => {}
+            _ => {
+                return Err(OrderBookError::SettlementUnavailable {
+                    reason: "Settlement application returned no settlement id".to_string(),
+                });
+            }
+        }
+        state.trade_settlements.insert(&trade_id, status)?;
+        Ok(())
+    }
+    
+    /// Takes the settlement application's rejection of a trade waiting on its settlement, if one
+    /// arrived, and fails the trade.
+    async fn sync_trade_settlement(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        trade_id: u64,
+    ) -> Result<(), OrderBookError> {
+        let Some((application_id, _)) = *state.settlement_application.get() else {
+            return Err(OrderBookError::SettlementUnavailable {
+                reason: "No settlement application configured".to_string(),
+            });
+        };
+        // A final status is left alone
+        let current = state.trade_settlements.get(&trade_id).await.map_err(|_| OrderBookError::ViewError)?;
+        if current != Some(TradeSettlement::Requested) {
+            return Ok(());
+        }
+        let operation = SettlementOperation::TakeSettlementRejection { trade_id };
+        let response = runtime.call_application(true, application_id.with_abi::<SettlementAbi>(), &operation);
+        let SettlementResponse::SettlementRejection { reason_code: Some(reason_code) } = response else {
+            return Ok(());
+        };
+        let status = TradeSettlement::Failed {
+            reason: format!("Rejected by the settlement contract: {reason_code:?}"),
+        };
+        self.resolve_unsettled_proceeds(state, trade_id, &status).await?;
+        state.trade_settlements.insert(&trade_id, status)?;
+        state.events.push_back(OrderBookEvent::TradeSettlementRejected {
+            trade_id,
+            reason_code,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Cancels up to `MAX_BAN_CANCELLATIONS` of the account's orders; call again while any remain.
+    async fn cancel_banned_orders(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let (cancelled, remaining) = self.cancel_account_orders(state, account, now).await?;
+        state.events.push_back(OrderBookEvent::BannedOrdersCancelled {
+            account,
+            order_ids: cancelled,
+            remaining,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    
+    /// Cancels up to `MAX_BAN_CANCELLATIONS` of the account's orders for the settlement contract;
+    /// the rest stay recorded for `CancelForcedOrders`.
+    async fn force_cancel_orders(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        reason: String,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let (cancelled, remaining) = self.cancel_account_orders(state, account, now).await?;
+        if remaining == 0 {
+            state.forced_cancellations.remove(&account)?;
+        } else {
+            state.forced_cancellations.insert(&account, reason.clone())?;
+        }
+        state.events.push_back(OrderBookEvent::OrdersForceCancelled {
+            account,
+            order_ids: cancelled,
+            remaining,
+            reason,
+            timestamp: now,
+        });
+        Ok(())
+    }
+    
+    /// Cancels the next `MAX_BAN_CANCELLATIONS` orders indexed for the account.
+    /// Returns the cancelled ids and how many index entries are left.
+    async fn cancel_account_orders(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        now: Timestamp,
+    ) -> Result<(Vec<OrderId>, usize), OrderBookError> {
+        let order_ids = state.user_orders.get(&account).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        let batch: Vec<OrderId> = order_ids.iter().take(MAX_BAN_CANCELLATIONS).copied().collect();
+        
+        let mut cancelled = Vec::new();
+        for order_id in &batch {
+            match state.orders.get(order_id).await.map_err(|_| OrderBookError::ViewError)? {
+                Some(order) if order.is_active() => {
+                    self.cancel_resting_order(state, order, now).await?;
+                    cancelled.push(*order_id);
+                }
+                // Filled or already cancelled: only the index entry is left
+                _ => {}
+            }
+        }
+        
+        let remaining: Vec<OrderId> = order_ids.into_iter().skip(batch.len()).collect();
+        let remaining_count = remaining.len();
+        if remaining.is_empty() {
+            state.user_orders.remove(&account)?;
+        } else {
+            state.user_orders.insert(&account, remaining)?;
+        }
+        Ok((cancelled, remaining_count))
+    }
+    
+    async fn ensure_not_banned(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+    ) -> Result<(), OrderBookError> {
+        match state.banned_accounts.get(&account).await.map_err(|_| OrderBookError::ViewError)? {
+            Some(ban) if ban.is_active(runtime.system_time()) => {
+                Err(OrderBookError::AccountBanned { expires_at: ban.expires_at })
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    async fn ensure_not_cooling_down(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+    ) -> Result<(), OrderBookError> {
+        match state.mmp_trackers.get(&account).await.map_err(|_| OrderBookError::ViewError)? {
+            Some(tracker) if tracker.is_cooling_down(runtime.system_time()) => {
+                Err(OrderBookError::MmpCooldown { until: tracker.cooldown_until.unwrap_or_default() })
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    async fn register_market_maker(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        max_spread_bps: u64,
+        min_uptime_bps: u64,
+        min_size: Quantity,
+    ) -> Result<(), OrderBookError> {
+        self.require_admin(runtime, state)?;
+        if max_spread_bps > math::BPS_DENOMINATOR || min_uptime_bps > math::BPS_DENOMINATOR {
+            return Err(OrderBookError::InvalidMarketMakerTerms { reason: "Basis points above 100%".to_string() });
+        }
+        if min_size == 0 {
+            return Err(OrderBookError::InvalidMarketMakerTerms { reason: "Minimum size must be positive".to_string() });
+        }
+        
+        let existing = state.dmm_agreements.get(&account).await.map_err(|_| OrderBookError::ViewError)?;
+        if existing.is_none() {
+            let count = state.dmm_agreements.indices().await.map_err(|_| OrderBookError::ViewError)?.len();
+            if count >= MAX_MARKET_MAKERS {
+                return Err(OrderBookError::TooManyMarketMakers { maximum: MAX_MARKET_MAKERS });
+            }
+        }
+        
+        // Close out the time under the old terms, then start this epoch's record afresh
+        self.sample_market_makers(runtime, state).await?;
+        let now = runtime.system_time();
+        let agreement = DmmAgreement {
+            account,
+            max_spread_bps,
+            min_uptime_bps,
+            min_size,
+            registered_at: existing.map_or(now, |existing| existing.registered_at),
+        };
+        let epoch = state.dmm_epoch.get();
+        let stats = match state.dmm_stats.get(&(epoch, account)).await.map_err(|_| OrderBookError::ViewError)? {
+            Some(stats) => DmmEpochStats { terms: agreement.clone(), ..stats },
+            None => DmmEpochStats::new(agreement.clone(), now),
+        };
+        state.dmm_stats.insert(&(epoch, account), stats)?;
+        state.dmm_agreements.insert(&account, agreement)?;
+        
+        self.sample_market_makers(runtime, state).await
+    }
+    
+    /// Records every registered market maker's in-band quote sizes against the current book.
+    async fn sample_market_makers(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let makers = state.dmm_agreements.indices().await.map_err(|_| OrderBookError::ViewError)?;
+        if makers.is_empty() {
+            return Ok(());
+        }
+        let now = runtime.system_time();
+        let epoch = state.dmm_epoch.get();
+        let top = state.best_bid.get().zip(state.best_ask.get());
+        
+        for account in makers {
+            let Some(agreement) = state.dmm_agreements.get(&account).await.map_err(|_| OrderBookError::ViewError)? else {
+                continue;
+            };
+            let band = top.and_then(|(bid, ask)| quoting_band(bid, ask, agreement.max_spread_bps));
+            let (bid_size, ask_size) = match band {
+                Some(band) => self.quoted_sizes(state, account, band).await?,
+                None => (0, 0),
+            };
+            
+            let mut stats = state.dmm_stats.get(&(epoch, account)).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or_else(|| DmmEpochStats::new(agreement, state.dmm_epoch_started_at.get()));
+            stats.record(now, bid_size, ask_size);
+            state.dmm_stats.insert(&(epoch, account), stats)?;
+        }
+        Ok(())
+    }
+    
+    /// Remaining size of the maker's resting bids and asks inside `band`
+    async fn quoted_sizes(
+        &self,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+        (min_bid, max_ask): (Price, Price),
+    ) -> Result<(Quantity, Quantity), OrderBookError> {
+        let order_ids = state.user_orders.get(&account).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        let (mut bid_size, mut ask_size) = (0u64, 0u64);
+        for order_id in order_ids {
+            let Some(order) = state.orders.get(&order_id).await.map_err(|_| OrderBookError::ViewError)? else {
+                continue;
+            };
+            if !order.is_active() {
+                continue;
+            }
+            match order.side {
+                OrderSide::Buy if order.price >= min_bid => bid_size = bid_size.saturating_add(order.remaining_quantity()),
+                OrderSide::Sell if order.price <= max_ask => ask_size = ask_size.saturating_add(order.remaining_quantity()),
+                _ => {}
+            }
+        }
+        Ok((bid_size, ask_size))
+    }
+    
+    async fn close_dmm_epoch(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let admin = self.require_admin(runtime, state)?;
+        // Credit everything up to the close
+        self.sample_market_makers(runtime, state).await?;
+        
+        let now = runtime.system_time();
+        let epoch = state.dmm_epoch.get();
+        state.dmm_epochs.insert(&epoch, DmmEpoch {
+            epoch,
+            started_at: state.dmm_epoch_started_at.get(),
+            closed_at: Some(now),
+        })?;
+        
+        for account in state.dmm_agreements.indices().await.map_err(|_| OrderBookError::ViewError)? {
+            let Some(agreement) = state.dmm_agreements.get(&account).await.map_err(|_| OrderBookError::ViewError)? else {
+                continue;
+            };
+            let next = match state.dmm_stats.get(&(epoch, account)).await.map_err(|_| OrderBookError::ViewError)? {
+                Some(stats) => stats.carry_over(agreement, now),
+                None => DmmEpochStats::new(agreement, now),
+            };
+            state.dmm_stats.insert(&(epoch + 1, account), next)?;
+        }
+        
+        state.dmm_epoch.set(epoch + 1);
+        state.dmm_epoch_started_at.set(now);
+        state.events.push_back(OrderBookEvent::DmmEpochClosed { epoch, closed_by: admin, timestamp: now });
+        Ok(())
+    }
+    
+    async fn place_twap_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        side: OrderSide,
+        total_quantity: Quantity,
+        duration_seconds: u64,
+        slice_count: u32,
+        limit_price: Option<Price>,
+    ) -> Result<(), OrderBookError> {
+        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        self.ensure_not_banned(runtime, state, user).await?;
+        self.ensure_not_cooling_down(runtime, state, user).await?;
+        let config = state.config.get();
+        if !config.is_active {
+            return Err(OrderBookError::MarketClosed);
+        }
+        // Slices trade immediately, so the parent is a taker order for the phase
+        state.market_phase.get().check_order(OrderType::Market, TimeInForce::IOC)?;
+        if slice_count == 0 || slice_count > MAX_TWAP_SLICES {
+            return Err(OrderBookError::InvalidOrder {
+                reason: format!("TWAP orders have 1 to {} slices", MAX_TWAP_SLICES),
+            });
+        }
+        if limit_price.is_none() && side == OrderSide::Buy {
+            return Err(OrderBookError::InvalidOrder {
+                reason: "Market TWAP buys need a limit price to lock against".to_string(),
+            });
+        }
+        let (order_type, price) = match limit_price {
+            Some(price) => (OrderType::Limit, price),
+            None => (OrderType::Market, 0),
+        };
+        validate_order(&config, order_type, price, total_quantity / slice_count as Quantity, TimeInForce::IOC)?;
+        
+        // Sells lock their base quantity whatever the price
+        let (asset, amount) = order_lock(&config, side, price, total_quantity)?;
+        self.lock_balance(state, user, asset, amount).await?;
+        
+        let now = runtime.system_time();
+        let twap_id = state.next_twap_id.get();
+        state.next_twap_id.set(twap_id + 1);
+        let mut twap = TwapOrder {
+            id: twap_id,
+            user,
+            side,
+            limit_price,
+            total_quantity,
+            filled_quantity: 0,
+            slice_count,
+            slices_released: 0,
+            duration_seconds,
+            started_at: now,
+            last_released_at: None,
+            locked: amount,
+            child_order_ids: Vec::new(),
+            status: TwapStatus::Active,
+        };
+        state.active_twap_orders.insert(&twap_id, ())?;
+        self.release_twap_slice(state, &config, &mut twap, now).await?;
+        state.twap_orders.insert(&twap_id, twap)?;
+        Ok(())
+    }
+    
+    async fn cancel_twap_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        twap_id: u64,
+    ) -> Result<(), OrderBookError> {
+        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        let mut twap = state.twap_orders.get(&twap_id).await.map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::TwapOrderNotFound { twap_id })?;
+        if twap.user != user {
+            return Err(OrderBookError::Unauthorized);
+        }
+        if twap.status != TwapStatus::Active {
+            return Err(OrderBookError::TwapOrderNotActive { status: twap.status });
+        }
+        let config = state.config.get();
+        self.finish_twap_order(state, &config, &mut twap, TwapStatus::Cancelled).await?;
+        state.twap_orders.insert(&twap_id, twap)?;
+        Ok(())
+    }
+    
+    async fn process_twap_orders(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<(), OrderBookError> {
+        let config = state.config.get();
+        // Due slices wait for continuous trading and catch up one per block after it resumes
+        if !config.is_active || state.market_phase.get() != MarketPhase::Continuous {
+            return Ok(());
+        }
+        let now = runtime.system_time();
+        let mut released = 0;
+        for twap_id in state.active_twap_orders.indices().await.map_err(|_| OrderBookError::ViewError)? {
+            if released == MAX_TWAP_RELEASES {
+                break;
+            }
+            let Some(mut twap) = state.twap_orders.get(&twap_id).await.map_err(|_| OrderBookError::ViewError)? else {
+                continue;
+            };
+            if !twap.is_due(now) {
+                continue;
+            }
+            // Slices of a banned account wait for the ban to end
+            let ban = state.banned_accounts.get(&twap.user).await.map_err(|_| OrderBookError::ViewError)?;
+            if ban.map_or(false, |ban| ban.is_active(now)) {
+                continue;
+            }
+            self.release_twap_slice(state, &config, &mut twap, now).await?;
+            state.twap_orders.insert(&twap_id, twap)?;
+            released += 1;
+        }
+        Ok(())
+    }
+    
+    /// Places the next slice as an IOC child order, funded from the parent's lock. The child's
+    /// unfilled part goes back to the parent, so the next slice catches up on it.
+    async fn release_twap_slice(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        twap: &mut TwapOrder,
+        now: Timestamp,
+    ) -> Result<(), OrderBookError> {
+        let quantity = twap.target_after(twap.slices_released).saturating_sub(twap.filled_quantity);
+        twap.slices_released += 1;
+        twap.last_released_at = Some(now);
+        
+        if quantity > 0 {
+            let order_id = state.next_order_id.get();
+            state.next_order_id.set(order_id + 1);
+            let mut child = Order {
+                id: order_id,
+                user: twap.user,
+                side: twap.side,
+                order_type: if twap.limit_price.is_some() { OrderType::Limit } else { OrderType::Market },
+                price: twap.limit_price.unwrap_or(0),
+                quantity,
+                filled_quantity: 0,
+                status: OrderStatus::Pending,
+                time_in_force: TimeInForce::IOC,
+                timestamp: now,
+                expires_at: None,
+            };
+            let asset = config.payment_asset(twap.side).to_string();
+            let (_, share) = order_lock(config, child.side, child.price, quantity)?;
+            twap.locked = math::checked_sub(twap.locked, share)?;
+            // Limit children draw on the lock as they fill; market children pay from the free balance
+            if child.order_type == OrderType::Market {
+                self.unlock_balance(state, twap.user, asset.clone(), share).await?;
+            } else {
+                self.add_open_requirement(state, twap.user, asset.clone(), share).await?;
+            }
+            
+            self.record_order_event(state, order_id, OrderHistoryEvent::placed(&child), now).await?;
+            self.match_order(state, config, &mut child, 0, None, now).await?;
+            
+            let unused = match child.order_type {
+                OrderType::Market => {
+                    let (_, unused) = order_lock(config, child.side, child.price, child.remaining_quantity())?;
+                    self.lock_balance(state, twap.user, asset, unused).await?;
+                    unused
+                }
+                _ => {
+                    // The unfilled rest goes back to the parent, which is not an open order
+                    let (_, unused) = locked_remaining(config, &child)?;
+                    if !child.is_fully_filled() {
+                        self.release_open_requirement(state, twap.user, asset, unused, true).await?;
+                    }
+                    unused
+                }
+            };
+            twap.locked = math::checked_add(twap.locked, unused)?;
+            twap.filled_quantity += child.filled_quantity;
+            if child.is_fully_filled() {
+                child.status = OrderStatus::Filled;
+            } else {
+                child.status = OrderStatus::Cancelled;
+                let remaining = child.remaining_quantity();
+                self.record_order_event(state, order_id, OrderHistoryEvent::Cancelled { remaining }, now).await?;
+            }
+            twap.child_order_ids.push(order_id);
+            state.orders.insert(&order_id, child)?;
+        }
+        
+        if twap.slices_released == twap.slice_count {
+            self.finish_twap_order(state, config, twap, TwapStatus::Completed).await?;
+        }
+        Ok(())
+    }
+    
+    /// Releases what is left of a TWAP order's lock and retires it.
+    async fn finish_twap_order(
+        &mut self,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        config: &MarketConfig,
+        twap: &mut TwapOrder,
+        status: TwapStatus,
+    ) -> Result<(), OrderBookError> {
+        let asset = config.payment_asset(twap.side).to_string();
+        self.unlock_balance(state, twap.user, asset, twap.locked).await?;
+        twap.locked = Amount::ZERO;
+        twap.status = status;
+        state.active_twap_orders.remove(&twap.id)?;
+        Ok(())
+    }
+    
+    /// Chain `account` escrows on: its registered home chain, or this one
+    async fn home_chain(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &OrderBookState<ContractRuntime<Self>>,
+        account: Account,
+    ) -> Result<ChainId, OrderBookError> {
+        let home_chain = state.home_chains.get(&account).await.map_err(|_| OrderBookError::ViewError)?;
+        Ok(home_chain.unwrap_or(runtime.chain_id()))
+    }
+    
+    /// Fails with `error` unless the signer records its rejections. If it does, whatever the
+    /// placement changed before failing is rolled back, the rejection is recorded in its place and
+    /// the operation succeeds.
+    async fn reject_placement(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut OrderBookState<ContractRuntime<Self>>,
-        asset: String,
-        amount: Amount,
+        error: OrderBookError,
+        order: RejectedOrder,
+    ) -> Result<(), OrderBookError> {
+        let Some(account) = runtime.authenticated_signer() else {
+            return Err(error);
+        };
+        if !state.rejection_recording.contains_key(&account).await.map_err(|_| OrderBookError::ViewError)? {
+            return Err(error);
+        }
+        state.rollback();
+        
+        let rejection = OrderRejection::new(&error, order, runtime.system_time());
+        let mut rejections = state.recent_rejections.get(&account).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        rejection::push_rejection(&mut rejections, rejection.clone());
+        state.recent_rejections.insert(&account, rejections)?;
+        state.events.push_back(OrderBookEvent::OrderRejected { account, rejection });
+        Ok(())
+    }
+    
+    /// Sends the caller's order to a registered sibling market, with the caller's authentication
+    async fn forward_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        market_chain: ChainId,
+        spec: ForwardedOrderSpec,
     ) -> Result<(), OrderBookError> {
         let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?;
-        let current_balance = current_balance.unwrap_or(Amount::ZERO);
-        let new_balance = current_balance + amount;
-        state.balances.insert(&balance_key, new_balance)?;
+        let market = state.sibling_markets.get(&market_chain).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .ok_or(OrderBookError::UnknownSiblingMarket { chain_id: market_chain })?;
+        
+        let forward_id = state.next_forward_id.get();
+        state.next_forward_id.set(forward_id + 1);
+        let forwarded = ForwardedOrder {
+            forward_id,
+            user,
+            market_chain,
+            spec,
+            outcome: ForwardOutcome::Pending,
+            forwarded_at: runtime.system_time(),
+            acknowledged_at: None,
+        };
+        state.forwarded_orders.insert(&forward_id, forwarded)?;
+        let mut forward_ids = state.user_forwarded_orders.get(&user).await
+            .map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or_default();
+        forward_ids.push(forward_id);
+        state.user_forwarded_orders.insert(&user, forward_ids)?;
+        
+        runtime
+            .prepare_message(MessageEnvelope::seal(&Message::ForwardOrder {
+                forward_id,
+                user,
+                base_asset: market.base_asset,
+                quote_asset: market.quote_asset,
+                spec,
+            }))
+            .with_authentication()
+            .send_to(market_chain);
+        Ok(())
+    }
+    
+    /// Places an order forwarded by a sibling market under `user`, who must have signed it, and
+    /// returns its id. Checked against this market's own pair and config like any placement.
+    async fn accept_forwarded_order(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        signer: Option<Account>,
+        user: Account,
+        base_asset: &str,
+        quote_asset: &str,
+        spec: ForwardedOrderSpec,
+    ) -> Result<OrderId, OrderBookError> {
+        if signer != Some(user) {
+            return Err(OrderBookError::Unauthorized);
+        }
+        if state.migration_cursor.get().is_some() {
+            return Err(OrderBookError::MigrationInProgress);
+        }
+        forwarding::check_pair(&state.config.get(), base_asset, quote_asset)?;
+        
+        // The placement takes the next order id
+        let order_id = state.next_order_id.get();
+        self.place_order(
+            runtime, state, signer, spec.side, spec.order_type, spec.price, spec.quantity, spec.time_in_force,
+            spec.expires_at, spec.require_full_fill, spec.min_fill_quantity, None, None, false,
+        ).await?;
+        state.book_sequence.set(state.book_sequence.get() + 1);
+        self.sample_market_makers(runtime, state).await?;
+        Ok(order_id)
+    }
+    
+    /// Publishes the index price, checked against the trade-derived price unless the admin
+    /// declares an emergency
+    fn update_index_price(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut OrderBookState<ContractRuntime<Self>>,
+        price: Price,
+        emergency: bool,
+    ) -> Result<(), OrderBookError> {
+        let signer = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        if state.admin.get().is_some_and(|admin| admin != signer) {
+            if state.price_oracle.get() != Some(signer) {
+                return Err(OrderBookError::NotPriceOracle);
+            }
+            if emergency {
+                return Err(OrderBookError::NotAdmin);
+            }
+        }
+        if price == 0 {
+            return Err(OrderBookError::InvalidReferencePrice { reason: "Index price must be positive".to_string() });
+        }
+        
+        let config = state.reference_price_config.get();
+        let recent_trades = state.recent_trades.get();
+        let trade_price = recent_trades.weighted_average(config.window_trades);
+        if !emergency && !config.within_deviation(&recent_trades, price) {
+            return Err(OrderBookError::IndexPriceDeviation {
+                price,
+                trade_price: trade_price.unwrap_or_default(),
+                max_deviation_bps: config.max_index_deviation_bps,
+            });
+        }
+        
+        let index = IndexPrice { price, updated_at: runtime.system_time(), updated_by: signer, emergency };
+        state.index_price.set(Some(index));
+        state.events.push_back(OrderBookEvent::IndexPriceUpdated { index, trade_price });
         Ok(())
     }
     
-    async fn withdraw(
-        &mut self,
-        runtime: &mut ContractRuntime<Self>,
-        state: &mut OrderBookState<ContractRuntime<Self>>,
-        asset: String,
-        amount: Amount,
-    ) -> Result<(), OrderBookError> {
-        let user = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await.map_err(|_| OrderBookError::ViewError)?;
-        let current_balance = current_balance.unwrap_or(Amount::ZERO);
-        if current_balance < amount {
-            return Err(OrderBookError::InsufficientBalance { required: amount, available: current_balance });
+    fn require_admin(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &OrderBookState<ContractRuntime<Self>>,
+    ) -> Result<Account, OrderBookError> {
+        let signer = runtime.authenticated_signer().ok_or(OrderBookError::Unauthorized)?;
+        match state.admin.get() {
+            Some(admin) if admin == signer => Ok(signer),
+            _ => Err(OrderBookError::NotAdmin),
+        }
+    }
+}
+
+/// Single price that uncrosses the book: of the level prices, the one executing the most volume,
+/// then the one leaving the smallest imbalance, then the lowest. None when nothing crosses.
+pub fn auction_clearing_price(bids: &[(Price, Quantity)], asks: &[(Price, Quantity)]) -> Option<(Price, Quantity)> {
+    let mut best: Option<(u128, u128, Price)> = None;
+    for &(price, _) in bids.iter().chain(asks) {
+        let demand: u128 = bids.iter().filter(|(bid, _)| *bid >= price).map(|(_, quantity)| *quantity as u128).sum();
+        let supply: u128 = asks.iter().filter(|(ask, _)| *ask <= price).map(|(_, quantity)| *quantity as u128).sum();
+        let volume = demand.min(supply);
+        let imbalance = demand.abs_diff(supply);
+        let better = best.map_or(true, |(best_volume, best_imbalance, best_price)| {
+            (volume, Reverse(imbalance), Reverse(price)) > (best_volume, Reverse(best_imbalance), Reverse(best_price))
+        });
+        if volume > 0 && better {
+            best = Some((volume, imbalance, price));
+        }
+    }
+    best.map(|(volume, _, price)| (price, u64::try_from(volume).unwrap_or(u64::MAX)))
+}
+
+/// Lowest bid and highest ask within `max_spread_bps` of mid, or None for a crossed book.
+pub fn quoting_band(best_bid: Price, best_ask: Price, max_spread_bps: u64) -> Option<(Price, Price)> {
+    if best_bid > best_ask {
+        return None;
+    }
+    let mid = (best_bid as u128 + best_ask as u128) / 2;
+    let distance = math::fee_from_bps(mid, max_spread_bps).ok()?;
+    Some(((mid - distance) as Price, (mid + distance).min(Price::MAX as u128) as Price))
+}
+
+/// Static checks on a new order against the market configuration.
+pub fn validate_order(
+    config: &MarketConfig,
+    order_type: OrderType,
+    price: Price,
+    quantity: Quantity,
+    time_in_force: TimeInForce,
+) -> Result<(), OrderBookError> {
+    if quantity < config.min_order_size {
+        return Err(OrderBookError::BelowMinimumSize { size: quantity, minimum: config.min_order_size });
+    }
+    if quantity > config.max_order_size {
+        return Err(OrderBookError::AboveMaximumSize { size: quantity, maximum: config.max_order_size });
+    }
+    match order_type {
+        OrderType::Limit => {
+            if price == 0 {
+                return Err(OrderBookError::InvalidOrder { reason: "Limit price must be positive".to_string() });
+            }
+            if config.tick_size > 0 && price % config.tick_size != 0 {
+                return Err(OrderBookError::InvalidTickSize);
+            }
+        }
+        OrderType::Market => {
+            if time_in_force == TimeInForce::PostOnly {
+                return Err(OrderBookError::InvalidOrder { reason: "Market orders cannot be post-only".to_string() });
+            }
+        }
+        OrderType::StopLoss { .. } | OrderType::TakeProfit { .. } => {
+            return Err(OrderBookError::InvalidOrder { reason: "Stop orders are not supported yet".to_string() });
+        }
+    }
+    Ok(())
+}
+
+/// Checks an order's minimum fill, which must be positive and at most the order quantity
+pub fn check_min_fill(min_fill_quantity: Option<Quantity>, quantity: Quantity) -> Result<(), OrderBookError> {
+    if min_fill_quantity.map_or(false, |min_fill| min_fill == 0 || min_fill > quantity) {
+        return Err(OrderBookError::InvalidOrder {
+            reason: "Minimum fill must be positive and at most the order quantity".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `level`, the level `order` is to rest at, has room for it under the market's
+/// caps on orders per price, in total and per account
+pub fn check_level_caps(config: &MarketConfig, level: &PriceLevel, order: &Order) -> Result<(), OrderBookError> {
+    let maximum = config.max_orders_per_level;
+    if maximum > 0 && level.order_count >= maximum {
+        return Err(OrderBookError::PriceLevelFull { price: order.price, maximum });
+    }
+    let maximum = config.max_account_orders_per_level;
+    if maximum > 0 && level.account_order_count(&order.user) >= maximum {
+        return Err(OrderBookError::AccountLevelOrderLimit { price: order.price, maximum });
+    }
+    Ok(())
+}
+
+/// Status of a newly placed order once matched: filled, resting, or cancelled when market or IOC.
+/// All-or-nothing orders (FOK, market orders requiring a full fill) fail instead of a partial fill.
+pub fn placement_status(order: &Order, require_full_fill: bool) -> Result<OrderStatus, OrderBookError> {
+    if order.remaining_quantity() == 0 {
+        return Ok(OrderStatus::Filled);
+    }
+    let all_or_nothing = match order.order_type {
+        OrderType::Market => require_full_fill,
+        _ => order.time_in_force == TimeInForce::FOK,
+    };
+    if all_or_nothing {
+        return Err(OrderBookError::NoLiquidity { requested: order.quantity, available: order.filled_quantity });
+    }
+    Ok(match (order.order_type, order.time_in_force) {
+        (OrderType::Market, _) | (_, TimeInForce::IOC) => OrderStatus::Cancelled,
+        _ if order.filled_quantity > 0 => OrderStatus::PartiallyFilled,
+        _ => OrderStatus::Open,
+    })
+}
+
+/// Whether a trade stays on the order book chain because a leg is below its settlement minimum.
+pub fn settles_internally(legs: [(Amount, Option<Amount>); 2]) -> bool {
+    legs.iter().any(|(amount, minimum)| minimum.map_or(false, |minimum| *amount < minimum))
+}
+
+/// Whether a `ForceCancelOrders` message came from the configured settlement application on its
+/// chain.
+/// Nothing is accepted while no settlement application is configured.
+pub fn is_settlement_origin<A: PartialEq>(
+    settlement: Option<(A, ChainId)>,
+    caller: Option<A>,
+    origin: Option<ChainId>,
+) -> bool {
+    settlement.is_some_and(|(application_id, chain_id)| {
+        caller == Some(application_id) && origin == Some(chain_id)
+    })
+}
+
+/// Whether the settlement contract would reject a trade for a leg above its settlement maximum.
+pub fn exceeds_settlement_maximum(legs: [(Amount, Option<Amount>); 2]) -> bool {
+    legs.iter().any(|(amount, maximum)| maximum.map_or(false, |maximum| *amount > maximum))
+}
+
+/// Asset and amount an order of `quantity` at `price` locks: quote for bids, rounded up, base for asks.
+/// Under `FeeInQuoteAsset` bids also lock the fee at the higher of the maker and taker rates.
+pub fn order_lock(
+    config: &MarketConfig,
+    side: OrderSide,
+    price: Price,
+    quantity: Quantity,
+) -> Result<(String, Amount), MathError> {
+    let amount = match side {
+        OrderSide::Buy => {
+            let quote = math::quote_lock(price, quantity)?;
+            match config.fee_model {
+                FeeModel::FeeInReceivedAsset => quote,
+                FeeModel::FeeInQuoteAsset => {
+                    let fee = math::amount_fee(quote, config.maker_fee_bps.max(config.taker_fee_bps))?;
+                    math::checked_add(quote, fee)?
+                }
+            }
+        }
+        OrderSide::Sell => math::fixed_to_amount(quantity as u128)?,
+    };
+    Ok((config.payment_asset(side).to_string(), amount))
+}
+
+/// Quote budget of an order placed with `PlaceQuoteOrder`, drawn down fill by fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteBudget {
+    pub remaining: Amount,
+    pub max_price: Option<Price>,
+}
+
+/// Most base a buy at `price` can take for `budget`, the taker fee included when it is charged
+/// in quote. Rounds down, so the fill never costs more than the budget.
+pub fn affordable_quantity(config: &MarketConfig, price: Price, budget: Amount) -> Result<Quantity, MathError> {
+    let budget = math::amount_to_fixed(budget)?;
+    let quote = if config.fee_asset(OrderSide::Buy) == config.payment_asset(OrderSide::Buy) {
+        let denominator = math::BPS_DENOMINATOR as u128;
+        budget.checked_mul(denominator).ok_or(MathError::Overflow)? / (denominator + config.taker_fee_bps as u128)
+    } else {
+        budget
+    };
+    math::quantity_for_quote(price, quote)
+}
+
+/// Remaining quantity once `reduce_by` is cut from `remaining`, or None when nothing or less
+/// than `minimum` would be left
+pub fn reduced_remaining(remaining: Quantity, reduce_by: Quantity, minimum: Quantity) -> Option<Quantity> {
+    remaining.checked_sub(reduce_by).filter(|left| *left > 0 && *left >= minimum)
+}
+
+/// Lock still held by a limit order. Taken as a difference of cumulative locks so that the
+/// per-fill draws in `lock_consumed` add up to exactly the initial lock, leaving no dust.
+pub fn locked_remaining(config: &MarketConfig, order: &Order) -> Result<(String, Amount), MathError> {
+    let (asset, total) = order_lock(config, order.side, order.price, order.quantity)?;
+    let (_, filled) = order_lock(config, order.side, order.price, order.filled_quantity)?;
+    Ok((asset, math::checked_sub(total, filled)?))
+}
+
+/// What each side of a fill pays and receives, fees included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FillAmounts {
+    pub taker_pays: Amount,
+    pub taker_receives: Amount,
+    pub taker_fee: Amount,
+    pub maker_pays: Amount,
+    pub maker_receives: Amount,
+    pub maker_fee: Amount,
+}
+
+/// Splits a fill of `quantity` at `price` between taker, maker and fees. A fee in the asset a side
+/// pays comes on top of its payment; a fee in the asset it receives comes out of its proceeds.
+pub fn fill_amounts(
+    config: &MarketConfig,
+    taker_side: OrderSide,
+    price: Price,
+    quantity: Quantity,
+) -> Result<FillAmounts, MathError> {
+    let base = math::fixed_to_amount(quantity as u128)?;
+    let quote = math::quote_credit(price, quantity)?;
+    // The buyer gives quote for base, the seller the reverse
+    let (taker_gives, maker_gives) = match taker_side {
+        OrderSide::Buy => (quote, base),
+        OrderSide::Sell => (base, quote),
+    };
+    let (taker_pays, taker_receives, taker_fee) =
+        charge_fee(config, taker_side, taker_gives, maker_gives, config.taker_fee_bps)?;
+    let (maker_pays, maker_receives, maker_fee) =
+        charge_fee(config, taker_side.opposite(), maker_gives, taker_gives, config.maker_fee_bps)?;
+    Ok(FillAmounts { taker_pays, taker_receives, taker_fee, maker_pays, maker_receives, maker_fee })
+}
+
+/// Paid, received and fee for the side that `gives` one leg and `gets` the other.
+fn charge_fee(
+    config: &MarketConfig,
+    side: OrderSide,
+    gives: Amount,
+    gets: Amount,
+    bps: u64,
+) -> Result<(Amount, Amount, Amount), MathError> {
+    if config.fee_asset(side) == config.payment_asset(side) {
+        let fee = math::amount_fee(gives, bps)?;
+        Ok((math::checked_add(gives, fee)?, gets, fee))
+    } else {
+        let fee = math::amount_fee(gets, bps)?;
+        Ok((gives, math::checked_sub(gets, fee)?, fee))
+    }
+}
+
+/// Part of a limit order's lock released by filling the next `quantity`.
+pub fn lock_consumed(config: &MarketConfig, order: &Order, quantity: Quantity) -> Result<Amount, MathError> {
+    let filled_after = order.filled_quantity.checked_add(quantity).ok_or(MathError::Overflow)?;
+    let (_, before) = order_lock(config, order.side, order.price, order.filled_quantity)?;
+    let (_, after) = order_lock(config, order.side, order.price, filled_after)?;
+    math::checked_sub(after, before)
+}
+
+/// Query types for Service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Query {
+    GetOrderBook { depth: usize },
+    GetOrder { order_id: OrderId },
+    /// Every recorded change to an order, oldest first; None for unknown orders
+    GetOrderHistory { order_id: OrderId },
+    GetReceipt { account: Account, client_request_id: u64 },
+    /// Receipt of the client request that placed the order, if any
+    GetOrderReceipt { order_id: OrderId },
+    GetBalance { asset: String },
+    GetMarketStats,
+    GetAccountBalance { account: Account, asset: String },
+    /// Most `account` can withdraw of `asset` without leaving its open orders short
+    GetMaxWithdrawable { account: Account, asset: String },
+    GetBan { account: Account },
+    /// Most recent audit events, oldest first
+    GetEvents { count: usize },
+    /// Market maker statistics for an epoch; the open epoch reads up to its last sample
+    GetDmmEpochReport { epoch: u64 },
+    /// TWAP parent with its fills so far and the next release time
+    GetTwapOrder { twap_id: u64 },
+    GetTradeSettlement { trade_id: u64 },
+    /// Settlement id returned for a trade handed over with `RequestTradeSettlement`
+    GetTradeSettlementId { trade_id: u64 },
+    GetSchemaVersion,
+    /// Trading fees collected in an asset
+    GetCollectedFees { asset: String },
+    GetQueuePosition { order_id: OrderId },
+    GetTradingPermission { principal: Account, delegate: Account },
+    /// Delegate that placed the order for its owner, if any
+    GetOrderDelegate { order_id: OrderId },
+    /// Trades from `from_id` on, by id. Only the trade retention is kept: ids before the oldest
+    /// retained one return `TradePage::Pruned`
+    GetTrades { from_id: u64, limit: usize },
+    /// Trades with `start <= timestamp < end`, oldest first, within the same retention
+    GetTradesByTimeRange { start: Timestamp, end: Timestamp, limit: usize },
+    /// How many trades are retained and the oldest retained id, i.e. how far back `GetTrades` reaches
+    GetTradeRetention,
+    /// Trade totals of the `STATS_WINDOW_HOURS` before `at`, from the rolling buckets
+    GetTradeStats { at: Timestamp },
+    /// Depth, stats, open orders and balances of `account` in one consistent response
+    GetTradingView { account: Account, depth_levels: usize },
+    /// Every balance of `account` with the sequence of the last balance delta sent for it, for an
+    /// aggregator that missed one
+    BalanceSnapshot { account: Account },
+    /// On the aggregator chain: net of the balance deltas of `account`'s `asset` received from
+    /// every market chain, in attos
+    GetAggregatedBalance { account: Account, asset: String },
+    /// On the aggregator chain: sequence of the last balance delta of `account` applied from `market`
+    GetAggregatedSequence { market: ChainId, account: Account },
+    /// Dry run of `PlaceOrder` signed by `account` at time `at`, through the same checks and
+    /// matching; nothing is stored
+    SimulatePlaceOrder {
+        side: OrderSide,
+        order_type: OrderType,
+        price: Price,
+        quantity: Quantity,
+        time_in_force: TimeInForce,
+        #[serde(default)]
+        require_full_fill: bool,
+        #[serde(default)]
+        min_fill_quantity: Option<Quantity>,
+        account: Account,
+        at: Timestamp,
+    },
+    /// Refused placements `account` submitted while recording its rejections, oldest first
+    GetRecentRejections { account: Account },
+    /// Registered home chain of `account`; None means this chain
+    GetHomeChain { account: Account },
+    /// Configured reference price and its value at time `at`
+    GetReferencePrice { at: Timestamp },
+    GetStaleOrderPolicy,
+    /// Whether the order was placed with `sweep_exempt`
+    IsSweepExempt { order_id: OrderId },
+    /// Registered sibling market chains with their pairs
+    GetSiblingMarkets,
+    /// Orders `user` forwarded from this chain, oldest first, with their acknowledgments
+    GetForwardedOrders { user: Account },
+    /// Messages waiting for a replay or discard, by parked id
+    GetParkedMessages,
+    /// Market maker protection of `account` with its counters as stored; None if it set none
+    GetMmpStatus { account: Account },
+}
+
+/// Query response type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueryResponse {
+    OrderBook { bids: Vec<(Price, Quantity)>, asks: Vec<(Price, Quantity)> },
+    Order(Option<Order>),
+    OrderHistory(Option<OrderHistory>),
+    Receipt(Option<OperationReceipt>),
+    Balance(Amount),
+    MarketStats(MarketStats),
+    AccountBalance { available: Amount, locked: Amount, unsettled: Amount },
+    MaxWithdrawable { amount: Amount, blocking_orders: u32 },
+    Ban(Option<AccountBan>),
+    Events(Vec<OrderBookEvent>),
+    DmmEpochReport { epoch: DmmEpoch, makers: Vec<DmmReportEntry> },
+    TwapOrder { order: Option<TwapOrder>, next_slice_at: Option<Timestamp> },
+    SchemaVersion { version: u32, migration_cursor: Option<u64> },
+    CollectedFees(Amount),
+    TradeSettlement(Option<TradeSettlement>),
+    TradeSettlementId(Option<u64>),
+    QueuePosition(QueuePosition),
+    TradingPermission(Option<TradingPermission>),
+    OrderDelegate(Option<Account>),
+    Trades(TradePage),
+    TradeRetention { trades: u64, first_retained_id: u64 },
+    TradeStats(TradeWindow),
+    TradingView(TradingView),
+    BalanceSnapshot(BalanceSnapshot),
+    AggregatedBalance(i128),
+    AggregatedSequence(u64),
+    PlacementSimulation(PlacementSimulation),
+    RecentRejections(Vec<OrderRejection>),
+    HomeChain(Option<ChainId>),
+    ReferencePrice(ReferencePrice),
+    StaleOrderPolicy(Option<StaleOrderPolicy>),
+    SweepExempt(bool),
+    SiblingMarkets(Vec<(ChainId, SiblingMarket)>),
+    ForwardedOrders(Vec<ForwardedOrder>),
+    ParkedMessages(Vec<ParkedMessage>),
+    MmpStatus(Option<MmpTracker>),
+    Error(String),
+}
+
+/// Service for GraphQL queries
+pub struct OrderBookService;
+
+impl WithServiceAbi for OrderBookService {
+    type Abi = OrderBookServiceAbi;
+}
+
+#[derive(Clone)]
+pub struct OrderBookServiceAbi;
+
+impl BaseServiceAbi for OrderBookServiceAbi {
+    type Query = Query;
+    type QueryResponse = QueryResponse;
+}
+
+#[async_trait]
+impl Service for OrderBookService {
+    type Parameters = ();
+
+    async fn new(_runtime: ServiceRuntime<Self>) -> Self {
+        OrderBookService
+    }
+
+    async fn handle_query(&self, _runtime: &ServiceRuntime<Self>, query: Query) -> QueryResponse {
+        let _state = OrderBookState::load(_runtime).await.ok();
+        match query {
+            Query::GetOrderBook { depth } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match (
+                    Self::book_side(&state.buy_levels, depth, OrderSide::Buy).await,
+                    Self::book_side(&state.sell_levels, depth, OrderSide::Sell).await,
+                ) {
+                    (Ok(bids), Ok(asks)) => QueryResponse::OrderBook { bids, asks },
+                    (Err(error), _) | (_, Err(error)) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetOrder { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.orders.get(&order_id).await {
+                    Ok(order) => QueryResponse::Order(order),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetOrderHistory { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.order_history.get(&order_id).await {
+                    Ok(history) => QueryResponse::OrderHistory(history),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetBalance { asset: _ } => {
+                // Would need account from context
+                QueryResponse::Balance(Amount::ZERO)
+            }
+            Query::GetMarketStats => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                QueryResponse::MarketStats(Self::market_stats(&state))
+            }
+            Query::GetTwapOrder { twap_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.twap_orders.get(&twap_id).await {
+                    Ok(order) => QueryResponse::TwapOrder {
+                        next_slice_at: order.as_ref().and_then(TwapOrder::next_slice_at),
+                        order,
+                    },
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetReceipt { account, client_request_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.receipts.get(&(account, client_request_id)).await {
+                    Ok(receipt) => QueryResponse::Receipt(receipt),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetOrderReceipt { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::order_receipt(&state, order_id).await {
+                    Ok(receipt) => QueryResponse::Receipt(receipt),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTradeSettlement { trade_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.trade_settlements.get(&trade_id).await {
+                    Ok(status) => QueryResponse::TradeSettlement(status),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTradeSettlementId { trade_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.trade_settlement_ids.get(&trade_id).await {
+                    Ok(settlement_id) => QueryResponse::TradeSettlementId(settlement_id),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetSchemaVersion => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                QueryResponse::SchemaVersion {
+                    version: state.schema_version.get(),
+                    migration_cursor: state.migration_cursor.get(),
+                }
+            }
+            Query::GetCollectedFees { asset } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.collected_fees.get(&asset).await {
+                    Ok(collected) => QueryResponse::CollectedFees(collected.unwrap_or(Amount::ZERO)),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTrades { from_id, limit } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::trades_from(&state, from_id, limit).await {
+                    Ok(page) => QueryResponse::Trades(page),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTradesByTimeRange { start, end, limit } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::trades_between(&state, start, end, limit).await {
+                    Ok(page) => QueryResponse::Trades(page),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTradeRetention => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                QueryResponse::TradeRetention {
+                    trades: state.trade_retention.get().unwrap_or(TRADE_RETENTION),
+                    first_retained_id: state.first_retained_trade_id.get(),
+                }
+            }
+            Query::GetTradeStats { at } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                QueryResponse::TradeStats(state.trade_stats.get().window(hour_of(at)))
+            }
+            Query::GetTradingView { account, depth_levels } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::trading_view(&state, account, depth_levels).await {
+                    Ok(view) => QueryResponse::TradingView(view),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::BalanceSnapshot { account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::balance_snapshot(&state, account).await {
+                    Ok(snapshot) => QueryResponse::BalanceSnapshot(snapshot),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetAggregatedBalance { account, asset } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.portfolio_balances.get(&(account, asset)).await {
+                    Ok(balance) => QueryResponse::AggregatedBalance(balance.unwrap_or(0)),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetAggregatedSequence { market, account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.portfolio_sequences.get(&(market, account)).await {
+                    Ok(sequence) => QueryResponse::AggregatedSequence(sequence.unwrap_or(0)),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::SimulatePlaceOrder {
+                side,
+                order_type,
+                price,
+                quantity,
+                time_in_force,
+                require_full_fill,
+                min_fill_quantity,
+                account,
+                at,
+            } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                let order = Order {
+                    id: state.next_order_id.get(),
+                    user: account,
+                    side,
+                    order_type,
+                    price: if order_type == OrderType::Market { 0 } else { price },
+                    quantity,
+                    filled_quantity: 0,
+                    status: OrderStatus::Pending,
+                    time_in_force,
+                    timestamp: at,
+                    expires_at: None,
+                };
+                match Self::simulate_place_order(&state, order, require_full_fill, min_fill_quantity).await {
+                    Ok(simulation) => QueryResponse::PlacementSimulation(simulation),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetQueuePosition { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::queue_position(&state, order_id).await {
+                    Ok(position) => QueryResponse::QueuePosition(position),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetAccountBalance { account, asset } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                let key = (account, asset);
+                match (
+                    state.balances.get(&key).await,
+                    state.locked_balances.get(&key).await,
+                    state.unsettled_balances.get(&key).await,
+                ) {
+                    (Ok(available), Ok(locked), Ok(unsettled)) => QueryResponse::AccountBalance {
+                        available: available.unwrap_or(Amount::ZERO),
+                        locked: locked.unwrap_or(Amount::ZERO),
+                        unsettled: unsettled.unwrap_or(Amount::ZERO),
+                    },
+                    (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetMaxWithdrawable { account, asset } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                let key = (account, asset);
+                match (
+                    state.balances.get(&key).await,
+                    state.locked_balances.get(&key).await,
+                    state.open_order_requirements.get(&key).await,
+                ) {
+                    (Ok(free), Ok(locked), Ok(requirement)) => {
+                        let requirement = requirement.unwrap_or_default();
+                        QueryResponse::MaxWithdrawable {
+                            amount: requirement.max_withdrawable(
+                                free.unwrap_or(Amount::ZERO),
+                                locked.unwrap_or(Amount::ZERO),
+                            ),
+                            blocking_orders: requirement.orders,
+                        }
+                    }
+                    (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetReferencePrice { at } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                let config = state.reference_price_config.get();
+                let recent_trades = state.recent_trades.get();
+                let index = state.index_price.get();
+                QueryResponse::ReferencePrice(ReferencePrice {
+                    config,
+                    price: config.price(&recent_trades, index.as_ref(), at),
+                    trade_price: recent_trades.weighted_average(config.window_trades),
+                    index,
+                    index_stale: index.is_some_and(|index| config.is_stale(&index, at)),
+                })
+            }
+            Query::GetStaleOrderPolicy => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                QueryResponse::StaleOrderPolicy(state.stale_order_policy.get())
+            }
+            Query::IsSweepExempt { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.sweep_exempt_orders.contains_key(&order_id).await {
+                    Ok(exempt) => QueryResponse::SweepExempt(exempt),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetSiblingMarkets => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::sibling_markets(&state).await {
+                    Ok(markets) => QueryResponse::SiblingMarkets(markets),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetForwardedOrders { user } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::forwarded_orders(&state, user).await {
+                    Ok(orders) => QueryResponse::ForwardedOrders(orders),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetParkedMessages => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::parked_messages(&state).await {
+                    Ok(messages) => QueryResponse::ParkedMessages(messages),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetMmpStatus { account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.mmp_trackers.get(&account).await {
+                    Ok(tracker) => QueryResponse::MmpStatus(tracker),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetHomeChain { account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.home_chains.get(&account).await {
+                    Ok(home_chain) => QueryResponse::HomeChain(home_chain),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetRecentRejections { account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.recent_rejections.get(&account).await {
+                    Ok(rejections) => QueryResponse::RecentRejections(rejections.unwrap_or_default()),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetTradingPermission { principal, delegate } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.trading_permissions.get(&(principal, delegate)).await {
+                    Ok(permission) => QueryResponse::TradingPermission(permission),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetOrderDelegate { order_id } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.order_delegates.get(&order_id).await {
+                    Ok(delegate) => QueryResponse::OrderDelegate(delegate),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetBan { account } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.banned_accounts.get(&account).await {
+                    Ok(ban) => QueryResponse::Ban(ban),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetDmmEpochReport { epoch } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match Self::dmm_epoch_report(&state, epoch).await {
+                    Ok(response) => response,
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+            Query::GetEvents { count } => {
+                let Some(state) = _state else {
+                    return QueryResponse::Error("State unavailable".to_string());
+                };
+                match state.events.read_back(count).await {
+                    Ok(events) => QueryResponse::Events(events),
+                    Err(error) => QueryResponse::Error(error.to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl OrderBookService {
+    async fn sibling_markets(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+    ) -> Result<Vec<(ChainId, SiblingMarket)>, linera_views::views::ViewError> {
+        let mut markets = Vec::new();
+        for chain_id in state.sibling_markets.indices().await? {
+            if let Some(market) = state.sibling_markets.get(&chain_id).await? {
+                markets.push((chain_id, market));
+            }
+        }
+        Ok(markets)
+    }
+    
+    async fn forwarded_orders(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        user: Account,
+    ) -> Result<Vec<ForwardedOrder>, linera_views::views::ViewError> {
+        let mut orders = Vec::new();
+        for forward_id in state.user_forwarded_orders.get(&user).await?.unwrap_or_default() {
+            if let Some(order) = state.forwarded_orders.get(&forward_id).await? {
+                orders.push(order);
+            }
+        }
+        Ok(orders)
+    }
+    
+    async fn parked_messages(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+    ) -> Result<Vec<ParkedMessage>, linera_views::views::ViewError> {
+        let mut messages = Vec::new();
+        for parked_id in state.parked_messages.indices().await? {
+            if let Some(message) = state.parked_messages.get(&parked_id).await? {
+                messages.push(message);
+            }
+        }
+        Ok(messages)
+    }
+    
+    async fn order_receipt(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        order_id: OrderId,
+    ) -> Result<Option<OperationReceipt>, linera_views::views::ViewError> {
+        let Some(order) = state.orders.get(&order_id).await? else {
+            return Ok(None);
+        };
+        let Some(client_request_id) = state.client_order_ids.get(&order_id).await? else {
+            return Ok(None);
+        };
+        state.receipts.get(&(order.user, client_request_id)).await
+    }
+    
+    /// Walks the orders ahead in the level's FIFO, up to `MAX_QUEUE_POSITION_WALK` of them
+    async fn queue_position(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        order_id: OrderId,
+    ) -> Result<QueuePosition, linera_views::views::ViewError> {
+        let Some(order) = state.orders.get(&order_id).await? else {
+            return Ok(QueuePosition::NotResting { status: None });
+        };
+        let not_resting = QueuePosition::NotResting { status: Some(order.status) };
+        if !order.is_active() {
+            return Ok(not_resting);
         }
-        let new_balance = current_balance - amount;
-        state.balances.insert(&balance_key, new_balance)?;
-        Ok(())
+        let level = match order.side {
+            OrderSide::Buy => state.buy_levels.get(&order.price).await?,
+            OrderSide::Sell => state.sell_levels.get(&order.price).await?,
+        };
+        let Some(level) = level else {
+            return Ok(not_resting);
+        };
+        let Some(index) = level.orders.iter().position(|id| *id == order_id) else {
+            return Ok(not_resting);
+        };
+        
+        let ahead = &level.orders[..index];
+        let mut orders_ahead = 0;
+        let mut quantity_ahead: Quantity = 0;
+        for id in ahead.iter().take(MAX_QUEUE_POSITION_WALK) {
+            // Levels drop stale ids lazily during matching
+            if let Some(order) = state.orders.get(id).await?.filter(Order::is_active) {
+                orders_ahead += 1;
+                quantity_ahead = quantity_ahead.saturating_add(order.remaining_quantity());
+            }
+        }
+        Ok(QueuePosition::Resting {
+            side: order.side,
+            price: order.price,
+            orders_ahead,
+            quantity_ahead,
+            level_quantity: level.total_quantity,
+            exact: ahead.len() <= MAX_QUEUE_POSITION_WALK,
+        })
     }
     
-    async fn update_config(
-        &mut self,
-        _runtime: &mut ContractRuntime<Self>,
-        state: &mut OrderBookState<ContractRuntime<Self>>,
-        min_order_size: Option<Quantity>,
-        max_order_size: Option<Quantity>,
-        tick_size: Option<Price>,
-    ) -> Result<(), OrderBookError> {
-        let mut config = state.config.get();
-        if let Some(min) = min_order_size { config.min_order_size = min; }
-        if let Some(max) = max_order_size { config.max_order_size = max; }
-        if let Some(tick) = tick_size { config.tick_size = tick; }
-        state.config.set(config);
-        Ok(())
+    async fn trades_from(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        from_id: u64,
+        limit: usize,
+    ) -> Result<TradePage, linera_views::views::ViewError> {
+        let first_retained_id = state.first_retained_trade_id.get();
+        if from_id < first_retained_id {
+            return Ok(TradePage::Pruned { first_retained_id });
+        }
+        let next_trade_id = state.next_trade_id.get();
+        let end = next_trade_id.min(from_id.saturating_add(limit.min(MAX_TRADE_PAGE) as u64));
+        let mut trades = Vec::new();
+        for id in from_id..end {
+            // Trades from before schema 2 appear once `Migrate` has moved them
+            if let Some(trade) = state.trades_by_id.get(&id).await? {
+                trades.push(trade);
+            }
+        }
+        Ok(TradePage::Trades { trades, next_id: (end < next_trade_id).then_some(end) })
     }
-}
-
-/// Query types for Service
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Query {
-    GetOrderBook { depth: usize },
-    GetOrder { order_id: OrderId },
-    GetBalance { asset: String },
-    GetMarketStats,
-}
-
-/// Query response type
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum QueryResponse {
-    OrderBook { bids: Vec<(Price, Quantity)>, asks: Vec<(Price, Quantity)> },
-    Order(Option<Order>),
-    Balance(Amount),
-    MarketStats(MarketStats),
-    Error(String),
-}
-
-/// Service for GraphQL queries
-pub struct OrderBookService;
-
-impl WithServiceAbi for OrderBookService {
-    type Abi = OrderBookServiceAbi;
-}
-
-#[derive(Clone)]
-pub struct OrderBookServiceAbi;
-
-impl BaseServiceAbi for OrderBookServiceAbi {
-    type Query = Query;
-    type QueryResponse = QueryResponse;
-}
-
-#[async_trait]
-impl Service for OrderBookService {
-    type Parameters = ();
-
-    async fn new(_runtime: ServiceRuntime<Self>) -> Self {
-        OrderBookService
+    
+    /// Starts from the last checkpoint at or before `start`, so at most
+    /// `TRADE_CHECKPOINT_INTERVAL` earlier trades are read and skipped. A partial last page's
+    /// `next_id` continues with `GetTrades`.
+    async fn trades_between(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        start: Timestamp,
+        end: Timestamp,
+        limit: usize,
+    ) -> Result<TradePage, linera_views::views::ViewError> {
+        let first_retained_id = state.first_retained_trade_id.get();
+        if first_retained_id > 0 {
+            // A pruned trade may share the oldest retained trade's timestamp
+            let oldest = state.trades_by_id.get(&first_retained_id).await?;
+            if oldest.map_or(true, |trade| start <= trade.timestamp) {
+                return Ok(TradePage::Pruned { first_retained_id });
+            }
+        }
+        let next_trade_id = state.next_trade_id.get();
+        let (mut low, mut high) = (
+            first_retained_id.div_ceil(TRADE_CHECKPOINT_INTERVAL),
+            next_trade_id.div_ceil(TRADE_CHECKPOINT_INTERVAL),
+        );
+        let mut from_id = first_retained_id;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let checkpoint = middle * TRADE_CHECKPOINT_INTERVAL;
+            match state.trade_checkpoints.get(&checkpoint).await? {
+                Some(timestamp) if timestamp <= start => {
+                    from_id = checkpoint;
+                    low = middle + 1;
+                }
+                _ => high = middle,
+            }
+        }
+        
+        let limit = limit.min(MAX_TRADE_PAGE);
+        let mut trades = Vec::new();
+        for id in from_id..next_trade_id {
+            let Some(trade) = state.trades_by_id.get(&id).await? else {
+                continue;
+            };
+            if trade.timestamp >= end {
+                break;
+            }
+            if trade.timestamp < start {
+                continue;
+            }
+            if trades.len() == limit {
+                return Ok(TradePage::Trades { trades, next_id: Some(id) });
+            }
+            trades.push(trade);
+        }
+        Ok(TradePage::Trades { trades, next_id: None })
     }
-
-    async fn handle_query(&self, _runtime: &ServiceRuntime<Self>, query: Query) -> QueryResponse {
-        let _state = OrderBookState::load(_runtime).await.ok();
-        match query {
-            Query::GetOrderBook { depth: _ } => {
-                // Placeholder implementation
-                QueryResponse::OrderBook {
-                    bids: vec![],
-                    asks: vec![],
+    
+    async fn dmm_epoch_report(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        epoch: u64,
+    ) -> Result<QueryResponse, linera_views::views::ViewError> {
+        let current = state.dmm_epoch.get();
+        let bounds = if epoch == current {
+            DmmEpoch { epoch, started_at: state.dmm_epoch_started_at.get(), closed_at: None }
+        } else {
+            match state.dmm_epochs.get(&epoch).await? {
+                Some(bounds) => bounds,
+                None => return Ok(QueryResponse::Error(format!("Unknown DMM epoch {epoch}"))),
+            }
+        };
+        
+        let mut makers = Vec::new();
+        for (stats_epoch, account) in state.dmm_stats.indices().await? {
+            if stats_epoch != epoch {
+                continue;
+            }
+            if let Some(stats) = state.dmm_stats.get(&(stats_epoch, account)).await? {
+                let uptime_bps = stats.uptime_bps();
+                makers.push(DmmReportEntry {
+                    meets_uptime: uptime_bps >= stats.terms.min_uptime_bps,
+                    uptime_bps,
+                    stats,
+                });
+            }
+        }
+        Ok(QueryResponse::DmmEpochReport { epoch: bounds, makers })
+    }
+    
+    /// Top `depth` levels of one side, best price first
+    /// All parts come from the state loaded for this query, so they agree with each other and
+    /// with `sequence`
+    async fn trading_view(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        account: Account,
+        depth_levels: usize,
+    ) -> Result<TradingView, linera_views::views::ViewError> {
+        let depth = depth_levels.min(MAX_TRADING_VIEW_DEPTH);
+        let order_ids = state.user_orders.get(&account).await?.unwrap_or_default();
+        let mut open_orders = Vec::new();
+        for id in order_ids.iter().take(MAX_TRADING_VIEW_ORDERS) {
+            if let Some(order) = state.orders.get(id).await?.filter(Order::is_active) {
+                open_orders.push(order);
+            }
+        }
+        let config = state.config.get();
+        let mut balances = Vec::new();
+        for asset in [config.base_asset, config.quote_asset] {
+            let key = (account, asset);
+            let available = state.balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            let locked = state.locked_balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            let unsettled = state.unsettled_balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            balances.push(AssetBalance { asset: key.1, available, locked, unsettled });
+        }
+        Ok(TradingView {
+            sequence: state.book_sequence.get(),
+            bids: Self::book_side(&state.buy_levels, depth, OrderSide::Buy).await?,
+            asks: Self::book_side(&state.sell_levels, depth, OrderSide::Sell).await?,
+            stats: Self::market_stats(state),
+            open_orders,
+            more_orders: order_ids.len() > MAX_TRADING_VIEW_ORDERS,
+            balances,
+        })
+    }
+    
+    /// Reads every balance map in full, as the assets an account holds are not indexed
+    async fn balance_snapshot(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        account: Account,
+    ) -> Result<BalanceSnapshot, linera_views::views::ViewError> {
+        let mut assets = std::collections::BTreeSet::new();
+        for map in [&state.balances, &state.locked_balances, &state.unsettled_balances] {
+            assets.extend(map.indices().await?.into_iter().filter(|key| key.0 == account).map(|key| key.1));
+        }
+        let mut balances = Vec::new();
+        for asset in assets {
+            let key = (account, asset);
+            let available = state.balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            let locked = state.locked_balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            let unsettled = state.unsettled_balances.get(&key).await?.unwrap_or(Amount::ZERO);
+            balances.push(AssetBalance { asset: key.1, available, locked, unsettled });
+        }
+        Ok(BalanceSnapshot {
+            account,
+            sequence: state.balance_sequences.get(&account).await?.unwrap_or(0),
+            balances,
+        })
+    }
+    
+    /// `place_order` without the writes: the same checks in the same order, matching on a
+    /// snapshot of the book, and the account's balance followed through the fills.
+    /// `order.price` is the limit price as given, zeroed for market orders.
+    async fn simulate_place_order(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        mut order: Order,
+        require_full_fill: bool,
+        min_fill_quantity: Option<Quantity>,
+    ) -> Result<PlacementSimulation, OrderBookError> {
+        let banned = state.banned_accounts.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?;
+        if let Some(ban) = banned.filter(|ban| ban.is_active(order.timestamp)) {
+            return Err(OrderBookError::AccountBanned { expires_at: ban.expires_at });
+        }
+        let protection = state.mmp_trackers.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?;
+        if let Some(tracker) = protection.filter(|tracker| tracker.is_cooling_down(order.timestamp)) {
+            return Err(OrderBookError::MmpCooldown { until: tracker.cooldown_until.unwrap_or_default() });
+        }
+        let config = state.config.get();
+        if !config.is_active {
+            return Err(OrderBookError::MarketClosed);
+        }
+        validate_order(&config, order.order_type, order.price, order.quantity, order.time_in_force)?;
+        let phase = state.market_phase.get();
+        phase.check_order(order.order_type, order.time_in_force)?;
+        check_min_fill(min_fill_quantity, order.quantity)?;
+        let min_fill = min_fill_quantity.unwrap_or(0);
+        
+        let (mut best_bid, mut best_ask) = (state.best_bid.get(), state.best_ask.get());
+        let payment_key = (order.user, config.payment_asset(order.side).to_string());
+        let mut balance = state.balances.get(&payment_key).await.map_err(|_| OrderBookError::ViewError)?
+            .unwrap_or(Amount::ZERO);
+        if config.allow_unsettled_trading {
+            let unsettled = state.unsettled_balances.get(&payment_key).await.map_err(|_| OrderBookError::ViewError)?
+                .unwrap_or(Amount::ZERO);
+            balance = math::checked_add(balance, unsettled)?;
+        }
+        if order.order_type == OrderType::Limit {
+            let best = match order.side {
+                OrderSide::Buy => best_ask,
+                OrderSide::Sell => best_bid,
+            };
+            let post_only = order.time_in_force == TimeInForce::PostOnly || phase == MarketPhase::PreOpen;
+            if post_only && best.map_or(false, |best| order.side.crosses(order.price, best)) {
+                return Err(OrderBookError::InvalidOrder { reason: "Post-only order would take liquidity".to_string() });
+            }
+            let (_, amount) = order_lock(&config, order.side, order.price, order.quantity)?;
+            if balance < amount {
+                return Err(OrderBookError::InsufficientBalance { required: amount, available: balance });
+            }
+        }
+        
+        let mut fills = Vec::new();
+        let mut fees = Amount::ZERO;
+        if phase == MarketPhase::Continuous {
+            let book = Self::book_snapshot(state, &order, min_fill, order.timestamp).await?;
+            let outcome = match_taker(&config, &order, min_fill, None, &book)?;
+            for fill in &outcome.fills {
+                // Market orders pay each fill from the free balance, which a fill against the
+                // account's own resting order tops up
+                if order.order_type == OrderType::Market {
+                    if balance < fill.amounts.taker_pays {
+                        return Err(OrderBookError::InsufficientBalance {
+                            required: fill.amounts.taker_pays,
+                            available: balance,
+                        });
+                    }
+                    balance = balance - fill.amounts.taker_pays;
+                    if fill.maker.user == order.user {
+                        balance = math::checked_add(balance, fill.amounts.maker_receives)?;
+                    }
                 }
+                fees = math::checked_add(fees, fill.amounts.taker_fee)?;
+                fills.push(SimulatedFill {
+                    maker_order_id: fill.maker.id,
+                    price: fill.price,
+                    quantity: fill.quantity,
+                    amounts: fill.amounts,
+                });
             }
-            Query::GetOrder { order_id: _ } => {
-                // Would need state access
-                QueryResponse::Order(None)
+            order.filled_quantity = outcome.filled_quantity();
+            match order.side {
+                OrderSide::Buy => best_ask = book.best_after(&outcome),
+                OrderSide::Sell => best_bid = book.best_after(&outcome),
             }
-            Query::GetBalance { asset: _ } => {
-                // Would need account from context
-                QueryResponse::Balance(Amount::ZERO)
+        }
+        
+        let status = placement_status(&order, require_full_fill)?;
+        if matches!(status, OrderStatus::Open | OrderStatus::PartiallyFilled) {
+            let level = match order.side {
+                OrderSide::Buy => state.buy_levels.get(&order.price).await,
+                OrderSide::Sell => state.sell_levels.get(&order.price).await,
+            };
+            check_level_caps(&config, &level.map_err(|_| OrderBookError::ViewError)?.unwrap_or_default(), &order)?;
+            match order.side {
+                OrderSide::Buy => best_bid = Some(best_bid.map_or(order.price, |best| best.max(order.price))),
+                OrderSide::Sell => best_ask = Some(best_ask.map_or(order.price, |best| best.min(order.price))),
             }
-            Query::GetMarketStats => {
-                // Would need state access
-                QueryResponse::MarketStats(MarketStats::default())
+        }
+        Ok(PlacementSimulation {
+            order_id: order.id,
+            status,
+            filled_quantity: order.filled_quantity,
+            fills,
+            fees,
+            best_bid,
+            best_ask,
+        })
+    }
+    
+    /// Levels opposite `taker` that it can reach, best first, loaded until their makers could fill it
+    async fn book_snapshot(
+        state: &OrderBookState<ServiceRuntime<Self>>,
+        taker: &Order,
+        min_fill: Quantity,
+        now: Timestamp,
+    ) -> Result<BookSnapshot, OrderBookError> {
+        let levels = match taker.side.opposite() {
+            OrderSide::Buy => &state.buy_levels,
+            OrderSide::Sell => &state.sell_levels,
+        };
+        let mut prices = levels.indices().await.map_err(|_| OrderBookError::ViewError)?;
+        prices.sort_unstable();
+        if taker.side == OrderSide::Sell {
+            prices.reverse();
+        }
+        
+        let mut book = BookSnapshot::default();
+        for price in prices {
+            if !book.needs_level(taker, None, price) {
+                book.next_price = Some(price);
+                break;
+            }
+            let level = levels.get(&price).await.map_err(|_| OrderBookError::ViewError)?.unwrap_or_default();
+            let mut orders = Vec::new();
+            for id in &level.orders {
+                if let Some(order) = state.orders.get(id).await.map_err(|_| OrderBookError::ViewError)? {
+                    let tracker = state.mmp_trackers.get(&order.user).await.map_err(|_| OrderBookError::ViewError)?;
+                    if let Some(tracker) = tracker {
+                        book.protect(order.user, tracker, now);
+                    }
+                    orders.push(order);
+                }
             }
+            book.push_level(price, level, orders, min_fill);
         }
+        Ok(book)
+    }
+    
+    /// Stored statistics with the current phase
+    fn market_stats(state: &OrderBookState<ServiceRuntime<Self>>) -> MarketStats {
+        MarketStats { phase: state.market_phase.get(), ..state.market_stats.get() }
+    }
+    
+    async fn book_side(
+        levels: &MapView<ServiceRuntime<Self>, Price, PriceLevel>,
+        depth: usize,
+        side: OrderSide,
+    ) -> Result<Vec<(Price, Quantity)>, linera_views::views::ViewError> {
+        let mut prices = levels.indices().await?;
+        prices.sort_unstable();
+        if side == OrderSide::Buy {
+            prices.reverse();
+        }
+        
+        let mut book = Vec::new();
+        for price in prices.into_iter().take(depth) {
+            if let Some(level) = levels.get(&price).await? {
+                book.push((price, level.total_quantity));
+            }
+        }
+        Ok(book)
     }
 }
 
@@ -711,4 +5848,363 @@ mod tests {
         assert_eq!(order.remaining_quantity(), 50000000);
         assert!(!order.is_fully_filled());
     }
+    
+    #[test]
+    fn test_order_lock_per_side() {
+        let config = MarketConfig::default();
+        // 0.5 BTC at 45,000 locks 22,500 USDT
+        let (asset, amount) = order_lock(&config, OrderSide::Buy, 45000_00000000, 50000000).unwrap();
+        assert_eq!(asset, "USDT");
+        assert_eq!(amount, Amount::from_tokens(22_500));
+        
+        let (asset, amount) = order_lock(&config, OrderSide::Sell, 45000_00000000, 50000000).unwrap();
+        assert_eq!(asset, "BTC");
+        assert_eq!(amount, Amount::from_millis(500));
+    }
+    
+    #[test]
+    fn test_fill_amounts_per_fee_model() {
+        let mut config = MarketConfig::default();
+        let (price, one_btc) = (50_000 * 100_000_000, 100_000_000);
+        
+        // Buyer pays 0.2% in BTC, seller 0.1% in USDT, both out of what they receive
+        let amounts = fill_amounts(&config, OrderSide::Buy, price, one_btc).unwrap();
+        assert_eq!(amounts.taker_pays, Amount::from_tokens(50_000));
+        assert_eq!(amounts.taker_receives, Amount::from_millis(998));
+        assert_eq!(amounts.taker_fee, Amount::from_millis(2));
+        assert_eq!(amounts.maker_pays, Amount::from_tokens(1));
+        assert_eq!(amounts.maker_receives, Amount::from_tokens(49_950));
+        assert_eq!(amounts.maker_fee, Amount::from_tokens(50));
+        
+        // The buyer's quote fee comes on top of the price instead
+        config.fee_model = FeeModel::FeeInQuoteAsset;
+        let amounts = fill_amounts(&config, OrderSide::Buy, price, one_btc).unwrap();
+        assert_eq!(amounts.taker_pays, Amount::from_tokens(50_100));
+        assert_eq!(amounts.taker_receives, Amount::from_tokens(1));
+        assert_eq!(amounts.taker_fee, Amount::from_tokens(100));
+        assert_eq!(amounts.maker_receives, Amount::from_tokens(49_950));
+        
+        // Bids lock that fee at the higher rate: 22,500 USDT plus 0.2%
+        let (_, amount) = order_lock(&config, OrderSide::Buy, 45000_00000000, 50000000).unwrap();
+        assert_eq!(amount, Amount::from_tokens(22_545));
+    }
+    
+    #[test]
+    fn test_auction_clearing_price() {
+        assert_eq!(auction_clearing_price(&[(100, 5)], &[(101, 5)]), None);
+        assert_eq!(auction_clearing_price(&[], &[(101, 5)]), None);
+        // 10 trade at 101, against 5 at 100 and 6 at 102
+        assert_eq!(auction_clearing_price(&[(102, 6), (101, 4)], &[(100, 5), (101, 5)]), Some((101, 10)));
+        // Equal volume and imbalance everywhere: the lowest price
+        assert_eq!(auction_clearing_price(&[(105, 3)], &[(100, 10)]), Some((100, 3)));
+        assert_eq!(auction_clearing_price(&[(105, 10), (103, 2)], &[(100, 4), (104, 20)]), Some((104, 10)));
+    }
+    
+    #[test]
+    fn test_phase_order_rules() {
+        assert!(MarketPhase::Continuous.check_order(OrderType::Market, TimeInForce::IOC).is_ok());
+        for phase in [MarketPhase::PreOpen, MarketPhase::Auction] {
+            assert!(phase.check_order(OrderType::Limit, TimeInForce::GTC).is_ok());
+            assert!(phase.check_order(OrderType::Limit, TimeInForce::PostOnly).is_ok());
+            assert!(phase.check_order(OrderType::Limit, TimeInForce::IOC).is_err());
+            assert!(phase.check_order(OrderType::Market, TimeInForce::GTC).is_err());
+        }
+        assert!(matches!(
+            MarketPhase::Halted.check_order(OrderType::Limit, TimeInForce::GTC),
+            Err(OrderBookError::MarketHalted)
+        ));
+    }
+    
+    #[test]
+    fn test_affordable_quantity_stays_within_budget() {
+        let mut config = MarketConfig::default();
+        let price = 50_000 * 100_000_000;
+        
+        // Exactly one BTC for 50,000 USDT when the fee comes out of the BTC
+        assert_eq!(affordable_quantity(&config, price, Amount::from_tokens(50_000)).unwrap(), 100_000_000);
+        
+        // With the fee on top, the same budget buys less and the fill still fits
+        config.fee_model = FeeModel::FeeInQuoteAsset;
+        let budget = Amount::from_tokens(50_000);
+        let quantity = affordable_quantity(&config, price, budget).unwrap();
+        assert!(quantity < 100_000_000);
+        assert!(fill_amounts(&config, OrderSide::Buy, price, quantity).unwrap().taker_pays <= budget);
+        let over = fill_amounts(&config, OrderSide::Buy, price, quantity + 1).unwrap().taker_pays;
+        assert!(over > budget);
+        
+        assert_eq!(affordable_quantity(&config, price, Amount::ZERO).unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_settlement_legs_net_of_fees() {
+        let account = linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0));
+        let mut config = MarketConfig::default();
+        let mut trade = Trade {
+            id: 1,
+            maker_order_id: 1,
+            taker_order_id: 2,
+            price: 50_000 * 100_000_000,
+            quantity: 100_000_000,
+            timestamp: Timestamp::default(),
+            maker: account,
+            taker: account,
+            maker_side: OrderSide::Sell,
+            maker_fee: Amount::from_tokens(50),
+            taker_fee: Amount::from_millis(2),
+            fee_model: FeeModel::FeeInReceivedAsset,
+        };
+        let chain = ChainId::root(0);
+        let legs = |trade: &Trade, config: &MarketConfig| match trade.settlement_request(config, chain, chain).unwrap() {
+            Message::SettlementRequest { maker_asset, taker_asset, maker_amount, taker_amount, .. } => {
+                (maker_asset, maker_amount, taker_asset, taker_amount)
+            }
+            other => panic!("unexpected message: {other:?}"),
+        };
+        assert_eq!(
+            legs(&trade, &config),
+            ("BTC".to_string(), Amount::from_millis(998), "USDT".to_string(), Amount::from_tokens(49_950)),
+        );
+        
+        // A fee paid on top is not part of what the counterparty receives
+        config.fee_model = FeeModel::FeeInQuoteAsset;
+        trade.fee_model = FeeModel::FeeInQuoteAsset;
+        trade.taker_fee = Amount::from_tokens(100);
+        assert_eq!(
+            legs(&trade, &config),
+            ("BTC".to_string(), Amount::from_tokens(1), "USDT".to_string(), Amount::from_tokens(49_950)),
+        );
+    }
+    
+    #[test]
+    fn test_lock_draws_add_up_to_initial_lock() {
+        let config = MarketConfig::default();
+        // At 1.5 three single-unit fills pay 1 each, but the three together lock 5
+        let mut order = Order {
+            id: 1,
+            user: linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0)),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            price: 150_000_000,
+            quantity: 3,
+            filled_quantity: 0,
+            status: OrderStatus::Open,
+            time_in_force: TimeInForce::GTC,
+            timestamp: Timestamp::default(),
+            expires_at: None,
+        };
+        let (_, initial) = order_lock(&config, order.side, order.price, order.quantity).unwrap();
+        
+        let mut drawn = Amount::ZERO;
+        for _ in 0..3 {
+            drawn = drawn + lock_consumed(&config, &order, 1).unwrap();
+            order.filled_quantity += 1;
+        }
+        assert_eq!(drawn, initial);
+        assert_eq!(locked_remaining(&config, &order).unwrap().1, Amount::ZERO);
+    }
+    
+    #[test]
+    fn test_reduction_releases_exact_lock() {
+        assert_eq!(reduced_remaining(10, 4, 5), Some(6));
+        assert_eq!(reduced_remaining(10, 6, 5), None);
+        assert_eq!(reduced_remaining(10, 10, 0), None);
+        assert_eq!(reduced_remaining(10, 11, 0), None);
+        
+        let config = MarketConfig::default();
+        let mut order = Order {
+            id: 1,
+            user: linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0)),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            price: 150_000_000,
+            quantity: 3,
+            filled_quantity: 1,
+            status: OrderStatus::PartiallyFilled,
+            time_in_force: TimeInForce::GTC,
+            timestamp: Timestamp::default(),
+            expires_at: None,
+        };
+        let (_, before) = locked_remaining(&config, &order).unwrap();
+        order.quantity -= 1;
+        let (_, after) = locked_remaining(&config, &order).unwrap();
+        // What the reduction releases and what a later fill draws add up to the lock before it
+        assert_eq!((before - after) + lock_consumed(&config, &order, 1).unwrap(), before);
+    }
+    
+    #[test]
+    fn test_validate_order() {
+        let config = MarketConfig::default();
+        assert!(validate_order(&config, OrderType::Market, 0, 1_000_000, TimeInForce::IOC).is_ok());
+        assert!(matches!(
+            validate_order(&config, OrderType::Limit, 0, 1_000_000, TimeInForce::GTC),
+            Err(OrderBookError::InvalidOrder { .. })
+        ));
+        assert!(matches!(
+            validate_order(&config, OrderType::Market, 0, 1, TimeInForce::IOC),
+            Err(OrderBookError::BelowMinimumSize { .. })
+        ));
+        assert!(matches!(
+            validate_order(&config, OrderType::Market, 0, 1_000_000, TimeInForce::PostOnly),
+            Err(OrderBookError::InvalidOrder { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_quoting_band() {
+        // Mid 100.00, 50 bps either side
+        assert_eq!(quoting_band(99_00000000, 101_00000000, 50), Some((99_50000000, 100_50000000)));
+        assert_eq!(quoting_band(101, 100, 50), None);
+    }
+    
+    #[test]
+    fn test_dmm_uptime_is_time_weighted() {
+        let account = linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0));
+        let terms = DmmAgreement {
+            account,
+            max_spread_bps: 50,
+            min_uptime_bps: 9_000,
+            min_size: 100,
+            registered_at: Timestamp::from(0),
+        };
+        let mut stats = DmmEpochStats::new(terms.clone(), Timestamp::from(0));
+        
+        // Two-sided for 3s, one-sided for 1s
+        stats.record(Timestamp::from(0), 100, 200);
+        stats.record(Timestamp::from(3_000_000), 100, 0);
+        stats.record(Timestamp::from(4_000_000), 100, 100);
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.qualifying_samples, 2);
+        assert_eq!(stats.uptime_bps(), 7_500);
+        assert_eq!(stats.bid_size_micros, 100 * 4_000_000);
+        assert_eq!(stats.ask_size_micros, 200 * 3_000_000);
+        
+        // The next epoch picks up the current quotes but none of the counters
+        let next = stats.carry_over(terms, Timestamp::from(4_000_000));
+        assert!(next.last_qualifying);
+        assert_eq!(next.observed_micros, 0);
+    }
+    
+    #[test]
+    fn test_ban_expiry() {
+        let account = linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0));
+        let mut ban = AccountBan {
+            account,
+            banned_by: account,
+            banned_at: Timestamp::from(0),
+            expires_at: Some(Timestamp::from(1_000)),
+            reason: "test".to_string(),
+        };
+        assert!(ban.is_active(Timestamp::from(999)));
+        assert!(!ban.is_active(Timestamp::from(1_000)));
+        
+        ban.expires_at = None;
+        assert!(ban.is_active(Timestamp::from(u64::MAX)));
+    }
+
+    #[test]
+    fn test_trading_permission_scope() {
+        let mut permission = TradingPermission { max_order_size: None, side: None, granted_at: Timestamp::from(0) };
+        assert!(permission.check(OrderSide::Buy, u64::MAX).is_ok());
+
+        permission.max_order_size = Some(1_000);
+        permission.side = Some(OrderSide::Sell);
+        assert!(permission.check(OrderSide::Sell, 1_000).is_ok());
+        assert!(matches!(
+            permission.check(OrderSide::Sell, 1_001),
+            Err(OrderBookError::OutsideTradingPermission { .. })
+        ));
+        assert!(matches!(
+            permission.check(OrderSide::Buy, 1),
+            Err(OrderBookError::OutsideTradingPermission { .. })
+        ));
+    }
+
+    #[test]
+    fn test_settles_internally_below_minimum() {
+        let minimum = Some(Amount::from(1_000));
+        assert!(!settles_internally([(Amount::from(1_000), minimum), (Amount::from(1), None)]));
+        assert!(settles_internally([(Amount::from(999), minimum), (Amount::from(1), None)]));
+        assert!(settles_internally([(Amount::from(5_000), None), (Amount::from(999), minimum)]));
+    }
+    
+    #[test]
+    fn test_only_settlement_application_can_force_cancel() {
+        let settlement = Some(("settlement", ChainId::root(0)));
+        assert!(is_settlement_origin(settlement, Some("settlement"), Some(ChainId::root(0))));
+        
+        // The right application on an arbitrary chain, or anything else on the right chain
+        assert!(!is_settlement_origin(settlement, Some("settlement"), Some(ChainId::root(1))));
+        assert!(!is_settlement_origin(settlement, Some("orderbook"), Some(ChainId::root(0))));
+        assert!(!is_settlement_origin(settlement, None, Some(ChainId::root(0))));
+        assert!(!is_settlement_origin(None, Some("settlement"), Some(ChainId::root(0))));
+    }
+    
+    #[test]
+    fn test_exceeds_settlement_maximum() {
+        let maximum = Some(Amount::from(1_000));
+        assert!(!exceeds_settlement_maximum([(Amount::from(1_000), maximum), (Amount::from(u128::MAX), None)]));
+        assert!(exceeds_settlement_maximum([(Amount::from(1_001), maximum), (Amount::from(1), None)]));
+        assert!(exceeds_settlement_maximum([(Amount::from(1), None), (Amount::from(1_001), maximum)]));
+    }
+    
+    fn twap_order(total_quantity: Quantity, slice_count: u32) -> TwapOrder {
+        TwapOrder {
+            id: 0,
+            user: linera_base::identifiers::Account::chain(linera_base::identifiers::ChainId::root(0)),
+            side: OrderSide::Sell,
+            limit_price: None,
+            total_quantity,
+            filled_quantity: 0,
+            slice_count,
+            slices_released: 0,
+            duration_seconds: 90,
+            started_at: Timestamp::from(1_000),
+            last_released_at: None,
+            locked: Amount::ZERO,
+            child_order_ids: Vec::new(),
+            status: TwapStatus::Active,
+        }
+    }
+    
+    #[test]
+    fn test_twap_schedule() {
+        let twap = twap_order(100, 3);
+        assert_eq!(twap.slice_due_at(0), Timestamp::from(1_000));
+        assert_eq!(twap.slice_due_at(1), Timestamp::from(30_001_000));
+        assert_eq!(twap.slice_due_at(2), Timestamp::from(60_001_000));
+        
+        // Rounding leftovers go into the last slice
+        assert_eq!(twap.target_after(0), 33);
+        assert_eq!(twap.target_after(1), 66);
+        assert_eq!(twap.target_after(2), 100);
+    }
+    
+    #[test]
+    fn test_twap_releases_once_per_block() {
+        let mut twap = twap_order(100, 2);
+        assert!(twap.is_due(Timestamp::from(1_000)));
+        
+        twap.slices_released = 1;
+        twap.last_released_at = Some(Timestamp::from(1_000));
+        assert!(!twap.is_due(Timestamp::from(45_000_999)));
+        assert!(twap.is_due(Timestamp::from(45_001_000)));
+        
+        // Overdue, but a slice already went out in this block
+        twap.last_released_at = Some(Timestamp::from(90_000_000));
+        assert!(!twap.is_due(Timestamp::from(90_000_000)));
+        
+        twap.slices_released = 2;
+        assert_eq!(twap.next_slice_at(), None);
+        assert!(!twap.is_due(Timestamp::from(u64::MAX)));
+    }
+    
+    #[test]
+    fn test_trade_retention_window() {
+        assert!(trades_to_prune(0, TRADE_RETENTION - 1, TRADE_RETENTION).is_empty());
+        assert_eq!(trades_to_prune(0, TRADE_RETENTION, TRADE_RETENTION), 0..1);
+        assert_eq!(trades_to_prune(1, TRADE_RETENTION, TRADE_RETENTION), 1..1);
+        // A market that fell behind, or shrank its retention, catches up from where it is
+        assert_eq!(trades_to_prune(5, TRADE_RETENTION + 9, TRADE_RETENTION), 5..10);
+        assert_eq!(trades_to_prune(5, 2_000, MIN_TRADE_RETENTION), 5..1_001);
+    }
 }
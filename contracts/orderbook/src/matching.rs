@@ -0,0 +1,390 @@
+//! Matching engine over an in-memory snapshot of one side of the book. Placement applies its
+//! outcome to the state; `SimulatePlaceOrder` only reports it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use axelarx_math::{self as math, MathError};
+use linera_base::{data_types::Timestamp, identifiers::Account};
+
+use crate::{
+    affordable_quantity, fill_amounts, FillAmounts, MarketConfig, MmpTracker, Order, OrderId, OrderType, Price,
+    PriceLevel, Quantity, QuoteBudget,
+};
+
+/// A price level a taker can reach, with the level's active orders
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotLevel {
+    pub price: Price,
+    pub level: PriceLevel,
+    /// Orders of the level by id; ids of inactive or missing orders are dropped when it is matched
+    pub makers: BTreeMap<OrderId, Order>,
+}
+
+/// Levels of the side a taker matches against, best first, loaded as far as the taker can get
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookSnapshot {
+    pub levels: Vec<SnapshotLevel>,
+    /// Best level behind the loaded ones, if any
+    pub next_price: Option<Price>,
+    /// Protection of the loaded makers that set one, with counters as of the match
+    pub protections: BTreeMap<Account, MmpTracker>,
+    /// Makers whose orders are passed over from the start, cooling down after a trigger
+    pub pulled: BTreeSet<Account>,
+    /// Remaining quantity of the loaded makers a fill would not pass over
+    fillable: Quantity,
+    /// `protections` and `pulled` as they would stand had every loaded maker filled in full
+    projected: BTreeMap<Account, MmpTracker>,
+    projected_pulled: BTreeSet<Account>,
+}
+
+impl BookSnapshot {
+    /// Whether `taker` needs the level at `price`, the next in priority order: the taker crosses
+    /// it and the levels loaded so far may not fill it
+    pub fn needs_level(&self, taker: &Order, max_price: Option<Price>, price: Price) -> bool {
+        reaches(taker, max_price, price) && self.fillable < taker.remaining_quantity()
+    }
+
+    /// Registers the protection of a maker of the next level, rolled to `now`; call before
+    /// `push_level` so the maker's orders count as fillable only until it would trigger
+    pub fn protect(&mut self, account: Account, mut tracker: MmpTracker, now: Timestamp) {
+        if self.protections.contains_key(&account) {
+            return;
+        }
+        tracker.roll(now);
+        if tracker.is_cooling_down(now) {
+            self.pulled.insert(account);
+            self.projected_pulled.insert(account);
+        }
+        self.protections.insert(account, tracker);
+        self.projected.insert(account, tracker);
+    }
+
+    /// Adds the next level in priority order with the orders its ids point to
+    pub fn push_level(
+        &mut self,
+        price: Price,
+        level: PriceLevel,
+        orders: impl IntoIterator<Item = Order>,
+        min_fill: Quantity,
+    ) {
+        let makers: BTreeMap<OrderId, Order> = orders.into_iter().map(|order| (order.id, order)).collect();
+        for id in &level.orders {
+            let Some(maker) = makers.get(id).filter(|maker| maker.is_active()) else {
+                continue;
+            };
+            if maker.remaining_quantity() < min_fill || self.projected_pulled.contains(&maker.user) {
+                continue;
+            }
+            self.fillable = self.fillable.saturating_add(maker.remaining_quantity());
+            let projected = self.projected.get_mut(&maker.user);
+            if projected.is_some_and(|tracker| tracker.record(maker.side, maker.remaining_quantity())) {
+                self.projected_pulled.insert(maker.user);
+            }
+        }
+        self.levels.push(SnapshotLevel { price, level, makers });
+    }
+
+    /// Best price of the side once `outcome` is applied
+    pub fn best_after(&self, outcome: &MatchOutcome) -> Option<Price> {
+        outcome.levels.iter()
+            .find(|(_, level)| !level.orders.is_empty())
+            .map(|(price, _)| *price)
+            .or_else(|| self.levels.get(outcome.levels.len()).map(|level| level.price))
+            .or(self.next_price)
+    }
+}
+
+/// One fill of the taker against a resting order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    /// The resting order as it stood before the fill
+    pub maker: Order,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub amounts: FillAmounts,
+}
+
+/// Fills of a taker, and the levels it reached as they stand after them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchOutcome {
+    pub fills: Vec<Fill>,
+    /// Levels left without orders are to be removed from the book
+    pub levels: Vec<(Price, PriceLevel)>,
+    /// The snapshot's protections with the fills counted
+    pub protections: BTreeMap<Account, MmpTracker>,
+    /// Makers whose protection a fill triggered, in order; their later orders were passed over
+    pub mmp_triggered: Vec<Account>,
+}
+
+impl MatchOutcome {
+    pub fn filled_quantity(&self) -> Quantity {
+        self.fills.iter().map(|fill| fill.quantity).sum()
+    }
+}
+
+/// Whether a taker can trade at `price`: its limit crosses it, and so does a budget's maximum price
+fn reaches(taker: &Order, max_price: Option<Price>, price: Price) -> bool {
+    (taker.order_type == OrderType::Market || taker.side.crosses(taker.price, price))
+        && max_price.map_or(true, |max_price| taker.side.crosses(max_price, price))
+}
+
+/// Fills `taker` against `book` in price-time priority, at the resting orders' prices.
+/// Stops when the taker is filled, the snapshot is exhausted, or a limit no longer crosses.
+/// Makers that would fill less than `min_fill` are passed over where they stand, unless the fill
+/// completes the taker; the walk then continues behind them and on worse levels.
+/// A taker with a `budget` pays each fill out of it and stops once the budget buys nothing
+/// at the next level or the level is above its maximum price.
+/// Orders of a maker whose protection is cooling down, or is triggered by a fill of this pass,
+/// are passed over too.
+pub fn match_taker(
+    config: &MarketConfig,
+    taker: &Order,
+    min_fill: Quantity,
+    mut budget: Option<&mut QuoteBudget>,
+    book: &BookSnapshot,
+) -> Result<MatchOutcome, MathError> {
+    let mut outcome = MatchOutcome { protections: book.protections.clone(), ..MatchOutcome::default() };
+    let mut pulled = book.pulled.clone();
+    let mut remaining = taker.remaining_quantity();
+    let mut exhausted = false;
+    for snapshot in &book.levels {
+        let max_price = budget.as_ref().and_then(|budget| budget.max_price);
+        if remaining == 0 || exhausted || !reaches(taker, max_price, snapshot.price) {
+            break;
+        }
+
+        let mut level = snapshot.level.clone();
+        let mut position = 0;
+        while remaining > 0 && position < level.orders.len() {
+            let id = level.orders[position];
+            let Some(maker) = snapshot.makers.get(&id).filter(|maker| maker.is_active()) else {
+                level.remove_at(position, snapshot.makers.get(&id).map(|order| order.user));
+                continue;
+            };
+            if pulled.contains(&maker.user) {
+                position += 1;
+                continue;
+            }
+
+            let mut quantity = remaining.min(maker.remaining_quantity());
+            if let Some(budget) = budget.as_deref() {
+                quantity = quantity.min(affordable_quantity(config, snapshot.price, budget.remaining)?);
+                if quantity == 0 {
+                    exhausted = true;
+                    break;
+                }
+            }
+            if quantity < min_fill && quantity < remaining {
+                position += 1;
+                continue;
+            }
+
+            let amounts = fill_amounts(config, taker.side, snapshot.price, quantity)?;
+            if let Some(budget) = budget.as_deref_mut() {
+                budget.remaining = math::checked_sub(budget.remaining, amounts.taker_pays)?;
+            }
+            remaining -= quantity;
+            level.total_quantity = level.total_quantity.saturating_sub(quantity);
+            if quantity == maker.remaining_quantity() {
+                level.remove_at(position, Some(maker.user));
+            }
+            let protection = outcome.protections.get_mut(&maker.user);
+            if protection.is_some_and(|tracker| tracker.record(maker.side, quantity)) {
+                pulled.insert(maker.user);
+                outcome.mmp_triggered.push(maker.user);
+            }
+            outcome.fills.push(Fill { maker: maker.clone(), price: snapshot.price, quantity, amounts });
+        }
+        outcome.levels.push((snapshot.price, level));
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_level_caps, MmpConfig, OrderBookError, OrderSide, OrderStatus, TimeInForce};
+    use linera_base::{
+        data_types::Amount,
+        identifiers::{ChainId, Owner},
+    };
+
+    const PRICE: Price = 50_000 * 100_000_000;
+
+    fn order(id: OrderId, side: OrderSide, order_type: OrderType, price: Price, quantity: Quantity) -> Order {
+        Order {
+            id,
+            user: Account::chain(ChainId::root(0), Owner::from([id as u8; 32])),
+            side,
+            order_type,
+            price,
+            quantity,
+            filled_quantity: 0,
+            status: OrderStatus::Open,
+            time_in_force: TimeInForce::GTC,
+            timestamp: Timestamp::default(),
+            expires_at: None,
+        }
+    }
+
+    /// Asks at `PRICE` and one tick of 1,000 above, each level's orders oldest first
+    fn asks(levels: &[&[(OrderId, Quantity)]], taker: &Order, min_fill: Quantity) -> BookSnapshot {
+        let mut book = BookSnapshot::default();
+        for (index, makers) in levels.iter().enumerate() {
+            let price = PRICE + index as Price * 1_000 * 100_000_000;
+            if !book.needs_level(taker, None, price) {
+                book.next_price = Some(price);
+                break;
+            }
+            let orders: Vec<_> = makers.iter()
+                .map(|(id, quantity)| order(*id, OrderSide::Sell, OrderType::Limit, price, *quantity))
+                .collect();
+            let mut level = PriceLevel::default();
+            orders.iter().for_each(|order| level.push_order(order));
+            book.push_level(price, level, orders, min_fill);
+        }
+        book
+    }
+
+    #[test]
+    fn test_price_time_priority_across_levels() {
+        let config = MarketConfig::default();
+        let taker = order(9, OrderSide::Buy, OrderType::Market, 0, 250);
+        let book = asks(&[&[(1, 100), (2, 100)], &[(3, 100)], &[(4, 100)]], &taker, 0);
+        // The third level is not needed to fill 250
+        assert_eq!((book.levels.len(), book.next_price), (2, Some(PRICE + 2_000 * 100_000_000)));
+
+        let outcome = match_taker(&config, &taker, 0, None, &book).unwrap();
+        let fills: Vec<_> = outcome.fills.iter().map(|fill| (fill.maker.id, fill.quantity)).collect();
+        assert_eq!(fills, vec![(1, 100), (2, 100), (3, 50)]);
+        assert_eq!(outcome.levels[0].1, PriceLevel::default());
+        let level = &outcome.levels[1].1;
+        assert_eq!((level.total_quantity, &level.orders, level.order_count), (50, &vec![3], 1));
+        assert_eq!(book.best_after(&outcome), Some(PRICE + 1_000 * 100_000_000));
+    }
+
+    #[test]
+    fn test_limit_and_min_fill() {
+        let config = MarketConfig::default();
+        // A limit at the first level stops there
+        let taker = order(9, OrderSide::Buy, OrderType::Limit, PRICE, 300);
+        let book = asks(&[&[(1, 100)], &[(2, 100)]], &taker, 0);
+        assert_eq!(book.next_price, Some(PRICE + 1_000 * 100_000_000));
+        let outcome = match_taker(&config, &taker, 0, None, &book).unwrap();
+        assert_eq!(outcome.filled_quantity(), 100);
+        assert_eq!(book.best_after(&outcome), book.next_price);
+
+        // The small maker keeps its place and the level stays best
+        let taker = order(9, OrderSide::Buy, OrderType::Market, 0, 150);
+        let book = asks(&[&[(1, 20), (2, 100)], &[(3, 100)]], &taker, 50);
+        let outcome = match_taker(&config, &taker, 50, None, &book).unwrap();
+        let fills: Vec<_> = outcome.fills.iter().map(|fill| (fill.maker.id, fill.quantity)).collect();
+        assert_eq!(fills, vec![(2, 100), (3, 50)]);
+        let level = &outcome.levels[0].1;
+        assert_eq!((level.total_quantity, &level.orders, level.order_count), (20, &vec![1], 1));
+        assert_eq!(book.best_after(&outcome), Some(PRICE));
+    }
+
+    #[test]
+    fn test_budget_stops_the_sweep() {
+        let config = MarketConfig::default();
+        let one_btc = 100_000_000;
+        let taker = order(9, OrderSide::Buy, OrderType::Market, 0, 2 * one_btc);
+        let book = asks(&[&[(1, one_btc)], &[(2, one_btc)]], &taker, 0);
+        let mut budget = QuoteBudget { remaining: Amount::from_tokens(75_000), max_price: Some(PRICE) };
+        let outcome = match_taker(&config, &taker, 0, Some(&mut budget), &book).unwrap();
+        assert_eq!(outcome.filled_quantity(), one_btc);
+        assert_eq!(budget.remaining, Amount::from_tokens(25_000));
+        assert_eq!(outcome.levels.len(), 1);
+    }
+
+    #[test]
+    fn test_level_caps_bound_the_walk() {
+        let config =
+            MarketConfig { max_orders_per_level: 4, max_account_orders_per_level: 2, ..MarketConfig::default() };
+        let accounts = [1, 2].map(|id| order(id, OrderSide::Sell, OrderType::Limit, PRICE, 0).user);
+        let mut level = PriceLevel::default();
+        let mut makers = Vec::new();
+        for id in 1..=4 {
+            let maker = order(id, OrderSide::Sell, OrderType::Limit, PRICE, 100);
+            let maker = Order { user: accounts[id as usize % 2], ..maker };
+            check_level_caps(&config, &level, &maker).unwrap();
+            level.push_order(&maker);
+            makers.push(maker);
+        }
+        assert_eq!((level.order_count, level.account_order_count(&accounts[0])), (4, 2));
+
+        let extra = order(5, OrderSide::Sell, OrderType::Limit, PRICE, 100);
+        assert!(matches!(
+            check_level_caps(&config, &level, &extra),
+            Err(OrderBookError::PriceLevelFull { price: PRICE, maximum: 4 })
+        ));
+        // Without the level cap, an account already at its own cap is still refused
+        let uncapped = MarketConfig { max_orders_per_level: 0, ..config.clone() };
+        assert!(check_level_caps(&uncapped, &level, &extra).is_ok());
+        let third = Order { user: accounts[0], ..extra };
+        assert!(matches!(
+            check_level_caps(&uncapped, &level, &third),
+            Err(OrderBookError::AccountLevelOrderLimit { price: PRICE, maximum: 2 })
+        ));
+
+        // A taker larger than the level visits each of its capped orders once
+        let taker = order(9, OrderSide::Buy, OrderType::Market, 0, 1_000);
+        let mut book = BookSnapshot::default();
+        book.push_level(PRICE, level, makers, 0);
+        let outcome = match_taker(&config, &taker, 0, None, &book).unwrap();
+        assert_eq!(outcome.fills.len(), 4);
+        assert_eq!(outcome.levels[0].1, PriceLevel::default());
+    }
+
+    #[test]
+    fn test_protection_pulls_a_maker_mid_sweep() {
+        let config = MarketConfig::default();
+        let taker = order(9, OrderSide::Buy, OrderType::Market, 0, 400);
+        let [protected, other] = [1, 2].map(|id| order(id, OrderSide::Sell, OrderType::Limit, PRICE, 0).user);
+        let mmp = MmpConfig { window_seconds: 10, max_fills: Some(2), max_delta: None, cooldown_seconds: 60 };
+        let now = Timestamp::from(0);
+
+        // The protected maker triggers on its second fill, order 3, so its order 4 cannot fill and
+        // the third level is needed
+        let mut book = BookSnapshot::default();
+        book.protect(protected, MmpTracker::new(mmp, now), now);
+        let levels = [
+            vec![(1, protected, 100), (2, other, 100), (3, protected, 100)],
+            vec![(4, protected, 100), (5, other, 50)],
+            vec![(6, other, 100)],
+        ];
+        for (index, makers) in levels.iter().enumerate() {
+            let price = PRICE + index as Price * 1_000 * 100_000_000;
+            assert!(book.needs_level(&taker, None, price));
+            let orders: Vec<_> = makers.iter()
+                .map(|(id, user, quantity)| {
+                    Order { user: *user, ..order(*id, OrderSide::Sell, OrderType::Limit, price, *quantity) }
+                })
+                .collect();
+            let mut level = PriceLevel::default();
+            orders.iter().for_each(|order| level.push_order(order));
+            book.push_level(price, level, orders, 0);
+        }
+
+        let outcome = match_taker(&config, &taker, 0, None, &book).unwrap();
+        let fills: Vec<_> = outcome.fills.iter().map(|fill| (fill.maker.id, fill.quantity)).collect();
+        assert_eq!(fills, vec![(1, 100), (2, 100), (3, 100), (5, 50), (6, 50)]);
+        assert_eq!(outcome.mmp_triggered, vec![protected]);
+        let tracker = outcome.protections[&protected];
+        assert_eq!((tracker.fills, tracker.delta), (2, -200));
+        // The passed-over order keeps its place for the cancellation to find
+        assert_eq!(outcome.levels[1].1.orders, vec![4]);
+
+        // A maker cooling down is passed over from the start
+        let mut cooling = MmpTracker::new(mmp, now);
+        cooling.trigger(now);
+        let mut book = BookSnapshot::default();
+        book.protect(protected, cooling, now);
+        let orders = vec![order(1, OrderSide::Sell, OrderType::Limit, PRICE, 100)];
+        let mut level = PriceLevel::default();
+        level.push_order(&orders[0]);
+        book.push_level(PRICE, level, orders, 0);
+        assert!(match_taker(&config, &taker, 0, None, &book).unwrap().fills.is_empty());
+    }
+}
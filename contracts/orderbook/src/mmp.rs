@@ -0,0 +1,122 @@
+//! Market maker protection (MMP): an account caps the fills, or the net position, its resting
+//! orders may take within a window. A fill that reaches a cap pulls the account's quotes: the
+//! matching pass stops filling its other orders, they are cancelled, and new placements are
+//! refused for the cooldown unless the account lifts it with `ResetMmp`.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{OrderSide, Quantity};
+
+/// Caps an account sets on the fills of its resting orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MmpConfig {
+    /// Length of the window the counters cover; they restart once it has passed
+    pub window_seconds: u64,
+    /// Fills within the window that trigger protection
+    pub max_fills: Option<u32>,
+    /// Net base quantity bought or sold within the window that triggers protection
+    pub max_delta: Option<Quantity>,
+    /// How long placements are refused once triggered
+    pub cooldown_seconds: u64,
+}
+
+impl MmpConfig {
+    /// The window must be positive and at least one cap set, and a cap of zero would never let a quote rest
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_seconds == 0 {
+            return Err("Window must be positive".to_string());
+        }
+        if self.max_fills.is_none() && self.max_delta.is_none() {
+            return Err("Set a fill or delta limit".to_string());
+        }
+        if self.max_fills == Some(0) || self.max_delta == Some(0) {
+            return Err("Limits must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// An account's protection settings with its counters for the current window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MmpTracker {
+    pub config: MmpConfig,
+    pub window_start: Timestamp,
+    /// Fills of the account's resting orders since `window_start`
+    pub fills: u32,
+    /// Base quantity its resting orders bought less what they sold since `window_start`
+    pub delta: i128,
+    /// End of the cooldown after the last trigger
+    pub cooldown_until: Option<Timestamp>,
+}
+
+impl MmpTracker {
+    pub fn new(config: MmpConfig, now: Timestamp) -> Self {
+        Self { config, window_start: now, fills: 0, delta: 0, cooldown_until: None }
+    }
+
+    /// Restarts the counters if the window has passed by `now`
+    pub fn roll(&mut self, now: Timestamp) {
+        let elapsed = now.micros().saturating_sub(self.window_start.micros());
+        if elapsed >= self.config.window_seconds.saturating_mul(1_000_000) {
+            self.window_start = now;
+            self.fills = 0;
+            self.delta = 0;
+        }
+    }
+
+    /// Counts a fill of one of the account's resting orders on `side`; true once a cap is reached
+    pub fn record(&mut self, side: OrderSide, quantity: Quantity) -> bool {
+        self.fills = self.fills.saturating_add(1);
+        self.delta += match side {
+            OrderSide::Buy => i128::from(quantity),
+            OrderSide::Sell => -i128::from(quantity),
+        };
+        self.config.max_fills.is_some_and(|max_fills| self.fills >= max_fills)
+            || self.config.max_delta.is_some_and(|max_delta| self.delta.unsigned_abs() >= u128::from(max_delta))
+    }
+
+    /// Whether placements are refused at `now`
+    pub fn is_cooling_down(&self, now: Timestamp) -> bool {
+        self.cooldown_until.is_some_and(|until| now < until)
+    }
+
+    /// Starts the cooldown at `now` and clears the counters; returns its end
+    pub fn trigger(&mut self, now: Timestamp) -> Timestamp {
+        let cooldown = self.config.cooldown_seconds.saturating_mul(1_000_000);
+        let until = Timestamp::from(now.micros().saturating_add(cooldown));
+        *self = Self { cooldown_until: Some(until), ..Self::new(self.config, now) };
+        until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caps_windows_and_cooldown() {
+        let config = MmpConfig { window_seconds: 10, max_fills: Some(3), max_delta: Some(100), cooldown_seconds: 60 };
+        assert!(config.validate().is_ok());
+        assert!(MmpConfig { window_seconds: 0, ..config }.validate().is_err());
+        assert!(MmpConfig { max_fills: None, max_delta: None, ..config }.validate().is_err());
+        assert!(MmpConfig { max_fills: Some(0), ..config }.validate().is_err());
+
+        // Opposite fills offset the delta but still count as fills
+        let mut tracker = MmpTracker::new(config, Timestamp::from(0));
+        assert!(!tracker.record(OrderSide::Buy, 60));
+        assert!(!tracker.record(OrderSide::Sell, 60));
+        assert!(tracker.record(OrderSide::Sell, 10));
+
+        // A new window starts from zero; the delta cap trips on its own
+        tracker.roll(Timestamp::from(10_000_000));
+        assert_eq!((tracker.fills, tracker.delta), (0, 0));
+        assert!(tracker.record(OrderSide::Sell, 100));
+
+        let until = tracker.trigger(Timestamp::from(20_000_000));
+        assert_eq!(until, Timestamp::from(80_000_000));
+        assert!(tracker.is_cooling_down(Timestamp::from(79_999_999)));
+        assert!(!tracker.is_cooling_down(until));
+        assert_eq!((tracker.fills, tracker.delta), (0, 0));
+    }
+}
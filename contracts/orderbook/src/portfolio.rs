@@ -0,0 +1,81 @@
+//! Balance notifications for a portfolio aggregator chain. Deposits, withdrawals and trading fees
+//! are coalesced per account and asset while a block executes, and leave as one net
+//! `Message::BalanceDelta` each once it has. The aggregator chain adds them up per account and
+//! asset across markets. Deltas are numbered per account, so an aggregator that sees a gap reads
+//! `Query::BalanceSnapshot` and resumes from its sequence.
+
+use linera_base::{data_types::Amount, identifiers::Account};
+use serde::{Deserialize, Serialize};
+
+use crate::AssetBalance;
+
+/// Why an account's balance changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalanceChangeReason {
+    Deposit,
+    Withdrawal,
+    Fee,
+    /// Changes of more than one kind within the block
+    Mixed,
+}
+
+impl BalanceChangeReason {
+    /// Reason of two changes coalesced into one delta
+    pub fn merge(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            BalanceChangeReason::Mixed
+        }
+    }
+}
+
+/// Change of one account's balance of one asset, awaiting the end of the block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingBalanceDelta {
+    /// In attos; negative when the balance went down
+    pub delta: i128,
+    pub reason: BalanceChangeReason,
+}
+
+impl PendingBalanceDelta {
+    pub fn new(amount: Amount, outgoing: bool, reason: BalanceChangeReason) -> Self {
+        let attos = i128::try_from(u128::from(amount)).unwrap_or(i128::MAX);
+        PendingBalanceDelta { delta: if outgoing { -attos } else { attos }, reason }
+    }
+
+    /// Folds a later change of the same balance in
+    pub fn add(&mut self, other: PendingBalanceDelta) {
+        self.delta = self.delta.saturating_add(other.delta);
+        self.reason = self.reason.merge(other.reason);
+    }
+}
+
+/// Returned by `Query::BalanceSnapshot`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub account: Account,
+    /// Sequence of the last delta sent for the account, which the balances include; zero before any
+    pub sequence: u64,
+    /// Every asset the account holds on this market, by name
+    pub balances: Vec<AssetBalance>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deltas_coalesce() {
+        let mut pending = PendingBalanceDelta::new(Amount::from_tokens(10), false, BalanceChangeReason::Deposit);
+        pending.add(PendingBalanceDelta::new(Amount::from_tokens(3), false, BalanceChangeReason::Deposit));
+        assert_eq!(pending.reason, BalanceChangeReason::Deposit);
+
+        pending.add(PendingBalanceDelta::new(Amount::from_tokens(15), true, BalanceChangeReason::Withdrawal));
+        assert_eq!(pending.delta, -i128::try_from(u128::from(Amount::from_tokens(2))).unwrap());
+        assert_eq!(pending.reason, BalanceChangeReason::Mixed);
+
+        pending.add(PendingBalanceDelta::new(Amount::from_tokens(2), false, BalanceChangeReason::Deposit));
+        assert_eq!(pending, PendingBalanceDelta { delta: 0, reason: BalanceChangeReason::Mixed });
+    }
+}
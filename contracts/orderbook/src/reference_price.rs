@@ -0,0 +1,178 @@
+//! Reference price for consumers that should not jump on a single small trade: the last trade, a
+//! size-weighted average of recent trades, or an index price supplied by the price oracle.
+
+use std::collections::VecDeque;
+
+use linera_base::{data_types::Timestamp, identifiers::Account};
+use serde::{Deserialize, Serialize};
+
+use crate::{Price, Quantity};
+
+/// Most trades `ReferencePriceConfig::window_trades` may average
+pub const MAX_REFERENCE_WINDOW: u32 = 100;
+
+/// Where the reference price comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferencePriceSource {
+    /// Price of the last trade, whatever its size
+    #[default]
+    LastTrade,
+    /// Size-weighted average of the last `window_trades` trades
+    SizeWeighted,
+    /// Price set with `UpdateIndexPrice`; none once older than `max_index_age_seconds`
+    Index,
+}
+
+/// Reference price configuration (admin only)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferencePriceConfig {
+    pub source: ReferencePriceSource,
+    /// Trades averaged into the trade-derived price, which index updates are checked against
+    pub window_trades: u32,
+    pub max_index_age_seconds: u64,
+    /// Furthest an index update may be from the trade-derived price, in basis points
+    pub max_index_deviation_bps: u64,
+}
+
+impl Default for ReferencePriceConfig {
+    fn default() -> Self {
+        Self {
+            source: ReferencePriceSource::LastTrade,
+            window_trades: 1,
+            max_index_age_seconds: 300,
+            max_index_deviation_bps: 500,
+        }
+    }
+}
+
+impl ReferencePriceConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_trades == 0 || self.window_trades > MAX_REFERENCE_WINDOW {
+            return Err(format!("Trade window must be between 1 and {MAX_REFERENCE_WINDOW}"));
+        }
+        if self.max_index_age_seconds == 0 {
+            return Err("Index price age limit must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether `index` is too old to use at `now`
+    pub fn is_stale(&self, index: &IndexPrice, now: Timestamp) -> bool {
+        now.micros().saturating_sub(index.updated_at.micros()) > self.max_index_age_seconds * 1_000_000
+    }
+
+    /// Reference price at `now`; None with no trades yet, or with a missing or stale index
+    pub fn price(&self, trades: &RecentTrades, index: Option<&IndexPrice>, now: Timestamp) -> Option<Price> {
+        match self.source {
+            ReferencePriceSource::LastTrade => trades.last_price(),
+            ReferencePriceSource::SizeWeighted => trades.weighted_average(self.window_trades),
+            ReferencePriceSource::Index => index.filter(|index| !self.is_stale(index, now)).map(|index| index.price),
+        }
+    }
+
+    /// Whether an index update to `price` stays within the allowed deviation from the trade-derived
+    /// price; always true before the first trade
+    pub fn within_deviation(&self, trades: &RecentTrades, price: Price) -> bool {
+        let Some(reference) = trades.weighted_average(self.window_trades) else {
+            return true;
+        };
+        let deviation = u128::from(price.abs_diff(reference)) * 10_000;
+        deviation <= u128::from(reference) * u128::from(self.max_index_deviation_bps)
+    }
+}
+
+/// Price and size of the latest trades, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentTrades {
+    pub trades: VecDeque<(Price, Quantity)>,
+}
+
+impl RecentTrades {
+    /// Adds a trade, keeping the latest `window` of them
+    pub fn record(&mut self, price: Price, quantity: Quantity, window: u32) {
+        self.trades.push_back((price, quantity));
+        while self.trades.len() > window as usize {
+            self.trades.pop_front();
+        }
+    }
+
+    pub fn last_price(&self) -> Option<Price> {
+        self.trades.back().map(|(price, _)| *price)
+    }
+
+    /// Size-weighted average price of the latest `window` trades
+    pub fn weighted_average(&self, window: u32) -> Option<Price> {
+        let (notional, size) = self.trades.iter().rev().take(window as usize).fold(
+            (0u128, 0u128),
+            |(notional, size), (price, quantity)| {
+                (notional + u128::from(*price) * u128::from(*quantity), size + u128::from(*quantity))
+            },
+        );
+        (size > 0).then(|| (notional / size) as Price)
+    }
+}
+
+/// Price set by the price oracle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexPrice {
+    pub price: Price,
+    pub updated_at: Timestamp,
+    pub updated_by: Account,
+    /// Set past the deviation check by the admin
+    pub emergency: bool,
+}
+
+/// Configured reference price and what it is made of
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferencePrice {
+    pub config: ReferencePriceConfig,
+    /// None with no trades yet, or with a missing or stale index
+    pub price: Option<Price>,
+    /// Size-weighted average of the configured trade window
+    pub trade_price: Option<Price>,
+    pub index: Option<IndexPrice>,
+    pub index_stale: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_base::identifiers::ChainId;
+
+    use super::*;
+
+    #[test]
+    fn test_weighted_average_discounts_small_trades() {
+        let mut trades = RecentTrades::default();
+        trades.record(100, 1_000, 3);
+        trades.record(110, 1_000, 3);
+        trades.record(200, 1, 3);
+        assert_eq!(trades.last_price(), Some(200));
+        assert_eq!(trades.weighted_average(3), Some(105));
+        assert_eq!(trades.weighted_average(1), Some(200));
+
+        // Only the window is kept
+        trades.record(120, 1_000, 3);
+        assert_eq!(trades.trades.len(), 3);
+        assert_eq!(trades.weighted_average(3), Some(115));
+    }
+
+    #[test]
+    fn test_index_deviation_and_staleness() {
+        let config = ReferencePriceConfig { source: ReferencePriceSource::Index, ..ReferencePriceConfig::default() };
+        let mut trades = RecentTrades::default();
+        assert!(config.within_deviation(&trades, 1));
+        trades.record(10_000, 5, config.window_trades);
+        assert!(config.within_deviation(&trades, 10_500));
+        assert!(config.within_deviation(&trades, 9_500));
+        assert!(!config.within_deviation(&trades, 10_501));
+
+        let index = IndexPrice {
+            price: 10_100,
+            updated_at: Timestamp::from(0),
+            updated_by: Account { chain_id: ChainId::root(0), owner: None },
+            emergency: false,
+        };
+        assert_eq!(config.price(&trades, Some(&index), Timestamp::from(300 * 1_000_000)), Some(10_100));
+        assert_eq!(config.price(&trades, Some(&index), Timestamp::from(301 * 1_000_000)), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Rejected placements kept for the accounts that asked for them, with a stable code per
+//! `OrderBookError` variant so bots need not parse error messages.
+
+use linera_base::{
+    data_types::{Amount, Timestamp},
+    identifiers::Account,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Operation, OrderBookError, OrderSide, OrderType, Price, Quantity, TimeInForce};
+
+/// Rejections kept per account; older ones are dropped first
+pub const MAX_RECENT_REJECTIONS: usize = 32;
+
+/// Stable code for each `OrderBookError` variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RejectionCode {
+    OrderNotFound,
+    InsufficientBalance,
+    WithdrawalBlockedByOpenOrders,
+    InvalidOrder,
+    OrderNotModifiable,
+    Unauthorized,
+    MarketClosed,
+    MarketHalted,
+    BookCrossed,
+    BelowMinimumSize,
+    ReductionExceedsRemaining,
+    AboveMaximumSize,
+    InvalidTickSize,
+    NotAdmin,
+    AccountBanned,
+    AccountNotBanned,
+    NoForcedCancellation,
+    MarketMakerNotFound,
+    TooManyMarketMakers,
+    InvalidMarketMakerTerms,
+    NoLiquidity,
+    DuplicateClientRequest,
+    TwapOrderNotFound,
+    TwapOrderNotActive,
+    NoMigrationPending,
+    MigrationInProgress,
+    FeeModelLocked,
+    NoTradingPermission,
+    OutsideTradingPermission,
+    InvalidTradingPermission,
+    TradeNotFound,
+    SettlementUnavailable,
+    PriceLevelFull,
+    AccountLevelOrderLimit,
+    NotPriceOracle,
+    InvalidReferencePrice,
+    IndexPriceDeviation,
+    MarketPairMismatch,
+    UnknownSiblingMarket,
+    InvalidStaleOrderPolicy,
+    ParkedMessageNotFound,
+    ParkedMessageUnreadable,
+    MmpCooldown,
+    InvalidMmpConfig,
+    InvalidTradeRetention,
+    Math,
+    ViewError,
+}
+
+impl OrderBookError {
+    /// No wildcard: a new variant does not compile until it has a code
+    pub fn rejection_code(&self) -> RejectionCode {
+        match self {
+            OrderBookError::OrderNotFound { .. } => RejectionCode::OrderNotFound,
+            OrderBookError::InsufficientBalance { .. } => RejectionCode::InsufficientBalance,
+            OrderBookError::WithdrawalBlockedByOpenOrders { .. } => RejectionCode::WithdrawalBlockedByOpenOrders,
+            OrderBookError::InvalidOrder { .. } => RejectionCode::InvalidOrder,
+            OrderBookError::OrderNotModifiable { .. } => RejectionCode::OrderNotModifiable,
+            OrderBookError::Unauthorized => RejectionCode::Unauthorized,
+            OrderBookError::MarketClosed => RejectionCode::MarketClosed,
+            OrderBookError::MarketHalted => RejectionCode::MarketHalted,
+            OrderBookError::BookCrossed => RejectionCode::BookCrossed,
+            OrderBookError::BelowMinimumSize { .. } => RejectionCode::BelowMinimumSize,
+            OrderBookError::ReductionExceedsRemaining { .. } => RejectionCode::ReductionExceedsRemaining,
+            OrderBookError::AboveMaximumSize { .. } => RejectionCode::AboveMaximumSize,
+            OrderBookError::InvalidTickSize => RejectionCode::InvalidTickSize,
+            OrderBookError::NotAdmin => RejectionCode::NotAdmin,
+            OrderBookError::AccountBanned { .. } => RejectionCode::AccountBanned,
+            OrderBookError::AccountNotBanned => RejectionCode::AccountNotBanned,
+            OrderBookError::NoForcedCancellation => RejectionCode::NoForcedCancellation,
+            OrderBookError::MarketMakerNotFound => RejectionCode::MarketMakerNotFound,
+            OrderBookError::TooManyMarketMakers { .. } => RejectionCode::TooManyMarketMakers,
+            OrderBookError::InvalidMarketMakerTerms { .. } => RejectionCode::InvalidMarketMakerTerms,
+            OrderBookError::NoLiquidity { .. } => RejectionCode::NoLiquidity,
+            OrderBookError::DuplicateClientRequest { .. } => RejectionCode::DuplicateClientRequest,
+            OrderBookError::TwapOrderNotFound { .. } => RejectionCode::TwapOrderNotFound,
+            OrderBookError::TwapOrderNotActive { .. } => RejectionCode::TwapOrderNotActive,
+            OrderBookError::NoMigrationPending { .. } => RejectionCode::NoMigrationPending,
+            OrderBookError::MigrationInProgress => RejectionCode::MigrationInProgress,
+            OrderBookError::FeeModelLocked => RejectionCode::FeeModelLocked,
+            OrderBookError::NoTradingPermission { .. } => RejectionCode::NoTradingPermission,
+            OrderBookError::OutsideTradingPermission { .. } => RejectionCode::OutsideTradingPermission,
+            OrderBookError::InvalidTradingPermission { .. } => RejectionCode::InvalidTradingPermission,
+            OrderBookError::TradeNotFound { .. } => RejectionCode::TradeNotFound,
+            OrderBookError::SettlementUnavailable { .. } => RejectionCode::SettlementUnavailable,
+            OrderBookError::PriceLevelFull { .. } => RejectionCode::PriceLevelFull,
+            OrderBookError::AccountLevelOrderLimit { .. } => RejectionCode::AccountLevelOrderLimit,
+            OrderBookError::NotPriceOracle => RejectionCode::NotPriceOracle,
+            OrderBookError::InvalidReferencePrice { .. } => RejectionCode::InvalidReferencePrice,
+            OrderBookError::IndexPriceDeviation { .. } => RejectionCode::IndexPriceDeviation,
+            OrderBookError::MarketPairMismatch { .. } => RejectionCode::MarketPairMismatch,
+            OrderBookError::UnknownSiblingMarket { .. } => RejectionCode::UnknownSiblingMarket,
+            OrderBookError::InvalidStaleOrderPolicy { .. } => RejectionCode::InvalidStaleOrderPolicy,
+            OrderBookError::ParkedMessageNotFound { .. } => RejectionCode::ParkedMessageNotFound,
+            OrderBookError::ParkedMessageUnreadable { .. } => RejectionCode::ParkedMessageUnreadable,
+            OrderBookError::MmpCooldown { .. } => RejectionCode::MmpCooldown,
+            OrderBookError::InvalidMmpConfig { .. } => RejectionCode::InvalidMmpConfig,
+            OrderBookError::InvalidTradeRetention { .. } => RejectionCode::InvalidTradeRetention,
+            OrderBookError::Math(_) => RejectionCode::Math,
+            OrderBookError::ViewError => RejectionCode::ViewError,
+        }
+    }
+}
+
+/// The placement that was refused, as submitted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RejectedOrder {
+    Order {
+        side: OrderSide,
+        order_type: OrderType,
+        price: Price,
+        quantity: Quantity,
+        time_in_force: TimeInForce,
+        client_request_id: Option<u64>,
+        on_behalf_of: Option<Account>,
+    },
+    QuoteOrder {
+        quote_amount: Amount,
+        max_price: Option<Price>,
+        client_request_id: Option<u64>,
+        on_behalf_of: Option<Account>,
+    },
+}
+
+impl RejectedOrder {
+    /// Summary of a placement operation; None for every other operation
+    pub fn of(operation: &Operation) -> Option<Self> {
+        match *operation {
+            Operation::PlaceOrder {
+                side, order_type, price, quantity, time_in_force, client_request_id, on_behalf_of, ..
+            } => Some(RejectedOrder::Order {
+                side,
+                order_type,
+                price,
+                quantity,
+                time_in_force,
+                client_request_id,
+                on_behalf_of,
+            }),
+            Operation::PlaceQuoteOrder { quote_amount, max_price, client_request_id, on_behalf_of } => {
+                Some(RejectedOrder::QuoteOrder { quote_amount, max_price, client_request_id, on_behalf_of })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A refused placement and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderRejection {
+    pub code: RejectionCode,
+    /// The error's message, with the values that failed the check
+    pub reason: String,
+    pub order: RejectedOrder,
+    pub timestamp: Timestamp,
+}
+
+impl OrderRejection {
+    pub fn new(error: &OrderBookError, order: RejectedOrder, timestamp: Timestamp) -> Self {
+        OrderRejection { code: error.rejection_code(), reason: error.to_string(), order, timestamp }
+    }
+}
+
+/// Appends `rejection`, dropping the oldest entries beyond `MAX_RECENT_REJECTIONS`
+pub fn push_rejection(rejections: &mut Vec<OrderRejection>, rejection: OrderRejection) {
+    rejections.push(rejection);
+    let excess = rejections.len().saturating_sub(MAX_RECENT_REJECTIONS);
+    rejections.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(error: &OrderBookError, quantity: Quantity) -> OrderRejection {
+        let order = RejectedOrder::Order {
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            price: 100,
+            quantity,
+            time_in_force: TimeInForce::GTC,
+            client_request_id: None,
+            on_behalf_of: None,
+        };
+        OrderRejection::new(error, order, Timestamp::from(0))
+    }
+
+    #[test]
+    fn test_rejection_carries_code_and_message() {
+        let error = OrderBookError::BelowMinimumSize { size: 1, minimum: 10 };
+        let rejection = rejection(&error, 1);
+        assert_eq!(rejection.code, RejectionCode::BelowMinimumSize);
+        assert_eq!(rejection.reason, "Order size below minimum: 1, minimum: 10");
+        assert_eq!(OrderBookError::MarketClosed.rejection_code(), RejectionCode::MarketClosed);
+    }
+
+    #[test]
+    fn test_recent_rejections_are_bounded() {
+        let mut rejections = Vec::new();
+        for quantity in 0..MAX_RECENT_REJECTIONS as u64 + 3 {
+            push_rejection(&mut rejections, rejection(&OrderBookError::InvalidTickSize, quantity));
+        }
+        assert_eq!(rejections.len(), MAX_RECENT_REJECTIONS);
+        assert!(matches!(rejections[0].order, RejectedOrder::Order { quantity: 3, .. }));
+    }
+}
@@ -0,0 +1,40 @@
+//! Open-order requirements: what an account's active limit orders still need of an asset, kept
+//! as running counters beside the locks. `Withdraw` checks them in O(1), so a gap in the lock
+//! accounting cannot leave resting orders under-collateralized.
+
+use axelarx_math as math;
+use linera_base::data_types::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Remaining cost of an account's active limit orders in one asset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenOrderRequirement {
+    /// Sum of the orders' remaining locks: notional for bids, quantity for asks
+    pub amount: Amount,
+    /// Active orders that need part of it
+    pub orders: u32,
+}
+
+impl OpenOrderRequirement {
+    /// Most of the `free` balance that can leave; whatever `locked` falls short of the
+    /// requirement stays behind for the orders
+    pub fn max_withdrawable(&self, free: Amount, locked: Amount) -> Amount {
+        math::saturating_sub(free, math::saturating_sub(self.amount, locked))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_an_uncovered_requirement_holds_back_free_balance() {
+        let requirement = OpenOrderRequirement { amount: Amount::from_tokens(100), orders: 2 };
+        let free = Amount::from_tokens(50);
+        assert_eq!(requirement.max_withdrawable(free, Amount::from_tokens(100)), free);
+        assert_eq!(requirement.max_withdrawable(free, Amount::from_tokens(120)), free);
+        assert_eq!(requirement.max_withdrawable(free, Amount::from_tokens(70)), Amount::from_tokens(20));
+        assert_eq!(requirement.max_withdrawable(free, Amount::ZERO), Amount::ZERO);
+        assert_eq!(OpenOrderRequirement::default().max_withdrawable(free, Amount::ZERO), free);
+    }
+}
@@ -0,0 +1,79 @@
+//! Stale-order housekeeping: old orders resting far from the reference price are cancelled in
+//! bounded batches by `SweepStaleOrders` once the book is deep enough to need it. Markets sweep
+//! nothing until the admin sets a policy, and orders placed with `sweep_exempt` are never swept.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::Price;
+
+/// Orders cancelled per `SweepStaleOrders` call
+pub const MAX_SWEEP_CANCELLATIONS: usize = 50;
+
+/// Resting orders inspected per `SweepStaleOrders` call, swept or not
+pub const MAX_SWEEP_INSPECTIONS: usize = 200;
+
+/// When resting orders become eligible for a sweep (admin only)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleOrderPolicy {
+    /// Time since placement before an order may be swept
+    pub min_age_seconds: u64,
+    /// Distance from the reference price, in basis points, from which an order may be swept
+    pub min_distance_bps: u64,
+    /// Sweeps only run while more orders than this rest on the book
+    pub min_resting_orders: u64,
+}
+
+impl Default for StaleOrderPolicy {
+    fn default() -> Self {
+        Self {
+            min_age_seconds: 30 * 86_400,
+            min_distance_bps: 5_000,
+            min_resting_orders: 1_000,
+        }
+    }
+}
+
+impl StaleOrderPolicy {
+    /// The distance must be positive so orders at the reference price are never swept; any age is allowed
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_distance_bps == 0 {
+            return Err("Minimum distance must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether orders at `price` are far enough from `reference` to be swept
+    pub fn is_distant(&self, price: Price, reference: Price) -> bool {
+        let distance = u128::from(price.abs_diff(reference)) * 10_000;
+        distance >= u128::from(reference) * u128::from(self.min_distance_bps)
+    }
+
+    /// Whether an order placed at `placed_at` is old enough at `now` to be swept
+    pub fn is_aged(&self, placed_at: Timestamp, now: Timestamp) -> bool {
+        now.micros().saturating_sub(placed_at.micros()) >= self.min_age_seconds.saturating_mul(1_000_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_needs_both_age_and_distance() {
+        let policy = StaleOrderPolicy { min_age_seconds: 60, min_distance_bps: 2_000, min_resting_orders: 0 };
+        assert!(policy.validate().is_ok());
+        assert!(StaleOrderPolicy { min_distance_bps: 0, ..policy }.validate().is_err());
+        assert!(StaleOrderPolicy { min_age_seconds: 0, ..policy }.validate().is_ok());
+
+        // 20% either side of the reference
+        assert!(policy.is_distant(80, 100));
+        assert!(policy.is_distant(120, 100));
+        assert!(!policy.is_distant(81, 100));
+        assert!(!policy.is_distant(119, 100));
+
+        assert!(policy.is_aged(Timestamp::from(0), Timestamp::from(60_000_000)));
+        assert!(!policy.is_aged(Timestamp::from(1), Timestamp::from(60_000_000)));
+        assert!(!policy.is_aged(Timestamp::from(10), Timestamp::from(0)));
+    }
+}
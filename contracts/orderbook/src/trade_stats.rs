@@ -0,0 +1,166 @@
+//! Rolling trade statistics: hourly buckets covering the last `STATS_WINDOW_HOURS`, updated as
+//! each trade is recorded so that market stats never read the trade history. Recording a trade
+//! and dropping the buckets that left the window touch at most one window of buckets.
+
+use linera_base::data_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{Price, Quantity};
+
+/// Hours the rolling statistics cover
+pub const STATS_WINDOW_HOURS: u64 = 24;
+
+/// Hour bucket of a timestamp
+pub fn hour_of(timestamp: Timestamp) -> u64 {
+    timestamp.micros() / 3_600_000_000
+}
+
+/// Trades within one hour
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeBucket {
+    pub hour: u64,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: Quantity,
+    pub trades: u64,
+}
+
+impl TradeBucket {
+    fn new(hour: u64, price: Price) -> Self {
+        Self { hour, open: price, high: price, low: price, close: price, volume: 0, trades: 0 }
+    }
+
+    fn add(&mut self, price: Price, quantity: Quantity) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.volume = self.volume.saturating_add(quantity);
+        self.trades += 1;
+    }
+}
+
+/// Hourly buckets of recent trades
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollingTradeStats {
+    /// Oldest first; at most one bucket per hour of the window
+    pub buckets: Vec<TradeBucket>,
+    /// Oldest trade folded in; `Migrate` backfills the trades before it
+    pub first_trade_id: Option<u64>,
+}
+
+impl RollingTradeStats {
+    /// Adds the newest trade to the bucket of `hour`, dropping buckets that left the window
+    pub fn record(&mut self, trade_id: u64, hour: u64, price: Price, quantity: Quantity) {
+        self.buckets.retain(|bucket| bucket.hour + STATS_WINDOW_HOURS > hour);
+        match self.buckets.last_mut() {
+            Some(bucket) if bucket.hour >= hour => {
+                bucket.close = price;
+                bucket.add(price, quantity);
+            }
+            _ => {
+                let mut bucket = TradeBucket::new(hour, price);
+                bucket.add(price, quantity);
+                self.buckets.push(bucket);
+            }
+        }
+        self.first_trade_id.get_or_insert(trade_id);
+    }
+
+    /// Adds a trade older than every one folded in so far; false, leaving the stats untouched,
+    /// once it falls outside the window at `now_hour`
+    pub fn backfill(&mut self, trade_id: u64, hour: u64, price: Price, quantity: Quantity, now_hour: u64) -> bool {
+        if hour + STATS_WINDOW_HOURS <= now_hour {
+            return false;
+        }
+        match self.buckets.first_mut() {
+            Some(bucket) if bucket.hour <= hour => {
+                bucket.open = price;
+                bucket.add(price, quantity);
+            }
+            _ => {
+                let mut bucket = TradeBucket::new(hour, price);
+                bucket.add(price, quantity);
+                self.buckets.insert(0, bucket);
+            }
+        }
+        self.first_trade_id = Some(trade_id);
+        true
+    }
+
+    /// Totals of the buckets still inside the window at `hour`
+    pub fn window(&self, hour: u64) -> TradeWindow {
+        let mut buckets = self.buckets.iter().filter(|bucket| bucket.hour + STATS_WINDOW_HOURS > hour);
+        let Some(first) = buckets.next() else {
+            return TradeWindow::default();
+        };
+        buckets.fold(
+            TradeWindow {
+                open: first.open,
+                high: first.high,
+                low: first.low,
+                close: first.close,
+                volume: first.volume,
+                trades: first.trades,
+            },
+            |window, bucket| TradeWindow {
+                high: window.high.max(bucket.high),
+                low: window.low.min(bucket.low),
+                close: bucket.close,
+                volume: window.volume.saturating_add(bucket.volume),
+                trades: window.trades + bucket.trades,
+                ..window
+            },
+        )
+    }
+}
+
+/// Trades within the window; all zero without any
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeWindow {
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: Quantity,
+    pub trades: u64,
+}
+
+impl TradeWindow {
+    /// Close less open, saturated to the range of `MarketStats::price_change_24h`
+    pub fn price_change(&self) -> i64 {
+        let change = i128::from(self.close) - i128::from(self.open);
+        change.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_rolls_and_backfills() {
+        let mut stats = RollingTradeStats::default();
+        stats.record(10, 100, 50, 2);
+        stats.record(11, 100, 70, 1);
+        stats.record(12, 110, 60, 4);
+        let window = stats.window(110);
+        assert_eq!(window, TradeWindow { open: 50, high: 70, low: 50, close: 60, volume: 7, trades: 3 });
+        assert_eq!(window.price_change(), 10);
+
+        // Hour 100 leaves the window at 124, as it is dropped by the next trade
+        assert_eq!(stats.window(124), TradeWindow { open: 60, high: 60, low: 60, close: 60, volume: 4, trades: 1 });
+        stats.record(13, 124, 55, 1);
+        assert_eq!(stats.buckets.len(), 2);
+        assert_eq!(stats.first_trade_id, Some(10));
+
+        // Earlier trades fold in front until one falls outside the window
+        let mut stats = RollingTradeStats::default();
+        stats.record(5, 200, 80, 1);
+        assert!(stats.backfill(4, 200, 90, 1, 200));
+        assert!(stats.backfill(3, 190, 40, 1, 200));
+        assert!(!stats.backfill(2, 176, 10, 1, 200));
+        assert_eq!(stats.first_trade_id, Some(3));
+        assert_eq!(stats.window(200), TradeWindow { open: 40, high: 90, low: 40, close: 80, volume: 3, trades: 3 });
+    }
+}
@@ -14,9 +14,12 @@ Supports both intra-Linera settlements and external blockchain bridges.
 */
 
 use async_trait::async_trait;
+use axelarx_bridge::{BridgeAbi, BridgeResponse, ExternalChain, Operation as BridgeOperation};
+use axelarx_math::{self as math, MathError};
 use linera_base::{
-    data_types::{Amount, ApplicationId, Timestamp},
-    identifiers::{Account, ChainId},
+    abi::{ContractAbi, ServiceAbi},
+    data_types::{Amount, ApplicationId, BlockHeight, Timestamp},
+    identifiers::{Account, ChainId, MessageId},
 };
 use linera_sdk::{
     base::{ContractRuntime, ServiceRuntime},
@@ -27,9 +30,62 @@ use linera_views::{
     views::{MapView, QueueView, RegisterView, ViewError},
     RootView,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
+/// Due recurring settlements handled per `ProcessRecurringSettlements` call when no limit is given
+pub const DEFAULT_RECURRING_BATCH: u32 = 10;
+
+/// Most due recurring settlements handled per `ProcessRecurringSettlements` call
+pub const MAX_RECURRING_BATCH: u32 = 50;
+
+/// Upcoming due times returned by `GetRecurringInstances`
+pub const MAX_UPCOMING_INSTANCES: usize = 10;
+
+/// Windowed settlements executed per `ExecuteWindow` call
+pub const MAX_WINDOW_EXECUTIONS: usize = 20;
+
+/// Expirations examined per `ProcessExpiredSettlements` call unless the caller asks otherwise
+pub const DEFAULT_EXPIRY_BATCH: u32 = 10;
+
+/// Most expirations one `ProcessExpiredSettlements` call may examine
+pub const MAX_EXPIRY_BATCH: u32 = 100;
+
+/// Legacy queued expirations one `ProcessExpiredSettlements` call moves to `expirations`
+pub const MAX_EXPIRY_MIGRATION: usize = 50;
+
+/// Seconds a settlement may stay `Executing` before `AuditEscrow` flags it as stuck
+pub const STUCK_EXECUTION_SECONDS: u64 = 600;
+
+/// How long a delivered message's dedupe key is remembered
+pub const MESSAGE_DEDUPE_TTL_SECONDS: u64 = 24 * 3_600;
+
+/// Most dedupe keys remembered; the oldest are forgotten first past it
+pub const MAX_SEEN_MESSAGES: u64 = 10_000;
+
+/// Dedupe keys forgotten per delivered message
+const MAX_DEDUPE_PRUNE: usize = 20;
+
+/// Orphaned escrow records returned to their owners per `AuditEscrow` run
+pub const MAX_ORPHAN_RETURNS: usize = 20;
+
+/// Longest settlement memo, in characters
+pub const MAX_MEMO_LENGTH: usize = 256;
+
+/// Longest external reference, in characters
+pub const MAX_EXTERNAL_REF_LENGTH: usize = 64;
+
+/// Most counterparties on each of an account's allowlist and denylist
+pub const MAX_COUNTERPARTY_LIST: usize = 256;
+
+/// Deferred settlement requests initiated per block while no throttle is configured
+pub const DEFAULT_DEFERRED_DRAIN: u32 = 50;
+
+/// Dead letters kept; past it the oldest are dropped
+pub const MAX_DEAD_LETTERS: u64 = 1_000;
+
 /// Settlement states with clear progression
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SettlementStatus {
@@ -68,6 +124,198 @@ pub struct EscrowState {
     pub escrowed_at: Option<Timestamp>,
     /// Transaction hash (for verification)
     pub tx_hash: Option<String>,
+    /// Custodian that confirmed the escrow for the party, if it was not the party itself
+    #[serde(default)]
+    pub confirmed_by: Option<Account>,
+    /// Set when `asset` and `amount` are a substitute for the leg's own asset
+    #[serde(default)]
+    pub substitution: Option<AssetSubstitution>,
+}
+
+impl EscrowState {
+    /// Escrow of a party that owes nothing once a same-asset settlement is netted: counts as
+    /// escrowed from the start and holds nothing
+    pub fn waived(asset: &str) -> Self {
+        EscrowState { is_escrowed: true, asset: asset.to_string(), ..EscrowState::default() }
+    }
+    
+    /// Whether there is still something in escrow to pay out or refund
+    pub fn holds_funds(&self) -> bool {
+        self.is_escrowed && self.amount > Amount::ZERO
+    }
+    
+    /// Asset held in escrow for a leg in `leg_asset`
+    pub fn held_asset<'a>(&'a self, leg_asset: &'a str) -> &'a str {
+        if self.substitution.is_some() {
+            &self.asset
+        } else {
+            leg_asset
+        }
+    }
+}
+
+/// Leg escrowed in a substitute asset at a published conversion rate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetSubstitution {
+    pub original_asset: String,
+    pub original_amount: Amount,
+    /// Substitute units per original unit (1e8 fixed point) the escrow was computed at
+    pub rate: u64,
+}
+
+/// Published rate at which one asset may be escrowed in place of another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionRate {
+    /// Units of the substitute per unit of the original (1e8 fixed point)
+    pub rate: u64,
+    /// How long the rate may be used after it was published
+    pub max_staleness_seconds: u64,
+    pub updated_at: Timestamp,
+    pub updated_by: Account,
+}
+
+impl ConversionRate {
+    pub fn is_stale(&self, now: Timestamp) -> bool {
+        now.micros().saturating_sub(self.updated_at.micros()) / 1_000_000 > self.max_staleness_seconds
+    }
+}
+
+/// Permission a settlement party gives a custodian to confirm escrows and claim refunds for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustodianGrant {
+    /// Assets the custodian may act on; empty covers every asset
+    pub assets: Vec<String>,
+    pub granted_at: Timestamp,
+}
+
+impl CustodianGrant {
+    pub fn covers(&self, asset: &str) -> bool {
+        self.assets.is_empty() || self.assets.iter().any(|covered| covered == asset)
+    }
+}
+
+/// List a counterparty is on in an account's counterparty policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CounterpartyListing {
+    /// While the allowlist is non-empty, only its counterparties may be settled with
+    Allowed,
+    /// Never settled with
+    Denied,
+}
+
+/// Counterparties an account restricts its new settlements to, and those it refuses
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterpartyPolicy {
+    pub allowlist: Vec<Account>,
+    pub denylist: Vec<Account>,
+}
+
+impl CounterpartyPolicy {
+    /// Moves `counterparty` onto the list of `listing`, or off both lists; false, changing
+    /// nothing, if that list already holds `MAX_COUNTERPARTY_LIST` others
+    pub fn set(&mut self, counterparty: Account, listing: Option<CounterpartyListing>) -> bool {
+        let target = match listing {
+            Some(CounterpartyListing::Allowed) => Some(&self.allowlist),
+            Some(CounterpartyListing::Denied) => Some(&self.denylist),
+            None => None,
+        };
+        if target.is_some_and(|list| list.len() >= MAX_COUNTERPARTY_LIST && !list.contains(&counterparty)) {
+            return false;
+        }
+        self.allowlist.retain(|listed| *listed != counterparty);
+        self.denylist.retain(|listed| *listed != counterparty);
+        match listing {
+            Some(CounterpartyListing::Allowed) => self.allowlist.push(counterparty),
+            Some(CounterpartyListing::Denied) => self.denylist.push(counterparty),
+            None => {}
+        }
+        true
+    }
+}
+
+/// Trading fees computed by the order book and charged when the settlement executes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementFees {
+    /// Kept from what the maker receives, in the taker asset
+    pub maker_fee: Amount,
+    /// Kept from what the taker receives, in the maker asset
+    pub taker_fee: Amount,
+    pub recipient: Account,
+}
+
+impl SettlementFees {
+    /// Each fee has to fit in the leg it is taken from.
+    pub fn validate(&self, maker_amount: Amount, taker_amount: Amount) -> Result<(), SettlementError> {
+        for (fee, amount) in [(self.maker_fee, taker_amount), (self.taker_fee, maker_amount)] {
+            if fee > amount {
+                return Err(SettlementError::FeeExceedsAmount { fee, amount });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Legs of a same-asset settlement netted against each other. Fees are taken as if both legs had
+/// moved, so what the parties owe always covers what they receive plus `fees`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetTransfer {
+    pub maker_owes: Amount,
+    pub taker_owes: Amount,
+    pub maker_receives: Amount,
+    pub taker_receives: Amount,
+    pub fees: Amount,
+}
+
+impl NetTransfer {
+    /// Nets the legs; `fees` must already fit them
+    pub fn new(maker_amount: Amount, taker_amount: Amount, fees: Option<&SettlementFees>) -> Self {
+        let (maker_fee, taker_fee) = fees.map_or((Amount::ZERO, Amount::ZERO), |fees| (fees.maker_fee, fees.taker_fee));
+        let maker_gets = taker_amount.saturating_sub(maker_fee);
+        let taker_gets = maker_amount.saturating_sub(taker_fee);
+        NetTransfer {
+            maker_owes: maker_amount.saturating_sub(maker_gets),
+            taker_owes: taker_amount.saturating_sub(taker_gets),
+            maker_receives: maker_gets.saturating_sub(maker_amount),
+            taker_receives: taker_gets.saturating_sub(taker_amount),
+            fees: maker_fee.saturating_add(taker_fee),
+        }
+    }
+    
+    /// Whether nothing moves at all
+    pub fn is_empty(&self) -> bool {
+        self.maker_owes == Amount::ZERO && self.taker_owes == Amount::ZERO
+    }
+}
+
+/// Who has to confirm a settlement that does not execute on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfirmationRule {
+    /// The first party to call `ExecuteSettlement` executes it
+    EitherParty,
+    /// It executes once both parties have called `ExecuteSettlement`
+    BothParties,
+}
+
+/// Final confirmation a fully escrowed settlement waits for instead of executing right away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationTerms {
+    /// Time from full escrow to confirm in; the settlement then expires and both escrows are refunded
+    pub window_seconds: u64,
+    pub rule: ConfirmationRule,
+}
+
+impl ConfirmationTerms {
+    /// The window must be positive, and windowed settlements already execute at their boundary
+    pub fn validate(&self, windowed: bool) -> Result<(), SettlementError> {
+        let reason = if self.window_seconds == 0 {
+            "Confirmation window must be positive"
+        } else if windowed {
+            "Windowed settlements execute at the window boundary"
+        } else {
+            return Ok(());
+        };
+        Err(SettlementError::InvalidConfirmationTerms { reason: reason.to_string() })
+    }
 }
 
 /// Settlement record with comprehensive tracking
@@ -103,6 +351,293 @@ pub struct Settlement {
     // Additional metadata
     pub failure_reason: Option<String>,
     pub retry_count: u32,
+    
+    /// Fees charged on execution; refunds return the full escrow
+    pub fees: Option<SettlementFees>,
+    
+    /// Executed at a settlement window boundary instead of as soon as it is fully escrowed
+    #[serde(default)]
+    pub windowed: bool,
+    /// Window boundary a fully escrowed windowed settlement waits for
+    #[serde(default)]
+    pub execute_at: Option<Timestamp>,
+    /// When execution started; set on entering `Executing`
+    #[serde(default)]
+    pub execution_started_at: Option<Timestamp>,
+    
+    /// Bridge transfer carrying the maker's leg: the deposit its escrow was proven with, then
+    /// the withdrawal delivering it to the taker
+    #[serde(default)]
+    pub maker_bridge_transfer_id: Option<u64>,
+    #[serde(default)]
+    pub maker_bridge_status: Option<BridgeTransferStatus>,
+    /// Bridge transfer carrying the taker's leg, as for the maker's
+    #[serde(default)]
+    pub taker_bridge_transfer_id: Option<u64>,
+    #[serde(default)]
+    pub taker_bridge_status: Option<BridgeTransferStatus>,
+    
+    /// Where the request creating the settlement came from; None for settlements created before
+    /// it was recorded
+    #[serde(default)]
+    pub provenance: Option<SettlementProvenance>,
+    
+    /// Free text from the creator; never read by settlement logic
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Creator's own id for the deal, unique per creator
+    #[serde(default)]
+    pub external_ref: Option<String>,
+    
+    /// Recurring settlement this settlement is an instance of
+    #[serde(default)]
+    pub recurring: Option<RecurringLink>,
+    /// Settlement template this settlement was initiated from
+    #[serde(default)]
+    pub template_id: Option<u64>,
+    
+    /// Confirmation awaited once fully escrowed; None executes on the second escrow
+    #[serde(default)]
+    pub confirmation: Option<ConfirmationTerms>,
+    /// Parties that confirmed execution so far
+    #[serde(default)]
+    pub confirmed_by: Vec<Account>,
+    /// One leg is zero: its party accepts with `ConfirmEscrow` instead of escrowing, and only the
+    /// other leg moves
+    #[serde(default)]
+    pub one_sided: bool,
+}
+
+impl Settlement {
+    /// Event announcing the completion, carrying the creator's memo and reference
+    pub fn completed_event(&self, timestamp: Timestamp) -> SettlementEvent {
+        SettlementEvent::SettlementCompleted {
+            settlement_id: self.id,
+            trade_id: self.trade_id,
+            memo: self.memo.clone(),
+            external_ref: self.external_ref.clone(),
+            timestamp,
+        }
+    }
+    
+    /// Whether the settlement executes as soon as it is fully escrowed
+    pub fn auto_execute(&self) -> bool {
+        self.confirmation.is_none()
+    }
+    
+    /// Whether the parties have confirmed enough for the settlement to execute
+    pub fn is_confirmed(&self) -> bool {
+        match self.confirmation.map(|terms| terms.rule) {
+            None => true,
+            Some(ConfirmationRule::EitherParty) => !self.confirmed_by.is_empty(),
+            Some(ConfirmationRule::BothParties) => {
+                [self.maker, self.taker].iter().all(|party| self.confirmed_by.contains(party))
+            }
+        }
+    }
+    
+    /// Whether escrow may be reclaimed: the settlement failed, was cancelled or ran out of time.
+    /// An interrupted execution is left to `ResolveStuckExecution` instead.
+    pub fn is_refundable(&self, now: Timestamp) -> bool {
+        match self.status {
+            SettlementStatus::Expired | SettlementStatus::Failed | SettlementStatus::Cancelled => true,
+            SettlementStatus::Executing => false,
+            _ => now > self.expires_at,
+        }
+    }
+    
+    /// Whether both legs are in the same asset, in which case only their net difference moves
+    pub fn is_same_asset(&self) -> bool {
+        self.maker_asset == self.taker_asset
+    }
+    
+    /// The netted legs of a same-asset settlement
+    pub fn net_transfer(&self) -> Option<NetTransfer> {
+        self.is_same_asset().then(|| NetTransfer::new(self.maker_amount, self.taker_amount, self.fees.as_ref()))
+    }
+    
+    /// What `party` has to escrow: its leg, or its share of the net for a same-asset settlement
+    pub fn escrow_required(&self, party: Account) -> Amount {
+        match self.net_transfer() {
+            Some(net) if party == self.maker => net.maker_owes,
+            Some(net) if party == self.taker => net.taker_owes,
+            Some(_) => Amount::ZERO,
+            None if party == self.maker => self.maker_amount,
+            None if party == self.taker => self.taker_amount,
+            None => Amount::ZERO,
+        }
+    }
+    
+    /// Whether `party` holds the zero leg of a one-sided settlement, which it accepts instead of
+    /// escrowing
+    pub fn is_zero_leg(&self, party: Account) -> bool {
+        let amount = if party == self.maker { self.maker_amount } else { self.taker_amount };
+        self.one_sided && amount == Amount::ZERO
+    }
+    
+    /// What the settlement adds to `SettlementStats::total_volume` on completion: both legs, less
+    /// the zero leg of a one-sided settlement
+    pub fn volume(&self) -> Result<Amount, MathError> {
+        [(self.maker, self.maker_amount), (self.taker, self.taker_amount)].into_iter()
+            .filter(|(party, _)| !self.is_zero_leg(*party))
+            .try_fold(Amount::ZERO, |total, (_, amount)| math::checked_add(total, amount))
+    }
+    
+    /// (payer, payee, asset, fee) of the maker's and the taker's leg; each side's fee is taken
+    /// from what it receives. A substituted leg pays out, and takes its fee, in the substitute.
+    pub fn legs(&self) -> Result<[(Account, Account, String, Amount); 2], MathError> {
+        let (maker_fee, taker_fee) = self.fees.as_ref()
+            .map_or((Amount::ZERO, Amount::ZERO), |fees| (fees.maker_fee, fees.taker_fee));
+        let leg = |escrow: &EscrowState, asset: &str, fee: Amount| -> Result<(String, Amount), MathError> {
+            match &escrow.substitution {
+                Some(substitution) => Ok((escrow.asset.clone(), math::convert(fee, substitution.rate)?)),
+                None => Ok((asset.to_string(), fee)),
+            }
+        };
+        let (maker_asset, taker_fee) = leg(&self.maker_escrow, &self.maker_asset, taker_fee)?;
+        let (taker_asset, maker_fee) = leg(&self.taker_escrow, &self.taker_asset, maker_fee)?;
+        Ok([
+            (self.maker, self.taker, maker_asset, taker_fee),
+            (self.taker, self.maker, taker_asset, maker_fee),
+        ])
+    }
+    
+    /// Bridge transfer linked to `payer`'s leg
+    pub fn bridge_transfer_id(&self, payer: Account) -> Option<u64> {
+        if payer == self.maker {
+            self.maker_bridge_transfer_id
+        } else if payer == self.taker {
+            self.taker_bridge_transfer_id
+        } else {
+            None
+        }
+    }
+    
+    /// Links `transfer_id` to `payer`'s leg, in place of any transfer linked before
+    pub fn link_bridge_transfer(&mut self, payer: Account, transfer_id: u64, status: BridgeTransferStatus) {
+        if payer == self.maker {
+            self.maker_bridge_transfer_id = Some(transfer_id);
+            self.maker_bridge_status = Some(status);
+        } else if payer == self.taker {
+            self.taker_bridge_transfer_id = Some(transfer_id);
+            self.taker_bridge_status = Some(status);
+        }
+    }
+    
+    /// What `party` has to do next, given what it still holds in escrow for this settlement.
+    /// Follows the checks of `confirm_escrow` and `claim_refund`.
+    pub fn next_action(&self, party: Account, escrowed: Amount, now: Timestamp) -> NextAction {
+        let (escrow, counterparty, asset) = if party == self.maker {
+            (&self.maker_escrow, &self.taker_escrow, &self.maker_asset)
+        } else if party == self.taker {
+            (&self.taker_escrow, &self.maker_escrow, &self.taker_asset)
+        } else {
+            return NextAction::None;
+        };
+        let amount = self.escrow_required(party);
+        
+        if self.is_refundable(now) {
+            return if escrowed > Amount::ZERO {
+                NextAction::ClaimRefund { asset: escrow.held_asset(asset).to_string(), amount: escrowed }
+            } else {
+                NextAction::None
+            };
+        }
+        let seconds_remaining = self.expires_at.micros().saturating_sub(now.micros()) / 1_000_000;
+        match self.status {
+            SettlementStatus::Pending | SettlementStatus::MakerEscrowed | SettlementStatus::TakerEscrowed => {
+                if !escrow.is_escrowed {
+                    NextAction::Escrow { asset: asset.clone(), amount, seconds_remaining }
+                } else if !counterparty.is_escrowed {
+                    NextAction::AwaitCounterparty { seconds_remaining }
+                } else {
+                    NextAction::None
+                }
+            }
+            _ => NextAction::None,
+        }
+    }
+}
+
+/// First window boundary strictly after `now`; windows are aligned to the Unix epoch.
+pub fn next_window_boundary(now: Timestamp, window_seconds: u64) -> Timestamp {
+    let window_micros = window_seconds.saturating_mul(1_000_000).max(1);
+    Timestamp::from((now.micros() / window_micros).saturating_add(1).saturating_mul(window_micros))
+}
+
+/// Settlement track record of an account, counted once per settlement when it is finalized
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reputation {
+    /// Settlements with this account that completed, were cancelled or expired
+    pub finalized: u64,
+    pub completed: u64,
+    /// Cancelled by this account
+    pub cancelled: u64,
+    /// Expired without this account's escrow after the counterparty escrowed
+    pub defaulted: u64,
+    /// Escrows made in finalized settlements, and their total delay after creation
+    pub escrows: u64,
+    pub total_escrow_seconds: u64,
+}
+
+impl Reputation {
+    /// Defaulted share of finalized settlements, in basis points; zero without history
+    pub fn default_rate_bps(&self) -> u64 {
+        if self.finalized == 0 {
+            return 0;
+        }
+        self.defaulted.saturating_mul(10_000) / self.finalized
+    }
+    
+    /// Mean time from settlement creation to this account's escrow
+    pub fn average_escrow_seconds(&self) -> Option<u64> {
+        self.total_escrow_seconds.checked_div(self.escrows)
+    }
+}
+
+/// Next step for a settlement participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NextAction {
+    /// Escrow `amount` of `asset` within `seconds_remaining`
+    Escrow { asset: String, amount: Amount, seconds_remaining: u64 },
+    /// Escrowed; the counterparty has `seconds_remaining` to escrow
+    AwaitCounterparty { seconds_remaining: u64 },
+    /// Reclaim what is still held in escrow
+    ClaimRefund { asset: String, amount: Amount },
+    /// Nothing left to do
+    None,
+}
+
+/// A settlement with each participant's next step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementActions {
+    pub settlement: Settlement,
+    pub maker: NextAction,
+    pub taker: NextAction,
+    /// Legs whose withdrawal waits on a corridor last reported unavailable
+    #[serde(default)]
+    pub delayed_withdrawals: Vec<DelayedWithdrawal>,
+}
+
+pub use axelarx_bridge::CorridorAvailability;
+
+/// Last availability the corridor source reported for a corridor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorridorStatus {
+    pub availability: CorridorAvailability,
+    pub changed_at: Timestamp,
+}
+
+/// A pending withdrawal of a settlement leg held up by its corridor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelayedWithdrawal {
+    /// Party whose leg the withdrawal carries
+    pub payer: Account,
+    pub transfer_id: u64,
+    pub corridor: u64,
+    pub availability: CorridorAvailability,
+    /// When the corridor stopped being open
+    pub since: Timestamp,
 }
 
 /// Cross-chain bridge information
@@ -117,6 +652,10 @@ pub struct BridgeConfig {
     pub fee_rate_bps: u64, // Basis points (1/10000)
     pub is_active: bool,
     pub supported_assets: Vec<String>,
+    /// Bridge application corridor (`ExternalChain::chain_id`) withdrawals to this chain go
+    /// through; None when its availability is not tracked
+    #[serde(default)]
+    pub corridor: Option<u64>,
 }
 
 /// Bridge transfer record
@@ -134,6 +673,37 @@ pub struct BridgeTransfer {
     pub created_at: Timestamp,
     pub completed_at: Option<Timestamp>,
     pub confirmations: u64,
+    /// Settlement leg the transfer carried
+    #[serde(default)]
+    pub settlement_ref: Option<SettlementRef>,
+}
+
+impl BridgeTransfer {
+    /// Whether the transfer proves `party` brought `asset` in to escrow: a completed deposit of
+    /// its own, not yet linked to another leg
+    pub fn check_escrow_proof(&self, party: Account, asset: &str) -> Result<(), SettlementError> {
+        let reason = if self.direction != BridgeDirection::Deposit {
+            "not a deposit"
+        } else if self.status != BridgeTransferStatus::Completed {
+            "deposit not completed"
+        } else if self.user != party {
+            "deposited for another account"
+        } else if self.asset != asset {
+            "deposited a different asset"
+        } else if self.settlement_ref.is_some() {
+            "already linked to a settlement leg"
+        } else {
+            return Ok(());
+        };
+        Err(SettlementError::InvalidBridgeLink { transfer_id: self.id, reason: reason.to_string() })
+    }
+}
+
+/// Settlement leg linked to a bridge transfer, named by the party that pays it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementRef {
+    pub settlement_id: u64,
+    pub payer: Account,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -151,1172 +721,4830 @@ pub enum BridgeTransferStatus {
     Refunded,
 }
 
-/// Settlement operations
+/// Recorded and recomputed escrow total for one asset
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum Operation {
-    /// Initialize a new settlement
-    InitiateSettlement {
-        trade_id: u64,
-        maker: Account,
-        taker: Account,
-        maker_asset: String,
-        taker_asset: String,
-        maker_amount: Amount,
-        taker_amount: Amount,
-        maker_chain: ChainId,
-        taker_chain: ChainId,
-        timeout_seconds: u64,
-    },
-    
-    /// Confirm escrow from a party (locks funds)
-    ConfirmEscrow {
-        settlement_id: u64,
-    },
-    
-    /// Execute settlement (after both parties escrow)
-    ExecuteSettlement {
-        settlement_id: u64,
-    },
-    
-    /// Cancel settlement (before both parties escrow)
-    CancelSettlement {
-        settlement_id: u64,
-        reason: String,
-    },
-    
-    /// Claim refund for expired/failed settlement
-    ClaimRefund {
-        settlement_id: u64,
-    },
-    
-    /// Process expired settlements (can be called by anyone)
-    ProcessExpiredSettlements,
-    
-    /// Configure bridge settings (admin only)
-    ConfigureBridge {
-        chain_id: String,
-        config: BridgeConfig,
-    },
-    
-    /// Disable a bridge (admin only)
-    DisableBridge {
-        chain_id: String,
-    },
-    
-    /// Process bridge deposit (from relayer)
-    ProcessBridgeDeposit {
-        chain_id: String,
-        tx_hash: String,
-        user: Account,
-        asset: String,
-        amount: Amount,
-        confirmations: u64,
-    },
-    
-    /// Initiate bridge withdrawal
-    InitiateBridgeWithdrawal {
-        chain_id: String,
-        asset: String,
-        amount: Amount,
-        destination_address: String,
-    },
-    
-    /// Complete bridge withdrawal (from relayer)
-    CompleteBridgeWithdrawal {
-        transfer_id: u64,
-        tx_hash: String,
-        success: bool,
-    },
-    
-    /// Deposit to settlement account
-    Deposit {
-        asset: String,
-        amount: Amount,
-    },
-    
-    /// Withdraw from settlement account
-    Withdraw {
-        asset: String,
-        amount: Amount,
-    },
+pub struct EscrowDrift {
+    pub asset: String,
+    /// Incrementally maintained `total_escrowed`
+    pub recorded: Amount,
+    /// Sum of the escrow records of active settlements
+    pub computed: Amount,
 }
 
-/// Cross-chain messages
+/// Result of the last `AuditEscrow` run
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum Message {
-    /// Settlement request from order book
-    SettlementRequest {
-        trade_id: u64,
-        maker: Account,
-        taker: Account,
-        maker_asset: String,
-        taker_asset: String,
-        maker_amount: Amount,
-        taker_amount: Amount,
-        timeout_seconds: u64,
-    },
-    
-    /// Escrow confirmation from another chain
-    EscrowConfirmation {
-        settlement_id: u64,
-        party: Account,
-        confirmed: bool,
-        amount: Amount,
-    },
-    
-    /// Settlement completion notification
-    SettlementComplete {
-        settlement_id: u64,
-        success: bool,
-        failure_reason: Option<String>,
-    },
-    
-    /// Bridge event notification
-    BridgeEvent {
-        chain_id: String,
-        event_type: BridgeEventType,
-        transfer_id: u64,
-        data: Vec<u8>,
-    },
-    
-    /// Refund notification
-    RefundProcessed {
-        settlement_id: u64,
-        party: Account,
-        amount: Amount,
-        asset: String,
-    },
+pub struct EscrowAuditReport {
+    pub audited_at: Timestamp,
+    pub active_settlements: u64,
+    /// Assets whose recorded total disagrees with the records; empty when consistent
+    pub drift: Vec<EscrowDrift>,
+    /// Settlements `Executing` for longer than `STUCK_EXECUTION_SECONDS`
+    #[serde(default)]
+    pub stuck_executions: Vec<u64>,
+    /// Escrow records left on completed or unknown settlements, held for admin review
+    #[serde(default)]
+    pub flagged_escrows: Vec<FlaggedEscrow>,
 }
 
+/// Escrow record that outlived a settlement which already paid out
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum BridgeEventType {
-    DepositDetected,
-    DepositConfirmed,
-    WithdrawalInitiated,
-    WithdrawalCompleted,
-    WithdrawalFailed,
+pub struct FlaggedEscrow {
+    pub settlement_id: u64,
+    pub party: Account,
+    pub asset: String,
+    pub amount: Amount,
+    /// `None` when the settlement record itself is missing
+    pub status: Option<SettlementStatus>,
 }
 
-/// Settlement errors
-#[derive(Error, Debug)]
-pub enum SettlementError {
-    #[error("Settlement not found: {settlement_id}")]
-    SettlementNotFound { settlement_id: u64 },
-    
-    #[error("Invalid settlement status: expected {expected:?}, got {actual:?}")]
-    InvalidStatus {
-        expected: SettlementStatus,
-        actual: SettlementStatus,
-    },
-    
-    #[error("Settlement expired at {expired_at:?}")]
-    SettlementExpired { expired_at: Timestamp },
-    
-    #[error("Unauthorized access: {reason}")]
-    Unauthorized { reason: String },
-    
-    #[error("Insufficient balance: required {required}, available {available}")]
-    InsufficientBalance { required: Amount, available: Amount },
-    
-    #[error("Bridge not configured for chain: {chain_id}")]
-    BridgeNotConfigured { chain_id: String },
-    
-    #[error("Bridge is disabled: {chain_id}")]
-    BridgeDisabled { chain_id: String },
-    
-    #[error("Bridge operation failed: {reason}")]
-    BridgeError { reason: String },
-    
-    #[error("Asset not supported by bridge: {asset}")]
-    AssetNotSupported { asset: String },
-    
-    #[error("Amount below minimum: {amount}, minimum: {minimum}")]
-    BelowMinimum { amount: Amount, minimum: Amount },
-    
-    #[error("Amount above maximum: {amount}, maximum: {maximum}")]
-    AboveMaximum { amount: Amount, maximum: Amount },
-    
-    #[error("Transfer not found: {transfer_id}")]
-    TransferNotFound { transfer_id: u64 },
-    
-    #[error("Already escrowed")]
-    AlreadyEscrowed,
-    
-    #[error("Cannot cancel: {reason}")]
-    CannotCancel { reason: String },
-    
-    #[error("View error: {0}")]
-    ViewError(#[from] ViewError),
+/// What the audit does with an escrow record whose settlement is no longer active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrphanedEscrow {
+    /// The settlement never paid out: the escrow goes back to its owner
+    Return,
+    /// The settlement paid out, so the record is double-counted; an admin has to look
+    Review,
 }
 
-/// Settlement engine state
-#[derive(RootView)]
-pub struct SettlementState<C> {
-    /// Next settlement ID
-    pub next_settlement_id: RegisterView<C, u64>,
-    
-    /// All settlements
-    pub settlements: MapView<C, u64, Settlement>,
-    
-    /// User settlements mapping
-    pub user_settlements: MapView<C, Account, Vec<u64>>,
-    
-    /// Active settlements (not yet completed/failed)
-    pub active_settlements: MapView<C, u64, ()>,
-    
-    /// Settlements pending expiration check
-    pub expiration_queue: QueueView<C, (Timestamp, u64)>,
-    
-    /// Bridge configurations
-    pub bridge_configs: MapView<C, String, BridgeConfig>,
-    
-    /// User balances per asset
-    pub balances: MapView<C, (Account, String), Amount>,
-    
-    /// Escrowed balances (locked in settlements)
-    pub escrowed_balances: MapView<C, (u64, Account, String), Amount>,
-    
-    /// Next bridge transfer ID
-    pub next_transfer_id: RegisterView<C, u64>,
-    
-    /// Bridge transfers
-    pub bridge_transfers: MapView<C, u64, BridgeTransfer>,
-    
-    /// User bridge transfers
-    pub user_transfers: MapView<C, Account, Vec<u64>>,
-    
-    /// Pending bridge deposits (tx_hash -> transfer_id)
-    pub pending_deposits: MapView<C, String, u64>,
-    
-    /// Pending bridge withdrawals
-    pub pending_withdrawals: QueueView<C, u64>,
+impl OrphanedEscrow {
+    /// Resolution for a record left on a settlement in `status`; `None` while the
+    /// settlement can still release it itself
+    pub fn for_status(status: Option<SettlementStatus>) -> Option<Self> {
+        match status {
+            Some(SettlementStatus::Expired | SettlementStatus::Cancelled | SettlementStatus::Failed
+                | SettlementStatus::Refunded) => Some(OrphanedEscrow::Return),
+            Some(SettlementStatus::Completed) | None => Some(OrphanedEscrow::Review),
+            Some(_) => None,
+        }
+    }
+}
+
+/// How `ResolveStuckExecution` finished an interrupted execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionResolution {
+    /// The legs still in escrow were paid out; the settlement completed
+    Completed,
+    /// The legs already paid were taken back and every escrow returned; the settlement failed
+    Reversed,
+}
+
+impl ExecutionResolution {
+    /// Completes when the escrow of every unpaid leg covers its fee, given as (escrowed, fee)
+    pub fn for_unpaid_legs(legs: &[(Amount, Amount)]) -> Self {
+        if legs.iter().all(|(escrowed, fee)| escrowed >= fee) {
+            ExecutionResolution::Completed
+        } else {
+            ExecutionResolution::Reversed
+        }
+    }
+}
+
+/// Per-asset holdings of the settlement contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetTotals {
+    pub asset: String,
+    pub balances: Amount,
+    pub escrowed: Amount,
+}
+
+/// Record created by an operation submitted with a client request id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationReceipt {
+    pub client_request_id: u64,
+    /// Id of the created settlement or bridge transfer
+    pub id: u64,
+    pub created_at: Timestamp,
+}
+
+/// How the request creating a settlement reached this application
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementOriginKind {
+    /// `Operation::InitiateSettlement` in a block of this chain
+    Operation,
+    /// `Operation::RequestSettlement` called by a market application on this chain
+    ApplicationCall,
+    /// `Message::SettlementRequest` from a market on another chain
+    Message,
+}
+
+/// Chain and application a settlement was requested from; settlements are indexed by it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SettlementOrigin {
+    pub chain_id: ChainId,
+    /// Market application behind a call or message; None for operations
+    pub application_id: Option<ApplicationId>,
+}
+
+/// Where the request creating a settlement came from, kept for disputes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementProvenance {
+    pub kind: SettlementOriginKind,
+    pub origin: SettlementOrigin,
+    /// Signer of the operation, or the one a call or message was made for
+    pub signer: Option<Account>,
+    /// Message that carried a `SettlementRequest`
+    pub message_id: Option<MessageId>,
+    /// Height of the block of this chain that created the settlement
+    pub block_height: BlockHeight,
+    /// Client request id an `InitiateSettlement` was submitted with
+    pub client_request_id: Option<u64>,
+}
+
+/// Order book market allowed to request settlements
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketRegistration {
+    /// Chain the market's order book runs on
+    pub chain_id: ChainId,
+    pub base_asset: String,
+    pub quote_asset: String,
+}
+
+impl MarketRegistration {
+    /// Checks that a settlement request came from the market's chain and trades its pair.
+    pub fn verify_request(
+        &self,
+        origin: Option<ChainId>,
+        maker_asset: &str,
+        taker_asset: &str,
+    ) -> Result<(), SettlementError> {
+        if origin != Some(self.chain_id) {
+            return Err(SettlementError::Unauthorized {
+                reason: "Settlement request from outside the market's chain".to_string(),
+            });
+        }
+        let pair = (maker_asset, taker_asset);
+        let base = self.base_asset.as_str();
+        let quote = self.quote_asset.as_str();
+        if pair != (base, quote) && pair != (quote, base) {
+            return Err(SettlementError::MarketAssetMismatch {
+                maker_asset: maker_asset.to_string(),
+                taker_asset: taker_asset.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Limit on how fast each origin, a market's chain and application, may initiate settlements.
+/// Requests past it are deferred, not dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementThrottle {
+    /// Most settlements one origin may initiate per window
+    pub max_per_window: u32,
+    /// Window length; zero counts per block
+    pub window_seconds: u64,
+    /// Most deferred requests initiated per block, across all origins
+    pub max_drained_per_block: u32,
+}
+
+impl SettlementThrottle {
+    pub fn validate(&self) -> Result<(), SettlementError> {
+        if self.max_per_window == 0 || self.max_drained_per_block == 0 {
+            return Err(SettlementError::InvalidThrottle { reason: "Limits must be positive".to_string() });
+        }
+        Ok(())
+    }
     
-    /// Settlement statistics
-    pub stats: RegisterView<C, SettlementStats>,
+    /// Window a request received at `now`, in the block at `height`, counts against
+    pub fn window(&self, now: Timestamp, height: BlockHeight) -> u64 {
+        if self.window_seconds == 0 {
+            height.0
+        } else {
+            now.micros() / self.window_seconds.saturating_mul(1_000_000)
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SettlementStats {
-    pub total_settlements: u64,
-    pub completed_settlements: u64,
-    pub failed_settlements: u64,
-    pub total_volume: Amount,
-    pub total_bridge_deposits: Amount,
-    pub total_bridge_withdrawals: Amount,
+/// Settlements an origin initiated in its current throttle window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThrottleBucket {
+    pub window: u64,
+    pub initiated: u32,
 }
 
-/// Settlement engine contract
-pub struct SettlementContract;
+impl ThrottleBucket {
+    /// Counts one more initiation in `window` unless it already holds `max_per_window`
+    pub fn admit(&mut self, window: u64, max_per_window: u32) -> bool {
+        if self.window != window {
+            *self = ThrottleBucket { window, initiated: 0 };
+        }
+        if self.initiated >= max_per_window {
+            return false;
+        }
+        self.initiated += 1;
+        true
+    }
+}
 
-#[async_trait]
-impl Contract for SettlementContract {
-    type Message = Message;
-    type Parameters = ();
-    type State = SettlementState<ContractRuntime<Self>>;
+/// A `SettlementRequest` held back by the throttle; it is initiated, in arrival order, once its
+/// origin has room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredSettlementRequest {
+    pub trade_id: u64,
+    pub maker: Account,
+    pub taker: Account,
+    pub maker_asset: String,
+    pub taker_asset: String,
+    pub maker_amount: Amount,
+    pub taker_amount: Amount,
+    /// Counted from initiation, not from deferral
+    pub timeout_seconds: u64,
+    pub fees: Option<SettlementFees>,
+    pub maker_chain: ChainId,
+    pub taker_chain: ChainId,
+    /// Provenance of the message that carried the request
+    pub provenance: SettlementProvenance,
+    pub deferred_at: Timestamp,
+}
 
-    async fn load(runtime: ContractRuntime<Self>) -> Self {
-        SettlementContract
+/// A market's settlement request relayed by this chain to the chain settling it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayedSettlementRequest {
+    pub settlement_chain: ChainId,
+    /// Why the settlement chain failed the request, until the market takes it
+    pub rejection: Option<SettlementRejectionCode>,
+}
+
+impl RelayedSettlementRequest {
+    /// Records a rejection sent from `origin`; only the settlement chain may reject the request.
+    pub fn reject(&mut self, origin: Option<ChainId>, reason_code: SettlementRejectionCode) -> bool {
+        if origin != Some(self.settlement_chain) {
+            return false;
+        }
+        self.rejection = Some(reason_code);
+        true
     }
+}
 
-    async fn instantiate(&mut self, state: &mut Self::State, _argument: ()) {
-        state.next_settlement_id.set(1);
-        state.next_transfer_id.set(1);
-        state.stats.set(SettlementStats::default());
+/// Positions `head..tail` of an origin's deferred requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredQueue {
+    pub head: u64,
+    pub tail: u64,
+}
+
+impl DeferredQueue {
+    pub fn depth(&self) -> u64 {
+        self.tail - self.head
     }
+}
 
-    async fn execute_operation(
-        &mut self,
-        runtime: &mut ContractRuntime<Self>,
-        state: &mut Self::State,
-        operation: Operation,
-    ) -> Result<(), Self::Error> {
-        match operation {
-            Operation::InitiateSettlement {
-                trade_id,
-                maker,
-                taker,
-                maker_asset,
-                taker_asset,
-                maker_amount,
-                taker_amount,
-                maker_chain,
-                taker_chain,
-                timeout_seconds,
-            } => {
-                self.initiate_settlement(
-                    runtime, state, trade_id, maker, taker,
-                    maker_asset, taker_asset, maker_amount, taker_amount,
-                    maker_chain, taker_chain, timeout_seconds,
-                ).await
-            }
-            
-            Operation::ConfirmEscrow { settlement_id } => {
-                self.confirm_escrow(runtime, state, settlement_id).await
-            }
+/// When a recurring settlement stops creating instances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecurringEnd {
+    /// After this many instances
+    Count(u32),
+    /// With the last instance due at or before this time
+    Until(Timestamp),
+}
+
+/// Lifecycle of a recurring settlement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecurringStatus {
+    Active,
+    /// Every scheduled instance was created
+    Ended,
+    Cancelled { by: Account },
+    /// Stopped after `max_consecutive_defaults` instances in a row were not paid
+    Defaulted,
+}
+
+/// Payments of `amount` of `asset` from `payer` to `payee` every `interval_seconds`. Each payment
+/// is a same-asset settlement of its own, escrowed and executed like any other; the payee's leg is
+/// zero, so only the payer escrows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringSettlement {
+    pub id: u64,
+    pub payer: Account,
+    pub payee: Account,
+    pub asset: String,
+    pub amount: Amount,
+    pub payer_chain: ChainId,
+    pub payee_chain: ChainId,
+    pub interval_seconds: u64,
+    /// Time each instance gives the payer to escrow; at most the interval
+    pub timeout_seconds: u64,
+    pub end: RecurringEnd,
+    /// Unpaid instances in a row that stop the schedule
+    pub max_consecutive_defaults: u32,
+    pub consecutive_defaults: u32,
+    /// Leading instances whose outcome is already counted in `consecutive_defaults`
+    pub counted_instances: u32,
+    /// When the next instance is due
+    pub next_due_at: Timestamp,
+    pub status: RecurringStatus,
+    pub created_at: Timestamp,
+}
+
+impl RecurringSettlement {
+    /// Checks the schedule the payer asked for
+    pub fn validate(&self) -> Result<(), SettlementError> {
+        let reason = if self.payer == self.payee {
+            "Payer and payee must differ"
+        } else if self.amount == Amount::ZERO {
+            "Amount must be positive"
+        } else if self.interval_seconds == 0 {
+            "Interval must be positive"
+        } else if self.timeout_seconds == 0 || self.timeout_seconds > self.interval_seconds {
+            "Timeout must be positive and at most the interval"
+        } else if self.max_consecutive_defaults == 0 {
+            "Must tolerate at least one default"
+        } else if !self.schedules(0, self.next_due_at) {
+            "Schedule ends before its first instance"
+        } else {
+            return Ok(());
+        };
+        Err(SettlementError::InvalidRecurringSettlement { reason: reason.to_string() })
+    }
+    
+    /// Whether an instance due at `due_at` still belongs to the schedule, after `created` instances
+    pub fn schedules(&self, created: u32, due_at: Timestamp) -> bool {
+        match self.end {
+            RecurringEnd::Count(count) => created < count,
+            RecurringEnd::Until(end) => due_at <= end,
+        }
+    }
+    
+    /// Due times of at most `count` instances still to create, after `created` instances
+    pub fn upcoming(&self, created: u32, count: usize) -> Vec<Timestamp> {
+        let mut upcoming = Vec::new();
+        if self.status != RecurringStatus::Active {
+            return upcoming;
+        }
+        let mut due_at = self.next_due_at;
+        while upcoming.len() < count && self.schedules(created + upcoming.len() as u32, due_at) {
+            upcoming.push(due_at);
+            due_at = due_at + std::time::Duration::from_secs(self.interval_seconds);
+        }
+        upcoming
+    }
+}
+
+/// One payment of a recurring settlement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringInstance {
+    /// 0 for the first payment
+    pub sequence: u32,
+    pub due_at: Timestamp,
+    /// None when the settlement could not be created
+    pub settlement_id: Option<u64>,
+    /// Why the settlement could not be created
+    pub failure_reason: Option<String>,
+}
+
+/// Recurring settlement and payment a settlement was created for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringLink {
+    pub recurring_id: u64,
+    pub sequence: u32,
+}
+
+/// How a recurring instance turned out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstanceOutcome {
+    /// Awaiting escrow or execution
+    InFlight,
+    Paid { completed_at: Option<Timestamp> },
+    /// Never created, or ended without paying out
+    Defaulted { reason: String },
+}
+
+impl InstanceOutcome {
+    /// Outcome at `now` of `instance`, whose settlement is `settlement`. A settlement past its
+    /// expiry counts as defaulted before `ProcessExpiredSettlements` gets to it.
+    pub fn of(instance: &RecurringInstance, settlement: Option<&Settlement>, now: Timestamp) -> Self {
+        let Some(settlement) = settlement else {
+            let reason = instance.failure_reason.clone().unwrap_or_else(|| "Settlement not found".to_string());
+            return InstanceOutcome::Defaulted { reason };
+        };
+        let reason = || settlement.failure_reason.clone().unwrap_or_else(|| "Not escrowed in time".to_string());
+        match settlement.status {
+            SettlementStatus::Completed => InstanceOutcome::Paid { completed_at: settlement.completed_at },
+            SettlementStatus::Refunded => InstanceOutcome::Defaulted { reason: reason() },
+            _ if settlement.is_refundable(now) => InstanceOutcome::Defaulted { reason: reason() },
+            _ => InstanceOutcome::InFlight,
+        }
+    }
+}
+
+/// Lifecycle of a settlement template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateStatus {
+    /// Waiting for the counterparty to accept its current terms
+    Proposed,
+    /// Accepted; settlements may be initiated from it
+    Active,
+    Revoked,
+}
+
+/// Settings of a settlement template beyond its pair and timeout
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateOptions {
+    /// Chain the counterparty escrows on; this chain when unset
+    #[serde(default)]
+    pub counterparty_chain: Option<ChainId>,
+    /// Settlements wait for the next settlement window boundary once fully escrowed
+    #[serde(default)]
+    pub windowed: bool,
+    /// Memo kept on every settlement, at most `MAX_MEMO_LENGTH` characters
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+/// Terms agreed once with a repeat counterparty. The creator is the maker of every settlement
+/// initiated from it and the counterparty the taker; only amounts and the trade id vary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementTemplate {
+    pub id: u64,
+    pub creator: Account,
+    pub counterparty: Account,
+    pub maker_asset: String,
+    pub taker_asset: String,
+    pub creator_chain: ChainId,
+    pub counterparty_chain: ChainId,
+    pub timeout_seconds: u64,
+    pub windowed: bool,
+    pub memo: Option<String>,
+    pub status: TemplateStatus,
+    pub created_at: Timestamp,
+    /// Last update of the terms; an update has to be accepted again
+    pub updated_at: Timestamp,
+    /// When the counterparty accepted the current terms
+    pub accepted_at: Option<Timestamp>,
+}
+
+impl SettlementTemplate {
+    /// Checks the static parts once, so settlements initiated from the template skip them
+    pub fn validate(&self) -> Result<(), SettlementError> {
+        if let Some(memo) = &self.memo {
+            validate_tag("memo", memo, MAX_MEMO_LENGTH)?;
+        }
+        let reason = if self.creator == self.counterparty {
+            "Creator and counterparty must differ"
+        } else if self.maker_asset.is_empty() || self.taker_asset.is_empty() {
+            "Assets must be named"
+        } else if self.timeout_seconds == 0 {
+            "Timeout must be positive"
+        } else {
+            return Ok(());
+        };
+        Err(SettlementError::InvalidSettlementTemplate { reason: reason.to_string() })
+    }
+}
+
+/// Notable contract events kept for monitoring
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementEvent {
+    /// A `SettlementRequest` message was dropped without creating a settlement
+    SettlementRequestRejected {
+        trade_id: u64,
+        caller: Option<ApplicationId>,
+        origin: Option<ChainId>,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    /// A redelivered message was acknowledged without being applied again
+    DuplicateMessageIgnored {
+        key: MessageDedupeKey,
+        origin: Option<ChainId>,
+        first_seen_at: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// A party let a custodian act on its escrows, or changed the custodian's assets
+    CustodianGranted {
+        party: Account,
+        custodian: Account,
+        grant: CustodianGrant,
+    },
+    /// A custodian lost its grant; it is refused from the next operation on
+    CustodianRevoked {
+        party: Account,
+        custodian: Account,
+        timestamp: Timestamp,
+    },
+    /// `owner` moved `counterparty` onto one of its counterparty lists, or off them with None
+    CounterpartyPolicyChanged {
+        owner: Account,
+        counterparty: Account,
+        listing: Option<CounterpartyListing>,
+        timestamp: Timestamp,
+    },
+    /// An admin finished a settlement left `Executing`
+    StuckExecutionResolved {
+        settlement_id: u64,
+        resolution: ExecutionResolution,
+        resolved_by: Account,
+        timestamp: Timestamp,
+    },
+    /// `AuditEscrow` returned an escrow record left on an inactive settlement to its owner
+    OrphanedEscrowReturned {
+        settlement_id: u64,
+        party: Account,
+        asset: String,
+        amount: Amount,
+        status: SettlementStatus,
+        timestamp: Timestamp,
+    },
+    /// `AuditEscrow` found an escrow record on a settlement that already paid out
+    OrphanedEscrowFlagged {
+        escrow: FlaggedEscrow,
+        timestamp: Timestamp,
+    },
+    /// A bridge transfer was linked to the settlement leg it carries
+    BridgeLegLinked {
+        settlement_id: u64,
+        payer: Account,
+        transfer_id: u64,
+        direction: BridgeDirection,
+        timestamp: Timestamp,
+    },
+    /// A linked bridge transfer finished and the leg's bridge status followed it
+    BridgeLegUpdated {
+        settlement_id: u64,
+        payer: Account,
+        transfer_id: u64,
+        status: BridgeTransferStatus,
+        timestamp: Timestamp,
+    },
+    /// The corridor source reported a change in a corridor's availability; pending withdrawals
+    /// through it show as delayed until it is open again
+    CorridorStatusChanged {
+        corridor: u64,
+        availability: CorridorAvailability,
+        timestamp: Timestamp,
+    },
+    /// A settlement without auto-execution is fully escrowed; it expires with refunds unless the
+    /// parties confirm by `confirm_by`
+    AwaitingConfirmation {
+        settlement_id: u64,
+        confirm_by: Timestamp,
+        timestamp: Timestamp,
+    },
+    /// A party confirmed execution of a settlement awaiting confirmation
+    ExecutionConfirmed {
+        settlement_id: u64,
+        party: Account,
+        timestamp: Timestamp,
+    },
+    /// A custodian confirmed an escrow or claimed a refund for a party
+    CustodialAction {
+        settlement_id: u64,
+        party: Account,
+        custodian: Account,
+        action: CustodialActionKind,
+        timestamp: Timestamp,
+    },
+    /// A settlement paid out both legs
+    SettlementCompleted {
+        settlement_id: u64,
+        trade_id: u64,
+        memo: Option<String>,
+        external_ref: Option<String>,
+        timestamp: Timestamp,
+    },
+    /// An expiry sweep expired and refunded settlements
+    ExpiredSettlementsProcessed {
+        expired: u32,
+        next_expires_at: Option<Timestamp>,
+        timestamp: Timestamp,
+    },
+    /// An origin went over its throttle and its requests started being deferred
+    OriginThrottled {
+        origin: SettlementOrigin,
+        trade_id: u64,
+        timestamp: Timestamp,
+    },
+    /// An admin initiated an origin's deferred requests at once, past the throttle
+    DeferredRequestsFlushed {
+        origin: SettlementOrigin,
+        count: u64,
+        flushed_by: Account,
+        timestamp: Timestamp,
+    },
+    /// An admin discarded an origin's deferred requests
+    DeferredRequestsDropped {
+        origin: SettlementOrigin,
+        count: u64,
+        dropped_by: Account,
+        timestamp: Timestamp,
+    },
+    /// A recurring settlement stopped creating instances
+    RecurringSettlementStopped {
+        recurring_id: u64,
+        status: RecurringStatus,
+        timestamp: Timestamp,
+    },
+    /// A message this build cannot read was parked instead of applied
+    MessageParked {
+        parked_id: u64,
+        version: u8,
+        origin: Option<ChainId>,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    /// An admin replayed or discarded a parked message
+    ParkedMessageResolved {
+        parked_id: u64,
+        replayed: bool,
+        resolved_by: Account,
+        timestamp: Timestamp,
+    },
+    /// A message failed to apply and was kept as a dead letter
+    MessageDeadLettered {
+        dead_letter_id: u64,
+        origin: Option<ChainId>,
+        trade_id: Option<u64>,
+        reason: String,
+        timestamp: Timestamp,
+    },
+    /// An admin retried or purged a dead letter
+    DeadLetterResolved {
+        dead_letter_id: u64,
+        retried: bool,
+        resolved_by: Account,
+        timestamp: Timestamp,
+    },
+}
+
+/// Operation a custodian signed for a party
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustodialActionKind {
+    ConfirmEscrow,
+    ClaimRefund,
+}
+
+/// Settlement operations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    /// Initialize a new settlement
+    InitiateSettlement {
+        trade_id: u64,
+        maker: Account,
+        taker: Account,
+        maker_asset: String,
+        taker_asset: String,
+        maker_amount: Amount,
+        taker_amount: Amount,
+        maker_chain: ChainId,
+        taker_chain: ChainId,
+        timeout_seconds: u64,
+        /// Trading fees to charge on execution
+        #[serde(default)]
+        fees: Option<SettlementFees>,
+        /// Wait for the next settlement window boundary once fully escrowed
+        #[serde(default)]
+        windowed: bool,
+        /// Caller-chosen id under which a receipt with the settlement id is stored
+        #[serde(default)]
+        client_request_id: Option<u64>,
+        /// Free text kept on the settlement, at most `MAX_MEMO_LENGTH` characters
+        #[serde(default)]
+        memo: Option<String>,
+        /// Signer's own deal id, at most `MAX_EXTERNAL_REF_LENGTH` characters; the settlement can be
+        /// looked up by it with `GetSettlementByExternalRef`
+        #[serde(default)]
+        external_ref: Option<String>,
+        /// Wait for the parties' `ExecuteSettlement` once fully escrowed; None executes on the
+        /// second escrow
+        #[serde(default)]
+        confirmation: Option<ConfirmationTerms>,
+        /// Exactly one leg is zero, as in a one-way payment; required for a zero leg so that one
+        /// is never settled by accident
+        #[serde(default)]
+        one_sided: bool,
+    },
+    
+    /// Settlement request from a registered market on this chain, called directly by its
+    /// application; the response carries the settlement id. A request naming another
+    /// `settlement_chain` is relayed there as a `Message::SettlementRequest` for the calling market.
+    RequestSettlement {
+        trade_id: u64,
+        maker: Account,
+        taker: Account,
+        maker_asset: String,
+        taker_asset: String,
+        maker_amount: Amount,
+        taker_amount: Amount,
+        timeout_seconds: u64,
+        #[serde(default)]
+        fees: Option<SettlementFees>,
+        /// Chains the parties escrow on; this chain when unset
+        #[serde(default)]
+        maker_chain: Option<ChainId>,
+        #[serde(default)]
+        taker_chain: Option<ChainId>,
+        /// Chain settling the trade; this chain when unset
+        #[serde(default)]
+        settlement_chain: Option<ChainId>,
+    },
+    
+    /// Take the rejection of a request the calling market had relayed to another chain; the
+    /// response carries its reason code, None while the request stands
+    TakeSettlementRejection {
+        trade_id: u64,
+    },
+    
+    /// Confirm escrow from a party (locks funds)
+    ConfirmEscrow {
+        settlement_id: u64,
+        /// Party to escrow for, when signing as its custodian; funds come from the party's balance
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+        /// Escrow this asset instead of the leg's, converted at the published rate; the
+        /// counterparty is paid in it
+        #[serde(default)]
+        substitute_asset: Option<String>,
+        /// Completed bridge deposit the escrowed funds came in with; it is linked to the leg
+        #[serde(default)]
+        bridge_transfer_id: Option<u64>,
+    },
+    
+    /// Execute settlement (after both parties escrow). On a settlement awaiting confirmation it
+    /// confirms for the signing party, executing once the confirmation rule is met.
+    ExecuteSettlement {
+        settlement_id: u64,
+    },
+    
+    /// Cancel settlement (before both parties escrow)
+    CancelSettlement {
+        settlement_id: u64,
+        reason: String,
+    },
+    
+    /// Claim refund for expired/failed settlement
+    ClaimRefund {
+        settlement_id: u64,
+        /// Party to refund, when signing as its custodian; funds return to the party's balance
+        #[serde(default)]
+        on_behalf_of: Option<Account>,
+    },
+    
+    /// Refund expired settlements, earliest `expires_at` first, ties by id (can be called by
+    /// anyone). Examines at most `limit` expirations, `DEFAULT_EXPIRY_BATCH` when unset, capped at
+    /// `MAX_EXPIRY_BATCH`.
+    ProcessExpiredSettlements {
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    
+    /// Execute fully escrowed windowed settlements whose window boundary has passed, oldest
+    /// window first (can be called by anyone)
+    ExecuteWindow,
+    
+    /// Refuse new settlements with counterparties whose default rate is above the maximum; `None`
+    /// removes the requirement
+    SetCounterpartyRequirement {
+        max_default_rate_bps: Option<u64>,
+    },
+    
+    /// Put a counterparty on the signer's allowlist or denylist, or take it off with `None`. While
+    /// the allowlist is non-empty new settlements need a listed counterparty; denylisted ones are
+    /// always refused. Settlements already initiated are not affected.
+    SetCounterpartyPolicy {
+        counterparty: Account,
+        listing: Option<CounterpartyListing>,
+    },
+    
+    /// Configure bridge settings (admin only)
+    ConfigureBridge {
+        chain_id: String,
+        config: BridgeConfig,
+    },
+    
+    /// Disable a bridge (admin only)
+    DisableBridge {
+        chain_id: String,
+    },
+    
+    /// Process bridge deposit (from relayer)
+    ProcessBridgeDeposit {
+        chain_id: String,
+        tx_hash: String,
+        user: Account,
+        asset: String,
+        amount: Amount,
+        confirmations: u64,
+    },
+    
+    /// Initiate bridge withdrawal
+    InitiateBridgeWithdrawal {
+        chain_id: String,
+        asset: String,
+        amount: Amount,
+        destination_address: String,
+        /// Caller-chosen id under which a receipt with the transfer id is stored
+        #[serde(default)]
+        client_request_id: Option<u64>,
+    },
+    
+    /// Withdraw through the bridge what the caller received from a completed settlement,
+    /// linking the transfer to the leg that paid it
+    WithdrawSettlementProceeds {
+        settlement_id: u64,
+        chain_id: String,
+        destination_address: String,
+        /// Caller-chosen id under which a receipt with the transfer id is stored
+        #[serde(default)]
+        client_request_id: Option<u64>,
+    },
+    
+    /// Complete bridge withdrawal (from relayer)
+    CompleteBridgeWithdrawal {
+        transfer_id: u64,
+        tx_hash: String,
+        success: bool,
+    },
+    
+    /// Deposit to settlement account
+    Deposit {
+        asset: String,
+        amount: Amount,
+    },
+    
+    /// Withdraw from settlement account
+    Withdraw {
+        asset: String,
+        amount: Amount,
+    },
+    
+    /// Recompute escrow totals from active settlements and record any drift, returning or flagging
+    /// escrow left on inactive settlements (can be called by anyone)
+    AuditEscrow,
+    
+    /// Finish a settlement left `Executing` with one leg paid: pay the rest, or reverse the paid
+    /// legs when the rest cannot be paid (admin only)
+    ResolveStuckExecution {
+        settlement_id: u64,
+    },
+    
+    /// Set the smallest amount of `asset` either settlement leg may carry; zero removes it (admin only)
+    SetMinSettlementAmount {
+        asset: String,
+        minimum: Amount,
+    },
+    
+    /// Set the largest amount of `asset` either settlement leg may carry; zero removes it (admin only)
+    SetMaxSettlementAmount {
+        asset: String,
+        maximum: Amount,
+    },
+    
+    /// Exempt settlements between two accounts, in either role, from the maximums (admin only)
+    SetUncappedPair {
+        first: Account,
+        second: Account,
+        uncapped: bool,
+    },
+    
+    /// Register the order book market allowed to send `SettlementRequest`s, or remove it with
+    /// `None` (admin only)
+    SetMarket {
+        application_id: ApplicationId,
+        market: Option<MarketRegistration>,
+    },
+    
+    /// Set the length of settlement windows; zero removes them (admin only)
+    SetSettlementWindow {
+        window_seconds: u64,
+    },
+    
+    /// Let `oracle` publish conversion rates, or leave it to the admin with `None` (admin only)
+    SetRateOracle {
+        oracle: Option<Account>,
+    },
+    
+    /// Bridge application on this chain asked for corridor availability before bridge
+    /// withdrawals; None lets withdrawals through unchecked (admin only)
+    SetCorridorSource {
+        application_id: Option<ApplicationId>,
+    },
+    
+    /// Record the corridor source's current availability of a corridor, so pending withdrawals
+    /// through it show as delayed; anyone may ask
+    RefreshCorridorStatus {
+        corridor: u64,
+    },
+    
+    /// Publish the rate at which `to_asset` may be escrowed for `from_asset` legs (admin or oracle)
+    SetConversionRate {
+        from_asset: String,
+        to_asset: String,
+        /// Units of `to_asset` per unit of `from_asset` (1e8 fixed point)
+        rate: u64,
+        max_staleness_seconds: u64,
+    },
+    
+    /// Stop accepting `to_asset` for `from_asset` legs (admin or oracle)
+    RemoveConversionRate {
+        from_asset: String,
+        to_asset: String,
+    },
+    
+    /// Let `custodian` confirm escrows and claim refunds for the caller on legs in `assets`, or
+    /// any asset when empty. Granting again replaces the assets.
+    GrantCustodian {
+        custodian: Account,
+        assets: Vec<String>,
+    },
+    
+    /// Stop `custodian` acting for the caller
+    RevokeCustodian {
+        custodian: Account,
+    },
+    
+    /// Limit settlement requests per origin, deferring the excess; `None` removes the limit, and
+    /// what is already deferred is still drained (admin only)
+    SetSettlementThrottle {
+        throttle: Option<SettlementThrottle>,
+    },
+    
+    /// Initiate deferred settlement requests whose origin has room again, one origin at a time,
+    /// up to the per-block bound (can be called by anyone)
+    ProcessDeferredRequests,
+    
+    /// Initiate all of an origin's deferred requests now, past the throttle (admin only)
+    FlushDeferredRequests {
+        origin: SettlementOrigin,
+    },
+    
+    /// Discard all of an origin's deferred requests (admin only)
+    DropDeferredRequests {
+        origin: SettlementOrigin,
+    },
+    
+    /// Schedule payments of `amount` of `asset` from the signer to `payee`, one settlement every
+    /// `interval_seconds` from `first_due_at` (now when unset) until `end`. Each instance gives the
+    /// payer `timeout_seconds` to escrow; `max_consecutive_defaults` unpaid instances in a row stop
+    /// the schedule.
+    CreateRecurringSettlement {
+        payee: Account,
+        asset: String,
+        amount: Amount,
+        /// Chain the payee is paid on; this chain when unset
+        #[serde(default)]
+        payee_chain: Option<ChainId>,
+        interval_seconds: u64,
+        timeout_seconds: u64,
+        end: RecurringEnd,
+        max_consecutive_defaults: u32,
+        #[serde(default)]
+        first_due_at: Option<Timestamp>,
+    },
+    
+    /// Stop a recurring settlement (payer or payee); instances already created are left to run
+    CancelRecurringSettlement {
+        recurring_id: u64,
+    },
+    
+    /// Create the next instance of recurring settlements that are due, one per schedule, for at
+    /// most `limit` schedules, `DEFAULT_RECURRING_BATCH` when unset, capped at
+    /// `MAX_RECURRING_BATCH` (can be called by anyone)
+    ProcessRecurringSettlements {
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    
+    /// Propose terms for settlements with `counterparty`, who has to accept them before the
+    /// template can be used. The signer is the maker of every settlement initiated from it.
+    CreateSettlementTemplate {
+        counterparty: Account,
+        maker_asset: String,
+        taker_asset: String,
+        timeout_seconds: u64,
+        #[serde(default)]
+        options: TemplateOptions,
+    },
+    
+    /// Replace a template's terms (creator only); the counterparty has to accept them again
+    UpdateSettlementTemplate {
+        template_id: u64,
+        maker_asset: String,
+        taker_asset: String,
+        timeout_seconds: u64,
+        #[serde(default)]
+        options: TemplateOptions,
+    },
+    
+    /// Accept a template's current terms (counterparty only)
+    AcceptSettlementTemplate {
+        template_id: u64,
+    },
+    
+    /// Retire a template for good (creator only); settlements already initiated are left to run
+    RevokeSettlementTemplate {
+        template_id: u64,
+    },
+    
+    /// Initiate a settlement on an accepted template's terms (creator only)
+    InitiateFromTemplate {
+        template_id: u64,
+        maker_amount: Amount,
+        taker_amount: Amount,
+        trade_id: u64,
+    },
+    
+    /// Apply a parked message as it arrived, once an upgrade can read it (admin only)
+    ReplayParkedMessage {
+        parked_id: u64,
+    },
+    
+    /// Drop a parked message unapplied (admin only)
+    DiscardParkedMessage {
+        parked_id: u64,
+    },
+    
+    /// Apply a dead letter again as it arrived; a repeated failure makes a new one. A settlement
+    /// request its market was told failed can only be purged (admin only)
+    RetryDeadLetter {
+        dead_letter_id: u64,
+    },
+    
+    /// Drop a dead letter unapplied (admin only)
+    PurgeDeadLetter {
+        dead_letter_id: u64,
+    },
+    
+    /// Hand the admin role to another account (admin only)
+    TransferAdmin {
+        new_admin: Account,
+    },
+}
+
+/// Cross-chain messages, sent sealed in a `MessageEnvelope`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Message {
+    /// Settlement request from a registered order book market
+    SettlementRequest {
+        trade_id: u64,
+        maker: Account,
+        taker: Account,
+        maker_asset: String,
+        taker_asset: String,
+        maker_amount: Amount,
+        taker_amount: Amount,
+        timeout_seconds: u64,
+        /// Maker and taker fees computed by the order book
+        #[serde(default)]
+        fees: Option<SettlementFees>,
+        /// Home chains of the parties, where they escrow; this chain when unset
+        #[serde(default)]
+        maker_chain: Option<ChainId>,
+        #[serde(default)]
+        taker_chain: Option<ChainId>,
+        /// Market application that requested the settlement through this application on the
+        /// sending chain; it must be registered for that chain
+        #[serde(default)]
+        market: Option<ApplicationId>,
+    },
+    
+    /// Escrow confirmation from another chain
+    EscrowConfirmation {
+        settlement_id: u64,
+        party: Account,
+        confirmed: bool,
+        amount: Amount,
+    },
+    
+    /// Settlement completion notification
+    SettlementComplete {
+        settlement_id: u64,
+        success: bool,
+        failure_reason: Option<String>,
+        #[serde(default)]
+        memo: Option<String>,
+        #[serde(default)]
+        external_ref: Option<String>,
+    },
+    
+    /// Bridge event notification
+    BridgeEvent {
+        chain_id: String,
+        event_type: BridgeEventType,
+        transfer_id: u64,
+        data: Vec<u8>,
+    },
+    
+    /// Refund notification
+    RefundProcessed {
+        settlement_id: u64,
+        party: Account,
+        amount: Amount,
+        asset: String,
+    },
+    
+    /// The settlement chain failed `market`'s relayed request for `trade_id`, so the trade will
+    /// not settle; kept on the relaying chain for `TakeSettlementRejection`
+    SettlementRejected {
+        market: Option<ApplicationId>,
+        trade_id: u64,
+        reason_code: SettlementRejectionCode,
+    },
+    
+}
+
+impl Message {
+    /// Key under which a redelivery of the message is recognized; None for notifications that
+    /// change no state, or only copy it from this chain's records
+    pub fn dedupe_key(&self) -> Option<MessageDedupeKey> {
+        match self {
+            Message::SettlementRequest { trade_id, market, .. } => {
+                Some(MessageDedupeKey::SettlementRequest { market: *market, trade_id: *trade_id })
+            }
+            Message::EscrowConfirmation { settlement_id, party, amount, .. } => {
+                Some(MessageDedupeKey::EscrowConfirmation {
+                    settlement_id: *settlement_id,
+                    party: *party,
+                    amount: *amount,
+                })
+            }
+            Message::SettlementComplete { .. }
+            | Message::SettlementRejected { .. }
+            | Message::BridgeEvent { .. }
+            | Message::RefundProcessed { .. } => None,
+        }
+    }
+}
+
+/// Identity of an inbound message for redelivery detection
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MessageDedupeKey {
+    /// Trade ids are unique per market application
+    SettlementRequest { market: Option<ApplicationId>, trade_id: u64 },
+    EscrowConfirmation { settlement_id: u64, party: Account, amount: Amount },
+}
+
+/// Why a `SettlementRequest` was rejected, as reported to the market that sent it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementRejectionCode {
+    /// The sender is not a registered market, or the assets are not its pair
+    UnknownMarket,
+    /// An asset has no enabled bridge
+    AssetNotSupported,
+    /// An amount is outside the bridge's limits
+    AmountOutOfLimits,
+    /// A party's counterparty policy or default-rate requirement excludes the other
+    CounterpartyRestricted,
+    /// The terms cannot settle, such as a fee above its amount or a missing conversion rate
+    InvalidTerms,
+    /// Anything else, such as a storage failure
+    Internal,
+}
+
+impl SettlementError {
+    /// Code a market is told when a settlement request fails with this error
+    pub fn rejection_code(&self) -> SettlementRejectionCode {
+        match self {
+            SettlementError::UnknownMarket { .. }
+            | SettlementError::Unauthorized { .. }
+            | SettlementError::MarketAssetMismatch { .. } => SettlementRejectionCode::UnknownMarket,
+            SettlementError::BridgeNotConfigured { .. }
+            | SettlementError::BridgeDisabled { .. }
+            | SettlementError::AssetNotSupported { .. } => SettlementRejectionCode::AssetNotSupported,
+            SettlementError::BelowMinimum { .. } | SettlementError::AboveMaximum { .. } => {
+                SettlementRejectionCode::AmountOutOfLimits
+            }
+            SettlementError::CounterpartyDefaultRate { .. }
+            | SettlementError::CounterpartyNotAllowlisted { .. }
+            | SettlementError::CounterpartyDenylisted { .. } => SettlementRejectionCode::CounterpartyRestricted,
+            SettlementError::FeeExceedsAmount { .. }
+            | SettlementError::InvalidSameAssetSettlement { .. }
+            | SettlementError::InvalidOneSidedSettlement { .. }
+            | SettlementError::InvalidSettlementTag { .. }
+            | SettlementError::InvalidConfirmationTerms { .. }
+            | SettlementError::NoConversionRate { .. }
+            | SettlementError::StaleConversionRate { .. }
+            | SettlementError::NoSettlementWindow => SettlementRejectionCode::InvalidTerms,
+            _ => SettlementRejectionCode::Internal,
+        }
+    }
+}
+
+/// Layout version of the messages this build sends. Envelopes of a higher version, and those
+/// this build cannot decode, are parked until an upgrade can read them.
+pub const MESSAGE_VERSION: u8 = 1;
+
+/// A cross-chain message as sent: its layout version and the message as JSON, which skips
+/// fields the receiver does not know and, through serde defaults, fills in those it lacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageEnvelope {
+    pub version: u8,
+    pub payload: Vec<u8>,
+}
+
+impl MessageEnvelope {
+    /// `message` in this build's layout
+    pub fn seal<M: Serialize>(message: &M) -> Self {
+        Self::with_version(MESSAGE_VERSION, message)
+    }
+    
+    pub fn with_version<M: Serialize>(version: u8, message: &M) -> Self {
+        let payload = serde_json::to_vec(message).expect("messages serialize to JSON");
+        MessageEnvelope { version, payload }
+    }
+    
+    /// The message, or why this build has to park it. Older versions are read with the current
+    /// layout.
+    pub fn open<M: DeserializeOwned>(&self) -> Result<M, String> {
+        if self.version > MESSAGE_VERSION {
+            return Err(format!("Message version {} is newer than {}", self.version, MESSAGE_VERSION));
+        }
+        serde_json::from_slice(&self.payload).map_err(|error| format!("Undecodable message: {error}"))
+    }
+}
+
+/// Delivery details of a message, captured on arrival so a parked message replays as it arrived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageContext {
+    pub message_id: Option<MessageId>,
+    /// Application that sent the message
+    pub caller: Option<ApplicationId>,
+    pub signer: Option<Account>,
+}
+
+impl MessageContext {
+    /// Chain the message came from
+    pub fn origin(&self) -> Option<ChainId> {
+        self.message_id.map(|message_id| message_id.chain_id)
+    }
+}
+
+/// A message this build could not read when it arrived, kept for `ReplayParkedMessage` after an
+/// upgrade or `DiscardParkedMessage`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParkedMessage {
+    pub id: u64,
+    pub envelope: MessageEnvelope,
+    pub context: MessageContext,
+    pub reason: String,
+    pub parked_at: Timestamp,
+}
+
+/// An inbound message that failed to apply, kept for `RetryDeadLetter` or `PurgeDeadLetter`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: u64,
+    pub envelope: MessageEnvelope,
+    /// Keccak-256 of the envelope's payload, to match it against what the sender sent
+    pub payload_hash: [u8; 32],
+    pub context: MessageContext,
+    /// Trade of a failed `SettlementRequest`, whose relaying chain was sent a `SettlementRejected`
+    pub trade_id: Option<u64>,
+    pub reason: String,
+    pub failed_at: Timestamp,
+}
+
+impl DeadLetter {
+    /// Whether its market was sent a `SettlementRejected`, which a retry would contradict
+    pub fn rejection_sent(&self) -> bool {
+        self.trade_id.is_some() && self.context.origin().is_some()
+    }
+}
+
+/// Checks a memo or external reference: not empty, at most `max_length` characters, no control
+/// characters
+pub fn validate_tag(field: &str, value: &str, max_length: usize) -> Result<(), SettlementError> {
+    let reason = if value.is_empty() {
+        "must not be empty".to_string()
+    } else if value.chars().count() > max_length {
+        format!("longer than {max_length} characters")
+    } else if value.chars().any(char::is_control) {
+        "contains control characters".to_string()
+    } else {
+        return Ok(());
+    };
+    Err(SettlementError::InvalidSettlementTag { field: field.to_string(), reason })
+}
+
+/// Dead letter to drop once `newest` joins those kept since `first`: the oldest, past
+/// `MAX_DEAD_LETTERS`
+pub fn dead_letter_to_evict(first: u64, newest: u64) -> Option<u64> {
+    (newest - first >= MAX_DEAD_LETTERS).then_some(first)
+}
+
+/// Key of `expires_at` in `expirations`: its micros big-endian, so byte order is time order
+pub fn expiration_key(expires_at: Timestamp) -> [u8; 8] {
+    expires_at.micros().to_be_bytes()
+}
+
+/// Whether a key seen at `seen_at` has outlived `MESSAGE_DEDUPE_TTL_SECONDS`
+pub fn dedupe_expired(seen_at: Timestamp, now: Timestamp) -> bool {
+    now.micros().saturating_sub(seen_at.micros()) / 1_000_000 >= MESSAGE_DEDUPE_TTL_SECONDS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeEventType {
+    DepositDetected,
+    DepositConfirmed,
+    WithdrawalInitiated,
+    WithdrawalCompleted,
+    WithdrawalFailed,
+}
+
+/// Settlement errors
+#[derive(Error, Debug)]
+pub enum SettlementError {
+    #[error("Settlement not found: {settlement_id}")]
+    SettlementNotFound { settlement_id: u64 },
+    
+    #[error("Invalid settlement status: expected {expected:?}, got {actual:?}")]
+    InvalidStatus {
+        expected: SettlementStatus,
+        actual: SettlementStatus,
+    },
+    
+    #[error("Settlement expired at {expired_at:?}")]
+    SettlementExpired { expired_at: Timestamp },
+    
+    #[error("Unauthorized access: {reason}")]
+    Unauthorized { reason: String },
+    
+    #[error("Insufficient balance: required {required}, available {available}")]
+    InsufficientBalance { required: Amount, available: Amount },
+    
+    #[error("Bridge not configured for chain: {chain_id}")]
+    BridgeNotConfigured { chain_id: String },
+    
+    #[error("Bridge is disabled: {chain_id}")]
+    BridgeDisabled { chain_id: String },
+    
+    #[error("Bridge corridor {corridor} is unavailable: {availability:?}")]
+    CorridorUnavailable { corridor: u64, availability: CorridorAvailability },
+    
+    #[error("Bridge operation failed: {reason}")]
+    BridgeError { reason: String },
+    
+    #[error("Asset not supported by bridge: {asset}")]
+    AssetNotSupported { asset: String },
+    
+    #[error("Amount below minimum: {amount}, minimum: {minimum}")]
+    BelowMinimum { amount: Amount, minimum: Amount },
+    
+    #[error("Amount above maximum: {amount}, maximum: {maximum}")]
+    AboveMaximum { amount: Amount, maximum: Amount },
+    
+    #[error("Transfer not found: {transfer_id}")]
+    TransferNotFound { transfer_id: u64 },
+    
+    #[error("Bridge transfer {transfer_id} cannot be linked: {reason}")]
+    InvalidBridgeLink { transfer_id: u64, reason: String },
+    
+    #[error("Already escrowed")]
+    AlreadyEscrowed,
+    
+    #[error("Execution already confirmed by this party")]
+    AlreadyConfirmed,
+    
+    #[error("Settlement awaits the parties' confirmation until {confirm_by:?}")]
+    AwaitingConfirmation { confirm_by: Timestamp },
+    
+    #[error("Invalid confirmation terms: {reason}")]
+    InvalidConfirmationTerms { reason: String },
+    
+    #[error("Cannot cancel: {reason}")]
+    CannotCancel { reason: String },
+    
+    #[error("Client request id already used: {client_request_id}")]
+    DuplicateClientRequest { client_request_id: u64 },
+    
+    #[error("Unknown market: {application_id:?}")]
+    UnknownMarket { application_id: Option<ApplicationId> },
+    
+    #[error("Assets {maker_asset}/{taker_asset} do not match the market's pair")]
+    MarketAssetMismatch { maker_asset: String, taker_asset: String },
+    
+    #[error("Counterparty {counterparty:?} defaulted on {default_rate_bps} bps of settlements, above the required {maximum_bps}")]
+    CounterpartyDefaultRate { counterparty: Account, default_rate_bps: u64, maximum_bps: u64 },
+    
+    #[error("{party:?} only settles with its allowlisted counterparties, which exclude {counterparty:?}")]
+    CounterpartyNotAllowlisted { party: Account, counterparty: Account },
+    
+    #[error("{party:?} denylisted counterparty {counterparty:?}")]
+    CounterpartyDenylisted { party: Account, counterparty: Account },
+    
+    #[error("Counterparty list full: at most {maximum} accounts")]
+    CounterpartyListFull { maximum: usize },
+    
+    #[error("No settlement window configured")]
+    NoSettlementWindow,
+    
+    #[error("Settlement waits for the window boundary at {execute_at:?}")]
+    WindowNotReached { execute_at: Timestamp },
+    
+    #[error("No conversion rate from {from_asset} to {to_asset}")]
+    NoConversionRate { from_asset: String, to_asset: String },
+    
+    #[error("Conversion rate from {from_asset} to {to_asset} is stale since {updated_at:?}")]
+    StaleConversionRate { from_asset: String, to_asset: String, updated_at: Timestamp },
+    
+    #[error("Invalid conversion rate: {reason}")]
+    InvalidConversionRate { reason: String },
+    
+    #[error("Unauthorized: not a custodian of {party:?}")]
+    NotCustodian { party: Account },
+    
+    #[error("Custodian not permitted to act on {asset}")]
+    AssetNotInCustody { asset: String },
+    
+    #[error("Fee {fee} exceeds the amount it is taken from: {amount}")]
+    FeeExceedsAmount { fee: Amount, amount: Amount },
+    
+    #[error("Invalid {field}: {reason}")]
+    InvalidSettlementTag { field: String, reason: String },
+    
+    #[error("External reference {external_ref} already names settlement {settlement_id}")]
+    DuplicateExternalRef { external_ref: String, settlement_id: u64 },
+    
+    #[error("Invalid same-asset settlement: {reason}")]
+    InvalidSameAssetSettlement { reason: String },
+    
+    #[error("Invalid one-sided settlement: {reason}")]
+    InvalidOneSidedSettlement { reason: String },
+    
+    #[error("Invalid settlement throttle: {reason}")]
+    InvalidThrottle { reason: String },
+    
+    #[error("No deferred settlement requests from {origin:?}")]
+    NoDeferredRequests { origin: SettlementOrigin },
+    
+    #[error("Recurring settlement not found: {recurring_id}")]
+    RecurringSettlementNotFound { recurring_id: u64 },
+    
+    #[error("Invalid recurring settlement: {reason}")]
+    InvalidRecurringSettlement { reason: String },
+    
+    #[error("Settlement template not found: {template_id}")]
+    SettlementTemplateNotFound { template_id: u64 },
+    
+    #[error("Invalid settlement template: {reason}")]
+    InvalidSettlementTemplate { reason: String },
+    
+    #[error("Settlement template {template_id} is {status:?}")]
+    TemplateNotActive { template_id: u64, status: TemplateStatus },
+    
+    #[error("Parked message not found: {parked_id}")]
+    ParkedMessageNotFound { parked_id: u64 },
+    
+    #[error("Parked message {parked_id} is still unreadable: {reason}")]
+    ParkedMessageUnreadable { parked_id: u64, reason: String },
+    
+    #[error("Dead letter not found: {dead_letter_id}")]
+    DeadLetterNotFound { dead_letter_id: u64 },
+    
+    #[error("Dead letter {dead_letter_id} was rejected to its market and can only be purged")]
+    DeadLetterRejected { dead_letter_id: u64 },
+    
+    #[error("Math error: {0}")]
+    Math(#[from] MathError),
+    
+    #[error("View error: {0}")]
+    ViewError(#[from] ViewError),
+}
+
+/// Settlement engine state
+#[derive(RootView)]
+pub struct SettlementState<C> {
+    /// Next settlement ID
+    pub next_settlement_id: RegisterView<C, u64>,
+    
+    /// All settlements
+    pub settlements: MapView<C, u64, Settlement>,
+    
+    /// User settlements mapping
+    pub user_settlements: MapView<C, Account, Vec<u64>>,
+    
+    /// Active settlements (not yet completed/failed)
+    pub active_settlements: MapView<C, u64, ()>,
+    
+    /// Expirations queued before `expirations` existed; each sweep moves `MAX_EXPIRY_MIGRATION` over
+    pub expiration_queue: QueueView<C, (Timestamp, u64)>,
+    
+    /// Settlements by the `expires_at` they were queued with, keyed by `expiration_key` so the
+    /// map iterates in deadline order, in id order. An entry whose settlement now expires at
+    /// another time is stale and skipped.
+    pub expirations: MapView<C, [u8; 8], Vec<u64>>,
+    
+    /// Bridge configurations
+    pub bridge_configs: MapView<C, String, BridgeConfig>,
+    
+    /// User balances per asset
+    pub balances: MapView<C, (Account, String), Amount>,
+    
+    /// Escrowed balances (locked in settlements)
+    pub escrowed_balances: MapView<C, (u64, Account, String), Amount>,
+    
+    /// Next bridge transfer ID
+    pub next_transfer_id: RegisterView<C, u64>,
+    
+    /// Bridge transfers
+    pub bridge_transfers: MapView<C, u64, BridgeTransfer>,
+    
+    /// User bridge transfers
+    pub user_transfers: MapView<C, Account, Vec<u64>>,
+    
+    /// Pending bridge deposits (tx_hash -> transfer_id)
+    pub pending_deposits: MapView<C, String, u64>,
+    
+    /// Pending bridge withdrawals
+    pub pending_withdrawals: QueueView<C, u64>,
+    
+    /// Settlement statistics
+    pub stats: RegisterView<C, SettlementStats>,
+    
+    /// Sum of free balances per asset
+    pub total_balances: MapView<C, String, Amount>,
+    
+    /// Sum of escrowed balances per asset
+    pub total_escrowed: MapView<C, String, Amount>,
+    
+    /// Last escrow audit
+    pub last_escrow_audit: RegisterView<C, Option<EscrowAuditReport>>,
+    
+    /// Receipts of operations submitted with a client request id: (caller, client id) -> receipt
+    pub receipts: MapView<C, (Account, u64), OperationReceipt>,
+    
+    /// Settlement admin, set at instantiation; privileged operations are refused while unset
+    pub admin: RegisterView<C, Option<Account>>,
+    
+    /// Smallest amount per asset a settlement leg may carry
+    pub min_settlement_amounts: MapView<C, String, Amount>,
+    
+    /// Largest amount per asset a settlement leg may carry
+    pub max_settlement_amounts: MapView<C, String, Amount>,
+    
+    /// Counterparty pairs exempt from the maximums, stored in the order they were allowed
+    pub uncapped_pairs: MapView<C, (Account, Account), ()>,
+    
+    /// Order book markets allowed to request settlements
+    pub markets: MapView<C, ApplicationId, MarketRegistration>,
+    
+    /// Requests of markets on this chain relayed to other chains, by market and trade
+    pub relayed_requests: MapView<C, (ApplicationId, u64), RelayedSettlementRequest>,
+    
+    /// Monitoring events
+    pub events: QueueView<C, SettlementEvent>,
+    
+    /// Settlement track record per account
+    pub reputations: MapView<C, Account, Reputation>,
+    
+    /// Highest counterparty default rate each account accepts, in basis points
+    pub max_counterparty_default_bps: MapView<C, Account, u64>,
+    
+    /// Counterparty allowlist and denylist of each account that set any
+    pub counterparty_policies: MapView<C, Account, CounterpartyPolicy>,
+    
+    /// The same lists by (owner, counterparty), checked at initiation without loading them
+    pub counterparty_listings: MapView<C, (Account, Account), CounterpartyListing>,
+    
+    /// Length of each non-empty allowlist
+    pub allowlist_sizes: MapView<C, Account, u32>,
+    
+    /// Length of settlement windows; zero when windowed settlements are not offered
+    pub settlement_window_seconds: RegisterView<C, u64>,
+    
+    /// Fully escrowed windowed settlements by the boundary they wait for (micros), in id order
+    pub window_queue: MapView<C, u64, Vec<u64>>,
+    
+    /// Custodians acting for settlement parties: (party, custodian) -> grant
+    pub custodians: MapView<C, (Account, Account), CustodianGrant>,
+    
+    /// Account allowed to publish conversion rates besides the admin
+    pub rate_oracle: RegisterView<C, Option<Account>>,
+    
+    /// Bridge application on this chain asked for corridor availability
+    pub corridor_source: RegisterView<C, Option<ApplicationId>>,
+    
+    /// Last availability reported per corridor; open corridors have no entry
+    pub corridor_statuses: MapView<C, u64, CorridorStatus>,
+    
+    /// Substitute assets accepted in escrow: (leg asset, substitute) -> rate
+    pub conversion_rates: MapView<C, (String, String), ConversionRate>,
+    
+    /// Dedupe keys of recently delivered messages -> first delivery
+    pub seen_messages: MapView<C, MessageDedupeKey, Timestamp>,
+    
+    /// The same keys in delivery order, for pruning
+    pub seen_message_order: QueueView<C, (Timestamp, MessageDedupeKey)>,
+    
+    /// Settlement ids by the chain and application that requested them, oldest first
+    pub settlements_by_origin: MapView<C, SettlementOrigin, Vec<u64>>,
+    
+    /// Settlement ids by (creator, external reference)
+    pub settlements_by_external_ref: MapView<C, (Account, String), u64>,
+    
+    /// Per-origin limit on initiated settlement requests; None when unthrottled
+    pub settlement_throttle: RegisterView<C, Option<SettlementThrottle>>,
+    
+    /// Settlements each origin initiated in its current throttle window
+    pub throttle_buckets: MapView<C, SettlementOrigin, ThrottleBucket>,
+    
+    /// Bounds of each origin's deferred requests; origins with none have no entry
+    pub deferred_queues: MapView<C, SettlementOrigin, DeferredQueue>,
+    
+    /// Deferred requests: (origin, position) -> request
+    pub deferred_requests: MapView<C, (SettlementOrigin, u64), DeferredSettlementRequest>,
+    
+    /// Block and the deferred requests initiated in it
+    pub deferred_drained: RegisterView<C, (BlockHeight, u32)>,
+    
+    /// Next recurring settlement ID
+    pub next_recurring_id: RegisterView<C, u64>,
+    
+    /// All recurring settlements
+    pub recurring_settlements: MapView<C, u64, RecurringSettlement>,
+    
+    /// Instances created so far per recurring settlement, in order
+    pub recurring_instances: MapView<C, u64, Vec<RecurringInstance>>,
+    
+    /// Recurring settlements still creating instances
+    pub active_recurring: MapView<C, u64, ()>,
+    
+    /// Recurring settlements per payer and payee
+    pub user_recurring: MapView<C, Account, Vec<u64>>,
+    
+    /// Next settlement template ID
+    pub next_template_id: RegisterView<C, u64>,
+    
+    /// All settlement templates
+    pub settlement_templates: MapView<C, u64, SettlementTemplate>,
+    
+    /// Settlement templates per creator and counterparty
+    pub account_templates: MapView<C, Account, Vec<u64>>,
+    
+    /// Settlements initiated from each template, in order
+    pub template_settlements: MapView<C, u64, Vec<u64>>,
+    
+    /// Messages this build could not read, until replayed or discarded
+    pub parked_messages: MapView<C, u64, ParkedMessage>,
+    
+    /// Next parked message ID
+    pub next_parked_message_id: RegisterView<C, u64>,
+    
+    /// Inbound messages that failed to apply, until retried, purged or dropped past
+    /// `MAX_DEAD_LETTERS`
+    pub dead_letters: MapView<C, u64, DeadLetter>,
+    
+    /// Oldest dead letter ID still kept, if not retried or purged
+    pub first_dead_letter_id: RegisterView<C, u64>,
+    
+    /// Next dead letter ID
+    pub next_dead_letter_id: RegisterView<C, u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementStats {
+    pub total_settlements: u64,
+    pub completed_settlements: u64,
+    pub failed_settlements: u64,
+    pub total_volume: Amount,
+    pub total_bridge_deposits: Amount,
+    pub total_bridge_withdrawals: Amount,
+}
+
+/// Settlement engine contract
+pub struct SettlementContract;
+
+#[async_trait]
+impl Contract for SettlementContract {
+    type Message = MessageEnvelope;
+    type Parameters = ();
+    type InstantiationArgument = Account;
+    type State = SettlementState<ContractRuntime<Self>>;
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        SettlementContract
+    }
+
+    async fn instantiate(&mut self, state: &mut Self::State, admin: Account) {
+        state.next_settlement_id.set(1);
+        state.next_transfer_id.set(1);
+        state.stats.set(SettlementStats::default());
+        state.admin.set(Some(admin));
+    }
+
+    async fn execute_operation(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut Self::State,
+        operation: Operation,
+    ) -> Result<SettlementResponse, Self::Error> {
+        let result = match operation {
+            Operation::InitiateSettlement {
+                trade_id,
+                maker,
+                taker,
+                maker_asset,
+                taker_asset,
+                maker_amount,
+                taker_amount,
+                maker_chain,
+                taker_chain,
+                timeout_seconds,
+                fees,
+                windowed,
+                client_request_id,
+                memo,
+                external_ref,
+                confirmation,
+                one_sided,
+            } => {
+                if let Some(terms) = &confirmation {
+                    terms.validate(windowed)?;
+                }
+                let provenance = self.provenance(runtime, SettlementOriginKind::Operation, client_request_id);
+                let settlement_id = self.initiate_settlement(
+                    runtime, state, trade_id, maker, taker,
+                    maker_asset, taker_asset, maker_amount, taker_amount,
+                    maker_chain, taker_chain, timeout_seconds, fees, windowed, one_sided, provenance, memo,
+                    external_ref,
+                ).await?;
+                // Netting may waive one escrow, but full escrow is only reached by a confirmation
+                if confirmation.is_some() {
+                    if let Some(mut settlement) = state.settlements.get(&settlement_id).await? {
+                        settlement.confirmation = confirmation;
+                        state.settlements.insert(&settlement_id, settlement)?;
+                    }
+                }
+                self.record_receipt(runtime, state, client_request_id, settlement_id).await
+            }
+            
+            Operation::RequestSettlement {
+                trade_id, maker, taker, maker_asset, taker_asset,
+                maker_amount, taker_amount, timeout_seconds, fees, maker_chain, taker_chain, settlement_chain,
+            } => {
+                let origin = Some(runtime.chain_id());
+                let caller = runtime.authenticated_caller_id();
+                if let Some(settlement_chain) = settlement_chain.filter(|chain_id| Some(*chain_id) != origin) {
+                    // The settlement chain checks the market against its registration for this chain
+                    let market = caller.ok_or(SettlementError::UnknownMarket { application_id: None })?;
+                    state.relayed_requests.insert(&(market, trade_id), RelayedSettlementRequest {
+                        settlement_chain,
+                        rejection: None,
+                    })?;
+                    runtime
+                        .prepare_message(MessageEnvelope::seal(&Message::SettlementRequest {
+                            trade_id, maker, taker, maker_asset, taker_asset,
+                            maker_amount, taker_amount, timeout_seconds, fees, maker_chain, taker_chain,
+                            market: caller,
+                        }))
+                        .with_authentication()
+                        .send_to(settlement_chain);
+                    return Ok(SettlementResponse::SettlementRequestForwarded);
+                }
+                self.verify_settlement_request(state, caller, origin, &maker_asset, &taker_asset).await?;
+                let chain_id = runtime.chain_id();
+                let provenance = self.provenance(runtime, SettlementOriginKind::ApplicationCall, None);
+                let settlement_id = self.initiate_settlement(
+                    runtime, state, trade_id, maker, taker,
+                    maker_asset, taker_asset, maker_amount, taker_amount,
+                    maker_chain.unwrap_or(chain_id), taker_chain.unwrap_or(chain_id),
+                    timeout_seconds, fees, false, false, provenance, None, None,
+                ).await?;
+                return Ok(SettlementResponse::SettlementInitiated { settlement_id });
+            }
+            
+            Operation::TakeSettlementRejection { trade_id } => {
+                let market = runtime.authenticated_caller_id()
+                    .ok_or(SettlementError::UnknownMarket { application_id: None })?;
+                let key = (market, trade_id);
+                let reason_code = match state.relayed_requests.get(&key).await? {
+                    Some(RelayedSettlementRequest { rejection: Some(reason_code), .. }) => {
+                        state.relayed_requests.remove(&key)?;
+                        Some(reason_code)
+                    }
+                    _ => None,
+                };
+                return Ok(SettlementResponse::SettlementRejection { reason_code });
+            }
+            
+            Operation::ConfirmEscrow { settlement_id, on_behalf_of, substitute_asset, bridge_transfer_id } => {
+                self.confirm_escrow(
+                    runtime, state, settlement_id, on_behalf_of, substitute_asset, bridge_transfer_id,
+                ).await
+            }
+            
+            Operation::ExecuteSettlement { settlement_id } => {
+                self.confirm_execution(runtime, state, settlement_id).await
+            }
+            
+            Operation::ExecuteWindow => {
+                self.execute_window(runtime, state).await
+            }
+            
+            Operation::CancelSettlement { settlement_id, reason } => {
+                self.cancel_settlement(runtime, state, settlement_id, reason).await
+            }
+            
+            Operation::ClaimRefund { settlement_id, on_behalf_of } => {
+                self.claim_refund(runtime, state, settlement_id, on_behalf_of).await
+            }
+            
+            Operation::ProcessExpiredSettlements { limit } => {
+                let limit = limit.unwrap_or(DEFAULT_EXPIRY_BATCH).min(MAX_EXPIRY_BATCH);
+                let (expired, next_expires_at) = self.process_expired_settlements(runtime, state, limit).await?;
+                return Ok(SettlementResponse::ExpiredSettlementsProcessed { expired, next_expires_at });
+            }
+            
+            Operation::GrantCustodian { custodian, assets } => {
+                let party = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                if custodian == party {
+                    return Err(SettlementError::Unauthorized { reason: "Cannot be one's own custodian".to_string() });
+                }
+                let grant = CustodianGrant { assets, granted_at: runtime.system_time() };
+                state.custodians.insert(&(party, custodian), grant.clone())?;
+                state.events.push_back(SettlementEvent::CustodianGranted { party, custodian, grant });
+                Ok(())
+            }
+            
+            Operation::RevokeCustodian { custodian } => {
+                let party = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                if !state.custodians.contains_key(&(party, custodian)).await? {
+                    return Err(SettlementError::NotCustodian { party });
+                }
+                state.custodians.remove(&(party, custodian))?;
+                state.events.push_back(SettlementEvent::CustodianRevoked {
+                    party,
+                    custodian,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::SetCounterpartyRequirement { max_default_rate_bps } => {
+                let caller = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                match max_default_rate_bps {
+                    Some(bps) if bps > 10_000 => return Err(MathError::InvalidBps { bps }.into()),
+                    Some(bps) => state.max_counterparty_default_bps.insert(&caller, bps)?,
+                    None => state.max_counterparty_default_bps.remove(&caller)?,
+                }
+                Ok(())
+            }
+            
+            Operation::SetCounterpartyPolicy { counterparty, listing } => {
+                self.set_counterparty_policy(runtime, state, counterparty, listing).await
+            }
+            
+            Operation::ConfigureBridge { chain_id, config } => {
+                self.configure_bridge(state, chain_id, config).await
+            }
+            
+            Operation::DisableBridge { chain_id } => {
+                self.disable_bridge(state, chain_id).await
+            }
+            
+            Operation::ProcessBridgeDeposit {
+                chain_id, tx_hash, user, asset, amount, confirmations,
+            } => {
+                self.process_bridge_deposit(
+                    runtime, state, chain_id, tx_hash, user, asset, amount, confirmations
+                ).await
+            }
+            
+            Operation::InitiateBridgeWithdrawal {
+                chain_id, asset, amount, destination_address, client_request_id,
+            } => {
+                let transfer_id = self.initiate_bridge_withdrawal(
+                    runtime, state, chain_id, asset, amount, destination_address
+                ).await?;
+                self.record_receipt(runtime, state, client_request_id, transfer_id).await
+            }
+            
+            Operation::WithdrawSettlementProceeds {
+                settlement_id, chain_id, destination_address, client_request_id,
+            } => {
+                let transfer_id = self.withdraw_settlement_proceeds(
+                    runtime, state, settlement_id, chain_id, destination_address
+                ).await?;
+                self.record_receipt(runtime, state, client_request_id, transfer_id).await
+            }
+            
+            Operation::CompleteBridgeWithdrawal {
+                transfer_id, tx_hash, success,
+            } => {
+                self.complete_bridge_withdrawal(runtime, state, transfer_id, tx_hash, success).await
+            }
+            
+            Operation::Deposit { asset, amount } => {
+                self.deposit(runtime, state, asset, amount).await
+            }
+            
+            Operation::Withdraw { asset, amount } => {
+                self.withdraw(runtime, state, asset, amount).await
+            }
+            
+            Operation::AuditEscrow => {
+                self.audit_escrow(runtime, state).await
+            }
+            
+            Operation::ResolveStuckExecution { settlement_id } => {
+                self.resolve_stuck_execution(runtime, state, settlement_id).await
+            }
+            
+            Operation::SetMinSettlementAmount { asset, minimum } => {
+                self.require_admin(runtime, state)?;
+                if minimum == Amount::ZERO {
+                    state.min_settlement_amounts.remove(&asset)?;
+                } else {
+                    state.min_settlement_amounts.insert(&asset, minimum)?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetMaxSettlementAmount { asset, maximum } => {
+                self.require_admin(runtime, state)?;
+                if maximum == Amount::ZERO {
+                    state.max_settlement_amounts.remove(&asset)?;
+                } else {
+                    state.max_settlement_amounts.insert(&asset, maximum)?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetUncappedPair { first, second, uncapped } => {
+                self.require_admin(runtime, state)?;
+                state.uncapped_pairs.remove(&(second, first))?;
+                if uncapped {
+                    state.uncapped_pairs.insert(&(first, second), ())?;
+                } else {
+                    state.uncapped_pairs.remove(&(first, second))?;
+                }
+                Ok(())
+            }
+            
+            Operation::SetMarket { application_id, market } => {
+                self.require_admin(runtime, state)?;
+                match market {
+                    Some(market) => state.markets.insert(&application_id, market)?,
+                    None => state.markets.remove(&application_id)?,
+                }
+                Ok(())
+            }
+            
+            Operation::SetSettlementWindow { window_seconds } => {
+                self.require_admin(runtime, state)?;
+                state.settlement_window_seconds.set(window_seconds);
+                Ok(())
+            }
+            
+            Operation::SetRateOracle { oracle } => {
+                self.require_admin(runtime, state)?;
+                state.rate_oracle.set(oracle);
+                Ok(())
+            }
+            
+            Operation::SetCorridorSource { application_id } => {
+                self.require_admin(runtime, state)?;
+                state.corridor_source.set(application_id);
+                Ok(())
+            }
+            
+            Operation::RefreshCorridorStatus { corridor } => {
+                self.corridor_availability(runtime, state, corridor).await?;
+                Ok(())
+            }
+            
+            Operation::SetConversionRate { from_asset, to_asset, rate, max_staleness_seconds } => {
+                let publisher = self.require_rate_publisher(runtime, state)?;
+                if rate == 0 || from_asset == to_asset {
+                    return Err(SettlementError::InvalidConversionRate {
+                        reason: "Rate must be positive and between different assets".to_string(),
+                    });
+                }
+                state.conversion_rates.insert(&(from_asset, to_asset), ConversionRate {
+                    rate,
+                    max_staleness_seconds,
+                    updated_at: runtime.system_time(),
+                    updated_by: publisher,
+                })?;
+                Ok(())
+            }
+            
+            Operation::RemoveConversionRate { from_asset, to_asset } => {
+                self.require_rate_publisher(runtime, state)?;
+                state.conversion_rates.remove(&(from_asset, to_asset))?;
+                Ok(())
+            }
+            
+            Operation::SetSettlementThrottle { throttle } => {
+                self.require_admin(runtime, state)?;
+                if let Some(throttle) = &throttle {
+                    throttle.validate()?;
+                }
+                state.settlement_throttle.set(throttle);
+                Ok(())
+            }
+            
+            Operation::ProcessDeferredRequests => {
+                let (initiated, remaining) = self.process_deferred_requests(runtime, state).await?;
+                return Ok(SettlementResponse::DeferredRequestsProcessed { initiated, remaining });
+            }
+            
+            Operation::FlushDeferredRequests { origin } => {
+                let admin = self.require_admin(runtime, state)?;
+                let queue = state.deferred_queues.get(&origin).await?
+                    .ok_or(SettlementError::NoDeferredRequests { origin })?;
+                while self.initiate_next_deferred(runtime, state, origin).await? {}
+                state.events.push_back(SettlementEvent::DeferredRequestsFlushed {
+                    origin,
+                    count: queue.depth(),
+                    flushed_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::DropDeferredRequests { origin } => {
+                let admin = self.require_admin(runtime, state)?;
+                let queue = state.deferred_queues.get(&origin).await?
+                    .ok_or(SettlementError::NoDeferredRequests { origin })?;
+                for position in queue.head..queue.tail {
+                    state.deferred_requests.remove(&(origin, position))?;
+                }
+                state.deferred_queues.remove(&origin)?;
+                state.events.push_back(SettlementEvent::DeferredRequestsDropped {
+                    origin,
+                    count: queue.depth(),
+                    dropped_by: admin,
+                    timestamp: runtime.system_time(),
+                });
+                Ok(())
+            }
+            
+            Operation::CreateRecurringSettlement {
+                payee, asset, amount, payee_chain, interval_seconds, timeout_seconds,
+                end, max_consecutive_defaults, first_due_at,
+            } => {
+                let payer = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                let now = runtime.system_time();
+                let recurring_id = state.next_recurring_id.get();
+                let recurring = RecurringSettlement {
+                    id: recurring_id,
+                    payer,
+                    payee,
+                    asset,
+                    amount,
+                    payer_chain: runtime.chain_id(),
+                    payee_chain: payee_chain.unwrap_or_else(|| runtime.chain_id()),
+                    interval_seconds,
+                    timeout_seconds,
+                    end,
+                    max_consecutive_defaults,
+                    consecutive_defaults: 0,
+                    counted_instances: 0,
+                    next_due_at: first_due_at.unwrap_or(now),
+                    status: RecurringStatus::Active,
+                    created_at: now,
+                };
+                recurring.validate()?;
+                state.recurring_settlements.insert(&recurring_id, recurring)?;
+                state.active_recurring.insert(&recurring_id, ())?;
+                for user in [payer, payee] {
+                    let mut user_recurring = state.user_recurring.get(&user).await?.unwrap_or_default();
+                    user_recurring.push(recurring_id);
+                    state.user_recurring.insert(&user, user_recurring)?;
+                }
+                state.next_recurring_id.set(recurring_id + 1);
+                return Ok(SettlementResponse::RecurringSettlementCreated { recurring_id });
+            }
+            
+            Operation::CancelRecurringSettlement { recurring_id } => {
+                let caller = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                let recurring = state.recurring_settlements.get(&recurring_id).await?
+                    .ok_or(SettlementError::RecurringSettlementNotFound { recurring_id })?;
+                if caller != recurring.payer && caller != recurring.payee {
+                    return Err(SettlementError::Unauthorized { reason: "Only participants can cancel".to_string() });
+                }
+                if recurring.status != RecurringStatus::Active {
+                    return Err(SettlementError::CannotCancel { reason: "Recurring settlement already stopped".to_string() });
+                }
+                self.stop_recurring(runtime, state, recurring, RecurringStatus::Cancelled { by: caller })
+            }
+            
+            Operation::ProcessRecurringSettlements { limit } => {
+                let limit = limit.unwrap_or(DEFAULT_RECURRING_BATCH).min(MAX_RECURRING_BATCH);
+                let instantiated = self.process_recurring_settlements(runtime, state, limit).await?;
+                return Ok(SettlementResponse::RecurringSettlementsProcessed { instantiated });
+            }
+            
+            Operation::CreateSettlementTemplate { counterparty, maker_asset, taker_asset, timeout_seconds, options } => {
+                let creator = runtime.authenticated_signer()
+                    .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+                let now = runtime.system_time();
+                let template_id = state.next_template_id.get();
+                let template = SettlementTemplate {
+                    id: template_id,
+                    creator,
+                    counterparty,
+                    maker_asset,
+                    taker_asset,
+                    creator_chain: runtime.chain_id(),
+                    counterparty_chain: options.counterparty_chain.unwrap_or_else(|| runtime.chain_id()),
+                    timeout_seconds,
+                    windowed: options.windowed,
+                    memo: options.memo,
+                    status: TemplateStatus::Proposed,
+                    created_at: now,
+                    updated_at: now,
+                    accepted_at: None,
+                };
+                template.validate()?;
+                state.settlement_templates.insert(&template_id, template)?;
+                for account in [creator, counterparty] {
+                    let mut account_templates = state.account_templates.get(&account).await?.unwrap_or_default();
+                    account_templates.push(template_id);
+                    state.account_templates.insert(&account, account_templates)?;
+                }
+                state.next_template_id.set(template_id + 1);
+                return Ok(SettlementResponse::SettlementTemplateCreated { template_id });
+            }
+            
+            Operation::UpdateSettlementTemplate { template_id, maker_asset, taker_asset, timeout_seconds, options } => {
+                let template = self.template_for(runtime, state, template_id, |template| template.creator).await?;
+                if template.status == TemplateStatus::Revoked {
+                    return Err(SettlementError::TemplateNotActive { template_id, status: template.status });
+                }
+                let template = SettlementTemplate {
+                    maker_asset,
+                    taker_asset,
+                    counterparty_chain: options.counterparty_chain.unwrap_or_else(|| runtime.chain_id()),
+                    timeout_seconds,
+                    windowed: options.windowed,
+                    memo: options.memo,
+                    status: TemplateStatus::Proposed,
+                    updated_at: runtime.system_time(),
+                    accepted_at: None,
+                    ..template
+                };
+                template.validate()?;
+                state.settlement_templates.insert(&template_id, template)?;
+                Ok(())
+            }
+            
+            Operation::AcceptSettlementTemplate { template_id } => {
+                let mut template = self.template_for(runtime, state, template_id, |template| template.counterparty).await?;
+                if template.status != TemplateStatus::Proposed {
+                    return Err(SettlementError::TemplateNotActive { template_id, status: template.status });
+                }
+                template.status = TemplateStatus::Active;
+                template.accepted_at = Some(runtime.system_time());
+                state.settlement_templates.insert(&template_id, template)?;
+                Ok(())
+            }
+            
+            Operation::RevokeSettlementTemplate { template_id } => {
+                let mut template = self.template_for(runtime, state, template_id, |template| template.creator).await?;
+                if template.status == TemplateStatus::Revoked {
+                    return Err(SettlementError::TemplateNotActive { template_id, status: template.status });
+                }
+                template.status = TemplateStatus::Revoked;
+                state.settlement_templates.insert(&template_id, template)?;
+                Ok(())
+            }
+            
+            Operation::InitiateFromTemplate { template_id, maker_amount, taker_amount, trade_id } => {
+                let template = self.template_for(runtime, state, template_id, |template| template.creator).await?;
+                if template.status != TemplateStatus::Active {
+                    return Err(SettlementError::TemplateNotActive { template_id, status: template.status });
+                }
+                // The memo was validated with the template; it is attached once the settlement exists
+                let provenance = self.provenance(runtime, SettlementOriginKind::Operation, None);
+                let settlement_id = self.initiate_settlement(
+                    runtime, state, trade_id, template.creator, template.counterparty,
+                    template.maker_asset, template.taker_asset, maker_amount, taker_amount,
+                    template.creator_chain, template.counterparty_chain, template.timeout_seconds,
+                    None, template.windowed, false, provenance, None, None,
+                ).await?;
+                if let Some(mut settlement) = state.settlements.get(&settlement_id).await? {
+                    settlement.memo = template.memo;
+                    settlement.template_id = Some(template_id);
+                    state.settlements.insert(&settlement_id, settlement)?;
+                }
+                let mut template_settlements = state.template_settlements.get(&template_id).await?.unwrap_or_default();
+                template_settlements.push(settlement_id);
+                state.template_settlements.insert(&template_id, template_settlements)?;
+                return Ok(SettlementResponse::SettlementInitiated { settlement_id });
+            }
+            
+            Operation::ReplayParkedMessage { parked_id } => {
+                self.resolve_parked_message(runtime, state, parked_id, true).await
+            }
+            
+            Operation::DiscardParkedMessage { parked_id } => {
+                self.resolve_parked_message(runtime, state, parked_id, false).await
+            }
+            
+            Operation::RetryDeadLetter { dead_letter_id } => {
+                self.resolve_dead_letter(runtime, state, dead_letter_id, true).await
+            }
+            
+            Operation::PurgeDeadLetter { dead_letter_id } => {
+                self.resolve_dead_letter(runtime, state, dead_letter_id, false).await
+            }
+            
+            Operation::TransferAdmin { new_admin } => {
+                self.require_admin(runtime, state)?;
+                state.admin.set(Some(new_admin));
+                Ok(())
+            }
+        };
+        result.map(|()| SettlementResponse::Ok)
+    }
+
+    async fn execute_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut Self::State,
+        envelope: MessageEnvelope,
+    ) {
+        let context = MessageContext {
+            message_id: runtime.message_id(),
+            caller: runtime.authenticated_caller_id(),
+            signer: runtime.authenticated_signer(),
+        };
+        match envelope.open() {
+            Ok(message) => self.deliver_message(runtime, state, envelope, message, context).await,
+            Err(reason) => {
+                if let Err(e) = self.park_message(runtime, state, envelope, context, reason) {
+                    tracing::error!("Failed to park message: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl SettlementContract {
+    /// Applies `message`, read from `envelope`, and keeps it as a dead letter if that fails
+    async fn deliver_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        envelope: MessageEnvelope,
+        message: Message,
+        context: MessageContext,
+    ) {
+        let dedupe_key = message.dedupe_key();
+        let request = match &message {
+            Message::SettlementRequest { trade_id, market, .. } => Some((*trade_id, *market)),
+            _ => None,
+        };
+        let Err(error) = self.apply_message(runtime, state, message, context).await else {
+            return;
+        };
+        if let Err(e) = self.dead_letter(runtime, state, envelope, context, dedupe_key, request, error) {
+            tracing::error!("Failed to keep dead letter: {}", e);
+        }
+    }
+    
+    /// Applies a delivered message; `context` is how it arrived, which for a replayed message
+    /// is not the current block's. Refused messages, such as those from unexpected senders, are
+    /// ignored; errors are failures to apply.
+    async fn apply_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        message: Message,
+        context: MessageContext,
+    ) -> Result<(), SettlementError> {
+        if !self.record_delivery(runtime, state, &message, context).await? {
+            return Ok(());
+        }
+        
+        match message {
+            Message::SettlementRequest {
+                trade_id, maker, taker, maker_asset, taker_asset,
+                maker_amount, taker_amount, timeout_seconds, fees, maker_chain, taker_chain, market,
+            } => {
+                // No application is authenticated while a message executes, so the market named by
+                // the sending chain must be registered for that chain
+                let origin = context.origin();
+                let mut verified =
+                    self.verify_settlement_request(state, market, origin, &maker_asset, &taker_asset).await;
+                if verified.is_ok() {
+                    // Checked again at initiation, for requests deferred meanwhile
+                    verified = self.check_counterparty_policies(state, maker, taker).await;
+                }
+                if let Err(e) = verified {
+                    tracing::warn!("Rejected settlement request for trade {}: {}", trade_id, e);
+                    state.events.push_back(SettlementEvent::SettlementRequestRejected {
+                        trade_id,
+                        caller: market,
+                        origin,
+                        reason: e.to_string(),
+                        timestamp: runtime.system_time(),
+                    });
+                    return Err(e);
+                }
+                
+                let request = DeferredSettlementRequest {
+                    trade_id,
+                    maker,
+                    taker,
+                    maker_asset,
+                    taker_asset,
+                    maker_amount,
+                    taker_amount,
+                    timeout_seconds,
+                    fees,
+                    maker_chain: maker_chain.unwrap_or(runtime.chain_id()),
+                    taker_chain: taker_chain.unwrap_or(runtime.chain_id()),
+                    provenance: self.message_provenance(runtime, context, market),
+                    deferred_at: runtime.system_time(),
+                };
+                self.admit_settlement_request(runtime, state, request).await?;
+            }
             
-            Operation::ExecuteSettlement { settlement_id } => {
-                self.execute_settlement(runtime, state, settlement_id).await
+            Message::EscrowConfirmation { settlement_id, party, confirmed, amount } => {
+                tracing::info!(
+                    "Escrow confirmation: settlement={}, party={:?}, confirmed={}, amount={}",
+                    settlement_id, party, confirmed, amount
+                );
+            }
+            
+            Message::SettlementComplete { settlement_id, success, failure_reason, external_ref, .. } => {
+                tracing::info!(
+                    "Settlement complete: id={}, success={}, reason={:?}, external_ref={:?}",
+                    settlement_id, success, failure_reason, external_ref
+                );
+            }
+            
+            Message::BridgeEvent { chain_id, event_type, transfer_id, data } => {
+                tracing::info!(
+                    "Bridge event: chain={}, type={:?}, transfer={}, data_len={}",
+                    chain_id, event_type, transfer_id, data.len()
+                );
+                // Transfer ids are only meaningful on the chain that recorded the transfer
+                if context.origin() != Some(runtime.chain_id()) {
+                    return Ok(());
+                }
+                self.update_bridge_leg(runtime, state, transfer_id).await?;
+            }
+            
+            Message::RefundProcessed { settlement_id, party, amount, asset } => {
+                tracing::info!(
+                    "Refund processed: settlement={}, party={:?}, amount={}, asset={}",
+                    settlement_id, party, amount, asset
+                );
+            }
+            
+            Message::SettlementRejected { market, trade_id, reason_code } => {
+                let Some(market) = market else {
+                    return Ok(());
+                };
+                let key = (market, trade_id);
+                let Some(mut relayed) = state.relayed_requests.get(&key).await? else {
+                    return Ok(());
+                };
+                if !relayed.reject(context.origin(), reason_code) {
+                    tracing::warn!("Ignored rejection of trade {} from {:?}", trade_id, context.origin());
+                    return Ok(());
+                }
+                state.relayed_requests.insert(&key, relayed)?;
+            }
+            
+        }
+        Ok(())
+    }
+    
+    /// Keeps a message this build cannot read for an admin to replay after an upgrade, or discard
+    fn park_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        envelope: MessageEnvelope,
+        context: MessageContext,
+        reason: String,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        let parked_id = state.next_parked_message_id.get();
+        tracing::warn!("Parking message {} of version {}: {}", parked_id, envelope.version, reason);
+        state.events.push_back(SettlementEvent::MessageParked {
+            parked_id,
+            version: envelope.version,
+            origin: context.origin(),
+            reason: reason.clone(),
+            timestamp: now,
+        });
+        state.parked_messages.insert(&parked_id, ParkedMessage {
+            id: parked_id,
+            envelope,
+            context,
+            reason,
+            parked_at: now,
+        })?;
+        state.next_parked_message_id.set(parked_id + 1);
+        Ok(())
+    }
+    
+    /// Replays or discards a parked message
+    async fn resolve_parked_message(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        parked_id: u64,
+        replay: bool,
+    ) -> Result<(), SettlementError> {
+        let admin = self.require_admin(runtime, state)?;
+        let parked = state.parked_messages.get(&parked_id).await?
+            .ok_or(SettlementError::ParkedMessageNotFound { parked_id })?;
+        if replay {
+            let message = parked.envelope.open()
+                .map_err(|reason| SettlementError::ParkedMessageUnreadable { parked_id, reason })?;
+            self.deliver_message(runtime, state, parked.envelope, message, parked.context).await;
+        }
+        state.parked_messages.remove(&parked_id)?;
+        state.events.push_back(SettlementEvent::ParkedMessageResolved {
+            parked_id,
+            replayed: replay,
+            resolved_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Keeps a message that failed to apply for an admin to retry or purge, dropping the oldest
+    /// dead letter past `MAX_DEAD_LETTERS`. The market of a failed settlement request, given
+    /// with its trade id, is told its trade will not settle.
+    fn dead_letter(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        envelope: MessageEnvelope,
+        context: MessageContext,
+        dedupe_key: Option<MessageDedupeKey>,
+        request: Option<(u64, Option<ApplicationId>)>,
+        error: SettlementError,
+    ) -> Result<(), SettlementError> {
+        // Forgotten, so neither a retry nor a redelivery is taken for a duplicate
+        if let Some(key) = dedupe_key {
+            state.seen_messages.remove(&key)?;
+        }
+        if let (Some((trade_id, market)), Some(origin)) = (request, context.origin()) {
+            self.send_rejection(runtime, origin, market, trade_id, &error);
+        }
+        let trade_id = request.map(|(trade_id, _)| trade_id);
+        
+        let now = runtime.system_time();
+        let dead_letter_id = state.next_dead_letter_id.get();
+        let reason = error.to_string();
+        tracing::warn!("Dead-lettering message {}: {}", dead_letter_id, reason);
+        state.events.push_back(SettlementEvent::MessageDeadLettered {
+            dead_letter_id,
+            origin: context.origin(),
+            trade_id,
+            reason: reason.clone(),
+            timestamp: now,
+        });
+        state.dead_letters.insert(&dead_letter_id, DeadLetter {
+            id: dead_letter_id,
+            payload_hash: Keccak256::digest(&envelope.payload).into(),
+            envelope,
+            context,
+            trade_id,
+            reason,
+            failed_at: now,
+        })?;
+        state.next_dead_letter_id.set(dead_letter_id + 1);
+        
+        if let Some(evicted) = dead_letter_to_evict(state.first_dead_letter_id.get(), dead_letter_id) {
+            tracing::warn!("Dropping dead letter {}", evicted);
+            state.dead_letters.remove(&evicted)?;
+            state.first_dead_letter_id.set(evicted + 1);
+        }
+        Ok(())
+    }
+    
+    /// Tells `chain`, which relayed `market`'s settlement request for `trade_id`, that it failed;
+    /// the market takes the rejection from there, as messages only reach this application
+    fn send_rejection(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        chain: ChainId,
+        market: Option<ApplicationId>,
+        trade_id: u64,
+        error: &SettlementError,
+    ) {
+        runtime
+            .prepare_message(MessageEnvelope::seal(&Message::SettlementRejected {
+                market,
+                trade_id,
+                reason_code: error.rejection_code(),
+            }))
+            .send_to(chain);
+    }
+    
+    /// Retries or purges a dead letter. The market of a settlement request heard of its failure
+    /// when it was dead-lettered and has failed the trade, so such a request is only purged.
+    async fn resolve_dead_letter(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        dead_letter_id: u64,
+        retry: bool,
+    ) -> Result<(), SettlementError> {
+        let admin = self.require_admin(runtime, state)?;
+        let dead_letter = state.dead_letters.get(&dead_letter_id).await?
+            .ok_or(SettlementError::DeadLetterNotFound { dead_letter_id })?;
+        if retry && dead_letter.rejection_sent() {
+            return Err(SettlementError::DeadLetterRejected { dead_letter_id });
+        }
+        state.dead_letters.remove(&dead_letter_id)?;
+        if retry {
+            match dead_letter.envelope.open() {
+                Ok(message) => {
+                    self.deliver_message(runtime, state, dead_letter.envelope, message, dead_letter.context).await;
+                }
+                Err(reason) => self.park_message(runtime, state, dead_letter.envelope, dead_letter.context, reason)?,
+            }
+        }
+        state.events.push_back(SettlementEvent::DeadLetterResolved {
+            dead_letter_id,
+            retried: retry,
+            resolved_by: admin,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Remembers the message's dedupe key. Returns false, after logging an event, when the key
+    /// was already delivered and the message must not be applied again.
+    async fn record_delivery(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        message: &Message,
+        context: MessageContext,
+    ) -> Result<bool, SettlementError> {
+        let now = runtime.system_time();
+        for _ in 0..MAX_DEDUPE_PRUNE {
+            let Some((seen_at, key)) = state.seen_message_order.front().await? else {
+                break;
+            };
+            if !dedupe_expired(seen_at, now) && state.seen_message_order.count() as u64 <= MAX_SEEN_MESSAGES {
+                break;
+            }
+            state.seen_message_order.pop_front();
+            state.seen_messages.remove(&key)?;
+        }
+        
+        let Some(key) = message.dedupe_key() else {
+            return Ok(true);
+        };
+        if let Some(first_seen_at) = state.seen_messages.get(&key).await? {
+            tracing::warn!("Ignoring redelivered message: {:?}", key);
+            state.events.push_back(SettlementEvent::DuplicateMessageIgnored {
+                key,
+                origin: context.origin(),
+                first_seen_at,
+                timestamp: now,
+            });
+            return Ok(false);
+        }
+        state.seen_messages.insert(&key, now)?;
+        state.seen_message_order.push_back((now, key));
+        Ok(true)
+    }
+    
+    async fn initiate_settlement(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        trade_id: u64,
+        maker: Account,
+        taker: Account,
+        maker_asset: String,
+        taker_asset: String,
+        maker_amount: Amount,
+        taker_amount: Amount,
+        maker_chain: ChainId,
+        taker_chain: ChainId,
+        timeout_seconds: u64,
+        fees: Option<SettlementFees>,
+        windowed: bool,
+        one_sided: bool,
+        provenance: SettlementProvenance,
+        memo: Option<String>,
+        external_ref: Option<String>,
+    ) -> Result<u64, SettlementError> {
+        let zero_legs = [maker_amount, taker_amount].iter().filter(|amount| **amount == Amount::ZERO).count();
+        match (one_sided, zero_legs) {
+            (false, 0) | (true, 1) => {}
+            (false, _) => {
+                return Err(SettlementError::InvalidOneSidedSettlement {
+                    reason: "A zero leg needs a one-sided settlement".to_string(),
+                });
+            }
+            (true, _) => {
+                return Err(SettlementError::InvalidOneSidedSettlement {
+                    reason: "Exactly one leg must be zero".to_string(),
+                });
+            }
+        }
+        if let Some(fees) = &fees {
+            fees.validate(maker_amount, taker_amount)?;
+        }
+        let net_transfer = (maker_asset == taker_asset).then(|| NetTransfer::new(maker_amount, taker_amount, fees.as_ref()));
+        if net_transfer.is_some_and(|net| net.is_empty()) {
+            return Err(SettlementError::InvalidSameAssetSettlement {
+                reason: "Legs cancel out, nothing to settle".to_string(),
+            });
+        }
+        if let Some(memo) = &memo {
+            validate_tag("memo", memo, MAX_MEMO_LENGTH)?;
+        }
+        let external_ref_key = match &external_ref {
+            Some(external_ref) => {
+                validate_tag("external reference", external_ref, MAX_EXTERNAL_REF_LENGTH)?;
+                let creator = provenance.signer.ok_or_else(|| SettlementError::Unauthorized {
+                    reason: "An external reference needs an authenticated signer".to_string(),
+                })?;
+                let key = (creator, external_ref.clone());
+                if let Some(settlement_id) = state.settlements_by_external_ref.get(&key).await? {
+                    return Err(SettlementError::DuplicateExternalRef { external_ref: external_ref.clone(), settlement_id });
+                }
+                Some(key)
+            }
+            None => None,
+        };
+        if windowed && state.settlement_window_seconds.get() == 0 {
+            return Err(SettlementError::NoSettlementWindow);
+        }
+        // The zero leg of a one-sided settlement moves nothing, so no minimum applies to it
+        for (asset, amount) in [(&maker_asset, maker_amount), (&taker_asset, taker_amount)] {
+            if amount == Amount::ZERO {
+                continue;
+            }
+            if let Some(minimum) = state.min_settlement_amounts.get(asset).await? {
+                if amount < minimum {
+                    return Err(SettlementError::BelowMinimum { amount, minimum });
+                }
+            }
+        }
+        self.check_counterparty_policies(state, maker, taker).await?;
+        for (party, counterparty) in [(maker, taker), (taker, maker)] {
+            if let Some(maximum_bps) = state.max_counterparty_default_bps.get(&party).await? {
+                let reputation = state.reputations.get(&counterparty).await?.unwrap_or_default();
+                let default_rate_bps = reputation.default_rate_bps();
+                if default_rate_bps > maximum_bps {
+                    return Err(SettlementError::CounterpartyDefaultRate { counterparty, default_rate_bps, maximum_bps });
+                }
+            }
+        }
+        if !self.is_uncapped_pair(state, maker, taker).await? {
+            for (asset, amount) in [(&maker_asset, maker_amount), (&taker_asset, taker_amount)] {
+                if let Some(maximum) = state.max_settlement_amounts.get(asset).await? {
+                    if amount > maximum {
+                        return Err(SettlementError::AboveMaximum { amount, maximum });
+                    }
+                }
+            }
+        }
+        let settlement_id = state.next_settlement_id.get();
+        let now = runtime.system_time();
+        let expires_at = now + std::time::Duration::from_secs(timeout_seconds);
+        
+        let mut settlement = Settlement {
+            id: settlement_id,
+            trade_id,
+            maker,
+            taker,
+            maker_asset: maker_asset.clone(),
+            taker_asset: taker_asset.clone(),
+            maker_amount,
+            taker_amount,
+            maker_chain,
+            taker_chain,
+            maker_escrow: EscrowState::default(),
+            taker_escrow: EscrowState::default(),
+            status: SettlementStatus::Pending,
+            created_at: now,
+            expires_at,
+            completed_at: None,
+            failure_reason: None,
+            retry_count: 0,
+            fees,
+            windowed,
+            execute_at: None,
+            execution_started_at: None,
+            maker_bridge_transfer_id: None,
+            maker_bridge_status: None,
+            taker_bridge_transfer_id: None,
+            taker_bridge_status: None,
+            provenance: Some(provenance.clone()),
+            memo,
+            external_ref,
+            recurring: None,
+            template_id: None,
+            confirmation: None,
+            confirmed_by: Vec::new(),
+            one_sided,
+        };
+        
+        // A party owing nothing after netting has nothing to escrow
+        if let Some(net) = net_transfer {
+            if net.maker_owes == Amount::ZERO {
+                settlement.maker_escrow = EscrowState::waived(&maker_asset);
+                settlement.status = SettlementStatus::MakerEscrowed;
+            } else if net.taker_owes == Amount::ZERO {
+                settlement.taker_escrow = EscrowState::waived(&taker_asset);
+                settlement.status = SettlementStatus::TakerEscrowed;
+            }
+        }
+        
+        // Store settlement
+        state.settlements.insert(&settlement_id, settlement.clone())?;
+        state.active_settlements.insert(&settlement_id, ())?;
+        if let Some(key) = external_ref_key {
+            state.settlements_by_external_ref.insert(&key, settlement_id)?;
+        }
+        
+        // Add to user settlements
+        for user in [maker, taker] {
+            let mut user_settlements = state.user_settlements.get(&user).await?.unwrap_or_default();
+            user_settlements.push(settlement_id);
+            state.user_settlements.insert(&user, user_settlements)?;
+        }
+        let mut origin_settlements = state.settlements_by_origin.get(&provenance.origin).await?.unwrap_or_default();
+        origin_settlements.push(settlement_id);
+        state.settlements_by_origin.insert(&provenance.origin, origin_settlements)?;
+        
+        self.queue_expiration(state, expires_at, settlement_id).await?;
+        
+        // Update stats
+        let mut stats = state.stats.get();
+        stats.total_settlements += 1;
+        state.stats.set(stats);
+        
+        // Increment settlement ID
+        state.next_settlement_id.set(settlement_id + 1);
+        
+        tracing::info!(
+            "Settlement initiated: id={}, trade_id={}, maker={:?}, taker={:?}",
+            settlement_id, trade_id, maker, taker
+        );
+        
+        Ok(settlement_id)
+    }
+    
+    async fn confirm_escrow(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        settlement_id: u64,
+        on_behalf_of: Option<Account>,
+        substitute_asset: Option<String>,
+        bridge_transfer_id: Option<u64>,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        let (caller, custodian) = self.acting_party(runtime, state, &settlement, on_behalf_of).await?;
+        
+        // Check expiration
+        if now > settlement.expires_at {
+            return Err(SettlementError::SettlementExpired { expired_at: settlement.expires_at });
+        }
+        
+        // Determine which party is escrowing
+        let amount = settlement.escrow_required(caller);
+        let (is_maker, asset) = if caller == settlement.maker {
+            if settlement.maker_escrow.is_escrowed {
+                return Err(SettlementError::AlreadyEscrowed);
+            }
+            (true, settlement.maker_asset.clone())
+        } else if caller == settlement.taker {
+            if settlement.taker_escrow.is_escrowed {
+                return Err(SettlementError::AlreadyEscrowed);
+            }
+            (false, settlement.taker_asset.clone())
+        } else {
+            return Err(SettlementError::Unauthorized { 
+                reason: "Caller is not a party to this settlement".to_string() 
+            });
+        };
+        
+        // The zero leg of a one-sided settlement is only accepted: its party confirms, nothing moves
+        let acceptance = settlement.one_sided && amount == Amount::ZERO;
+        if acceptance && (substitute_asset.is_some() || bridge_transfer_id.is_some()) {
+            return Err(SettlementError::InvalidOneSidedSettlement {
+                reason: "A zero leg is accepted, not escrowed".to_string(),
+            });
+        }
+        
+        // A substitute is escrowed at the current rate, rounded up so the counterparty is not short.
+        // Netting needs both sides in one asset, so same-asset settlements take no substitute.
+        let substitute_asset = substitute_asset.filter(|substitute| *substitute != asset);
+        if substitute_asset.is_some() && settlement.is_same_asset() {
+            return Err(SettlementError::InvalidSameAssetSettlement {
+                reason: "Escrow cannot be substituted".to_string(),
+            });
+        }
+        let mut substitution = None;
+        let (asset, amount) = match substitute_asset {
+            Some(substitute) => {
+                let conversion = state.conversion_rates.get(&(asset.clone(), substitute.clone())).await?
+                    .ok_or_else(|| SettlementError::NoConversionRate {
+                        from_asset: asset.clone(),
+                        to_asset: substitute.clone(),
+                    })?;
+                if conversion.is_stale(now) {
+                    return Err(SettlementError::StaleConversionRate {
+                        from_asset: asset,
+                        to_asset: substitute,
+                        updated_at: conversion.updated_at,
+                    });
+                }
+                if let Some(custodian) = custodian {
+                    let grant = state.custodians.get(&(caller, custodian)).await?
+                        .ok_or(SettlementError::NotCustodian { party: caller })?;
+                    if !grant.covers(&substitute) {
+                        return Err(SettlementError::AssetNotInCustody { asset: substitute });
+                    }
+                }
+                let converted = math::convert_up(amount, conversion.rate)?;
+                substitution = Some(AssetSubstitution { original_asset: asset, original_amount: amount, rate: conversion.rate });
+                (substitute, converted)
+            }
+            None => (asset, amount),
+        };
+        
+        let bridge_deposit = match bridge_transfer_id {
+            Some(transfer_id) => {
+                let transfer = state.bridge_transfers.get(&transfer_id).await?
+                    .ok_or(SettlementError::TransferNotFound { transfer_id })?;
+                transfer.check_escrow_proof(caller, &asset)?;
+                Some(transfer)
             }
+            None => None,
+        };
+        
+        // Lock balance (move to escrow)
+        if !acceptance {
+            self.debit_balance(state, caller, &asset, amount).await?;
+            self.add_escrow(state, (settlement_id, caller, asset.clone()), amount).await?;
+        }
+        
+        // Update escrow state
+        let escrow_state = EscrowState {
+            is_escrowed: true,
+            amount,
+            asset: asset.clone(),
+            escrowed_at: Some(now),
+            tx_hash: None,
+            confirmed_by: custodian,
+            substitution,
+        };
+        
+        if is_maker {
+            settlement.maker_escrow = escrow_state;
+            settlement.status = if settlement.taker_escrow.is_escrowed {
+                SettlementStatus::FullyEscrowed
+            } else {
+                SettlementStatus::MakerEscrowed
+            };
+        } else {
+            settlement.taker_escrow = escrow_state;
+            settlement.status = if settlement.maker_escrow.is_escrowed {
+                SettlementStatus::FullyEscrowed
+            } else {
+                SettlementStatus::TakerEscrowed
+            };
+        }
+        
+        if let Some(mut transfer) = bridge_deposit {
+            transfer.settlement_ref = Some(SettlementRef { settlement_id, payer: caller });
+            settlement.link_bridge_transfer(caller, transfer.id, transfer.status);
+            state.bridge_transfers.insert(&transfer.id, transfer.clone())?;
+            state.events.push_back(SettlementEvent::BridgeLegLinked {
+                settlement_id,
+                payer: caller,
+                transfer_id: transfer.id,
+                direction: transfer.direction,
+                timestamp: now,
+            });
+        }
+        
+        state.settlements.insert(&settlement_id, settlement.clone())?;
+        if let Some(custodian) = custodian {
+            state.events.push_back(SettlementEvent::CustodialAction {
+                settlement_id,
+                party: caller,
+                custodian,
+                action: CustodialActionKind::ConfirmEscrow,
+                timestamp: now,
+            });
+        }
+        
+        tracing::info!(
+            "Escrow confirmed: settlement_id={}, party={:?}, custodian={:?}, asset={}, amount={}",
+            settlement_id, caller, custodian, asset, amount
+        );
+        
+        if settlement.status != SettlementStatus::FullyEscrowed {
+            return Ok(());
+        }
+        
+        // Settlements awaiting confirmation get their window for it, replacing the original timeout
+        if let Some(terms) = settlement.confirmation {
+            let confirm_by = now + std::time::Duration::from_secs(terms.window_seconds);
+            settlement.expires_at = confirm_by;
+            state.settlements.insert(&settlement_id, settlement)?;
+            self.queue_expiration(state, confirm_by, settlement_id).await?;
+            state.events.push_back(SettlementEvent::AwaitingConfirmation { settlement_id, confirm_by, timestamp: now });
+            return Ok(());
+        }
+        
+        // Windowed settlements wait for the next boundary; the rest execute right away
+        let window_seconds = state.settlement_window_seconds.get();
+        if settlement.windowed && window_seconds > 0 {
+            let execute_at = next_window_boundary(now, window_seconds);
+            settlement.execute_at = Some(execute_at);
+            state.settlements.insert(&settlement_id, settlement)?;
             
-            Operation::CancelSettlement { settlement_id, reason } => {
-                self.cancel_settlement(runtime, state, settlement_id, reason).await
+            let mut queued = state.window_queue.get(&execute_at.micros()).await?.unwrap_or_default();
+            queued.push(settlement_id);
+            queued.sort_unstable();
+            state.window_queue.insert(&execute_at.micros(), queued)?;
+            return Ok(());
+        }
+        self.execute_settlement(runtime, state, settlement_id).await
+    }
+    
+    async fn execute_settlement(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        settlement_id: u64,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        
+        // Verify status
+        if settlement.status != SettlementStatus::FullyEscrowed {
+            return Err(SettlementError::InvalidStatus {
+                expected: SettlementStatus::FullyEscrowed,
+                actual: settlement.status,
+            });
+        }
+        
+        if let Some(execute_at) = settlement.execute_at.filter(|execute_at| now < *execute_at) {
+            return Err(SettlementError::WindowNotReached { execute_at });
+        }
+        if !settlement.is_confirmed() {
+            return Err(SettlementError::AwaitingConfirmation { confirm_by: settlement.expires_at });
+        }
+        
+        // Check expiration
+        if now > settlement.expires_at {
+            settlement.status = SettlementStatus::Expired;
+            state.settlements.insert(&settlement_id, settlement)?;
+            return Err(SettlementError::SettlementExpired { expired_at: settlement.expires_at });
+        }
+        
+        settlement.status = SettlementStatus::Executing;
+        settlement.execution_started_at = Some(now);
+        state.settlements.insert(&settlement_id, settlement.clone())?;
+        
+        if let Some(net) = settlement.net_transfer() {
+            self.pay_net(state, &settlement, net).await?;
+        } else {
+            // Everything that can fail is checked before the first leg is paid, and each leg pays
+            // its fee with it, so an interruption leaves whole legs either paid or in escrow.
+            // The zero leg of a one-sided settlement has nothing to pay.
+            let legs: Vec<_> = settlement.legs()?.into_iter()
+                .filter(|(payer, ..)| settlement.escrow_required(*payer) > Amount::ZERO)
+                .collect();
+            for (payer, _, asset, fee) in &legs {
+                let escrow_key = (settlement_id, *payer, asset.clone());
+                let escrowed = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
+                if escrowed < *fee {
+                    return Err(SettlementError::FeeExceedsAmount { fee: *fee, amount: escrowed });
+                }
+            }
+            for (payer, payee, asset, fee) in &legs {
+                self.pay_leg(state, &settlement, *payer, *payee, asset, *fee).await?;
+            }
+        }
+        
+        // Update settlement status
+        settlement.status = SettlementStatus::Completed;
+        settlement.completed_at = Some(now);
+        state.settlements.insert(&settlement_id, settlement.clone())?;
+        self.record_outcome(state, &settlement, None).await?;
+        state.events.push_back(settlement.completed_event(now));
+        
+        // Remove from active settlements
+        state.active_settlements.remove(&settlement_id)?;
+        
+        // Update stats
+        let mut stats = state.stats.get();
+        stats.completed_settlements += 1;
+        stats.total_volume = math::checked_add(stats.total_volume, settlement.volume()?)?;
+        state.stats.set(stats);
+        
+        tracing::info!(
+            "Settlement executed: id={}, maker={:?}, taker={:?}",
+            settlement_id, settlement.maker, settlement.taker
+        );
+        
+        Ok(())
+    }
+    
+    /// `ExecuteSettlement`: records the signer's confirmation on a settlement awaiting one, then
+    /// executes once the confirmation rule is met. Other settlements execute right away.
+    async fn confirm_execution(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        settlement_id: u64,
+    ) -> Result<(), SettlementError> {
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        if settlement.auto_execute() {
+            return self.execute_settlement(runtime, state, settlement_id).await;
+        }
+        if settlement.status != SettlementStatus::FullyEscrowed {
+            return Err(SettlementError::InvalidStatus {
+                expected: SettlementStatus::FullyEscrowed,
+                actual: settlement.status,
+            });
+        }
+        let now = runtime.system_time();
+        if now > settlement.expires_at {
+            return Err(SettlementError::SettlementExpired { expired_at: settlement.expires_at });
+        }
+        
+        let caller = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        if caller != settlement.maker && caller != settlement.taker {
+            return Err(SettlementError::Unauthorized {
+                reason: "Only participants can confirm execution".to_string(),
+            });
+        }
+        if settlement.confirmed_by.contains(&caller) {
+            return Err(SettlementError::AlreadyConfirmed);
+        }
+        settlement.confirmed_by.push(caller);
+        let confirmed = settlement.is_confirmed();
+        state.settlements.insert(&settlement_id, settlement)?;
+        state.events.push_back(SettlementEvent::ExecutionConfirmed { settlement_id, party: caller, timestamp: now });
+        
+        if confirmed {
+            return self.execute_settlement(runtime, state, settlement_id).await;
+        }
+        Ok(())
+    }
+    
+    async fn cancel_settlement(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        settlement_id: u64,
+        reason: String,
+    ) -> Result<(), SettlementError> {
+        let caller = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        
+        // Only participants can cancel
+        if caller != settlement.maker && caller != settlement.taker {
+            return Err(SettlementError::Unauthorized { 
+                reason: "Only participants can cancel".to_string() 
+            });
+        }
+        
+        // Can only cancel if not fully escrowed or completed
+        match settlement.status {
+            SettlementStatus::Pending |
+            SettlementStatus::MakerEscrowed |
+            SettlementStatus::TakerEscrowed => {
+                // Allowed to cancel
+            }
+            SettlementStatus::FullyEscrowed |
+            SettlementStatus::Executing => {
+                return Err(SettlementError::CannotCancel { 
+                    reason: "Settlement is already being executed".to_string() 
+                });
+            }
+            _ => {
+                return Err(SettlementError::CannotCancel { 
+                    reason: "Settlement is already finalized".to_string() 
+                });
+            }
+        }
+        
+        // Process refunds for any escrowed amounts
+        self.process_refund(state, &settlement).await?;
+        
+        settlement.status = SettlementStatus::Cancelled;
+        settlement.failure_reason = Some(reason);
+        self.record_outcome(state, &settlement, Some(caller)).await?;
+        state.settlements.insert(&settlement_id, settlement)?;
+        
+        // Remove from active settlements
+        state.active_settlements.remove(&settlement_id)?;
+        
+        tracing::info!("Settlement cancelled: id={}, by={:?}", settlement_id, caller);
+        
+        Ok(())
+    }
+    
+    async fn claim_refund(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        settlement_id: u64,
+        on_behalf_of: Option<Account>,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        let (caller, custodian) = self.acting_party(runtime, state, &settlement, on_behalf_of).await?;
+        
+        // Only participants can claim refund
+        if caller != settlement.maker && caller != settlement.taker {
+            return Err(SettlementError::Unauthorized { 
+                reason: "Only participants can claim refund".to_string() 
+            });
+        }
+        
+        // Check if refund is allowed
+        let can_refund = settlement.is_refundable(now);
+        if can_refund && now > settlement.expires_at && !matches!(
+            settlement.status,
+            SettlementStatus::Expired | SettlementStatus::Failed | SettlementStatus::Cancelled
+        ) {
+            // Mark as expired
+            settlement.status = SettlementStatus::Expired;
+            state.settlements.insert(&settlement_id, settlement.clone())?;
+            self.record_outcome(state, &settlement, None).await?;
+        }
+        
+        if !can_refund {
+            return Err(SettlementError::CannotCancel { 
+                reason: "Refund not available for this settlement status".to_string() 
+            });
+        }
+        
+        // Process refund for the caller
+        let asset = if caller == settlement.maker && settlement.maker_escrow.is_escrowed {
+            settlement.maker_escrow.held_asset(&settlement.maker_asset).to_string()
+        } else if caller == settlement.taker && settlement.taker_escrow.is_escrowed {
+            settlement.taker_escrow.held_asset(&settlement.taker_asset).to_string()
+        } else {
+            return Err(SettlementError::InsufficientBalance { 
+                required: Amount::ZERO, 
+                available: Amount::ZERO 
+            });
+        };
+        let escrow_key = (settlement_id, caller, asset.clone());
+        
+        // Return to user balance; a party whose escrow was waived gets nothing back
+        let escrowed = self.release_escrow(state, &escrow_key).await?;
+        self.credit_balance(state, caller, &asset, escrowed).await?;
+        if caller == settlement.maker {
+            settlement.maker_escrow.is_escrowed = false;
+        } else {
+            settlement.taker_escrow.is_escrowed = false;
+        }
+        
+        // Update status to refunded once neither party holds anything in escrow
+        if !settlement.maker_escrow.holds_funds() && !settlement.taker_escrow.holds_funds() {
+            settlement.status = SettlementStatus::Refunded;
+            state.active_settlements.remove(&settlement_id)?;
+        }
+        
+        state.settlements.insert(&settlement_id, settlement)?;
+        if let Some(custodian) = custodian {
+            state.events.push_back(SettlementEvent::CustodialAction {
+                settlement_id,
+                party: caller,
+                custodian,
+                action: CustodialActionKind::ClaimRefund,
+                timestamp: now,
+            });
+        }
+        
+        tracing::info!(
+            "Refund claimed: settlement_id={}, user={:?}, custodian={:?}",
+            settlement_id, caller, custodian
+        );
+        
+        Ok(())
+    }
+    
+    /// Party an escrow operation acts for: the signer, or the party it names if the signer is that
+    /// party's custodian for the asset of its leg. Returns the party and, for custodial calls, the
+    /// custodian. Grants are read on every call, so a revocation applies to the next operation.
+    async fn acting_party(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &SettlementState<ContractRuntime<Self>>,
+        settlement: &Settlement,
+        on_behalf_of: Option<Account>,
+    ) -> Result<(Account, Option<Account>), SettlementError> {
+        let signer = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let Some(party) = on_behalf_of.filter(|party| *party != signer) else {
+            return Ok((signer, None));
+        };
+        let grant = state.custodians.get(&(party, signer)).await?
+            .ok_or(SettlementError::NotCustodian { party })?;
+        let asset = if party == settlement.maker {
+            &settlement.maker_asset
+        } else if party == settlement.taker {
+            &settlement.taker_asset
+        } else {
+            return Err(SettlementError::Unauthorized {
+                reason: "Caller is not a party to this settlement".to_string(),
+            });
+        };
+        if !grant.covers(asset) {
+            return Err(SettlementError::AssetNotInCustody { asset: asset.clone() });
+        }
+        Ok((party, Some(signer)))
+    }
+    
+    /// Updates both parties' reputations for a settlement that just became `Completed`, `Cancelled`
+    /// or `Expired`. Called once per settlement, on that transition, before any escrow is refunded.
+    async fn record_outcome(
+        &self,
+        state: &mut SettlementState<ContractRuntime<SettlementContract>>,
+        settlement: &Settlement,
+        cancelled_by: Option<Account>,
+    ) -> Result<(), SettlementError> {
+        let parties = [
+            (settlement.maker, &settlement.maker_escrow, &settlement.taker_escrow),
+            (settlement.taker, &settlement.taker_escrow, &settlement.maker_escrow),
+        ];
+        for (party, escrow, counterparty_escrow) in parties {
+            let mut reputation = state.reputations.get(&party).await?.unwrap_or_default();
+            reputation.finalized += 1;
+            match settlement.status {
+                SettlementStatus::Completed => reputation.completed += 1,
+                SettlementStatus::Cancelled if cancelled_by == Some(party) => reputation.cancelled += 1,
+                SettlementStatus::Expired if !escrow.is_escrowed && counterparty_escrow.is_escrowed => {
+                    reputation.defaulted += 1;
+                }
+                _ => {}
             }
-            
-            Operation::ClaimRefund { settlement_id } => {
-                self.claim_refund(runtime, state, settlement_id).await
+            // Accepting the zero leg of a one-sided settlement is not an escrow
+            if let Some(escrowed_at) = escrow.escrowed_at.filter(|_| !settlement.is_zero_leg(party)) {
+                let delay = escrowed_at.micros().saturating_sub(settlement.created_at.micros()) / 1_000_000;
+                reputation.escrows += 1;
+                reputation.total_escrow_seconds = reputation.total_escrow_seconds.saturating_add(delay);
             }
-            
-            Operation::ProcessExpiredSettlements => {
-                self.process_expired_settlements(runtime, state).await
+            state.reputations.insert(&party, reputation)?;
+        }
+        Ok(())
+    }
+    
+    async fn process_refund(
+        &self,
+        state: &mut SettlementState<ContractRuntime<SettlementContract>>,
+        settlement: &Settlement,
+    ) -> Result<(), SettlementError> {
+        // Refund maker if escrowed
+        if settlement.maker_escrow.is_escrowed {
+            let asset = settlement.maker_escrow.held_asset(&settlement.maker_asset);
+            let escrow_key = (settlement.id, settlement.maker, asset.to_string());
+            let escrowed = self.release_escrow(state, &escrow_key).await?;
+            self.credit_balance(state, settlement.maker, asset, escrowed).await?;
+        }
+        
+        // Refund taker if escrowed
+        if settlement.taker_escrow.is_escrowed {
+            let asset = settlement.taker_escrow.held_asset(&settlement.taker_asset);
+            let escrow_key = (settlement.id, settlement.taker, asset.to_string());
+            let escrowed = self.release_escrow(state, &escrow_key).await?;
+            self.credit_balance(state, settlement.taker, asset, escrowed).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Queues `settlement_id` to expire at `expires_at`
+    async fn queue_expiration(
+        &mut self,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        expires_at: Timestamp,
+        settlement_id: u64,
+    ) -> Result<(), SettlementError> {
+        let key = expiration_key(expires_at);
+        let mut queued = state.expirations.get(&key).await?.unwrap_or_default();
+        if let Err(position) = queued.binary_search(&settlement_id) {
+            queued.insert(position, settlement_id);
+        }
+        state.expirations.insert(&key, queued)?;
+        Ok(())
+    }
+    
+    /// Expires up to `limit` queued settlements due by now, in non-decreasing `expires_at` order,
+    /// after moving up to `MAX_EXPIRY_MIGRATION` legacy queued expirations over. Returns how many
+    /// were expired and when the earliest expiration left is due.
+    async fn process_expired_settlements(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        limit: u32,
+    ) -> Result<(u32, Option<Timestamp>), SettlementError> {
+        let now = runtime.system_time();
+        for _ in 0..MAX_EXPIRY_MIGRATION {
+            let Some((expires_at, settlement_id)) = state.expiration_queue.front().await? else {
+                break;
+            };
+            state.expiration_queue.pop_front();
+            self.queue_expiration(state, expires_at, settlement_id).await?;
+        }
+        
+        // Each due deadline holds at least one settlement, so `limit` of them are enough
+        let mut deadlines = Vec::new();
+        let mut next_expires_at = None;
+        state.expirations.for_each_index_while(|key| {
+            let deadline = u64::from_be_bytes(key);
+            if deadline > now.micros() || deadlines.len() == limit as usize {
+                next_expires_at = Some(Timestamp::from(deadline));
+                return Ok(false);
             }
-            
-            Operation::ConfigureBridge { chain_id, config } => {
-                self.configure_bridge(state, chain_id, config).await
+            deadlines.push(deadline);
+            Ok(true)
+        }).await?;
+        
+        let mut examined = 0;
+        let mut expired = 0;
+        for deadline in deadlines {
+            if examined == limit {
+                next_expires_at = Some(Timestamp::from(deadline));
+                break;
             }
-            
-            Operation::DisableBridge { chain_id } => {
-                self.disable_bridge(state, chain_id).await
+            let key = deadline.to_be_bytes();
+            let mut queued = state.expirations.get(&key).await?.unwrap_or_default();
+            let mut done = 0;
+            for settlement_id in &queued {
+                if examined == limit {
+                    break;
+                }
+                examined += 1;
+                done += 1;
+                let Some(mut settlement) = state.settlements.get(settlement_id).await? else {
+                    continue;
+                };
+                // Requeued under a later deadline, or already finished
+                if settlement.expires_at.micros() != deadline || matches!(
+                    settlement.status,
+                    SettlementStatus::Completed | SettlementStatus::Refunded
+                        | SettlementStatus::Cancelled | SettlementStatus::Executing
+                ) {
+                    continue;
+                }
+                
+                self.process_refund(state, &settlement).await?;
+                
+                // A refund claim may already have expired it and recorded the outcome
+                let newly_expired = settlement.status != SettlementStatus::Expired;
+                settlement.status = SettlementStatus::Expired;
+                if newly_expired {
+                    self.record_outcome(state, &settlement, None).await?;
+                }
+                state.settlements.insert(settlement_id, settlement)?;
+                state.active_settlements.remove(settlement_id)?;
+                
+                let mut stats = state.stats.get();
+                stats.failed_settlements += 1;
+                state.stats.set(stats);
+                
+                expired += 1;
             }
             
-            Operation::ProcessBridgeDeposit {
-                chain_id, tx_hash, user, asset, amount, confirmations,
-            } => {
-                self.process_bridge_deposit(
-                    runtime, state, chain_id, tx_hash, user, asset, amount, confirmations
-                ).await
+            queued.drain(..done);
+            if queued.is_empty() {
+                state.expirations.remove(&key)?;
+            } else {
+                state.expirations.insert(&key, queued)?;
+                next_expires_at = Some(Timestamp::from(deadline));
+                break;
             }
-            
-            Operation::InitiateBridgeWithdrawal {
-                chain_id, asset, amount, destination_address,
-            } => {
-                self.initiate_bridge_withdrawal(
-                    runtime, state, chain_id, asset, amount, destination_address
-                ).await
+        }
+        
+        // Legacy expirations not moved over yet may already be due
+        if state.expiration_queue.count() > 0 {
+            next_expires_at = Some(next_expires_at.map_or(now, |next| next.min(now)));
+        }
+        
+        if expired > 0 {
+            state.events.push_back(SettlementEvent::ExpiredSettlementsProcessed {
+                expired,
+                next_expires_at,
+                timestamp: now,
+            });
+        }
+        
+        Ok((expired, next_expires_at))
+    }
+    
+    async fn execute_window(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        let mut boundaries = state.window_queue.indices().await?;
+        boundaries.sort_unstable();
+        
+        let mut executed = 0;
+        for boundary in boundaries.into_iter().take_while(|boundary| *boundary <= now.micros()) {
+            let mut queued = state.window_queue.get(&boundary).await?.unwrap_or_default();
+            let mut done = 0;
+            for settlement_id in &queued {
+                if executed == MAX_WINDOW_EXECUTIONS {
+                    break;
+                }
+                done += 1;
+                // Cancelled or expired since it was queued: expiry processing refunds it
+                let Some(settlement) = state.settlements.get(settlement_id).await? else {
+                    continue;
+                };
+                if settlement.status != SettlementStatus::FullyEscrowed || now > settlement.expires_at {
+                    continue;
+                }
+                self.execute_settlement(runtime, state, *settlement_id).await?;
+                executed += 1;
             }
             
-            Operation::CompleteBridgeWithdrawal {
-                transfer_id, tx_hash, success,
-            } => {
-                self.complete_bridge_withdrawal(runtime, state, transfer_id, tx_hash, success).await
+            queued.drain(..done);
+            if queued.is_empty() {
+                state.window_queue.remove(&boundary)?;
+            } else {
+                state.window_queue.insert(&boundary, queued)?;
+                break;
             }
+        }
+        
+        if executed > 0 {
+            tracing::info!("Executed {} windowed settlements", executed);
+        }
+        
+        Ok(())
+    }
+    
+    async fn configure_bridge(
+        &mut self,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        chain_id: String,
+        config: BridgeConfig,
+    ) -> Result<(), SettlementError> {
+        // TODO: Add admin check
+        state.bridge_configs.insert(&chain_id, config.clone())?;
+        
+        tracing::info!("Bridge configured: chain_id={}, address={}", chain_id, config.bridge_address);
+        
+        Ok(())
+    }
+    
+    async fn disable_bridge(
+        &mut self,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        chain_id: String,
+    ) -> Result<(), SettlementError> {
+        let mut config = state.bridge_configs.get(&chain_id).await?
+            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
+        
+        config.is_active = false;
+        state.bridge_configs.insert(&chain_id, config)?;
+        
+        tracing::info!("Bridge disabled: chain_id={}", chain_id);
+        
+        Ok(())
+    }
+    
+    async fn process_bridge_deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        chain_id: String,
+        tx_hash: String,
+        user: Account,
+        asset: String,
+        amount: Amount,
+        confirmations: u64,
+    ) -> Result<(), SettlementError> {
+        let now = runtime.system_time();
+        
+        // Verify bridge configuration
+        let config = state.bridge_configs.get(&chain_id).await?
+            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
+        
+        if !config.is_active {
+            return Err(SettlementError::BridgeDisabled { chain_id });
+        }
+        
+        if !config.supported_assets.contains(&asset) {
+            return Err(SettlementError::AssetNotSupported { asset });
+        }
+        
+        if amount < config.min_amount {
+            return Err(SettlementError::BelowMinimum { amount, minimum: config.min_amount });
+        }
+        
+        if amount > config.max_amount {
+            return Err(SettlementError::AboveMaximum { amount, maximum: config.max_amount });
+        }
+        
+        // Check if already processed
+        if state.pending_deposits.get(&tx_hash).await?.is_some() {
+            return Err(SettlementError::BridgeError { 
+                reason: "Deposit already processed".to_string() 
+            });
+        }
+        
+        // Check confirmations
+        let status = if confirmations >= config.confirmation_blocks {
+            BridgeTransferStatus::Completed
+        } else {
+            BridgeTransferStatus::Confirming
+        };
+        
+        // Create transfer record
+        let transfer_id = state.next_transfer_id.get();
+        let transfer = BridgeTransfer {
+            id: transfer_id,
+            chain_id: chain_id.clone(),
+            user,
+            asset: asset.clone(),
+            amount,
+            direction: BridgeDirection::Deposit,
+            status,
+            tx_hash: Some(tx_hash.clone()),
+            destination_address: None,
+            created_at: now,
+            completed_at: if status == BridgeTransferStatus::Completed { Some(now) } else { None },
+            confirmations,
+            settlement_ref: None,
+        };
+        
+        state.bridge_transfers.insert(&transfer_id, transfer)?;
+        state.pending_deposits.insert(&tx_hash, transfer_id)?;
+        state.next_transfer_id.set(transfer_id + 1);
+        
+        // If confirmed, credit user balance
+        if status == BridgeTransferStatus::Completed {
+            // Deduct bridge fee
+            let fee = math::amount_fee(amount, config.fee_rate_bps)?;
+            let credited = math::checked_sub(amount, fee)?;
             
-            Operation::Deposit { asset, amount } => {
-                self.deposit(runtime, state, asset, amount).await
-            }
+            self.credit_balance(state, user, &asset, credited).await?;
             
-            Operation::Withdraw { asset, amount } => {
-                self.withdraw(runtime, state, asset, amount).await
+            // Update stats
+            let mut stats = state.stats.get();
+            stats.total_bridge_deposits = stats.total_bridge_deposits + credited;
+            state.stats.set(stats);
+        }
+        
+        tracing::info!(
+            "Bridge deposit processed: chain={}, user={:?}, asset={}, amount={}, status={:?}",
+            chain_id, user, asset, amount, status
+        );
+        
+        Ok(())
+    }
+    
+    async fn initiate_bridge_withdrawal(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        chain_id: String,
+        asset: String,
+        amount: Amount,
+        destination_address: String,
+    ) -> Result<u64, SettlementError> {
+        let user = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let now = runtime.system_time();
+        
+        // Verify bridge configuration
+        let config = state.bridge_configs.get(&chain_id).await?
+            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
+        
+        if !config.is_active {
+            return Err(SettlementError::BridgeDisabled { chain_id });
+        }
+        
+        if !config.supported_assets.contains(&asset) {
+            return Err(SettlementError::AssetNotSupported { asset });
+        }
+        
+        if amount < config.min_amount {
+            return Err(SettlementError::BelowMinimum { amount, minimum: config.min_amount });
+        }
+        
+        if amount > config.max_amount {
+            return Err(SettlementError::AboveMaximum { amount, maximum: config.max_amount });
+        }
+        
+        // A withdrawal the bridge cannot carry now would only wait out its expiry
+        if let Some(corridor) = config.corridor {
+            let availability = self.corridor_availability(runtime, state, corridor).await?;
+            if availability != CorridorAvailability::Open {
+                return Err(SettlementError::CorridorUnavailable { corridor, availability });
             }
         }
+        
+        // Deduct balance
+        self.debit_balance(state, user, &asset, amount).await?;
+        
+        // Create transfer record
+        let transfer_id = state.next_transfer_id.get();
+        let transfer = BridgeTransfer {
+            id: transfer_id,
+            chain_id: chain_id.clone(),
+            user,
+            asset: asset.clone(),
+            amount,
+            direction: BridgeDirection::Withdrawal,
+            status: BridgeTransferStatus::Pending,
+            tx_hash: None,
+            destination_address: Some(destination_address.clone()),
+            created_at: now,
+            completed_at: None,
+            confirmations: 0,
+            settlement_ref: None,
+        };
+        
+        state.bridge_transfers.insert(&transfer_id, transfer)?;
+        state.pending_withdrawals.push_back(transfer_id);
+        state.next_transfer_id.set(transfer_id + 1);
+        
+        // Add to user transfers
+        let mut user_transfers = state.user_transfers.get(&user).await?.unwrap_or_default();
+        user_transfers.push(transfer_id);
+        state.user_transfers.insert(&user, user_transfers)?;
+        
+        tracing::info!(
+            "Bridge withdrawal initiated: chain={}, user={:?}, asset={}, amount={}, destination={}",
+            chain_id, user, asset, amount, destination_address
+        );
+        
+        Ok(transfer_id)
     }
-
-    async fn execute_message(
+    
+    /// Availability of `corridor` according to the corridor source, recording changes for
+    /// `Query::GetCorridorStatus`; open while no source is set. An unexpected answer counts as paused.
+    async fn corridor_availability(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
-        state: &mut Self::State,
-        message: Message,
-    ) {
-        match message {
-            Message::SettlementRequest {
-                trade_id, maker, taker, maker_asset, taker_asset,
-                maker_amount, taker_amount, timeout_seconds,
-            } => {
-                let maker_chain = runtime.chain_id();
-                let taker_chain = runtime.chain_id();
-                
-                if let Err(e) = self.initiate_settlement(
-                    runtime, state, trade_id, maker, taker,
-                    maker_asset, taker_asset, maker_amount, taker_amount,
-                    maker_chain, taker_chain, timeout_seconds,
-                ).await {
-                    tracing::error!("Failed to initiate settlement: {}", e);
-                }
-            }
-            
-            Message::EscrowConfirmation { settlement_id, party, confirmed, amount } => {
-                tracing::info!(
-                    "Escrow confirmation: settlement={}, party={:?}, confirmed={}, amount={}",
-                    settlement_id, party, confirmed, amount
-                );
-            }
-            
-            Message::SettlementComplete { settlement_id, success, failure_reason } => {
-                tracing::info!(
-                    "Settlement complete: id={}, success={}, reason={:?}",
-                    settlement_id, success, failure_reason
-                );
-            }
-            
-            Message::BridgeEvent { chain_id, event_type, transfer_id, data } => {
-                tracing::info!(
-                    "Bridge event: chain={}, type={:?}, transfer={}, data_len={}",
-                    chain_id, event_type, transfer_id, data.len()
-                );
-            }
-            
-            Message::RefundProcessed { settlement_id, party, amount, asset } => {
-                tracing::info!(
-                    "Refund processed: settlement={}, party={:?}, amount={}, asset={}",
-                    settlement_id, party, amount, asset
-                );
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        corridor: u64,
+    ) -> Result<CorridorAvailability, SettlementError> {
+        let Some(bridge) = *state.corridor_source.get() else {
+            return Ok(CorridorAvailability::Open);
+        };
+        let operation = BridgeOperation::CorridorAvailabilityOf { chain: ExternalChain::from_chain_id(corridor) };
+        let availability = match runtime.call_application(true, bridge.with_abi::<BridgeAbi>(), &operation) {
+            BridgeResponse::CorridorAvailability(availability) => availability,
+            _ => CorridorAvailability::Paused,
+        };
+        
+        let recorded = state.corridor_statuses.get(&corridor).await?
+            .map_or(CorridorAvailability::Open, |status| status.availability);
+        if availability != recorded {
+            let now = runtime.system_time();
+            if availability == CorridorAvailability::Open {
+                state.corridor_statuses.remove(&corridor)?;
+            } else {
+                state.corridor_statuses.insert(&corridor, CorridorStatus { availability, changed_at: now })?;
             }
+            state.events.push_back(SettlementEvent::CorridorStatusChanged {
+                corridor,
+                availability,
+                timestamp: now,
+            });
         }
+        Ok(availability)
     }
-}
-
-impl SettlementContract {
-    async fn initiate_settlement(
+    
+    async fn complete_bridge_withdrawal(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        trade_id: u64,
-        maker: Account,
-        taker: Account,
-        maker_asset: String,
-        taker_asset: String,
-        maker_amount: Amount,
-        taker_amount: Amount,
-        maker_chain: ChainId,
-        taker_chain: ChainId,
-        timeout_seconds: u64,
+        transfer_id: u64,
+        tx_hash: String,
+        success: bool,
     ) -> Result<(), SettlementError> {
-        let settlement_id = state.next_settlement_id.get();
         let now = runtime.system_time();
-        let expires_at = now + std::time::Duration::from_secs(timeout_seconds);
-        
-        let settlement = Settlement {
-            id: settlement_id,
-            trade_id,
-            maker,
-            taker,
-            maker_asset: maker_asset.clone(),
-            taker_asset: taker_asset.clone(),
-            maker_amount,
-            taker_amount,
-            maker_chain,
-            taker_chain,
-            maker_escrow: EscrowState::default(),
-            taker_escrow: EscrowState::default(),
-            status: SettlementStatus::Pending,
-            created_at: now,
-            expires_at,
-            completed_at: None,
-            failure_reason: None,
-            retry_count: 0,
-        };
         
-        // Store settlement
-        state.settlements.insert(&settlement_id, settlement.clone())?;
-        state.active_settlements.insert(&settlement_id, ())?;
+        let mut transfer = state.bridge_transfers.get(&transfer_id).await?
+            .ok_or(SettlementError::TransferNotFound { transfer_id })?;
         
-        // Add to user settlements
-        for user in [maker, taker] {
-            let mut user_settlements = state.user_settlements.get(&user).await?.unwrap_or_default();
-            user_settlements.push(settlement_id);
-            state.user_settlements.insert(&user, user_settlements)?;
+        if success {
+            transfer.status = BridgeTransferStatus::Completed;
+            transfer.tx_hash = Some(tx_hash);
+            transfer.completed_at = Some(now);
+            
+            // Update stats
+            let mut stats = state.stats.get();
+            stats.total_bridge_withdrawals = stats.total_bridge_withdrawals + transfer.amount;
+            state.stats.set(stats);
+        } else {
+            transfer.status = BridgeTransferStatus::Failed;
+            
+            // Refund user
+            self.credit_balance(state, transfer.user, &transfer.asset, transfer.amount).await?;
         }
         
-        // Add to expiration queue
-        state.expiration_queue.push_back((expires_at, settlement_id));
-        
-        // Update stats
-        let mut stats = state.stats.get();
-        stats.total_settlements += 1;
-        state.stats.set(stats);
+        // A settlement leg follows its transfer through this chain's own message
+        if transfer.settlement_ref.is_some() {
+            let event_type = if success {
+                BridgeEventType::WithdrawalCompleted
+            } else {
+                BridgeEventType::WithdrawalFailed
+            };
+            let chain_id = runtime.chain_id();
+            runtime
+                .prepare_message(MessageEnvelope::seal(&Message::BridgeEvent {
+                    chain_id: transfer.chain_id.clone(),
+                    event_type,
+                    transfer_id,
+                    data: Vec::new(),
+                }))
+                .send_to(chain_id);
+        }
         
-        // Increment settlement ID
-        state.next_settlement_id.set(settlement_id + 1);
+        state.bridge_transfers.insert(&transfer_id, transfer)?;
         
         tracing::info!(
-            "Settlement initiated: id={}, trade_id={}, maker={:?}, taker={:?}",
-            settlement_id, trade_id, maker, taker
+            "Bridge withdrawal completed: transfer_id={}, success={}",
+            transfer_id, success
         );
         
         Ok(())
     }
     
-    async fn confirm_escrow(
+    /// Withdraws what the caller was paid by the counterparty's leg of a completed settlement,
+    /// net of its fee, and links the withdrawal to that leg. A leg whose withdrawal failed may
+    /// be withdrawn again.
+    async fn withdraw_settlement_proceeds(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
         settlement_id: u64,
-    ) -> Result<(), SettlementError> {
+        chain_id: String,
+        destination_address: String,
+    ) -> Result<u64, SettlementError> {
         let caller = runtime.authenticated_signer()
             .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        let now = runtime.system_time();
-        
         let mut settlement = state.settlements.get(&settlement_id).await?
             .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
-        
-        // Check expiration
-        if now > settlement.expires_at {
-            return Err(SettlementError::SettlementExpired { expired_at: settlement.expires_at });
+        if settlement.status != SettlementStatus::Completed {
+            return Err(SettlementError::InvalidStatus {
+                expected: SettlementStatus::Completed,
+                actual: settlement.status,
+            });
         }
         
-        // Determine which party is escrowing
-        let (is_maker, asset, amount) = if caller == settlement.maker {
-            if settlement.maker_escrow.is_escrowed {
-                return Err(SettlementError::AlreadyEscrowed);
-            }
-            (true, settlement.maker_asset.clone(), settlement.maker_amount)
-        } else if caller == settlement.taker {
-            if settlement.taker_escrow.is_escrowed {
-                return Err(SettlementError::AlreadyEscrowed);
-            }
-            (false, settlement.taker_asset.clone(), settlement.taker_amount)
+        let [maker_leg, taker_leg] = settlement.legs()?;
+        let ((payer, _, asset, fee), escrow) = if caller == settlement.taker {
+            (maker_leg, &settlement.maker_escrow)
+        } else if caller == settlement.maker {
+            (taker_leg, &settlement.taker_escrow)
         } else {
-            return Err(SettlementError::Unauthorized { 
-                reason: "Caller is not a party to this settlement".to_string() 
+            return Err(SettlementError::Unauthorized {
+                reason: "Caller is not a party to this settlement".to_string(),
             });
         };
-        
-        // Check balance
-        let balance_key = (caller, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-        
-        if current_balance < amount {
-            return Err(SettlementError::InsufficientBalance {
-                required: amount,
-                available: current_balance,
+        let amount = math::checked_sub(escrow.amount, fee)?;
+        if amount == Amount::ZERO {
+            return Err(SettlementError::InvalidOneSidedSettlement {
+                reason: "Nothing to withdraw from this leg".to_string(),
             });
         }
         
-        // Lock balance (move to escrow)
-        let new_balance = current_balance - amount;
-        state.balances.insert(&balance_key, new_balance)?;
+        if let Some(linked_id) = settlement.bridge_transfer_id(payer) {
+            let linked = state.bridge_transfers.get(&linked_id).await?
+                .ok_or(SettlementError::TransferNotFound { transfer_id: linked_id })?;
+            if linked.direction == BridgeDirection::Withdrawal && linked.status != BridgeTransferStatus::Failed {
+                return Err(SettlementError::InvalidBridgeLink {
+                    transfer_id: linked_id,
+                    reason: "leg already withdrawn".to_string(),
+                });
+            }
+        }
         
-        // Record escrowed amount
-        let escrow_key = (settlement_id, caller, asset.clone());
-        state.escrowed_balances.insert(&escrow_key, amount)?;
+        let transfer_id = self.initiate_bridge_withdrawal(
+            runtime, state, chain_id, asset, amount, destination_address
+        ).await?;
+        let mut transfer = state.bridge_transfers.get(&transfer_id).await?
+            .ok_or(SettlementError::TransferNotFound { transfer_id })?;
+        transfer.settlement_ref = Some(SettlementRef { settlement_id, payer });
+        settlement.link_bridge_transfer(payer, transfer_id, transfer.status);
+        state.bridge_transfers.insert(&transfer_id, transfer)?;
+        state.settlements.insert(&settlement_id, settlement)?;
+        state.events.push_back(SettlementEvent::BridgeLegLinked {
+            settlement_id,
+            payer,
+            transfer_id,
+            direction: BridgeDirection::Withdrawal,
+            timestamp: runtime.system_time(),
+        });
         
-        // Update escrow state
-        let escrow_state = EscrowState {
-            is_escrowed: true,
-            amount,
-            asset: asset.clone(),
-            escrowed_at: Some(now),
-            tx_hash: None,
+        Ok(transfer_id)
+    }
+    
+    /// Copies a linked transfer's status to the settlement leg it carries, unless the leg has
+    /// since been linked to another transfer
+    async fn update_bridge_leg(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        transfer_id: u64,
+    ) -> Result<(), SettlementError> {
+        let transfer = state.bridge_transfers.get(&transfer_id).await?
+            .ok_or(SettlementError::TransferNotFound { transfer_id })?;
+        let Some(SettlementRef { settlement_id, payer }) = transfer.settlement_ref else {
+            return Ok(());
         };
-        
-        if is_maker {
-            settlement.maker_escrow = escrow_state;
-            settlement.status = if settlement.taker_escrow.is_escrowed {
-                SettlementStatus::FullyEscrowed
-            } else {
-                SettlementStatus::MakerEscrowed
-            };
-        } else {
-            settlement.taker_escrow = escrow_state;
-            settlement.status = if settlement.maker_escrow.is_escrowed {
-                SettlementStatus::FullyEscrowed
-            } else {
-                SettlementStatus::TakerEscrowed
-            };
+        let mut settlement = state.settlements.get(&settlement_id).await?
+            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
+        if settlement.bridge_transfer_id(payer) != Some(transfer_id) {
+            return Ok(());
         }
         
-        state.settlements.insert(&settlement_id, settlement.clone())?;
+        settlement.link_bridge_transfer(payer, transfer_id, transfer.status);
+        state.settlements.insert(&settlement_id, settlement)?;
+        state.events.push_back(SettlementEvent::BridgeLegUpdated {
+            settlement_id,
+            payer,
+            transfer_id,
+            status: transfer.status,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    async fn deposit(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), SettlementError> {
+        let user = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        
+        self.credit_balance(state, user, &asset, amount).await?;
+        
+        tracing::info!("Deposit: user={:?}, asset={}, amount={}", user, asset, amount);
+        
+        Ok(())
+    }
+    
+    async fn withdraw(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        asset: String,
+        amount: Amount,
+    ) -> Result<(), SettlementError> {
+        let user = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
         
-        tracing::info!(
-            "Escrow confirmed: settlement_id={}, party={:?}, asset={}, amount={}",
-            settlement_id, caller, asset, amount
-        );
+        self.debit_balance(state, user, &asset, amount).await?;
         
-        // Auto-execute if fully escrowed
-        if settlement.status == SettlementStatus::FullyEscrowed {
-            self.execute_settlement(runtime, state, settlement_id).await?;
-        }
+        tracing::info!("Withdrawal: user={:?}, asset={}, amount={}", user, asset, amount);
         
         Ok(())
     }
     
-    async fn execute_settlement(
+    /// Sums the escrow records of all active settlements per asset and compares them with
+    /// `total_escrowed`. The report is stored even when drift is found.
+    async fn audit_escrow(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        settlement_id: u64,
     ) -> Result<(), SettlementError> {
         let now = runtime.system_time();
-        
-        let mut settlement = state.settlements.get(&settlement_id).await?
-            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
-        
-        // Verify status
-        if settlement.status != SettlementStatus::FullyEscrowed {
-            return Err(SettlementError::InvalidStatus {
-                expected: SettlementStatus::FullyEscrowed,
-                actual: settlement.status,
-            });
+        let flagged_escrows = self.sweep_orphaned_escrow(state, now).await?;
+        let mut computed: BTreeMap<String, Amount> = BTreeMap::new();
+        let mut stuck_executions = Vec::new();
+        let active = state.active_settlements.indices().await?;
+        for settlement_id in &active {
+            let Some(settlement) = state.settlements.get(settlement_id).await? else {
+                continue;
+            };
+            if settlement.status == SettlementStatus::Executing {
+                let started_at = settlement.execution_started_at.unwrap_or(settlement.created_at);
+                if now.micros().saturating_sub(started_at.micros()) / 1_000_000 >= STUCK_EXECUTION_SECONDS {
+                    stuck_executions.push(settlement.id);
+                }
+            }
+            for (party, asset) in [
+                (settlement.maker, settlement.maker_escrow.held_asset(&settlement.maker_asset)),
+                (settlement.taker, settlement.taker_escrow.held_asset(&settlement.taker_asset)),
+            ] {
+                let escrow_key = (settlement.id, party, asset.to_string());
+                if let Some(escrowed) = state.escrowed_balances.get(&escrow_key).await? {
+                    let total = computed.entry(asset.to_string()).or_default();
+                    *total = math::checked_add(*total, escrowed)?;
+                }
+            }
         }
         
-        // Check expiration
-        if now > settlement.expires_at {
-            settlement.status = SettlementStatus::Expired;
-            state.settlements.insert(&settlement_id, settlement)?;
-            return Err(SettlementError::SettlementExpired { expired_at: settlement.expires_at });
+        for asset in state.total_escrowed.indices().await? {
+            computed.entry(asset).or_default();
+        }
+        let mut drift = Vec::new();
+        for (asset, computed) in computed {
+            let recorded = state.total_escrowed.get(&asset).await?.unwrap_or_default();
+            if recorded != computed {
+                drift.push(EscrowDrift { asset, recorded, computed });
+            }
         }
         
-        settlement.status = SettlementStatus::Executing;
-        state.settlements.insert(&settlement_id, settlement.clone())?;
-        
-        // Execute the swap
-        // Transfer maker asset from escrow to taker
-        let maker_escrow_key = (settlement_id, settlement.maker, settlement.maker_asset.clone());
-        let maker_escrowed = state.escrowed_balances.get(&maker_escrow_key).await?.unwrap_or_default();
-        
-        let taker_balance_key = (settlement.taker, settlement.maker_asset.clone());
-        let taker_balance = state.balances.get(&taker_balance_key).await?.unwrap_or_default();
-        
-        state.balances.insert(&taker_balance_key, taker_balance + maker_escrowed)?;
-        state.escrowed_balances.remove(&maker_escrow_key)?;
-        
-        // Transfer taker asset from escrow to maker
-        let taker_escrow_key = (settlement_id, settlement.taker, settlement.taker_asset.clone());
-        let taker_escrowed = state.escrowed_balances.get(&taker_escrow_key).await?.unwrap_or_default();
-        
-        let maker_balance_key = (settlement.maker, settlement.taker_asset.clone());
-        let maker_balance = state.balances.get(&maker_balance_key).await?.unwrap_or_default();
-        
-        state.balances.insert(&maker_balance_key, maker_balance + taker_escrowed)?;
-        state.escrowed_balances.remove(&taker_escrow_key)?;
-        
-        // Update settlement status
-        settlement.status = SettlementStatus::Completed;
-        settlement.completed_at = Some(now);
-        state.settlements.insert(&settlement_id, settlement.clone())?;
-        
-        // Remove from active settlements
-        state.active_settlements.remove(&settlement_id)?;
-        
-        // Update stats
-        let mut stats = state.stats.get();
-        stats.completed_settlements += 1;
-        stats.total_volume = stats.total_volume + settlement.maker_amount + settlement.taker_amount;
-        state.stats.set(stats);
-        
-        tracing::info!(
-            "Settlement executed: id={}, maker={:?}, taker={:?}",
-            settlement_id, settlement.maker, settlement.taker
-        );
-        
+        if !drift.is_empty() {
+            tracing::error!("Escrow totals drifted: {:?}", drift);
+        }
+        if !stuck_executions.is_empty() {
+            tracing::error!("Settlements stuck executing: {:?}", stuck_executions);
+        }
+        state.last_escrow_audit.set(Some(EscrowAuditReport {
+            audited_at: now,
+            active_settlements: active.len() as u64,
+            drift,
+            stuck_executions,
+            flagged_escrows,
+        }));
         Ok(())
     }
     
-    async fn cancel_settlement(
+    /// Resolves escrow records whose settlement left `active_settlements`: they are returned to
+    /// their owner when the settlement never paid out (up to `MAX_ORPHAN_RETURNS` per run) and
+    /// flagged otherwise. Only records not flagged by the previous audit raise an event.
+    async fn sweep_orphaned_escrow(
+        &mut self,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        now: Timestamp,
+    ) -> Result<Vec<FlaggedEscrow>, SettlementError> {
+        let previously_flagged = state.last_escrow_audit.get().as_ref()
+            .map(|audit| audit.flagged_escrows.clone())
+            .unwrap_or_default();
+        let mut flagged = Vec::new();
+        let mut returned = 0;
+        for escrow_key in state.escrowed_balances.indices().await? {
+            let (settlement_id, party, asset) = escrow_key.clone();
+            if state.active_settlements.contains_key(&settlement_id).await? {
+                continue;
+            }
+            let settlement = state.settlements.get(&settlement_id).await?;
+            let status = settlement.as_ref().map(|settlement| settlement.status);
+            match OrphanedEscrow::for_status(status) {
+                Some(OrphanedEscrow::Return) if returned < MAX_ORPHAN_RETURNS => {
+                    let Some(mut settlement) = settlement else {
+                        continue;
+                    };
+                    let status = settlement.status;
+                    let amount = self.release_escrow(state, &escrow_key).await?;
+                    self.credit_balance(state, party, &asset, amount).await?;
+                    if party == settlement.maker && settlement.maker_escrow.held_asset(&settlement.maker_asset) == asset {
+                        settlement.maker_escrow.is_escrowed = false;
+                    }
+                    if party == settlement.taker && settlement.taker_escrow.held_asset(&settlement.taker_asset) == asset {
+                        settlement.taker_escrow.is_escrowed = false;
+                    }
+                    state.settlements.insert(&settlement_id, settlement)?;
+                    tracing::warn!("Returned orphaned escrow of settlement {} to its owner", settlement_id);
+                    state.events.push_back(SettlementEvent::OrphanedEscrowReturned {
+                        settlement_id,
+                        party,
+                        asset,
+                        amount,
+                        status,
+                        timestamp: now,
+                    });
+                    returned += 1;
+                }
+                Some(OrphanedEscrow::Review) => {
+                    let amount = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
+                    let escrow = FlaggedEscrow { settlement_id, party, asset, amount, status };
+                    if !previously_flagged.contains(&escrow) {
+                        tracing::error!("Escrow left on settlement {} after it paid out", settlement_id);
+                        state.events.push_back(SettlementEvent::OrphanedEscrowFlagged {
+                            escrow: escrow.clone(),
+                            timestamp: now,
+                        });
+                    }
+                    flagged.push(escrow);
+                }
+                _ => {}
+            }
+        }
+        Ok(flagged)
+    }
+    
+    /// Finishes a settlement left `Executing`. Legs whose escrow record is gone were paid; the
+    /// rest are paid too when their escrow covers the fee, otherwise the paid legs are debited
+    /// back from the payee and fee recipient and every escrow returns to its payer.
+    async fn resolve_stuck_execution(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
         settlement_id: u64,
-        reason: String,
     ) -> Result<(), SettlementError> {
-        let caller = runtime.authenticated_signer()
-            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        
+        let admin = self.require_admin(runtime, state)?;
+        let now = runtime.system_time();
         let mut settlement = state.settlements.get(&settlement_id).await?
             .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
-        
-        // Only participants can cancel
-        if caller != settlement.maker && caller != settlement.taker {
-            return Err(SettlementError::Unauthorized { 
-                reason: "Only participants can cancel".to_string() 
+        if settlement.status != SettlementStatus::Executing {
+            return Err(SettlementError::InvalidStatus {
+                expected: SettlementStatus::Executing,
+                actual: settlement.status,
             });
         }
         
-        // Can only cancel if not fully escrowed or completed
-        match settlement.status {
-            SettlementStatus::Pending |
-            SettlementStatus::MakerEscrowed |
-            SettlementStatus::TakerEscrowed => {
-                // Allowed to cancel
+        let mut paid = Vec::new();
+        let mut unpaid = Vec::new();
+        let amounts = [settlement.maker_escrow.amount, settlement.taker_escrow.amount];
+        for (leg, amount) in settlement.legs()?.into_iter().zip(amounts) {
+            let escrow_key = (settlement_id, leg.0, leg.2.clone());
+            match state.escrowed_balances.get(&escrow_key).await? {
+                Some(escrowed) => unpaid.push((leg, escrowed)),
+                None => paid.push((leg, amount)),
             }
-            SettlementStatus::FullyEscrowed |
-            SettlementStatus::Executing => {
-                return Err(SettlementError::CannotCancel { 
-                    reason: "Settlement is already being executed".to_string() 
-                });
+        }
+        let fees: Vec<_> = unpaid.iter().map(|((_, _, _, fee), escrowed)| (*escrowed, *fee)).collect();
+        // A net transfer pays out in one step, so any escrow left means none of it was paid
+        let net_transfer = settlement.net_transfer();
+        let resolution = match net_transfer {
+            Some(_) => ExecutionResolution::Completed,
+            None => ExecutionResolution::for_unpaid_legs(&fees),
+        };
+        
+        let mut stats = state.stats.get();
+        match resolution {
+            ExecutionResolution::Completed => {
+                match net_transfer {
+                    Some(net) if !unpaid.is_empty() => self.pay_net(state, &settlement, net).await?,
+                    Some(_) => {}
+                    None => {
+                        for ((payer, payee, asset, fee), _) in &unpaid {
+                            self.pay_leg(state, &settlement, *payer, *payee, asset, *fee).await?;
+                        }
+                    }
+                }
+                settlement.status = SettlementStatus::Completed;
+                settlement.completed_at = Some(now);
+                stats.completed_settlements += 1;
+                stats.total_volume = math::checked_add(stats.total_volume, settlement.volume()?)?;
             }
-            _ => {
-                return Err(SettlementError::CannotCancel { 
-                    reason: "Settlement is already finalized".to_string() 
-                });
+            ExecutionResolution::Reversed => {
+                for ((payer, payee, asset, fee), amount) in &paid {
+                    self.debit_balance(state, *payee, asset, math::checked_sub(*amount, *fee)?).await?;
+                    if let Some(fees) = &settlement.fees {
+                        self.debit_balance(state, fees.recipient, asset, *fee).await?;
+                    }
+                    self.credit_balance(state, *payer, asset, *amount).await?;
+                }
+                for ((payer, _, asset, _), _) in &unpaid {
+                    let escrow_key = (settlement_id, *payer, asset.clone());
+                    let escrowed = self.release_escrow(state, &escrow_key).await?;
+                    self.credit_balance(state, *payer, asset, escrowed).await?;
+                }
+                settlement.status = SettlementStatus::Failed;
+                settlement.failure_reason = Some("Interrupted execution reversed".to_string());
+                stats.failed_settlements += 1;
             }
         }
-        
-        // Process refunds for any escrowed amounts
-        self.process_refund(state, &settlement).await?;
-        
-        settlement.status = SettlementStatus::Cancelled;
-        settlement.failure_reason = Some(reason);
-        state.settlements.insert(&settlement_id, settlement)?;
-        
-        // Remove from active settlements
+        state.stats.set(stats);
+        state.settlements.insert(&settlement_id, settlement.clone())?;
+        self.record_outcome(state, &settlement, None).await?;
         state.active_settlements.remove(&settlement_id)?;
+        if settlement.status == SettlementStatus::Completed {
+            state.events.push_back(settlement.completed_event(now));
+        }
+        state.events.push_back(SettlementEvent::StuckExecutionResolved {
+            settlement_id,
+            resolution,
+            resolved_by: admin,
+            timestamp: now,
+        });
         
-        tracing::info!("Settlement cancelled: id={}, by={:?}", settlement_id, caller);
-        
+        tracing::warn!("Stuck execution resolved: id={}, resolution={:?}", settlement_id, resolution);
         Ok(())
     }
     
-    async fn claim_refund(
+    /// Stores the receipt for `client_request_id`, rejecting reuse of the id by the same caller.
+    async fn record_receipt(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        settlement_id: u64,
+        client_request_id: Option<u64>,
+        id: u64,
     ) -> Result<(), SettlementError> {
+        let Some(client_request_id) = client_request_id else {
+            return Ok(());
+        };
         let caller = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "Receipts need an authenticated signer".to_string() })?;
+        let key = (caller, client_request_id);
+        if state.receipts.get(&key).await?.is_some() {
+            return Err(SettlementError::DuplicateClientRequest { client_request_id });
+        }
+        state.receipts.insert(&key, OperationReceipt {
+            client_request_id,
+            id,
+            created_at: runtime.system_time(),
+        })?;
+        Ok(())
+    }
+    
+    /// Whether the pair was exempted from the maximums, in either order
+    async fn is_uncapped_pair(
+        &self,
+        state: &SettlementState<ContractRuntime<Self>>,
+        maker: Account,
+        taker: Account,
+    ) -> Result<bool, SettlementError> {
+        Ok(state.uncapped_pairs.contains_key(&(maker, taker)).await?
+            || state.uncapped_pairs.contains_key(&(taker, maker)).await?)
+    }
+    
+    async fn set_counterparty_policy(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        counterparty: Account,
+        listing: Option<CounterpartyListing>,
+    ) -> Result<(), SettlementError> {
+        let owner = runtime.authenticated_signer()
             .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        let now = runtime.system_time();
-        
-        let mut settlement = state.settlements.get(&settlement_id).await?
-            .ok_or(SettlementError::SettlementNotFound { settlement_id })?;
-        
-        // Only participants can claim refund
-        if caller != settlement.maker && caller != settlement.taker {
-            return Err(SettlementError::Unauthorized { 
-                reason: "Only participants can claim refund".to_string() 
-            });
+        let mut policy = state.counterparty_policies.get(&owner).await?.unwrap_or_default();
+        if !policy.set(counterparty, listing) {
+            return Err(SettlementError::CounterpartyListFull { maximum: MAX_COUNTERPARTY_LIST });
         }
         
-        // Check if refund is allowed
-        let can_refund = match settlement.status {
-            SettlementStatus::Expired |
-            SettlementStatus::Failed |
-            SettlementStatus::Cancelled => true,
-            _ if now > settlement.expires_at => {
-                // Mark as expired
-                settlement.status = SettlementStatus::Expired;
-                state.settlements.insert(&settlement_id, settlement.clone())?;
-                true
+        match listing {
+            Some(listing) => state.counterparty_listings.insert(&(owner, counterparty), listing)?,
+            None => state.counterparty_listings.remove(&(owner, counterparty))?,
+        }
+        if policy.allowlist.is_empty() {
+            state.allowlist_sizes.remove(&owner)?;
+        } else {
+            state.allowlist_sizes.insert(&owner, policy.allowlist.len() as u32)?;
+        }
+        if policy == CounterpartyPolicy::default() {
+            state.counterparty_policies.remove(&owner)?;
+        } else {
+            state.counterparty_policies.insert(&owner, policy)?;
+        }
+        state.events.push_back(SettlementEvent::CounterpartyPolicyChanged {
+            owner,
+            counterparty,
+            listing,
+            timestamp: runtime.system_time(),
+        });
+        Ok(())
+    }
+    
+    /// Refuses the pair if either party denylisted the other, or keeps an allowlist without it;
+    /// at most two lookups per party
+    async fn check_counterparty_policies(
+        &self,
+        state: &SettlementState<ContractRuntime<Self>>,
+        maker: Account,
+        taker: Account,
+    ) -> Result<(), SettlementError> {
+        for (party, counterparty) in [(maker, taker), (taker, maker)] {
+            match state.counterparty_listings.get(&(party, counterparty)).await? {
+                Some(CounterpartyListing::Denied) => {
+                    return Err(SettlementError::CounterpartyDenylisted { party, counterparty });
+                }
+                Some(CounterpartyListing::Allowed) => {}
+                None if state.allowlist_sizes.contains_key(&party).await? => {
+                    return Err(SettlementError::CounterpartyNotAllowlisted { party, counterparty });
+                }
+                None => {}
             }
-            _ => false,
+        }
+        Ok(())
+    }
+    
+    /// Provenance of a settlement created by the operation, call or message being executed
+    fn provenance(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        kind: SettlementOriginKind,
+        client_request_id: Option<u64>,
+    ) -> SettlementProvenance {
+        let message_id = runtime.message_id();
+        let application_id = match kind {
+            SettlementOriginKind::Operation => None,
+            SettlementOriginKind::ApplicationCall | SettlementOriginKind::Message => runtime.authenticated_caller_id(),
         };
-        
-        if !can_refund {
-            return Err(SettlementError::CannotCancel { 
-                reason: "Refund not available for this settlement status".to_string() 
-            });
+        SettlementProvenance {
+            kind,
+            origin: SettlementOrigin {
+                chain_id: message_id.map_or_else(|| runtime.chain_id(), |message_id| message_id.chain_id),
+                application_id,
+            },
+            signer: runtime.authenticated_signer(),
+            message_id,
+            block_height: runtime.block_height(),
+            client_request_id,
+        }
+    }
+    
+    /// Provenance of a settlement requested for `market` by a message delivered with `context`
+    fn message_provenance(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        context: MessageContext,
+        market: Option<ApplicationId>,
+    ) -> SettlementProvenance {
+        SettlementProvenance {
+            kind: SettlementOriginKind::Message,
+            origin: SettlementOrigin {
+                chain_id: context.origin().unwrap_or_else(|| runtime.chain_id()),
+                application_id: market,
+            },
+            signer: context.signer,
+            message_id: context.message_id,
+            block_height: runtime.block_height(),
+            client_request_id: None,
+        }
+    }
+    
+    /// Initiates a verified settlement request unless its origin is over the throttle or already
+    /// has requests waiting, in which case it joins the origin's deferred requests.
+    async fn admit_settlement_request(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        request: DeferredSettlementRequest,
+    ) -> Result<(), SettlementError> {
+        let origin = request.provenance.origin;
+        let queue = state.deferred_queues.get(&origin).await?;
+        // Requests never overtake the ones already waiting
+        if queue.is_none() && self.take_throttle_slot(runtime, state, origin).await? {
+            return self.initiate_request(runtime, state, request).await.map(|_| ());
         }
         
-        // Process refund for the caller
-        let (asset, escrow_key) = if caller == settlement.maker && settlement.maker_escrow.is_escrowed {
-            (settlement.maker_asset.clone(), (settlement_id, settlement.maker, settlement.maker_asset.clone()))
-        } else if caller == settlement.taker && settlement.taker_escrow.is_escrowed {
-            (settlement.taker_asset.clone(), (settlement_id, settlement.taker, settlement.taker_asset.clone()))
-        } else {
-            return Err(SettlementError::InsufficientBalance { 
-                required: Amount::ZERO, 
-                available: Amount::ZERO 
+        let mut queue = queue.unwrap_or_default();
+        if queue.depth() == 0 {
+            state.events.push_back(SettlementEvent::OriginThrottled {
+                origin,
+                trade_id: request.trade_id,
+                timestamp: runtime.system_time(),
             });
+        }
+        state.deferred_requests.insert(&(origin, queue.tail), request)?;
+        queue.tail += 1;
+        state.deferred_queues.insert(&origin, queue)?;
+        Ok(())
+    }
+    
+    /// Counts a settlement initiated by `origin` in its throttle window; false when the window
+    /// is full. Always true without a throttle.
+    async fn take_throttle_slot(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        origin: SettlementOrigin,
+    ) -> Result<bool, SettlementError> {
+        let Some(throttle) = state.settlement_throttle.get() else {
+            return Ok(true);
         };
-        
-        let escrowed = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
-        if escrowed > Amount::ZERO {
-            // Return to user balance
-            let balance_key = (caller, asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            state.balances.insert(&balance_key, current_balance + escrowed)?;
-            
-            // Clear escrow
-            state.escrowed_balances.remove(&escrow_key)?;
-            
-            // Update escrow state
-            if caller == settlement.maker {
-                settlement.maker_escrow.is_escrowed = false;
-            } else {
-                settlement.taker_escrow.is_escrowed = false;
+        let window = throttle.window(runtime.system_time(), runtime.block_height());
+        let mut bucket = state.throttle_buckets.get(&origin).await?.unwrap_or_default();
+        let admitted = bucket.admit(window, throttle.max_per_window);
+        state.throttle_buckets.insert(&origin, bucket)?;
+        Ok(admitted)
+    }
+    
+    async fn initiate_request(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        request: DeferredSettlementRequest,
+    ) -> Result<u64, SettlementError> {
+        self.initiate_settlement(
+            runtime, state, request.trade_id, request.maker, request.taker,
+            request.maker_asset, request.taker_asset, request.maker_amount, request.taker_amount,
+            request.maker_chain, request.taker_chain, request.timeout_seconds, request.fees, false, false,
+            request.provenance, None, None,
+        ).await
+    }
+    
+    /// Initiates the oldest of `origin`'s deferred requests, recording a rejection event when it
+    /// no longer passes. Returns whether more are waiting.
+    async fn initiate_next_deferred(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        origin: SettlementOrigin,
+    ) -> Result<bool, SettlementError> {
+        let Some(mut queue) = state.deferred_queues.get(&origin).await? else {
+            return Ok(false);
+        };
+        let key = (origin, queue.head);
+        if let Some(request) = state.deferred_requests.get(&key).await? {
+            state.deferred_requests.remove(&key)?;
+            let trade_id = request.trade_id;
+            if let Err(e) = self.initiate_request(runtime, state, request).await {
+                tracing::warn!("Rejected deferred settlement request for trade {}: {}", trade_id, e);
+                state.events.push_back(SettlementEvent::SettlementRequestRejected {
+                    trade_id,
+                    caller: origin.application_id,
+                    origin: Some(origin.chain_id),
+                    reason: e.to_string(),
+                    timestamp: runtime.system_time(),
+                });
+                self.send_rejection(runtime, origin.chain_id, origin.application_id, trade_id, &e);
             }
         }
         
-        // Update status to refunded if both parties have been refunded
-        if !settlement.maker_escrow.is_escrowed && !settlement.taker_escrow.is_escrowed {
-            settlement.status = SettlementStatus::Refunded;
-            state.active_settlements.remove(&settlement_id)?;
+        queue.head += 1;
+        if queue.depth() == 0 {
+            state.deferred_queues.remove(&origin)?;
+            return Ok(false);
         }
-        
-        state.settlements.insert(&settlement_id, settlement)?;
-        
-        tracing::info!("Refund claimed: settlement_id={}, user={:?}", settlement_id, caller);
-        
-        Ok(())
+        state.deferred_queues.insert(&origin, queue)?;
+        Ok(true)
     }
     
-    async fn process_refund(
-        &self,
-        state: &mut SettlementState<ContractRuntime<SettlementContract>>,
-        settlement: &Settlement,
-    ) -> Result<(), SettlementError> {
-        // Refund maker if escrowed
-        if settlement.maker_escrow.is_escrowed {
-            let escrow_key = (settlement.id, settlement.maker, settlement.maker_asset.clone());
-            let escrowed = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
-            
-            if escrowed > Amount::ZERO {
-                let balance_key = (settlement.maker, settlement.maker_asset.clone());
-                let balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-                state.balances.insert(&balance_key, balance + escrowed)?;
-                state.escrowed_balances.remove(&escrow_key)?;
+    /// Drains deferred requests one per origin per round, so a flooding origin cannot starve the
+    /// others, skipping origins whose window is full. Returns the number initiated and the number
+    /// still deferred.
+    async fn process_deferred_requests(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+    ) -> Result<(u32, u64), SettlementError> {
+        let limit = state.settlement_throttle.get()
+            .map_or(DEFAULT_DEFERRED_DRAIN, |throttle| throttle.max_drained_per_block);
+        let height = runtime.block_height();
+        let (drained_height, drained) = state.deferred_drained.get();
+        let mut drained = if drained_height == height { drained } else { 0 };
+        
+        let mut initiated = 0;
+        let mut origins = state.deferred_queues.indices().await?;
+        while drained < limit && !origins.is_empty() {
+            let mut waiting = Vec::new();
+            for origin in origins {
+                if drained == limit {
+                    break;
+                }
+                if !self.take_throttle_slot(runtime, state, origin).await? {
+                    continue;
+                }
+                if self.initiate_next_deferred(runtime, state, origin).await? {
+                    waiting.push(origin);
+                }
+                drained += 1;
+                initiated += 1;
             }
+            origins = waiting;
         }
+        state.deferred_drained.set((height, drained));
         
-        // Refund taker if escrowed
-        if settlement.taker_escrow.is_escrowed {
-            let escrow_key = (settlement.id, settlement.taker, settlement.taker_asset.clone());
-            let escrowed = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
+        let mut remaining = 0;
+        for origin in state.deferred_queues.indices().await? {
+            remaining += state.deferred_queues.get(&origin).await?.map_or(0, |queue| queue.depth());
+        }
+        Ok((initiated, remaining))
+    }
+    
+    /// Creates the next instance of at most `limit` due recurring settlements. Before that, the
+    /// outcomes of earlier instances are counted, and a schedule with too many defaults in a row
+    /// stops instead. Returns the number of instances created.
+    async fn process_recurring_settlements(
+        &mut self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        limit: u32,
+    ) -> Result<u32, SettlementError> {
+        let now = runtime.system_time();
+        let (mut processed, mut instantiated) = (0, 0);
+        for recurring_id in state.active_recurring.indices().await? {
+            if processed == limit {
+                break;
+            }
+            let Some(mut recurring) = state.recurring_settlements.get(&recurring_id).await? else {
+                continue;
+            };
+            if recurring.next_due_at > now {
+                continue;
+            }
+            processed += 1;
+            let mut instances = state.recurring_instances.get(&recurring_id).await?.unwrap_or_default();
+            for instance in &instances[recurring.counted_instances as usize..] {
+                let settlement = match instance.settlement_id {
+                    Some(settlement_id) => state.settlements.get(&settlement_id).await?,
+                    None => None,
+                };
+                match InstanceOutcome::of(instance, settlement.as_ref(), now) {
+                    InstanceOutcome::InFlight => break,
+                    InstanceOutcome::Paid { .. } => recurring.consecutive_defaults = 0,
+                    InstanceOutcome::Defaulted { .. } => recurring.consecutive_defaults += 1,
+                }
+                recurring.counted_instances += 1;
+            }
+            if recurring.consecutive_defaults >= recurring.max_consecutive_defaults {
+                self.stop_recurring(runtime, state, recurring, RecurringStatus::Defaulted)?;
+                continue;
+            }
             
-            if escrowed > Amount::ZERO {
-                let balance_key = (settlement.taker, settlement.taker_asset.clone());
-                let balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-                state.balances.insert(&balance_key, balance + escrowed)?;
-                state.escrowed_balances.remove(&escrow_key)?;
+            let sequence = instances.len() as u32;
+            instances.push(self.instantiate_recurring(runtime, state, &recurring, sequence).await?);
+            state.recurring_instances.insert(&recurring_id, instances)?;
+            instantiated += 1;
+            recurring.next_due_at = recurring.next_due_at + std::time::Duration::from_secs(recurring.interval_seconds);
+            if recurring.schedules(sequence + 1, recurring.next_due_at) {
+                state.recurring_settlements.insert(&recurring_id, recurring)?;
+            } else {
+                self.stop_recurring(runtime, state, recurring, RecurringStatus::Ended)?;
             }
         }
-        
-        Ok(())
+        Ok(instantiated)
     }
     
-    async fn process_expired_settlements(
+    /// Creates the settlement for payment `sequence` of `recurring`, linked back to it. A
+    /// settlement the limits refuse is recorded as an instance that was never created.
+    async fn instantiate_recurring(
         &mut self,
         runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-    ) -> Result<(), SettlementError> {
-        let now = runtime.system_time();
-        let mut processed = 0;
-        
-        // Process up to 10 expired settlements at a time
-        while processed < 10 {
-            let Some((expires_at, settlement_id)) = state.expiration_queue.front().await? else {
-                break;
-            };
-            
-            if expires_at > now {
-                break; // No more expired settlements
-            }
-            
-            // Remove from queue
-            state.expiration_queue.pop_front();
-            
-            // Get settlement
-            if let Some(mut settlement) = state.settlements.get(&settlement_id).await? {
-                if settlement.status != SettlementStatus::Completed &&
-                   settlement.status != SettlementStatus::Refunded &&
-                   settlement.status != SettlementStatus::Cancelled {
-                    
-                    // Process refunds
-                    self.process_refund(state, &settlement).await?;
-                    
-                    settlement.status = SettlementStatus::Expired;
+        recurring: &RecurringSettlement,
+        sequence: u32,
+    ) -> Result<RecurringInstance, SettlementError> {
+        let mut instance = RecurringInstance {
+            sequence,
+            due_at: recurring.next_due_at,
+            settlement_id: None,
+            failure_reason: None,
+        };
+        let provenance = self.provenance(runtime, SettlementOriginKind::Operation, None);
+        let result = self.initiate_settlement(
+            runtime, state, 0, recurring.payer, recurring.payee,
+            recurring.asset.clone(), recurring.asset.clone(), recurring.amount, Amount::ZERO,
+            recurring.payer_chain, recurring.payee_chain, recurring.timeout_seconds, None, false, true, provenance,
+            None, None,
+        ).await;
+        match result {
+            Ok(settlement_id) => {
+                if let Some(mut settlement) = state.settlements.get(&settlement_id).await? {
+                    settlement.recurring = Some(RecurringLink { recurring_id: recurring.id, sequence });
                     state.settlements.insert(&settlement_id, settlement)?;
-                    state.active_settlements.remove(&settlement_id)?;
-                    
-                    // Update stats
-                    let mut stats = state.stats.get();
-                    stats.failed_settlements += 1;
-                    state.stats.set(stats);
-                    
-                    processed += 1;
                 }
+                instance.settlement_id = Some(settlement_id);
+            }
+            Err(SettlementError::ViewError(error)) => return Err(SettlementError::ViewError(error)),
+            Err(e) => {
+                tracing::warn!("Could not create instance {} of recurring settlement {}: {}", sequence, recurring.id, e);
+                instance.failure_reason = Some(e.to_string());
             }
         }
-        
-        if processed > 0 {
-            tracing::info!("Processed {} expired settlements", processed);
-        }
-        
-        Ok(())
+        Ok(instance)
     }
     
-    async fn configure_bridge(
+    fn stop_recurring(
         &mut self,
+        runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        chain_id: String,
-        config: BridgeConfig,
+        mut recurring: RecurringSettlement,
+        status: RecurringStatus,
     ) -> Result<(), SettlementError> {
-        // TODO: Add admin check
-        state.bridge_configs.insert(&chain_id, config.clone())?;
-        
-        tracing::info!("Bridge configured: chain_id={}, address={}", chain_id, config.bridge_address);
-        
+        let recurring_id = recurring.id;
+        recurring.status = status;
+        state.recurring_settlements.insert(&recurring_id, recurring)?;
+        state.active_recurring.remove(&recurring_id)?;
+        state.events.push_back(SettlementEvent::RecurringSettlementStopped {
+            recurring_id,
+            status,
+            timestamp: runtime.system_time(),
+        });
         Ok(())
     }
     
-    async fn disable_bridge(
-        &mut self,
-        state: &mut SettlementState<ContractRuntime<Self>>,
-        chain_id: String,
+    /// Loads a template for the signer, who has to be the party `role` picks
+    async fn template_for(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &SettlementState<ContractRuntime<Self>>,
+        template_id: u64,
+        role: fn(&SettlementTemplate) -> Account,
+    ) -> Result<SettlementTemplate, SettlementError> {
+        let signer = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        let template = state.settlement_templates.get(&template_id).await?
+            .ok_or(SettlementError::SettlementTemplateNotFound { template_id })?;
+        if signer != role(&template) {
+            return Err(SettlementError::Unauthorized { reason: "Signer may not act on this template".to_string() });
+        }
+        Ok(template)
+    }
+    
+    /// Accepts a settlement request only from a registered market, made from its chain, for its pair.
+    /// `origin` is the sending chain for messages and this chain for direct calls.
+    async fn verify_settlement_request(
+        &self,
+        state: &SettlementState<ContractRuntime<Self>>,
+        caller: Option<ApplicationId>,
+        origin: Option<ChainId>,
+        maker_asset: &str,
+        taker_asset: &str,
     ) -> Result<(), SettlementError> {
-        let mut config = state.bridge_configs.get(&chain_id).await?
-            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
-        
-        config.is_active = false;
-        state.bridge_configs.insert(&chain_id, config)?;
-        
-        tracing::info!("Bridge disabled: chain_id={}", chain_id);
-        
-        Ok(())
+        let market = match caller {
+            Some(application_id) => state.markets.get(&application_id).await?,
+            None => None,
+        };
+        let market = market.ok_or(SettlementError::UnknownMarket { application_id: caller })?;
+        market.verify_request(origin, maker_asset, taker_asset)
     }
     
-    async fn process_bridge_deposit(
-        &mut self,
+    fn require_admin(
+        &self,
+        runtime: &mut ContractRuntime<Self>,
+        state: &SettlementState<ContractRuntime<Self>>,
+    ) -> Result<Account, SettlementError> {
+        let signer = runtime.authenticated_signer()
+            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
+        match state.admin.get() {
+            Some(admin) if admin == signer => Ok(signer),
+            _ => Err(SettlementError::Unauthorized { reason: "Not settlement admin".to_string() }),
+        }
+    }
+    
+    /// The rate oracle, or the admin, may publish conversion rates
+    fn require_rate_publisher(
+        &self,
         runtime: &mut ContractRuntime<Self>,
+        state: &SettlementState<ContractRuntime<Self>>,
+    ) -> Result<Account, SettlementError> {
+        match (runtime.authenticated_signer(), state.rate_oracle.get()) {
+            (Some(signer), Some(oracle)) if signer == oracle => Ok(signer),
+            _ => self.require_admin(runtime, state),
+        }
+    }
+    
+    /// Adds to a free balance and the asset's running total.
+    async fn credit_balance(
+        &mut self,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        chain_id: String,
-        tx_hash: String,
         user: Account,
-        asset: String,
+        asset: &str,
         amount: Amount,
-        confirmations: u64,
     ) -> Result<(), SettlementError> {
-        let now = runtime.system_time();
-        
-        // Verify bridge configuration
-        let config = state.bridge_configs.get(&chain_id).await?
-            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
-        
-        if !config.is_active {
-            return Err(SettlementError::BridgeDisabled { chain_id });
-        }
-        
-        if !config.supported_assets.contains(&asset) {
-            return Err(SettlementError::AssetNotSupported { asset });
-        }
-        
-        if amount < config.min_amount {
-            return Err(SettlementError::BelowMinimum { amount, minimum: config.min_amount });
-        }
-        
-        if amount > config.max_amount {
-            return Err(SettlementError::AboveMaximum { amount, maximum: config.max_amount });
-        }
-        
-        // Check if already processed
-        if state.pending_deposits.get(&tx_hash).await?.is_some() {
-            return Err(SettlementError::BridgeError { 
-                reason: "Deposit already processed".to_string() 
-            });
-        }
-        
-        // Check confirmations
-        let status = if confirmations >= config.confirmation_blocks {
-            BridgeTransferStatus::Completed
-        } else {
-            BridgeTransferStatus::Confirming
-        };
-        
-        // Create transfer record
-        let transfer_id = state.next_transfer_id.get();
-        let transfer = BridgeTransfer {
-            id: transfer_id,
-            chain_id: chain_id.clone(),
-            user,
-            asset: asset.clone(),
-            amount,
-            direction: BridgeDirection::Deposit,
-            status,
-            tx_hash: Some(tx_hash.clone()),
-            destination_address: None,
-            created_at: now,
-            completed_at: if status == BridgeTransferStatus::Completed { Some(now) } else { None },
-            confirmations,
-        };
-        
-        state.bridge_transfers.insert(&transfer_id, transfer)?;
-        state.pending_deposits.insert(&tx_hash, transfer_id)?;
-        state.next_transfer_id.set(transfer_id + 1);
-        
-        // If confirmed, credit user balance
-        if status == BridgeTransferStatus::Completed {
-            // Deduct bridge fee
-            let fee = Amount::from((amount.into_inner() * config.fee_rate_bps as u128) / 10000);
-            let credited = amount - fee;
-            
-            let balance_key = (user, asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            state.balances.insert(&balance_key, current_balance + credited)?;
-            
-            // Update stats
-            let mut stats = state.stats.get();
-            stats.total_bridge_deposits = stats.total_bridge_deposits + credited;
-            state.stats.set(stats);
+        if amount == Amount::ZERO {
+            return Ok(());
         }
-        
-        tracing::info!(
-            "Bridge deposit processed: chain={}, user={:?}, asset={}, amount={}, status={:?}",
-            chain_id, user, asset, amount, status
-        );
-        
+        let balance_key = (user, asset.to_string());
+        let balance = state.balances.get(&balance_key).await?.unwrap_or_default();
+        state.balances.insert(&balance_key, math::checked_add(balance, amount)?)?;
+        let total = state.total_balances.get(&balance_key.1).await?.unwrap_or_default();
+        state.total_balances.insert(&balance_key.1, math::checked_add(total, amount)?)?;
         Ok(())
     }
     
-    async fn initiate_bridge_withdrawal(
+    /// Takes from a free balance and the asset's running total.
+    async fn debit_balance(
         &mut self,
-        runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        chain_id: String,
-        asset: String,
+        user: Account,
+        asset: &str,
         amount: Amount,
-        destination_address: String,
     ) -> Result<(), SettlementError> {
-        let user = runtime.authenticated_signer()
-            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        let now = runtime.system_time();
-        
-        // Verify bridge configuration
-        let config = state.bridge_configs.get(&chain_id).await?
-            .ok_or(SettlementError::BridgeNotConfigured { chain_id: chain_id.clone() })?;
-        
-        if !config.is_active {
-            return Err(SettlementError::BridgeDisabled { chain_id });
+        let balance_key = (user, asset.to_string());
+        let balance = state.balances.get(&balance_key).await?.unwrap_or_default();
+        if balance < amount {
+            return Err(SettlementError::InsufficientBalance { required: amount, available: balance });
         }
-        
-        if !config.supported_assets.contains(&asset) {
-            return Err(SettlementError::AssetNotSupported { asset });
-        }
-        
-        if amount < config.min_amount {
-            return Err(SettlementError::BelowMinimum { amount, minimum: config.min_amount });
-        }
-        
-        if amount > config.max_amount {
-            return Err(SettlementError::AboveMaximum { amount, maximum: config.max_amount });
-        }
-        
-        // Check user balance
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-        
-        if current_balance < amount {
-            return Err(SettlementError::InsufficientBalance {
-                required: amount,
-                available: current_balance,
-            });
-        }
-        
-        // Deduct balance
-        let new_balance = current_balance - amount;
-        state.balances.insert(&balance_key, new_balance)?;
-        
-        // Create transfer record
-        let transfer_id = state.next_transfer_id.get();
-        let transfer = BridgeTransfer {
-            id: transfer_id,
-            chain_id: chain_id.clone(),
-            user,
-            asset: asset.clone(),
-            amount,
-            direction: BridgeDirection::Withdrawal,
-            status: BridgeTransferStatus::Pending,
-            tx_hash: None,
-            destination_address: Some(destination_address.clone()),
-            created_at: now,
-            completed_at: None,
-            confirmations: 0,
-        };
-        
-        state.bridge_transfers.insert(&transfer_id, transfer)?;
-        state.pending_withdrawals.push_back(transfer_id);
-        state.next_transfer_id.set(transfer_id + 1);
-        
-        // Add to user transfers
-        let mut user_transfers = state.user_transfers.get(&user).await?.unwrap_or_default();
-        user_transfers.push(transfer_id);
-        state.user_transfers.insert(&user, user_transfers)?;
-        
-        tracing::info!(
-            "Bridge withdrawal initiated: chain={}, user={:?}, asset={}, amount={}, destination={}",
-            chain_id, user, asset, amount, destination_address
-        );
-        
+        state.balances.insert(&balance_key, balance - amount)?;
+        let total = state.total_balances.get(&balance_key.1).await?.unwrap_or_default();
+        state.total_balances.insert(&balance_key.1, math::checked_sub(total, amount)?)?;
         Ok(())
     }
     
-    async fn complete_bridge_withdrawal(
+    async fn add_escrow(
         &mut self,
-        runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        transfer_id: u64,
-        tx_hash: String,
-        success: bool,
+        escrow_key: (u64, Account, String),
+        amount: Amount,
     ) -> Result<(), SettlementError> {
-        let now = runtime.system_time();
-        
-        let mut transfer = state.bridge_transfers.get(&transfer_id).await?
-            .ok_or(SettlementError::TransferNotFound { transfer_id })?;
-        
-        if success {
-            transfer.status = BridgeTransferStatus::Completed;
-            transfer.tx_hash = Some(tx_hash);
-            transfer.completed_at = Some(now);
-            
-            // Update stats
-            let mut stats = state.stats.get();
-            stats.total_bridge_withdrawals = stats.total_bridge_withdrawals + transfer.amount;
-            state.stats.set(stats);
-        } else {
-            transfer.status = BridgeTransferStatus::Failed;
-            
-            // Refund user
-            let balance_key = (transfer.user, transfer.asset.clone());
-            let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-            state.balances.insert(&balance_key, current_balance + transfer.amount)?;
-        }
-        
-        state.bridge_transfers.insert(&transfer_id, transfer)?;
-        
-        tracing::info!(
-            "Bridge withdrawal completed: transfer_id={}, success={}",
-            transfer_id, success
-        );
-        
+        let escrowed = state.escrowed_balances.get(&escrow_key).await?.unwrap_or_default();
+        state.escrowed_balances.insert(&escrow_key, math::checked_add(escrowed, amount)?)?;
+        let total = state.total_escrowed.get(&escrow_key.2).await?.unwrap_or_default();
+        state.total_escrowed.insert(&escrow_key.2, math::checked_add(total, amount)?)?;
         Ok(())
     }
     
-    async fn deposit(
+    /// Moves `payer`'s escrow to `payee`, less `fee` to the settlement's fee recipient.
+    async fn pay_leg(
         &mut self,
-        runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        asset: String,
-        amount: Amount,
-    ) -> Result<(), SettlementError> {
-        let user = runtime.authenticated_signer()
-            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-        let new_balance = current_balance + amount;
-        
-        state.balances.insert(&balance_key, new_balance)?;
-        
-        tracing::info!("Deposit: user={:?}, asset={}, amount={}", user, asset, amount);
-        
+        settlement: &Settlement,
+        payer: Account,
+        payee: Account,
+        asset: &str,
+        fee: Amount,
+    ) -> Result<(), SettlementError> {
+        let escrow_key = (settlement.id, payer, asset.to_string());
+        let escrowed = self.release_escrow(state, &escrow_key).await?;
+        self.credit_balance(state, payee, asset, math::checked_sub(escrowed, fee)?).await?;
+        if let Some(fees) = &settlement.fees {
+            self.credit_balance(state, fees.recipient, asset, fee).await?;
+        }
         Ok(())
     }
     
-    async fn withdraw(
+    /// Pays a same-asset settlement's net transfer out of both parties' escrow.
+    async fn pay_net(
         &mut self,
-        runtime: &mut ContractRuntime<Self>,
         state: &mut SettlementState<ContractRuntime<Self>>,
-        asset: String,
-        amount: Amount,
+        settlement: &Settlement,
+        net: NetTransfer,
     ) -> Result<(), SettlementError> {
-        let user = runtime.authenticated_signer()
-            .ok_or(SettlementError::Unauthorized { reason: "No authenticated signer".to_string() })?;
-        
-        let balance_key = (user, asset.clone());
-        let current_balance = state.balances.get(&balance_key).await?.unwrap_or_default();
-        
-        if current_balance < amount {
-            return Err(SettlementError::InsufficientBalance {
-                required: amount,
-                available: current_balance,
-            });
+        let asset = &settlement.maker_asset;
+        let mut released = Amount::ZERO;
+        for payer in [settlement.maker, settlement.taker] {
+            let escrowed = self.release_escrow(state, &(settlement.id, payer, asset.clone())).await?;
+            released = math::checked_add(released, escrowed)?;
+        }
+        let owed = math::checked_add(net.maker_owes, net.taker_owes)?;
+        if released != owed {
+            return Err(SettlementError::InsufficientBalance { required: owed, available: released });
+        }
+        self.credit_balance(state, settlement.maker, asset, net.maker_receives).await?;
+        self.credit_balance(state, settlement.taker, asset, net.taker_receives).await?;
+        if let Some(fees) = &settlement.fees {
+            self.credit_balance(state, fees.recipient, asset, net.fees).await?;
         }
-        
-        let new_balance = current_balance - amount;
-        state.balances.insert(&balance_key, new_balance)?;
-        
-        tracing::info!("Withdrawal: user={:?}, asset={}, amount={}", user, asset, amount);
-        
         Ok(())
     }
+    
+    /// Clears an escrow record, returning the amount it held.
+    async fn release_escrow(
+        &mut self,
+        state: &mut SettlementState<ContractRuntime<Self>>,
+        escrow_key: &(u64, Account, String),
+    ) -> Result<Amount, SettlementError> {
+        let Some(escrowed) = state.escrowed_balances.get(escrow_key).await? else {
+            return Ok(Amount::ZERO);
+        };
+        state.escrowed_balances.remove(escrow_key)?;
+        let total = state.total_escrowed.get(&escrow_key.2).await?.unwrap_or_default();
+        state.total_escrowed.insert(&escrow_key.2, math::checked_sub(total, escrowed)?)?;
+        Ok(escrowed)
+    }
+}
+
+/// Query types for Service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Query {
+    GetSettlement { settlement_id: u64 },
+    /// Settlement `creator` initiated under its own deal id `external_ref`
+    GetSettlementByExternalRef { creator: Account, external_ref: String },
+    /// Settlement with what each participant has to do next at time `at`
+    GetSettlementActions { settlement_id: u64, at: Timestamp },
+    GetReceipt { account: Account, client_request_id: u64 },
+    GetBalance { account: Account, asset: String },
+    GetUserSettlements { account: Account },
+    GetStats,
+    /// Free and escrowed totals for every asset
+    GetAssetTotals,
+    GetHealth,
+    /// Smallest settlement leg allowed for an asset; zero when unrestricted
+    GetMinSettlementAmount { asset: String },
+    /// Largest settlement leg allowed for an asset; None when unrestricted
+    GetMaxSettlementAmount { asset: String },
+    /// Whether settlements between the two accounts skip the maximums
+    GetUncappedPair { first: Account, second: Account },
+    /// Registration of an order book market allowed to request settlements
+    GetMarket { application_id: ApplicationId },
+    /// Settlement track record of an account
+    GetReputation { account: Account },
+    /// Most recent monitoring events
+    GetEvents { count: usize },
+    /// Window length and the windowed settlements waiting for each boundary
+    GetSettlementWindow,
+    /// Grant letting `custodian` act for `party`, if any
+    GetCustodian { party: Account, custodian: Account },
+    /// Counterparty allowlist and denylist of `account`; empty lists when it set none
+    GetCounterpartyPolicy { account: Account },
+    /// Rate at which `to_asset` is accepted in escrow for `from_asset` legs
+    GetConversionRate { from_asset: String, to_asset: String },
+    /// Bridge transfer, with the settlement leg it carries if any
+    GetBridgeTransfer { transfer_id: u64 },
+    /// Ids of the settlements requested from a chain and application, oldest first
+    GetSettlementsByOrigin { origin: SettlementOrigin },
+    GetRecurringSettlement { recurring_id: u64 },
+    /// Instances created so far with their outcome as of `at`, and the due times of the next ones
+    GetRecurringInstances { recurring_id: u64, at: Timestamp },
+    /// Ids of the recurring settlements the account pays or is paid by
+    GetUserRecurringSettlements { account: Account },
+    GetSettlementTemplate { template_id: u64 },
+    /// Templates the account created or is the counterparty of, oldest first
+    GetAccountTemplates { account: Account },
+    /// Ids of the settlements initiated from a template, oldest first
+    GetTemplateSettlements { template_id: u64 },
+    /// Last availability the corridor source reported for a corridor; None while it is open
+    GetCorridorStatus { corridor: u64 },
+    /// Messages waiting for replay or discard, oldest first
+    GetParkedMessages,
+    /// Messages that failed to apply, waiting for retry or purge, oldest first
+    GetDeadLetters,
+}
+
+/// Query response type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueryResponse {
+    Settlement(Option<Settlement>),
+    SettlementActions(Option<SettlementActions>),
+    Receipt(Option<OperationReceipt>),
+    Balance(Amount),
+    UserSettlements(Vec<u64>),
+    Stats(SettlementStats),
+    AssetTotals(Vec<AssetTotals>),
+    Health {
+        /// No drift or stuck execution found by the last escrow audit
+        healthy: bool,
+        last_escrow_audit: Option<EscrowAuditReport>,
+        /// Settlement requests deferred by the throttle, per origin that has any
+        deferred_requests: Vec<(SettlementOrigin, u64)>,
+    },
+    MinSettlementAmount(Amount),
+    MaxSettlementAmount(Option<Amount>),
+    UncappedPair(bool),
+    Market(Option<MarketRegistration>),
+    Reputation(Reputation),
+    Events(Vec<SettlementEvent>),
+    SettlementWindow {
+        window_seconds: u64,
+        /// (boundary, settlement ids), earliest boundary first
+        queued: Vec<(Timestamp, Vec<u64>)>,
+    },
+    Custodian(Option<CustodianGrant>),
+    CounterpartyPolicy(CounterpartyPolicy),
+    ConversionRate(Option<ConversionRate>),
+    BridgeTransfer(Option<BridgeTransfer>),
+    SettlementsByOrigin(Vec<u64>),
+    RecurringSettlement(Option<RecurringSettlement>),
+    RecurringInstances {
+        /// Oldest first
+        past: Vec<(RecurringInstance, InstanceOutcome)>,
+        /// At most `MAX_UPCOMING_INSTANCES`
+        upcoming: Vec<Timestamp>,
+    },
+    UserRecurringSettlements(Vec<u64>),
+    SettlementTemplate(Option<SettlementTemplate>),
+    AccountTemplates(Vec<SettlementTemplate>),
+    TemplateSettlements(Vec<u64>),
+    CorridorStatus(Option<CorridorStatus>),
+    ParkedMessages(Vec<ParkedMessage>),
+    DeadLetters(Vec<DeadLetter>),
+    Error(String),
+}
+
+/// Settlement application ABI
+pub struct SettlementAbi;
+
+impl ContractAbi for SettlementAbi {
+    type Operation = Operation;
+    type Response = SettlementResponse;
+}
+
+/// Result of an operation, returned to applications calling this one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementResponse {
+    Ok,
+    /// `RequestSettlement` or `InitiateFromTemplate` created this settlement
+    SettlementInitiated { settlement_id: u64 },
+    /// `RequestSettlement` was relayed to its settlement chain, which assigns the settlement id
+    SettlementRequestForwarded,
+    /// `TakeSettlementRejection` found the settlement chain's rejection, or None while the request stands
+    SettlementRejection { reason_code: Option<SettlementRejectionCode> },
+    /// `ProcessExpiredSettlements` expired this many settlements; the earliest expiration still
+    /// queued is due at `next_expires_at`, so a time at or before now means more are due
+    ExpiredSettlementsProcessed { expired: u32, next_expires_at: Option<Timestamp> },
+    /// `ProcessDeferredRequests` initiated this many deferred requests; `remaining` are still deferred
+    DeferredRequestsProcessed { initiated: u32, remaining: u64 },
+    /// `CreateRecurringSettlement` created this recurring settlement
+    RecurringSettlementCreated { recurring_id: u64 },
+    /// `ProcessRecurringSettlements` created this many settlement instances
+    RecurringSettlementsProcessed { instantiated: u32 },
+    /// `CreateSettlementTemplate` created this template
+    SettlementTemplateCreated { template_id: u64 },
+}
+
+impl ServiceAbi for SettlementAbi {
+    type Query = Query;
+    type QueryResponse = QueryResponse;
 }
 
 /// Service for GraphQL queries
@@ -1332,7 +5560,238 @@ impl Service for SettlementService {
     }
 
     async fn handle_query(&mut self, state: &Self::State, query: &[u8]) -> Vec<u8> {
-        serde_json::to_vec(&"Settlement query handled").unwrap_or_default()
+        let response = match serde_json::from_slice::<Query>(query) {
+            Ok(query) => self.query(state, query).await.unwrap_or_else(|e| QueryResponse::Error(e.to_string())),
+            Err(e) => QueryResponse::Error(e.to_string()),
+        };
+        serde_json::to_vec(&response).unwrap_or_default()
+    }
+}
+
+impl SettlementService {
+    async fn query(
+        &self,
+        state: &SettlementState<ServiceRuntime<Self>>,
+        query: Query,
+    ) -> Result<QueryResponse, SettlementError> {
+        match query {
+            Query::GetSettlement { settlement_id } => {
+                Ok(QueryResponse::Settlement(state.settlements.get(&settlement_id).await?))
+            }
+            Query::GetSettlementByExternalRef { creator, external_ref } => {
+                match state.settlements_by_external_ref.get(&(creator, external_ref)).await? {
+                    Some(settlement_id) => Ok(QueryResponse::Settlement(state.settlements.get(&settlement_id).await?)),
+                    None => Ok(QueryResponse::Settlement(None)),
+                }
+            }
+            Query::GetSettlementActions { settlement_id, at } => {
+                let Some(settlement) = state.settlements.get(&settlement_id).await? else {
+                    return Ok(QueryResponse::SettlementActions(None));
+                };
+                let maker_asset = settlement.maker_escrow.held_asset(&settlement.maker_asset).to_string();
+                let taker_asset = settlement.taker_escrow.held_asset(&settlement.taker_asset).to_string();
+                let maker_key = (settlement_id, settlement.maker, maker_asset);
+                let taker_key = (settlement_id, settlement.taker, taker_asset);
+                let maker_escrowed = state.escrowed_balances.get(&maker_key).await?.unwrap_or_default();
+                let taker_escrowed = state.escrowed_balances.get(&taker_key).await?.unwrap_or_default();
+                let mut delayed_withdrawals = Vec::new();
+                for payer in [settlement.maker, settlement.taker] {
+                    if let Some(delay) = self.withdrawal_delay(state, &settlement, payer).await? {
+                        delayed_withdrawals.push(delay);
+                    }
+                }
+                Ok(QueryResponse::SettlementActions(Some(SettlementActions {
+                    maker: settlement.next_action(settlement.maker, maker_escrowed, at),
+                    taker: settlement.next_action(settlement.taker, taker_escrowed, at),
+                    settlement,
+                    delayed_withdrawals,
+                })))
+            }
+            Query::GetReceipt { account, client_request_id } => {
+                Ok(QueryResponse::Receipt(state.receipts.get(&(account, client_request_id)).await?))
+            }
+            Query::GetBalance { account, asset } => {
+                Ok(QueryResponse::Balance(state.balances.get(&(account, asset)).await?.unwrap_or_default()))
+            }
+            Query::GetUserSettlements { account } => {
+                Ok(QueryResponse::UserSettlements(
+                    state.user_settlements.get(&account).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetStats => Ok(QueryResponse::Stats(state.stats.get())),
+            Query::GetMinSettlementAmount { asset } => {
+                Ok(QueryResponse::MinSettlementAmount(
+                    state.min_settlement_amounts.get(&asset).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetMaxSettlementAmount { asset } => {
+                Ok(QueryResponse::MaxSettlementAmount(state.max_settlement_amounts.get(&asset).await?))
+            }
+            Query::GetCustodian { party, custodian } => {
+                Ok(QueryResponse::Custodian(state.custodians.get(&(party, custodian)).await?))
+            }
+            Query::GetCounterpartyPolicy { account } => {
+                let policy = state.counterparty_policies.get(&account).await?.unwrap_or_default();
+                Ok(QueryResponse::CounterpartyPolicy(policy))
+            }
+            Query::GetConversionRate { from_asset, to_asset } => {
+                Ok(QueryResponse::ConversionRate(state.conversion_rates.get(&(from_asset, to_asset)).await?))
+            }
+            Query::GetBridgeTransfer { transfer_id } => {
+                Ok(QueryResponse::BridgeTransfer(state.bridge_transfers.get(&transfer_id).await?))
+            }
+            Query::GetSettlementsByOrigin { origin } => {
+                Ok(QueryResponse::SettlementsByOrigin(
+                    state.settlements_by_origin.get(&origin).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetRecurringSettlement { recurring_id } => {
+                Ok(QueryResponse::RecurringSettlement(state.recurring_settlements.get(&recurring_id).await?))
+            }
+            Query::GetRecurringInstances { recurring_id, at } => {
+                let recurring = state.recurring_settlements.get(&recurring_id).await?
+                    .ok_or(SettlementError::RecurringSettlementNotFound { recurring_id })?;
+                let instances = state.recurring_instances.get(&recurring_id).await?.unwrap_or_default();
+                let upcoming = recurring.upcoming(instances.len() as u32, MAX_UPCOMING_INSTANCES);
+                let mut past = Vec::new();
+                for instance in instances {
+                    let settlement = match instance.settlement_id {
+                        Some(settlement_id) => state.settlements.get(&settlement_id).await?,
+                        None => None,
+                    };
+                    let outcome = InstanceOutcome::of(&instance, settlement.as_ref(), at);
+                    past.push((instance, outcome));
+                }
+                Ok(QueryResponse::RecurringInstances { past, upcoming })
+            }
+            Query::GetUserRecurringSettlements { account } => {
+                Ok(QueryResponse::UserRecurringSettlements(
+                    state.user_recurring.get(&account).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetSettlementTemplate { template_id } => {
+                Ok(QueryResponse::SettlementTemplate(state.settlement_templates.get(&template_id).await?))
+            }
+            Query::GetAccountTemplates { account } => {
+                let mut templates = Vec::new();
+                for template_id in state.account_templates.get(&account).await?.unwrap_or_default() {
+                    templates.extend(state.settlement_templates.get(&template_id).await?);
+                }
+                Ok(QueryResponse::AccountTemplates(templates))
+            }
+            Query::GetTemplateSettlements { template_id } => {
+                if !state.settlement_templates.contains_key(&template_id).await? {
+                    return Err(SettlementError::SettlementTemplateNotFound { template_id });
+                }
+                Ok(QueryResponse::TemplateSettlements(
+                    state.template_settlements.get(&template_id).await?.unwrap_or_default(),
+                ))
+            }
+            Query::GetCorridorStatus { corridor } => {
+                Ok(QueryResponse::CorridorStatus(state.corridor_statuses.get(&corridor).await?))
+            }
+            Query::GetParkedMessages => {
+                let mut parked = Vec::new();
+                for parked_id in state.parked_messages.indices().await? {
+                    parked.extend(state.parked_messages.get(&parked_id).await?);
+                }
+                Ok(QueryResponse::ParkedMessages(parked))
+            }
+            Query::GetDeadLetters => {
+                let mut dead_letters = Vec::new();
+                for dead_letter_id in state.first_dead_letter_id.get()..state.next_dead_letter_id.get() {
+                    dead_letters.extend(state.dead_letters.get(&dead_letter_id).await?);
+                }
+                Ok(QueryResponse::DeadLetters(dead_letters))
+            }
+            Query::GetUncappedPair { first, second } => {
+                Ok(QueryResponse::UncappedPair(
+                    state.uncapped_pairs.contains_key(&(first, second)).await?
+                        || state.uncapped_pairs.contains_key(&(second, first)).await?,
+                ))
+            }
+            Query::GetReputation { account } => {
+                Ok(QueryResponse::Reputation(state.reputations.get(&account).await?.unwrap_or_default()))
+            }
+            Query::GetMarket { application_id } => {
+                Ok(QueryResponse::Market(state.markets.get(&application_id).await?))
+            }
+            Query::GetEvents { count } => {
+                Ok(QueryResponse::Events(state.events.read_back(count).await?))
+            }
+            Query::GetSettlementWindow => {
+                let mut boundaries = state.window_queue.indices().await?;
+                boundaries.sort_unstable();
+                let mut queued = Vec::new();
+                for boundary in boundaries {
+                    let settlement_ids = state.window_queue.get(&boundary).await?.unwrap_or_default();
+                    queued.push((Timestamp::from(boundary), settlement_ids));
+                }
+                Ok(QueryResponse::SettlementWindow { window_seconds: state.settlement_window_seconds.get(), queued })
+            }
+            Query::GetAssetTotals => {
+                let mut assets = state.total_balances.indices().await?;
+                assets.extend(state.total_escrowed.indices().await?);
+                assets.sort();
+                assets.dedup();
+                
+                let mut totals = Vec::new();
+                for asset in assets {
+                    totals.push(AssetTotals {
+                        balances: state.total_balances.get(&asset).await?.unwrap_or_default(),
+                        escrowed: state.total_escrowed.get(&asset).await?.unwrap_or_default(),
+                        asset,
+                    });
+                }
+                Ok(QueryResponse::AssetTotals(totals))
+            }
+            Query::GetHealth => {
+                let last_escrow_audit = state.last_escrow_audit.get();
+                let mut deferred_requests = Vec::new();
+                for origin in state.deferred_queues.indices().await? {
+                    if let Some(queue) = state.deferred_queues.get(&origin).await? {
+                        deferred_requests.push((origin, queue.depth()));
+                    }
+                }
+                Ok(QueryResponse::Health {
+                    healthy: last_escrow_audit.as_ref()
+                        .map_or(true, |audit| {
+                            audit.drift.is_empty() && audit.stuck_executions.is_empty() && audit.flagged_escrows.is_empty()
+                        }),
+                    last_escrow_audit,
+                    deferred_requests,
+                })
+            }
+        }
+    }
+    
+    /// Delay of `payer`'s leg: its linked withdrawal is still pending and the corridor of the
+    /// chain it goes to was last reported unavailable
+    async fn withdrawal_delay(
+        &self,
+        state: &SettlementState<ServiceRuntime<Self>>,
+        settlement: &Settlement,
+        payer: Account,
+    ) -> Result<Option<DelayedWithdrawal>, SettlementError> {
+        let Some(transfer_id) = settlement.bridge_transfer_id(payer) else {
+            return Ok(None);
+        };
+        let Some(transfer) = state.bridge_transfers.get(&transfer_id).await? else {
+            return Ok(None);
+        };
+        if transfer.direction != BridgeDirection::Withdrawal || transfer.status != BridgeTransferStatus::Pending {
+            return Ok(None);
+        }
+        let Some(corridor) = state.bridge_configs.get(&transfer.chain_id).await?.and_then(|config| config.corridor) else {
+            return Ok(None);
+        };
+        Ok(state.corridor_statuses.get(&corridor).await?.map(|status| DelayedWithdrawal {
+            payer,
+            transfer_id,
+            corridor,
+            availability: status.availability,
+            since: status.changed_at,
+        }))
     }
 }
 
@@ -1351,6 +5810,39 @@ mod tests {
         assert_eq!(escrow.amount, Amount::ZERO);
     }
     
+    #[test]
+    fn test_custodian_grant_covers_assets() {
+        let mut grant = CustodianGrant { assets: vec![], granted_at: Timestamp::from(0) };
+        assert!(grant.covers("BTC"));
+        
+        grant.assets = vec!["USDC".to_string()];
+        assert!(grant.covers("USDC"));
+        assert!(!grant.covers("BTC"));
+    }
+    
+    #[test]
+    fn test_counterparty_policy_lists() {
+        let account = |index| Account::chain(ChainId::root(index));
+        let mut policy = CounterpartyPolicy::default();
+        assert!(policy.set(account(1), Some(CounterpartyListing::Allowed)));
+    
+        // Listing moves a counterparty between lists rather than duplicating it
+        assert!(policy.set(account(1), Some(CounterpartyListing::Denied)));
+        assert_eq!(policy, CounterpartyPolicy { allowlist: vec![], denylist: vec![account(1)] });
+        assert!(policy.set(account(1), None));
+        assert_eq!(policy, CounterpartyPolicy::default());
+    
+        // A full list refuses newcomers but keeps accepting its own entries
+        for index in 0..MAX_COUNTERPARTY_LIST as u32 {
+            assert!(policy.set(account(index), Some(CounterpartyListing::Allowed)));
+        }
+        let full = policy.clone();
+        assert!(!policy.set(account(1_000), Some(CounterpartyListing::Allowed)));
+        assert_eq!(policy, full);
+        assert!(policy.set(account(0), Some(CounterpartyListing::Allowed)));
+        assert!(policy.set(account(1_000), Some(CounterpartyListing::Denied)));
+    }
+    
     #[test]
     fn test_settlement_status_progression() {
         // Valid status transitions
@@ -1360,6 +5852,616 @@ mod tests {
         assert!(matches!(SettlementStatus::Completed, SettlementStatus::Completed));
     }
     
+    #[test]
+    fn test_settlement_fees_fit_their_legs() {
+        let fees = SettlementFees {
+            maker_fee: Amount::from(10),
+            taker_fee: Amount::from(500),
+            recipient: Account::chain(ChainId::root(0)),
+        };
+        // The maker fee comes out of the taker leg and vice versa
+        assert!(fees.validate(Amount::from(500), Amount::from(10)).is_ok());
+        assert!(matches!(
+            fees.validate(Amount::from(499), Amount::from(10)),
+            Err(SettlementError::FeeExceedsAmount { .. })
+        ));
+        assert!(matches!(
+            fees.validate(Amount::from(500), Amount::from(9)),
+            Err(SettlementError::FeeExceedsAmount { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_same_asset_legs_net() {
+        let mut settlement = test_settlement(SettlementStatus::Pending);
+        settlement.taker_asset = "BTC".to_string();
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        let net = settlement.net_transfer().unwrap();
+        assert_eq!((net.maker_owes, net.taker_owes), (Amount::ZERO, Amount::from(490)));
+        assert_eq!((net.maker_receives, net.taker_receives), (Amount::from(490), Amount::ZERO));
+        assert_eq!(settlement.escrow_required(maker), Amount::ZERO);
+        assert_eq!(settlement.escrow_required(taker), Amount::from(490));
+        
+        // Fees larger than the difference leave both sides owing
+        settlement.maker_amount = Amount::from(498);
+        settlement.fees = Some(SettlementFees {
+            maker_fee: Amount::from(5),
+            taker_fee: Amount::from(3),
+            recipient: Account::chain(ChainId::root(2)),
+        });
+        let net = settlement.net_transfer().unwrap();
+        assert_eq!((net.maker_owes, net.taker_owes), (Amount::from(3), Amount::from(5)));
+        assert_eq!((net.maker_receives, net.taker_receives, net.fees), (Amount::ZERO, Amount::ZERO, Amount::from(8)));
+        
+        assert!(NetTransfer::new(Amount::from(7), Amount::from(7), None).is_empty());
+        assert_eq!(test_settlement(SettlementStatus::Pending).net_transfer(), None);
+    }
+    
+    #[test]
+    fn test_waived_escrow_awaits_counterparty() {
+        let mut settlement = test_settlement(SettlementStatus::MakerEscrowed);
+        settlement.taker_asset = "BTC".to_string();
+        settlement.maker_escrow = EscrowState::waived("BTC");
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        assert!(!settlement.maker_escrow.holds_funds());
+        let now = Timestamp::from(20_000_000);
+        assert_eq!(settlement.next_action(maker, Amount::ZERO, now), NextAction::AwaitCounterparty { seconds_remaining: 40 });
+        assert_eq!(
+            settlement.next_action(taker, Amount::ZERO, now),
+            NextAction::Escrow { asset: "BTC".to_string(), amount: Amount::from(490), seconds_remaining: 40 },
+        );
+        // Nothing to reclaim for the waived side
+        assert_eq!(settlement.next_action(maker, Amount::ZERO, Timestamp::from(60_000_001)), NextAction::None);
+    }
+    
+    #[test]
+    fn test_one_sided_zero_leg_is_accepted() {
+        let mut settlement = test_settlement(SettlementStatus::MakerEscrowed);
+        settlement.taker_amount = Amount::ZERO;
+        settlement.one_sided = true;
+        settlement.maker_escrow = EscrowState {
+            is_escrowed: true,
+            amount: Amount::from(10),
+            asset: "BTC".to_string(),
+            ..EscrowState::default()
+        };
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        let now = Timestamp::from(20_000_000);
+        // The taker's escrow is an acceptance of nothing
+        assert_eq!(settlement.escrow_required(taker), Amount::ZERO);
+        assert_eq!(
+            settlement.next_action(taker, Amount::ZERO, now),
+            NextAction::Escrow { asset: "USDC".to_string(), amount: Amount::ZERO, seconds_remaining: 40 },
+        );
+        // Only the maker has anything to reclaim once it lapses
+        let late = Timestamp::from(60_000_001);
+        assert_eq!(
+            settlement.next_action(maker, Amount::from(10), late),
+            NextAction::ClaimRefund { asset: "BTC".to_string(), amount: Amount::from(10) },
+        );
+        assert_eq!(settlement.next_action(taker, Amount::ZERO, late), NextAction::None);
+    }
+    
+    #[test]
+    fn test_one_sided_volume_skips_zero_leg() {
+        let mut settlement = test_settlement(SettlementStatus::Completed);
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        assert_eq!(settlement.volume().unwrap(), Amount::from(510));
+        assert!(!settlement.is_zero_leg(taker));
+        
+        settlement.taker_amount = Amount::ZERO;
+        settlement.one_sided = true;
+        assert!(settlement.is_zero_leg(taker));
+        assert!(!settlement.is_zero_leg(maker));
+        assert_eq!(settlement.volume().unwrap(), Amount::from(10));
+    }
+    
+    fn test_settlement(status: SettlementStatus) -> Settlement {
+        Settlement {
+            id: 1,
+            trade_id: 1,
+            maker: Account::chain(ChainId::root(0)),
+            taker: Account::chain(ChainId::root(1)),
+            maker_asset: "BTC".to_string(),
+            taker_asset: "USDC".to_string(),
+            maker_amount: Amount::from(10),
+            taker_amount: Amount::from(500),
+            maker_chain: ChainId::root(0),
+            taker_chain: ChainId::root(1),
+            maker_escrow: EscrowState::default(),
+            taker_escrow: EscrowState::default(),
+            status,
+            created_at: Timestamp::from(0),
+            expires_at: Timestamp::from(60_000_000),
+            completed_at: None,
+            failure_reason: None,
+            retry_count: 0,
+            fees: None,
+            windowed: false,
+            execute_at: None,
+            execution_started_at: None,
+            maker_bridge_transfer_id: None,
+            maker_bridge_status: None,
+            taker_bridge_transfer_id: None,
+            taker_bridge_status: None,
+            provenance: None,
+            memo: None,
+            external_ref: None,
+            recurring: None,
+            template_id: None,
+            confirmation: None,
+            confirmed_by: Vec::new(),
+            one_sided: false,
+        }
+    }
+    
+    #[test]
+    fn test_next_action_before_expiry() {
+        let mut settlement = test_settlement(SettlementStatus::Pending);
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        let now = Timestamp::from(20_000_000);
+        assert_eq!(
+            settlement.next_action(maker, Amount::ZERO, now),
+            NextAction::Escrow { asset: "BTC".to_string(), amount: Amount::from(10), seconds_remaining: 40 },
+        );
+        
+        settlement.maker_escrow.is_escrowed = true;
+        settlement.status = SettlementStatus::MakerEscrowed;
+        assert_eq!(
+            settlement.next_action(maker, Amount::from(10), now),
+            NextAction::AwaitCounterparty { seconds_remaining: 40 },
+        );
+        assert_eq!(
+            settlement.next_action(taker, Amount::ZERO, now),
+            NextAction::Escrow { asset: "USDC".to_string(), amount: Amount::from(500), seconds_remaining: 40 },
+        );
+        
+        // Escrow is still accepted at the deadline itself
+        assert!(!settlement.is_refundable(settlement.expires_at));
+        assert!(matches!(
+            settlement.next_action(taker, Amount::ZERO, settlement.expires_at),
+            NextAction::Escrow { seconds_remaining: 0, .. }
+        ));
+        
+        let outsider = Account::chain(ChainId::root(2));
+        assert_eq!(settlement.next_action(outsider, Amount::ZERO, now), NextAction::None);
+    }
+    
+    #[test]
+    fn test_next_action_after_expiry_or_failure() {
+        let mut settlement = test_settlement(SettlementStatus::MakerEscrowed);
+        settlement.maker_escrow.is_escrowed = true;
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        let late = Timestamp::from(60_000_001);
+        assert!(settlement.is_refundable(late));
+        assert_eq!(
+            settlement.next_action(maker, Amount::from(10), late),
+            NextAction::ClaimRefund { asset: "BTC".to_string(), amount: Amount::from(10) },
+        );
+        // Nothing to reclaim without escrow, or once the refund was claimed
+        assert_eq!(settlement.next_action(taker, Amount::ZERO, late), NextAction::None);
+        assert_eq!(settlement.next_action(maker, Amount::ZERO, late), NextAction::None);
+        
+        settlement.status = SettlementStatus::Failed;
+        let early = Timestamp::from(0);
+        assert!(settlement.is_refundable(early));
+        assert_eq!(
+            settlement.next_action(maker, Amount::from(10), early),
+            NextAction::ClaimRefund { asset: "BTC".to_string(), amount: Amount::from(10) },
+        );
+        
+        let completed = test_settlement(SettlementStatus::Completed);
+        assert!(!completed.is_refundable(early));
+        assert_eq!(completed.next_action(maker, Amount::ZERO, early), NextAction::None);
+    }
+    
+    #[test]
+    fn test_stuck_execution_resolution() {
+        // Left to the resolution even after expiry
+        let executing = test_settlement(SettlementStatus::Executing);
+        assert!(!executing.is_refundable(Timestamp::from(u64::MAX)));
+        
+        let fee = Amount::from(5);
+        assert_eq!(ExecutionResolution::for_unpaid_legs(&[]), ExecutionResolution::Completed);
+        assert_eq!(ExecutionResolution::for_unpaid_legs(&[(Amount::from(5), fee)]), ExecutionResolution::Completed);
+        assert_eq!(
+            ExecutionResolution::for_unpaid_legs(&[(Amount::from(10), fee), (Amount::from(4), fee)]),
+            ExecutionResolution::Reversed,
+        );
+    }
+    
+    #[test]
+    fn test_orphaned_escrow_resolution() {
+        for status in [SettlementStatus::Expired, SettlementStatus::Cancelled, SettlementStatus::Failed, SettlementStatus::Refunded] {
+            assert_eq!(OrphanedEscrow::for_status(Some(status)), Some(OrphanedEscrow::Return));
+        }
+        // Paid out, or no record to say it did not
+        assert_eq!(OrphanedEscrow::for_status(Some(SettlementStatus::Completed)), Some(OrphanedEscrow::Review));
+        assert_eq!(OrphanedEscrow::for_status(None), Some(OrphanedEscrow::Review));
+        // Still able to release its own escrow
+        assert_eq!(OrphanedEscrow::for_status(Some(SettlementStatus::Executing)), None);
+        assert_eq!(OrphanedEscrow::for_status(Some(SettlementStatus::MakerEscrowed)), None);
+    }
+    
+    #[test]
+    fn test_substituted_leg_pays_out_in_substitute() {
+        let mut settlement = test_settlement(SettlementStatus::FullyEscrowed);
+        settlement.fees = Some(SettlementFees {
+            maker_fee: Amount::from(100),
+            taker_fee: Amount::from(2),
+            recipient: Account::chain(ChainId::root(2)),
+        });
+        // The taker escrowed 1000 DAI for its 500 USDC leg at 2 DAI per USDC
+        settlement.taker_escrow = EscrowState {
+            is_escrowed: true,
+            amount: Amount::from(1_000),
+            asset: "DAI".to_string(),
+            substitution: Some(AssetSubstitution {
+                original_asset: "USDC".to_string(),
+                original_amount: Amount::from(500),
+                rate: 2 * math::PRICE_SCALE as u64,
+            }),
+            ..EscrowState::default()
+        };
+        let [maker_leg, taker_leg] = settlement.legs().unwrap();
+        assert_eq!((maker_leg.2.as_str(), maker_leg.3), ("BTC", Amount::from(2)));
+        assert_eq!((taker_leg.2.as_str(), taker_leg.3), ("DAI", Amount::from(200)));
+        assert_eq!(settlement.taker_escrow.held_asset(&settlement.taker_asset), "DAI");
+        assert_eq!(settlement.maker_escrow.held_asset(&settlement.maker_asset), "BTC");
+        
+        let rate = ConversionRate {
+            rate: 2 * math::PRICE_SCALE as u64,
+            max_staleness_seconds: 60,
+            updated_at: Timestamp::from(1_000_000),
+            updated_by: settlement.maker,
+        };
+        assert!(!rate.is_stale(Timestamp::from(61_000_000)));
+        assert!(rate.is_stale(Timestamp::from(61_000_001)));
+    }
+    
+    #[test]
+    fn test_settlement_tags_are_validated() {
+        assert!(validate_tag("memo", "Desk 4 / deal 1182", MAX_MEMO_LENGTH).is_ok());
+        assert!(validate_tag("memo", &"é".repeat(MAX_MEMO_LENGTH), MAX_MEMO_LENGTH).is_ok());
+        let too_long = "x".repeat(MAX_EXTERNAL_REF_LENGTH + 1);
+        for value in ["", "line\nbreak", "nul\0", too_long.as_str()] {
+            assert!(matches!(
+                validate_tag("external reference", value, MAX_EXTERNAL_REF_LENGTH),
+                Err(SettlementError::InvalidSettlementTag { .. })
+            ));
+        }
+    }
+    
+    #[test]
+    fn test_redelivered_messages_share_a_dedupe_key() {
+        let party = Account::chain(ChainId::root(0));
+        let confirmation = |amount| Message::EscrowConfirmation { settlement_id: 1, party, confirmed: true, amount };
+        assert_eq!(
+            confirmation(Amount::from(10)).dedupe_key(),
+            confirmation(Amount::from(10)).dedupe_key(),
+        );
+        assert_ne!(
+            confirmation(Amount::from(10)).dedupe_key(),
+            confirmation(Amount::from(11)).dedupe_key(),
+        );
+        
+        let complete = Message::SettlementComplete {
+            settlement_id: 1,
+            success: true,
+            failure_reason: None,
+            memo: None,
+            external_ref: None,
+        };
+        assert_eq!(complete.dedupe_key(), None);
+        
+        let day = MESSAGE_DEDUPE_TTL_SECONDS * 1_000_000;
+        assert!(!dedupe_expired(Timestamp::from(5), Timestamp::from(day + 4)));
+        assert!(dedupe_expired(Timestamp::from(5), Timestamp::from(day + 5)));
+    }
+    
+    #[test]
+    fn test_expiration_keys_sort_by_time() {
+        // Little-endian keys would put 256 micros before 1
+        let times = [0, 1, 255, 256, 1_000_000, u64::MAX].map(Timestamp::from);
+        for pair in times.windows(2) {
+            assert!(expiration_key(pair[0]) < expiration_key(pair[1]));
+        }
+        assert_eq!(u64::from_be_bytes(expiration_key(Timestamp::from(256))), 256);
+    }
+    
+    #[test]
+    fn test_dead_letters() {
+        // Up to the bound everything is kept; past it the oldest goes, one per new dead letter
+        assert_eq!(dead_letter_to_evict(0, MAX_DEAD_LETTERS - 1), None);
+        assert_eq!(dead_letter_to_evict(0, MAX_DEAD_LETTERS), Some(0));
+        assert_eq!(dead_letter_to_evict(5, MAX_DEAD_LETTERS + 5), Some(5));
+        
+        // Only a settlement request from another chain was rejected to a market
+        let mut dead_letter = DeadLetter {
+            id: 0,
+            envelope: MessageEnvelope::seal(&()),
+            payload_hash: [0; 32],
+            context: MessageContext { message_id: None, caller: None, signer: None },
+            trade_id: Some(4),
+            reason: "Unknown market".to_string(),
+            failed_at: Timestamp::from(0),
+        };
+        assert!(!dead_letter.rejection_sent());
+        dead_letter.context.message_id = Some(MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight::from(3),
+            index: 0,
+        });
+        assert!(dead_letter.rejection_sent());
+        dead_letter.trade_id = None;
+        assert!(!dead_letter.rejection_sent());
+    }
+    
+    #[test]
+    fn test_only_the_settlement_chain_rejects_a_relayed_request() {
+        let mut relayed = RelayedSettlementRequest { settlement_chain: ChainId::root(0), rejection: None };
+        assert!(!relayed.reject(Some(ChainId::root(1)), SettlementRejectionCode::UnknownMarket));
+        assert!(!relayed.reject(None, SettlementRejectionCode::UnknownMarket));
+        assert_eq!(relayed.rejection, None);
+        
+        assert!(relayed.reject(Some(ChainId::root(0)), SettlementRejectionCode::AmountOutOfLimits));
+        assert_eq!(relayed.rejection, Some(SettlementRejectionCode::AmountOutOfLimits));
+    }
+    
+    #[test]
+    fn test_envelopes_round_trip_across_layouts() {
+        // The same message as an older build, and a newer one with a field and a variant more
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum OlderMessage {
+            SettlementComplete { settlement_id: u64, success: bool, failure_reason: Option<String> },
+        }
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum NewerMessage {
+            SettlementComplete {
+                settlement_id: u64,
+                success: bool,
+                failure_reason: Option<String>,
+                memo: Option<String>,
+                external_ref: Option<String>,
+                priority: u8,
+            },
+            SettlementDisputed { settlement_id: u64 },
+        }
+        let complete = Message::SettlementComplete {
+            settlement_id: 7,
+            success: true,
+            failure_reason: None,
+            memo: Some("rent".to_string()),
+            external_ref: None,
+        };
+        let sealed = MessageEnvelope::seal(&complete);
+        assert_eq!(sealed.version, MESSAGE_VERSION);
+        assert_eq!(sealed.open::<Message>(), Ok(complete.clone()));
+        
+        // Fields one side lacks are defaulted on the way in and skipped on the way out
+        let older = OlderMessage::SettlementComplete { settlement_id: 7, success: true, failure_reason: None };
+        assert_eq!(MessageEnvelope::seal(&older).open::<Message>(), Ok(Message::SettlementComplete {
+            settlement_id: 7,
+            success: true,
+            failure_reason: None,
+            memo: None,
+            external_ref: None,
+        }));
+        assert_eq!(sealed.open::<OlderMessage>(), Ok(older.clone()));
+        let newer = NewerMessage::SettlementComplete {
+            settlement_id: 7,
+            success: true,
+            failure_reason: None,
+            memo: Some("rent".to_string()),
+            external_ref: None,
+            priority: 2,
+        };
+        assert_eq!(MessageEnvelope::seal(&newer).open::<Message>(), sealed.open::<Message>());
+        
+        // Unknown variants and higher versions are refused, to be parked
+        assert!(MessageEnvelope::seal(&NewerMessage::SettlementDisputed { settlement_id: 7 }).open::<Message>().is_err());
+        let ahead = MessageEnvelope::with_version(MESSAGE_VERSION + 1, &complete);
+        assert!(ahead.open::<Message>().is_err());
+        assert_eq!(MessageEnvelope::with_version(MESSAGE_VERSION - 1, &older).open::<OlderMessage>(), Ok(older));
+    }
+    
+    #[test]
+    fn test_escrow_after_boundary_waits_for_next_window() {
+        let hour = 3_600_000_000;
+        assert_eq!(next_window_boundary(Timestamp::from(hour - 1), 3_600), Timestamp::from(hour));
+        // Escrowed exactly at or just after a boundary: that window has closed
+        assert_eq!(next_window_boundary(Timestamp::from(hour), 3_600), Timestamp::from(2 * hour));
+        assert_eq!(next_window_boundary(Timestamp::from(hour + 1), 3_600), Timestamp::from(2 * hour));
+    }
+    
+    #[test]
+    fn test_reputation_rates() {
+        let mut reputation = Reputation::default();
+        assert_eq!(reputation.default_rate_bps(), 0);
+        assert_eq!(reputation.average_escrow_seconds(), None);
+        
+        reputation.finalized = 4;
+        reputation.defaulted = 1;
+        reputation.escrows = 3;
+        reputation.total_escrow_seconds = 100;
+        assert_eq!(reputation.default_rate_bps(), 2_500);
+        assert_eq!(reputation.average_escrow_seconds(), Some(33));
+    }
+    
+    #[test]
+    fn test_market_verifies_origin_and_pair() {
+        let market = MarketRegistration {
+            chain_id: ChainId::root(0),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDC".to_string(),
+        };
+        assert!(market.verify_request(Some(ChainId::root(0)), "BTC", "USDC").is_ok());
+        assert!(market.verify_request(Some(ChainId::root(0)), "USDC", "BTC").is_ok());
+        
+        assert!(matches!(
+            market.verify_request(Some(ChainId::root(1)), "BTC", "USDC"),
+            Err(SettlementError::Unauthorized { .. })
+        ));
+        assert!(matches!(
+            market.verify_request(None, "BTC", "USDC"),
+            Err(SettlementError::Unauthorized { .. })
+        ));
+        assert!(matches!(
+            market.verify_request(Some(ChainId::root(0)), "ETH", "USDC"),
+            Err(SettlementError::MarketAssetMismatch { .. })
+        ));
+        assert!(matches!(
+            market.verify_request(Some(ChainId::root(0)), "BTC", "BTC"),
+            Err(SettlementError::MarketAssetMismatch { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_rejection_codes() {
+        let market = MarketRegistration {
+            chain_id: ChainId::root(0),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDC".to_string(),
+        };
+        let error = market.verify_request(Some(ChainId::root(1)), "BTC", "USDC").unwrap_err();
+        assert_eq!(error.rejection_code(), SettlementRejectionCode::UnknownMarket);
+        
+        let error = SettlementError::BelowMinimum { amount: Amount::ZERO, minimum: Amount::ONE };
+        assert_eq!(error.rejection_code(), SettlementRejectionCode::AmountOutOfLimits);
+        let error = SettlementError::CounterpartyDenylisted {
+            party: Account::chain(ChainId::root(0)),
+            counterparty: Account::chain(ChainId::root(1)),
+        };
+        assert_eq!(error.rejection_code(), SettlementRejectionCode::CounterpartyRestricted);
+        assert_eq!(SettlementError::AlreadyEscrowed.rejection_code(), SettlementRejectionCode::Internal);
+    }
+    
+    #[test]
+    fn test_throttle_counts_per_window() {
+        let per_block = SettlementThrottle { max_per_window: 2, window_seconds: 0, max_drained_per_block: 5 };
+        let per_minute = SettlementThrottle { window_seconds: 60, ..per_block };
+        assert!(per_block.validate().is_ok());
+        assert!(SettlementThrottle { max_per_window: 0, ..per_block }.validate().is_err());
+        
+        // Zero-second windows are blocks; others are slices of time
+        let now = Timestamp::from(90_000_000);
+        assert_eq!(per_block.window(now, BlockHeight(7)), 7);
+        assert_eq!(per_minute.window(now, BlockHeight(7)), 1);
+        assert_eq!(per_minute.window(Timestamp::from(119_000_000), BlockHeight(8)), 1);
+        
+        let mut bucket = ThrottleBucket::default();
+        let window = per_block.window(now, BlockHeight(7));
+        assert!(bucket.admit(window, 2));
+        assert!(bucket.admit(window, 2));
+        assert!(!bucket.admit(window, 2));
+        assert_eq!(bucket.initiated, 2);
+        
+        // A new window starts empty
+        assert!(bucket.admit(window + 1, 2));
+        assert_eq!(bucket, ThrottleBucket { window: window + 1, initiated: 1 });
+    }
+    
+    #[test]
+    fn test_recurring_schedule_and_outcomes() {
+        let mut recurring = RecurringSettlement {
+            id: 0,
+            payer: Account::chain(ChainId::root(0)),
+            payee: Account::chain(ChainId::root(1)),
+            asset: "USDT".to_string(),
+            amount: Amount::from(100),
+            payer_chain: ChainId::root(0),
+            payee_chain: ChainId::root(1),
+            interval_seconds: 60,
+            timeout_seconds: 30,
+            end: RecurringEnd::Count(3),
+            max_consecutive_defaults: 2,
+            consecutive_defaults: 0,
+            counted_instances: 0,
+            next_due_at: Timestamp::from(0),
+            status: RecurringStatus::Active,
+            created_at: Timestamp::from(0),
+        };
+        assert!(recurring.validate().is_ok());
+        assert!(RecurringSettlement { timeout_seconds: 61, ..recurring.clone() }.validate().is_err());
+        assert!(RecurringSettlement { end: RecurringEnd::Count(0), ..recurring.clone() }.validate().is_err());
+        
+        // Two of three left after the first, or as many as fit before the end time
+        let upcoming = recurring.upcoming(1, MAX_UPCOMING_INSTANCES);
+        assert_eq!(upcoming, vec![Timestamp::from(0), Timestamp::from(60_000_000)]);
+        recurring.end = RecurringEnd::Until(Timestamp::from(150_000_000));
+        assert_eq!(recurring.upcoming(0, MAX_UPCOMING_INSTANCES).len(), 3);
+        recurring.status = RecurringStatus::Defaulted;
+        assert!(recurring.upcoming(0, MAX_UPCOMING_INSTANCES).is_empty());
+        
+        // An instance past its escrow deadline has defaulted even before the expiry sweep
+        let instance = RecurringInstance { sequence: 0, due_at: Timestamp::from(0), settlement_id: Some(1), failure_reason: None };
+        let settlement = test_settlement(SettlementStatus::MakerEscrowed);
+        assert_eq!(InstanceOutcome::of(&instance, Some(&settlement), Timestamp::from(0)), InstanceOutcome::InFlight);
+        assert!(matches!(
+            InstanceOutcome::of(&instance, Some(&settlement), Timestamp::from(60_000_001)),
+            InstanceOutcome::Defaulted { .. }
+        ));
+        let completed = test_settlement(SettlementStatus::Completed);
+        assert_eq!(
+            InstanceOutcome::of(&instance, Some(&completed), Timestamp::from(60_000_001)),
+            InstanceOutcome::Paid { completed_at: None }
+        );
+        let refused = RecurringInstance { settlement_id: None, failure_reason: Some("Below minimum".to_string()), ..instance };
+        assert_eq!(InstanceOutcome::of(&refused, None, Timestamp::from(0)), InstanceOutcome::Defaulted {
+            reason: "Below minimum".to_string(),
+        });
+    }
+    
+    #[test]
+    fn test_template_validates_static_terms() {
+        let template = SettlementTemplate {
+            id: 0,
+            creator: Account::chain(ChainId::root(0)),
+            counterparty: Account::chain(ChainId::root(1)),
+            maker_asset: "BTC".to_string(),
+            taker_asset: "USDC".to_string(),
+            creator_chain: ChainId::root(0),
+            counterparty_chain: ChainId::root(1),
+            timeout_seconds: 3_600,
+            windowed: false,
+            memo: Some("Monthly rebalance".to_string()),
+            status: TemplateStatus::Proposed,
+            created_at: Timestamp::from(0),
+            updated_at: Timestamp::from(0),
+            accepted_at: None,
+        };
+        assert!(template.validate().is_ok());
+        let creator = template.creator;
+        assert!(SettlementTemplate { counterparty: creator, ..template.clone() }.validate().is_err());
+        assert!(SettlementTemplate { taker_asset: String::new(), ..template.clone() }.validate().is_err());
+        assert!(SettlementTemplate { timeout_seconds: 0, ..template.clone() }.validate().is_err());
+        assert!(matches!(
+            SettlementTemplate { memo: Some("x".repeat(MAX_MEMO_LENGTH + 1)), ..template }.validate(),
+            Err(SettlementError::InvalidSettlementTag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_confirmation_rules() {
+        let terms = ConfirmationTerms { window_seconds: 600, rule: ConfirmationRule::BothParties };
+        assert!(terms.validate(false).is_ok());
+        assert!(terms.validate(true).is_err());
+        assert!(ConfirmationTerms { window_seconds: 0, ..terms }.validate(false).is_err());
+
+        let mut settlement = test_settlement(SettlementStatus::FullyEscrowed);
+        assert!(settlement.auto_execute() && settlement.is_confirmed());
+        settlement.confirmation = Some(terms);
+        assert!(!settlement.auto_execute() && !settlement.is_confirmed());
+        settlement.confirmed_by.push(settlement.taker);
+        assert!(!settlement.is_confirmed());
+        settlement.confirmed_by.push(settlement.maker);
+        assert!(settlement.is_confirmed());
+
+        settlement.confirmation = Some(ConfirmationTerms { rule: ConfirmationRule::EitherParty, ..terms });
+        settlement.confirmed_by = vec![settlement.maker];
+        assert!(settlement.is_confirmed());
+    }
+
     #[test]
     fn test_bridge_config() {
         let config = BridgeConfig {
@@ -1372,10 +6474,47 @@ mod tests {
             fee_rate_bps: 30, // 0.3%
             is_active: true,
             supported_assets: vec!["ETH".to_string(), "USDT".to_string()],
+            corridor: Some(1),
         };
         
         assert!(config.is_active);
         assert_eq!(config.fee_rate_bps, 30);
         assert!(config.supported_assets.contains(&"ETH".to_string()));
     }
+    
+    #[test]
+    fn test_bridge_deposit_proves_one_leg() {
+        let mut settlement = test_settlement(SettlementStatus::Pending);
+        let (maker, taker) = (settlement.maker, settlement.taker);
+        let mut deposit = BridgeTransfer {
+            id: 3,
+            chain_id: "ethereum".to_string(),
+            user: maker,
+            asset: "BTC".to_string(),
+            amount: Amount::from(10),
+            direction: BridgeDirection::Deposit,
+            status: BridgeTransferStatus::Completed,
+            tx_hash: Some("0xabc".to_string()),
+            destination_address: None,
+            created_at: Timestamp::from(0),
+            completed_at: Some(Timestamp::from(0)),
+            confirmations: 12,
+            settlement_ref: None,
+        };
+        assert!(deposit.check_escrow_proof(maker, "BTC").is_ok());
+        for (party, asset) in [(taker, "BTC"), (maker, "USDC")] {
+            assert!(matches!(
+                deposit.check_escrow_proof(party, asset),
+                Err(SettlementError::InvalidBridgeLink { transfer_id: 3, .. })
+            ));
+        }
+        
+        deposit.settlement_ref = Some(SettlementRef { settlement_id: settlement.id, payer: maker });
+        assert!(deposit.check_escrow_proof(maker, "BTC").is_err());
+        
+        settlement.link_bridge_transfer(maker, 3, BridgeTransferStatus::Completed);
+        assert_eq!(settlement.bridge_transfer_id(maker), Some(3));
+        assert_eq!(settlement.bridge_transfer_id(taker), None);
+        assert_eq!(settlement.maker_bridge_status, Some(BridgeTransferStatus::Completed));
+    }
 }
@@ -63,6 +63,7 @@ echo -e "${BLUE}🔗 Creating microchains for markets...${NC}"
 
 declare -a MARKETS=("BTC_USDT" "ETH_USDT" "SOL_USDT")
 declare -A MARKET_CHAINS
+declare -A MARKET_OWNERS
 
 for market in "${MARKETS[@]}"; do
     echo -e "${YELLOW}  Creating chain for $market...${NC}"
@@ -73,6 +74,7 @@ for market in "${MARKETS[@]}"; do
     ACCOUNT="${CHAIN_INFO[1]}"
     
     MARKET_CHAINS[$market]=$CHAIN_ID
+    MARKET_OWNERS[$market]=$ACCOUNT
     
     echo -e "${GREEN}  ✅ $market chain: $CHAIN_ID${NC}"
 done
@@ -99,6 +101,8 @@ echo -e "${BLUE}📦 Deploying contracts...${NC}"
 
 for market in "${MARKETS[@]}"; do
     CHAIN_ID="${MARKET_CHAINS[$market]}"
+    # The chain owner becomes the admin of the applications created there
+    ADMIN_ACCOUNT="{\"chain_id\": \"$CHAIN_ID\", \"owner\": \"${MARKET_OWNERS[$market]}\"}"
     
     echo -e "${YELLOW}  Deploying to $market chain ($CHAIN_ID)...${NC}"
     
@@ -107,7 +111,7 @@ for market in "${MARKETS[@]}"; do
         target/wasm32-unknown-unknown/release/axelarx_orderbook.wasm \
         target/wasm32-unknown-unknown/release/axelarx_orderbook_service.wasm \
         --chain $CHAIN_ID \
-        --json-argument '{}' 2>/dev/null | grep -o 'e[0-9a-f]*' | head -1)
+        --json-argument "$ADMIN_ACCOUNT" 2>/dev/null | grep -o 'e[0-9a-f]*' | head -1)
     
     if [ -n "$ORDERBOOK_APP_ID" ]; then
         echo -e "${GREEN}    ✅ Order Book deployed: $ORDERBOOK_APP_ID${NC}"
@@ -120,7 +124,7 @@ for market in "${MARKETS[@]}"; do
         target/wasm32-unknown-unknown/release/axelarx_settlement.wasm \
         target/wasm32-unknown-unknown/release/axelarx_settlement_service.wasm \
         --chain $CHAIN_ID \
-        --json-argument '{}' 2>/dev/null | grep -o 'e[0-9a-f]*' | head -1)
+        --json-argument "$ADMIN_ACCOUNT" 2>/dev/null | grep -o 'e[0-9a-f]*' | head -1)
     
     if [ -n "$SETTLEMENT_APP_ID" ]; then
         echo -e "${GREEN}    ✅ Settlement Engine deployed: $SETTLEMENT_APP_ID${NC}"